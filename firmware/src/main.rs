@@ -6,16 +6,23 @@ use rppal::{
     gpio::{Gpio, Trigger},
     i2c::I2c,
 };
-use server::control::{msg::WsMessage, ControllerMessage};
+use server::control::{
+    msg::{Orientation, Rumble, ServerMessage, TimedOrientation, WsMessage},
+    ControllerMessage,
+};
 use std::{
+    env,
     fs::File,
     io::Read,
     sync::mpsc::channel,
     sync::{Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tokio_tungstenite::connect_async;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Server the controller connects to when `SPJORTS_SERVER` isn't set
+pub const DEFAULT_SERVER: &str = "ws://192.168.10.137:7878";
 
 /// Poll time for angles
 pub const ANGLE_WAIT_TIME: u64 = 50;
@@ -32,6 +39,10 @@ pub const ACCEL_XOUT_H: u8 = 0x3B;
 pub const BUTTON_A_PIN: u8 = 5;
 /// B Button pins
 pub const BUTTON_B_PIN: u8 = 6;
+/// Rumble motor pin
+pub const RUMBLE_PIN: u8 = 13;
+/// PWM frequency the rumble motor is driven at, its duty cycle sets the strength
+pub const RUMBLE_PWM_HZ: f64 = 200.0;
 
 /// Sensitivity constants (assuming ±2g and ±250 deg/s)
 const ACCEL_SENS: f32 = 16384.0; // LSB/g
@@ -69,9 +80,9 @@ async fn main() {
     let (tx_main, rx_main) = channel();
 
     // Connect to server
-    let ws = connect_with_retries("ws://192.168.10.137:7878", Duration::from_secs(15)).await;
+    let ws = connect_with_retries(&read_server_url(), Duration::from_secs(15)).await;
 
-    let (mut write, _read) = ws.split();
+    let (mut write, mut read) = ws.split();
     write
         .send(
             WsMessage::Controller(id)
@@ -105,14 +116,48 @@ async fn main() {
 
     println!("Left pairing mode");
 
+    // Rumbles buzz the motor on their own thread so reading from the server never stalls
+    let mut motor = gpio
+        .get(RUMBLE_PIN)
+        .expect("Get GPIO pin for rumble motor")
+        .into_output_low();
+    let (tx_rumble, rx_rumble) = channel::<Rumble>();
+    thread::spawn(move || {
+        while let Ok(rumble) = rx_rumble.recv() {
+            let duty = f64::from(rumble.intensity) / f64::from(u8::MAX);
+            if motor.set_pwm_frequency(RUMBLE_PWM_HZ, duty).is_ok() {
+                thread::sleep(Duration::from_millis(u64::from(rumble.millis)));
+            }
+            let _ = motor.clear_pwm();
+            motor.set_low();
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(Ok(msg)) = read.next().await {
+            if let Message::Binary(buf) = msg {
+                if let Ok(ServerMessage::Rumble(rumble)) = ServerMessage::from_binary(&buf) {
+                    if tx_rumble.send(rumble).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    // Buttons are pulled down, so they rise when pressed and fall when released. Both are sent
+    // so games can tell taps from holds
     let tx_a = tx_main.clone();
     button_a
         .set_async_interrupt(
-            Trigger::RisingEdge,
+            Trigger::Both,
             Some(Duration::from_millis(50)),
-            move |_| {
-                tx_a.send(ControllerMessage::ButtonPressA)
-                    .expect("Send button press A");
+            move |event| {
+                let msg = match event.trigger {
+                    Trigger::FallingEdge => ControllerMessage::ButtonReleaseA,
+                    _ => ControllerMessage::ButtonPressA,
+                };
+                tx_a.send(msg).expect("Send button A");
             },
         )
         .expect("Set interrupt for Button A");
@@ -120,11 +165,14 @@ async fn main() {
     let tx_b = tx_main.clone();
     button_b
         .set_async_interrupt(
-            Trigger::RisingEdge,
+            Trigger::Both,
             Some(Duration::from_millis(50)),
-            move |_| {
-                tx_b.send(ControllerMessage::ButtonPressB)
-                    .expect("Send button press B");
+            move |event| {
+                let msg = match event.trigger {
+                    Trigger::FallingEdge => ControllerMessage::ButtonReleaseB,
+                    _ => ControllerMessage::ButtonPressB,
+                };
+                tx_b.send(msg).expect("Send button B");
             },
         )
         .expect("Set interrupt for Button B");
@@ -146,31 +194,36 @@ async fn main() {
     );
 
     // Shared angles protected by a mutex so the thread can update them
-    let angles = Arc::new(Mutex::new((0f32, 0f32, 0f32))); // (pitch, roll, yaw)
+    let angles = Arc::new(Mutex::new(Orientation::default()));
 
     // Spawn a thread to continuously read and update angles
     let angles_clone = angles.clone();
     let tx_main_clone = tx_main.clone();
     thread::spawn(move || {
-        let mut prev_pitch = 0.0;
-        let mut prev_roll = 0.0;
-        let mut prev_yaw = 0.0;
+        let mut prev = Orientation::default();
 
         let dt = ANGLE_WAIT_TIME as f32 / 1000.0;
 
         loop {
-            if let Some((pitch, roll, yaw)) = read_mpu6050(
-                &mut i2c, dt, gx_offset, gy_offset, gz_offset, prev_pitch, prev_roll, prev_yaw,
-            ) {
-                prev_pitch = pitch;
-                prev_roll = roll;
-                prev_yaw = yaw;
+            if let Some(orientation) =
+                read_mpu6050(&mut i2c, dt, gx_offset, gy_offset, gz_offset, prev)
+            {
+                prev = orientation;
 
                 if let Ok(mut lock) = angles_clone.lock() {
-                    *lock = (pitch, roll, yaw);
+                    *lock = orientation;
                 }
 
-                let msg = ControllerMessage::AngleInfo(pitch, 0., roll);
+                // Integrated gyro yaw drifts, so games steer with the accelerometer backed roll
+                // axis instead. Readings are stamped so games can measure how long they took to
+                // arrive
+                let sent_at_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |since| since.as_millis() as u64);
+                let msg = ControllerMessage::TimedAngleInfo(TimedOrientation::new(
+                    Orientation::new(orientation.pitch, 0., orientation.roll),
+                    sent_at_ms,
+                ));
                 if tx_main_clone.send(msg).is_err() {
                     break;
                 }
@@ -191,20 +244,19 @@ async fn main() {
     }
 }
 
-/// Reads raw data from MPU6050, performs a simple complementary filter, and returns (pitch, roll, yaw).
+/// Reads raw data from MPU6050, performs a simple complementary filter, and returns the new
+/// orientation.
 ///
 /// - `gx_offset, gy_offset, gz_offset`: offsets found by calibration
-/// - `(prev_pitch, prev_roll, prev_yaw)`: the angles from previous iteration for the gyro integration
+/// - `prev`: the orientation from previous iteration for the gyro integration
 fn read_mpu6050(
     i2c: &mut I2c,
     dt: f32,
     gx_offset: f32,
     gy_offset: f32,
     gz_offset: f32,
-    prev_pitch: f32,
-    prev_roll: f32,
-    prev_yaw: f32,
-) -> Option<(f32, f32, f32)> {
+    prev: Orientation,
+) -> Option<Orientation> {
     let mut buf = [0; 14];
     if i2c.block_read(ACCEL_XOUT_H, &mut buf).is_err() {
         eprintln!("Failed to read from MPU6050");
@@ -237,14 +289,14 @@ fn read_mpu6050(
     let accel_roll = -ay.atan2((ax * ax + az * az).sqrt());
 
     // Integrate the gyro for pitch, roll, yaw
-    let mut pitch = prev_pitch + gx_rad_s * dt;
-    let mut roll = prev_roll + gy_rad_s * dt;
-    let yaw = prev_yaw + gz_rad_s * dt;
+    let mut pitch = prev.pitch + gx_rad_s * dt;
+    let mut roll = prev.roll + gy_rad_s * dt;
+    let yaw = prev.yaw + gz_rad_s * dt;
 
     pitch = ALPHA * pitch + (1.0 - ALPHA) * accel_pitch;
     roll = ALPHA * roll + (1.0 - ALPHA) * accel_roll;
 
-    Some((pitch, roll, yaw))
+    Some(Orientation::new(pitch, roll, yaw))
 }
 
 /// Calibrate gyro offsets by averaging samples while the MPU6050 is still.
@@ -275,6 +327,11 @@ fn calibrate_gyro(i2c: &mut I2c) -> (f32, f32, f32) {
     (gx_off, gy_off, gz_off)
 }
 
+/// Gets the server to connect to, a `wss://` address when the server is behind TLS
+fn read_server_url() -> String {
+    env::var("SPJORTS_SERVER").unwrap_or_else(|_| DEFAULT_SERVER.to_string())
+}
+
 /// Gets the controller ID from the configuration file
 fn read_id() -> u64 {
     let mut file = File::open("/home/braden/.id").expect("Failed to read identity file");
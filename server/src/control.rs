@@ -5,8 +5,13 @@ use std::sync::Arc;
 
 use futures::SinkExt;
 pub use msg::ControllerMessage;
+use msg::{Rumble, ServerMessage};
 use tokio::sync::Mutex;
-use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+};
+use tracing::{debug, warn};
 
 use crate::serve::service::WebsocketWriteStream;
 
@@ -17,24 +22,54 @@ pub type ControllerId = u64;
 pub struct Controller {
     /// ID
     pub id: u64,
+    /// Web Socket stream back to the controller itself
+    stream: Arc<Mutex<WebsocketWriteStream>>,
     /// Web Socket streams listening to the controller
     listeners: Vec<Arc<Mutex<WebsocketWriteStream>>>,
 }
 
 impl Controller {
-    /// Creates a new controller
-    pub fn new(id: u64) -> Self {
+    /// Creates a new controller that's written back to through `stream`
+    pub fn new(id: u64, stream: Arc<Mutex<WebsocketWriteStream>>) -> Self {
         Self {
             id,
+            stream,
             listeners: vec![],
         }
     }
 
+    /// Tells the controller to buzz its rumble motor. Best effort, a controller that can't be
+    /// reached is dropped once its heartbeat lapses
+    pub async fn rumble(&self, rumble: Rumble) {
+        if let Ok(msg) = ServerMessage::Rumble(rumble).to_ws_message() {
+            if self.stream.lock().await.send(msg).await.is_err() {
+                debug!(controller = self.id, "rumble couldn't reach the controller");
+            }
+        }
+    }
+
     /// Adds a new listener to the controller
     pub fn new_listener(&mut self, listener: Arc<Mutex<WebsocketWriteStream>>) {
         self.listeners.push(listener);
     }
 
+    /// Tells every listener the controller has gone away by closing their connection, then lets
+    /// go of them. Best effort, a listener that already left is skipped
+    pub async fn disconnect(&mut self) {
+        for listener in self.listeners.drain(..) {
+            close(&listener, "Controller disconnected").await;
+        }
+    }
+
+    /// Closes the controller's own connection along with every listener's, for when the server
+    /// is going down
+    pub async fn shutdown(&mut self) {
+        for listener in self.listeners.drain(..) {
+            close(&listener, "Server shutting down").await;
+        }
+        close(&self.stream, "Server shutting down").await;
+    }
+
     /// Broadcast a binary message to all listeners connected
     pub async fn broadcast(&mut self, msg: &[u8]) {
         let mut drop_queue = vec![];
@@ -50,6 +85,14 @@ impl Controller {
             }
         }
 
+        if !drop_queue.is_empty() {
+            warn!(
+                controller = self.id,
+                dropped = drop_queue.len(),
+                "dropping listeners that couldn't be reached"
+            );
+        }
+
         let filtered: Vec<_> = self
             .listeners
             .clone()
@@ -62,3 +105,13 @@ impl Controller {
         self.listeners = filtered
     }
 }
+
+/// Sends a close frame down a web socket, saying why it's being closed. Best effort, a socket
+/// that's already gone is skipped
+pub async fn close(stream: &Mutex<WebsocketWriteStream>, reason: &'static str) {
+    let frame = CloseFrame {
+        code: CloseCode::Away,
+        reason: reason.into(),
+    };
+    let _ = stream.lock().await.send(Message::Close(Some(frame))).await;
+}
@@ -1,8 +1,45 @@
 //! Controller message protocol
 
-use deku::{DekuContainerWrite, DekuError, DekuRead, DekuWrite};
+use deku::{DekuContainerRead, DekuContainerWrite, DekuError, DekuRead, DekuWrite};
 use tokio_tungstenite::tungstenite::Message;
 
+/// A controller's orientation in radians, sent over the wire as (pitch, roll, yaw)
+#[derive(DekuRead, DekuWrite, Debug, Default, Clone, Copy, PartialEq)]
+pub struct Orientation {
+    /// Rotation about the X axis
+    pub pitch: f32,
+    /// Rotation about the Y axis
+    pub roll: f32,
+    /// Rotation about the Z axis
+    pub yaw: f32,
+}
+
+impl Orientation {
+    /// Creates a new orientation
+    pub fn new(pitch: f32, roll: f32, yaw: f32) -> Self {
+        Self { pitch, roll, yaw }
+    }
+}
+
+/// An orientation stamped with when the controller read it
+#[derive(DekuRead, DekuWrite, Debug, Default, Clone, Copy, PartialEq)]
+pub struct TimedOrientation {
+    /// The orientation read
+    pub orientation: Orientation,
+    /// When it was read, in milliseconds since the Unix epoch
+    pub sent_at_ms: u64,
+}
+
+impl TimedOrientation {
+    /// Creates a new timed orientation
+    pub fn new(orientation: Orientation, sent_at_ms: u64) -> Self {
+        Self {
+            orientation,
+            sent_at_ms,
+        }
+    }
+}
+
 /// Messages a controller can send through
 #[derive(DekuRead, DekuWrite, Debug, Clone, Copy, PartialEq)]
 #[deku(id_type = "u8")]
@@ -16,12 +53,46 @@ pub enum ControllerMessage {
     /// Press B button
     #[deku(id = 0x03)]
     ButtonPressB,
-    /// Update current angle (pitch, roll, yaw)
+    /// Update current angle
     #[deku(id = 0x04)]
-    AngleInfo(f32, f32, f32),
+    AngleInfo(Orientation),
     /// Controller is accepting new client listener connections
     #[deku(id = 0x05)]
-    DevicePairing
+    DevicePairing,
+    /// Update current angle, stamped with when it was read so games can measure latency
+    #[deku(id = 0x06)]
+    TimedAngleInfo(TimedOrientation),
+    /// Release A button
+    #[deku(id = 0x07)]
+    ButtonReleaseA,
+    /// Release B button
+    #[deku(id = 0x08)]
+    ButtonReleaseB,
+}
+
+/// How hard and how long a controller's rumble motor should buzz for
+#[derive(DekuRead, DekuWrite, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rumble {
+    /// Motor strength, from 0 (off) to 255 (full)
+    pub intensity: u8,
+    /// How long to buzz for, in milliseconds
+    pub millis: u16,
+}
+
+impl Rumble {
+    /// Creates a new rumble command
+    pub fn new(intensity: u8, millis: u16) -> Self {
+        Self { intensity, millis }
+    }
+}
+
+/// Messages the server sends down to a controller
+#[derive(DekuRead, DekuWrite, Debug, Clone, Copy, PartialEq, Eq)]
+#[deku(id_type = "u8")]
+pub enum ServerMessage {
+    /// Buzz the rumble motor
+    #[deku(id = 0x01)]
+    Rumble(Rumble),
 }
 
 /// Messages a web socket connection can send before it's upgraded to a Controller or kept as is
@@ -34,6 +105,70 @@ pub enum WsMessage {
     /// Establish connection as a controller with the provided ID
     #[deku(id = 0x02)]
     Controller(u64),
+    /// Join the room with a join code, listening to every controller in it
+    #[deku(id = 0x03)]
+    JoinRoom(u64),
+}
+
+/// A controller's message as relayed to the pages in a room, tagged with the controller's slot
+#[derive(DekuRead, DekuWrite, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerMessage {
+    /// Player slot of the controller that sent it
+    pub player: u8,
+    /// What the controller sent
+    pub message: ControllerMessage,
+}
+
+/// A player's name as they entered it on the game page
+#[derive(DekuRead, DekuWrite, Debug, Default, Clone, PartialEq, Eq)]
+pub struct PlayerName {
+    /// Length of the name in bytes, 0 if the player didn't give one
+    len: u8,
+    /// The name, as UTF-8
+    #[deku(count = "len")]
+    name: Vec<u8>,
+}
+
+impl PlayerName {
+    /// Creates a player's name, cut off at 255 bytes
+    pub fn new(name: &str) -> Self {
+        let name: Vec<u8> = name.bytes().take(u8::MAX as usize).collect();
+        Self {
+            len: name.len() as u8,
+            name,
+        }
+    }
+
+    /// The name, or `None` if the player didn't give one
+    pub fn get(&self) -> Option<String> {
+        (!self.name.is_empty()).then(|| String::from_utf8_lossy(&self.name).into_owned())
+    }
+}
+
+/// Messages a game page can send back once it's listening to a controller
+#[derive(DekuRead, DekuWrite, Debug, Clone, PartialEq, Eq)]
+#[deku(id_type = "u8")]
+pub enum ListenerMessage {
+    /// A game finished with a final score for each player
+    #[deku(id = 0x01)]
+    GameResult {
+        /// Length of the game's name in bytes
+        game_len: u8,
+        /// Name of the game that was played, as UTF-8
+        #[deku(count = "game_len")]
+        game: Vec<u8>,
+        /// How many players took part
+        players: u8,
+        /// Each player's final score, in turn order
+        #[deku(count = "players")]
+        scores: Vec<u32>,
+        /// Each player's name, in turn order
+        #[deku(count = "players")]
+        names: Vec<PlayerName>,
+    },
+    /// Something happened in game that the controller should buzz for
+    #[deku(id = 0x02)]
+    Rumble(Rumble),
 }
 
 impl ControllerMessage {
@@ -44,6 +179,51 @@ impl ControllerMessage {
     }
 }
 
+impl ListenerMessage {
+    /// Creates a game result message, filling in the length prefixes. Names longer than 255 bytes
+    /// and any players past the 255th are cut off, and players without a name are sent without one
+    pub fn game_result(game: &str, scores: &[u32], names: &[&str]) -> Self {
+        let game: Vec<u8> = game.bytes().take(u8::MAX as usize).collect();
+        let scores: Vec<u32> = scores.iter().copied().take(u8::MAX as usize).collect();
+        let names = (0..scores.len())
+            .map(|player| PlayerName::new(names.get(player).copied().unwrap_or_default()))
+            .collect();
+        Self::GameResult {
+            game_len: game.len() as u8,
+            game,
+            players: scores.len() as u8,
+            scores,
+            names,
+        }
+    }
+
+    /// Converts message to binary and then to a tokio tungstenite Message type
+    pub fn to_ws_message(&self) -> Result<Message, DekuError> {
+        let bytes = self.to_bytes()?;
+        Ok(Message::Binary(bytes))
+    }
+}
+
+impl ServerMessage {
+    /// Converts message to binary and then to a tokio tungstenite Message type
+    pub fn to_ws_message(&self) -> Result<Message, DekuError> {
+        let bytes = self.to_bytes()?;
+        Ok(Message::Binary(bytes))
+    }
+
+    /// Parses a message from the binary a controller received
+    pub fn from_binary(buf: &[u8]) -> Result<Self, DekuError> {
+        Self::from_bytes((buf, 0)).map(|(_, msg)| msg)
+    }
+}
+
+impl PlayerMessage {
+    /// Parses a message from the binary a room's page received
+    pub fn from_binary(buf: &[u8]) -> Result<Self, DekuError> {
+        Self::from_bytes((buf, 0)).map(|(_, msg)| msg)
+    }
+}
+
 impl WsMessage {
     /// Converts message to binary and then to a tokio tungstenite Message type
     pub fn to_ws_message(&self) -> Result<Message, DekuError> {
@@ -3,18 +3,39 @@
 //! the site itself is *what* game the controller is currently in (there is no user data, all is
 //! linked and contained via controller). The game logic itself is handled in WASM on the frontend
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use hyper::server::conn::http1;
 use hyper_util::rt::TokioIo;
-use server::serve::{service::SpjortService, SpjortState};
-use tokio::{net::TcpListener, sync::Mutex};
+use server::{
+    serve::{assets::StaticFiles, service::SpjortService, SpjortState, HEARTBEAT_INTERVAL},
+    tls::TlsConfig,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+    signal,
+    sync::{watch, Mutex},
+    task::JoinSet,
+};
+use tokio_util::task::TaskTracker;
+use tracing::{error, info, info_span, warn, Instrument};
+use tracing_subscriber::EnvFilter;
 
 /// How many controller connections are allowed to be queued
 pub const CONTROLLER_QUEUE_LIMIT: usize = 15;
+/// Longest open connections are given to finish up once the server is asked to shut down
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Environment variable picking what gets logged, e.g. `debug` or `server=debug,hyper=warn`
+pub const LOG_VAR: &str = "SPJORTS_LOG";
+/// What gets logged when `SPJORTS_LOG` isn't set
+pub const DEFAULT_LOG: &str = "info";
 
 #[tokio::main]
 async fn main() {
+    let filter = EnvFilter::try_from_env(LOG_VAR).unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
     let (state, controller_write, mut controller_read) = SpjortState::new(15);
     let state = Arc::new(Mutex::new(state));
 
@@ -22,41 +43,122 @@ async fn main() {
         .await
         .expect("Failed to bind to server");
 
-    println!("🏂🎾⛳");
-    println!("Listening on http://localhost:7878");
+    // TLS is only terminated here when a certificate and key are configured
+    let tls = TlsConfig::from_env().map(|config| {
+        config
+            .acceptor()
+            .expect("Failed to load TLS certificate and key")
+    });
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
+    info!("🏂🎾⛳ Listening on {scheme}://localhost:7878");
 
-    let state_clone_server = state.clone();
+    // Dead controller disconnect loop, closing the pages that were listening to them
+    let state_clone_heartbeat = state.clone();
     tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
         loop {
-            let (socket, _) = listener
-                .accept()
-                .await
-                .expect("Failed to accept connection");
-
-            let io = TokioIo::new(socket);
-
-            let service = SpjortService::new(controller_write.clone(), state_clone_server.clone());
-            tokio::spawn(async move {
-                if let Err(e) = http1::Builder::new()
-                    .serve_connection(io, service)
-                    .with_upgrades()
-                    .await
-                {
-                    eprintln!("Error serving connection: {}", e);
-                }
-            });
+            interval.tick().await;
+            let evicted = state_clone_heartbeat.lock().await.heartbeat().await;
+            for controller in evicted {
+                controller.lock().await.disconnect().await;
+            }
         }
     });
 
     // Connection handler thread
-    while let Some(controller) = controller_read.recv().await {
-        state.lock().await.connect(controller).await;
+    let state_clone_connect = state.clone();
+    tokio::spawn(async move {
+        while let Some(controller) = controller_read.recv().await {
+            state_clone_connect.lock().await.connect(controller).await;
+        }
+    });
+
+    // Accept loop, running until Ctrl-C
+    let static_files = Arc::new(StaticFiles::default());
+    let (shutdown_tx, shutdown_rx) = watch::channel(());
+    let mut connections = JoinSet::new();
+    let websockets = TaskTracker::new();
+    let ctrl_c = signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            accepted = listener.accept() => {
+                let (socket, peer) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!(error = %e, "couldn't accept connection");
+                        continue;
+                    }
+                };
+
+                let service = SpjortService::new(
+                    controller_write.clone(),
+                    state.clone(),
+                    static_files.clone(),
+                    websockets.clone(),
+                    shutdown_rx.clone(),
+                );
+                let tls = tls.clone();
+                let shutdown = shutdown_rx.clone();
+                connections.spawn(
+                    async move {
+                        match tls {
+                            Some(acceptor) => match acceptor.accept(socket).await {
+                                Ok(stream) => serve(stream, service, shutdown).await,
+                                Err(e) => warn!(error = %e, "TLS handshake failed"),
+                            },
+                            None => serve(socket, service, shutdown).await,
+                        }
+                    }
+                    .instrument(info_span!("connection", %peer)),
+                );
+            }
+        }
+    }
+
+    // Stop accepting, close every web socket and give open requests a moment to finish
+    info!("shutting down");
+    drop(listener);
+    let _ = shutdown_tx.send(());
+    websockets.close();
+    let drained = tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, async {
+        state.lock().await.shutdown().await;
+        while connections.join_next().await.is_some() {}
+        websockets.wait().await;
+    })
+    .await;
+    if drained.is_err() {
+        warn!(
+            open = connections.len(),
+            websockets = websockets.len(),
+            timeout = ?SHUTDOWN_DRAIN_TIMEOUT,
+            "dropping connections still open after the drain timeout"
+        );
     }
+}
 
-    // TODO Later if controller persistence is really an issue
-    // Dead controller disconnect loop :)
-    /*loop {
-        state.lock().await.heartbeat();
-        std::thread::sleep(Duration::from_secs(30));
-    }*/
+/// Serves HTTP and web socket upgrades over a connection, plain or already wrapped in TLS. Once
+/// `shutdown` fires the connection finishes the request it's on and closes
+async fn serve<S>(stream: S, service: SpjortService, mut shutdown: watch::Receiver<()>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let connection = http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .with_upgrades();
+    tokio::pin!(connection);
+
+    let res = tokio::select! {
+        res = connection.as_mut() => res,
+        _ = shutdown.changed() => {
+            connection.as_mut().graceful_shutdown();
+            connection.await
+        }
+    };
+    if let Err(e) = res {
+        error!(error = %e, "error serving connection");
+    }
 }
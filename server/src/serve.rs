@@ -3,6 +3,7 @@
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 
 use tokio::sync::{
@@ -10,13 +11,22 @@ use tokio::sync::{
     Mutex,
 };
 
+use tracing::{info, warn};
+
 use crate::control::{Controller, ControllerId, ControllerMessage};
+use results::GameResult;
+use room::{random_code, Room, RoomCode};
 
+pub mod assets;
 pub mod registry;
+pub mod results;
+pub mod room;
 pub mod service;
 
 /// How many heartbeat checks before a controller should be dropped
 pub const HEARTBEAT_LIMIT: usize = 50;
+/// How often heartbeats are checked, a controller silent for `HEARTBEAT_LIMIT` of these is dropped
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Controller metadata
 pub type ControllerInfo = (ControllerId, ControllerMessage);
@@ -30,6 +40,10 @@ pub struct SpjortState {
     time_since_heartbeat: HashMap<ControllerId, usize>,
     /// What controller IDs are currently waiting to pair with a listener
     pairing_controllers: HashSet<u64>,
+    /// Every finished game result recorded this session, keyed by the controller it was played with
+    results: HashMap<ControllerId, Vec<GameResult>>,
+    /// Every open room, keyed by its join code
+    rooms: HashMap<RoomCode, Arc<Mutex<Room>>>,
 }
 
 impl SpjortState {
@@ -47,6 +61,8 @@ impl SpjortState {
                 controllers: HashMap::new(),
                 time_since_heartbeat: HashMap::new(),
                 pairing_controllers: HashSet::new(),
+                results: HashMap::new(),
+                rooms: HashMap::new(),
             },
             sender,
             receiver,
@@ -60,6 +76,11 @@ impl SpjortState {
         self.time_since_heartbeat.insert(id, 0);
     }
 
+    /// Gets a connected controller by ID
+    pub fn get_controller(&self, id: ControllerId) -> Option<Arc<Mutex<Controller>>> {
+        self.controllers.get(&id).cloned()
+    }
+
     /// Registers a new controller as awaiting a pairing
     pub fn set_pairing_id(&mut self, controller_id: u64) {
         self.pairing_controllers.insert(controller_id);
@@ -76,8 +97,83 @@ impl SpjortState {
         self.pairing_controllers.iter().cloned().collect()
     }
 
-    /// Checks all heart beats and removes any connections that are higher than the limit
-    pub fn heartbeat(&mut self) {
+    /// Records a finished game result played with a controller
+    pub fn record_result(&mut self, id: ControllerId, result: GameResult) {
+        self.results.entry(id).or_default().push(result);
+    }
+
+    /// Returns all recorded game results across every controller
+    pub fn get_results(&self) -> Vec<GameResult> {
+        self.results.values().flatten().cloned().collect()
+    }
+
+    /// Returns the game results recorded for a single controller
+    pub fn get_controller_results(&self, id: ControllerId) -> &[GameResult] {
+        self.results.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Opens a new empty room, returning its join code
+    pub fn open_room(&mut self) -> RoomCode {
+        let mut code = random_code();
+        while self.rooms.contains_key(&code) {
+            code = random_code();
+        }
+        self.rooms
+            .insert(code, Arc::new(Mutex::new(Room::new(code))));
+        code
+    }
+
+    /// Gets an open room by its join code
+    pub fn get_room(&self, code: RoomCode) -> Option<Arc<Mutex<Room>>> {
+        self.rooms.get(&code).cloned()
+    }
+
+    /// Returns every open room as an *unreferenced* list (so we don't get any nasty locks)
+    pub fn get_rooms(&self) -> Vec<Arc<Mutex<Room>>> {
+        self.rooms.values().cloned().collect()
+    }
+
+    /// Puts a connected controller into a room, returning the player slot it joined into. Returns
+    /// `None` if either the room or the controller doesn't exist
+    pub async fn join_room(&self, code: RoomCode, id: ControllerId) -> Option<usize> {
+        if !self.controllers.contains_key(&id) {
+            return None;
+        }
+        let room = self.get_room(code)?;
+        let slot = room.lock().await.join_controller(id);
+        Some(slot)
+    }
+
+    /// Closes every controller, listener and room connection and forgets them, so the server
+    /// can go down without leaving anyone hanging
+    pub async fn shutdown(&mut self) {
+        info!(
+            controllers = self.controllers.len(),
+            rooms = self.rooms.len(),
+            "closing every connection"
+        );
+        for (_, controller) in self.controllers.drain() {
+            controller.lock().await.shutdown().await;
+        }
+        for (_, room) in self.rooms.drain() {
+            room.lock().await.shutdown().await;
+        }
+        self.time_since_heartbeat.clear();
+        self.pairing_controllers.clear();
+    }
+
+    /// Marks a controller as alive, restarting its heartbeat count. Unknown controllers are
+    /// ignored
+    pub fn beat(&mut self, id: ControllerId) {
+        if let Some(count) = self.time_since_heartbeat.get_mut(&id) {
+            *count = 0;
+        }
+    }
+
+    /// Checks all heart beats and removes any connections that are higher than the limit, freeing
+    /// up their room slots and returning the controllers that were dropped so their listeners can
+    /// be told
+    pub async fn heartbeat(&mut self) -> Vec<Arc<Mutex<Controller>>> {
         let mut naughty = vec![];
         self.time_since_heartbeat.iter_mut().for_each(|(key, val)| {
             *val += 1;
@@ -87,19 +183,40 @@ impl SpjortState {
             }
         });
 
-        naughty.iter().for_each(|key| {
-            self.controllers.remove(key);
-            self.time_since_heartbeat.remove(key);
-        });
+        for key in &naughty {
+            warn!(
+                controller = key,
+                "evicting controller after missed heartbeats"
+            );
+        }
+
+        for room in self.rooms.values() {
+            let mut room = room.lock().await;
+            naughty.iter().for_each(|key| {
+                room.leave_controller(*key);
+            });
+        }
+
+        naughty
+            .iter()
+            .filter_map(|key| {
+                self.time_since_heartbeat.remove(key);
+                self.pairing_controllers.remove(key);
+                self.controllers.remove(key)
+            })
+            .collect()
     }
 }
 
 /// Type of websocket connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WsConnectionType {
     /// Controller with an ID
     Controller(u64),
     /// Listener listening to a controller with ID
     Listener(u64),
+    /// Listener listening to every controller in the room with a join code
+    Room(u64),
     /// Nothing yet
     None,
 }
@@ -0,0 +1,154 @@
+//! Static file serving, cached in memory and revalidated by browsers through ETags
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use http_body_util::Full;
+use hyper::{
+    body::Bytes,
+    header::{CACHE_CONTROL, CONTENT_TYPE, ETAG},
+    http::HeaderValue,
+    Response, StatusCode,
+};
+use tokio::sync::Mutex;
+
+/// Browsers keep files but check back every time, so a rebuilt game is picked up straight away
+/// while an unchanged one only costs a `304 Not Modified`
+const CACHE_CONTROL_VALUE: &str = "no-cache";
+
+/// A file read into memory, along with when it was last changed on disk
+#[derive(Debug, Clone)]
+pub struct CachedFile {
+    /// The file's contents
+    pub body: Bytes,
+    /// When the file was last modified when it was read
+    modified: SystemTime,
+    /// Tag browsers send back to check whether their copy is still current
+    pub etag: String,
+}
+
+/// Every static file served so far, re-read from disk whenever it changes
+#[derive(Debug, Default)]
+pub struct StaticFiles {
+    /// Files read so far, keyed by their path on disk
+    cache: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+/// Maps a request path to the file it serves, if it's a static file. Paths trying to climb out
+/// of the served directories are never static files
+pub fn static_path(uri: &str) -> Option<PathBuf> {
+    if uri.contains("..") {
+        return None;
+    }
+    match uri {
+        "/" => Some("frontend/index.html".into()),
+        "/game" => Some("frontend/game.html".into()),
+        "/favicon.ico" => Some("frontend/favicon.ico".into()),
+        fs if fs.starts_with("/frontend/") || fs.starts_with("/wasm") => Some(fs[1..].into()),
+        _ => None,
+    }
+}
+
+/// Content type served for files without a known extension
+const FALLBACK_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Content types of the files served, by extension
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("wasm", "application/wasm"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("wav", "audio/wav"),
+    ("ogg", "audio/ogg"),
+    ("mp3", "audio/mpeg"),
+    ("glb", "model/gltf-binary"),
+    ("gltf", "model/gltf+json"),
+];
+
+/// The content type to serve a file with, going by its extension
+pub fn content_type(path: &Path) -> &'static str {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| {
+            CONTENT_TYPES
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+        })
+        .map_or(FALLBACK_CONTENT_TYPE, |(_, content_type)| content_type)
+}
+
+impl StaticFiles {
+    /// Gets a file's contents, only going to disk if it hasn't been read yet or has changed since
+    pub async fn load(&self, path: &Path) -> io::Result<CachedFile> {
+        let modified = tokio::fs::metadata(path).await?.modified()?;
+        if let Some(file) = self.cache.lock().await.get(path) {
+            if file.modified == modified {
+                return Ok(file.clone());
+            }
+        }
+
+        let body = Bytes::from(tokio::fs::read(path).await?);
+        let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let file = CachedFile {
+            etag: format!("\"{:x}-{:x}\"", since_epoch.as_nanos(), body.len()),
+            body,
+            modified,
+        };
+        self.cache
+            .lock()
+            .await
+            .insert(path.to_path_buf(), file.clone());
+        Ok(file)
+    }
+
+    /// Responds with a file, or just `304 Not Modified` if the browser's copy is still current.
+    /// Files that can't be read are not found
+    pub async fn serve(
+        &self,
+        path: &Path,
+        if_none_match: Option<&HeaderValue>,
+    ) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+        let Ok(file) = self.load(path).await else {
+            return Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Full::new(Bytes::from_static(b"Not Found")));
+        };
+
+        let response = Response::builder()
+            .header(ETAG, &file.etag)
+            .header(CACHE_CONTROL, CACHE_CONTROL_VALUE);
+        let current = if_none_match
+            .and_then(|tags| tags.to_str().ok())
+            .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == file.etag));
+        if current {
+            return response
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Full::new(Bytes::new()));
+        }
+
+        response
+            .header(CONTENT_TYPE, content_type(path))
+            .status(StatusCode::OK)
+            .body(Full::new(file.body))
+    }
+}
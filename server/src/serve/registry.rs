@@ -17,6 +17,8 @@ pub fn render_id_connection(id: u64) -> String {
 /// Game information for rendering
 #[derive(Serialize)]
 pub struct Game {
+    /// URL-safe name the game's page is served under, as `/sports/{slug}`
+    pub slug: &'static str,
     /// Path to the WASM runtime
     pub wasm_path: &'static str,
     /// Path to thumbnail image
@@ -25,6 +27,12 @@ pub struct Game {
     pub name: &'static str,
     /// If a game is multiplayer or not
     pub multiplayer: bool,
+    /// If a game can be mirrored for left-handed players
+    pub handed: bool,
+    /// If a lone player can be offered a computer opponent
+    pub bot: bool,
+    /// If the game saves unfinished games the page can resume after a reload
+    pub resumable: bool,
 }
 
 impl Game {
@@ -37,7 +45,7 @@ impl Game {
                 <div class="game-name">{}</div>
             </div>
             "#,
-            self.name, self.img, self.name, self.name
+            self.slug, self.img, self.name, self.name
         )
     }
 
@@ -104,16 +112,19 @@ impl Game {
                     </script>
 
                     <script type="module">
-                        import init, {{ Runner }} from '{}'
+                        import init, {{ Runner, RunnerConfig }} from '{}'
 
                         const socket = new WebSocket("/");
                         socket.binaryType = "arraybuffer";
 
+                        // A page in a room hears every controller in it through this one socket
+                        const room = localStorage.getItem("Room");
+
                         socket.addEventListener("open", () => {{
                             console.log("WebSocket connection opened");
                             let id = parseInt(localStorage.getItem("ID"));
                             console.log(`ID: ${{id}}`);
-                            const buffer = createWsMessage(1, id);
+                            const buffer = room ? createWsMessage(3, room) : createWsMessage(1, id);
 
                             socket.send(buffer);
                             console.log("ArrayBuffer sent:", buffer);
@@ -132,41 +143,191 @@ impl Game {
                         }}
 
                         init().then(() => {{
-                            let runner = new Runner();
-                            let send = runner.get_send();
+                            let runner = new Runner(new RunnerConfig().with_rotation_coalescing(true));
+                            let input = runner.get_input();
+                            let session = runner.get_session();
+                            let feedback = runner.get_feedback();
 
-                            if ({}) {{
+                            // Names players entered, sent along with their scores
+                            const names = [];
+{}
+                            if (!resuming && {}) {{
                                 let players = parseInt(prompt("How many players:"));
-                                send.set_players(players);
+                                session.set_players(players);
+
+                                for (let player = 0; player < players; player++) {{
+                                    let name = prompt(`Player ${{player + 1}} name:`);
+                                    if (name) {{
+                                        names[player] = name;
+                                        session.set_player_name(player, name);
+                                    }}
+                                }}
+
+                                if (players === 1 && {} && confirm("Play against the computer?")) {{
+                                    let skill = parseInt(prompt("Computer skill (1-10):"));
+                                    session.set_bot(Number.isNaN(skill) ? 5 : Math.min(Math.max(skill, 1), 10));
+                                }}
+                            }}
+
+                            // Handedness is remembered between visits and only takes effect before the first throw
+                            if ({}) {{
+                                let leftHanded = localStorage.getItem("leftHanded") === "true";
+                                const handedness = document.createElement("button");
+                                handedness.className = "handedness";
+
+                                const applyHandedness = () => {{
+                                    handedness.textContent = leftHanded ? "Left-handed" : "Right-handed";
+                                    session.apply_settings(1.0, 1.0, false, true, leftHanded);
+                                }};
+
+                                handedness.addEventListener("click", () => {{
+                                    leftHanded = !leftHanded;
+                                    localStorage.setItem("leftHanded", leftHanded);
+                                    applyHandedness();
+                                }});
+
+                                document.body.appendChild(handedness);
+                                applyHandedness();
                             }}
 
-                            socket.addEventListener("message", (event) => {{
-                                const buffer = event.data;
+                            // Feeds a controller's buttons and angles into the game
+                            function routeControls(input) {{
+                                return (event) => {{
+                                    const buffer = event.data;
+                                    const dataView = new DataView(buffer);
+                                    const id = dataView.getUint8(0);
+
+                                    switch (id) {{
+                                        case 2:
+                                            // Button A
+                                            input.press_a();
+                                            break;
+                                        case 3:
+                                            // Button B
+                                            input.press_b();
+                                            break;
+                                        case 4:
+                                            // Angle data
+                                            const pitch = dataView.getFloat32(1, true);
+                                            const roll = dataView.getFloat32(5, true);
+                                            const yaw = dataView.getFloat32(9, true);
+                                            input.rotate(pitch, roll, yaw);
+                                            break;
+                                        case 6:
+                                            // Angle data stamped with when the controller read it
+                                            input.rotate_at(
+                                                dataView.getFloat32(1, true),
+                                                dataView.getFloat32(5, true),
+                                                dataView.getFloat32(9, true),
+                                                Number(dataView.getBigUint64(13, true)),
+                                            );
+                                            break;
+                                        case 7:
+                                            // Button A released
+                                            input.release_a();
+                                            break;
+                                        case 8:
+                                            // Button B released
+                                            input.release_b();
+                                            break;
+                                        default:
+                                            console.log("Unknown ID found: ", id);
+                                    }}
+                                }};
+                            }}
+
+                            if (room) {{
+                                // Room frames lead with the player slot of the controller that sent them
+                                const players = [routeControls(input)];
+                                socket.addEventListener("message", (event) => {{
+                                    const slot = new Uint8Array(event.data)[0];
+                                    players[slot] = players[slot] || routeControls(input.for_player(slot));
+                                    players[slot]({{ data: event.data.slice(1) }});
+                                }});
+                            }} else {{
+                                socket.addEventListener("message", routeControls(input));
+
+                                // Every other paired controller gets its own connection, tagged with
+                                // the player slot it was paired into after this page's controller
+                                const primary = parseInt(localStorage.getItem("ID"));
+                                const paired = JSON.parse(localStorage.getItem("PairedIDs") || "[]")
+                                    .filter((id) => id !== primary);
+                                paired.forEach((id, idx) => {{
+                                    const extra = new WebSocket("/");
+                                    extra.binaryType = "arraybuffer";
+                                    extra.addEventListener("open", () => extra.send(createWsMessage(1, id)));
+                                    extra.addEventListener("message", routeControls(input.for_player(idx + 1)));
+                                }});
+                            }}
+
+                            // Synthesized tones for sound cues, [frequency, seconds]
+                            const cues = {{ gutter: [110, 0.4] }};
+                            const audio = new AudioContext();
+                            document.addEventListener("spjorts:sound", (event) => {{
+                                const [frequency, duration] = cues[event.detail] || [440, 0.15];
+                                const oscillator = audio.createOscillator();
+                                const gain = audio.createGain();
+                                oscillator.frequency.value = frequency;
+                                gain.gain.value = 0.2;
+                                oscillator.connect(gain).connect(audio.destination);
+                                oscillator.start();
+                                oscillator.stop(audio.currentTime + duration);
+                            }});
+
+                            // Rumbles go back to the server to be forwarded on to the controller
+                            document.addEventListener("spjorts:rumble", (event) => {{
+                                const [intensity, millis] = event.detail.split(",").map((value) => parseInt(value));
+                                const buffer = new ArrayBuffer(4);
+                                const dataView = new DataView(buffer);
+
+                                dataView.setUint8(0, 2);
+                                dataView.setUint8(1, intensity);
+                                dataView.setUint16(2, millis, true);
+
+                                socket.send(buffer);
+                            }});
+
+                            // Final scores go back to the server over the same socket, tagged with the game and
+                            // followed by each player's name, empty for players who didn't give one
+                            document.addEventListener("spjorts:result", (event) => {{
+                                const scores = event.detail.split(",").map((score) => parseInt(score));
+                                const game = new TextEncoder().encode("{}");
+                                const encoded = scores.map((_, i) => new TextEncoder().encode(names[i] || "").slice(0, 255));
+                                const namesLength = encoded.reduce((length, name) => length + 1 + name.length, 0);
+                                const buffer = new ArrayBuffer(3 + game.length + scores.length * 4 + namesLength);
                                 const dataView = new DataView(buffer);
-                                const id = dataView.getUint8(0);
-
-                                switch (id) {{
-                                    case 2:
-                                        // Button A
-                                        send.press_a();
-                                        break;
-                                    case 3:
-                                        // Button B
-                                        send.press_b();
-                                        break;
-                                    case 4:
-                                        // Angle data
-                                        const pitch = dataView.getFloat32(1, true);
-                                        const yaw = dataView.getFloat32(5, true);
-                                        const roll = dataView.getFloat32(9, true);
-                                        send.rotate(pitch, yaw, roll);
-                                        break;
-                                    default:
-                                        console.log("Unknown ID found: ", id);
-                                }}
 
+                                dataView.setUint8(0, 1);
+                                dataView.setUint8(1, game.length);
+                                new Uint8Array(buffer, 2, game.length).set(game);
+                                dataView.setUint8(2 + game.length, scores.length);
+                                scores.forEach((score, i) => {{
+                                    dataView.setUint32(3 + game.length + i * 4, score, true);
+                                }});
+                                let offset = 3 + game.length + scores.length * 4;
+                                encoded.forEach((name) => {{
+                                    dataView.setUint8(offset, name.length);
+                                    new Uint8Array(buffer, offset + 1, name.length).set(name);
+                                    offset += 1 + name.length;
+                                }});
+
+                                socket.send(buffer);
                             }});
 
+                            function tick() {{
+                                input.poll_gamepad();
+
+                                let event;
+                                while ((event = feedback.poll())) {{
+                                    document.dispatchEvent(
+                                        new CustomEvent(`spjorts:${{event.kind}}`, {{ detail: event.data }})
+                                    );
+                                }}
+
+                                requestAnimationFrame(tick);
+                            }}
+                            requestAnimationFrame(tick);
+
                             socket.addEventListener("error", (error) => {{
                                 console.error("WebSocket error:", error);
                             }});
@@ -175,6 +336,10 @@ impl Game {
                                 console.log("WebSocket connection closed");
                             }});
                             
+                            runner.set_log_callback((level, message) => {{
+                                (console[level] || console.log)(`[game] ${{message}}`);
+                            }});
+
                             console.log("Run has begun");
                             runner.run();
                         }});
@@ -183,18 +348,68 @@ impl Game {
                 </body>
             </html>
             "#,
-            self.name, self.wasm_path, self.multiplayer
+            self.name,
+            self.wasm_path,
+            self.resume_script(),
+            self.multiplayer,
+            self.bot,
+            self.handed,
+            self.name
         )
     }
+
+    /// Script that stashes an unfinished game whenever the page is left and offers to resume it
+    /// after a reload, setting up the players it was being played by again. Games that don't save
+    /// are never resumed
+    fn resume_script(&self) -> &'static str {
+        if self.resumable {
+            RESUME_SCRIPT
+        } else {
+            "\n                            const resuming = false;\n"
+        }
+    }
 }
 
+/// Page script for games that save, run once the session is available. Defines `resuming`
+const RESUME_SCRIPT: &str = r#"
+                            // An unfinished game is stashed whenever the page is left, and can be resumed after a reload
+                            const saveKey = `save:${document.title}`;
+                            const saved = JSON.parse(localStorage.getItem(saveKey) || "{}");
+                            const resuming = saved.game !== undefined && confirm("Resume your unfinished game?");
+                            const stashSave = () => localStorage.setItem(saveKey, runner.export_state());
+                            window.addEventListener("pagehide", stashSave);
+                            document.addEventListener("visibilitychange", () => {
+                                if (document.visibilityState === "hidden") {
+                                    stashSave();
+                                }
+                            });
+
+                            if (resuming) {
+                                session.set_players(saved.players);
+                                if (saved.bot) {
+                                    session.set_bot(saved.bot);
+                                }
+                                saved.names.forEach((name, player) => {
+                                    if (name) {
+                                        names[player] = name;
+                                        session.set_player_name(player, name);
+                                    }
+                                });
+                                runner.import_state(JSON.stringify(saved));
+                            }
+"#;
+
 macro_rules! game {
-    ($wasm:expr_2021, $img:expr_2021, $descr:expr_2021, $mult:expr_2021) => {
+    ($slug:expr_2021, $wasm:expr_2021, $img:expr_2021, $descr:expr_2021, $mult:expr_2021, $handed:expr_2021, $bot:expr_2021, $resumable:expr_2021) => {
         Game {
+            slug: $slug,
             wasm_path: $wasm,
             img: $img,
             name: $descr,
             multiplayer: $mult,
+            handed: $handed,
+            bot: $bot,
+            resumable: $resumable,
         }
     };
 }
@@ -202,15 +417,279 @@ macro_rules! game {
 /// All registered games
 pub const GAMES: &'static [Game] = &[
     game!(
+        "cube",
         "/wasm/cube/out/cube.js",
         "/frontend/bg/cube.png",
         "THE_CUBE",
+        false,
+        false,
+        false,
         false
     ),
     game!(
+        "bowling",
         "/wasm/bowling/out/bowling.js",
         "/frontend/bg/bowling.jpg",
         "Bowling",
+        true,
+        true,
+        true,
         true
     ),
+    game!(
+        "golf",
+        "/wasm/golf/out/golf.js",
+        "/frontend/bg/splash.png",
+        "Golf",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "darts",
+        "/wasm/darts/out/darts.js",
+        "/frontend/bg/splash.png",
+        "Darts",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "tennis",
+        "/wasm/tennis/out/tennis.js",
+        "/frontend/bg/splash.png",
+        "Tennis",
+        true,
+        true,
+        true,
+        false
+    ),
+    game!(
+        "archery",
+        "/wasm/archery/out/archery.js",
+        "/frontend/bg/splash.png",
+        "Archery",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "curling",
+        "/wasm/curling/out/curling.js",
+        "/frontend/bg/splash.png",
+        "Curling",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "slalom",
+        "/wasm/slalom/out/slalom.js",
+        "/frontend/bg/splash.png",
+        "Slalom",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "batting",
+        "/wasm/batting/out/batting.js",
+        "/frontend/bg/splash.png",
+        "Batting",
+        true,
+        true,
+        false,
+        false
+    ),
+    game!(
+        "pingpong",
+        "/wasm/pingpong/out/pingpong.js",
+        "/frontend/bg/splash.png",
+        "Ping Pong",
+        true,
+        false,
+        true,
+        false
+    ),
+    game!(
+        "discgolf",
+        "/wasm/discgolf/out/discgolf.js",
+        "/frontend/bg/splash.png",
+        "Disc Golf",
+        true,
+        true,
+        false,
+        false
+    ),
+    game!(
+        "axethrow",
+        "/wasm/axethrow/out/axethrow.js",
+        "/frontend/bg/splash.png",
+        "Axe Throwing",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "horseshoes",
+        "/wasm/horseshoes/out/horseshoes.js",
+        "/frontend/bg/splash.png",
+        "Horseshoes",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "cornhole",
+        "/wasm/cornhole/out/cornhole.js",
+        "/frontend/bg/splash.png",
+        "Cornhole",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "skeeball",
+        "/wasm/skeeball/out/skeeball.js",
+        "/frontend/bg/splash.png",
+        "Skee-Ball",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "boxing",
+        "/wasm/boxing/out/boxing.js",
+        "/frontend/bg/splash.png",
+        "Boxing",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "fishing",
+        "/wasm/fishing/out/fishing.js",
+        "/frontend/bg/splash.png",
+        "Fishing",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "trackfield",
+        "/wasm/trackfield/out/trackfield.js",
+        "/frontend/bg/splash.png",
+        "Track & Field",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "minigolf",
+        "/wasm/minigolf/out/minigolf.js",
+        "/frontend/bg/splash.png",
+        "Mini Golf",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "pool",
+        "/wasm/pool/out/pool.js",
+        "/frontend/bg/splash.png",
+        "Pool",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "shuffleboard",
+        "/wasm/shuffleboard/out/shuffleboard.js",
+        "/frontend/bg/splash.png",
+        "Shuffleboard",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "volleyserve",
+        "/wasm/volleyserve/out/volleyserve.js",
+        "/frontend/bg/splash.png",
+        "Volleyball Serve",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "freethrow",
+        "/wasm/freethrow/out/freethrow.js",
+        "/frontend/bg/splash.png",
+        "Free Throws",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "hammerthrow",
+        "/wasm/hammerthrow/out/hammerthrow.js",
+        "/frontend/bg/splash.png",
+        "Hammer Throw",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "kayak",
+        "/wasm/kayak/out/kayak.js",
+        "/frontend/bg/splash.png",
+        "Kayak Sprint",
+        true,
+        false,
+        false,
+        false
+    ),
+    game!(
+        "fencing",
+        "/wasm/fencing/out/fencing.js",
+        "/frontend/bg/splash.png",
+        "Fencing",
+        true,
+        true,
+        false,
+        false
+    ),
+    game!(
+        "airhockey",
+        "/wasm/airhockey/out/airhockey.js",
+        "/frontend/bg/splash.png",
+        "Air Hockey",
+        true,
+        false,
+        true,
+        false
+    ),
 ];
+
+/// The game served at a `/sports/{slug}` path, if the slug matches one exactly
+pub fn game_for_path(path: &str) -> Option<&'static Game> {
+    let slug = path.strip_prefix("/sports/");
+    GAMES.iter().find(|g| slug == Some(g.slug))
+}
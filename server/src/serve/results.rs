@@ -0,0 +1,211 @@
+//! Finished game results and end-of-night party summaries
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+/// Default summary window if none is provided, in hours
+pub const DEFAULT_SUMMARY_HOURS: u64 = 12;
+
+/// A single finished game for one player
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GameResult {
+    /// Name of the game played
+    pub game: String,
+    /// Who played
+    pub player: String,
+    /// Final score
+    pub score: u32,
+    /// Strikes thrown, if the game has them
+    pub strikes: u32,
+    /// Seconds since the unix epoch the result was recorded at
+    pub recorded_at: u64,
+}
+
+impl GameResult {
+    /// Creates a new result stamped with the current time
+    pub fn new(
+        game: impl Into<String>,
+        player: impl Into<String>,
+        score: u32,
+        strikes: u32,
+    ) -> Self {
+        Self {
+            game: game.into(),
+            player: player.into(),
+            score,
+            strikes,
+            recorded_at: now(),
+        }
+    }
+}
+
+/// The best score recorded for a game
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TopScore {
+    /// Name of the game
+    pub game: String,
+    /// Who scored it
+    pub player: String,
+    /// The score
+    pub score: u32,
+}
+
+/// The player whose score rose the most between their first and last game
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MostImproved {
+    /// Who improved
+    pub player: String,
+    /// Name of the game they improved at
+    pub game: String,
+    /// How many points their last score beat their first by
+    pub improvement: i64,
+}
+
+/// Aggregate recap of every result within a time window
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PartySummary {
+    /// How many hours back the summary covers
+    pub window_hours: u64,
+    /// Total games played
+    pub games_played: usize,
+    /// Best score per game
+    pub top_scores: Vec<TopScore>,
+    /// Most improved player, if anyone played a game more than once
+    pub most_improved: Option<MostImproved>,
+    /// Total strikes across every game
+    pub total_strikes: u32,
+}
+
+/// Seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|dur| dur.as_secs())
+        .unwrap_or(0)
+}
+
+impl PartySummary {
+    /// Summarizes all results recorded within the last `window_hours`
+    pub fn from_results(results: &[GameResult], window_hours: u64) -> Self {
+        let cutoff = now().saturating_sub(window_hours * 60 * 60);
+        let mut in_window: Vec<_> = results
+            .iter()
+            .filter(|res| res.recorded_at >= cutoff)
+            .collect();
+        in_window.sort_by_key(|res| res.recorded_at);
+
+        let mut top: HashMap<&str, &GameResult> = HashMap::new();
+        for &res in &in_window {
+            let best = top.entry(res.game.as_str()).or_insert(res);
+            if res.score > best.score {
+                *best = res;
+            }
+        }
+
+        let mut top_scores: Vec<_> = top
+            .into_values()
+            .map(|res| TopScore {
+                game: res.game.clone(),
+                player: res.player.clone(),
+                score: res.score,
+            })
+            .collect();
+        top_scores.sort_by(|a, b| a.game.cmp(&b.game));
+
+        let mut first_last: HashMap<(&str, &str), (u32, u32)> = HashMap::new();
+        for res in &in_window {
+            first_last
+                .entry((res.player.as_str(), res.game.as_str()))
+                .and_modify(|(_, last)| *last = res.score)
+                .or_insert((res.score, res.score));
+        }
+
+        let most_improved = first_last
+            .into_iter()
+            .map(|((player, game), (first, last))| MostImproved {
+                player: player.to_string(),
+                game: game.to_string(),
+                improvement: last as i64 - first as i64,
+            })
+            .filter(|imp| imp.improvement > 0)
+            .max_by(|a, b| {
+                a.improvement
+                    .cmp(&b.improvement)
+                    .then_with(|| b.player.cmp(&a.player))
+            });
+
+        Self {
+            window_hours,
+            games_played: in_window.len(),
+            top_scores,
+            most_improved,
+            total_strikes: in_window.iter().map(|res| res.strikes).sum(),
+        }
+    }
+
+    /// Renders a shareable recap page for putting on the TV
+    pub fn render_html(&self) -> String {
+        let top_scores = if self.top_scores.is_empty() {
+            r#"<div class="name">No games played yet!</div>"#.to_string()
+        } else {
+            self.top_scores
+                .iter()
+                .map(|top| {
+                    format!(
+                        r#"<div class="name">{}: {} by {}</div>"#,
+                        escape(&top.game),
+                        top.score,
+                        escape(&top.player)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let most_improved = match &self.most_improved {
+            Some(imp) => format!(
+                r#"<div class="name">Most Improved: {} (+{} at {})</div>"#,
+                escape(&imp.player),
+                imp.improvement,
+                escape(&imp.game)
+            ),
+            None => String::new(),
+        };
+
+        format!(
+            r#"
+            <!DOCTYPE html>
+            <html lang="en">
+                <head>
+                    <meta charset="UTF-8">
+                    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+                    <link rel="stylesheet" href="/frontend/style/index.css"/>
+                    <title>Spjörts Party Recap</title>
+                </head>
+                <body>
+                    <div class="container">
+                        <div class="title"><b class="white">Party</b> Recap</div>
+                        <div class="name">{} games played in the last {} hours</div>
+                        <div class="name">{} strikes thrown</div>
+                        {}
+                        {}
+                    </div>
+                </body>
+            </html>
+            "#,
+            self.games_played, self.window_hours, self.total_strikes, top_scores, most_improved
+        )
+    }
+}
+
+/// Escapes user provided text for safe embedding in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,141 @@
+//! Rooms that pair several controllers to one game session under a join code
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+};
+
+use futures::SinkExt;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::warn;
+
+use crate::control::{close, ControllerId};
+
+use super::service::WebsocketWriteStream;
+
+/// Join code players type in to get into a room
+pub type RoomCode = u64;
+
+/// Smallest join code handed out, so every code is four digits
+const MIN_CODE: RoomCode = 1000;
+/// How many join codes there are to pick from
+const CODE_SPAN: RoomCode = 9000;
+
+/// Picks a random four digit join code
+pub fn random_code() -> RoomCode {
+    MIN_CODE + RandomState::new().build_hasher().finish() % CODE_SPAN
+}
+
+/// A game session any number of controllers and pages can join. Each controller keeps the player
+/// slot it joined into, and everything it sends is relayed to every page in the room tagged with
+/// that slot
+pub struct Room {
+    /// The join code
+    pub code: RoomCode,
+    /// Controller in each player slot, `None` once one has left so the others keep their slots
+    controllers: Vec<Option<ControllerId>>,
+    /// Web Socket streams of the pages playing in the room
+    listeners: Vec<Arc<Mutex<WebsocketWriteStream>>>,
+}
+
+impl Room {
+    /// Creates an empty room
+    pub fn new(code: RoomCode) -> Self {
+        Self {
+            code,
+            controllers: vec![],
+            listeners: vec![],
+        }
+    }
+
+    /// Puts a controller in the first free player slot, returning the slot. A controller already
+    /// in the room keeps its slot
+    pub fn join_controller(&mut self, id: ControllerId) -> usize {
+        if let Some(slot) = self.slot_of(id) {
+            return slot;
+        }
+        match self.controllers.iter().position(Option::is_none) {
+            Some(slot) => {
+                self.controllers[slot] = Some(id);
+                slot
+            }
+            None => {
+                self.controllers.push(Some(id));
+                self.controllers.len() - 1
+            }
+        }
+    }
+
+    /// Frees up a controller's player slot, returning whether it was in the room
+    pub fn leave_controller(&mut self, id: ControllerId) -> bool {
+        match self.slot_of(id) {
+            Some(slot) => {
+                self.controllers[slot] = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The player slot a controller is in, if it's in the room
+    pub fn slot_of(&self, id: ControllerId) -> Option<usize> {
+        self.controllers.iter().position(|slot| *slot == Some(id))
+    }
+
+    /// The controller in a player slot, if there is one
+    pub fn controller(&self, slot: usize) -> Option<ControllerId> {
+        self.controllers.get(slot).copied().flatten()
+    }
+
+    /// Every controller in the room, in slot order
+    pub fn controllers(&self) -> impl Iterator<Item = ControllerId> + '_ {
+        self.controllers.iter().flatten().copied()
+    }
+
+    /// Adds a page to the room
+    pub fn new_listener(&mut self, listener: Arc<Mutex<WebsocketWriteStream>>) {
+        self.listeners.push(listener);
+    }
+
+    /// Relays a controller's binary message to every page in the room, prefixed with the
+    /// controller's player slot. Pages that can't be reached are dropped, as are messages from
+    /// controllers that aren't in the room or whose slot doesn't fit in a byte
+    pub async fn relay(&mut self, id: ControllerId, msg: &[u8]) {
+        let Some(slot) = self.slot_of(id).and_then(|slot| u8::try_from(slot).ok()) else {
+            return;
+        };
+        let mut tagged = Vec::with_capacity(msg.len() + 1);
+        tagged.push(slot);
+        tagged.extend_from_slice(msg);
+
+        let listening = self.listeners.len();
+        let mut kept = Vec::with_capacity(listening);
+        for listener in self.listeners.drain(..) {
+            let sent = listener
+                .lock()
+                .await
+                .send(Message::binary(tagged.clone()))
+                .await;
+            if sent.is_ok() {
+                kept.push(listener);
+            }
+        }
+        let dropped = listening - kept.len();
+        if dropped > 0 {
+            warn!(
+                room = self.code,
+                dropped, "dropping pages that couldn't be reached"
+            );
+        }
+        self.listeners = kept;
+    }
+
+    /// Closes every page's connection and lets go of them, for when the server is going down
+    pub async fn shutdown(&mut self) {
+        for listener in self.listeners.drain(..) {
+            close(&listener, "Server shutting down").await;
+        }
+    }
+}
@@ -1,31 +1,41 @@
 //! Hyper service implementation
 
-use std::{fs::File, future::Future, io::Read, pin::Pin, sync::Arc};
+use std::{future::Future, pin::Pin, sync::Arc};
 
 use deku::DekuContainerRead;
-use futures::{stream::SplitSink, StreamExt};
+use futures::{Sink, StreamExt};
 use http_body_util::Full;
 use hyper::{
     body::{self, Bytes},
+    header::IF_NONE_MATCH,
     service::Service,
-    upgrade::Upgraded,
     Method, Request, Response, StatusCode,
 };
 use hyper_tungstenite::is_upgrade_request;
-use hyper_util::rt::TokioIo;
-use tokio::sync::{mpsc::Sender, Mutex};
-use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+use tokio::sync::{mpsc::Sender, watch, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, field, info, info_span, warn, Instrument, Span};
 use url::Url;
 
 use crate::{
-    control::{msg::WsMessage, Controller},
+    control::{
+        close,
+        msg::{ListenerMessage, PlayerName, WsMessage},
+        Controller,
+    },
     serve::{registry::GAMES, SpjortState, WsConnectionType},
 };
 
-use super::registry::render_id_connection;
+use super::{
+    assets::{static_path, StaticFiles},
+    registry::{game_for_path, render_id_connection},
+    results::{GameResult, PartySummary, DEFAULT_SUMMARY_HOURS},
+};
 
 /// Web socket write stream
-pub type WebsocketWriteStream = SplitSink<WebSocketStream<TokioIo<Upgraded>>, Message>;
+pub type WebsocketWriteStream =
+    Box<dyn Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Send + Unpin>;
 
 /// Service implementation responsible for handling routes and updating new controller connections
 pub struct SpjortService {
@@ -33,67 +43,231 @@ pub struct SpjortService {
     controller_sender: Sender<Arc<Mutex<Controller>>>,
     /// The current state
     state: Arc<Mutex<SpjortState>>,
+    /// Static files shared between every connection
+    static_files: Arc<StaticFiles>,
+    /// Web sockets upgraded from any connection, so shutdown can wait for them to close
+    websockets: TaskTracker,
+    /// Fires when the server is shutting down, closing every web socket
+    shutdown: watch::Receiver<()>,
 }
 
 impl SpjortService {
-    /// Creates a new spjort service wrapping a controller sender
+    /// Creates a new spjort service wrapping a controller sender. Web sockets it upgrades are
+    /// spawned on `websockets` and closed once `shutdown` fires
     pub fn new(
         controller_sender: Sender<Arc<Mutex<Controller>>>,
         state: Arc<Mutex<SpjortState>>,
+        static_files: Arc<StaticFiles>,
+        websockets: TaskTracker,
+        shutdown: watch::Receiver<()>,
     ) -> Self {
         Self {
             controller_sender,
             state,
+            static_files,
+            websockets,
+            shutdown,
         }
     }
 }
 
+/// Ways a binary websocket frame can violate the connection protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsProtocolError {
+    /// An empty frame was received
+    EmptyFrame,
+    /// A handshake frame could not be parsed
+    MalformedHandshake,
+    /// The requested controller is not connected
+    UnknownController(u64),
+    /// No room is open with the requested join code
+    UnknownRoom(u64),
+    /// A listener connection sent data that isn't a valid listener message
+    MalformedListenerMessage,
+    /// The controller connection queue has been closed
+    ControllerQueueClosed,
+}
+
+/// Handles a single binary frame from a websocket, upgrading the connection type on a valid
+/// handshake and forwarding controller data to its listeners and rooms. Any frame from a controller counts
+/// as a heartbeat, since controllers streaming their angle don't send separate ones
 async fn handle_ws_binary(
     buf: &[u8],
     controller_type: &mut WsConnectionType,
     sender: Sender<Arc<Mutex<Controller>>>,
     state: Arc<Mutex<SpjortState>>,
     write_stream: Arc<Mutex<WebsocketWriteStream>>,
-) {
+) -> Result<(), WsProtocolError> {
+    let opcode = *buf.first().ok_or(WsProtocolError::EmptyFrame)?;
+
     match controller_type {
         WsConnectionType::Controller(id) => {
-            match buf[0] {
+            state.lock().await.beat(*id);
+            match opcode {
+                0x01 => {
+                    // Heartbeats only keep the controller alive, listeners don't need them
+                }
                 0x05 => {
                     // Controller ID wants to be paired
-                    {
-                        state.lock().await.set_pairing_id(*id);
-                    }
+                    state.lock().await.set_pairing_id(*id);
+                    info!("controller waiting to pair");
                 }
                 _ => {
-                    let controller = &state.lock().await.controllers[&id];
-                    let mut controller = controller.lock().await;
-                    controller.broadcast(buf).await
+                    let controller = state
+                        .lock()
+                        .await
+                        .get_controller(*id)
+                        .ok_or(WsProtocolError::UnknownController(*id))?;
+                    controller.lock().await.broadcast(buf).await;
+                    let rooms = state.lock().await.get_rooms();
+                    for room in rooms {
+                        room.lock().await.relay(*id, buf).await;
+                    }
                 }
             }
         }
         WsConnectionType::None => {
-            let (_, val) = WsMessage::from_bytes((buf, 0)).unwrap();
+            let (_, val) =
+                WsMessage::from_bytes((buf, 0)).map_err(|_| WsProtocolError::MalformedHandshake)?;
             match val {
                 WsMessage::Controller(id) => {
-                    *controller_type = WsConnectionType::Controller(id);
-                    let new_controller = Arc::new(Mutex::new(Controller::new(id)));
+                    let new_controller = Arc::new(Mutex::new(Controller::new(id, write_stream)));
                     sender
                         .send(new_controller)
                         .await
-                        .expect("Send new controller");
+                        .map_err(|_| WsProtocolError::ControllerQueueClosed)?;
+                    *controller_type = WsConnectionType::Controller(id);
+                    info!(controller = id, "controller connected");
                 }
                 WsMessage::Establish(id) => {
+                    let controller = state
+                        .lock()
+                        .await
+                        .get_controller(id)
+                        .ok_or(WsProtocolError::UnknownController(id))?;
+                    controller.lock().await.new_listener(write_stream);
                     *controller_type = WsConnectionType::Listener(id);
-                    let controller = &state.lock().await.controllers[&id];
-                    let mut controller = controller.lock().await;
-                    controller.new_listener(write_stream);
+                    info!(listener = id, "listening to controller");
+                }
+                WsMessage::JoinRoom(code) => {
+                    let room = state
+                        .lock()
+                        .await
+                        .get_room(code)
+                        .ok_or(WsProtocolError::UnknownRoom(code))?;
+                    room.lock().await.new_listener(write_stream);
+                    *controller_type = WsConnectionType::Room(code);
+                    info!(room = code, "listening to room");
                 }
             }
         }
-        WsConnectionType::Listener(_) => {
-            unreachable!("Listeners should only listen")
+        WsConnectionType::Listener(id) => {
+            let (_, val) = ListenerMessage::from_bytes((buf, 0))
+                .map_err(|_| WsProtocolError::MalformedListenerMessage)?;
+            match val {
+                ListenerMessage::GameResult {
+                    game,
+                    scores,
+                    names,
+                    ..
+                } => {
+                    let game = String::from_utf8_lossy(&game);
+                    info!(%game, players = scores.len(), "game result recorded");
+                    let mut state = state.lock().await;
+                    for (player, score) in scores.into_iter().enumerate() {
+                        let player = player_name(&names, player);
+                        state.record_result(*id, GameResult::new(game.as_ref(), player, score, 0));
+                    }
+                }
+                ListenerMessage::Rumble(rumble) => {
+                    let controller = state
+                        .lock()
+                        .await
+                        .get_controller(*id)
+                        .ok_or(WsProtocolError::UnknownController(*id))?;
+                    controller.lock().await.rumble(rumble).await;
+                }
+            }
+        }
+        WsConnectionType::Room(code) => {
+            let (_, val) = ListenerMessage::from_bytes((buf, 0))
+                .map_err(|_| WsProtocolError::MalformedListenerMessage)?;
+            let room = state
+                .lock()
+                .await
+                .get_room(*code)
+                .ok_or(WsProtocolError::UnknownRoom(*code))?;
+            match val {
+                ListenerMessage::GameResult {
+                    game,
+                    scores,
+                    names,
+                    ..
+                } => {
+                    // Each player's score is kept with the controller in their slot
+                    let game = String::from_utf8_lossy(&game);
+                    info!(%game, players = scores.len(), "game result recorded");
+                    let room = room.lock().await;
+                    let mut state = state.lock().await;
+                    for (player, score) in scores.into_iter().enumerate() {
+                        if let Some(id) = room.controller(player) {
+                            let player = player_name(&names, player);
+                            state.record_result(
+                                id,
+                                GameResult::new(game.as_ref(), player, score, 0),
+                            );
+                        }
+                    }
+                }
+                ListenerMessage::Rumble(rumble) => {
+                    // Rumbles aren't tagged with a player, so everyone in the room feels them
+                    let ids: Vec<_> = room.lock().await.controllers().collect();
+                    let controllers: Vec<_> = {
+                        let state = state.lock().await;
+                        ids.into_iter()
+                            .filter_map(|id| state.get_controller(id))
+                            .collect()
+                    };
+                    for controller in controllers {
+                        controller.lock().await.rumble(rumble).await;
+                    }
+                }
+            }
         }
     }
+
+    Ok(())
+}
+
+/// What to call a player in their results: the name they entered, or their place in the turn
+/// order if they didn't give one
+fn player_name(names: &[PlayerName], player: usize) -> String {
+    names
+        .get(player)
+        .and_then(PlayerName::get)
+        .unwrap_or_else(|| format!("Player {}", player + 1))
+}
+
+/// Notes what a web socket turned out to be on its span, once its handshake has gone through
+fn record_connection(span: &Span, conn: WsConnectionType) {
+    match conn {
+        WsConnectionType::Controller(id) => span.record("controller", id),
+        WsConnectionType::Listener(id) => span.record("listener", id),
+        WsConnectionType::Room(code) => span.record("room", code),
+        WsConnectionType::None => span,
+    };
+}
+
+/// Reads the `hours` query parameter of a summary request, falling back to the default window
+fn summary_window_hours(uri: &str) -> u64 {
+    Url::parse(&format!("https://dumbfix.com/{}", uri))
+        .ok()
+        .and_then(|url| {
+            url.query_pairs()
+                .find(|(key, _)| key == "hours")
+                .and_then(|(_, hours)| hours.parse().ok())
+        })
+        .unwrap_or(DEFAULT_SUMMARY_HOURS)
 }
 
 impl Service<Request<body::Incoming>> for SpjortService {
@@ -102,71 +276,103 @@ impl Service<Request<body::Incoming>> for SpjortService {
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
     fn call(&self, mut req: Request<body::Incoming>) -> Self::Future {
+        let span = info_span!("request", method = %req.method(), route = %req.uri().path());
         if is_upgrade_request(&req) {
             let (response, websocket) =
                 hyper_tungstenite::upgrade(&mut req, None).expect("Upgrade to WebSocket");
+            info!(parent: &span, "upgrading to web socket");
 
+            let ws_span = info_span!(
+                parent: &span,
+                "websocket",
+                controller = field::Empty,
+                listener = field::Empty,
+                room = field::Empty,
+            );
             let mut controller_type = WsConnectionType::None;
             let sender = self.controller_sender.clone();
             let state = self.state.clone();
-            tokio::spawn(async move {
-                let (ws_write, mut ws_read) = websocket.await.expect("Await websocket").split();
-                let ws_write = Arc::new(Mutex::new(ws_write));
-                while let Some(Ok(msg)) = ws_read.next().await {
-                    match msg {
-                        Message::Binary(buf) => {
-                            handle_ws_binary(
-                                &buf,
-                                &mut controller_type,
-                                sender.clone(),
-                                state.clone(),
-                                ws_write.clone(),
-                            )
-                            .await
+            let mut shutdown = self.shutdown.clone();
+            self.websockets.spawn(
+                async move {
+                    // A socket still upgrading when the server goes down is never opened
+                    let websocket = tokio::select! {
+                        websocket = websocket => websocket.expect("Await websocket"),
+                        _ = shutdown.changed() => return,
+                    };
+                    let (ws_write, mut ws_read) = websocket.split();
+                    let ws_write: WebsocketWriteStream = Box::new(ws_write);
+                    let ws_write = Arc::new(Mutex::new(ws_write));
+                    loop {
+                        // Every socket is closed on shutdown, even one that hasn't said what it is
+                        let msg = tokio::select! {
+                            msg = ws_read.next() => msg,
+                            _ = shutdown.changed() => {
+                                close(&ws_write, "Server shutting down").await;
+                                break;
+                            }
+                        };
+                        let Some(Ok(msg)) = msg else {
+                            break;
+                        };
+                        match msg {
+                            Message::Binary(buf) => {
+                                let before = controller_type;
+                                if let Err(e) = handle_ws_binary(
+                                    &buf,
+                                    &mut controller_type,
+                                    sender.clone(),
+                                    state.clone(),
+                                    ws_write.clone(),
+                                )
+                                .await
+                                {
+                                    warn!(error = ?e, "web socket protocol error");
+                                }
+                                if controller_type != before {
+                                    record_connection(&Span::current(), controller_type);
+                                }
+                            }
+                            _ => {}
                         }
-                        _ => {}
                     }
+                    debug!("web socket closed");
                 }
-            });
+                .instrument(ws_span),
+            );
 
             Box::pin(async { Ok(response) })
+        } else if let (&Method::GET, Some(path)) = (req.method(), static_path(req.uri().path())) {
+            let static_files = self.static_files.clone();
+            let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+            Box::pin(
+                async move {
+                    let res = static_files.serve(&path, if_none_match.as_ref()).await;
+                    if let Ok(res) = &res {
+                        debug!(status = %res.status(), "served static file");
+                    }
+                    res
+                }
+                .instrument(span),
+            )
         } else {
-            let mut response = Response::builder();
+            let _request = span.enter();
+            let response = Response::builder();
 
             let res = match *req.method() {
                 Method::GET => match req.uri().path() {
-                    "/" => {
-                        let mut buf = vec![];
-                        let mut page =
-                            File::open("frontend/index.html").expect("Failed to find file");
-                        page.read_to_end(&mut buf)
-                            .expect("Failed to read to buffer");
-                        response
-                            .status(StatusCode::OK)
-                            .body(Full::new(Bytes::copy_from_slice(&buf)))
-                    }
-                    "/game" => {
-                        let mut buf = vec![];
-                        let mut page =
-                            File::open("frontend/game.html").expect("Failed to find file");
-                        page.read_to_end(&mut buf)
-                            .expect("Failed to read to buffer");
-                        response
-                            .status(StatusCode::OK)
-                            .body(Full::new(Bytes::copy_from_slice(&buf)))
-                    }
-                    "/games" => {
+                    "/fragments/games" => {
                         let games = GAMES
                             .iter()
                             .map(|game| game.render_html())
                             .collect::<Vec<_>>()
                             .join(" ");
                         response
-                            .header("content-type", "application/json")
+                            .header("content-type", "text/html")
                             .status(StatusCode::OK)
                             .body(Full::new(Bytes::copy_from_slice(games.as_bytes())))
                     }
-                    "/controllers" => {
+                    "/fragments/controllers" => {
                         let ids = {
                             futures::executor::block_on(self.state.lock()).get_pairing_devices()
                         };
@@ -176,19 +382,104 @@ impl Service<Request<body::Incoming>> for SpjortService {
                             .collect::<Vec<_>>()
                             .join(" ");
                         response
-                            .header("content-type", "application/json")
+                            .header("content-type", "text/html")
                             .status(StatusCode::OK)
                             .body(Full::new(Bytes::copy_from_slice(controller_ids.as_bytes())))
                     }
-                    "/favicon.ico" => {
-                        let mut buf = vec![];
-                        let mut page =
-                            File::open("frontend/favicon.ico").expect("Failed to find file");
-                        page.read_to_end(&mut buf)
-                            .expect("Failed to read to buffer");
+                    "/api/games" => {
+                        let json = serde_json::to_string(GAMES).expect("Serialize games");
                         response
+                            .header("content-type", "application/json")
                             .status(StatusCode::OK)
-                            .body(Full::new(Bytes::copy_from_slice(&buf)))
+                            .body(Full::new(Bytes::copy_from_slice(json.as_bytes())))
+                    }
+                    "/api/controllers" => {
+                        let state = self.state.clone();
+                        return Box::pin(
+                            async move {
+                                let ids = state.lock().await.get_pairing_devices();
+                                let json =
+                                    serde_json::to_string(&ids).expect("Serialize controller IDs");
+                                response
+                                    .header("content-type", "application/json")
+                                    .status(StatusCode::OK)
+                                    .body(Full::new(Bytes::copy_from_slice(json.as_bytes())))
+                            }
+                            .instrument(span.clone()),
+                        );
+                    }
+                    "/rooms/join" => {
+                        let uri = req.uri().to_string();
+                        let request_url =
+                            Url::parse(&format!("https://dumbfix.com/{}", uri)).unwrap();
+                        let param = |name: &str| -> Option<u64> {
+                            request_url
+                                .query_pairs()
+                                .find(|(key, _)| key == name)
+                                .and_then(|(_, val)| val.parse().ok())
+                        };
+                        let (code, id) = (param("code"), param("id"));
+                        let state = self.state.clone();
+                        return Box::pin(
+                            async move {
+                                let slot = match (code, id) {
+                                    (Some(code), Some(id)) => {
+                                        let slot = state.lock().await.join_room(code, id).await;
+                                        match slot {
+                                            Some(slot) => info!(
+                                                room = code,
+                                                controller = id,
+                                                slot,
+                                                "joined room"
+                                            ),
+                                            None => warn!(
+                                                room = code,
+                                                controller = id,
+                                                "room join refused"
+                                            ),
+                                        }
+                                        slot
+                                    }
+                                    _ => None,
+                                };
+                                let body = slot
+                                    .map_or_else(|| "false".to_string(), |slot| slot.to_string());
+                                response
+                                    .header("content-type", "application/json")
+                                    .status(StatusCode::OK)
+                                    .body(Full::new(Bytes::from(body)))
+                            }
+                            .instrument(span.clone()),
+                        );
+                    }
+                    path @ ("/summary" | "/summary/page") => {
+                        let hours = summary_window_hours(&req.uri().to_string());
+                        let json = path == "/summary";
+                        let state = self.state.clone();
+                        return Box::pin(
+                            async move {
+                                // The lock is only held long enough to copy the results out
+                                let results = state.lock().await.get_results();
+                                let summary = PartySummary::from_results(&results, hours);
+
+                                if json {
+                                    let json = serde_json::to_string(&summary)
+                                        .expect("Serialize party summary");
+                                    response
+                                        .header("content-type", "application/json")
+                                        .status(StatusCode::OK)
+                                        .body(Full::new(Bytes::copy_from_slice(json.as_bytes())))
+                                } else {
+                                    response
+                                        .header("content-type", "text/html")
+                                        .status(StatusCode::OK)
+                                        .body(Full::new(Bytes::copy_from_slice(
+                                            summary.render_html().as_bytes(),
+                                        )))
+                                }
+                            }
+                            .instrument(span.clone()),
+                        );
                     }
                     "/connect" => {
                         let uri = req.uri().to_string();
@@ -203,6 +494,7 @@ impl Service<Request<body::Incoming>> for SpjortService {
                                 };
 
                                 if id_exists {
+                                    info!(controller = id, "controller paired");
                                     let res = response
                                         .header("content-type", "application/json")
                                         .status(StatusCode::OK)
@@ -218,32 +510,36 @@ impl Service<Request<body::Incoming>> for SpjortService {
                             .status(StatusCode::OK)
                             .body(Full::new(Bytes::copy_from_slice(b"false")))
                     }
-                    fs if fs.starts_with("/frontend/") || fs.starts_with("/wasm") => {
-                        let mut buf = vec![];
-                        let mut page = File::open(&fs[1..]).expect("Failed to find file");
-                        page.read_to_end(&mut buf)
-                            .expect("Failed to read to buffer");
-                        if fs.starts_with("/wasm") {
-                            if fs.ends_with("js") {
-                                response = response.header("content-type", "text/javascript");
-                            } else if fs.ends_with("wasm") {
-                                response = response.header("content-type", "application/wasm");
-                            }
+                    game if game.starts_with("/sports/") => match game_for_path(game) {
+                        Some(game) => {
+                            let game = game.render_game_scene();
+                            response
+                                .header("content-type", "text/html; charset=utf-8")
+                                .status(StatusCode::OK)
+                                .body(Full::new(Bytes::copy_from_slice(game.as_bytes())))
                         }
-
-                        response
-                            .status(StatusCode::OK)
-                            .body(Full::new(Bytes::copy_from_slice(&buf)))
-                    }
-                    game if game.starts_with("/sports/") => {
-                        let game = GAMES
-                            .iter()
-                            .find(|g| game.contains(g.name))
-                            .expect("Valid game from query");
-                        let game = game.render_game_scene();
-                        response
-                            .status(StatusCode::OK)
-                            .body(Full::new(Bytes::copy_from_slice(game.as_bytes())))
+                        None => response
+                            .status(StatusCode::NOT_FOUND)
+                            .body(Full::new(Bytes::from_static(b"Not Found"))),
+                    },
+                    _ => response
+                        .status(StatusCode::NOT_FOUND)
+                        .body(Full::new(Bytes::from_static(b"Not Found"))),
+                },
+                Method::POST => match req.uri().path() {
+                    "/rooms/new" => {
+                        let state = self.state.clone();
+                        return Box::pin(
+                            async move {
+                                let code = state.lock().await.open_room();
+                                info!(room = code, "room opened");
+                                response
+                                    .header("content-type", "application/json")
+                                    .status(StatusCode::OK)
+                                    .body(Full::new(Bytes::from(code.to_string())))
+                            }
+                            .instrument(span.clone()),
+                        );
                     }
                     _ => response
                         .status(StatusCode::NOT_FOUND)
@@ -258,3 +554,773 @@ impl Service<Request<body::Incoming>> for SpjortService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use deku::DekuContainerWrite;
+    use futures::{channel::mpsc::UnboundedReceiver, SinkExt, StreamExt};
+    use http_body_util::BodyExt;
+    use hyper::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG},
+        StatusCode,
+    };
+    use tokio::sync::{mpsc::Receiver, Mutex};
+    use tokio_tungstenite::tungstenite::{Error, Message};
+
+    use super::{game_for_path, handle_ws_binary, WebsocketWriteStream, WsProtocolError, GAMES};
+    use crate::{
+        control::{
+            msg::{
+                ListenerMessage, Orientation, PlayerMessage, Rumble, ServerMessage,
+                TimedOrientation, WsMessage,
+            },
+            Controller, ControllerMessage,
+        },
+        serve::{
+            assets::{content_type, static_path, StaticFiles},
+            SpjortState, WsConnectionType, HEARTBEAT_LIMIT,
+        },
+    };
+
+    /// Test harness holding everything a single websocket connection needs
+    struct Harness {
+        state: Arc<Mutex<SpjortState>>,
+        sender: tokio::sync::mpsc::Sender<Arc<Mutex<Controller>>>,
+        receiver: Receiver<Arc<Mutex<Controller>>>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let (state, sender, receiver) = SpjortState::new(15);
+            Self {
+                state: Arc::new(Mutex::new(state)),
+                sender,
+                receiver,
+            }
+        }
+
+        async fn handle(
+            &self,
+            buf: &[u8],
+            conn: &mut WsConnectionType,
+            stream: Arc<Mutex<WebsocketWriteStream>>,
+        ) -> Result<(), WsProtocolError> {
+            handle_ws_binary(buf, conn, self.sender.clone(), self.state.clone(), stream).await
+        }
+
+        /// Connects a controller through its handshake, returning its connection, write stream and
+        /// everything written back to it
+        async fn controller(
+            &mut self,
+            id: u64,
+        ) -> (
+            WsConnectionType,
+            Arc<Mutex<WebsocketWriteStream>>,
+            UnboundedReceiver<Message>,
+        ) {
+            let (stream, rx) = test_stream();
+            let mut conn = WsConnectionType::None;
+            self.handle(
+                &handshake(WsMessage::Controller(id)),
+                &mut conn,
+                stream.clone(),
+            )
+            .await
+            .unwrap();
+            self.connect_queued().await;
+            (conn, stream, rx)
+        }
+
+        /// Drains any queued controllers into the state like the main connection loop does
+        async fn connect_queued(&mut self) {
+            while let Ok(controller) = self.receiver.try_recv() {
+                self.state.lock().await.connect(controller).await;
+            }
+        }
+    }
+
+    /// Creates a write stream that forwards every message into a channel
+    fn test_stream() -> (Arc<Mutex<WebsocketWriteStream>>, UnboundedReceiver<Message>) {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let sink: WebsocketWriteStream = Box::new(tx.sink_map_err(|_| Error::ConnectionClosed));
+        (Arc::new(Mutex::new(sink)), rx)
+    }
+
+    fn handshake(msg: WsMessage) -> Vec<u8> {
+        msg.to_bytes().expect("Serialize handshake")
+    }
+
+    #[tokio::test]
+    async fn controller_handshake_registers_controller() {
+        let mut harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::None;
+
+        harness
+            .handle(&handshake(WsMessage::Controller(7)), &mut conn, stream)
+            .await
+            .expect("Valid controller handshake");
+        harness.connect_queued().await;
+
+        assert_eq!(conn, WsConnectionType::Controller(7));
+        assert!(harness.state.lock().await.get_controller(7).is_some());
+    }
+
+    #[tokio::test]
+    async fn pairing_request_marks_controller_as_pairing() {
+        let mut harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::None;
+
+        harness
+            .handle(
+                &handshake(WsMessage::Controller(3)),
+                &mut conn,
+                stream.clone(),
+            )
+            .await
+            .unwrap();
+        harness.connect_queued().await;
+        harness.handle(&[0x05], &mut conn, stream).await.unwrap();
+
+        assert_eq!(harness.state.lock().await.get_pairing_devices(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn listener_receives_controller_broadcasts() {
+        let mut harness = Harness::new();
+        let (controller_stream, _) = test_stream();
+        let (listener_stream, mut listener_rx) = test_stream();
+        let mut controller = WsConnectionType::None;
+        let mut listener = WsConnectionType::None;
+
+        harness
+            .handle(
+                &handshake(WsMessage::Controller(1)),
+                &mut controller,
+                controller_stream.clone(),
+            )
+            .await
+            .unwrap();
+        harness.connect_queued().await;
+        harness
+            .handle(
+                &handshake(WsMessage::Establish(1)),
+                &mut listener,
+                listener_stream,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listener, WsConnectionType::Listener(1));
+
+        let data = ControllerMessage::AngleInfo(Orientation::new(1.0, 2.0, 3.0))
+            .to_bytes()
+            .unwrap();
+        harness
+            .handle(&data, &mut controller, controller_stream)
+            .await
+            .unwrap();
+
+        assert_eq!(listener_rx.next().await, Some(Message::binary(data)));
+    }
+
+    #[test]
+    fn timed_angles_keep_the_layout_pages_decode() {
+        let data = ControllerMessage::TimedAngleInfo(TimedOrientation::new(
+            Orientation::new(1.0, 2.0, 3.0),
+            1_700_000_000_123,
+        ))
+        .to_bytes()
+        .unwrap();
+
+        assert_eq!(data.len(), 21);
+        assert_eq!(data[0], 0x06);
+        assert_eq!(data[1..5], 1.0f32.to_le_bytes());
+        assert_eq!(data[9..13], 3.0f32.to_le_bytes());
+        assert_eq!(data[13..], 1_700_000_000_123u64.to_le_bytes());
+    }
+
+    #[tokio::test]
+    async fn establishing_unknown_controller_is_rejected() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::None;
+
+        let res = harness
+            .handle(&handshake(WsMessage::Establish(42)), &mut conn, stream)
+            .await;
+
+        assert_eq!(res, Err(WsProtocolError::UnknownController(42)));
+        assert_eq!(conn, WsConnectionType::None);
+    }
+
+    #[tokio::test]
+    async fn controller_data_before_registration_is_rejected() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::Controller(9);
+
+        let res = harness.handle(&[0x02], &mut conn, stream).await;
+
+        assert_eq!(res, Err(WsProtocolError::UnknownController(9)));
+    }
+
+    #[tokio::test]
+    async fn empty_frames_are_rejected() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+
+        for mut conn in [
+            WsConnectionType::None,
+            WsConnectionType::Controller(1),
+            WsConnectionType::Listener(1),
+        ] {
+            let res = harness.handle(&[], &mut conn, stream.clone()).await;
+            assert_eq!(res, Err(WsProtocolError::EmptyFrame));
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_and_truncated_handshakes_are_rejected() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+
+        for buf in [&[0xFF][..], &[0x00, 1, 2], &[0x01, 1, 2, 3], &[0x02]] {
+            let mut conn = WsConnectionType::None;
+            let res = harness.handle(buf, &mut conn, stream.clone()).await;
+            assert_eq!(res, Err(WsProtocolError::MalformedHandshake));
+            assert_eq!(conn, WsConnectionType::None);
+        }
+    }
+
+    #[tokio::test]
+    async fn listeners_sending_garbage_is_a_violation() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::Listener(1);
+
+        let res = harness.handle(&[0x02], &mut conn, stream).await;
+
+        assert_eq!(res, Err(WsProtocolError::MalformedListenerMessage));
+        assert_eq!(conn, WsConnectionType::Listener(1));
+    }
+
+    #[tokio::test]
+    async fn listener_game_results_are_stored_by_controller() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::Listener(4);
+
+        let data = ListenerMessage::game_result("Bowling", &[187, 92], &["Ada"])
+            .to_bytes()
+            .unwrap();
+        harness.handle(&data, &mut conn, stream).await.unwrap();
+
+        let state = harness.state.lock().await;
+        let results = state.get_controller_results(4);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].game, "Bowling");
+        assert_eq!(results[0].player, "Ada");
+        assert_eq!(results[0].score, 187);
+        assert_eq!(results[1].player, "Player 2");
+        assert_eq!(results[1].score, 92);
+        assert!(state.get_controller_results(1).is_empty());
+    }
+
+    #[tokio::test]
+    async fn listener_rumbles_are_forwarded_to_the_controller() {
+        let mut harness = Harness::new();
+        let (controller_stream, mut controller_rx) = test_stream();
+        let (listener_stream, _) = test_stream();
+        let mut controller = WsConnectionType::None;
+        let mut listener = WsConnectionType::Listener(6);
+
+        harness
+            .handle(
+                &handshake(WsMessage::Controller(6)),
+                &mut controller,
+                controller_stream,
+            )
+            .await
+            .unwrap();
+        harness.connect_queued().await;
+
+        let rumble = Rumble::new(200, 150);
+        let data = ListenerMessage::Rumble(rumble).to_bytes().unwrap();
+        harness
+            .handle(&data, &mut listener, listener_stream)
+            .await
+            .unwrap();
+
+        let expected = ServerMessage::Rumble(rumble).to_bytes().unwrap();
+        assert_eq!(controller_rx.next().await, Some(Message::binary(expected)));
+    }
+
+    #[tokio::test]
+    async fn rumbles_for_unknown_controllers_are_rejected() {
+        let harness = Harness::new();
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::Listener(11);
+
+        let data = ListenerMessage::Rumble(Rumble::new(255, 400))
+            .to_bytes()
+            .unwrap();
+        let res = harness.handle(&data, &mut conn, stream).await;
+
+        assert_eq!(res, Err(WsProtocolError::UnknownController(11)));
+    }
+
+    #[tokio::test]
+    async fn closed_controller_queue_is_reported() {
+        let Harness {
+            state,
+            sender,
+            receiver,
+        } = Harness::new();
+        drop(receiver);
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::None;
+
+        let res = handle_ws_binary(
+            &handshake(WsMessage::Controller(1)),
+            &mut conn,
+            sender,
+            state,
+            stream,
+        )
+        .await;
+
+        assert_eq!(res, Err(WsProtocolError::ControllerQueueClosed));
+        assert_eq!(conn, WsConnectionType::None);
+    }
+
+    #[tokio::test]
+    async fn heartbeats_keep_controllers_alive_without_reaching_listeners() {
+        let mut harness = Harness::new();
+        let (controller_stream, _) = test_stream();
+        let (listener_stream, mut listener_rx) = test_stream();
+        let mut controller = WsConnectionType::None;
+        let mut listener = WsConnectionType::None;
+
+        harness
+            .handle(
+                &handshake(WsMessage::Controller(8)),
+                &mut controller,
+                controller_stream.clone(),
+            )
+            .await
+            .unwrap();
+        harness.connect_queued().await;
+        harness
+            .handle(
+                &handshake(WsMessage::Establish(8)),
+                &mut listener,
+                listener_stream,
+            )
+            .await
+            .unwrap();
+
+        let beat = ControllerMessage::Heartbeat.to_bytes().unwrap();
+        for _ in 0..HEARTBEAT_LIMIT * 2 {
+            assert!(harness.state.lock().await.heartbeat().await.is_empty());
+            harness
+                .handle(&beat, &mut controller, controller_stream.clone())
+                .await
+                .unwrap();
+        }
+
+        assert!(harness.state.lock().await.get_controller(8).is_some());
+        assert!(listener_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn silent_controllers_are_evicted_and_their_listeners_closed() {
+        let mut harness = Harness::new();
+        let (controller_stream, _) = test_stream();
+        let (listener_stream, mut listener_rx) = test_stream();
+        let mut controller = WsConnectionType::None;
+        let mut listener = WsConnectionType::None;
+
+        harness
+            .handle(
+                &handshake(WsMessage::Controller(9)),
+                &mut controller,
+                controller_stream.clone(),
+            )
+            .await
+            .unwrap();
+        harness.connect_queued().await;
+        harness
+            .handle(
+                &handshake(WsMessage::Establish(9)),
+                &mut listener,
+                listener_stream,
+            )
+            .await
+            .unwrap();
+        harness
+            .handle(&[0x05], &mut controller, controller_stream)
+            .await
+            .unwrap();
+
+        for _ in 1..HEARTBEAT_LIMIT {
+            assert!(harness.state.lock().await.heartbeat().await.is_empty());
+        }
+        let evicted = harness.state.lock().await.heartbeat().await;
+        assert_eq!(evicted.len(), 1);
+        for controller in evicted {
+            controller.lock().await.disconnect().await;
+        }
+
+        let state = harness.state.lock().await;
+        assert!(state.get_controller(9).is_none());
+        assert!(state.get_pairing_devices().is_empty());
+        assert!(matches!(
+            listener_rx.next().await,
+            Some(Message::Close(Some(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn room_listeners_receive_input_tagged_with_player_slots() {
+        let mut harness = Harness::new();
+        let (mut first, first_stream, _) = harness.controller(21).await;
+        let (mut second, second_stream, _) = harness.controller(22).await;
+        let (listener_stream, mut listener_rx) = test_stream();
+        let mut listener = WsConnectionType::None;
+
+        let code = harness.state.lock().await.open_room();
+        {
+            let state = harness.state.lock().await;
+            assert_eq!(state.join_room(code, 21).await, Some(0));
+            assert_eq!(state.join_room(code, 22).await, Some(1));
+            assert_eq!(state.join_room(code, 21).await, Some(0));
+        }
+        harness
+            .handle(
+                &handshake(WsMessage::JoinRoom(code)),
+                &mut listener,
+                listener_stream,
+            )
+            .await
+            .unwrap();
+        assert_eq!(listener, WsConnectionType::Room(code));
+
+        let press = ControllerMessage::ButtonPressA;
+        let angle = ControllerMessage::AngleInfo(Orientation::new(0.5, 0.0, -0.5));
+        harness
+            .handle(&angle.to_bytes().unwrap(), &mut second, second_stream)
+            .await
+            .unwrap();
+        harness
+            .handle(&press.to_bytes().unwrap(), &mut first, first_stream)
+            .await
+            .unwrap();
+
+        let Some(Message::Binary(buf)) = listener_rx.next().await else {
+            panic!("Expected a relayed frame");
+        };
+        let relayed = PlayerMessage::from_binary(&buf).unwrap();
+        assert_eq!(relayed.player, 1);
+        assert_eq!(relayed.message, angle);
+
+        let Some(Message::Binary(buf)) = listener_rx.next().await else {
+            panic!("Expected a relayed frame");
+        };
+        let relayed = PlayerMessage::from_binary(&buf).unwrap();
+        assert_eq!(relayed.player, 0);
+        assert_eq!(relayed.message, press);
+    }
+
+    #[tokio::test]
+    async fn joining_unknown_rooms_is_rejected() {
+        let mut harness = Harness::new();
+        harness.controller(23).await;
+        let (stream, _) = test_stream();
+        let mut conn = WsConnectionType::None;
+
+        let res = harness
+            .handle(&handshake(WsMessage::JoinRoom(42)), &mut conn, stream)
+            .await;
+
+        assert_eq!(res, Err(WsProtocolError::UnknownRoom(42)));
+        assert_eq!(conn, WsConnectionType::None);
+
+        let mut state = harness.state.lock().await;
+        assert_eq!(state.join_room(42, 23).await, None);
+        let code = state.open_room();
+        assert_eq!(state.join_room(code, 99).await, None);
+    }
+
+    #[tokio::test]
+    async fn room_results_and_rumbles_reach_each_slots_controller() {
+        let mut harness = Harness::new();
+        let (_, _, mut first_rx) = harness.controller(31).await;
+        let (_, _, mut second_rx) = harness.controller(32).await;
+        let (listener_stream, _) = test_stream();
+        let mut listener = WsConnectionType::None;
+
+        let code = harness.state.lock().await.open_room();
+        {
+            let state = harness.state.lock().await;
+            state.join_room(code, 32).await;
+            state.join_room(code, 31).await;
+        }
+        harness
+            .handle(
+                &handshake(WsMessage::JoinRoom(code)),
+                &mut listener,
+                listener_stream.clone(),
+            )
+            .await
+            .unwrap();
+
+        let data = ListenerMessage::game_result("Ping Pong", &[11, 7], &["Ada", ""])
+            .to_bytes()
+            .unwrap();
+        harness
+            .handle(&data, &mut listener, listener_stream.clone())
+            .await
+            .unwrap();
+
+        let rumble = Rumble::new(90, 60);
+        let data = ListenerMessage::Rumble(rumble).to_bytes().unwrap();
+        harness
+            .handle(&data, &mut listener, listener_stream)
+            .await
+            .unwrap();
+
+        {
+            let state = harness.state.lock().await;
+            assert_eq!(state.get_controller_results(32)[0].score, 11);
+            assert_eq!(state.get_controller_results(32)[0].player, "Ada");
+            assert_eq!(state.get_controller_results(31)[0].score, 7);
+            assert_eq!(state.get_controller_results(31)[0].player, "Player 2");
+        }
+        let expected = Message::binary(ServerMessage::Rumble(rumble).to_bytes().unwrap());
+        assert_eq!(first_rx.next().await, Some(expected.clone()));
+        assert_eq!(second_rx.next().await, Some(expected));
+    }
+
+    #[tokio::test]
+    async fn evicted_controllers_free_their_room_slot() {
+        let mut harness = Harness::new();
+        harness.controller(41).await;
+
+        let code = harness.state.lock().await.open_room();
+        assert_eq!(
+            harness.state.lock().await.join_room(code, 41).await,
+            Some(0)
+        );
+        for _ in 0..HEARTBEAT_LIMIT {
+            harness.state.lock().await.heartbeat().await;
+        }
+
+        harness.controller(42).await;
+        let state = harness.state.lock().await;
+        let room = state.get_room(code).unwrap();
+        assert_eq!(room.lock().await.slot_of(41), None);
+        assert_eq!(state.join_room(code, 42).await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn shutdown_closes_every_socket() {
+        let mut harness = Harness::new();
+        let (_, _, mut controller_rx) = harness.controller(51).await;
+        let (listener_stream, mut listener_rx) = test_stream();
+        let (room_stream, mut room_rx) = test_stream();
+        let mut listener = WsConnectionType::None;
+        let mut room = WsConnectionType::None;
+
+        harness
+            .handle(
+                &handshake(WsMessage::Establish(51)),
+                &mut listener,
+                listener_stream,
+            )
+            .await
+            .unwrap();
+        let code = harness.state.lock().await.open_room();
+        harness
+            .handle(
+                &handshake(WsMessage::JoinRoom(code)),
+                &mut room,
+                room_stream,
+            )
+            .await
+            .unwrap();
+
+        harness.state.lock().await.shutdown().await;
+
+        for rx in [&mut controller_rx, &mut listener_rx, &mut room_rx] {
+            assert!(matches!(rx.next().await, Some(Message::Close(Some(_)))));
+        }
+        let state = harness.state.lock().await;
+        assert!(state.get_controller(51).is_none());
+        assert!(state.get_room(code).is_none());
+    }
+
+    #[test]
+    fn games_api_lists_every_registered_game() {
+        let json = serde_json::to_value(GAMES).expect("Serialize games");
+        let games = json.as_array().expect("Games are a JSON array");
+
+        assert_eq!(games.len(), GAMES.len());
+        assert_eq!(games[0]["name"], GAMES[0].name);
+        assert!(games.iter().all(|game| game["multiplayer"].is_boolean()));
+        assert!(games.iter().all(|game| game["bot"].is_boolean()));
+    }
+
+    #[test]
+    fn every_game_routes_to_itself() {
+        for game in GAMES {
+            let routed = game_for_path(&format!("/sports/{}", game.slug));
+            assert!(
+                routed.is_some_and(|routed| routed.wasm_path == game.wasm_path),
+                "{}",
+                game.name
+            );
+            assert!(game
+                .slug
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit()));
+        }
+        assert_eq!(
+            game_for_path("/sports/discgolf").map(|g| g.name),
+            Some("Disc Golf")
+        );
+        assert_eq!(game_for_path("/sports/golf").map(|g| g.name), Some("Golf"));
+    }
+
+    #[test]
+    fn game_names_with_spaces_route_by_slug() {
+        let cases = [
+            ("Ping Pong", "pingpong"),
+            ("Axe Throwing", "axethrow"),
+            ("Track & Field", "trackfield"),
+            ("Mini Golf", "minigolf"),
+            ("Volleyball Serve", "volleyserve"),
+            ("Free Throws", "freethrow"),
+            ("Hammer Throw", "hammerthrow"),
+            ("Kayak Sprint", "kayak"),
+            ("Air Hockey", "airhockey"),
+        ];
+        for (name, slug) in cases {
+            let game = game_for_path(&format!("/sports/{slug}")).expect("Game routes by slug");
+            assert_eq!(game.name, name);
+            assert!(game
+                .render_html()
+                .contains(&format!(r#"hx-get="sports/{slug}""#)));
+            assert!(
+                game_for_path(&format!("/sports/{name}")).is_none(),
+                "{name}"
+            );
+        }
+        assert!(game_for_path("/sports/").is_none());
+        assert!(game_for_path("/sports/pingpong/extra").is_none());
+    }
+
+    #[tokio::test]
+    async fn static_files_are_cached_until_they_change() {
+        let dir = std::env::temp_dir().join(format!("spjorts-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.wasm");
+        std::fs::write(&path, b"first build").unwrap();
+        let files = StaticFiles::default();
+
+        let res = files.serve(&path, None).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[CONTENT_TYPE], "application/wasm");
+        assert_eq!(res.headers()[CACHE_CONTROL], "no-cache");
+        let etag = res.headers()[ETAG].clone();
+
+        let res = files.serve(&path, Some(&etag)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert!(res.into_body().frame().await.is_none());
+
+        std::fs::write(&path, b"second build").unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let res = files.serve(&path, Some(&etag)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_ne!(res.headers()[ETAG], etag);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body.as_ref(), b"second build");
+
+        let res = files.serve(&dir.join("missing.js"), None).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn static_files_get_content_types_by_extension() {
+        let cases = [
+            ("frontend/style/game.css", "text/css; charset=utf-8"),
+            ("frontend/index.html", "text/html; charset=utf-8"),
+            ("frontend/bg/splash.png", "image/png"),
+            ("frontend/bg/court.JPG", "image/jpeg"),
+            ("frontend/favicon.ico", "image/x-icon"),
+            ("frontend/fonts/font.woff", "font/woff"),
+            ("frontend/sounds/strike.wav", "audio/wav"),
+            (
+                "wasm/bowling/pkg/bowling.js",
+                "text/javascript; charset=utf-8",
+            ),
+            ("wasm/bowling/pkg/bowling_bg.wasm", "application/wasm"),
+            ("frontend/README", "application/octet-stream"),
+            ("frontend/notes.unknown", "application/octet-stream"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(content_type(std::path::Path::new(path)), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn static_paths_stay_inside_the_served_directories() {
+        assert_eq!(static_path("/"), Some("frontend/index.html".into()));
+        assert_eq!(
+            static_path("/wasm/bowling/pkg/bowling.js"),
+            Some("wasm/bowling/pkg/bowling.js".into())
+        );
+        assert_eq!(static_path("/frontend/../server/Cargo.toml"), None);
+        assert_eq!(static_path("/summary"), None);
+    }
+
+    #[tokio::test]
+    async fn arbitrary_frames_never_panic() {
+        let mut harness = Harness::new();
+        let (stream, _) = test_stream();
+
+        // Simple xorshift so the fuzz corpus is reproducible without extra dependencies
+        let mut seed: u64 = 0x5EED_CAFE_F00D_BEEF;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        let mut connections = [
+            WsConnectionType::None,
+            WsConnectionType::Controller(0),
+            WsConnectionType::Listener(0),
+            WsConnectionType::Room(0),
+        ];
+
+        for _ in 0..2_000 {
+            let len = (next() % 24) as usize;
+            let buf: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let conn = &mut connections[(next() % 4) as usize];
+
+            let _ = harness.handle(&buf, conn, stream.clone()).await;
+            harness.connect_queued().await;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Optional TLS termination, so the site and its web sockets can be served over https and wss
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufReader},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use tokio_rustls::{
+    rustls::{crypto::ring, ServerConfig},
+    TlsAcceptor,
+};
+
+/// Environment variable holding the path to the PEM certificate chain
+pub const CERT_VAR: &str = "SPJORTS_TLS_CERT";
+/// Environment variable holding the path to the PEM private key
+pub const KEY_VAR: &str = "SPJORTS_TLS_KEY";
+
+/// Where the certificate and key to serve TLS with are kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first
+    pub cert: PathBuf,
+    /// PEM private key for the leaf certificate
+    pub key: PathBuf,
+}
+
+impl TlsConfig {
+    /// Creates a config from a certificate chain and key path
+    pub fn new(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        Self {
+            cert: cert.into(),
+            key: key.into(),
+        }
+    }
+
+    /// Reads the certificate and key paths from `SPJORTS_TLS_CERT` and `SPJORTS_TLS_KEY`. TLS is
+    /// only turned on if both are set
+    pub fn from_env() -> Option<Self> {
+        let cert = env::var_os(CERT_VAR)?;
+        let key = env::var_os(KEY_VAR)?;
+        Some(Self::new(cert, key))
+    }
+
+    /// Loads the certificate and key into an acceptor that wraps incoming connections in TLS
+    pub fn acceptor(&self) -> io::Result<TlsAcceptor> {
+        let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(&self.cert)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(&self.key)?))?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "No private key found"))?;
+
+        // The provider is picked explicitly so it can't clash with one another crate turns on
+        let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
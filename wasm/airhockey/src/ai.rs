@@ -0,0 +1,118 @@
+//! Computer opponent for an end no one is playing: it guards its goal while the puck is on the
+//! other half and gets behind the puck to strike it at the far goal once it comes over, reacting
+//! slower and misjudging the puck more the lower its skill
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use spjorts_core::{
+    players::{Jitter, PlayerRegistry, MAX_BOT_SKILL},
+    spectator::is_playing,
+};
+
+use crate::{
+    lineup::Lineup,
+    phase::AirHockeyPhase,
+    table::{Mallet, Puck, Side, MALLET_RADIUS, PUCK_RADIUS},
+};
+
+/// Skill the computer plays at when no skill has been picked for it
+const DEFAULT_SKILL: u8 = 5;
+/// How often the most skilled computer looks at the puck again, in seconds
+const REACTION_SECS: f32 = 0.05;
+/// How much longer the least skilled computer takes to look again
+const MAX_REACTION_DELAY: f32 = 0.25;
+/// How far in front of its goal the computer guards, in meters
+const GUARD_DISTANCE: f32 = 0.25;
+/// How far ahead the computer reads the puck's path, in seconds
+const LOOK_AHEAD_SECS: f32 = 0.1;
+/// How far past the puck the computer follows through a strike, in meters
+const FOLLOW_THROUGH: f32 = 0.15;
+/// How far behind the puck the computer lines up before striking, past touching it, in meters
+const WIND_UP: f32 = 0.05;
+/// Furthest the least skilled computer misjudges where the puck is, in meters
+const MAX_READ_ERROR: f32 = 0.12;
+
+/// Computer opponent state
+#[derive(Resource, Debug)]
+pub struct AirHockeyAi {
+    /// Counts down to the computer looking at the puck again
+    look: Timer,
+    /// Where the computer's mistakes come from
+    jitter: Jitter,
+}
+
+impl Default for AirHockeyAi {
+    fn default() -> Self {
+        Self {
+            look: Timer::from_seconds(REACTION_SECS, TimerMode::Once),
+            jitter: Jitter::default(),
+        }
+    }
+}
+
+/// How far the computer's play is from perfect, from just above 0 at top skill to 1
+fn sloppiness(registry: &PlayerRegistry) -> f32 {
+    let skill = registry.bot().unwrap_or(DEFAULT_SKILL);
+    f32::from(MAX_BOT_SKILL - skill + 1) / f32::from(MAX_BOT_SKILL)
+}
+
+/// Where an end's mallet goes for the puck at `puck`: in front of its goal on the line to the
+/// puck while it's on the other half, and otherwise lined up behind it before striking through
+/// it at the far goal
+fn plan(side: Side, mallet: Vec2, puck: Vec2) -> Vec2 {
+    let goal = side.goal().xz();
+    if Side::of(Vec3::new(puck.x, 0.0, puck.y)) != side {
+        return goal + (puck - goal).normalize_or_zero() * GUARD_DISTANCE;
+    }
+    let toward = (side.opponent().goal().xz() - puck).normalize_or_zero();
+    let behind = puck - toward * (MALLET_RADIUS + PUCK_RADIUS + WIND_UP);
+    if (mallet - puck).dot(toward) < -(MALLET_RADIUS + PUCK_RADIUS) {
+        puck + toward * FOLLOW_THROUGH
+    } else {
+        behind
+    }
+}
+
+/// Plugin that adds the computer opponent
+pub struct AirHockeyAiPlugin;
+
+impl Plugin for AirHockeyAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AirHockeyAi>().add_systems(
+            Update,
+            play_puck
+                .run_if(in_state(AirHockeyPhase::Play))
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Every so often reads where the puck is headed and pushes the computer's mallet after it
+fn play_puck(
+    mut ai: ResMut<'_, AirHockeyAi>,
+    mut mallets: Query<'_, '_, (&Transform, &mut Mallet), Without<Puck>>,
+    puck: Query<'_, '_, (&Transform, &Velocity), With<Puck>>,
+    lineup: Res<'_, Lineup>,
+    registry: Res<'_, PlayerRegistry>,
+    time: Res<'_, Time>,
+) {
+    if !ai.look.tick(time.delta()).finished() {
+        return;
+    }
+    let Ok((puck, velocity)) = puck.get_single() else {
+        return;
+    };
+    let sloppiness = sloppiness(&registry);
+    ai.look = Timer::from_seconds(
+        REACTION_SECS + MAX_REACTION_DELAY * sloppiness,
+        TimerMode::Once,
+    );
+
+    let error = Vec2::new(ai.jitter.sample(), ai.jitter.sample()) * MAX_READ_ERROR * sloppiness;
+    let read = (puck.translation + velocity.linvel * LOOK_AHEAD_SECS).xz() + error;
+    for (transform, mut mallet) in &mut mallets {
+        if lineup.player(mallet.side).is_none() {
+            mallet.target = plan(mallet.side, transform.translation.xz(), read);
+        }
+    }
+}
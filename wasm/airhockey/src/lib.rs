@@ -0,0 +1,152 @@
+//! Bevy air hockey game
+
+use ai::AirHockeyAiPlugin;
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::Velocity,
+};
+use lineup::{Lineup, LineupPlugin};
+use phase::{AirHockeyPhase, AirHockeyPhasePlugin};
+use play::{NewGame, PlayPlugin};
+use scoreboard::ScoreboardPlugin;
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    ActionReader,
+};
+use table::{Mallet, Puck, Side, TablePlugin, HALF_LENGTH, HALF_WIDTH, MALLET_RADIUS};
+
+pub mod ai;
+pub mod lineup;
+pub mod phase;
+pub mod play;
+pub mod score;
+pub mod scoreboard;
+pub mod table;
+
+/// How far across the table a radian of controller yaw moves the mallet, in meters
+const REACH_ACROSS: f32 = 0.8;
+/// How far up the table a radian of controller pitch moves the mallet, in meters
+const REACH_FORWARD: f32 = 0.9;
+/// Fastest a mallet slides after where it's being pushed, in meters per second
+pub const MALLET_SPEED: f32 = 3.0;
+/// Fastest the puck is allowed to go, in meters per second. Physics steps can't keep up with
+/// a puck going any faster off a mallet and the rails
+pub const MAX_PUCK_SPEED: f32 = 4.5;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(AirHockeyPhasePlugin)
+    .add_plugins(LineupPlugin)
+    .add_plugins(TablePlugin)
+    .add_plugins(PlayPlugin)
+    .add_plugins(ScoreboardPlugin)
+    .add_plugins(AirHockeyAiPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.08, 0.08, 0.12)))
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            move_mallets.run_if(in_state(AirHockeyPhase::Play)),
+            cap_puck_speed,
+        )
+            .chain()
+            .run_if(is_playing),
+    );
+});
+
+/// Where on the surface a controller held at an orientation pushes an end's mallet. Held level
+/// and straight the mallet sits in front of its goal, and yaw slides it across the table while
+/// pitch pushes it up toward the centre line
+pub fn mallet_target(side: Side, orientation: Orientation) -> Vec2 {
+    let home = side.home().xz();
+    home + Vec2::new(
+        -orientation.yaw * REACH_ACROSS,
+        -orientation.pitch * REACH_FORWARD,
+    ) * side.sign()
+}
+
+/// Keeps a spot on the surface inside an end's half, leaving room for the mallet
+fn within_half(side: Side, at: Vec2) -> Vec2 {
+    let across = HALF_WIDTH - MALLET_RADIUS;
+    let (near, far) = (MALLET_RADIUS, HALF_LENGTH - MALLET_RADIUS);
+    Vec2::new(
+        at.x.clamp(-across, across),
+        (at.y * side.sign()).clamp(near, far) * side.sign(),
+    )
+}
+
+/// Everything input handling changes besides the mallets
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: each player's rotation pushes the mallet on their end round the
+/// table, and A starts a new game once one is over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut mallets: Query<'_, '_, &mut Mallet>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<AirHockeyPhase>>,
+    lineup: Res<'_, Lineup>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (player, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == AirHockeyPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let Some(side) = lineup.side_of(player) else {
+                    continue;
+                };
+                let orientation = settings.apply_rotation(orientation);
+                if let Some(mut mallet) = mallets.iter_mut().find(|mallet| mallet.side == side) {
+                    mallet.target = mallet_target(side, orientation);
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Slides each mallet after where it's being pushed, no faster than a hand can move it and never
+/// over the centre line or through the rails
+fn move_mallets(mut mallets: Query<'_, '_, (&mut Transform, &Mallet)>, time: Res<'_, Time>) {
+    let step = MALLET_SPEED * time.delta_secs();
+    for (mut transform, mallet) in &mut mallets {
+        let at = transform.translation.xz();
+        let to = within_half(mallet.side, mallet.target);
+        let moved = within_half(mallet.side, at + (to - at).clamp_length_max(step));
+        transform.translation.x = moved.x;
+        transform.translation.z = moved.y;
+    }
+}
+
+/// Holds the puck under its top speed, so a hard hit can't send it through a rail
+fn cap_puck_speed(mut puck: Query<'_, '_, &mut Velocity, With<Puck>>) {
+    for mut velocity in &mut puck {
+        if velocity.linvel.length() > MAX_PUCK_SPEED {
+            velocity.linvel = velocity.linvel.clamp_length_max(MAX_PUCK_SPEED);
+        }
+    }
+}
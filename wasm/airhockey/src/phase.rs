@@ -0,0 +1,27 @@
+//! Phases a game of air hockey moves through, from each faceoff to the winning goal
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of a point
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AirHockeyPhase {
+    /// The puck is set down at rest on one end's half, about to be put in play
+    #[default]
+    Faceoff,
+    /// The puck is in play
+    Play,
+    /// A goal has gone in and the puck is being fished out
+    Goal,
+    /// An end has won the game and the final score is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct AirHockeyPhasePlugin;
+
+impl Plugin for AirHockeyPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AirHockeyPhase>();
+    }
+}
@@ -0,0 +1,229 @@
+//! Flow of a point: setting the puck down for a faceoff, watching the goal sensors and the
+//! mallets' hits, fishing the puck out after a goal and starting games over
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::{CollisionEvent, Velocity};
+use spjorts_core::{
+    communication::GameEvent, scorecard::Banner, spectator::is_playing, FeedbackSender,
+};
+
+use crate::{
+    lineup::Lineup,
+    phase::AirHockeyPhase,
+    score::AirHockeyScore,
+    table::{Goal, Mallet, Puck, Side, HALF_LENGTH, HALF_WIDTH},
+    MAX_PUCK_SPEED,
+};
+
+/// How long the puck sits at a faceoff before play starts, in seconds
+const FACEOFF_SECS: f32 = 1.0;
+/// How long the puck takes to be fished out of a goal, in seconds
+const GOAL_PAUSE_SECS: f32 = 1.5;
+/// How far past the rails the puck can get before it's counted off the table
+const LOST_MARGIN: f32 = 0.3;
+/// Hardest a hit rumbles the controller, out of 255
+const MAX_RUMBLE: f32 = 160.0;
+/// How long a hit rumbles the controller, in milliseconds
+const RUMBLE_MILLIS: u16 = 60;
+
+/// The state of the point being played
+#[derive(Resource, Debug)]
+pub struct Point {
+    /// The end whose mallet hit the puck last, `None` since the faceoff
+    pub last_hitter: Option<Side>,
+    /// Fastest the puck has gone this game, in meters per second
+    pub top_speed: f32,
+    /// Counts down the faceoff, then the pause after a goal
+    pause: Timer,
+}
+
+impl Default for Point {
+    fn default() -> Self {
+        Self {
+            last_hitter: None,
+            top_speed: 0.0,
+            pause: Timer::from_seconds(FACEOFF_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// Asks for the game to be started over
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Plugin that runs each point and the flow between them
+pub struct PlayPlugin;
+
+impl Plugin for PlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Point>()
+            .init_resource::<AirHockeyScore>()
+            .add_event::<NewGame>()
+            .add_systems(OnEnter(AirHockeyPhase::Faceoff), prepare_faceoff)
+            .add_systems(OnEnter(AirHockeyPhase::Goal), reset_pause)
+            .add_systems(
+                Update,
+                (
+                    start_play.run_if(in_state(AirHockeyPhase::Faceoff)),
+                    watch_puck.run_if(in_state(AirHockeyPhase::Play)),
+                    next_faceoff.run_if(in_state(AirHockeyPhase::Goal)),
+                    new_game_for_lineup.run_if(resource_changed::<Lineup>),
+                    start_new_game,
+                    // A new game started mid faceoff doesn't leave the phase, so it's set up here
+                    prepare_faceoff
+                        .run_if(in_state(AirHockeyPhase::Faceoff))
+                        .run_if(resource_changed::<AirHockeyScore>),
+                )
+                    .chain()
+                    .run_if(is_playing),
+            );
+    }
+}
+
+/// Sets the puck down at rest on the receiving end's half and sends the mallets back in front of
+/// their goals
+fn prepare_faceoff(
+    mut puck: Query<'_, '_, (&mut Transform, &mut Velocity), With<Puck>>,
+    mut mallets: Query<'_, '_, (&mut Transform, &mut Mallet), Without<Puck>>,
+    mut point: ResMut<'_, Point>,
+    mut banner: ResMut<'_, Banner>,
+    score: Res<'_, AirHockeyScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    point.last_hitter = None;
+    point.pause = Timer::from_seconds(FACEOFF_SECS, TimerMode::Once);
+    for (mut transform, mut mallet) in &mut mallets {
+        transform.translation = mallet.side.home();
+        mallet.target = mallet.side.home().xz();
+    }
+    for (mut transform, mut velocity) in &mut puck {
+        transform.translation = score.receiver().faceoff();
+        *velocity = Velocity::zero();
+    }
+    banner.show(format!("{}'s puck", lineup.name(score.receiver())));
+}
+
+/// Puts the puck in play once it has sat for a moment
+fn start_play(
+    mut point: ResMut<'_, Point>,
+    mut next_phase: ResMut<'_, NextState<AirHockeyPhase>>,
+    time: Res<'_, Time>,
+) {
+    if point.pause.tick(time.delta()).just_finished() {
+        next_phase.set(AirHockeyPhase::Play);
+    }
+}
+
+/// Everything that changes once a goal goes in
+#[derive(SystemParam)]
+struct Umpire<'w> {
+    /// The game score
+    score: ResMut<'w, AirHockeyScore>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the game goes next
+    next_phase: ResMut<'w, NextState<AirHockeyPhase>>,
+    /// Who plays each end, to name the scorer
+    lineup: Res<'w, Lineup>,
+}
+
+impl Umpire<'_> {
+    /// Awards a goal, calling it and moving on to the next faceoff or the end of the game
+    fn award(&mut self, scorer: Side) {
+        let name = self.lineup.name(scorer);
+        if self.score.goal_scored(scorer) {
+            self.banner.show(format!("Game, {name}"));
+            self.next_phase.set(AirHockeyPhase::GameOver);
+            return;
+        }
+        self.banner.show(format!(
+            "Goal, {name}! {}-{}",
+            self.score.goals(scorer),
+            self.score.goals(scorer.opponent())
+        ));
+        self.next_phase.set(AirHockeyPhase::Goal);
+    }
+}
+
+/// Watches the puck: a goal once it reaches the sensor in either end's pocket, a rumble when a
+/// player's mallet hits it, and a fresh faceoff for the same end if it somehow leaves the table
+fn watch_puck(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    puck: Query<'_, '_, (Entity, &Transform, &Velocity), With<Puck>>,
+    goals: Query<'_, '_, &Goal>,
+    mallets: Query<'_, '_, &Mallet>,
+    mut point: ResMut<'_, Point>,
+    mut umpire: Umpire<'_>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let Ok((puck, transform, velocity)) = puck.get_single() else {
+        return;
+    };
+    let speed = velocity.linvel.length();
+    point.top_speed = point.top_speed.max(speed);
+
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        if first != puck && second != puck {
+            continue;
+        }
+        let other = if first == puck { second } else { first };
+        if let Ok(goal) = goals.get(other) {
+            umpire.award(goal.side.opponent());
+            return;
+        }
+        if let Ok(mallet) = mallets.get(other) {
+            point.last_hitter = Some(mallet.side);
+            if umpire.lineup.player(mallet.side).is_some() {
+                feedback.send(GameEvent::Rumble {
+                    intensity: (speed / MAX_PUCK_SPEED * MAX_RUMBLE) as u8,
+                    millis: RUMBLE_MILLIS,
+                });
+            }
+        }
+    }
+
+    let position = transform.translation;
+    if position.x.abs() > HALF_WIDTH + LOST_MARGIN || position.z.abs() > HALF_LENGTH + LOST_MARGIN {
+        umpire.banner.show("Off the table!");
+        umpire.next_phase.set(AirHockeyPhase::Goal);
+    }
+}
+
+/// Restarts the pause after a goal
+fn reset_pause(mut point: ResMut<'_, Point>) {
+    point.pause = Timer::from_seconds(GOAL_PAUSE_SECS, TimerMode::Once);
+}
+
+/// Moves on to the next faceoff once the puck has been fished out
+fn next_faceoff(
+    mut point: ResMut<'_, Point>,
+    mut next_phase: ResMut<'_, NextState<AirHockeyPhase>>,
+    time: Res<'_, Time>,
+) {
+    if point.pause.tick(time.delta()).just_finished() {
+        next_phase.set(AirHockeyPhase::Faceoff);
+    }
+}
+
+/// Starts a fresh game whenever a second player joins or leaves
+fn new_game_for_lineup(mut requests: EventWriter<'_, NewGame>) {
+    requests.send(NewGame);
+}
+
+/// Starts the game over with the near end getting the puck first
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut score: ResMut<'_, AirHockeyScore>,
+    mut point: ResMut<'_, Point>,
+    mut next_phase: ResMut<'_, NextState<AirHockeyPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+    *score = AirHockeyScore::default();
+    point.top_speed = 0.0;
+    next_phase.set(AirHockeyPhase::Faceoff);
+}
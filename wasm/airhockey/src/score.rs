@@ -0,0 +1,58 @@
+//! Game scoring: the first end to seven goals wins, and the end that let the last goal in gets
+//! the puck for the next faceoff
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::table::Side;
+
+/// Goals an end has to score to win the game
+pub const GOALS_TO_WIN: u8 = 7;
+
+/// Goals on each end, near first
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AirHockeyScore {
+    /// Goals scored in the game
+    goals: [u8; 2],
+    /// The end getting the puck for the next faceoff
+    receiver: Side,
+    /// The end that won the game, once one has
+    winner: Option<Side>,
+}
+
+impl Default for AirHockeyScore {
+    fn default() -> Self {
+        Self {
+            goals: [0; 2],
+            receiver: Side::Near,
+            winner: None,
+        }
+    }
+}
+
+impl AirHockeyScore {
+    /// Goals an end has scored
+    pub fn goals(&self, side: Side) -> u8 {
+        self.goals[side.index()]
+    }
+
+    /// The end that won the game, once one has
+    pub fn winner(&self) -> Option<Side> {
+        self.winner
+    }
+
+    /// The end getting the puck for the next faceoff
+    pub fn receiver(&self) -> Side {
+        self.receiver
+    }
+
+    /// Awards a goal to an end, handing the puck to the other, returning whether it won the game
+    pub fn goal_scored(&mut self, side: Side) -> bool {
+        self.goals[side.index()] += 1;
+        self.receiver = side.opponent();
+        if self.goals[side.index()] >= GOALS_TO_WIN {
+            self.winner = Some(side);
+        }
+        self.winner.is_some()
+    }
+}
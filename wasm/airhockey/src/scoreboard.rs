@@ -0,0 +1,131 @@
+//! The scoreboard: goals on each end, who gets the puck and the fastest shot so far on the shared
+//! scorecard HUD, plus the game result sent back to the page
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    FeedbackSender,
+};
+
+use crate::{
+    lineup::Lineup,
+    phase::AirHockeyPhase,
+    play::Point,
+    score::{AirHockeyScore, GOALS_TO_WIN},
+    table::Side,
+};
+
+/// Meters per second in a kilometer per hour
+const KMH: f32 = 3.6;
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct AirHockeySnapshot<'a> {
+    /// Goals and who gets the puck next
+    score: &'a AirHockeyScore,
+    /// The end that hit the puck last
+    last_hitter: Option<Side>,
+    /// Fastest the puck has gone this game, in meters per second
+    top_speed: f32,
+    /// Where the game is at
+    phase: AirHockeyPhase,
+}
+
+/// Plugin that shows the score on the scorecard HUD and reports the result
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(AirHockeyPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(AirHockeyPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Fills in the scorecard HUD with goals on each end and the fastest shot of the game
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, AirHockeyScore>,
+    point: Res<'_, Point>,
+    lineup: Res<'_, Lineup>,
+) {
+    let rows = Side::BOTH
+        .into_iter()
+        .map(|side| format!("{}: {}", lineup.name(side), score.goals(side)))
+        .collect();
+    let footer = format!(
+        "{}'s puck\nFastest shot: {:.0} km/h",
+        lineup.name(score.receiver()),
+        point.top_speed * KMH
+    );
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Air Hockey, first to {GOALS_TO_WIN}"),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Shows the goals each end scored once the game is over
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, AirHockeyScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    let mut lines = vec!["Final Score".to_string()];
+    if let Some(winner) = score.winner() {
+        lines.push(format!("{} wins!", lineup.name(winner)));
+    }
+    for side in Side::BOTH {
+        lines.push(format!("{}: {}", lineup.name(side), score.goals(side)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final score when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends the goals each player scored back to the page once the game is over, so it can submit
+/// them to the server
+fn submit_result(
+    score: Res<'_, AirHockeyScore>,
+    lineup: Res<'_, Lineup>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = Side::BOTH
+        .into_iter()
+        .filter(|side| lineup.player(*side).is_some())
+        .map(|side| u32::from(score.goals(side)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    score: Res<'_, AirHockeyScore>,
+    point: Res<'_, Point>,
+    phase: Res<'_, State<AirHockeyPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&AirHockeySnapshot {
+        score: &score,
+        last_hitter: point.last_hitter,
+        top_speed: point.top_speed,
+        phase: *phase.get(),
+    });
+}
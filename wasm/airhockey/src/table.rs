@@ -0,0 +1,322 @@
+//! The table: its air cushioned surface, the rails round it with a goal in each end, the puck
+//! and a mallet on either end
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    ActiveEvents, Ccd, Collider, ColliderMassProperties, Damping, Friction, GravityScale,
+    LockedAxes, Restitution, RigidBody, Sensor, Velocity,
+};
+use serde::{Deserialize, Serialize};
+
+/// Half the length of the playing surface, from the centre line to an end rail
+pub const HALF_LENGTH: f32 = 1.2;
+/// Half the width of the playing surface
+pub const HALF_WIDTH: f32 = 0.6;
+/// Height of the playing surface above the floor
+pub const TABLE_HEIGHT: f32 = 0.8;
+/// Half the width of a goal's mouth
+pub const GOAL_HALF_WIDTH: f32 = 0.13;
+/// Radius of the puck
+pub const PUCK_RADIUS: f32 = 0.04;
+/// Radius of a mallet
+pub const MALLET_RADIUS: f32 = 0.05;
+
+/// Thickness of the puck
+const PUCK_HEIGHT: f32 = 0.01;
+/// Mass of the puck, in kilograms
+const PUCK_MASS: f32 = 0.03;
+/// How quickly the puck slows on the air cushion, a little for the drag of the air
+const PUCK_DRAG: f32 = 0.08;
+/// Height of a mallet
+const MALLET_HEIGHT: f32 = 0.06;
+/// Thickness of the rails round the surface
+const RAIL_THICKNESS: f32 = 0.06;
+/// Height of the rails above the surface
+const RAIL_HEIGHT: f32 = 0.04;
+/// How deep a goal's pocket is behind its end rail
+const GOAL_DEPTH: f32 = 0.12;
+/// Thickness of the table's body under the surface
+const BODY_THICKNESS: f32 = 0.15;
+/// Half the size of the floor round the table
+const FLOOR_HALF_SIZE: f32 = 8.0;
+
+/// One end of the table
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The end nearest the camera
+    Near,
+    /// The end across the centre line
+    Far,
+}
+
+impl Side {
+    /// Both ends, near first
+    pub const BOTH: [Self; 2] = [Self::Near, Self::Far];
+
+    /// The end across the centre line from this one
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Near => Self::Far,
+            Self::Far => Self::Near,
+        }
+    }
+
+    /// Which way along z this end lies from the centre line, positive for the near end
+    pub fn sign(self) -> f32 {
+        match self {
+            Self::Near => 1.0,
+            Self::Far => -1.0,
+        }
+    }
+
+    /// Which end of the table a spot is on
+    pub fn of(position: Vec3) -> Self {
+        if position.z >= 0.0 {
+            Self::Near
+        } else {
+            Self::Far
+        }
+    }
+
+    /// Index of the end, near first, for per-side arrays
+    pub fn index(self) -> usize {
+        match self {
+            Self::Near => 0,
+            Self::Far => 1,
+        }
+    }
+
+    /// Where this end's mallet rests, in front of its goal
+    pub fn home(self) -> Vec3 {
+        Vec3::new(0.0, puck_height(), HALF_LENGTH * 0.8 * self.sign())
+    }
+
+    /// Where the puck is set down for this end to put it in play, in the middle of its half
+    pub fn faceoff(self) -> Vec3 {
+        Vec3::new(0.0, puck_height(), HALF_LENGTH * 0.45 * self.sign())
+    }
+
+    /// Middle of this end's goal mouth
+    pub fn goal(self) -> Vec3 {
+        Vec3::new(0.0, puck_height(), HALF_LENGTH * self.sign())
+    }
+}
+
+/// Height the middle of the puck rides at on the surface
+pub fn puck_height() -> f32 {
+    TABLE_HEIGHT + PUCK_HEIGHT / 2.0
+}
+
+/// Marks the puck
+#[derive(Component, Debug, Default)]
+pub struct Puck;
+
+/// A mallet on one end of the table
+#[derive(Component, Debug)]
+pub struct Mallet {
+    /// The end it plays from
+    pub side: Side,
+    /// Where it's being pushed to on the surface, as X across and Z along the table
+    pub target: Vec2,
+}
+
+/// The sensor in the pocket of one end's goal
+#[derive(Component, Debug)]
+pub struct Goal {
+    /// The end defending it
+    pub side: Side,
+}
+
+/// Plugin that lays out the table, the puck and the mallets
+pub struct TablePlugin;
+
+impl Plugin for TablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_table);
+    }
+}
+
+/// Spawns the floor, the table with its rails and goals, the puck, the mallets, the camera and
+/// the light
+fn setup_table(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(FLOOR_HALF_SIZE * 2.0, FLOOR_HALF_SIZE * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.2, 0.25))),
+        Name::new("Floor"),
+    ));
+
+    let outer = Vec2::new(
+        HALF_WIDTH + RAIL_THICKNESS,
+        HALF_LENGTH + GOAL_DEPTH + RAIL_THICKNESS,
+    );
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(outer.x * 2.0, BODY_THICKNESS, outer.y * 2.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.15, 0.15, 0.18))),
+        Transform::from_xyz(0.0, TABLE_HEIGHT - BODY_THICKNESS / 2.0 - 0.001, 0.0),
+        Name::new("Table"),
+    ));
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(HALF_WIDTH * 2.0, HALF_LENGTH * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.9, 0.92, 0.95),
+            perceptual_roughness: 0.2,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, TABLE_HEIGHT, 0.0),
+        Name::new("Surface"),
+    ));
+    let legs = meshes.add(Cuboid::new(0.1, TABLE_HEIGHT - BODY_THICKNESS, 0.1));
+    let dark = materials.add(Color::srgb(0.1, 0.1, 0.12));
+    for (x, z) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+        commands.spawn((
+            Mesh3d(legs.clone()),
+            MeshMaterial3d(dark.clone()),
+            Transform::from_xyz(
+                (HALF_WIDTH - 0.1) * x,
+                (TABLE_HEIGHT - BODY_THICKNESS) / 2.0,
+                (HALF_LENGTH - 0.2) * z,
+            ),
+        ));
+    }
+
+    // The centre line and the circle round it
+    let red = materials.add(Color::srgb(0.8, 0.15, 0.15));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(HALF_WIDTH * 2.0, 0.01))),
+        MeshMaterial3d(red.clone()),
+        Transform::from_xyz(0.0, TABLE_HEIGHT + 0.001, 0.0),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Annulus::new(0.18, 0.19))),
+        MeshMaterial3d(red),
+        Transform::from_xyz(0.0, TABLE_HEIGHT + 0.001, 0.0)
+            .with_rotation(Quat::from_rotation_x(-PI / 2.0)),
+    ));
+
+    // Rails down the sides, and either side of each goal across the ends
+    let rail = materials.add(Color::srgb(0.85, 0.75, 0.2));
+    let mut rail_at = |half_size: Vec2, at: Vec2| {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(
+                half_size.x * 2.0,
+                RAIL_HEIGHT,
+                half_size.y * 2.0,
+            ))),
+            MeshMaterial3d(rail.clone()),
+            Transform::from_xyz(at.x, TABLE_HEIGHT + RAIL_HEIGHT / 2.0, at.y),
+            RigidBody::Fixed,
+            Collider::cuboid(half_size.x, RAIL_HEIGHT / 2.0, half_size.y),
+            Restitution::coefficient(0.9),
+            Friction::coefficient(0.0),
+        ));
+    };
+    for sign in [-1.0, 1.0] {
+        rail_at(
+            Vec2::new(RAIL_THICKNESS / 2.0, outer.y),
+            Vec2::new((HALF_WIDTH + RAIL_THICKNESS / 2.0) * sign, 0.0),
+        );
+        let end_half = (HALF_WIDTH - GOAL_HALF_WIDTH) / 2.0;
+        for across in [-1.0, 1.0] {
+            rail_at(
+                Vec2::new(end_half, RAIL_THICKNESS / 2.0),
+                Vec2::new(
+                    (GOAL_HALF_WIDTH + end_half) * across,
+                    (HALF_LENGTH + RAIL_THICKNESS / 2.0) * sign,
+                ),
+            );
+        }
+        // The back of the goal's pocket
+        rail_at(
+            Vec2::new(GOAL_HALF_WIDTH, RAIL_THICKNESS / 2.0),
+            Vec2::new(0.0, (outer.y - RAIL_THICKNESS / 2.0) * sign),
+        );
+    }
+
+    for side in Side::BOTH {
+        commands.spawn((
+            // Set back a little from the goal line, so the puck has to be over it to count
+            Transform::from_xyz(
+                0.0,
+                puck_height(),
+                (HALF_LENGTH + GOAL_DEPTH * 0.6) * side.sign(),
+            ),
+            Collider::cuboid(GOAL_HALF_WIDTH, RAIL_HEIGHT / 2.0, GOAL_DEPTH * 0.4),
+            Sensor,
+            Goal { side },
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(PUCK_RADIUS, PUCK_HEIGHT))),
+        MeshMaterial3d(materials.add(Color::srgb(0.1, 0.1, 0.1))),
+        Transform::from_translation(Side::Near.faceoff()),
+        RigidBody::Dynamic,
+        Collider::cylinder(PUCK_HEIGHT / 2.0, PUCK_RADIUS),
+        ColliderMassProperties::Mass(PUCK_MASS),
+        Friction::coefficient(0.0),
+        Restitution::coefficient(0.9),
+        GravityScale(0.0),
+        // The puck only slides across the surface, riding on the air
+        LockedAxes::ROTATION_LOCKED | LockedAxes::TRANSLATION_LOCKED_Y,
+        Damping {
+            linear_damping: PUCK_DRAG,
+            angular_damping: 0.0,
+        },
+        Ccd::enabled(),
+        Velocity::zero(),
+        ActiveEvents::COLLISION_EVENTS,
+        Puck,
+    ));
+
+    let mallet = meshes.add(Cylinder::new(MALLET_RADIUS, MALLET_HEIGHT));
+    for (side, color) in [
+        (Side::Near, Color::srgb(0.15, 0.35, 0.9)),
+        (Side::Far, Color::srgb(0.9, 0.2, 0.15)),
+    ] {
+        let home = side.home();
+        commands.spawn((
+            Mesh3d(mallet.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(home),
+            RigidBody::KinematicPositionBased,
+            Collider::cylinder(MALLET_HEIGHT / 2.0, MALLET_RADIUS),
+            Friction::coefficient(0.0),
+            Mallet {
+                side,
+                target: home.xz(),
+            },
+        ));
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, TABLE_HEIGHT + 1.5, HALF_LENGTH + 1.0)
+            .looking_at(Vec3::new(0.0, TABLE_HEIGHT, -0.2), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(1.0, 6.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
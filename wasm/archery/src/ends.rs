@@ -0,0 +1,267 @@
+//! Ends on the shooting line: scoring each arrow, pulling them from the target and handing
+//! over to the next archer, all shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::ArcheryPhase,
+    tally::{Tally, ARROWS_PER_END, ENDS},
+    wind::Wind,
+    Arrow, ArrowLanded, Bow,
+};
+
+/// How long the arrows stay in the target after an end, in seconds
+const SCORING_SECS: f32 = 2.0;
+
+/// Asks for the match to be started over from the first archer's first end
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts down before the arrows are pulled from the target
+#[derive(Resource, Debug)]
+struct Scoring(Timer);
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SCORING_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct ArcherySnapshot<'a> {
+    /// Archer on the shooting line
+    player: usize,
+    /// The end being shot, starting from zero
+    end: usize,
+    /// Every archer's arrows so far
+    tally: &'a Tally,
+    /// The wind blowing this end
+    wind: &'a Wind,
+    /// Where the match is at
+    phase: ArcheryPhase,
+}
+
+/// Plugin that scores ends and shows them on the scorecard HUD
+pub struct EndsPlugin;
+
+impl Plugin for EndsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Tally>()
+            .init_resource::<Scoring>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    score_arrow.run_if(in_state(ArcheryPhase::Flying)),
+                    collect_arrows.run_if(in_state(ArcheryPhase::Scoring)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(ArcheryPhase::Scoring), reset_scoring)
+            .add_systems(
+                OnEnter(ArcheryPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(ArcheryPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh match whenever the number of archers changes
+fn fit_match(turns: Res<'_, TurnManager>, mut tally: ResMut<'_, Tally>) {
+    if tally.players() != turns.players() {
+        *tally = Tally::new(turns.players());
+    }
+}
+
+/// Scores the arrow that just landed, moving on to the next arrow or the end's scoring
+pub fn score_arrow(
+    mut landed: EventReader<'_, '_, ArrowLanded>,
+    mut tally: ResMut<'_, Tally>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<ArcheryPhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    for ArrowLanded(points) in landed.read() {
+        let player = turns.current();
+        let end_over = tally.record(player, *points);
+        match *points {
+            10 => banner.show("Ten!"),
+            0 => banner.show("Miss"),
+            _ => {}
+        }
+        if end_over {
+            let scored: u32 = tally.end(player, turns.round()).iter().sum();
+            banner.show(format!("Player {} scores {scored} this end", player + 1));
+            next_phase.set(ArcheryPhase::Scoring);
+        } else {
+            next_phase.set(ArcheryPhase::Drawing);
+        }
+    }
+}
+
+/// Gives archers a moment to see where the end's arrows landed
+fn reset_scoring(mut scoring: ResMut<'_, Scoring>) {
+    scoring.0.reset();
+}
+
+/// Pulls the end's arrows from the target and hands the line to the next archer, finishing the
+/// match once everyone has shot every end
+fn collect_arrows(
+    mut commands: Commands<'_, '_>,
+    mut scoring: ResMut<'_, Scoring>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<ArcheryPhase>>,
+    arrows: Query<'_, '_, Entity, With<Arrow>>,
+    time: Res<'_, Time>,
+) {
+    if !scoring.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for arrow in &arrows {
+        commands.entity(arrow).despawn_recursive();
+    }
+    turns.advance();
+    if turns.round() >= ENDS {
+        next_phase.set(ArcheryPhase::GameOver);
+    } else {
+        next_phase.set(ArcheryPhase::Drawing);
+    }
+}
+
+/// Starts the match over from the first archer's first end
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut tally: ResMut<'_, Tally>,
+    mut next_phase: ResMut<'_, NextState<ArcheryPhase>>,
+    arrows: Query<'_, '_, Entity, With<Arrow>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for arrow in &arrows {
+        commands.entity(arrow).despawn_recursive();
+    }
+    turns.restart();
+    *tally = Tally::new(turns.players());
+    next_phase.set(ArcheryPhase::Drawing);
+}
+
+/// Fills in the scorecard HUD with every archer's ends and total, the arrows of the end being
+/// shot, the wind and how far the bow is drawn
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    tally: Res<'_, Tally>,
+    turns: Res<'_, TurnManager>,
+    wind: Res<'_, Wind>,
+    bow: Res<'_, Bow>,
+    time: Res<'_, Time>,
+) {
+    let rows = (0..tally.players())
+        .map(|player| {
+            let ends: Vec<String> = (0..ENDS)
+                .map(|end| match tally.end(player, end) {
+                    [] => "-".to_string(),
+                    arrows => arrows.iter().sum::<u32>().to_string(),
+                })
+                .collect();
+            format!(
+                "Player {}: {} ({})",
+                player + 1,
+                tally.total(player),
+                ends.join(" / ")
+            )
+        })
+        .collect();
+    let end = turns.round().min(ENDS - 1);
+    let mut arrows: Vec<String> = tally
+        .end(turns.current(), end)
+        .iter()
+        .map(|points| match points {
+            0 => "M".to_string(),
+            points => points.to_string(),
+        })
+        .collect();
+    arrows.resize(ARROWS_PER_END, "-".to_string());
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Archery, end {} of {ENDS}", end + 1),
+        rows,
+        footer: format!(
+            "Arrows: {}\nWind: {:.1} m/s {}\nDraw {:.0}%",
+            arrows.join("  "),
+            wind.speed(),
+            wind.direction_name(),
+            bow.draw(time.elapsed_secs()) * 100.0
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every archer's total once the match is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, tally: Res<'_, Tally>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    let leaders = tally.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if tally.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..tally.players() {
+        lines.push(format!("Player {}: {}", player + 1, tally.total(player)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new match starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every archer's total back to the page once the match is over, so it can submit them
+/// to the server
+fn submit_result(tally: Res<'_, Tally>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..tally.players())
+        .map(|player| tally.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    tally: Res<'_, Tally>,
+    wind: Res<'_, Wind>,
+    phase: Res<'_, State<ArcheryPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&ArcherySnapshot {
+        player: turns.current(),
+        end: turns.round(),
+        tally: &tally,
+        wind: &wind,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,368 @@
+//! Bevy archery game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{ActiveEvents, Ccd, Collider, CollisionEvent, CollisionGroups, RigidBody, Velocity},
+};
+use ends::{score_arrow, EndsPlugin, NewGame};
+use phase::{ArcheryPhase, ArcheryPhasePlugin};
+use spjorts_core::{
+    communication::JsMessage, menu::MenuAction, settings::GameSettings, spectator::is_playing,
+    turns::TurnPlugin, ActionReader,
+};
+use target::{
+    ring_score, Target, TargetPlugin, ARROWS, ARROW_STOPS, SHOOTING_DISTANCE, TARGET_CENTRE,
+};
+use wind::WindPlugin;
+
+pub mod ends;
+pub mod phase;
+pub mod tally;
+pub mod target;
+pub mod wind;
+
+/// How far a radian of controller rotation turns the bow, in radians
+const AIM_SCALE: f32 = 0.15;
+/// Furthest the bow can be turned from straight at the target, in radians
+const MAX_AIM: f32 = 0.3;
+/// How long A has to be held to draw the bow all the way, in seconds
+const FULL_DRAW_SECS: f32 = 1.5;
+/// Speed an arrow loosed with barely any draw leaves the bow at, in meters per second
+const MIN_ARROW_SPEED: f32 = 20.0;
+/// Speed an arrow loosed at full draw leaves the bow at, in meters per second
+const MAX_ARROW_SPEED: f32 = 55.0;
+/// Where arrows are loosed from, at the archer's anchor on the shooting line
+const ANCHOR: Vec3 = Vec3::new(0.0, 1.5, SHOOTING_DISTANCE);
+/// Radius of an arrow's point
+const ARROW_POINT_RADIUS: f32 = 0.005;
+/// Longest an arrow can fly before it's counted as a miss, in seconds
+const MAX_FLIGHT_SECS: f32 = 4.0;
+/// Acceleration arrows fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// Time step flights are predicted with, in seconds
+const PREDICTION_STEP: f32 = 1.0 / 120.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(ArcheryPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(TargetPlugin)
+    .add_plugins(WindPlugin)
+    .add_plugins(EndsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Bow>()
+    .add_event::<Loose>()
+    .add_event::<ArrowLanded>()
+    .add_systems(Startup, setup_arrow_model)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            loose_arrow.run_if(in_state(ArcheryPhase::Drawing)),
+            (point_arrows, stick_arrows, lose_arrows).run_if(in_state(ArcheryPhase::Flying)),
+        )
+            .chain()
+            .before(score_arrow)
+            .run_if(is_playing),
+    )
+    .add_systems(Update, draw_sight.run_if(in_state(ArcheryPhase::Drawing)));
+});
+
+/// Where the archer on the line is aiming and how long they've been drawing
+#[derive(Resource, Debug, Default)]
+pub struct Bow {
+    /// Yaw and pitch of the bow, in radians from straight at the target. Positive yaw turns
+    /// left and positive pitch lifts the bow
+    pub aim: Vec2,
+    /// Seconds since startup A was pressed to start drawing, while it's held
+    drawing_since: Option<f32>,
+}
+
+impl Bow {
+    /// How far the bow is drawn, from 0 to 1
+    pub fn draw(&self, now: f32) -> f32 {
+        self.drawing_since.map_or(0.0, |since| {
+            ((now - since) / FULL_DRAW_SECS).clamp(0.0, 1.0)
+        })
+    }
+}
+
+/// An arrow loosed from the shooting line
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Loose {
+    /// Yaw and pitch of the bow, in radians from straight at the target
+    pub aim: Vec2,
+    /// How far the bow was drawn, from 0 to 1
+    pub power: f32,
+}
+
+impl Loose {
+    /// Velocity the arrow leaves the bow with
+    fn velocity(&self) -> Vec3 {
+        let direction = Quat::from_rotation_y(self.aim.x) * Quat::from_rotation_x(self.aim.y);
+        let speed =
+            MIN_ARROW_SPEED + (MAX_ARROW_SPEED - MIN_ARROW_SPEED) * self.power.clamp(0.0, 1.0);
+        direction * Vec3::NEG_Z * speed
+    }
+
+    /// Where the arrow crosses the target's face, falling under gravity and pushed by `push`,
+    /// the wind's acceleration
+    pub fn landing(&self, push: Vec3) -> Vec3 {
+        let acceleration = push - Vec3::Y * GRAVITY;
+        let mut position = ANCHOR;
+        let mut velocity = self.velocity();
+        let mut secs = 0.0;
+        while position.z > TARGET_CENTRE.z && secs < MAX_FLIGHT_SECS {
+            velocity += acceleration * PREDICTION_STEP;
+            position += velocity * PREDICTION_STEP;
+            secs += PREDICTION_STEP;
+        }
+        position
+    }
+}
+
+/// An arrow landed, scoring the ring it hit
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ArrowLanded(pub u32);
+
+/// A loosed arrow
+#[derive(Component, Debug)]
+pub struct Arrow {
+    /// Seconds since startup the arrow was loosed at, while it's still in the air
+    flying_since: Option<f32>,
+}
+
+impl Arrow {
+    /// Whether the arrow is still in the air
+    pub fn is_flying(&self) -> bool {
+        self.flying_since.is_some()
+    }
+}
+
+/// Meshes and materials every arrow is built from
+#[derive(Resource)]
+struct ArrowModel {
+    /// The shaft, running back from the point
+    shaft: Handle<Mesh>,
+    /// A fletching vane, with one spawned flat and one upright
+    vane: Handle<Mesh>,
+    /// Material of the shaft
+    shaft_material: Handle<StandardMaterial>,
+    /// Material of the fletching
+    vane_material: Handle<StandardMaterial>,
+}
+
+impl ArrowModel {
+    /// Spawns an arrow's shaft and fletching behind a point at the parent's origin, facing the
+    /// parent's forward
+    fn build(&self, arrow: &mut ChildBuilder<'_>) {
+        arrow.spawn((
+            Mesh3d(self.shaft.clone()),
+            MeshMaterial3d(self.shaft_material.clone()),
+            Transform::from_xyz(0.0, 0.0, 0.35)
+                .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        ));
+        for roll in [0.0, std::f32::consts::FRAC_PI_2] {
+            arrow.spawn((
+                Mesh3d(self.vane.clone()),
+                MeshMaterial3d(self.vane_material.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.63).with_rotation(Quat::from_rotation_z(roll)),
+            ));
+        }
+    }
+}
+
+/// Builds the meshes and materials arrows are spawned with
+fn setup_arrow_model(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ArrowModel {
+        shaft: meshes.add(Cylinder::new(ARROW_POINT_RADIUS, 0.7)),
+        vane: meshes.add(Cuboid::new(0.04, 0.001, 0.08)),
+        shaft_material: materials.add(Color::srgb(0.3, 0.2, 0.12)),
+        vane_material: materials.add(Color::srgb(0.9, 0.2, 0.5)),
+    });
+}
+
+/// Everything input handling changes besides the bow itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Arrows to loose
+    looses: EventWriter<'w, Loose>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: pitch and yaw aim, holding A draws the bow and releasing it looses
+/// the arrow. A starts a new match once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut bow: ResMut<'_, Bow>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<ArcheryPhase>>,
+    time: Res<'_, Time>,
+) {
+    let drawing = *phase.get() == ArcheryPhase::Drawing;
+    let now = time.elapsed_secs();
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == ArcheryPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if drawing => {
+                bow.drawing_since = Some(now);
+            }
+            JsMessage::ReleaseA if drawing && bow.drawing_since.is_some() => {
+                let power = bow.draw(now);
+                bow.drawing_since = None;
+                effects.looses.send(Loose {
+                    aim: bow.aim,
+                    power,
+                });
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if drawing => {
+                let orientation = settings.apply_rotation(orientation);
+                bow.aim = (Vec2::new(orientation.yaw, orientation.pitch) * AIM_SCALE)
+                    .clamp(Vec2::splat(-MAX_AIM), Vec2::splat(MAX_AIM));
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Launches an arrow from the anchor down the range
+fn loose_arrow(
+    mut commands: Commands<'_, '_>,
+    mut looses: EventReader<'_, '_, Loose>,
+    mut next_phase: ResMut<'_, NextState<ArcheryPhase>>,
+    model: Res<'_, ArrowModel>,
+    time: Res<'_, Time>,
+) {
+    let Some(loose) = looses.read().last().copied() else {
+        return;
+    };
+    let velocity = loose.velocity();
+
+    commands
+        .spawn((
+            Transform::from_translation(ANCHOR).looking_to(velocity, Vec3::Y),
+            Visibility::Visible,
+            RigidBody::Dynamic,
+            Collider::ball(ARROW_POINT_RADIUS),
+            Ccd::enabled(),
+            Velocity::linear(velocity),
+            ActiveEvents::COLLISION_EVENTS,
+            // Arrows only hit the target and the ground, never each other
+            CollisionGroups::new(ARROWS, ARROW_STOPS),
+            Arrow {
+                flying_since: Some(time.elapsed_secs()),
+            },
+        ))
+        .with_children(|arrow| model.build(arrow));
+    next_phase.set(ArcheryPhase::Flying);
+}
+
+/// Turns flying arrows to face the way they're going
+fn point_arrows(mut arrows: Query<'_, '_, (&mut Transform, &Velocity, &Arrow)>) {
+    for (mut transform, velocity, arrow) in &mut arrows {
+        if arrow.is_flying() && velocity.linvel.length_squared() > f32::EPSILON {
+            transform.look_to(velocity.linvel, Vec3::Y);
+        }
+    }
+}
+
+/// Sticks a flying arrow where it hits, scoring it if that was the target
+fn stick_arrows(
+    mut commands: Commands<'_, '_>,
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut arrows: Query<'_, '_, (&Transform, &mut Arrow)>,
+    targets: Query<'_, '_, (), With<Target>>,
+    mut landed: EventWriter<'_, ArrowLanded>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let (arrow, other) = if arrows.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let Ok((transform, mut state)) = arrows.get_mut(arrow) else {
+            continue;
+        };
+        if state.flying_since.take().is_none() {
+            continue;
+        }
+
+        let points = if targets.contains(other) {
+            ring_score((transform.translation - TARGET_CENTRE).truncate())
+        } else {
+            0
+        };
+        commands
+            .entity(arrow)
+            .insert((RigidBody::Fixed, Velocity::zero()));
+        landed.send(ArrowLanded(points));
+    }
+}
+
+/// Counts an arrow that never hit anything as a miss
+fn lose_arrows(
+    mut arrows: Query<'_, '_, &mut Arrow>,
+    mut landed: EventWriter<'_, ArrowLanded>,
+    time: Res<'_, Time>,
+) {
+    for mut arrow in &mut arrows {
+        if arrow
+            .flying_since
+            .is_some_and(|since| time.elapsed_secs() - since > MAX_FLIGHT_SECS)
+        {
+            arrow.flying_since = None;
+            landed.send(ArrowLanded(0));
+        }
+    }
+}
+
+/// Rings where on the face the arrow would land with the current draw, or a full one before
+/// drawing starts, leaving the wind for the archer to allow for
+fn draw_sight(
+    mut gizmos: Gizmos<'_, '_>,
+    bow: Res<'_, Bow>,
+    settings: Res<'_, GameSettings>,
+    time: Res<'_, Time>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let draw = bow.draw(time.elapsed_secs());
+    let power = if draw > 0.0 { draw } else { 1.0 };
+    let spot = Loose {
+        aim: bow.aim,
+        power,
+    }
+    .landing(Vec3::ZERO);
+    gizmos.circle(
+        Isometry3d::from_translation(spot.with_z(TARGET_CENTRE.z + 0.05)),
+        0.03,
+        Color::WHITE,
+    );
+}
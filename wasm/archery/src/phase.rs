@@ -0,0 +1,27 @@
+//! Phases an archery match moves through, from drawing the bow to the final scores
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the match is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ArcheryPhase {
+    /// The archer on the line is aiming and drawing their next arrow
+    #[default]
+    Drawing,
+    /// An arrow is on its way down the range
+    Flying,
+    /// The end is over and its arrows are being scored and pulled from the target
+    Scoring,
+    /// Every archer has shot every end and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the match is in
+pub struct ArcheryPhasePlugin;
+
+impl Plugin for ArcheryPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<ArcheryPhase>();
+    }
+}
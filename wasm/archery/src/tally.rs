@@ -0,0 +1,72 @@
+//! Match scoring: every archer shoots the same number of ends of a few arrows each, and the
+//! highest total across them all wins
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Ends each archer shoots in a match
+pub const ENDS: usize = 3;
+/// Arrows shot in each end
+pub const ARROWS_PER_END: usize = 3;
+
+/// Every arrow each archer has scored this match
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    /// Each archer's arrow scores in the order they were shot, in turn order
+    arrows: Vec<Vec<u32>>,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Tally {
+    /// Starts a match for a number of archers with nothing shot
+    pub fn new(players: usize) -> Self {
+        Self {
+            arrows: vec![Vec::with_capacity(ENDS * ARROWS_PER_END); players.max(1)],
+        }
+    }
+
+    /// How many archers are in the match
+    pub fn players(&self) -> usize {
+        self.arrows.len()
+    }
+
+    /// Records an arrow's score for an archer, returning whether it was the last of their end
+    pub fn record(&mut self, player: usize, points: u32) -> bool {
+        let Some(arrows) = self.arrows.get_mut(player) else {
+            return false;
+        };
+        arrows.push(points);
+        arrows.len() % ARROWS_PER_END == 0
+    }
+
+    /// The scores of an archer's arrows in an end, as many as they've shot so far
+    pub fn end(&self, player: usize, end: usize) -> &[u32] {
+        let arrows = self.arrows.get(player).map_or(&[][..], Vec::as_slice);
+        let start = (end * ARROWS_PER_END).min(arrows.len());
+        let stop = (start + ARROWS_PER_END).min(arrows.len());
+        &arrows[start..stop]
+    }
+
+    /// An archer's total across every arrow they've shot
+    pub fn total(&self, player: usize) -> u32 {
+        self.arrows
+            .get(player)
+            .map_or(0, |arrows| arrows.iter().sum())
+    }
+
+    /// The archers on the highest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+}
@@ -0,0 +1,161 @@
+//! The target: a ten ring face on a boss at the end of the range, and the score of any spot on
+//! it
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, CollisionGroups, Group, RigidBody};
+
+/// Centre of the target's gold, facing back down the range
+pub const TARGET_CENTRE: Vec3 = Vec3::new(0.0, 1.3, 0.0);
+/// How far down the range the shooting line is from the target
+pub const SHOOTING_DISTANCE: f32 = 30.0;
+/// Radius of the scoring face, out to the edge of the one ring
+pub const FACE_RADIUS: f32 = 0.61;
+/// Rings on the face, scoring from one on the outside up to ten in the middle
+const RINGS: u32 = 10;
+/// Width of each ring
+const RING_WIDTH: f32 = FACE_RADIUS / RINGS as f32;
+/// How thick the straw boss the face is pinned to is
+const BOSS_THICKNESS: f32 = 0.3;
+/// Half the width of the boss, which runs a little past the face
+const BOSS_HALF_WIDTH: f32 = 0.7;
+
+/// Collision group of everything arrows can stick into
+pub const ARROW_STOPS: Group = Group::GROUP_1;
+/// Collision group of arrows, which never hit each other
+pub const ARROWS: Group = Group::GROUP_2;
+
+/// Scores a spot on the face, in meters from the centre with up being positive y. Arrows on a
+/// line take the higher ring, and anything off the face scores nothing
+pub fn ring_score(spot: Vec2) -> u32 {
+    let from_edge = FACE_RADIUS - spot.length();
+    if from_edge < 0.0 {
+        return 0;
+    }
+    (from_edge / RING_WIDTH) as u32 + 1
+}
+
+/// Marks the boss's collider, which arrows stick into and get scored from
+#[derive(Component, Debug)]
+pub struct Target;
+
+/// Plugin that builds the range: the target, the ground and the view from the shooting line
+pub struct TargetPlugin;
+
+impl Plugin for TargetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_range);
+    }
+}
+
+/// Spawns the face's rings, the boss, its stand, the ground, the camera and the light
+fn setup_range(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    // Outermost first, two rings to each color
+    let colors = [
+        materials.add(Color::srgb(0.95, 0.95, 0.95)),
+        materials.add(Color::srgb(0.08, 0.08, 0.08)),
+        materials.add(Color::srgb(0.1, 0.55, 0.85)),
+        materials.add(Color::srgb(0.85, 0.12, 0.1)),
+        materials.add(Color::srgb(0.98, 0.8, 0.1)),
+    ];
+    let outline = materials.add(Color::srgb(0.3, 0.3, 0.3));
+    commands
+        .spawn((
+            Transform::from_translation(TARGET_CENTRE),
+            Visibility::Visible,
+            Name::new("Target face"),
+        ))
+        .with_children(|face| {
+            // Each ring is painted just in front of the last, so only its band shows
+            for ring in 0..RINGS {
+                let radius = FACE_RADIUS - ring as f32 * RING_WIDTH;
+                face.spawn((
+                    Mesh3d(meshes.add(Circle::new(radius))),
+                    MeshMaterial3d(colors[ring as usize / 2].clone()),
+                    Transform::from_xyz(0.0, 0.0, 0.002 * (ring + 1) as f32),
+                ));
+                face.spawn((
+                    Mesh3d(meshes.add(Annulus::new(radius - 0.002, radius))),
+                    MeshMaterial3d(outline.clone()),
+                    Transform::from_xyz(0.0, 0.0, 0.002 * (ring + 1) as f32 + 0.001),
+                ));
+            }
+        });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            BOSS_HALF_WIDTH * 2.0,
+            BOSS_HALF_WIDTH * 2.0,
+            BOSS_THICKNESS,
+        ))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.45))),
+        Transform::from_translation(TARGET_CENTRE - Vec3::Z * BOSS_THICKNESS / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(BOSS_HALF_WIDTH, BOSS_HALF_WIDTH, BOSS_THICKNESS / 2.0),
+        CollisionGroups::new(ARROW_STOPS, Group::ALL),
+        Target,
+    ));
+
+    let leg = meshes.add(Cylinder::new(0.04, TARGET_CENTRE.y + 0.3));
+    let wood = materials.add(Color::srgb(0.4, 0.27, 0.15));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(leg.clone()),
+            MeshMaterial3d(wood.clone()),
+            Transform::from_xyz(
+                side * BOSS_HALF_WIDTH * 0.8,
+                (TARGET_CENTRE.y + 0.3) / 2.0,
+                -BOSS_THICKNESS - 0.1,
+            )
+            .with_rotation(Quat::from_rotation_x(-0.15)),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(30.0, SHOOTING_DISTANCE + 30.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.6, 0.25))),
+        Transform::from_xyz(0.0, 0.0, SHOOTING_DISTANCE / 2.0),
+        Name::new("Range"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, SHOOTING_DISTANCE / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(15.0, 0.1, SHOOTING_DISTANCE / 2.0 + 15.0),
+        CollisionGroups::new(ARROW_STOPS, Group::ALL),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(1.5, 0.05))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, 0.005, SHOOTING_DISTANCE),
+        Name::new("Shooting line"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: 0.12,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 1.6, SHOOTING_DISTANCE + 0.5).looking_at(TARGET_CENTRE, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 10.0, SHOOTING_DISTANCE)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.7)),
+    ));
+}
@@ -0,0 +1,403 @@
+//! Bevy axe throwing game
+
+use std::f32::consts::TAU;
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{ActiveEvents, Ccd, Collider, CollisionEvent, Restitution, RigidBody, Velocity},
+};
+use phase::{AxePhase, AxePhasePlugin};
+use spjorts_core::{
+    communication::JsMessage,
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader,
+};
+use target::{ring_score, Target, TargetPlugin, TARGET_CENTRE, THROWING_DISTANCE};
+use throws::{score_axe, NewGame, ThrowsPlugin};
+
+pub mod phase;
+pub mod tally;
+pub mod target;
+pub mod throws;
+
+/// How far a radian of controller yaw turns the throw, in radians
+const AIM_SCALE: f32 = 0.15;
+/// Furthest a throw can be turned from straight at the target, in radians
+const MAX_AIM: f32 = 0.12;
+/// How far the controller has to come down from the top of a swing for it to count as an
+/// overhead throw, in radians
+const OVERHEAD_DROP: f32 = 1.0;
+/// Slowest an axe can turn end over end, in radians per second
+const MIN_SPIN: f32 = 6.0;
+/// Fastest an axe can turn end over end, in radians per second
+const MAX_SPIN: f32 = 25.0;
+/// Speed an axe would leave the hand at without any swing behind it, in meters per second
+const BASE_SPEED: f32 = 4.2;
+/// Speed every radian per second of swing adds to the axe, in meters per second
+const SPEED_PER_SPIN: f32 = 0.35;
+/// How far above level the axe leaves the hand, in radians
+const LAUNCH_ANGLE: f32 = 0.15;
+/// Where axes leave the hand, above the thrower's head on the throwing line
+const RELEASE: Vec3 = Vec3::new(0.0, 1.85, THROWING_DISTANCE);
+/// Radius of the axe's head, which is what meets the boards
+const HEAD_RADIUS: f32 = 0.06;
+/// Furthest the blade can be turned from pointing straight into the boards and still bite, in
+/// radians
+const STICK_WINDOW: f32 = 0.5;
+/// Longest an axe can fly before it's counted as a miss, in seconds
+const MAX_FLIGHT_SECS: f32 = 2.0;
+/// Acceleration axes fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// Times the spin that sticks an axe is halved in on
+const SPIN_SEARCH_STEPS: usize = 20;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(AxePhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(TargetPlugin)
+    .add_plugins(ThrowsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.2, 0.17, 0.15)))
+    .init_resource::<Grip>()
+    .add_event::<Throw>()
+    .add_event::<AxeLanded>()
+    .add_systems(Startup, setup_axe_model)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            throw_axe.run_if(in_state(AxePhase::Aiming)),
+            (stick_axes, lose_axes).run_if(in_state(AxePhase::Flying)),
+        )
+            .chain()
+            .before(score_axe)
+            .run_if(is_playing),
+    )
+    .add_systems(Update, draw_aim_guide.run_if(in_state(AxePhase::Aiming)));
+});
+
+/// How the thrower on the line is holding the axe, and the last axe thrown
+#[derive(Resource, Debug, Default)]
+pub struct Grip {
+    /// How far the throw is turned from straight at the target, in radians. Positive turns left
+    pub aim: f32,
+    /// Highest the controller has been pitched since it was last at rest, in radians
+    top_pitch: f32,
+    /// Watches the controller for overhead swings
+    detector: GestureDetector,
+    /// The last axe thrown, shown on the HUD
+    pub last: Option<Throw>,
+}
+
+/// An axe let go from the throwing line
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Throw {
+    /// How far the throw is turned from straight at the target, in radians
+    pub aim: f32,
+    /// How fast the axe turns end over end, matching the swing that threw it, in radians per
+    /// second
+    pub spin: f32,
+}
+
+impl Throw {
+    /// Velocity the axe leaves the hand with, faster the harder the swing
+    fn velocity(&self) -> Vec3 {
+        let direction = Quat::from_rotation_y(self.aim) * Quat::from_rotation_x(LAUNCH_ANGLE);
+        direction * Vec3::NEG_Z * (BASE_SPEED + SPEED_PER_SPIN * self.spin)
+    }
+
+    /// Angular velocity the axe leaves the hand with, turning its head forward and down
+    fn angular_velocity(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Vec3::NEG_X * self.spin
+    }
+
+    /// Where the axe's head meets the boards and how far it has turned end over end by then, in
+    /// radians
+    pub fn arrival(&self) -> (Vec3, f32) {
+        let velocity = self.velocity();
+        let secs = (THROWING_DISTANCE - HEAD_RADIUS) / -velocity.z;
+        let spot = RELEASE + velocity * secs - Vec3::Y * GRAVITY * secs * secs / 2.0;
+        (spot, self.spin * secs)
+    }
+
+    /// How many times the axe turns end over end before it meets the boards
+    pub fn turns(&self) -> f32 {
+        self.arrival().1 / TAU
+    }
+}
+
+/// The spin that brings an axe aimed with `aim` round exactly once, blade first into the boards
+pub fn sticking_spin(aim: f32) -> f32 {
+    let (mut low, mut high) = (MIN_SPIN, MAX_SPIN);
+    for _ in 0..SPIN_SEARCH_STEPS {
+        let spin = (low + high) / 2.0;
+        if (Throw { aim, spin }).arrival().1 < TAU {
+            low = spin;
+        } else {
+            high = spin;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Whether an axe turned to `rotation` has its blade pointing into the boards closely enough to
+/// bite, rather than hitting them with the handle or the back of the head
+fn blade_first(rotation: Quat) -> bool {
+    (rotation * Vec3::NEG_Z).dot(Vec3::NEG_Z) >= STICK_WINDOW.cos()
+}
+
+/// How a throw ended
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxeLanded {
+    /// The blade bit into the boards, scoring the ring it's in
+    Stuck(u32),
+    /// The axe hit the boards handle or head first and bounced off
+    Bounced,
+    /// The axe never reached the boards
+    Missed,
+}
+
+/// A thrown axe
+#[derive(Component, Debug)]
+pub struct Axe {
+    /// Seconds since startup the axe was thrown at, while it's still in the air
+    flying_since: Option<f32>,
+}
+
+/// Meshes and materials every axe is built from
+#[derive(Resource)]
+struct AxeModel {
+    /// The handle, hanging below the head
+    handle: Handle<Mesh>,
+    /// The head, around the top of the handle
+    head: Handle<Mesh>,
+    /// The sharpened edge at the front of the head
+    edge: Handle<Mesh>,
+    /// Material of the handle
+    wood: Handle<StandardMaterial>,
+    /// Material of the head
+    steel: Handle<StandardMaterial>,
+    /// Material of the edge
+    polished: Handle<StandardMaterial>,
+}
+
+impl AxeModel {
+    /// Spawns an axe's handle, head and edge around a head at the parent's origin, with the
+    /// blade facing the parent's forward and the handle hanging down
+    fn build(&self, axe: &mut ChildBuilder<'_>) {
+        axe.spawn((
+            Mesh3d(self.handle.clone()),
+            MeshMaterial3d(self.wood.clone()),
+            Transform::from_xyz(0.0, -0.18, 0.0),
+        ));
+        axe.spawn((
+            Mesh3d(self.head.clone()),
+            MeshMaterial3d(self.steel.clone()),
+            Transform::from_xyz(0.0, 0.0, -0.02),
+        ));
+        axe.spawn((
+            Mesh3d(self.edge.clone()),
+            MeshMaterial3d(self.polished.clone()),
+            Transform::from_xyz(0.0, 0.0, -0.085),
+        ));
+    }
+}
+
+/// Builds the meshes and materials axes are spawned with
+fn setup_axe_model(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(AxeModel {
+        handle: meshes.add(Cylinder::new(0.015, 0.4)),
+        head: meshes.add(Cuboid::new(0.025, 0.07, 0.12)),
+        edge: meshes.add(Cuboid::new(0.01, 0.1, 0.015)),
+        wood: materials.add(Color::srgb(0.55, 0.36, 0.2)),
+        steel: materials.add(Color::srgb(0.25, 0.25, 0.28)),
+        polished: materials.add(Color::srgb(0.85, 0.85, 0.9)),
+    });
+}
+
+/// Everything input handling changes besides the grip itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Axes to throw
+    throws: EventWriter<'w, Throw>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims while the controller is still, and an overhead swing
+/// throws the axe spinning as fast as the swing turned. A starts a new match once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut grip: ResMut<'_, Grip>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<AxePhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == AxePhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == AxePhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                let detected = grip.detector.update(orientation, time.elapsed_secs());
+                let overhead = grip.top_pitch - orientation.pitch >= OVERHEAD_DROP;
+                if grip.detector.speed() < grip.detector.thresholds.rest_speed {
+                    grip.aim = (orientation.yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                    grip.top_pitch = orientation.pitch;
+                } else {
+                    grip.top_pitch = grip.top_pitch.max(orientation.pitch);
+                }
+
+                if let Some(detected) = detected.filter(|detected| {
+                    overhead && matches!(detected.gesture, Gesture::Swing | Gesture::Flick)
+                }) {
+                    effects.throws.send(Throw {
+                        aim: grip.aim,
+                        spin: detected.intensity.clamp(MIN_SPIN, MAX_SPIN),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets an axe go from above the thrower's head, turning end over end toward the target
+fn throw_axe(
+    mut commands: Commands<'_, '_>,
+    mut throws: EventReader<'_, '_, Throw>,
+    mut grip: ResMut<'_, Grip>,
+    mut next_phase: ResMut<'_, NextState<AxePhase>>,
+    model: Res<'_, AxeModel>,
+    time: Res<'_, Time>,
+) {
+    let Some(throw) = throws.read().last().copied() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Transform::from_translation(RELEASE).with_rotation(Quat::from_rotation_y(throw.aim)),
+            Visibility::Visible,
+            RigidBody::Dynamic,
+            Collider::ball(HEAD_RADIUS),
+            Restitution::coefficient(0.3),
+            Ccd::enabled(),
+            Velocity {
+                linvel: throw.velocity(),
+                angvel: throw.angular_velocity(),
+            },
+            ActiveEvents::COLLISION_EVENTS,
+            Axe {
+                flying_since: Some(time.elapsed_secs()),
+            },
+        ))
+        .with_children(|axe| model.build(axe));
+    grip.last = Some(throw);
+    next_phase.set(AxePhase::Flying);
+}
+
+/// Sticks an axe that meets the boards blade first, scoring the ring it bit into, and lets one
+/// that meets them any other way bounce off
+fn stick_axes(
+    mut commands: Commands<'_, '_>,
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut axes: Query<'_, '_, (&Transform, &mut Axe)>,
+    targets: Query<'_, '_, (), With<Target>>,
+    mut landed: EventWriter<'_, AxeLanded>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let (axe, other) = if axes.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let Ok((transform, mut state)) = axes.get_mut(axe) else {
+            continue;
+        };
+        if state.flying_since.take().is_none() {
+            continue;
+        }
+
+        if !targets.contains(other) {
+            landed.send(AxeLanded::Missed);
+        } else if blade_first(transform.rotation) {
+            commands
+                .entity(axe)
+                .insert((RigidBody::Fixed, Velocity::zero()));
+            landed.send(AxeLanded::Stuck(ring_score(
+                (transform.translation - TARGET_CENTRE).truncate(),
+            )));
+        } else {
+            landed.send(AxeLanded::Bounced);
+        }
+    }
+}
+
+/// Counts an axe that never hit anything as a miss
+fn lose_axes(
+    mut axes: Query<'_, '_, &mut Axe>,
+    mut landed: EventWriter<'_, AxeLanded>,
+    time: Res<'_, Time>,
+) {
+    for mut axe in &mut axes {
+        if axe
+            .flying_since
+            .is_some_and(|since| time.elapsed_secs() - since > MAX_FLIGHT_SECS)
+        {
+            axe.flying_since = None;
+            landed.send(AxeLanded::Missed);
+        }
+    }
+}
+
+/// Rings where on the boards an axe thrown the way the grip is aimed lands when it's swung just
+/// hard enough to turn once and stick
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    grip: Res<'_, Grip>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let throw = Throw {
+        aim: grip.aim,
+        spin: sticking_spin(grip.aim),
+    };
+    let (spot, _) = throw.arrival();
+    gizmos.circle(
+        Isometry3d::from_translation(spot.with_z(TARGET_CENTRE.z + 0.02)),
+        0.04,
+        Color::WHITE,
+    );
+}
@@ -0,0 +1,27 @@
+//! Phases an axe throwing match moves through, from winding up a throw to the final scores
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the match is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AxePhase {
+    /// The thrower on the line is lining up their next throw
+    #[default]
+    Aiming,
+    /// An axe is turning end over end on its way to the target
+    Flying,
+    /// The throw has been called and the axe is left where it landed for a moment
+    Scoring,
+    /// Every thrower has thrown every axe and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the match is in
+pub struct AxePhasePlugin;
+
+impl Plugin for AxePhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<AxePhase>();
+    }
+}
@@ -0,0 +1,63 @@
+//! Match scoring: every thrower takes the same number of throws, and the highest total across
+//! them wins
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// Throws each player takes in a match
+pub const THROWS: usize = 10;
+
+/// Every throw each player has scored this match
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    /// Each player's throw scores in the order they were thrown, in turn order
+    throws: Vec<Vec<u32>>,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Tally {
+    /// Starts a match for a number of players with nothing thrown
+    pub fn new(players: usize) -> Self {
+        Self {
+            throws: vec![Vec::with_capacity(THROWS); players.max(1)],
+        }
+    }
+
+    /// How many players are in the match
+    pub fn players(&self) -> usize {
+        self.throws.len()
+    }
+
+    /// Records a throw's score for a player
+    pub fn record(&mut self, player: usize, points: u32) {
+        if let Some(throws) = self.throws.get_mut(player) {
+            throws.push(points);
+        }
+    }
+
+    /// The scores of a player's throws so far
+    pub fn throws(&self, player: usize) -> &[u32] {
+        self.throws.get(player).map_or(&[][..], Vec::as_slice)
+    }
+
+    /// A player's total across every throw they've made
+    pub fn total(&self, player: usize) -> u32 {
+        self.throws(player).iter().sum()
+    }
+
+    /// The players on the highest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+}
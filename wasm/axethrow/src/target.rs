@@ -0,0 +1,184 @@
+//! The lane: a wooden target of boards with painted rings at the end of it, the throwing line
+//! and the score of any spot on the target
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody};
+
+/// Centre of the bullseye, on the face of the boards
+pub const TARGET_CENTRE: Vec3 = Vec3::new(0.0, 1.5, 0.0);
+/// How far back from the target the throwing line is
+pub const THROWING_DISTANCE: f32 = 4.0;
+/// Points for a blade in the bullseye
+pub const BULLSEYE: u32 = 6;
+/// Each painted ring's outer radius and the points for sticking inside it, from the bullseye out
+const RINGS: [(f32, u32); 5] = [(0.05, BULLSEYE), (0.13, 4), (0.21, 3), (0.29, 2), (0.37, 1)];
+/// Boards the target is built from, standing side by side
+const BOARDS: usize = 5;
+/// Width of each board
+const BOARD_WIDTH: f32 = 0.2;
+/// Half the height of the boards
+const BOARD_HALF_HEIGHT: f32 = 0.6;
+/// How thick the boards are
+const BOARD_THICKNESS: f32 = 0.08;
+/// Half the width of the lane
+const LANE_HALF_WIDTH: f32 = 1.5;
+
+/// Scores a spot on the boards, in meters from the bullseye with up being positive y. A blade
+/// on a line takes the higher ring, and anything outside the rings scores nothing
+pub fn ring_score(spot: Vec2) -> u32 {
+    let distance = spot.length();
+    RINGS
+        .iter()
+        .find(|(radius, _)| distance <= *radius)
+        .map_or(0, |(_, points)| *points)
+}
+
+/// Marks the boards' collider, which axes stick into and get scored from
+#[derive(Component, Debug)]
+pub struct Target;
+
+/// Plugin that builds the lane: the target, its back wall, the floor and the view from the line
+pub struct TargetPlugin;
+
+impl Plugin for TargetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_lane);
+    }
+}
+
+/// Spawns the boards and their rings, the back wall, the floor, the throwing line, the camera
+/// and the light
+fn setup_lane(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let board = meshes.add(Cuboid::new(
+        BOARD_WIDTH - 0.004,
+        BOARD_HALF_HEIGHT * 2.0,
+        BOARD_THICKNESS,
+    ));
+    let pine = [
+        materials.add(Color::srgb(0.78, 0.62, 0.4)),
+        materials.add(Color::srgb(0.72, 0.56, 0.35)),
+    ];
+    let half_width = BOARD_WIDTH * BOARDS as f32 / 2.0;
+    for index in 0..BOARDS {
+        commands.spawn((
+            Mesh3d(board.clone()),
+            MeshMaterial3d(pine[index % 2].clone()),
+            Transform::from_translation(
+                TARGET_CENTRE
+                    + Vec3::new(
+                        (index as f32 + 0.5) * BOARD_WIDTH - half_width,
+                        0.0,
+                        -BOARD_THICKNESS / 2.0,
+                    ),
+            ),
+        ));
+    }
+    commands.spawn((
+        Transform::from_translation(TARGET_CENTRE - Vec3::Z * BOARD_THICKNESS / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(half_width, BOARD_HALF_HEIGHT, BOARD_THICKNESS / 2.0),
+        Restitution::coefficient(0.3),
+        Friction::coefficient(0.6),
+        Name::new("Target"),
+        Target,
+    ));
+
+    // Outermost first, each painted just in front of the last so only its band shows
+    let white = materials.add(Color::srgb(0.95, 0.95, 0.9));
+    let blue = materials.add(Color::srgb(0.15, 0.35, 0.75));
+    let paint = [
+        blue.clone(),
+        white.clone(),
+        blue,
+        white,
+        materials.add(Color::srgb(0.85, 0.12, 0.1)),
+    ];
+    let outline = materials.add(Color::srgb(0.1, 0.1, 0.1));
+    commands
+        .spawn((
+            Transform::from_translation(TARGET_CENTRE),
+            Visibility::Visible,
+            Name::new("Rings"),
+        ))
+        .with_children(|rings| {
+            for (layer, ((radius, _), color)) in RINGS.iter().rev().zip(paint).enumerate() {
+                let depth = 0.002 * (layer + 1) as f32;
+                rings.spawn((
+                    Mesh3d(meshes.add(Circle::new(*radius))),
+                    MeshMaterial3d(color),
+                    Transform::from_xyz(0.0, 0.0, depth),
+                ));
+                rings.spawn((
+                    Mesh3d(meshes.add(Annulus::new(radius - 0.006, *radius))),
+                    MeshMaterial3d(outline.clone()),
+                    Transform::from_xyz(0.0, 0.0, depth + 0.001),
+                ));
+            }
+        });
+
+    let wall_height = 3.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(LANE_HALF_WIDTH * 2.0, wall_height, 0.1))),
+        MeshMaterial3d(materials.add(Color::srgb(0.35, 0.25, 0.18))),
+        Transform::from_xyz(0.0, wall_height / 2.0, -BOARD_THICKNESS - 0.1),
+        RigidBody::Fixed,
+        Collider::cuboid(LANE_HALF_WIDTH, wall_height / 2.0, 0.05),
+        Restitution::coefficient(0.2),
+        Name::new("Back wall"),
+    ));
+
+    let lane_length = THROWING_DISTANCE + 4.0;
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(LANE_HALF_WIDTH * 2.0, lane_length),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.45, 0.45, 0.47))),
+        Transform::from_xyz(0.0, 0.0, lane_length / 2.0 - 0.2),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, lane_length / 2.0 - 0.2),
+        RigidBody::Fixed,
+        Collider::cuboid(LANE_HALF_WIDTH, 0.1, lane_length / 2.0),
+        Restitution::coefficient(0.1),
+        Friction::coefficient(0.8),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(LANE_HALF_WIDTH * 2.0, 0.05))),
+        MeshMaterial3d(materials.add(Color::srgb(0.95, 0.8, 0.1))),
+        Transform::from_xyz(0.0, 0.005, THROWING_DISTANCE),
+        Name::new("Throwing line"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.4, 1.8, THROWING_DISTANCE + 2.0).looking_at(TARGET_CENTRE, Vec3::Y),
+    ));
+    commands.spawn((
+        PointLight {
+            intensity: 400_000.0,
+            range: 20.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.0, THROWING_DISTANCE / 2.0),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 5.0, THROWING_DISTANCE)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.6)),
+    ));
+}
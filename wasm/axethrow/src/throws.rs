@@ -0,0 +1,260 @@
+//! Throws at the line: scoring each axe, pulling it from the boards and handing over to the
+//! next thrower, all shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::AxePhase,
+    tally::{Tally, THROWS},
+    target::BULLSEYE,
+    Axe, AxeLanded, Grip,
+};
+
+/// How long the axe stays where it landed after a throw, in seconds
+const SCORING_SECS: f32 = 1.5;
+
+/// Asks for the match to be started over from the first thrower's first throw
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts down before the axe is pulled and the next thrower steps up
+#[derive(Resource, Debug)]
+struct Scoring(Timer);
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SCORING_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct AxeThrowSnapshot<'a> {
+    /// Thrower on the line
+    player: usize,
+    /// The throw being made, starting from zero
+    throw: usize,
+    /// Every thrower's throws so far
+    tally: &'a Tally,
+    /// Where the match is at
+    phase: AxePhase,
+}
+
+/// Plugin that scores throws and shows them on the scorecard HUD
+pub struct ThrowsPlugin;
+
+impl Plugin for ThrowsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Tally>()
+            .init_resource::<Scoring>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    score_axe.run_if(in_state(AxePhase::Flying)),
+                    pull_axe.run_if(in_state(AxePhase::Scoring)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(AxePhase::Scoring), reset_scoring)
+            .add_systems(
+                OnEnter(AxePhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(AxePhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh match whenever the number of throwers changes
+fn fit_match(turns: Res<'_, TurnManager>, mut tally: ResMut<'_, Tally>) {
+    if tally.players() != turns.players() {
+        *tally = Tally::new(turns.players());
+    }
+}
+
+/// Scores the axe that just landed and leaves it there for a moment
+pub fn score_axe(
+    mut landed: EventReader<'_, '_, AxeLanded>,
+    mut tally: ResMut<'_, Tally>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<AxePhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    for landing in landed.read() {
+        let points = match *landing {
+            AxeLanded::Stuck(points) => points,
+            AxeLanded::Bounced | AxeLanded::Missed => 0,
+        };
+        tally.record(turns.current(), points);
+        match *landing {
+            AxeLanded::Stuck(BULLSEYE) => banner.show("Bullseye!"),
+            AxeLanded::Stuck(0) => banner.show("Stuck, but off the rings"),
+            AxeLanded::Stuck(points) => banner.show(format!("{points} points")),
+            AxeLanded::Bounced => banner.show("Bounced off!"),
+            AxeLanded::Missed => banner.show("Miss"),
+        }
+        next_phase.set(AxePhase::Scoring);
+    }
+}
+
+/// Gives throwers a moment to see where the axe landed
+fn reset_scoring(mut scoring: ResMut<'_, Scoring>) {
+    scoring.0.reset();
+}
+
+/// Pulls the axe and hands the line to the next thrower, finishing the match once everyone has
+/// taken every throw
+fn pull_axe(
+    mut commands: Commands<'_, '_>,
+    mut scoring: ResMut<'_, Scoring>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<AxePhase>>,
+    axes: Query<'_, '_, Entity, With<Axe>>,
+    time: Res<'_, Time>,
+) {
+    if !scoring.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for axe in &axes {
+        commands.entity(axe).despawn_recursive();
+    }
+    turns.advance();
+    if turns.round() >= THROWS {
+        next_phase.set(AxePhase::GameOver);
+    } else {
+        next_phase.set(AxePhase::Aiming);
+    }
+}
+
+/// Starts the match over from the first thrower's first throw
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut tally: ResMut<'_, Tally>,
+    mut next_phase: ResMut<'_, NextState<AxePhase>>,
+    axes: Query<'_, '_, Entity, With<Axe>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for axe in &axes {
+        commands.entity(axe).despawn_recursive();
+    }
+    turns.restart();
+    *tally = Tally::new(turns.players());
+    next_phase.set(AxePhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every thrower's total and throws, and the spin of the last
+/// axe thrown
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    tally: Res<'_, Tally>,
+    turns: Res<'_, TurnManager>,
+    grip: Res<'_, Grip>,
+) {
+    let rows = (0..tally.players())
+        .map(|player| {
+            let mut throws: Vec<String> = tally
+                .throws(player)
+                .iter()
+                .map(|points| match points {
+                    0 => "X".to_string(),
+                    points => points.to_string(),
+                })
+                .collect();
+            throws.resize(THROWS, "-".to_string());
+            format!(
+                "Player {}: {} ({})",
+                player + 1,
+                tally.total(player),
+                throws.join(" ")
+            )
+        })
+        .collect();
+    let footer = grip
+        .last
+        .map(|throw| {
+            format!(
+                "Last axe: {:.1} rad/s, {:.2} turns",
+                throw.spin,
+                throw.turns()
+            )
+        })
+        .unwrap_or_else(|| "Swing overhead to throw".to_string());
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!(
+            "Axe Throwing, throw {} of {THROWS}",
+            turns.round().min(THROWS - 1) + 1
+        ),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every thrower's total once the match is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, tally: Res<'_, Tally>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    let leaders = tally.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if tally.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..tally.players() {
+        lines.push(format!("Player {}: {}", player + 1, tally.total(player)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new match starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every thrower's total back to the page once the match is over, so it can submit them
+/// to the server
+fn submit_result(tally: Res<'_, Tally>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..tally.players())
+        .map(|player| tally.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    tally: Res<'_, Tally>,
+    phase: Res<'_, State<AxePhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&AxeThrowSnapshot {
+        player: turns.current(),
+        throw: turns.round(),
+        tally: &tally,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,113 @@
+//! Derby scoring: how every pitch each batter saw went, with fair balls adding their distance
+//! to the batter's total
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Pitches every batter sees
+pub const PITCHES_PER_BATTER: usize = 10;
+
+/// How a pitch went
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// The batter missed or never swung
+    Strike,
+    /// The ball was hit outside the foul lines
+    Foul,
+    /// The ball landed fair inside the park, this many meters from home plate
+    Fair(f32),
+    /// The ball cleared the fence, landing this many meters from home plate
+    HomeRun(f32),
+}
+
+impl Outcome {
+    /// How many meters the pitch adds to the batter's total
+    pub fn distance(&self) -> f32 {
+        match self {
+            Self::Strike | Self::Foul => 0.0,
+            Self::Fair(distance) | Self::HomeRun(distance) => *distance,
+        }
+    }
+
+    /// How the pitch reads on the scorecard
+    pub fn label(&self) -> String {
+        match self {
+            Self::Strike => "K".to_string(),
+            Self::Foul => "F".to_string(),
+            Self::Fair(distance) => format!("{distance:.0}"),
+            Self::HomeRun(distance) => format!("{distance:.0}*"),
+        }
+    }
+}
+
+/// Every batter's pitches so far
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Derby {
+    /// How every pitch each batter has seen went, in turn order
+    pitches: Vec<Vec<Outcome>>,
+}
+
+impl Default for Derby {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Derby {
+    /// Starts a derby for a number of batters with no pitches seen
+    pub fn new(players: usize) -> Self {
+        Self {
+            pitches: vec![Vec::new(); players.max(1)],
+        }
+    }
+
+    /// How many batters are in the derby
+    pub fn players(&self) -> usize {
+        self.pitches.len()
+    }
+
+    /// Records how a pitch to a batter went
+    pub fn record(&mut self, player: usize, outcome: Outcome) {
+        if let Some(pitches) = self.pitches.get_mut(player) {
+            pitches.push(outcome);
+        }
+    }
+
+    /// How every pitch a batter has seen went
+    pub fn pitches(&self, player: usize) -> &[Outcome] {
+        self.pitches.get(player).map_or(&[], Vec::as_slice)
+    }
+
+    /// Pitches a batter has still to see
+    pub fn pitches_left(&self, player: usize) -> usize {
+        PITCHES_PER_BATTER.saturating_sub(self.pitches(player).len())
+    }
+
+    /// A batter's home runs
+    pub fn home_runs(&self, player: usize) -> usize {
+        self.pitches(player)
+            .iter()
+            .filter(|outcome| matches!(outcome, Outcome::HomeRun(_)))
+            .count()
+    }
+
+    /// A batter's total distance across every fair ball, in whole meters
+    pub fn total(&self, player: usize) -> u32 {
+        self.pitches(player)
+            .iter()
+            .map(Outcome::distance)
+            .sum::<f32>()
+            .round() as u32
+    }
+
+    /// The batters with the longest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+}
@@ -0,0 +1,141 @@
+//! The ballpark: home plate, the pitching machine on the mound, the foul lines and the outfield
+//! fence balls have to clear for a home run
+
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody};
+
+/// Where balls are pitched to, the point of home plate
+pub const PLATE: Vec3 = Vec3::ZERO;
+/// Where the pitching machine lets the ball go, out on the mound
+pub const RELEASE: Vec3 = Vec3::new(0.0, 1.6, -17.5);
+/// How far from home plate the outfield fence stands, in meters
+pub const FENCE_DISTANCE: f32 = 100.0;
+/// How tall the outfield fence is, in meters
+pub const FENCE_HEIGHT: f32 = 3.0;
+/// Widest a ball can be hit from straight up the middle and stay fair, in radians
+const FAIR_ANGLE: f32 = FRAC_PI_4;
+/// How far the grass runs past the fence
+const GROUND_RUNOFF: f32 = 60.0;
+/// How many panels the fence is built from
+const FENCE_PANELS: usize = 24;
+/// Width of the chalk foul lines
+const LINE_WIDTH: f32 = 0.1;
+
+/// How far a spot on the field is from home plate along the ground
+pub fn distance(position: Vec3) -> f32 {
+    (position - PLATE).with_y(0.0).length()
+}
+
+/// Whether a spot on the field is between the foul lines
+pub fn is_fair(position: Vec3) -> bool {
+    let from_plate = position - PLATE;
+    from_plate.z < 0.0 && from_plate.x.atan2(-from_plate.z).abs() <= FAIR_ANGLE
+}
+
+/// Plugin that lays out the ballpark
+pub struct FieldPlugin;
+
+impl Plugin for FieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_field);
+    }
+}
+
+/// Spawns the grass, the infield, the foul lines, the fence, the mound and plate, the camera
+/// and the light
+fn setup_field(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let half = FENCE_DISTANCE + GROUND_RUNOFF;
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(half * 2.0, half * 2.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.25, 0.55, 0.25))),
+        Transform::from_xyz(0.0, 0.0, -half + 10.0),
+        Name::new("Field"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, -half + 10.0),
+        RigidBody::Fixed,
+        Collider::cuboid(half, 0.1, half),
+        Restitution::coefficient(0.4),
+        Friction::coefficient(0.6),
+    ));
+
+    let dirt = materials.add(Color::srgb(0.6, 0.45, 0.3));
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(28.0))),
+        MeshMaterial3d(dirt.clone()),
+        Transform::from_xyz(0.0, 0.005, -19.0)
+            .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(2.7, 0.25))),
+        MeshMaterial3d(dirt),
+        Transform::from_xyz(RELEASE.x, 0.0, RELEASE.z),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.43, 0.02, 0.43))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_translation(PLATE + Vec3::new(0.0, 0.01, -0.2))
+            .with_rotation(Quat::from_rotation_y(FRAC_PI_4)),
+    ));
+
+    // The pitching machine: a box with its barrel pointing at the plate
+    let machine = materials.add(Color::srgb(0.2, 0.2, 0.25));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.6, 0.5, 0.8))),
+        MeshMaterial3d(machine.clone()),
+        Transform::from_translation(RELEASE + Vec3::new(0.0, -0.3, -0.5)),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.08, 0.6))),
+        MeshMaterial3d(machine),
+        Transform::from_translation(RELEASE + Vec3::new(0.0, 0.0, -0.1))
+            .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+    ));
+
+    let chalk = materials.add(Color::WHITE);
+    for side in [-1.0, 1.0] {
+        let direction = Quat::from_rotation_y(side * FAIR_ANGLE) * Vec3::NEG_Z;
+        commands.spawn((
+            Mesh3d(meshes.add(Plane3d::default().mesh().size(LINE_WIDTH, FENCE_DISTANCE))),
+            MeshMaterial3d(chalk.clone()),
+            Transform::from_translation(PLATE + direction * FENCE_DISTANCE / 2.0 + Vec3::Y * 0.01)
+                .with_rotation(Quat::from_rotation_y(side * FAIR_ANGLE)),
+        ));
+    }
+
+    // The fence runs in flat panels around the outfield from one foul pole to the other
+    let panel_width = FENCE_DISTANCE * 2.0 * FAIR_ANGLE / FENCE_PANELS as f32 + 0.2;
+    let panel = meshes.add(Cuboid::new(panel_width, FENCE_HEIGHT, 0.3));
+    let padding = materials.add(Color::srgb(0.1, 0.3, 0.2));
+    for idx in 0..FENCE_PANELS {
+        let angle = -FAIR_ANGLE + (idx as f32 + 0.5) * 2.0 * FAIR_ANGLE / FENCE_PANELS as f32;
+        let rotation = Quat::from_rotation_y(angle);
+        commands.spawn((
+            Mesh3d(panel.clone()),
+            MeshMaterial3d(padding.clone()),
+            Transform::from_translation(
+                PLATE + rotation * Vec3::NEG_Z * FENCE_DISTANCE + Vec3::Y * FENCE_HEIGHT / 2.0,
+            )
+            .with_rotation(rotation),
+        ));
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.2, 4.5).looking_at(RELEASE.with_y(1.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(20.0, 40.0, 20.0).looking_at(Vec3::new(0.0, 0.0, -50.0), Vec3::Y),
+    ));
+}
@@ -0,0 +1,314 @@
+//! Bevy home run derby game
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{Damping, ExternalImpulse, Velocity},
+};
+use field::{FieldPlugin, PLATE, RELEASE};
+use machine::{time_to_plate, Ball, MachinePlugin, PitchingMachine, BALL_MASS};
+use phase::{BattingPhase, BattingPhasePlugin};
+use rounds::{NewGame, RoundsPlugin};
+use spjorts_core::{
+    communication::JsMessage,
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader,
+};
+
+pub mod derby;
+pub mod field;
+pub mod machine;
+pub mod phase;
+pub mod rounds;
+
+/// How far a radian of controller pitch tilts the bat's path, in radians
+const LAUNCH_SCALE: f32 = 1.0;
+/// Lowest the bat's path can send the ball, in radians above the ground
+const MIN_LAUNCH: f32 = -0.2;
+/// Highest the bat's path can send the ball, in radians above the ground
+const MAX_LAUNCH: f32 = 0.8;
+/// How fast the controller has to swing to swing the bat as hard as it goes, in radians per
+/// second
+const FULL_SWING_SPEED: f32 = 12.0;
+/// Furthest from the perfect moment a swing still makes contact, in seconds either way
+const TIMING_WINDOW: f32 = 0.1;
+/// Speed a full swing puts on the ball, in meters per second
+const BAT_SPEED: f32 = 40.0;
+/// How much of the pitch's speed comes back off the bat
+const REBOUND: f32 = 0.2;
+/// How much of the ball's speed is left after contact right at the edge of the timing window
+const EDGE_CONTACT: f32 = 0.5;
+/// How far early or late contact sprays the ball from straight up the middle, in radians
+const MAX_SPRAY: f32 = 1.0;
+/// How hard the air holds back a hit ball, matching a real baseball's carry
+const AIR_DRAG: f32 = 0.2;
+/// How long a swing takes from loaded to follow through, in seconds
+const SWING_SECS: f32 = 0.2;
+/// Length of the bat
+const BAT_LENGTH: f32 = 0.85;
+/// Where the batter's hands hold the bat, for a right-handed batter
+const HANDS: Vec3 = Vec3::new(-0.6, 1.0, 0.2);
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(BattingPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(FieldPlugin)
+    .add_plugins(MachinePlugin)
+    .add_plugins(RoundsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Batter>()
+    .add_event::<Swing>()
+    .add_systems(Startup, spawn_bat)
+    .add_systems(OnEnter(BattingPhase::Waiting), load_bat)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            hit_ball.run_if(in_state(BattingPhase::Pitching)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (swing_bat, follow_ball, draw_target));
+});
+
+/// The batter at the plate
+#[derive(Resource, Debug, Default)]
+pub struct Batter {
+    /// How steeply the bat's path is tilted, as the angle in radians it sends the ball off at
+    pub angle: f32,
+    /// Watches the controller for swings
+    detector: GestureDetector,
+}
+
+/// A swing of the bat
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Swing {
+    /// How steeply the bat's path is tilted, as the angle in radians it sends the ball off at
+    pub angle: f32,
+    /// How hard the bat was swung, from 0 to 1
+    pub power: f32,
+}
+
+/// The bat, pivoting around the batter's hands
+#[derive(Component, Debug, Default)]
+struct Bat {
+    /// Seconds since startup the bat was last swung, until it's loaded again
+    swung_at: Option<f32>,
+}
+
+/// Spawns the bat at the batter's hands
+fn spawn_bat(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn((
+            Transform::from_translation(HANDS),
+            Visibility::Visible,
+            Bat::default(),
+        ))
+        .with_children(|bat| {
+            bat.spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.035, BAT_LENGTH))),
+                MeshMaterial3d(materials.add(Color::srgb(0.7, 0.5, 0.3))),
+                Transform::from_xyz(BAT_LENGTH / 2.0, 0.0, 0.0)
+                    .with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+                BatBarrel,
+            ));
+        });
+}
+
+/// The bat's barrel, which runs out from the hands towards the plate
+#[derive(Component, Debug)]
+struct BatBarrel;
+
+/// Brings the bat back over the batter's shoulder ready for the next pitch
+fn load_bat(mut bats: Query<'_, '_, &mut Bat>) {
+    for mut bat in &mut bats {
+        bat.swung_at = None;
+    }
+}
+
+/// Everything input handling changes besides the batter themselves
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Swings of the bat
+    swings: EventWriter<'w, Swing>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: pitch tilts the bat's path and a swing of the controller swings the
+/// bat. A starts a new derby once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut batter: ResMut<'_, Batter>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<BattingPhase>>,
+    time: Res<'_, Time>,
+) {
+    let batting = *phase.get() == BattingPhase::Pitching;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == BattingPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let orientation = settings.apply_rotation(orientation);
+                batter.angle = (orientation.pitch * LAUNCH_SCALE).clamp(MIN_LAUNCH, MAX_LAUNCH);
+                let swung = batter
+                    .detector
+                    .update(orientation, time.elapsed_secs())
+                    .filter(|detected| detected.gesture == Gesture::Swing);
+                if let Some(detected) = swung.filter(|_| batting) {
+                    effects.swings.send(Swing {
+                        angle: batter.angle,
+                        power: (detected.intensity / FULL_SWING_SPEED).clamp(0.0, 1.0),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Swings at the pitch, making contact when the timing is close enough. Early contact pulls the
+/// ball and late contact pushes it the other way, and the further off the timing the less of
+/// the swing goes into the ball. The bat's path sets how high the ball is launched, and the
+/// ball is sent off with a Rapier impulse
+fn hit_ball(
+    mut commands: Commands<'_, '_>,
+    mut swings: EventReader<'_, '_, Swing>,
+    mut balls: Query<'_, '_, (Entity, &Transform, &Velocity, &mut Damping, &mut Ball)>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<BattingPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    for swing in swings.read() {
+        let Ok((entity, transform, velocity, mut damping, mut ball)) = balls.get_single_mut()
+        else {
+            continue;
+        };
+        if ball.swung {
+            continue;
+        }
+        ball.swung = true;
+
+        let early = time_to_plate(transform.translation, velocity.linvel);
+        if early.abs() > TIMING_WINDOW {
+            banner.show(if early > 0.0 {
+                "Too early!"
+            } else {
+                "Too late!"
+            });
+            continue;
+        }
+
+        let timing = 1.0 - early.abs() / TIMING_WINDOW;
+        let speed = (BAT_SPEED * swing.power + velocity.linvel.length() * REBOUND)
+            * (EDGE_CONTACT + (1.0 - EDGE_CONTACT) * timing);
+        let spray = early / TIMING_WINDOW * MAX_SPRAY * settings.handedness();
+        let off_bat =
+            Quat::from_rotation_y(spray) * Quat::from_rotation_x(swing.angle) * Vec3::NEG_Z;
+
+        commands.entity(entity).insert(ExternalImpulse {
+            impulse: (off_bat * speed - velocity.linvel) * BALL_MASS,
+            torque_impulse: Vec3::ZERO,
+        });
+        damping.linear_damping = AIR_DRAG;
+        next_phase.set(BattingPhase::Flight);
+    }
+}
+
+/// Swings the bat through the zone after a swing, otherwise holding it loaded over the batter's
+/// shoulder, with the bat's path tilted the way the batter has it and mirrored for left-handed
+/// batters
+fn swing_bat(
+    mut bats: Query<'_, '_, (&mut Transform, &mut Bat), Without<BatBarrel>>,
+    mut barrels: Query<'_, '_, &mut Transform, With<BatBarrel>>,
+    mut swings: EventReader<'_, '_, Swing>,
+    batter: Res<'_, Batter>,
+    settings: Res<'_, GameSettings>,
+    time: Res<'_, Time>,
+) {
+    let now = time.elapsed_secs();
+    let swung = swings.read().last().is_some();
+    let hand = settings.handedness();
+    for (mut transform, mut bat) in &mut bats {
+        if swung && bat.swung_at.is_none() {
+            bat.swung_at = Some(now);
+        }
+        let through = bat
+            .swung_at
+            .map_or(0.0, |at| ((now - at) / SWING_SECS).clamp(0.0, 1.0));
+        // Loaded, the bat points back at the catcher. Halfway through it's across the plate
+        let sweep = -FRAC_PI_2 + through * FRAC_PI_2 * 2.2;
+        transform.translation = HANDS * Vec3::new(hand, 1.0, 1.0);
+        transform.rotation =
+            Quat::from_rotation_y(hand * sweep) * Quat::from_rotation_z(-hand * batter.angle);
+    }
+    for mut barrel in &mut barrels {
+        barrel.translation = Vec3::X * hand * BAT_LENGTH / 2.0;
+    }
+}
+
+/// Watches a hit ball fly, looking back down at the machine otherwise
+fn follow_ball(
+    mut cameras: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Ball>)>,
+    balls: Query<'_, '_, &Transform, With<Ball>>,
+    phase: Res<'_, State<BattingPhase>>,
+) {
+    let Ok(mut camera) = cameras.get_single_mut() else {
+        return;
+    };
+    let watching = match (phase.get(), balls.get_single()) {
+        (BattingPhase::Flight | BattingPhase::Result, Ok(ball)) => ball.translation,
+        _ => RELEASE.with_y(1.0),
+    };
+    camera.look_at(watching, Vec3::Y);
+}
+
+/// Marks where the pitch on its way will cross the plate, when the aim guide is on
+fn draw_target(
+    mut gizmos: Gizmos<'_, '_>,
+    machine: Res<'_, PitchingMachine>,
+    phase: Res<'_, State<BattingPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() != BattingPhase::Pitching {
+        return;
+    }
+    let Some(pitch) = machine.last else {
+        return;
+    };
+    gizmos.circle(
+        Isometry3d::from_translation(PLATE + pitch.target.extend(0.0)),
+        0.06,
+        Color::srgb(1.0, 0.8, 0.1),
+    );
+}
@@ -0,0 +1,184 @@
+//! The pitching machine: winds up, then fires a ball at a random speed to a random spot over the
+//! plate
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    Ccd, Collider, ColliderMassProperties, Damping, Restitution, RigidBody, Velocity,
+};
+use serde::Serialize;
+use spjorts_core::spectator::is_playing;
+
+use crate::{
+    field::{PLATE, RELEASE},
+    phase::BattingPhase,
+};
+
+/// How long the machine winds up before each pitch, in seconds
+const WIND_UP_SECS: f32 = 2.0;
+/// Slowest pitch the machine throws, in meters per second
+const MIN_PITCH_SPEED: f32 = 30.0;
+/// Fastest pitch the machine throws, in meters per second
+const MAX_PITCH_SPEED: f32 = 42.0;
+/// Furthest either side of the middle of the plate a pitch crosses, in meters
+const MAX_PITCH_WIDTH: f32 = 0.25;
+/// Lowest a pitch crosses the plate, in meters
+const MIN_PITCH_HEIGHT: f32 = 0.55;
+/// Highest a pitch crosses the plate, in meters
+const MAX_PITCH_HEIGHT: f32 = 1.05;
+/// Radius of a baseball
+pub const BALL_RADIUS: f32 = 0.037;
+/// Mass of a baseball, in kilograms
+pub const BALL_MASS: f32 = 0.145;
+/// Acceleration balls fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+
+/// A pitch the machine throws
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Pitch {
+    /// How fast the ball leaves the machine, in meters per second
+    pub speed: f32,
+    /// Where the ball crosses the plate, across and up from the point of the plate
+    pub target: Vec2,
+}
+
+impl Pitch {
+    /// Velocity the ball leaves the machine with to drop onto its target
+    pub fn velocity(&self) -> Vec3 {
+        let target = PLATE + self.target.extend(0.0);
+        let secs = (target.z - RELEASE.z) / self.speed;
+        (target - RELEASE) / secs + Vec3::Y * GRAVITY * secs / 2.0
+    }
+}
+
+/// Seconds until a ball going a certain way crosses the plate, negative once it's past
+pub fn time_to_plate(position: Vec3, velocity: Vec3) -> f32 {
+    if velocity.z <= 0.0 {
+        return f32::NEG_INFINITY;
+    }
+    (PLATE.z - position.z) / velocity.z
+}
+
+/// A pitched ball
+#[derive(Component, Debug, Default)]
+pub struct Ball {
+    /// Whether the batter has swung at it
+    pub swung: bool,
+    /// Whether it cleared the fence on the fly
+    pub cleared_fence: bool,
+}
+
+/// The pitching machine on the mound
+#[derive(Resource, Debug)]
+pub struct PitchingMachine {
+    /// Counts down the wind up before the next pitch
+    timer: Timer,
+    /// Xorshift state the pitches are picked with
+    seed: u32,
+    /// The pitch thrown last
+    pub last: Option<Pitch>,
+}
+
+impl Default for PitchingMachine {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(WIND_UP_SECS, TimerMode::Once),
+            seed: 0x2545_F491,
+            last: None,
+        }
+    }
+}
+
+impl PitchingMachine {
+    /// A pseudo random number from 0.0 to 1.0
+    fn next(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+
+    /// Picks the next pitch's speed and where it crosses the plate
+    fn pick(&mut self) -> Pitch {
+        let speed = MIN_PITCH_SPEED + (MAX_PITCH_SPEED - MIN_PITCH_SPEED) * self.next();
+        let target = Vec2::new(
+            (self.next() * 2.0 - 1.0) * MAX_PITCH_WIDTH,
+            MIN_PITCH_HEIGHT + (MAX_PITCH_HEIGHT - MIN_PITCH_HEIGHT) * self.next(),
+        );
+        Pitch { speed, target }
+    }
+}
+
+/// Mesh and material every ball is built from
+#[derive(Resource)]
+struct BallModel {
+    /// The ball's sphere
+    mesh: Handle<Mesh>,
+    /// The ball's leather
+    material: Handle<StandardMaterial>,
+}
+
+/// Plugin that adds the pitching machine
+pub struct MachinePlugin;
+
+impl Plugin for MachinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PitchingMachine>()
+            .add_systems(Startup, setup_ball_model)
+            .add_systems(OnEnter(BattingPhase::Waiting), wind_up)
+            .add_systems(
+                Update,
+                throw_pitch
+                    .run_if(in_state(BattingPhase::Waiting))
+                    .run_if(is_playing),
+            );
+    }
+}
+
+/// Builds the mesh and material balls are spawned with
+fn setup_ball_model(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(BallModel {
+        mesh: meshes.add(Sphere::new(BALL_RADIUS)),
+        material: materials.add(Color::srgb(0.95, 0.95, 0.9)),
+    });
+}
+
+/// Starts the machine winding up for the next pitch
+fn wind_up(mut machine: ResMut<'_, PitchingMachine>) {
+    machine.timer.reset();
+}
+
+/// Fires the next pitch once the machine has wound up
+fn throw_pitch(
+    mut commands: Commands<'_, '_>,
+    mut machine: ResMut<'_, PitchingMachine>,
+    mut next_phase: ResMut<'_, NextState<BattingPhase>>,
+    model: Res<'_, BallModel>,
+    time: Res<'_, Time>,
+) {
+    if !machine.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let pitch = machine.pick();
+    machine.last = Some(pitch);
+    commands.spawn((
+        Mesh3d(model.mesh.clone()),
+        MeshMaterial3d(model.material.clone()),
+        Transform::from_translation(RELEASE),
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Mass(BALL_MASS),
+        Restitution::coefficient(0.5),
+        Ccd::enabled(),
+        Velocity::linear(pitch.velocity()),
+        // The air only starts holding the ball back once it's hit, so pitches land where the
+        // machine aims them
+        Damping::default(),
+        Ball::default(),
+    ));
+    next_phase.set(BattingPhase::Pitching);
+}
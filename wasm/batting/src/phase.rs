@@ -0,0 +1,29 @@
+//! Phases a home run derby moves through, from the machine winding up to the final results
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the derby is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BattingPhase {
+    /// The pitching machine is winding up for the next pitch
+    #[default]
+    Waiting,
+    /// A pitch is on its way to the plate
+    Pitching,
+    /// A hit ball is in the air
+    Flight,
+    /// The pitch is over and how it went is up
+    Result,
+    /// Every batter has seen every pitch and the final results are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the derby is in
+pub struct BattingPhasePlugin;
+
+impl Plugin for BattingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<BattingPhase>();
+    }
+}
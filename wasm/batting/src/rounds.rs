@@ -0,0 +1,336 @@
+//! Rounds at the plate: calling strikes, following hit balls to where they land or over the
+//! fence, and handing the bat to the next batter after their pitches, all shown on the shared
+//! scorecard HUD
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    derby::{Derby, Outcome},
+    field::{distance, is_fair, FENCE_DISTANCE, FENCE_HEIGHT, PLATE},
+    machine::{Ball, Pitch, PitchingMachine, BALL_RADIUS},
+    phase::BattingPhase,
+    Batter,
+};
+
+/// How far behind the plate a pitch has to get before it's called a strike, in meters
+const STRIKE_DEPTH: f32 = 1.5;
+/// Longest a hit ball is followed before it's called where it is, in seconds
+const MAX_FLIGHT_SECS: f32 = 10.0;
+/// How long the result of a pitch stays up before the next, in seconds
+const RESULT_SECS: f32 = 2.5;
+/// Meters per second in a kilometer per hour
+const KMH: f32 = 3.6;
+
+/// Asks for the derby to be started over from the first batter's first pitch
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts how long a hit ball has been in the air
+#[derive(Resource, Debug)]
+struct FlightClock(Timer);
+
+impl Default for FlightClock {
+    fn default() -> Self {
+        Self(Timer::from_seconds(MAX_FLIGHT_SECS, TimerMode::Once))
+    }
+}
+
+/// Counts down before the next pitch
+#[derive(Resource, Debug)]
+struct ResultPause(Timer);
+
+impl Default for ResultPause {
+    fn default() -> Self {
+        Self(Timer::from_seconds(RESULT_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct BattingSnapshot<'a> {
+    /// Batter at the plate
+    player: usize,
+    /// Every batter's pitches so far
+    derby: &'a Derby,
+    /// The pitch thrown last
+    pitch: Option<Pitch>,
+    /// Where the derby is at
+    phase: BattingPhase,
+}
+
+/// Plugin that calls each pitch and shows the derby on the scorecard HUD
+pub struct RoundsPlugin;
+
+impl Plugin for RoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Derby>()
+            .init_resource::<FlightClock>()
+            .init_resource::<ResultPause>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    call_strike.run_if(in_state(BattingPhase::Pitching)),
+                    follow_flight.run_if(in_state(BattingPhase::Flight)),
+                    next_pitch.run_if(in_state(BattingPhase::Result)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(BattingPhase::Flight), reset_flight_clock)
+            .add_systems(OnEnter(BattingPhase::Result), reset_result_pause)
+            .add_systems(
+                OnEnter(BattingPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(BattingPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh derby whenever the number of batters changes
+fn fit_match(turns: Res<'_, TurnManager>, mut derby: ResMut<'_, Derby>) {
+    if derby.players() != turns.players() {
+        *derby = Derby::new(turns.players());
+    }
+}
+
+/// Everything calling a pitch changes
+#[derive(SystemParam)]
+struct Umpire<'w> {
+    /// The derby's pitches so far
+    derby: ResMut<'w, Derby>,
+    /// Calls shown to batters
+    banner: ResMut<'w, Banner>,
+    /// Whose turn at the plate it is
+    turns: Res<'w, TurnManager>,
+    /// Where the derby goes next
+    next_phase: ResMut<'w, NextState<BattingPhase>>,
+}
+
+impl Umpire<'_> {
+    /// Records how the pitch went for the batter at the plate and calls it
+    fn call(&mut self, outcome: Outcome) {
+        self.derby.record(self.turns.current(), outcome);
+        match outcome {
+            Outcome::Strike => self.banner.show("Strike!"),
+            Outcome::Foul => self.banner.show("Foul ball"),
+            Outcome::Fair(distance) => self.banner.show(format!("{distance:.0} m")),
+            Outcome::HomeRun(distance) => self.banner.show(format!("Home run! {distance:.0} m")),
+        }
+        self.next_phase.set(BattingPhase::Result);
+    }
+}
+
+/// Calls a strike once a pitch gets past the plate without being hit
+fn call_strike(balls: Query<'_, '_, &Transform, With<Ball>>, mut umpire: Umpire<'_>) {
+    if balls
+        .iter()
+        .any(|ball| ball.translation.z > PLATE.z + STRIKE_DEPTH)
+    {
+        umpire.call(Outcome::Strike);
+    }
+}
+
+/// Starts timing a hit ball's flight
+fn reset_flight_clock(mut clock: ResMut<'_, FlightClock>) {
+    clock.0.reset();
+}
+
+/// Follows a hit ball until it lands, noting when it clears the fence and stopping it dead if
+/// it hits the fence instead
+fn follow_flight(
+    mut balls: Query<'_, '_, (&Transform, &mut Velocity, &mut Ball)>,
+    mut clock: ResMut<'_, FlightClock>,
+    mut umpire: Umpire<'_>,
+    time: Res<'_, Time>,
+) {
+    let Ok((transform, mut velocity, mut ball)) = balls.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+    let from_plate = distance(position);
+
+    if !ball.cleared_fence && from_plate >= FENCE_DISTANCE && is_fair(position) {
+        if position.y > FENCE_HEIGHT {
+            ball.cleared_fence = true;
+            umpire.banner.show("It's going, going...");
+        } else {
+            velocity.linvel = Vec3::ZERO;
+            umpire.call(Outcome::Fair(FENCE_DISTANCE));
+            return;
+        }
+    }
+
+    let landed = position.y <= BALL_RADIUS * 2.0 && velocity.linvel.y <= 0.0;
+    if !landed && !clock.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let outcome = if ball.cleared_fence {
+        Outcome::HomeRun(from_plate)
+    } else if is_fair(position) {
+        Outcome::Fair(from_plate)
+    } else {
+        Outcome::Foul
+    };
+    umpire.call(outcome);
+}
+
+/// Gives batters a moment to see how the pitch went
+fn reset_result_pause(mut pause: ResMut<'_, ResultPause>) {
+    pause.0.reset();
+}
+
+/// Clears the ball away for the next pitch, handing the bat to the next batter once they've
+/// seen all theirs and finishing the derby once everyone has batted
+fn next_pitch(
+    mut commands: Commands<'_, '_>,
+    mut pause: ResMut<'_, ResultPause>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<BattingPhase>>,
+    balls: Query<'_, '_, Entity, With<Ball>>,
+    derby: Res<'_, Derby>,
+    time: Res<'_, Time>,
+) {
+    if !pause.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for ball in &balls {
+        commands.entity(ball).despawn_recursive();
+    }
+    if derby.pitches_left(turns.current()) == 0 {
+        turns.advance();
+        if turns.round() > 0 {
+            next_phase.set(BattingPhase::GameOver);
+            return;
+        }
+    }
+    next_phase.set(BattingPhase::Waiting);
+}
+
+/// Starts the derby over from the first batter's first pitch
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut derby: ResMut<'_, Derby>,
+    mut next_phase: ResMut<'_, NextState<BattingPhase>>,
+    balls: Query<'_, '_, Entity, With<Ball>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for ball in &balls {
+        commands.entity(ball).despawn_recursive();
+    }
+    turns.restart();
+    *derby = Derby::new(turns.players());
+    next_phase.set(BattingPhase::Waiting);
+}
+
+/// Fills in the scorecard HUD with every batter's pitches, distance and home runs, the pitches
+/// left, the last pitch's speed and the bat's path
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    derby: Res<'_, Derby>,
+    turns: Res<'_, TurnManager>,
+    machine: Res<'_, PitchingMachine>,
+    batter: Res<'_, Batter>,
+) {
+    let rows = (0..derby.players())
+        .map(|player| {
+            let pitches: Vec<String> = derby.pitches(player).iter().map(Outcome::label).collect();
+            format!(
+                "Player {}: {} m, {} HR ({})",
+                player + 1,
+                derby.total(player),
+                derby.home_runs(player),
+                pitches.join(" ")
+            )
+        })
+        .collect();
+
+    let mut footer = vec![
+        format!("Pitches left: {}", derby.pitches_left(turns.current())),
+        format!("Bat angle: {:.0}°", batter.angle.to_degrees()),
+    ];
+    if let Some(pitch) = machine.last {
+        footer.push(format!("Last pitch: {:.0} km/h", pitch.speed * KMH));
+    }
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Home Run Derby, Player {} batting", turns.current() + 1),
+        rows,
+        footer: footer.join("\n"),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every batter's distance and home runs once the derby is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, derby: Res<'_, Derby>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    let leaders = derby.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if derby.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..derby.players() {
+        lines.push(format!(
+            "Player {}: {} m, {} home runs",
+            player + 1,
+            derby.total(player),
+            derby.home_runs(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new derby starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every batter's total distance back to the page once the derby is over, so it can
+/// submit them to the server
+fn submit_result(derby: Res<'_, Derby>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..derby.players())
+        .map(|player| derby.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    derby: Res<'_, Derby>,
+    machine: Res<'_, PitchingMachine>,
+    phase: Res<'_, State<BattingPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&BattingSnapshot {
+        player: turns.current(),
+        derby: &derby,
+        pitch: machine.last,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,172 @@
+//! Announcer that calls out gutterballs, strike streaks and split pickups, voicing them when its
+//! clips are available
+
+use bevy::{audio::Volume, prelude::*};
+use spjorts_core::{assets::AssetBasePath, settings::GameSettings};
+
+use crate::{setup::Gutterball, turns::Celebration};
+
+/// How long a callout stays on screen
+const CALLOUT_SECS: f32 = 2.5;
+/// Font size of callouts
+const CALLOUT_FONT_SIZE: f32 = 28.0;
+
+/// Something the announcer has a line for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Callout {
+    /// The ball dropped into a gutter
+    Gutterball,
+    /// Two strikes in a row
+    Double,
+    /// Three or more strikes in a row
+    Turkey,
+    /// A split converted for a spare
+    Pickup,
+}
+
+impl Callout {
+    /// Every callout
+    const ALL: [Self; 4] = [Self::Gutterball, Self::Double, Self::Turkey, Self::Pickup];
+
+    /// The callout for a scored throw, if the announcer has one
+    fn from_celebration(celebration: Celebration) -> Option<Self> {
+        match celebration {
+            Celebration::Double => Some(Self::Double),
+            Celebration::Turkey => Some(Self::Turkey),
+            Celebration::Pickup => Some(Self::Pickup),
+            Celebration::Strike | Celebration::Spare | Celebration::Split => None,
+        }
+    }
+
+    /// What the announcer says
+    fn line(&self) -> &'static str {
+        match self {
+            Self::Gutterball => "Right in the gutter... shake it off.",
+            Self::Double => "Back to back strikes!",
+            Self::Turkey => "A turkey! They can't miss!",
+            Self::Pickup => "What a pickup!",
+        }
+    }
+
+    /// Name of the voice clip for this callout
+    fn clip(&self) -> &'static str {
+        match self {
+            Self::Gutterball => "gutter",
+            Self::Double => "double",
+            Self::Turkey => "turkey",
+            Self::Pickup => "pickup",
+        }
+    }
+}
+
+/// Voice clips for every callout. Clips that fail to load are skipped and only the text shows
+#[derive(Resource)]
+struct AnnouncerVoice(Vec<(Callout, Handle<AudioSource>)>);
+
+impl AnnouncerVoice {
+    /// The clip for a callout
+    fn clip(&self, callout: Callout) -> Option<&Handle<AudioSource>> {
+        self.0
+            .iter()
+            .find(|(clip_for, _)| *clip_for == callout)
+            .map(|(_, handle)| handle)
+    }
+}
+
+/// The announcer's line at the bottom of the screen
+#[derive(Component)]
+struct CalloutText {
+    /// Time left before the line hides itself
+    timer: Timer,
+}
+
+/// Plugin that adds the announcer
+pub struct AnnouncerPlugin;
+
+impl Plugin for AnnouncerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_announcer)
+            .add_systems(Update, (announce, hide_callout).chain());
+    }
+}
+
+/// Loads the voice clips and spawns the hidden callout line
+fn setup_announcer(
+    mut commands: Commands<'_, '_>,
+    asset_server: Res<'_, AssetServer>,
+    base_path: Res<'_, AssetBasePath>,
+) {
+    let clips = Callout::ALL
+        .into_iter()
+        .map(|callout| {
+            let path = format!("frontend/sounds/bowling/announcer/{}.wav", callout.clip());
+            (callout, asset_server.load(base_path.join(&path)))
+        })
+        .collect();
+    commands.insert_resource(AnnouncerVoice(clips));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(CALLOUT_FONT_SIZE),
+        TextColor(Color::srgb(1.0, 0.95, 0.7)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(12.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+        CalloutText {
+            timer: Timer::from_seconds(CALLOUT_SECS, TimerMode::Once),
+        },
+    ));
+}
+
+/// Calls out gutterballs and scored throws worth a line, voicing them if the clip has loaded
+fn announce(
+    mut commands: Commands<'_, '_>,
+    mut gutterballs: EventReader<'_, '_, Gutterball>,
+    mut celebrations: EventReader<'_, '_, Celebration>,
+    mut text: Query<'_, '_, (&mut Text, &mut Visibility, &mut CalloutText)>,
+    voice: Res<'_, AnnouncerVoice>,
+    settings: Res<'_, GameSettings>,
+    asset_server: Res<'_, AssetServer>,
+) {
+    let gutter = gutterballs.read().last().map(|_| Callout::Gutterball);
+    let scored = celebrations
+        .read()
+        .filter_map(|celebration| Callout::from_celebration(*celebration))
+        .last();
+    let Some(callout) = scored.or(gutter) else {
+        return;
+    };
+
+    if let Ok((mut text, mut visibility, mut line)) = text.get_single_mut() {
+        *text = Text::new(callout.line());
+        *visibility = Visibility::Visible;
+        line.timer.reset();
+    }
+
+    if let Some(clip) = voice
+        .clip(callout)
+        .filter(|clip| asset_server.is_loaded(*clip))
+    {
+        if !settings.is_muted() {
+            commands.spawn((
+                AudioPlayer(clip.clone()),
+                PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.volume)),
+            ));
+        }
+    }
+}
+
+/// Hides the callout line once its timer runs out
+fn hide_callout(mut text: Query<'_, '_, (&mut Visibility, &mut CalloutText)>, time: Res<'_, Time>) {
+    for (mut visibility, mut line) in &mut text {
+        if line.timer.tick(time.delta()).just_finished() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Bowling sound effects
+
+use bevy::{audio::Volume, prelude::*};
+use bevy_rapier3d::prelude::ContactForceEvent;
+use spjorts_core::{assets::AssetBasePath, settings::GameSettings};
+
+use crate::phase::BowlingPhase;
+
+/// Contact force at which a pin impact plays at full volume
+const PIN_IMPACT_FULL_FORCE: f32 = 400.0;
+
+/// A one-shot sound effect
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum BowlingSound {
+    /// The ball left the player's hand
+    Release,
+    /// A pin was hit, with a volume from 0.0 to 1.0 based on how hard
+    PinImpact(f32),
+    /// The ball dropped into a gutter
+    Gutter,
+    /// Every pin went down on the first throw
+    Strike,
+    /// The last player finished their 10th frame
+    GameOver,
+}
+
+/// Handles to every bowling sound
+#[derive(Resource)]
+struct BowlingSounds {
+    /// Ball release thud
+    release: Handle<AudioSource>,
+    /// Looping roll down the lane
+    rolling: Handle<AudioSource>,
+    /// Pin clack
+    pin: Handle<AudioSource>,
+    /// Gutterball
+    gutter: Handle<AudioSource>,
+    /// Strike crash
+    strike: Handle<AudioSource>,
+    /// Game over jingle
+    game_over: Handle<AudioSource>,
+}
+
+/// Marks the looping rolling sound while a ball is on the lane
+#[derive(Component)]
+struct RollingSound;
+
+/// Plugin that loads and plays bowling sound effects
+pub struct BowlingAudioPlugin;
+
+impl Plugin for BowlingAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BowlingSound>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(OnEnter(BowlingPhase::Rolling), start_rolling_sound)
+            .add_systems(OnExit(BowlingPhase::Rolling), stop_rolling_sound)
+            .add_systems(Update, (queue_pin_impacts, play_sounds).chain());
+    }
+}
+
+/// Loads every bowling sound through the shared asset path
+fn load_sounds(
+    mut commands: Commands<'_, '_>,
+    asset_server: Res<'_, AssetServer>,
+    base_path: Res<'_, AssetBasePath>,
+) {
+    let load = |name: &str| {
+        asset_server.load(base_path.join(&format!("frontend/sounds/bowling/{name}.wav")))
+    };
+
+    commands.insert_resource(BowlingSounds {
+        release: load("release"),
+        rolling: load("rolling"),
+        pin: load("pin"),
+        gutter: load("gutter"),
+        strike: load("strike"),
+        game_over: load("game_over"),
+    });
+}
+
+/// Plays the release sound and starts the rolling loop when the ball is let go
+fn start_rolling_sound(
+    mut commands: Commands<'_, '_>,
+    sounds: Res<'_, BowlingSounds>,
+    settings: Res<'_, GameSettings>,
+    mut events: EventWriter<'_, BowlingSound>,
+) {
+    events.send(BowlingSound::Release);
+    commands.spawn((
+        AudioPlayer(sounds.rolling.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::new(settings.volume)),
+        RollingSound,
+    ));
+}
+
+/// Stops the rolling loop once the ball is off the lane
+fn stop_rolling_sound(
+    mut commands: Commands<'_, '_>,
+    rolling: Query<'_, '_, Entity, With<RollingSound>>,
+) {
+    for entity in &rolling {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Turns the hardest pin contact this frame into an impact sound
+fn queue_pin_impacts(
+    mut contacts: EventReader<'_, '_, ContactForceEvent>,
+    mut events: EventWriter<'_, BowlingSound>,
+) {
+    let hardest = contacts
+        .read()
+        .map(|contact| contact.total_force_magnitude)
+        .fold(0.0, f32::max);
+
+    if hardest > 0.0 {
+        events.send(BowlingSound::PinImpact(
+            (hardest / PIN_IMPACT_FULL_FORCE).clamp(0.1, 1.0),
+        ));
+    }
+}
+
+/// Plays queued one-shot sounds at the player's volume
+fn play_sounds(
+    mut commands: Commands<'_, '_>,
+    mut events: EventReader<'_, '_, BowlingSound>,
+    sounds: Res<'_, BowlingSounds>,
+    settings: Res<'_, GameSettings>,
+) {
+    for sound in events.read() {
+        if settings.is_muted() {
+            continue;
+        }
+
+        let (handle, scale) = match sound {
+            BowlingSound::Release => (&sounds.release, 1.0),
+            BowlingSound::PinImpact(scale) => (&sounds.pin, *scale),
+            BowlingSound::Gutter => (&sounds.gutter, 1.0),
+            BowlingSound::Strike => (&sounds.strike, 1.0),
+            BowlingSound::GameOver => (&sounds.game_over, 1.0),
+        };
+
+        commands.spawn((
+            AudioPlayer(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.volume * scale)),
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+//! Computer opponent that bowls its own turns through the regular input handling
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use crossbeam_channel::{Receiver, Sender};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    players::{Jitter, PlayerRegistry, MAX_BOT_SKILL},
+    settings::GameSettings,
+    spectator::is_playing,
+    swing::SAMPLE_WINDOW,
+    ActionReader, Communication,
+};
+
+use crate::{
+    phase::BowlingPhase,
+    setup::{
+        ball::{HOOK_SCALE, MAX_SPEED, MIN_SPEED, SPEED_SCALE},
+        Ball, LANE_WIDTH,
+    },
+    turns::BowlingStateWrapper,
+    PHYSICS_STEP_SECS,
+};
+
+/// How long the bot waits before lining up and before swinging
+const THINK_SECS: f32 = 0.75;
+/// How close the sliding ball has to be to the bot's mark before it stops it
+const AIM_TOLERANCE: f32 = 0.05;
+/// Pitch the bot starts its swing down from
+const BACKSWING_PITCH: f32 = 0.6;
+
+/// Where the bot is in its turn
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum BotStep {
+    /// Not the bot's turn, or waiting for the lane to be ready to aim
+    #[default]
+    Waiting,
+    /// Thinking before lining up its shot
+    Thinking,
+    /// Waiting for the sliding ball to reach its mark
+    Aiming {
+        /// Where on the lane the bot wants to release from
+        target_x: f32,
+    },
+    /// Pausing before swinging
+    Settling,
+    /// Swung and released, waiting for the throw to be scored
+    Thrown,
+}
+
+/// Computer opponent state and the channel its input is fed through
+#[derive(Resource)]
+pub struct BowlingBot {
+    /// Write half of the bot's input channel
+    sender: Sender<Communication>,
+    /// Read half, drained by `handle_input` while the bot has the turn
+    receiver: Receiver<Communication>,
+    /// Whether the bot has the current turn
+    has_turn: bool,
+    /// Where the bot is in its turn
+    step: BotStep,
+    /// Delay before the bot's next step
+    timer: Timer,
+    /// Where the bot's mistakes come from
+    jitter: Jitter,
+}
+
+impl Default for BowlingBot {
+    fn default() -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        Self {
+            sender,
+            receiver,
+            has_turn: false,
+            step: BotStep::Waiting,
+            timer: Timer::from_seconds(THINK_SECS, TimerMode::Once),
+            jitter: Jitter::default(),
+        }
+    }
+}
+
+impl BowlingBot {
+    /// Whether the bot has the current turn, so controller input should be ignored
+    pub fn has_turn(&self) -> bool {
+        self.has_turn
+    }
+
+    /// Takes the bot's next pending input message, if there is one
+    pub fn next_message(&self) -> Option<Communication> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Queues an input message as if it came from a controller
+    fn send(&self, msg: Communication) {
+        let _ = self.sender.send(msg);
+    }
+
+    /// Switches to a step, restarting the delay before the next one
+    fn enter(&mut self, step: BotStep) {
+        self.step = step;
+        self.timer.reset();
+    }
+}
+
+/// Input `handle_input` reads: the controllers, plus the bot on its turn
+#[derive(SystemParam)]
+pub struct BowlingInput<'w> {
+    /// Controller input from JavaScript
+    read: Res<'w, ActionReader>,
+    /// The computer opponent
+    bot: Res<'w, BowlingBot>,
+    /// Clock input is stamped with as it's read
+    time: Res<'w, Time>,
+}
+
+impl BowlingInput<'_> {
+    /// Seconds since startup, for stamping the messages read this frame
+    pub fn now(&self) -> f32 {
+        self.time.elapsed_secs()
+    }
+
+    /// Takes the next pending controller message and, on the bot's turn, the bot's next message.
+    /// Controllers can't touch the ball while the bot has the turn
+    pub fn next_messages(&self) -> impl Iterator<Item = Communication> {
+        let bot_turn = self.bot.has_turn();
+        let controller = self.read.0.try_recv().ok().filter(|msg| {
+            !(bot_turn
+                && matches!(
+                    msg,
+                    JsMessage::ButtonA
+                        | JsMessage::ButtonB
+                        | JsMessage::ReleaseA
+                        | JsMessage::ReleaseB
+                        | JsMessage::Rotate(_)
+                        | JsMessage::TimedRotate(..)
+                ))
+        });
+        let bot = if bot_turn {
+            self.bot.next_message()
+        } else {
+            None
+        };
+
+        controller.into_iter().chain(bot)
+    }
+}
+
+/// Plugin that lets a computer opponent take the last turn when one is registered
+pub struct BowlingBotPlugin;
+
+impl Plugin for BowlingBotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BowlingBot>()
+            .add_systems(Update, drive_bot.run_if(is_playing));
+    }
+}
+
+/// Lines up, swings and releases the ball on the bot's turn, missing more at lower skill levels
+fn drive_bot(
+    mut bot: ResMut<'_, BowlingBot>,
+    ball: Query<'_, '_, (&Transform, &Ball)>,
+    registry: Res<'_, PlayerRegistry>,
+    state: Res<'_, BowlingStateWrapper>,
+    phase: Res<'_, State<BowlingPhase>>,
+    settings: Res<'_, GameSettings>,
+    time: Res<'_, Time>,
+) {
+    bot.has_turn =
+        registry.bot().is_some() && state.get_turn() == registry.count() && !state.is_game_over();
+    let (Some(skill), Ok((transform, ball))) = (registry.bot(), ball.get_single()) else {
+        return;
+    };
+    // How far off the bot's throws can be, from 0.0 for a perfect bot to 1.0
+    let error = 1.0 - f32::from(skill) / f32::from(MAX_BOT_SKILL);
+
+    if !bot.has_turn {
+        bot.step = BotStep::Waiting;
+        return;
+    }
+
+    let ready = bot.timer.tick(time.delta()).finished();
+    match bot.step {
+        BotStep::Waiting => {
+            if *phase.get() == BowlingPhase::Aiming {
+                bot.enter(BotStep::Thinking);
+            }
+        }
+        BotStep::Thinking => {
+            if ready {
+                let target_x = bot.jitter.sample() * error * LANE_WIDTH * 0.3;
+                bot.enter(BotStep::Aiming { target_x });
+            }
+        }
+        BotStep::Aiming { target_x } => {
+            if ball.moving.is_some() && (transform.translation.x - target_x).abs() < AIM_TOLERANCE {
+                bot.send(JsMessage::ButtonB);
+                bot.enter(BotStep::Settling);
+            }
+        }
+        BotStep::Settling => {
+            if ready {
+                let speed = (MIN_SPEED + (MAX_SPEED - MIN_SPEED) * 0.65)
+                    * (1.0 + bot.jitter.sample() * error * 0.3);
+                let hook = bot.jitter.sample() * error * 1.5;
+                for orientation in swing(speed, hook * settings.handedness()) {
+                    bot.send(JsMessage::Rotate(unapply_settings(&settings, orientation)));
+                }
+                bot.send(JsMessage::ButtonA);
+                bot.enter(BotStep::Thrown);
+            }
+        }
+        BotStep::Thrown => {
+            if *phase.get() == BowlingPhase::Resetting {
+                bot.enter(BotStep::Waiting);
+            }
+        }
+    }
+}
+
+/// Orientation readings for a steady swing down from the backswing that `Ball::get_speed` and
+/// `Ball::get_hook` read back as the given release speed and hook. Readings are taken one per
+/// physics step, so the swing is planned around the step time and spans the whole sample window.
+/// The wrist roll is part of the swing's rotation, so the hook is capped at what the speed leaves
+/// room for. The wrist roll ends straight so the launch direction isn't turned
+fn swing(speed: f32, hook: f32) -> Vec<Orientation> {
+    let steps = (SAMPLE_WINDOW / PHYSICS_STEP_SECS).ceil() as usize + 1;
+    let angle = speed.clamp(MIN_SPEED, MAX_SPEED) * PHYSICS_STEP_SECS / SPEED_SCALE;
+    let yaw = (hook * PHYSICS_STEP_SECS / HOOK_SCALE).clamp(-angle, angle);
+    let pitch = (angle * angle - yaw * yaw).max(0.0).sqrt();
+
+    (0..=steps)
+        .map(|step| {
+            let step = step as f32;
+            Orientation::new(
+                BACKSWING_PITCH - pitch * step,
+                0.0,
+                yaw * (step - steps as f32),
+            )
+        })
+        .collect()
+}
+
+/// Undoes the player's sensitivity and axis inversion so the bot's readings land as intended
+fn unapply_settings(settings: &GameSettings, orientation: Orientation) -> Orientation {
+    let sensitivity = if settings.sensitivity.abs() > f32::EPSILON {
+        settings.sensitivity
+    } else {
+        1.0
+    };
+    let pitch = if settings.invert_y {
+        -orientation.pitch
+    } else {
+        orientation.pitch
+    };
+
+    Orientation::new(
+        pitch / sensitivity,
+        orientation.roll / sensitivity,
+        orientation.yaw / sensitivity,
+    )
+}
@@ -0,0 +1,172 @@
+//! Animated overlays for strikes, spares, strike streaks and splits
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::turns::Celebration;
+
+/// How long a celebration stays on screen, short enough to finish before the pinsetter does
+const CELEBRATION_SECS: f32 = 1.8;
+/// Font size the banner text settles at
+const BANNER_FONT_SIZE: f32 = 72.0;
+/// How many confetti pieces burst out for a strike
+const CONFETTI_COUNT: usize = 24;
+/// Confetti colors, cycled through piece by piece
+const CONFETTI_COLORS: [Color; 4] = [
+    Color::srgb(1.0, 0.85, 0.1),
+    Color::srgb(0.9, 0.15, 0.2),
+    Color::srgb(0.2, 0.6, 1.0),
+    Color::srgb(0.3, 0.9, 0.4),
+];
+
+/// Root of a celebration overlay, despawned along with its children once its timer runs out
+#[derive(Component)]
+pub struct CelebrationOverlay {
+    /// Time left on screen
+    timer: Timer,
+}
+
+/// The overlay's scaling banner text
+#[derive(Component)]
+struct CelebrationText;
+
+/// A single piece of confetti flying away from the banner
+#[derive(Component)]
+struct Confetti {
+    /// Screen space velocity in pixels per second
+    velocity: Vec2,
+    /// Current offset from the center of the screen in pixels
+    offset: Vec2,
+}
+
+/// Plugin that shows celebration overlays after notable throws
+pub struct CelebrationPlugin;
+
+impl Plugin for CelebrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Celebration>().add_systems(
+            Update,
+            (spawn_celebrations, animate_celebrations, animate_confetti).chain(),
+        );
+    }
+}
+
+/// Spawns an overlay for every celebration, replacing any still on screen
+fn spawn_celebrations(
+    mut commands: Commands<'_, '_>,
+    mut celebrations: EventReader<'_, '_, Celebration>,
+    overlays: Query<'_, '_, Entity, With<CelebrationOverlay>>,
+) {
+    let Some(&celebration) = celebrations.read().last() else {
+        return;
+    };
+
+    for overlay in &overlays {
+        commands.entity(overlay).despawn_recursive();
+    }
+
+    let pieces = match celebration {
+        Celebration::Spare | Celebration::Split => 0,
+        Celebration::Strike | Celebration::Pickup => CONFETTI_COUNT / 2,
+        Celebration::Double | Celebration::Turkey => CONFETTI_COUNT,
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            CelebrationOverlay {
+                timer: Timer::from_seconds(CELEBRATION_SECS, TimerMode::Once),
+            },
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                Text::new(celebration.message()),
+                TextFont::from_font_size(BANNER_FONT_SIZE),
+                TextColor(Color::srgb(1.0, 0.85, 0.1)),
+                TextLayout::new_with_justify(JustifyText::Center),
+                CelebrationText,
+            ));
+
+            for idx in 0..pieces {
+                let angle = idx as f32 / pieces as f32 * TAU;
+                let speed = 250.0 + 100.0 * (idx % 3) as f32;
+                overlay.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(50.0),
+                        top: Val::Percent(50.0),
+                        width: Val::Px(10.0),
+                        height: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(CONFETTI_COLORS[idx % CONFETTI_COLORS.len()]),
+                    Confetti {
+                        velocity: Vec2::from_angle(angle) * speed,
+                        offset: Vec2::ZERO,
+                    },
+                ));
+            }
+        });
+}
+
+/// Pops the banner text in, fades it out, and despawns overlays whose time is up
+fn animate_celebrations(
+    mut commands: Commands<'_, '_>,
+    mut overlays: Query<'_, '_, (Entity, &mut CelebrationOverlay)>,
+    mut texts: Query<'_, '_, (&mut TextFont, &mut TextColor), With<CelebrationText>>,
+    time: Res<'_, Time>,
+) {
+    for (entity, mut overlay) in &mut overlays {
+        if overlay.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let progress = overlay.timer.fraction();
+        // Overshoot slightly while popping in, then hold before fading over the last third
+        let scale = if progress < 0.15 {
+            progress / 0.15 * 1.2
+        } else if progress < 0.25 {
+            1.2 - (progress - 0.15) / 0.1 * 0.2
+        } else {
+            1.0
+        };
+        let alpha = ((1.0 - progress) * 3.0).min(1.0);
+
+        for (mut font, mut color) in &mut texts {
+            font.font_size = BANNER_FONT_SIZE * scale.max(0.01);
+            color.0.set_alpha(alpha);
+        }
+    }
+}
+
+/// Flies confetti outwards, slowing it down and fading it as it goes
+fn animate_confetti(
+    mut confetti: Query<'_, '_, (&mut Confetti, &mut Node, &mut BackgroundColor)>,
+    time: Res<'_, Time>,
+) {
+    let delta = time.delta_secs();
+    for (mut piece, mut node, mut color) in &mut confetti {
+        piece.velocity *= 1.0 - 1.5 * delta;
+        piece.velocity.y += 300.0 * delta;
+        let step = piece.velocity * delta;
+        piece.offset += step;
+
+        node.margin = UiRect {
+            left: Val::Px(piece.offset.x),
+            top: Val::Px(piece.offset.y),
+            ..default()
+        };
+
+        let alpha = color.0.alpha();
+        color.0.set_alpha((alpha - 0.6 * delta).max(0.0));
+    }
+}
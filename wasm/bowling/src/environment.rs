@@ -0,0 +1,423 @@
+//! Scenery around the lane, picked before the game starts
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::menu::{Menu, MenuSelected};
+
+use crate::{
+    practice::GameMode,
+    setup::{
+        pin::pin_mesh, GUTTER_WIDTH, LANE_LENGTH, LANE_START_Z, LANE_WIDTH, PIN_HEIGHT, PIN_RADIUS,
+        PIN_START_Z,
+    },
+    variant::spawn_variant_menu,
+};
+
+/// Font size of the environment menu's options
+const MENU_FONT_SIZE: f32 = 36.0;
+/// Color of the highlighted environment
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+/// Distance between the centres of neighbouring lanes
+const LANE_PITCH: f32 = LANE_WIDTH + GUTTER_WIDTH * 2.0 + 0.4;
+/// How many neighbouring lanes sit on each side of the player's lane
+const NEIGHBOR_LANES: usize = 2;
+/// Where the back wall stands, just behind the pin deck
+const BACK_WALL_Z: f32 = PIN_START_Z + 6.0;
+/// Middle of the lane along its length
+const LANE_MID_Z: f32 = LANE_START_Z + LANE_LENGTH * 0.5;
+
+/// Scenery and lighting surrounding the lane
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaneEnvironment {
+    /// A bowling alley with neighbouring lanes under warm ceiling lights
+    #[default]
+    Classic,
+    /// A dark glow-bowl lit by neon
+    Cosmic,
+    /// A lane out on a seaside boardwalk in the sun
+    Boardwalk,
+}
+
+impl LaneEnvironment {
+    /// Every environment, in menu order
+    const ALL: [Self; 3] = [Self::Classic, Self::Cosmic, Self::Boardwalk];
+
+    /// Name shown in the environment menu
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic Alley",
+            Self::Cosmic => "Cosmic Bowl",
+            Self::Boardwalk => "Boardwalk",
+        }
+    }
+
+    /// Color of the sky, or of the void past the scenery indoors
+    fn sky(&self) -> Color {
+        match self {
+            Self::Classic => Color::srgb(0.12, 0.08, 0.06),
+            Self::Cosmic => Color::srgb(0.02, 0.0, 0.06),
+            Self::Boardwalk => Color::srgb(0.53, 0.78, 0.95),
+        }
+    }
+
+    /// Light that reaches everything, however it's facing
+    fn ambient(&self) -> AmbientLight {
+        let (color, brightness) = match self {
+            Self::Classic => (Color::srgb(1.0, 0.9, 0.75), 300.0),
+            Self::Cosmic => (Color::srgb(0.4, 0.2, 1.0), 80.0),
+            Self::Boardwalk => (Color::srgb(0.85, 0.92, 1.0), 600.0),
+        };
+        AmbientLight { color, brightness }
+    }
+}
+
+/// Marks everything spawned for the current environment, cleared when it changes
+#[derive(Component)]
+struct EnvironmentProp;
+
+/// Marks the environment menu
+#[derive(Component)]
+struct EnvironmentMenu;
+
+/// An entry in the environment menu
+#[derive(Component)]
+struct EnvironmentOption(usize);
+
+/// Plugin that adds the environment menu and builds the picked environment's scenery
+pub struct EnvironmentPlugin;
+
+impl Plugin for EnvironmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LaneEnvironment>().add_systems(
+            Update,
+            (
+                highlight_environment_menu,
+                choose_environment,
+                apply_environment,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Spawns the environment menu, focused so it takes input straight away
+pub fn spawn_environment_menu(commands: &mut Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Menu::new(LaneEnvironment::ALL.len()),
+            EnvironmentMenu,
+        ))
+        .with_children(|menu| {
+            for (idx, environment) in LaneEnvironment::ALL.iter().enumerate() {
+                menu.spawn((
+                    Text::new(environment.name()),
+                    TextFont::from_font_size(MENU_FONT_SIZE),
+                    TextColor::WHITE,
+                    EnvironmentOption(idx),
+                ));
+            }
+        });
+}
+
+/// Colors the highlighted environment
+fn highlight_environment_menu(
+    menus: Query<'_, '_, &Menu, (With<EnvironmentMenu>, Changed<Menu>)>,
+    mut options: Query<'_, '_, (&EnvironmentOption, &mut TextColor)>,
+) {
+    for menu in &menus {
+        for (option, mut color) in &mut options {
+            color.0 = if option.0 == menu.selected {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+/// Sets the environment once one is picked and closes the environment menu, moving on to the
+/// variant menu for standard games
+fn choose_environment(
+    mut commands: Commands<'_, '_>,
+    mut selections: EventReader<'_, '_, MenuSelected>,
+    menus: Query<'_, '_, (), With<EnvironmentMenu>>,
+    mut environment: ResMut<'_, LaneEnvironment>,
+    mode: Res<'_, GameMode>,
+) {
+    for selection in selections.read() {
+        if menus.get(selection.menu).is_err() {
+            continue;
+        }
+
+        environment.set_if_neq(LaneEnvironment::ALL[selection.index]);
+        commands.entity(selection.menu).despawn_recursive();
+
+        if *mode == GameMode::Standard {
+            spawn_variant_menu(&mut commands);
+        }
+    }
+}
+
+/// Swaps out the scenery, lights and sky whenever the environment changes, including the first
+/// frame so the default environment is built
+fn apply_environment(
+    mut commands: Commands<'_, '_>,
+    environment: Res<'_, LaneEnvironment>,
+    props: Query<'_, '_, Entity, With<EnvironmentProp>>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    if !environment.is_changed() {
+        return;
+    }
+
+    for prop in &props {
+        commands.entity(prop).despawn_recursive();
+    }
+
+    commands.insert_resource(ClearColor(environment.sky()));
+    commands.insert_resource(environment.ambient());
+
+    let mut scenery = Scenery {
+        commands: &mut commands,
+        meshes: &mut meshes,
+        materials: &mut materials,
+    };
+    match *environment {
+        LaneEnvironment::Classic => scenery.classic(),
+        LaneEnvironment::Cosmic => scenery.cosmic(),
+        LaneEnvironment::Boardwalk => scenery.boardwalk(),
+    }
+}
+
+/// Spawns environment props
+struct Scenery<'a, 'w, 's> {
+    /// Where props are spawned
+    commands: &'a mut Commands<'w, 's>,
+    /// Prop meshes
+    meshes: &'a mut Assets<Mesh>,
+    /// Prop materials
+    materials: &'a mut Assets<StandardMaterial>,
+}
+
+impl Scenery<'_, '_, '_> {
+    /// Spawns a box prop
+    fn block(&mut self, size: Vec3, at: Vec3, material: &Handle<StandardMaterial>) {
+        self.commands.spawn((
+            Mesh3d(self.meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(at),
+            EnvironmentProp,
+        ));
+    }
+
+    /// Spawns a point light
+    fn light(&mut self, color: Color, intensity: f32, at: Vec3) {
+        self.commands.spawn((
+            PointLight {
+                color,
+                intensity,
+                range: 30.0,
+                ..default()
+            },
+            Transform::from_translation(at),
+            EnvironmentProp,
+        ));
+    }
+
+    /// Spawns a decorative lane centred at `x`, with a rack of pins at the end that can't be hit
+    fn neighbor_lane(
+        &mut self,
+        x: f32,
+        lane: &Handle<StandardMaterial>,
+        pin: &Handle<StandardMaterial>,
+    ) {
+        self.block(
+            Vec3::new(LANE_WIDTH, 0.1, LANE_LENGTH),
+            Vec3::new(x, -0.05, LANE_MID_Z),
+            lane,
+        );
+
+        let pin_mesh = self.meshes.add(pin_mesh(PIN_RADIUS, PIN_HEIGHT));
+        for row in 1..=4 {
+            let start = -((row - 1) as f32 / 2.0) * PIN_RADIUS * 4.0;
+            for idx in 0..row {
+                self.commands.spawn((
+                    Mesh3d(pin_mesh.clone()),
+                    MeshMaterial3d(pin.clone()),
+                    Transform::from_xyz(
+                        x + start + idx as f32 * PIN_RADIUS * 4.0,
+                        PIN_HEIGHT * 0.5,
+                        PIN_START_Z + row as f32,
+                    ),
+                    EnvironmentProp,
+                ));
+            }
+        }
+    }
+
+    /// Neighbouring lanes and ball returns under warm ceiling lights
+    fn classic(&mut self) {
+        let lane = self.materials.add(StandardMaterial {
+            base_color: Color::hsl(44.0, 0.31, 0.71),
+            perceptual_roughness: 0.1,
+            ..default()
+        });
+        let pin = self.materials.add(Color::WHITE);
+        let divider = self.materials.add(Color::srgb(0.25, 0.25, 0.28));
+        let wall = self.materials.add(Color::srgb(0.35, 0.12, 0.1));
+
+        for side in [-1.0, 1.0] {
+            for n in 1..=NEIGHBOR_LANES {
+                let x = side * n as f32 * LANE_PITCH;
+                self.neighbor_lane(x, &lane, &pin);
+                self.block(
+                    Vec3::new(0.3, 0.4, LANE_LENGTH * 0.3),
+                    Vec3::new(x - side * LANE_PITCH * 0.5, 0.2, LANE_START_Z + 2.0),
+                    &divider,
+                );
+            }
+        }
+
+        let span = LANE_PITCH * (NEIGHBOR_LANES * 2 + 1) as f32;
+        self.block(
+            Vec3::new(span, 4.0, 0.2),
+            Vec3::new(0.0, 2.0, BACK_WALL_Z),
+            &wall,
+        );
+
+        for z in [LANE_START_Z + 5.0, LANE_MID_Z, PIN_START_Z] {
+            self.light(
+                Color::srgb(1.0, 0.85, 0.6),
+                400_000.0,
+                Vec3::new(0.0, 5.0, z),
+            );
+        }
+    }
+
+    /// Glowing strips along the gutters and rings over the pin deck in the dark
+    fn cosmic(&mut self) {
+        let pink = self.materials.add(StandardMaterial {
+            base_color: Color::BLACK,
+            emissive: LinearRgba::rgb(4.0, 0.2, 2.5),
+            ..default()
+        });
+        let cyan = self.materials.add(StandardMaterial {
+            base_color: Color::BLACK,
+            emissive: LinearRgba::rgb(0.2, 3.0, 4.0),
+            ..default()
+        });
+        let wall = self.materials.add(Color::srgb(0.03, 0.02, 0.08));
+
+        for side in [-1.0, 1.0] {
+            let x = side * (LANE_WIDTH * 0.5 + GUTTER_WIDTH + 0.15);
+            let strip = if side < 0.0 { &pink } else { &cyan };
+            self.block(
+                Vec3::new(0.05, 0.05, LANE_LENGTH),
+                Vec3::new(x, 0.05, LANE_MID_Z),
+                strip,
+            );
+        }
+
+        let span = LANE_PITCH * (NEIGHBOR_LANES * 2 + 1) as f32;
+        self.block(
+            Vec3::new(span, 4.0, 0.2),
+            Vec3::new(0.0, 2.0, BACK_WALL_Z),
+            &wall,
+        );
+
+        for (idx, radius) in [1.2, 1.8, 2.4].into_iter().enumerate() {
+            self.commands.spawn((
+                Mesh3d(self.meshes.add(Torus::new(radius - 0.05, radius))),
+                MeshMaterial3d(if idx % 2 == 0 {
+                    pink.clone()
+                } else {
+                    cyan.clone()
+                }),
+                Transform::from_xyz(0.0, 2.0, BACK_WALL_Z - 0.2)
+                    .with_rotation(Quat::from_rotation_x(PI / 2.0)),
+                EnvironmentProp,
+            ));
+        }
+
+        self.light(
+            Color::srgb(0.8, 0.2, 1.0),
+            300_000.0,
+            Vec3::new(0.0, 4.0, PIN_START_Z),
+        );
+        self.light(
+            Color::srgb(0.2, 0.6, 1.0),
+            200_000.0,
+            Vec3::new(0.0, 4.0, LANE_START_Z + 5.0),
+        );
+    }
+
+    /// Planks, railings and sea under the sun
+    fn boardwalk(&mut self) {
+        let planks = self.materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.4, 0.25),
+            perceptual_roughness: 0.9,
+            ..default()
+        });
+        let rail = self.materials.add(Color::srgb(0.95, 0.95, 0.9));
+        let sea = self.materials.add(StandardMaterial {
+            base_color: Color::srgb(0.1, 0.35, 0.6),
+            perceptual_roughness: 0.2,
+            ..default()
+        });
+
+        let deck_width = LANE_PITCH * 3.0;
+        self.block(
+            Vec3::new(deck_width, 0.2, LANE_LENGTH + 10.0),
+            Vec3::new(0.0, -GUTTER_WIDTH - 0.2, LANE_MID_Z),
+            &planks,
+        );
+        self.commands.spawn((
+            Mesh3d(
+                self.meshes
+                    .add(Plane3d::default().mesh().size(400.0, 400.0)),
+            ),
+            MeshMaterial3d(sea),
+            Transform::from_xyz(0.0, -3.0, LANE_MID_Z),
+            EnvironmentProp,
+        ));
+
+        for side in [-1.0, 1.0] {
+            let x = side * deck_width * 0.5;
+            self.block(
+                Vec3::new(0.1, 0.1, LANE_LENGTH + 10.0),
+                Vec3::new(x, 0.6, LANE_MID_Z),
+                &rail,
+            );
+            for post in 0..=8 {
+                let z = LANE_START_Z - 5.0 + post as f32 * (LANE_LENGTH + 10.0) / 8.0;
+                self.block(Vec3::new(0.12, 1.2, 0.12), Vec3::new(x, 0.0, z), &rail);
+            }
+        }
+
+        self.commands.spawn((
+            DirectionalLight {
+                color: Color::srgb(1.0, 0.95, 0.85),
+                illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+                shadows_enabled: true,
+                ..default()
+            },
+            Transform::from_xyz(5.0, 10.0, 0.0)
+                .looking_at(Vec3::new(0.0, 0.0, LANE_MID_Z), Vec3::Y),
+            EnvironmentProp,
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+//! Controller rumble for the moments a throw should be felt, forwarded to the controller by the page
+
+use bevy::prelude::*;
+use spjorts_core::{communication::GameEvent, spectator::is_playing, FeedbackSender};
+
+use crate::audio::BowlingSound;
+
+/// Shortest gap between pin impact rumbles, so a clattering deck buzzes rather than drones
+const PIN_RUMBLE_GAP_SECS: f32 = 0.15;
+
+/// Plugin that turns the moments of a throw into controller rumbles
+pub struct HapticsPlugin;
+
+impl Plugin for HapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, send_rumbles.run_if(is_playing));
+    }
+}
+
+/// Rumble `(intensity, millis)` felt for a sound cue, if it has one
+fn rumble_for(sound: BowlingSound) -> Option<(u8, u16)> {
+    match sound {
+        BowlingSound::Release => Some((160, 80)),
+        BowlingSound::PinImpact(scale) => Some(((scale * 200.0) as u8, 40)),
+        BowlingSound::Gutter => Some((90, 250)),
+        BowlingSound::Strike => Some((255, 400)),
+        BowlingSound::GameOver => None,
+    }
+}
+
+/// Sends a rumble for every release, strike and gutterball, and for pin impacts spaced out by
+/// [`PIN_RUMBLE_GAP_SECS`]
+fn send_rumbles(
+    mut sounds: EventReader<'_, '_, BowlingSound>,
+    feedback: Res<'_, FeedbackSender>,
+    time: Res<'_, Time>,
+    mut last_pin_rumble: Local<'_, Option<f32>>,
+) {
+    let now = time.elapsed_secs();
+    for sound in sounds.read() {
+        if let BowlingSound::PinImpact(_) = sound {
+            if last_pin_rumble.is_some_and(|at| now - at < PIN_RUMBLE_GAP_SECS) {
+                continue;
+            }
+            *last_pin_rumble = Some(now);
+        }
+
+        if let Some((intensity, millis)) = rumble_for(*sound) {
+            feedback.send(GameEvent::Rumble { intensity, millis });
+        }
+    }
+}
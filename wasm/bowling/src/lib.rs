@@ -1,59 +1,140 @@
 //! Bevy bowling game
 
-use bevy::{asset::AssetMetaCheck, prelude::*};
+use announcer::AnnouncerPlugin;
+use audio::{BowlingAudioPlugin, BowlingSound};
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
 use bevy_rapier3d::{
-    plugin::{NoUserData, RapierPhysicsPlugin},
-    prelude::{RigidBody, Velocity},
+    plugin::{NoUserData, RapierPhysicsPlugin, TimestepMode},
+    prelude::{
+        Collider, ColliderMassProperties, CollisionEvent, ContactForceEvent, ExternalForce,
+        Friction, RigidBody, Sleeping, Velocity,
+    },
 };
-use crossbeam_channel::Sender;
-use setup::{setup, Ball, Pin, Scorecard, BALL_START_Z, LANE_WIDTH};
-use spjorts_core::{communication::JsMessage, ActionReader, ActionSender, Communication};
-use turns::{BowlingStateWrapper, BowlingTurnPlugin};
-use wasm_bindgen::prelude::wasm_bindgen;
+use bot::{BowlingBotPlugin, BowlingInput};
+use celebration::CelebrationPlugin;
+use environment::EnvironmentPlugin;
+use haptics::HapticsPlugin;
+use phase::{BowlingPhase, BowlingPhasePlugin};
+use pinsetter::{run_pinsetter, Pinsetter, PinsetterPlugin};
+use power::PowerSavingPlugin;
+use practice::{PracticeEditor, PracticePlugin};
+use rematch::{Rematch, RematchPlugin};
+use replay::ReplayPlugin;
+use save::SavePlugin;
+use scoreboard::ScoreboardPlugin;
+use setup::{
+    oil::DRY_MULTIPLIER, setup, Ball, BallPicker, BallSpec, FingerHole, Gutter, Gutterball,
+    LaneZone, OilPattern, Pin, PinToppled, PowerMeter, PowerMeterFill, ThrowBanner, BALL_START_Z,
+    LANE_LENGTH, LANE_START_Z, LANE_WIDTH, PIN_START_Z, POWER_METER_MARGIN,
+};
+use spectate::SpectatePlugin;
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    diagnostics::DiagnosticSender,
+    menu::{Menu, MenuAction},
+    physics::PhysicsTuning,
+    settings::GameSettings,
+    spectator::is_playing,
+};
+use turns::{count_toppled_pins, BowlingStateWrapper, BowlingTurnPlugin};
+use tutorial::{TutorialPlugin, TutorialStep};
+use variant::VariantPlugin;
 
+pub mod announcer;
+pub mod audio;
+pub mod bot;
+pub mod celebration;
+pub mod environment;
+pub mod haptics;
+pub mod phase;
+pub mod pinsetter;
+pub mod power;
+pub mod practice;
+pub mod rematch;
+pub mod replay;
+pub mod save;
+pub mod scoreboard;
 pub mod setup;
+pub mod spectate;
 pub mod turns;
+pub mod tutorial;
+pub mod variant;
 
-/// System responsible for running and communicating with a Bevy app
-#[wasm_bindgen]
-pub struct Runner {
-    app: App,
-    write: Sender<Communication>,
-}
-
-#[wasm_bindgen]
-impl Runner {
-    /// Creates a new runner
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let (write, read) = crossbeam_channel::unbounded();
+/// Seconds of simulation in every physics step. Physics and ball input run on this fixed step
+/// rather than the frame rate, so the same throw always scatters the pins the same way
+pub const PHYSICS_STEP_SECS: f32 = 1.0 / 60.0;
 
-        let mut app = App::new();
-        app.add_plugins(DefaultPlugins.set(AssetPlugin {
-            meta_check: AssetMetaCheck::Never,
-            ..default()
-        }))
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
-        .add_plugins(BowlingTurnPlugin)
-        .insert_resource(ActionReader(read))
-        .add_systems(Startup, setup)
-        .add_systems(Update, (handle_input, handle_ball, check_pins, update_ui));
-
-        Runner { app, write }
-    }
-
-    /// Get the sender pipeline
-    #[wasm_bindgen]
-    pub fn get_send(&self) -> ActionSender {
-        ActionSender::new(self.write.clone())
-    }
+/// Length of the launch direction arrow drawn in front of the ball while aiming
+const AIM_ARROW_LENGTH: f32 = 2.0;
 
-    /// Run the Bevy App
-    #[wasm_bindgen]
-    pub fn run(&mut self) {
-        self.app.run();
-    }
-}
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .insert_resource(Time::<Fixed>::from_seconds(f64::from(PHYSICS_STEP_SECS)))
+    .insert_resource(TimestepMode::Fixed {
+        dt: PHYSICS_STEP_SECS,
+        substeps: 1,
+    })
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule())
+    .add_plugins(BowlingPhasePlugin)
+    .add_plugins(BowlingTurnPlugin)
+    .add_plugins(BowlingAudioPlugin)
+    .add_plugins(HapticsPlugin)
+    .add_plugins(PinsetterPlugin)
+    .add_plugins(CelebrationPlugin)
+    .add_plugins(AnnouncerPlugin)
+    .add_plugins(BowlingBotPlugin)
+    .add_plugins(ScoreboardPlugin)
+    .add_plugins(RematchPlugin)
+    .add_plugins(PracticePlugin)
+    .add_plugins(ReplayPlugin)
+    .add_plugins(VariantPlugin)
+    .add_plugins(EnvironmentPlugin)
+    .add_plugins(SavePlugin)
+    .add_plugins(TutorialPlugin)
+    .add_plugins(SpectatePlugin)
+    .add_plugins(PowerSavingPlugin)
+    .add_event::<Gutterball>()
+    .add_event::<PinToppled>()
+    .init_resource::<OilPattern>()
+    .add_systems(Startup, setup)
+    .add_systems(
+        FixedUpdate,
+        (
+            lock_handedness,
+            handle_input,
+            handle_ball,
+            apply_hook.run_if(in_state(BowlingPhase::Rolling)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(OnExit(BowlingPhase::Rolling), clear_hook)
+    .add_systems(
+        Update,
+        (
+            select_oil_pattern,
+            apply_oil_pattern,
+            check_gutter
+                .run_if(in_state(BowlingPhase::Rolling))
+                .run_if(is_playing),
+            announce_gutterball,
+            update_banner,
+            update_power_meter.run_if(not(in_state(BowlingPhase::Rolling))),
+            place_power_meter,
+            apply_ball_spec,
+            fit_finger_holes,
+            update_ball_picker,
+            draw_aim_guide.run_if(not(in_state(BowlingPhase::Rolling))),
+            (mark_disturbed_pins, check_pins, count_toppled_pins)
+                .chain()
+                .before(run_pinsetter)
+                .run_if(is_playing),
+        ),
+    );
+});
 
 /// Handles resetting the ball and pins if they go too far
 fn handle_ball(
@@ -63,39 +144,51 @@ fn handle_ball(
         (
             &mut Transform,
             &mut Ball,
+            &BallSpec,
             &mut Velocity,
             &mut RigidBody,
             &mut Visibility,
         ),
     >,
-    state: Res<'_, BowlingStateWrapper>,
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    settings: Res<'_, GameSettings>,
     time: Res<'_, Time>,
+    phase: Res<'_, State<BowlingPhase>>,
+    mut next_phase: ResMut<'_, NextState<BowlingPhase>>,
 ) {
-    if let Ok((mut transform, mut ball, mut velocity, mut rigid, mut visibility)) =
+    if let Ok((mut transform, mut ball, spec, mut velocity, mut rigid, mut visibility)) =
         ball.get_single_mut()
     {
-        if transform.translation.y <= -6.0 || (ball.released && *velocity == Velocity::zero()) {
+        if transform.translation.y <= -6.0
+            || ball.in_gutter
+            || (*phase.get() == BowlingPhase::Rolling && *velocity == Velocity::zero())
+        {
             reset_ball(
                 &mut transform,
                 &mut ball,
+                spec,
                 &mut rigid,
                 &mut velocity,
                 &mut visibility,
             );
-            state.inc_throw_num();
+            pinsetter.settle();
+            next_phase.set(BowlingPhase::Resetting);
         } else {
             if let Some(direction) = &mut ball.moving {
+                // Left-handed bowlers slide the other way, so work in mirrored lane coordinates
+                let handedness = settings.handedness();
                 let threshold = LANE_WIDTH / 2.0;
                 let dx = if *direction { 1.5 } else { -1.5 };
-                transform.translation.x += dx * time.delta_secs();
+                transform.translation.x += dx * handedness * time.delta_secs();
 
                 if transform.translation.y <= -0.05 {
                     *visibility = Visibility::Hidden;
                 }
 
-                if transform.translation.x >= threshold {
+                let x = transform.translation.x * handedness;
+                if x >= threshold {
                     *direction = false
-                } else if transform.translation.x <= -threshold {
+                } else if x <= -threshold {
                     *direction = true
                 }
             }
@@ -103,73 +196,439 @@ fn handle_ball(
     }
 }
 
-/// Updates the UI
-fn update_ui(
-    mut ui_elements: Query<'_, '_, (&mut Text, &Scorecard)>,
+/// Curves a released ball sideways by its hook while it's still rolling down the lane
+fn apply_hook(
+    mut ball: Query<'_, '_, (&Transform, &Ball, &Velocity, &mut ExternalForce)>,
+    oil: Res<'_, OilPattern>,
+) {
+    if let Ok((transform, ball, velocity, mut force)) = ball.get_single_mut() {
+        force.force = if velocity.linvel.z > 0.0 {
+            // Oil lets the ball skid, so it only really hooks once it reaches the dry backend
+            let progress = (transform.translation.z - LANE_START_Z) / LANE_LENGTH;
+            let grip = oil.multiplier_at(progress) / DRY_MULTIPLIER;
+            Vec3::X * ball.hook * grip
+        } else {
+            Vec3::ZERO
+        };
+    }
+}
+
+/// Stops curving the ball once it's off the lane
+fn clear_hook(mut ball: Query<'_, '_, &mut ExternalForce, With<Ball>>) {
+    for mut force in &mut ball {
+        force.force = Vec3::ZERO;
+    }
+}
+
+/// Lets players cycle the oil pattern with the menu buttons before the first throw
+fn select_oil_pattern(
+    mut actions: EventReader<'_, '_, MenuAction>,
+    mut oil: ResMut<'_, OilPattern>,
+    mut banner: Query<'_, '_, (&mut Text, &mut Visibility, &mut ThrowBanner)>,
+    state: Res<'_, BowlingStateWrapper>,
+    menus: Query<'_, '_, &Menu>,
+) {
+    for action in actions.read() {
+        // Menus get the buttons first, the oil pattern only changes outside of them
+        if state.has_started() || menus.iter().any(|menu| menu.focused) {
+            continue;
+        }
+
+        let pattern = match action {
+            MenuAction::Up => oil.previous(),
+            MenuAction::Down => oil.next(),
+            MenuAction::Select | MenuAction::Back => continue,
+        };
+        *oil = pattern;
+
+        if let Ok((mut text, mut visibility, mut banner)) = banner.get_single_mut() {
+            *text = Text::new(format!("Oil Pattern: {}", pattern.name()));
+            *visibility = Visibility::Visible;
+            banner.timer.reset();
+        }
+    }
+}
+
+/// Re-oils the lane's friction zones whenever the pattern changes
+fn apply_oil_pattern(
+    oil: Res<'_, OilPattern>,
+    physics: Res<'_, PhysicsTuning>,
+    mut zones: Query<'_, '_, (&LaneZone, &mut Friction)>,
+) {
+    if oil.is_changed() {
+        let multipliers = oil.multipliers();
+        for (zone, mut friction) in &mut zones {
+            *friction = Friction::coefficient(physics.ground.friction * multipliers[zone.index]);
+        }
+    }
+}
+
+/// Flags a released ball that drifts over a gutter so its throw ends with no pins
+fn check_gutter(
+    mut balls: Query<'_, '_, (&Transform, &mut Ball)>,
+    gutters: Query<'_, '_, (&Gutter, &Transform)>,
+    mut gutterballs: EventWriter<'_, Gutterball>,
+) {
+    for (transform, mut ball) in &mut balls {
+        if !ball.in_gutter
+            && gutters.iter().any(|(gutter, gutter_transform)| {
+                gutter.contains(gutter_transform, transform.translation)
+            })
+        {
+            ball.in_gutter = true;
+            gutterballs.send(Gutterball);
+        }
+    }
+}
+
+/// Shows the gutterball banner and plays its sound
+fn announce_gutterball(
+    mut gutterballs: EventReader<'_, '_, Gutterball>,
+    mut banner: Query<'_, '_, (&mut Text, &mut Visibility, &mut ThrowBanner)>,
+    mut sounds: EventWriter<'_, BowlingSound>,
+) {
+    for _ in gutterballs.read() {
+        if let Ok((mut text, mut visibility, mut banner)) = banner.get_single_mut() {
+            *text = Text::new("Gutterball!");
+            *visibility = Visibility::Visible;
+            banner.timer.reset();
+        }
+
+        sounds.send(BowlingSound::Gutter);
+    }
+}
+
+/// Hides the throw banner once its timer runs out
+fn update_banner(
+    mut banner: Query<'_, '_, (&mut Visibility, &mut ThrowBanner)>,
+    time: Res<'_, Time>,
+) {
+    for (mut visibility, mut banner) in &mut banner {
+        if banner.timer.tick(time.delta()).just_finished() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Draws the launch direction as a short arrow on the lane while aiming, and where the ball would
+/// roll from its current position and facing if aiming aids are on
+fn draw_aim_guide(
+    ball: Query<'_, '_, (&Transform, &Ball)>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<BowlingPhase>>,
+    mut gizmos: Gizmos<'_, '_>,
+) {
+    let Ok((transform, ball)) = ball.get_single() else {
+        return;
+    };
+    let forward = ball.launch_direction(transform.rotation);
+    let Some(direction) = Vec3::new(forward.x, 0.0, forward.z).try_normalize() else {
+        return;
+    };
+    let start = Vec3::new(transform.translation.x, 0.02, transform.translation.z);
+
+    if *phase.get() == BowlingPhase::Aiming {
+        gizmos.arrow(
+            start,
+            start + direction * AIM_ARROW_LENGTH,
+            Color::srgb(1.0, 0.85, 0.1),
+        );
+    }
+
+    if settings.aim_guide {
+        let length = (PIN_START_Z - start.z) / direction.z.max(0.1);
+        gizmos.line(
+            start,
+            start + direction * length,
+            Color::srgba(1.0, 1.0, 1.0, 0.6),
+        );
+    }
+}
+
+/// Fills the power meter with how hard the ball would be thrown right now, holding it once the
+/// ball is released
+fn update_power_meter(
+    ball: Query<'_, '_, (&Ball, &BallSpec)>,
+    mut fill: Query<'_, '_, (&mut Node, &mut BackgroundColor), With<PowerMeterFill>>,
+) {
+    if let (Ok((ball, spec)), Ok((mut node, mut color))) =
+        (ball.get_single(), fill.get_single_mut())
+    {
+        let power = ball.get_power(spec);
+        node.height = Val::Percent(power * 100.0);
+        *color = BackgroundColor(Color::srgb(power, 1.0 - power, 0.0));
+    }
+}
+
+/// Moves the power meter to the side of the screen away from the bowling hand
+fn place_power_meter(
+    settings: Res<'_, GameSettings>,
+    mut meter: Query<'_, '_, &mut Node, With<PowerMeter>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut node in &mut meter {
+        if settings.left_handed {
+            node.left = Val::Px(POWER_METER_MARGIN);
+            node.right = Val::Auto;
+        } else {
+            node.left = Val::Auto;
+            node.right = Val::Px(POWER_METER_MARGIN);
+        }
+    }
+}
+
+/// Resizes and reweighs the ball whenever a different one is picked
+fn apply_ball_spec(
+    mut ball: Query<
+        '_,
+        '_,
+        (
+            &BallSpec,
+            &mut Transform,
+            &mut Mesh3d,
+            &mut Collider,
+            &mut ColliderMassProperties,
+        ),
+        Changed<BallSpec>,
+    >,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    physics: Res<'_, PhysicsTuning>,
+) {
+    for (spec, mut transform, mut mesh, mut collider, mut mass) in &mut ball {
+        transform.translation.y = spec.radius;
+        *mesh = Mesh3d(meshes.add(Sphere::new(spec.radius).mesh().uv(32, 18)));
+        *collider = Collider::ball(spec.radius);
+        *mass = ColliderMassProperties::Density(physics.projectile.density * spec.density);
+    }
+}
+
+/// Keeps the finger holes on the ball's surface whenever a different ball is picked
+fn fit_finger_holes(
+    ball: Query<'_, '_, (&BallSpec, &Children), Changed<BallSpec>>,
+    mut holes: Query<'_, '_, (&FingerHole, &mut Transform)>,
+) {
+    for (spec, children) in &ball {
+        for child in children {
+            if let Ok((hole, mut transform)) = holes.get_mut(*child) {
+                *transform = hole.transform(spec.radius);
+            }
+        }
+    }
+}
+
+/// Shows which ball is picked until the player confirms it
+fn update_ball_picker(
+    ball: Query<'_, '_, &BallSpec, With<Ball>>,
+    mut picker: Query<'_, '_, (&mut Text, &mut Visibility), With<BallPicker>>,
+    menus: Query<'_, '_, &Menu>,
+    phase: Res<'_, State<BowlingPhase>>,
+) {
+    if let (Ok(spec), Ok((mut text, mut visibility))) = (ball.get_single(), picker.get_single_mut())
+    {
+        if *phase.get() == BowlingPhase::Choosing && !menus.iter().any(|menu| menu.focused) {
+            *text = Text::new(format!(
+                "Ball: {} (B: next ball, A: bowl with it)",
+                spec.name
+            ));
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Lane state that decides what the A and B buttons do
+#[derive(SystemParam)]
+struct Lane<'w, 's> {
+    /// Whether the pins are ready for the next throw
+    pinsetter: Res<'w, Pinsetter>,
+    /// Scores and player names
+    state: Res<'w, BowlingStateWrapper>,
+    /// Where the game is, from picking a ball to the final score
+    phase: Res<'w, State<BowlingPhase>>,
+    /// Phase to move to once this frame is over
+    next_phase: ResMut<'w, NextState<BowlingPhase>>,
+    /// Open menus, which take A and B presses before the ball does
+    menus: Query<'w, 's, &'static Menu>,
+    /// Practice pin setup editor
+    editor: ResMut<'w, PracticeEditor>,
+    /// Tutorial step the player is on, if one is running
+    tutorial: Res<'w, State<TutorialStep>>,
+    /// Used to skip the tutorial
+    next_tutorial: ResMut<'w, NextState<TutorialStep>>,
+}
+
+impl Lane<'_, '_> {
+    /// Whether any menu is taking input
+    fn menu_open(&self) -> bool {
+        self.menus.iter().any(|menu| menu.focused)
+    }
+
+    /// Whether the game is in the given phase
+    fn in_phase(&self, phase: BowlingPhase) -> bool {
+        *self.phase.get() == phase
+    }
+
+    /// Whether the tutorial is running and takes B to skip itself
+    fn in_tutorial(&self) -> bool {
+        *self.tutorial.get() != TutorialStep::Off
+    }
+}
+
+/// Locks in the hand the game is bowled with once the first ball has been thrown, undoing any
+/// handedness change that comes in after it
+fn lock_handedness(
     state: Res<'_, BowlingStateWrapper>,
+    mut settings: ResMut<'_, GameSettings>,
+    mut locked: Local<'_, bool>,
 ) {
-    let render = state.render();
-    if let Ok((mut txt, _)) = ui_elements.get_single_mut() {
-        *txt = Text::new(render);
+    if !state.has_started() {
+        *locked = settings.left_handed;
+    } else if settings.left_handed != *locked {
+        settings.left_handed = *locked;
     }
 }
 
-/// Reads input from the channel and applies i2 to the ball’s transform or sets release velocity
+/// Reads input from the channel, or the bot on its turn, and applies it to the ball’s transform or
+/// sets release velocity
 fn handle_input(
     mut param_set: ParamSet<
         '_,
         '_,
         (
-            Query<'_, '_, (&mut Transform, &mut Ball, &mut Velocity, &mut RigidBody)>,
+            Query<
+                '_,
+                '_,
+                (
+                    &mut Transform,
+                    &mut Ball,
+                    &mut BallSpec,
+                    &mut Velocity,
+                    &mut RigidBody,
+                ),
+            >,
             Query<'_, '_, (&mut Transform, &Pin, &mut Velocity)>,
         ),
     >,
-    read: Res<'_, ActionReader>,
-    state: Res<'_, BowlingStateWrapper>,
+    input: BowlingInput<'_>,
+    settings: Res<'_, GameSettings>,
+    mut menu: EventWriter<'_, MenuAction>,
+    mut rematch: EventWriter<'_, Rematch>,
+    diagnostics: Res<'_, DiagnosticSender>,
+    mut lane: Lane<'_, '_>,
 ) {
-    if let Ok(msg) = read.0.try_recv() {
-        if let Ok((mut transform, mut ball, mut velocity, mut rigid)) =
+    for msg in input.next_messages() {
+        if let Ok((mut transform, mut ball, mut spec, mut velocity, mut rigid)) =
             param_set.p0().get_single_mut()
         {
             match msg {
+                JsMessage::Restart => {
+                    rematch.send(Rematch);
+                }
+                JsMessage::ButtonA if lane.in_phase(BowlingPhase::GameOver) => {
+                    rematch.send(Rematch);
+                }
+                JsMessage::ButtonA if lane.menu_open() => {
+                    menu.send(MenuAction::Select);
+                }
+                JsMessage::ButtonB if lane.menu_open() => {
+                    menu.send(MenuAction::Down);
+                }
+                JsMessage::ButtonA if lane.in_phase(BowlingPhase::Choosing) => {
+                    lane.next_phase.set(BowlingPhase::Aiming);
+                }
+                JsMessage::ButtonB if lane.in_phase(BowlingPhase::Choosing) => *spec = spec.next(),
+                JsMessage::ButtonA if lane.editor.is_open() => lane.editor.confirm(),
+                JsMessage::ButtonB if lane.editor.is_open() => lane.editor.cycle(),
+                JsMessage::ButtonB if lane.in_tutorial() => {
+                    lane.next_tutorial.set(TutorialStep::Off);
+                }
                 JsMessage::ButtonA => {
-                    if !ball.released && ball.moving.is_none() {
-                        ball.released = true;
+                    if lane.in_phase(BowlingPhase::Aiming)
+                        && ball.moving.is_none()
+                        && lane.pinsetter.is_idle()
+                    {
+                        lane.next_phase.set(BowlingPhase::Rolling);
                         *rigid = RigidBody::Dynamic;
 
-                        let forward = transform.local_z();
-                        let curr_velocity = forward.normalize() * ball.get_speed();
-                        *velocity = Velocity::linear(curr_velocity);
+                        let forward = ball.launch_direction(transform.rotation).normalize();
+                        let curr_velocity = forward * ball.get_speed(&spec);
+                        ball.hook = ball.get_hook() * settings.handedness();
+                        *velocity = Velocity {
+                            linvel: curr_velocity,
+                            angvel: forward * ball.hook + spec.rolling_spin(curr_velocity),
+                        };
                     }
                 }
                 JsMessage::ButtonB => {
                     ball.moving = None;
                 }
-                JsMessage::Rotate(pitch, _, yaw) => {
-                    if !ball.released {
+                JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                    if !lane.in_phase(BowlingPhase::Rolling) {
+                        let Orientation { pitch, yaw, .. } = settings.apply_rotation(orientation);
                         let new = Quat::from_euler(EulerRot::XYZ, pitch, 0f32, yaw);
                         transform.rotation = new;
-                        ball.rotations.push(new);
+                        ball.record_rotation(new, input.now());
+                        if lane.in_phase(BowlingPhase::Aiming) {
+                            ball.aim_at(yaw);
+                        }
+                    }
+                }
+                JsMessage::SetPlayerName(player, name) => {
+                    lane.state.set_player_name(player, &name);
+                }
+                other => {
+                    if let Some(action) = MenuAction::from_message(&other) {
+                        menu.send(action);
                     }
                 }
-                JsMessage::SetPlayers(num) => state.set_players(num),
             }
+        } else {
+            diagnostics.warn(format!("Dropped {msg:?}, there is no ball to apply it to"));
+        }
+    }
+}
+
+/// Flags every pin something ran into, so it gets checked for toppling once it stops moving
+fn mark_disturbed_pins(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut contacts: EventReader<'_, '_, ContactForceEvent>,
+    mut pins: Query<'_, '_, &mut Pin>,
+) {
+    let started = collisions.read().filter_map(|collision| match collision {
+        CollisionEvent::Started(a, b, _) => Some([*a, *b]),
+        CollisionEvent::Stopped(..) => None,
+    });
+    let forced = contacts
+        .read()
+        .map(|contact| [contact.collider1, contact.collider2]);
+
+    for entity in started.chain(forced).flatten() {
+        if let Ok(mut pin) = pins.get_mut(entity) {
+            pin.disturbed = true;
         }
     }
 }
 
-/// Checks for whether pins are toppled or not
+/// Checks hit pins for toppling once they come to rest, or as soon as they leave the deck, leaving
+/// toppled pins on the deck for the pinsetter to sweep
 pub fn check_pins(
-    mut pins: Query<'_, '_, (&mut Pin, &mut Transform)>,
-    state: Res<'_, BowlingStateWrapper>,
+    mut pins: Query<'_, '_, (Entity, &mut Pin, &Transform, &Velocity, &Sleeping)>,
+    mut toppled: EventWriter<'_, PinToppled>,
 ) {
-    for (mut pin, mut transform) in &mut pins {
-        let height = transform.translation.y;
-        if height < 0.2 && !pin.toppled {
+    for (entity, mut pin, transform, velocity, sleeping) in &mut pins {
+        let settled = sleeping.sleeping || Pin::is_resting(velocity);
+        if !pin.disturbed || !(settled || pin.is_off_deck(transform)) {
+            continue;
+        }
+
+        pin.disturbed = false;
+        if !pin.toppled && pin.is_down(transform) {
             pin.toppled = true;
-            state.topple_pin();
-            // 🤭
-            *transform = Transform::from_xyz(0.0, -100_000.0, 0.0);
+            toppled.send(PinToppled(entity));
         }
     }
 }
@@ -178,17 +637,20 @@ pub fn check_pins(
 pub fn reset_ball(
     transform: &mut Transform,
     ball: &mut Ball,
+    spec: &BallSpec,
     rigid: &mut RigidBody,
     velocity: &mut Velocity,
     visibility: &mut Visibility,
 ) {
-    transform.translation = Vec3::new(0.0, 0.3, BALL_START_Z);
+    transform.translation = Vec3::new(0.0, spec.radius, BALL_START_Z);
     transform.rotation = Quat::IDENTITY;
     ball.velocity = Vec3::ZERO;
     ball.moving = Some(true);
-    ball.rotations = vec![];
+    ball.swing.clear();
+    ball.hook = 0.0;
+    ball.aim = 0.0;
+    ball.in_gutter = false;
     *velocity = Velocity::zero();
     *rigid = RigidBody::KinematicPositionBased;
     *visibility = Visibility::Visible;
-    ball.released = false;
 }
@@ -0,0 +1,52 @@
+//! Phases a bowling game moves through, from picking a ball to the final score
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::spectator::is_playing;
+
+use crate::{pinsetter::Pinsetter, turns::BowlingStateWrapper};
+
+/// Where the lane is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BowlingPhase {
+    /// Picking a ball before the first throw
+    #[default]
+    Choosing,
+    /// Lining up and swinging, the ball can be thrown once the pinsetter is done
+    Aiming,
+    /// The ball is on its way down the lane
+    Rolling,
+    /// The throw is being scored and the deck cleared for the next throw or the next player
+    Resetting,
+    /// Every player has finished their 10th frame and the final score is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct BowlingPhasePlugin;
+
+impl Plugin for BowlingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<BowlingPhase>().add_systems(
+            Update,
+            finish_reset
+                .run_if(in_state(BowlingPhase::Resetting))
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Moves on once the pinsetter is done with the deck, to the next throw or the final score
+fn finish_reset(
+    pinsetter: Res<'_, Pinsetter>,
+    state: Res<'_, BowlingStateWrapper>,
+    mut phase: ResMut<'_, NextState<BowlingPhase>>,
+) {
+    if pinsetter.is_idle() {
+        phase.set(if state.is_game_over() {
+            BowlingPhase::GameOver
+        } else {
+            BowlingPhase::Aiming
+        });
+    }
+}
@@ -0,0 +1,300 @@
+//! Pinsetter that waits for the deck to settle, then sweeps toppled pins away and re-spots the
+//! rack between throws
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RigidBody, Sleeping, Velocity};
+use spjorts_core::spectator::is_playing;
+
+use crate::{
+    setup::{Pin, LANE_WIDTH, PIN_START_Z},
+    turns::BowlingStateWrapper,
+};
+
+/// How long standing pins take to be lifted or lowered
+const LIFT_SECS: f32 = 0.5;
+/// How long the sweep bar takes to cross the pin deck
+const SWEEP_SECS: f32 = 1.0;
+/// How far above the deck standing pins are lifted
+const LIFT_HEIGHT: f32 = 1.2;
+/// Where the sweep bar starts, just in front of the head pin
+const SWEEP_START_Z: f32 = PIN_START_Z;
+/// Where the sweep bar stops, past the back row and into the pit
+const SWEEP_END_Z: f32 = PIN_START_Z + 6.0;
+/// Height the sweep bar rides at
+const SWEEP_HEIGHT: f32 = 0.3;
+/// Where swept pins are parked out of sight
+const PARKED: Vec3 = Vec3::new(0.0, -100_000.0, 0.0);
+/// Every pin number in a full rack
+pub const FULL_RACK: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+/// Longest the pinsetter waits for every pin to fall asleep before scoring a throw anyway. Rapier
+/// puts bodies to sleep after a couple of seconds at rest, so this leaves room for a late wobble
+const MAX_SETTLE_SECS: f32 = 6.0;
+
+/// What the pinsetter is currently doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PinsetterPhase {
+    /// Waiting for a throw to finish, the ball may be thrown
+    #[default]
+    Idle,
+    /// Waiting for every pin to fall asleep before the throw is scored, so late topples count
+    Settling,
+    /// Waiting for the settled throw to be scored
+    Scoring,
+    /// Raising the standing pins off the deck
+    Lifting,
+    /// Pushing the sweep bar across the deck to clear fallen pins
+    Sweeping,
+    /// Setting pins back down onto the deck
+    Lowering,
+}
+
+impl PinsetterPhase {
+    /// How long this phase runs for
+    fn duration(&self) -> f32 {
+        match self {
+            Self::Idle | Self::Scoring => 0.0,
+            Self::Settling => MAX_SETTLE_SECS,
+            Self::Lifting | Self::Lowering => LIFT_SECS,
+            Self::Sweeping => SWEEP_SECS,
+        }
+    }
+
+    /// The phase that follows this one
+    fn next(&self) -> Self {
+        match self {
+            Self::Idle | Self::Lowering => Self::Idle,
+            Self::Settling => Self::Scoring,
+            Self::Scoring => Self::Lifting,
+            Self::Lifting => Self::Sweeping,
+            Self::Sweeping => Self::Lowering,
+        }
+    }
+}
+
+/// Pinsetter state machine, cycled once after every throw
+#[derive(Resource, Debug, Default)]
+pub struct Pinsetter {
+    /// Current phase
+    phase: PinsetterPhase,
+    /// Time spent in the current phase
+    timer: Timer,
+    /// Whether every pin is swept and a fresh rack is set, rather than re-spotting standing pins
+    full_rack: bool,
+    /// Pin numbers a fresh rack is set with, every pin if `None`
+    rack: Option<Vec<u8>>,
+    /// Whether the pinsetter is paused where it is
+    held: bool,
+}
+
+impl Pinsetter {
+    /// Waits for the deck to settle after the ball is gone, then marks the throw as done so it
+    /// can be scored
+    pub fn settle(&mut self) {
+        self.enter(PinsetterPhase::Settling);
+    }
+
+    /// Clears and re-spots the deck once a throw is scored, clearing the whole deck for a fresh rack if `full_rack` is set
+    pub fn start(&mut self, full_rack: bool) {
+        self.full_rack = full_rack;
+        self.rack = None;
+        self.enter(PinsetterPhase::Lifting);
+    }
+
+    /// Clears the whole deck and sets only the given pin numbers, for practicing leaves or
+    /// resuming a frame
+    pub fn rack(&mut self, pins: &[u8]) {
+        self.full_rack = true;
+        self.rack = Some(pins.to_vec());
+        self.enter(PinsetterPhase::Lifting);
+    }
+
+    /// Whether the pinsetter is done and the next throw can be made
+    pub fn is_idle(&self) -> bool {
+        !self.held && self.phase == PinsetterPhase::Idle
+    }
+
+    /// Pauses the pinsetter where it is until it's released, such as while a replay plays
+    pub fn hold(&mut self) {
+        self.held = true;
+    }
+
+    /// Carries on after being held
+    pub fn release(&mut self) {
+        self.held = false;
+    }
+
+    /// Abandons any cycle in progress
+    pub fn stop(&mut self) {
+        self.enter(PinsetterPhase::Idle);
+    }
+
+    /// Switches to a phase and restarts its timer
+    fn enter(&mut self, phase: PinsetterPhase) {
+        self.phase = phase;
+        self.timer = Timer::from_seconds(phase.duration(), TimerMode::Once);
+    }
+
+    /// Whether a pin gets swept off the deck this cycle
+    fn sweeps(&self, pin: &Pin) -> bool {
+        self.full_rack || pin.toppled
+    }
+
+    /// Whether a fresh rack includes a pin
+    fn racks(&self, pin: &Pin) -> bool {
+        self.rack
+            .as_deref()
+            .unwrap_or(FULL_RACK)
+            .contains(&pin.number)
+    }
+}
+
+/// Marks the sweep bar entity
+#[derive(Component)]
+pub struct SweepBar;
+
+/// Plugin that animates pins being cleared and reset between throws
+pub struct PinsetterPlugin;
+
+impl Plugin for PinsetterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pinsetter>()
+            .add_systems(Startup, spawn_sweep_bar)
+            .add_systems(Update, run_pinsetter.run_if(is_playing));
+    }
+}
+
+/// Spawns the hidden sweep bar across the pin deck
+fn spawn_sweep_bar(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(LANE_WIDTH + 0.4, 0.4, 0.1))),
+        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.2, 0.25))),
+        Transform::from_xyz(0.0, SWEEP_HEIGHT, SWEEP_START_Z),
+        Visibility::Hidden,
+        SweepBar,
+        Name::new("Sweep Bar"),
+    ));
+}
+
+/// Advances the pinsetter, moving the sweep bar and pins for the current phase
+pub fn run_pinsetter(
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    mut pins: Query<
+        '_,
+        '_,
+        (
+            &mut Pin,
+            &mut Transform,
+            &mut Velocity,
+            &mut RigidBody,
+            &mut Visibility,
+            &Sleeping,
+        ),
+    >,
+    mut bar: Query<'_, '_, (&mut Transform, &mut Visibility), (With<SweepBar>, Without<Pin>)>,
+    state: Res<'_, BowlingStateWrapper>,
+    time: Res<'_, Time>,
+) {
+    if pinsetter.held
+        || matches!(
+            pinsetter.phase,
+            PinsetterPhase::Idle | PinsetterPhase::Scoring
+        )
+    {
+        return;
+    }
+
+    let finished = pinsetter.timer.tick(time.delta()).just_finished();
+    let progress = pinsetter.timer.fraction();
+
+    match pinsetter.phase {
+        PinsetterPhase::Idle | PinsetterPhase::Scoring => {}
+        PinsetterPhase::Settling => {
+            // Every pin has to be asleep, and hit pins checked for toppling, before the throw can
+            // be scored
+            let settled = pins.iter().all(|(pin, transform, .., sleeping)| {
+                transform.translation == PARKED || (!pin.disturbed && sleeping.sleeping)
+            });
+
+            if settled || finished {
+                state.inc_throw_num();
+                pinsetter.enter(PinsetterPhase::Scoring);
+            }
+            return;
+        }
+        PinsetterPhase::Lifting => {
+            for (mut pin, mut transform, mut velocity, mut rigid, ..) in &mut pins {
+                if pinsetter.sweeps(&pin) {
+                    continue;
+                }
+
+                if *rigid != RigidBody::KinematicPositionBased {
+                    pin.mark_spot(&transform);
+                    *rigid = RigidBody::KinematicPositionBased;
+                    *velocity = Velocity::zero();
+                }
+
+                *transform = pin.spot;
+                transform.translation.y += LIFT_HEIGHT * progress;
+            }
+        }
+        PinsetterPhase::Sweeping => {
+            let bar_z = SWEEP_START_Z.lerp(SWEEP_END_Z, progress);
+            if let Ok((mut transform, mut visibility)) = bar.get_single_mut() {
+                transform.translation.z = bar_z;
+                *visibility = if finished {
+                    Visibility::Hidden
+                } else {
+                    Visibility::Visible
+                };
+            }
+
+            for (mut pin, mut transform, mut velocity, mut rigid, mut visibility, _) in &mut pins {
+                let parked = transform.translation == PARKED;
+                if pinsetter.sweeps(&pin)
+                    && !parked
+                    && (finished || transform.translation.z <= bar_z)
+                {
+                    // Swept pins count as down so they aren't scored again while parked
+                    pin.toppled = true;
+                    *transform = Transform::from_translation(PARKED);
+                    *velocity = Velocity::zero();
+                    *rigid = RigidBody::KinematicPositionBased;
+                    *visibility = Visibility::Hidden;
+                }
+            }
+        }
+        PinsetterPhase::Lowering => {
+            for (mut pin, mut transform, mut velocity, mut rigid, mut visibility, _) in &mut pins {
+                if pinsetter.full_rack {
+                    if !pinsetter.racks(&pin) {
+                        continue;
+                    }
+                    if pin.toppled {
+                        pin.toppled = false;
+                        pin.spot = pin.initial_coords;
+                    }
+                } else if pin.toppled {
+                    continue;
+                }
+
+                *transform = pin.spot;
+                transform.translation.y += LIFT_HEIGHT * (1.0 - progress);
+                *visibility = Visibility::Visible;
+
+                if finished {
+                    *velocity = Velocity::zero();
+                    *rigid = RigidBody::Dynamic;
+                }
+            }
+        }
+    }
+
+    if finished {
+        let next = pinsetter.phase.next();
+        pinsetter.enter(next);
+    }
+}
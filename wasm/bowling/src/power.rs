@@ -0,0 +1,107 @@
+//! Dropping to a low frame rate while nothing is happening on the lane, so phones don't burn
+//! battery redrawing the same scene between turns
+
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    winit::{UpdateMode, WinitSettings},
+};
+use spjorts_core::menu::Menu;
+
+use crate::{bot::BowlingBot, phase::BowlingPhase, pinsetter::Pinsetter, setup::Ball};
+
+/// Seconds without input or movement before the frame rate drops
+const IDLE_SECS: f32 = 5.0;
+
+/// How far the ball has to move, in metres or radians, to count as the player doing something.
+/// A controller held still keeps streaming rotations, so any smaller change is sensor noise
+const BALL_MOVE_THRESHOLD: f32 = 0.02;
+
+/// Frame rate while idle. Controller input is only picked up on these frames, so it has to stay
+/// high enough that the first swing after a pause doesn't feel laggy
+const IDLE_FPS: f64 = 10.0;
+
+/// Plugin that switches to reactive low-power updates while the lane is idle
+pub struct PowerSavingPlugin;
+
+impl Plugin for PowerSavingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, save_power);
+    }
+}
+
+/// Everything on the lane that keeps it at full speed
+#[derive(SystemParam)]
+struct LaneActivity<'w, 's> {
+    /// The ball, turned and slid by controller input
+    ball: Query<'w, 's, &'static Transform, With<Ball>>,
+    /// Menus moved through by controller input this frame
+    menus: Query<'w, 's, (), Changed<Menu>>,
+    /// The computer opponent
+    bot: Res<'w, BowlingBot>,
+    /// Whether the pinsetter is moving pins
+    pinsetter: Res<'w, Pinsetter>,
+    /// Where the game is
+    phase: Res<'w, State<BowlingPhase>>,
+}
+
+impl LaneActivity<'_, '_> {
+    /// Whether anything is moving or being done, given where the ball was when last checked
+    fn is_active(&self, last_ball: &mut Transform) -> bool {
+        let in_flight = matches!(
+            self.phase.get(),
+            BowlingPhase::Rolling | BowlingPhase::Resetting
+        );
+        let ball_moved = self.ball.get_single().is_ok_and(|ball| {
+            let moved = ball.translation.distance(last_ball.translation) > BALL_MOVE_THRESHOLD
+                || ball.rotation.angle_between(last_ball.rotation) > BALL_MOVE_THRESHOLD;
+            if moved {
+                *last_ball = *ball;
+            }
+            moved
+        });
+
+        in_flight
+            || ball_moved
+            || !self.menus.is_empty()
+            || self.bot.has_turn()
+            || !self.pinsetter.is_idle()
+    }
+}
+
+/// Update modes for the lane sitting idle
+fn idle_settings() -> WinitSettings {
+    let mode = UpdateMode::reactive_low_power(Duration::from_secs_f64(1.0 / IDLE_FPS));
+    WinitSettings {
+        focused_mode: mode,
+        unfocused_mode: mode,
+    }
+}
+
+/// Drops to [`IDLE_FPS`] once the ball, menus and pinsetter have sat still for [`IDLE_SECS`],
+/// going back to full speed as soon as anything happens
+fn save_power(
+    mut winit: ResMut<'_, WinitSettings>,
+    mut last_active: Local<'_, f32>,
+    mut last_ball: Local<'_, Transform>,
+    time: Res<'_, Time<Real>>,
+    activity: LaneActivity<'_, '_>,
+) {
+    let now = time.elapsed_secs();
+    if activity.is_active(&mut last_ball) {
+        *last_active = now;
+    }
+
+    let settings = if now - *last_active >= IDLE_SECS {
+        idle_settings()
+    } else {
+        WinitSettings::game()
+    };
+    if winit.focused_mode != settings.focused_mode
+        || winit.unfocused_mode != settings.unfocused_mode
+    {
+        *winit = settings;
+    }
+}
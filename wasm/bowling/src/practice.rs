@@ -0,0 +1,300 @@
+//! Practice mode with unlimited throws at a chosen pin leave
+
+use bevy::prelude::*;
+use spjorts_core::{
+    menu::{Menu, MenuSelected},
+    spectator::is_playing,
+};
+
+use crate::{
+    environment::spawn_environment_menu,
+    phase::BowlingPhase,
+    pinsetter::{Pinsetter, FULL_RACK},
+    setup::ThrowBanner,
+    turns::BowlingStateWrapper,
+};
+
+/// Font size of the mode menu's options
+const MENU_FONT_SIZE: f32 = 36.0;
+/// Color of the highlighted mode
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+
+/// How the current game is played
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    /// Ten scored frames per player
+    #[default]
+    Standard,
+    /// Unscored throws at a chosen leave, for as long as the player likes
+    Practice,
+}
+
+impl GameMode {
+    /// Every mode, in menu order
+    const ALL: [Self; 2] = [Self::Standard, Self::Practice];
+
+    /// Name shown in the mode menu
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard Game",
+            Self::Practice => "Practice",
+        }
+    }
+}
+
+/// Pin setups practice can start from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Leave {
+    /// All ten pins
+    #[default]
+    FullRack,
+    /// The 7 and 10 pins in the back corners
+    SevenTenSplit,
+    /// The 2, 4, 5 and 8 pins bunched on the left
+    Bucket,
+    /// The head pin with the 2, 4 and 10 pins
+    Washout,
+}
+
+impl Leave {
+    /// Every leave, in the order B cycles through them
+    const ALL: [Self; 4] = [
+        Self::FullRack,
+        Self::SevenTenSplit,
+        Self::Bucket,
+        Self::Washout,
+    ];
+
+    /// Name shown in the pin setup editor
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FullRack => "Full Rack",
+            Self::SevenTenSplit => "7-10 Split",
+            Self::Bucket => "Bucket",
+            Self::Washout => "Washout",
+        }
+    }
+
+    /// Numbers of the pins left standing
+    pub fn pins(&self) -> &'static [u8] {
+        match self {
+            Self::FullRack => FULL_RACK,
+            Self::SevenTenSplit => &[7, 10],
+            Self::Bucket => &[2, 4, 5, 8],
+            Self::Washout => &[1, 2, 4, 10],
+        }
+    }
+
+    /// The next leave, wrapping back to a full rack
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|leave| leave == self)
+            .unwrap_or_default();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Pin setup editor shown between practice throws
+#[derive(Resource, Debug, Default)]
+pub struct PracticeEditor {
+    /// Whether the editor is taking A and B presses
+    open: bool,
+    /// The leave being practiced
+    leave: Leave,
+    /// Whether the pinsetter still has to set up the confirmed leave
+    pending: bool,
+}
+
+impl PracticeEditor {
+    /// Whether the editor is taking A and B presses
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Moves on to the next leave
+    pub fn cycle(&mut self) {
+        self.leave = self.leave.next();
+    }
+
+    /// Closes the editor and has the pinsetter set up the chosen leave
+    pub fn confirm(&mut self) {
+        self.open = false;
+        self.pending = true;
+    }
+}
+
+/// Marks the mode menu
+#[derive(Component)]
+struct ModeMenu;
+
+/// An entry in the mode menu
+#[derive(Component)]
+struct ModeOption(usize);
+
+/// Text describing the pin setup editor
+#[derive(Component)]
+struct EditorText;
+
+/// Plugin that adds the mode menu and practice mode
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<PracticeEditor>()
+            .add_systems(Startup, spawn_practice_ui)
+            .add_systems(
+                Update,
+                (
+                    highlight_mode_menu,
+                    choose_mode,
+                    score_practice_throw
+                        .run_if(resource_equals(GameMode::Practice))
+                        .run_if(is_playing),
+                    rack_leave,
+                    update_editor_text,
+                ),
+            );
+    }
+}
+
+/// Spawns the mode menu, focused so it is the first thing players see, and the hidden editor text
+fn spawn_practice_ui(mut commands: Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Menu::new(GameMode::ALL.len()),
+            ModeMenu,
+        ))
+        .with_children(|menu| {
+            for (idx, mode) in GameMode::ALL.iter().enumerate() {
+                menu.spawn((
+                    Text::new(mode.name()),
+                    TextFont::from_font_size(MENU_FONT_SIZE),
+                    TextColor::WHITE,
+                    ModeOption(idx),
+                ));
+            }
+        });
+
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(32.0),
+        TextColor::WHITE,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(25.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+        EditorText,
+    ));
+}
+
+/// Colors the highlighted mode
+fn highlight_mode_menu(
+    menus: Query<'_, '_, &Menu, (With<ModeMenu>, Changed<Menu>)>,
+    mut options: Query<'_, '_, (&ModeOption, &mut TextColor)>,
+) {
+    for menu in &menus {
+        for (option, mut color) in &mut options {
+            color.0 = if option.0 == menu.selected {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+/// Sets the game mode once one is picked and closes the mode menu, moving on to the environment
+/// menu
+fn choose_mode(
+    mut commands: Commands<'_, '_>,
+    mut selections: EventReader<'_, '_, MenuSelected>,
+    menus: Query<'_, '_, (), With<ModeMenu>>,
+    mut mode: ResMut<'_, GameMode>,
+    mut editor: ResMut<'_, PracticeEditor>,
+) {
+    for selection in selections.read() {
+        if menus.get(selection.menu).is_err() {
+            continue;
+        }
+
+        *mode = GameMode::ALL[selection.index];
+        editor.open = *mode == GameMode::Practice;
+        commands.entity(selection.menu).despawn_recursive();
+
+        spawn_environment_menu(&mut commands);
+    }
+}
+
+/// Reports how a practice throw went and opens the editor for the next one
+fn score_practice_throw(
+    state: Res<'_, BowlingStateWrapper>,
+    mut editor: ResMut<'_, PracticeEditor>,
+    mut banner: Query<'_, '_, (&mut Text, &mut Visibility, &mut ThrowBanner)>,
+) {
+    if !state.is_throw_done() {
+        return;
+    }
+
+    let pinfall = usize::from(state.take_unscored_throw());
+    let standing = editor.leave.pins().len();
+    let message = if pinfall >= standing {
+        format!("{} cleared!", editor.leave.name())
+    } else {
+        format!("{pinfall} of {standing} pins")
+    };
+
+    if let Ok((mut text, mut visibility, mut banner)) = banner.get_single_mut() {
+        *text = Text::new(message);
+        *visibility = Visibility::Visible;
+        banner.timer.reset();
+    }
+
+    editor.open = true;
+}
+
+/// Has the pinsetter set up a confirmed leave
+fn rack_leave(mut editor: ResMut<'_, PracticeEditor>, mut pinsetter: ResMut<'_, Pinsetter>) {
+    if editor.pending {
+        editor.pending = false;
+        pinsetter.rack(editor.leave.pins());
+    }
+}
+
+/// Shows the editor's leave once the ball is picked, hiding it while the editor is closed
+fn update_editor_text(
+    editor: Res<'_, PracticeEditor>,
+    phase: Res<'_, State<BowlingPhase>>,
+    mut text: Query<'_, '_, (&mut Text, &mut Visibility), With<EditorText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+
+    if editor.open && *phase.get() != BowlingPhase::Choosing {
+        *text = Text::new(format!(
+            "Pins: {} (B: next setup, A: set pins)",
+            editor.leave.name()
+        ));
+        *visibility = Visibility::Visible;
+    } else {
+        *visibility = Visibility::Hidden;
+    }
+}
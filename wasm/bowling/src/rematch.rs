@@ -0,0 +1,107 @@
+//! Starting a new game in place once the last one is over
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{RigidBody, Velocity};
+
+use crate::{
+    phase::BowlingPhase,
+    pinsetter::{Pinsetter, SweepBar},
+    reset_ball,
+    setup::{Ball, BallSpec, FinalScore, Hideable, Pin, ScorecardBg},
+    turns::BowlingStateWrapper,
+};
+
+/// Sent to start a new game with the same players
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Rematch;
+
+/// Plugin that resets the lane and scores for a rematch
+pub struct RematchPlugin;
+
+impl Plugin for RematchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Rematch>()
+            .add_systems(Update, restart_game)
+            .add_systems(OnExit(BowlingPhase::GameOver), hide_final_score);
+    }
+}
+
+/// Resets the scores, re-racks the pins and returns the ball for picking again
+fn restart_game(
+    mut rematches: EventReader<'_, '_, Rematch>,
+    mut queries: ParamSet<
+        '_,
+        '_,
+        (
+            Query<'_, '_, (&mut Pin, &mut Transform, &mut Velocity, &mut RigidBody)>,
+            Query<
+                '_,
+                '_,
+                (
+                    &mut Transform,
+                    &mut Ball,
+                    &BallSpec,
+                    &mut RigidBody,
+                    &mut Velocity,
+                    &mut Visibility,
+                ),
+            >,
+        ),
+    >,
+    state: Res<'_, BowlingStateWrapper>,
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    mut phase: ResMut<'_, NextState<BowlingPhase>>,
+) {
+    if rematches.read().count() == 0 {
+        return;
+    }
+
+    state.restart();
+    pinsetter.stop();
+
+    for (mut pin, mut transform, mut velocity, mut rigid) in &mut queries.p0() {
+        pin.reset(&mut transform);
+        *velocity = Velocity::zero();
+        *rigid = RigidBody::Dynamic;
+    }
+
+    if let Ok((mut transform, mut ball, spec, mut rigid, mut velocity, mut visibility)) =
+        queries.p1().get_single_mut()
+    {
+        reset_ball(
+            &mut transform,
+            &mut ball,
+            spec,
+            &mut rigid,
+            &mut velocity,
+            &mut visibility,
+        );
+    }
+
+    phase.set(BowlingPhase::Choosing);
+}
+
+/// Takes down the final score screen and brings back everything it hid
+fn hide_final_score(
+    mut queries: ParamSet<
+        '_,
+        '_,
+        (
+            Query<'_, '_, &mut Visibility, With<Hideable>>,
+            Query<'_, '_, &mut Visibility, Or<(With<ScorecardBg>, With<SweepBar>)>>,
+        ),
+    >,
+    mut text: Query<'_, '_, &mut Text, With<FinalScore>>,
+) {
+    for mut visibility in &mut queries.p0() {
+        *visibility = Visibility::Visible;
+    }
+
+    for mut visibility in &mut queries.p1() {
+        *visibility = Visibility::Hidden;
+    }
+
+    for mut text in &mut text {
+        text.0.clear();
+    }
+}
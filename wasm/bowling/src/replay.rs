@@ -0,0 +1,297 @@
+//! Slow-motion instant replays of strikes, watched from beside the pin deck
+
+use std::collections::VecDeque;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::plugin::RapierConfiguration;
+
+use crate::{
+    phase::BowlingPhase,
+    pinsetter::Pinsetter,
+    setup::{Ball, Pin, LANE_WIDTH, PIN_START_Z},
+    turns::{BowlingStateWrapper, Celebration},
+};
+
+/// How much of a throw is kept for replaying, in seconds
+const REPLAY_SECS: f32 = 2.0;
+/// How fast a replay plays back compared to real time
+const REPLAY_SPEED: f32 = 0.3;
+/// Where the replay camera sits, off to the side of the pin deck
+const REPLAY_CAMERA: Vec3 = Vec3::new(LANE_WIDTH * 2.0, 1.5, PIN_START_Z - 3.0);
+/// What the replay camera looks at
+const REPLAY_TARGET: Vec3 = Vec3::new(0.0, 0.4, PIN_START_Z + 1.0);
+
+/// Where the ball and every pin were at one point in time
+struct ReplayFrame {
+    /// Seconds since startup the frame was recorded at
+    at: f32,
+    /// The ball's transform
+    ball: Transform,
+    /// Every pin's transform
+    pins: Vec<(Entity, Transform)>,
+}
+
+impl ReplayFrame {
+    /// Blends between this frame and a later one, `t` going from 0.0 to 1.0
+    fn lerp(&self, next: &Self, t: f32) -> Self {
+        let blend = |a: &Transform, b: &Transform| Transform {
+            translation: a.translation.lerp(b.translation, t),
+            rotation: a.rotation.slerp(b.rotation, t),
+            scale: a.scale,
+        };
+
+        Self {
+            at: self.at.lerp(next.at, t),
+            ball: blend(&self.ball, &next.ball),
+            pins: self
+                .pins
+                .iter()
+                .zip(&next.pins)
+                .map(|((entity, a), (_, b))| (*entity, blend(a, b)))
+                .collect(),
+        }
+    }
+}
+
+/// A replay being played back
+struct Playback {
+    /// Seconds of the recording played so far
+    elapsed: f32,
+    /// Where everything was when the replay started, put back once it's over
+    live: ReplayFrame,
+    /// Where the camera was when the replay started
+    camera: Transform,
+    /// Label shown while the replay plays
+    label: Entity,
+}
+
+/// The last few seconds of a throw, and the replay of them if one is playing
+#[derive(Resource, Default)]
+pub struct InstantReplay {
+    /// Recorded frames, oldest first
+    frames: VecDeque<ReplayFrame>,
+    /// The replay being played back, if any
+    playback: Option<Playback>,
+}
+
+impl InstantReplay {
+    /// Whether a replay is currently playing
+    pub fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// The recorded frame at `at` seconds since startup, blended between its neighbours
+    fn frame_at(&self, at: f32) -> Option<ReplayFrame> {
+        let last = self.frames.len().checked_sub(1)?;
+        let next = self
+            .frames
+            .iter()
+            .position(|frame| frame.at >= at)
+            .unwrap_or(last);
+        let (prev, next) = (&self.frames[next.saturating_sub(1)], &self.frames[next]);
+        let span = next.at - prev.at;
+        let t = if span > f32::EPSILON {
+            ((at - prev.at) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        Some(prev.lerp(next, t))
+    }
+}
+
+/// Marks the label shown while a replay plays
+#[derive(Component)]
+struct ReplayLabel;
+
+/// Everything a replay moves around
+#[derive(SystemParam)]
+struct ReplayScene<'w, 's> {
+    /// The ball
+    ball: Query<'w, 's, &'static mut Transform, (With<Ball>, Without<Pin>, Without<Camera3d>)>,
+    /// Every pin
+    pins: Query<
+        'w,
+        's,
+        (Entity, &'static mut Transform),
+        (With<Pin>, Without<Ball>, Without<Camera3d>),
+    >,
+    /// The lane camera
+    camera: Query<'w, 's, &'static mut Transform, (With<Camera3d>, Without<Ball>, Without<Pin>)>,
+    /// Physics settings, paused while a replay plays
+    physics: Query<'w, 's, &'static mut RapierConfiguration>,
+}
+
+impl ReplayScene<'_, '_> {
+    /// Records where everything is right now
+    fn capture(&self, at: f32) -> Option<ReplayFrame> {
+        Some(ReplayFrame {
+            at,
+            ball: *self.ball.get_single().ok()?,
+            pins: self
+                .pins
+                .iter()
+                .map(|(entity, transform)| (entity, *transform))
+                .collect(),
+        })
+    }
+
+    /// Moves everything to where it was in a frame
+    fn pose(&mut self, frame: &ReplayFrame) {
+        if let Ok(mut ball) = self.ball.get_single_mut() {
+            *ball = frame.ball;
+        }
+        for (entity, transform) in &frame.pins {
+            if let Ok((_, mut pin)) = self.pins.get_mut(*entity) {
+                *pin = *transform;
+            }
+        }
+    }
+
+    /// Pauses or resumes the physics simulation
+    fn set_physics(&mut self, active: bool) {
+        for mut config in &mut self.physics {
+            config.physics_pipeline_active = active;
+        }
+    }
+}
+
+/// Plugin that replays strikes in slow motion before the pinsetter clears the deck
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InstantReplay>()
+            .add_systems(OnEnter(BowlingPhase::Rolling), clear_frames)
+            .add_systems(
+                Update,
+                (
+                    record_frames.run_if(in_state(BowlingPhase::Rolling)),
+                    start_replay,
+                    play_replay,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Forgets the last throw's recording as a new one starts
+fn clear_frames(mut replay: ResMut<'_, InstantReplay>) {
+    replay.frames.clear();
+}
+
+/// Records the ball and pins every frame while the ball is rolling, keeping the last few seconds
+fn record_frames(
+    mut replay: ResMut<'_, InstantReplay>,
+    scene: ReplayScene<'_, '_>,
+    time: Res<'_, Time>,
+) {
+    if replay.is_playing() {
+        return;
+    }
+
+    let now = time.elapsed_secs();
+    if let Some(frame) = scene.capture(now) {
+        replay.frames.push_back(frame);
+    }
+    while replay
+        .frames
+        .front()
+        .is_some_and(|frame| now - frame.at > REPLAY_SECS)
+    {
+        replay.frames.pop_front();
+    }
+}
+
+/// Starts a replay after a strike, holding the pinsetter and physics until it's over. The last
+/// strike of a game isn't replayed since the final score covers the lane
+fn start_replay(
+    mut commands: Commands<'_, '_>,
+    mut celebrations: EventReader<'_, '_, Celebration>,
+    mut replay: ResMut<'_, InstantReplay>,
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    mut scene: ReplayScene<'_, '_>,
+    state: Res<'_, BowlingStateWrapper>,
+    time: Res<'_, Time>,
+) {
+    let strike = celebrations
+        .read()
+        .any(|celebration| celebration.is_strike());
+    if !strike || replay.is_playing() || replay.frames.is_empty() || state.is_game_over() {
+        return;
+    }
+
+    let (Some(live), Ok(camera)) = (
+        scene.capture(time.elapsed_secs()),
+        scene.camera.get_single_mut(),
+    ) else {
+        return;
+    };
+    let camera = std::mem::replace(
+        camera.into_inner(),
+        Transform::from_translation(REPLAY_CAMERA).looking_at(REPLAY_TARGET, Vec3::Y),
+    );
+
+    let label = commands
+        .spawn((
+            Text::new("Instant Replay"),
+            TextFont::from_font_size(32.0),
+            TextColor(Color::srgb(1.0, 0.85, 0.1)),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                right: Val::Px(20.0),
+                ..default()
+            },
+            ReplayLabel,
+        ))
+        .id();
+
+    pinsetter.hold();
+    scene.set_physics(false);
+    replay.playback = Some(Playback {
+        elapsed: 0.0,
+        live,
+        camera,
+        label,
+    });
+}
+
+/// Steps through the recording in slow motion, putting everything back once it's over
+fn play_replay(
+    mut commands: Commands<'_, '_>,
+    mut replay: ResMut<'_, InstantReplay>,
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    mut scene: ReplayScene<'_, '_>,
+    time: Res<'_, Time>,
+) {
+    let Some(playback) = &mut replay.playback else {
+        return;
+    };
+    playback.elapsed += time.delta_secs() * REPLAY_SPEED;
+    let elapsed = playback.elapsed;
+
+    let (Some(start), Some(end)) = (replay.frames.front(), replay.frames.back()) else {
+        return;
+    };
+    let at = start.at + elapsed;
+
+    if at < end.at {
+        if let Some(frame) = replay.frame_at(at) {
+            scene.pose(&frame);
+        }
+        return;
+    }
+
+    let Some(playback) = replay.playback.take() else {
+        return;
+    };
+    scene.pose(&playback.live);
+    if let Ok(mut camera) = scene.camera.get_single_mut() {
+        *camera = playback.camera;
+    }
+    commands.entity(playback.label).despawn_recursive();
+    scene.set_physics(true);
+    pinsetter.release();
+    replay.frames.clear();
+}
@@ -0,0 +1,100 @@
+//! Saving an unfinished standard game between throws and resuming it after a reload
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use spjorts_core::{
+    diagnostics::DiagnosticSender, menu::Menu, snapshot::SaveSlot, spectator::is_playing,
+};
+
+use crate::{
+    phase::BowlingPhase,
+    pinsetter::Pinsetter,
+    practice::GameMode,
+    turns::{sync_players, BowlingState, BowlingStateWrapper},
+    variant::BowlingVariant,
+};
+
+/// Plugin that keeps the save up to date and resumes games handed in from JavaScript
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(BowlingPhase::Aiming),
+            store_save
+                .run_if(resource_equals(GameMode::Standard))
+                .run_if(is_playing),
+        )
+        .add_systems(
+            OnEnter(BowlingPhase::GameOver),
+            clear_save.run_if(is_playing),
+        )
+        .add_systems(Update, load_save.after(sync_players).run_if(is_playing));
+    }
+}
+
+/// Everything set up for a game that a resumed save skips past
+#[derive(SystemParam)]
+struct Lobby<'w, 's> {
+    /// Used to close the pre-game menus
+    commands: Commands<'w, 's>,
+    /// Open pre-game menus
+    menus: Query<'w, 's, Entity, With<Menu>>,
+    /// Mode picked in the mode menu
+    mode: ResMut<'w, GameMode>,
+    /// Variant picked in the variant menu
+    variant: ResMut<'w, BowlingVariant>,
+    /// Sets the resumed frame's standing pins
+    pinsetter: ResMut<'w, Pinsetter>,
+    /// Phase to move to once the save is loaded
+    next_phase: ResMut<'w, NextState<BowlingPhase>>,
+}
+
+impl Lobby<'_, '_> {
+    /// Closes the menus and gets the lane ready for the next throw of a resumed game
+    fn resume(&mut self, variant: BowlingVariant, standing: &[u8]) {
+        for menu in &self.menus {
+            self.commands.entity(menu).despawn_recursive();
+        }
+
+        *self.mode = GameMode::Standard;
+        self.variant.set_if_neq(variant);
+        self.pinsetter.rack(standing);
+        self.next_phase.set(BowlingPhase::Aiming);
+    }
+}
+
+/// Saves the game each time the lane is ready for the next throw
+fn store_save(bowling_state: Res<'_, BowlingStateWrapper>, slot: Res<'_, SaveSlot>) {
+    bowling_state.save(&slot);
+}
+
+/// Forgets the save once the game is over, there's nothing left to resume
+fn clear_save(slot: Res<'_, SaveSlot>) {
+    slot.clear();
+}
+
+/// Loads a save handed in from JavaScript, skipping the pre-game menus and re-racking the pins
+/// that were standing
+fn load_save(
+    slot: Res<'_, SaveSlot>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+    diagnostics: Res<'_, DiagnosticSender>,
+    mut lobby: Lobby<'_, '_>,
+) {
+    let state = match slot.take_import::<BowlingState>() {
+        None => return,
+        Some(Ok(state)) if state.is_resumable() => state,
+        Some(Ok(_)) => {
+            diagnostics.warn("Ignored a save that isn't between throws of an unfinished game");
+            return;
+        }
+        Some(Err(err)) => {
+            diagnostics.warn(format!("Ignored an unreadable save: {err}"));
+            return;
+        }
+    };
+
+    lobby.resume(state.get_variant(), &state.standing());
+    bowling_state.load(state);
+    diagnostics.info(bowling_state.render());
+}
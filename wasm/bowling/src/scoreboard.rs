@@ -0,0 +1,260 @@
+//! Scoreboard grid showing every player's marks and running totals
+
+use bevy::prelude::*;
+use spjorts_core::players::PlayerRegistry;
+
+use crate::turns::{BowlingStateWrapper, FRAME_COUNT};
+
+/// Width of every scoreboard column, relative to the viewport so the board fits small screens
+const CELL_WIDTH: Val = Val::Vw(7.5);
+/// Most characters of a player's name that fit in their label
+const LABEL_LEN: usize = 6;
+/// Font size of marks and totals
+const CELL_FONT_SIZE: f32 = 16.0;
+/// Background of the whole board
+const BOARD_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.75);
+/// Background of cells that aren't highlighted
+const CELL_COLOR: Color = Color::NONE;
+/// Background of the current player's name and frame
+const HIGHLIGHT_COLOR: Color = Color::srgb(0.2, 0.35, 0.7);
+/// Cell borders
+const BORDER_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+/// Most marks a frame can have
+const MARKS_PER_FRAME: usize = 3;
+/// Circle drawn around a mark that left a split
+const SPLIT_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
+
+/// Root node of the scoreboard
+#[derive(Component)]
+pub struct Scorecard;
+
+/// A player's row on the scoreboard
+#[derive(Component)]
+struct PlayerRow;
+
+/// Text on the scoreboard kept in sync with the bowling state
+#[derive(Component, Clone, Copy)]
+enum ScoreText {
+    /// A player's name
+    Label(usize),
+    /// A player's mark for a throw in a frame
+    Mark(usize, usize, usize),
+    /// A player's running total after a frame
+    Total(usize, usize),
+}
+
+/// Circle around a player's mark for a throw in a frame, drawn when the throw left a split
+#[derive(Component, Clone, Copy)]
+struct SplitCircle(usize, usize, usize);
+
+/// Cells highlighted when it's their player's turn
+#[derive(Component, Clone, Copy)]
+enum ScoreHighlight {
+    /// A player's name cell
+    Player(usize),
+    /// A player's cell for a frame
+    Frame(usize, usize),
+}
+
+/// Plugin that draws the scoreboard from the bowling state
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_scoreboard)
+            .add_systems(Update, (sync_player_rows, update_scoreboard).chain());
+    }
+}
+
+/// A bordered scoreboard cell
+fn cell() -> (Node, BorderColor, BackgroundColor) {
+    (
+        Node {
+            width: CELL_WIDTH,
+            min_width: Val::Px(28.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(1.0)),
+            padding: UiRect::vertical(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(BORDER_COLOR),
+        BackgroundColor(CELL_COLOR),
+    )
+}
+
+/// Scoreboard text with the given contents
+fn cell_text(text: impl Into<String>) -> (Text, TextFont, TextColor) {
+    (
+        Text::new(text),
+        TextFont::from_font_size(CELL_FONT_SIZE),
+        TextColor::WHITE,
+    )
+}
+
+/// Spawns the scoreboard with its header row, player rows are added as players join
+fn spawn_scoreboard(mut commands: Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(BOARD_COLOR),
+            Scorecard,
+        ))
+        .with_children(|board| {
+            board
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                })
+                .with_children(|header| {
+                    header.spawn(cell()).with_child(cell_text("Plr"));
+                    for frame in 1..=FRAME_COUNT {
+                        header
+                            .spawn(cell())
+                            .with_child(cell_text(frame.to_string()));
+                    }
+                });
+        });
+}
+
+/// Rebuilds the player rows whenever the number of players changes
+fn sync_player_rows(
+    mut commands: Commands<'_, '_>,
+    board: Query<'_, '_, Entity, With<Scorecard>>,
+    rows: Query<'_, '_, Entity, With<PlayerRow>>,
+    registry: Res<'_, PlayerRegistry>,
+) {
+    let Ok(board) = board.get_single() else {
+        return;
+    };
+    if rows.iter().count() == registry.total() {
+        return;
+    }
+
+    for row in &rows {
+        commands.entity(row).despawn_recursive();
+    }
+
+    commands.entity(board).with_children(|board| {
+        for player in 0..registry.total() {
+            board
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    PlayerRow,
+                ))
+                .with_children(|row| {
+                    row.spawn((cell(), ScoreHighlight::Player(player)))
+                        .with_child((cell_text(""), ScoreText::Label(player)));
+
+                    for frame in 0..FRAME_COUNT {
+                        row.spawn((cell(), ScoreHighlight::Frame(player, frame)))
+                            .with_children(|cell| {
+                                cell.spawn(Node {
+                                    flex_direction: FlexDirection::Row,
+                                    ..default()
+                                })
+                                .with_children(|marks| {
+                                    for throw in 0..MARKS_PER_FRAME {
+                                        marks
+                                            .spawn((
+                                                Node {
+                                                    border: UiRect::all(Val::Px(1.0)),
+                                                    padding: UiRect::horizontal(Val::Px(2.0)),
+                                                    ..default()
+                                                },
+                                                BorderRadius::MAX,
+                                                BorderColor(Color::NONE),
+                                                SplitCircle(player, frame, throw),
+                                            ))
+                                            .with_child((
+                                                cell_text(""),
+                                                ScoreText::Mark(player, frame, throw),
+                                            ));
+                                    }
+                                });
+                                cell.spawn((cell_text(""), ScoreText::Total(player, frame)));
+                            });
+                    }
+                });
+        }
+    });
+}
+
+/// Fills in marks, totals and names, circles splits, and highlights whose turn and frame it is
+fn update_scoreboard(
+    mut texts: Query<'_, '_, (&mut Text, &ScoreText)>,
+    mut highlights: Query<'_, '_, (&mut BackgroundColor, &ScoreHighlight)>,
+    mut circles: Query<'_, '_, (&mut BorderColor, &SplitCircle)>,
+    state: Res<'_, BowlingStateWrapper>,
+    registry: Res<'_, PlayerRegistry>,
+) {
+    let snapshot = state.snapshot();
+
+    for (mut text, score_text) in &mut texts {
+        let contents = match *score_text {
+            ScoreText::Label(player) => match snapshot.names.get(player).cloned().flatten() {
+                Some(name) => name.chars().take(LABEL_LEN).collect(),
+                None if registry.bot().is_some() && player == registry.count() => "CPU".to_string(),
+                None => format!("P{}", player + 1),
+            },
+            ScoreText::Mark(player, frame, throw) => snapshot
+                .frames
+                .get(player)
+                .and_then(|frames| frames.get(frame))
+                .and_then(|marks| marks.chars().nth(throw))
+                .map(String::from)
+                .unwrap_or_default(),
+            ScoreText::Total(player, frame) => snapshot
+                .totals
+                .get(player)
+                .and_then(|totals| totals.get(frame).copied().flatten())
+                .map(|total| total.to_string())
+                .unwrap_or_default(),
+        };
+
+        if text.0 != contents {
+            text.0 = contents;
+        }
+    }
+
+    for (mut border, &SplitCircle(player, frame, throw)) in &mut circles {
+        let split = snapshot
+            .splits
+            .get(player)
+            .and_then(|splits| splits.get(frame).copied().flatten());
+        let color = if split == Some(throw) {
+            SPLIT_COLOR
+        } else {
+            Color::NONE
+        };
+
+        if border.0 != color {
+            border.0 = color;
+        }
+    }
+
+    for (mut background, highlight) in &mut highlights {
+        let highlighted = match *highlight {
+            ScoreHighlight::Player(player) => player == snapshot.turn,
+            ScoreHighlight::Frame(player, frame) => {
+                player == snapshot.turn && frame + 1 == snapshot.frame
+            }
+        };
+        let color = if highlighted {
+            HIGHLIGHT_COLOR
+        } else {
+            CELL_COLOR
+        };
+
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
@@ -4,19 +4,32 @@ use std::f32::consts::PI;
 
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::{
-    Ccd, Collider, ColliderMassProperties, Friction, GravityScale, Restitution, RigidBody, Velocity,
+    ActiveEvents, Ccd, Collider, ColliderMassProperties, ContactForceEventThreshold, ExternalForce,
+    Friction, GravityScale, Restitution, RigidBody, Sleeping, Velocity,
 };
+use spjorts_core::physics::PhysicsTuning;
 
 pub mod ball;
+pub mod gutter;
+pub mod lathe;
+pub mod oil;
 pub mod pin;
 
-pub use ball::Ball;
-pub use pin::Pin;
+pub use ball::{Ball, BallSpec, FingerHole};
+pub use gutter::{Gutter, Gutterball};
+pub use oil::{LaneZone, OilPattern};
+pub use pin::{Pin, PinToppled};
 
 /// Lane length
-const LANE_LENGTH: f32 = 30.0;
+pub const LANE_LENGTH: f32 = 30.0;
+/// Where the lane starts
+pub const LANE_START_Z: f32 = -10.0;
 /// Lane width
 pub const LANE_WIDTH: f32 = 3.0;
+/// Gutter width
+pub const GUTTER_WIDTH: f32 = 0.6;
+/// How far below the lane surface the gutter floor sits
+const GUTTER_DEPTH: f32 = 0.25;
 
 /// Number of pins in a standard arrangement
 const PIN_COUNT: usize = 10;
@@ -30,14 +43,26 @@ pub const BALL_START_Z: f32 = -5.0;
 /// How fast the ball moves once “released”
 pub const BALL_SPEED: f32 = 10.0;
 
-/// Pin radius
-const PIN_RADIUS: f32 = 0.15;
-/// Pin height
-const PIN_HEIGHT: f32 = 0.8;
+/// How far past where the ball starts the aiming dots sit
+const DOTS_OFFSET_Z: f32 = 2.0;
+/// How far past where the ball starts the aiming arrows sit
+const ARROWS_OFFSET_Z: f32 = 5.0;
+/// How many aiming arrows span the lane
+const ARROW_COUNT: usize = 7;
 
-/// Scorecard identifying Component
-#[derive(Component)]
-pub struct Scorecard;
+/// Ball radius
+pub const BALL_RADIUS: f32 = 0.3;
+
+/// Tenpin radius, which also sets the spacing between pins for every variant
+pub const PIN_RADIUS: f32 = 0.15;
+/// Smallest contact force on a pin that is loud enough to hear
+const PIN_IMPACT_THRESHOLD: f32 = 20.0;
+/// Tenpin height
+pub const PIN_HEIGHT: f32 = 0.8;
+/// How far above the pin deck its lights hang
+const DECK_LIGHT_HEIGHT: f32 = 3.5;
+/// Gap between the power meter and the edges of the screen
+pub const POWER_METER_MARGIN: f32 = 24.0;
 
 /// Scorecard bg identifying Component
 #[derive(Component)]
@@ -51,34 +76,147 @@ pub struct Hideable;
 #[derive(Component)]
 pub struct FinalScore;
 
+/// The vertical power meter, moved to the left for left-handed play
+#[derive(Component)]
+pub struct PowerMeter;
+
+/// Fill of the vertical power meter
+#[derive(Component)]
+pub struct PowerMeterFill;
+
+/// Ball picker shown before the first throw
+#[derive(Component)]
+pub struct BallPicker;
+
+/// Short lived message shown after a notable throw
+#[derive(Component)]
+pub struct ThrowBanner {
+    /// Time left before the banner hides itself
+    pub timer: Timer,
+}
+
 /// Spawns the lane, the ball, and pins
 pub fn setup(
     mut commands: Commands<'_, '_>,
     mut meshes: ResMut<'_, Assets<Mesh>>,
     mut materials: ResMut<'_, Assets<StandardMaterial>>,
-    asset_server: Res<'_, AssetServer>,
+    physics: Res<'_, PhysicsTuning>,
+    oil: Res<'_, OilPattern>,
 ) {
-    let bowling_pin = asset_server.load("/frontend/sprites/bowling/pin.png");
-    let bowling_ball = asset_server.load("/frontend/sprites/bowling/ball.png");
-
     // Spawn Lane
     commands.spawn((
         Mesh3d(meshes.add(Cuboid::new(LANE_WIDTH, 0.1, LANE_LENGTH))),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::hsl(44.0, 0.31, 0.71),
             perceptual_roughness: 0.1,
+            reflectance: 0.6,
             ..default()
         })),
-        Transform::from_xyz(0.0, -0.05, LANE_LENGTH * 0.5 - 10.0),
+        Transform::from_xyz(0.0, -0.05, LANE_START_Z + LANE_LENGTH * 0.5),
         Name::new("Lane"),
-        Collider::cuboid(LANE_WIDTH * 0.5, 0.05, LANE_LENGTH * 0.5),
-        Restitution::coefficient(0.01),
-        RigidBody::Fixed,
-        Friction::coefficient(0.04),
-        Visibility::Hidden,
+        Visibility::Visible,
     ));
 
+    // Spawn the lane's friction zones, oiled according to the current pattern
+    let zone_length = LANE_LENGTH / oil::ZONE_COUNT as f32;
+    for (index, multiplier) in oil.multipliers().into_iter().enumerate() {
+        commands.spawn((
+            Transform::from_xyz(
+                0.0,
+                -0.05,
+                LANE_START_Z + zone_length * (index as f32 + 0.5),
+            ),
+            Name::new(format!("Lane Zone {index}")),
+            LaneZone { index },
+            Collider::cuboid(LANE_WIDTH * 0.5, 0.05, zone_length * 0.5),
+            Restitution::coefficient(physics.ground.restitution),
+            RigidBody::Fixed,
+            Friction::coefficient(physics.ground.friction * multiplier),
+        ));
+    }
+
+    // Spawn aiming dots and arrows, evenly spread across the lane like a real one
+    let marker_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.45, 0.1, 0.05),
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let spacing = LANE_WIDTH / (ARROW_COUNT + 1) as f32;
+    for idx in 0..ARROW_COUNT {
+        let x_pos = (idx as f32 + 1.0) * spacing - LANE_WIDTH * 0.5;
+        // The middle arrow sits furthest down the lane, making a V like a real lane
+        let stagger = (ARROW_COUNT / 2).abs_diff(idx) as f32 * -0.3;
+
+        commands.spawn((
+            Mesh3d(meshes.add(Triangle3d::new(
+                Vec3::new(0.0, 0.0, 0.35),
+                Vec3::new(0.08, 0.0, 0.0),
+                Vec3::new(-0.08, 0.0, 0.0),
+            ))),
+            MeshMaterial3d(marker_material.clone()),
+            Transform::from_xyz(x_pos, 0.01, BALL_START_Z + ARROWS_OFFSET_Z + stagger),
+            Name::new(format!("Arrow {idx}")),
+            Hideable,
+        ));
+
+        commands.spawn((
+            Mesh3d(meshes.add(Circle::new(0.04))),
+            MeshMaterial3d(marker_material.clone()),
+            Transform::from_xyz(x_pos, 0.01, BALL_START_Z + DOTS_OFFSET_Z)
+                .with_rotation(Quat::from_rotation_x(-PI / 2.0)),
+            Name::new(format!("Dot {idx}")),
+            Hideable,
+        ));
+    }
+
+    // Spawn gutters, with an outer wall so the ball can't hop out
+    let gutter_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 0.2, 0.22),
+        perceptual_roughness: 0.4,
+        metallic: 0.6,
+        ..default()
+    });
+    for side in [-1.0, 1.0] {
+        let x_pos = side * (LANE_WIDTH + GUTTER_WIDTH) * 0.5;
+        let half_extents = Vec3::new(GUTTER_WIDTH * 0.5, 0.05, LANE_LENGTH * 0.5);
+
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(half_extents * 2.0))),
+            MeshMaterial3d(gutter_material.clone()),
+            Transform::from_xyz(
+                x_pos,
+                -GUTTER_DEPTH - 0.05,
+                LANE_START_Z + LANE_LENGTH * 0.5,
+            ),
+            Name::new("Gutter"),
+            Gutter::new(half_extents),
+            Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            Restitution::coefficient(physics.ground.restitution),
+            RigidBody::Fixed,
+            Friction::coefficient(physics.ground.friction),
+            Visibility::Visible,
+        ));
+
+        commands.spawn((
+            Transform::from_xyz(
+                side * (LANE_WIDTH * 0.5 + GUTTER_WIDTH + 0.05),
+                0.0,
+                LANE_START_Z + LANE_LENGTH * 0.5,
+            ),
+            Name::new("Gutter Wall"),
+            Collider::cuboid(0.05, 0.5, LANE_LENGTH * 0.5),
+            RigidBody::Fixed,
+        ));
+    }
+
     // Spawn pins
+    let pin_mesh = meshes.add(pin::pin_mesh(PIN_RADIUS, PIN_HEIGHT));
+    let pin_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 0.25,
+        reflectance: 0.6,
+        ..default()
+    });
     let rows = how_many_rows(PIN_COUNT);
     for row in 1..=rows {
         let z_pos = PIN_START_Z + (row as f32);
@@ -89,28 +227,27 @@ pub fn setup(
             let x_pos = start_pos + ((pin as f32) * PIN_RADIUS * 4.0);
 
             let point = Transform::from_xyz(x_pos, PIN_HEIGHT * 0.5 + 0.05, z_pos);
-            let material_handle = materials.add(StandardMaterial {
-                base_color_texture: Some(bowling_pin.clone()),
-                alpha_mode: AlphaMode::Blend,
-                cull_mode: None,
-                unlit: true,
-                ..default()
-            });
-
+            // Pins are numbered left to right from the bowler's view, which looks down +Z so
+            // their left is +X
+            let number = (row * (row - 1) / 2 + (row - pin)) as u8;
             commands.spawn((
-                Mesh3d(meshes.add(Rectangle::new(PIN_HEIGHT, PIN_HEIGHT))),
-                MeshMaterial3d(material_handle),
-                point.clone().with_rotation(Quat::from_rotation_y(PI)),
-                Pin::new(point),
+                Mesh3d(pin_mesh.clone()),
+                MeshMaterial3d(pin_material.clone()),
+                point,
+                Pin::new(point, number),
                 Name::new(format!("Pin {pin} in Row {row}")),
-                Collider::cylinder(PIN_HEIGHT * 0.5, PIN_RADIUS),
+                pin::pin_collider(PIN_RADIUS, PIN_HEIGHT),
                 RigidBody::Dynamic,
-                Restitution::coefficient(0.8),
-                Friction::coefficient(0.6),
-                GravityScale(0.9),
-                ColliderMassProperties::Density(0.8),
-                Velocity::linear(Vec3::ZERO),
-                Ccd::enabled(),
+                Restitution::coefficient(physics.target.restitution),
+                Friction::coefficient(physics.target.friction),
+                GravityScale(physics.target.gravity_scale),
+                ColliderMassProperties::Density(physics.target.density),
+                (Velocity::linear(Vec3::ZERO), Sleeping::default()),
+                (
+                    Ccd::enabled(),
+                    ActiveEvents::CONTACT_FORCE_EVENTS | ActiveEvents::COLLISION_EVENTS,
+                    ContactForceEventThreshold(PIN_IMPACT_THRESHOLD),
+                ),
                 Visibility::Visible,
                 Hideable,
             ));
@@ -118,54 +255,128 @@ pub fn setup(
     }
 
     let ball_material_handle = materials.add(StandardMaterial {
-        base_color: Color::srgb(1.0, 0.0, 0.0),
-        base_color_texture: Some(bowling_ball.clone()),
-        alpha_mode: AlphaMode::Blend,
-        cull_mode: None,
-        unlit: true,
+        base_color: Color::srgb(0.6, 0.0, 0.05),
+        perceptual_roughness: 0.15,
+        metallic: 0.1,
+        clearcoat: 1.0,
+        ..default()
+    });
+
+    let hole_mesh = meshes.add(Sphere::new(1.0).mesh().uv(12, 8));
+    let hole_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.03, 0.03, 0.03),
+        perceptual_roughness: 0.9,
         ..default()
     });
 
     // Spawn Ball
-    commands.spawn((
-        //Mesh3d(meshes.add(Sphere::new(0.3))),
-        Mesh3d(meshes.add(Rectangle::new(0.6, 0.6))),
-        MeshMaterial3d(ball_material_handle),
-        Transform::from_xyz(0.0, 0.3, BALL_START_Z).looking_at(Vec3::ZERO, Vec3::Y),
-        Ball::default(),
-        Name::new("Ball"),
-        RigidBody::KinematicPositionBased,
-        Collider::ball(0.3),
-        Restitution::coefficient(0.4),
-        GravityScale(1.0),
-        Friction::coefficient(0.6),
-        Velocity::linear(Vec3::ZERO),
-        ColliderMassProperties::Density(1.2),
-        Ccd::enabled(),
-        Visibility::Visible,
-        Hideable,
-    ));
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Sphere::new(BALL_RADIUS).mesh().uv(32, 18))),
+            MeshMaterial3d(ball_material_handle),
+            Transform::from_xyz(0.0, BALL_RADIUS, BALL_START_Z).looking_at(Vec3::ZERO, Vec3::Y),
+            (Ball::default(), BallSpec::default()),
+            Name::new("Ball"),
+            RigidBody::KinematicPositionBased,
+            Collider::ball(BALL_RADIUS),
+            Restitution::coefficient(physics.projectile.restitution),
+            GravityScale(physics.projectile.gravity_scale),
+            Friction::coefficient(physics.projectile.friction),
+            (Velocity::linear(Vec3::ZERO), ExternalForce::default()),
+            ColliderMassProperties::Density(physics.projectile.density),
+            Ccd::enabled(),
+            Visibility::Visible,
+            Hideable,
+        ))
+        .with_children(|ball| {
+            for hole in FingerHole::all() {
+                ball.spawn((
+                    Mesh3d(hole_mesh.clone()),
+                    MeshMaterial3d(hole_material.clone()),
+                    hole.transform(BALL_RADIUS),
+                    hole,
+                ));
+            }
+        });
 
     commands.spawn((
         Camera3d::default(),
         Transform::from_xyz(0.0, 3.0, -10.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
 
-    // Spawn UI Camera
-    commands.spawn(Camera2d::default());
+    // Spawn UI Camera, drawn over the lane without clearing it
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: 1,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+    ));
     commands.spawn((
-        Text::new(":D"),
+        Text::new(""),
+        TextFont::from_font_size(48.0),
         TextColor::WHITE,
-        BackgroundColor(Color::BLACK),
-        Scorecard,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+        ThrowBanner {
+            timer: Timer::from_seconds(1.5, TimerMode::Once),
+        },
     ));
 
     commands.spawn((
-        Sprite::from_image(asset_server.load("/frontend/sprites/bowling/bg.png")),
-        Visibility::Visible,
-        Hideable,
+        Text::new(""),
+        TextFont::from_font_size(32.0),
+        TextColor::WHITE,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(15.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        BallPicker,
     ));
 
+    // Spawn power meter
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(POWER_METER_MARGIN),
+                bottom: Val::Px(POWER_METER_MARGIN),
+                width: Val::Px(24.0),
+                height: Val::Px(200.0),
+                flex_direction: FlexDirection::ColumnReverse,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            BorderColor(Color::WHITE),
+            Visibility::Visible,
+            Hideable,
+            PowerMeter,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(0.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.0, 1.0, 0.0)),
+                PowerMeterFill,
+            ));
+        });
+
     commands
         .spawn((
             Node {
@@ -184,9 +395,28 @@ pub fn setup(
         });
 
     commands.spawn((
-        DirectionalLight::default(),
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
         Transform::from_xyz(0.0, 3.0, -13.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
+
+    // Soft lights over the pin deck, left shadowless since the directional light already casts
+    // the pins' shadows
+    for x_pos in [-LANE_WIDTH * 0.25, LANE_WIDTH * 0.25] {
+        commands.spawn((
+            PointLight {
+                intensity: 150_000.0,
+                radius: 0.5,
+                range: 12.0,
+                ..default()
+            },
+            Transform::from_xyz(x_pos, DECK_LIGHT_HEIGHT, PIN_START_Z + 1.5),
+            Name::new("Deck Light"),
+        ));
+    }
 }
 
 /// Calculates how many rows a bowling lane should have
@@ -1,19 +1,154 @@
 //! Ball logic and velocity calculation methods
 
 use bevy::{
-    math::{Quat, Vec3},
-    prelude::Component,
+    math::{EulerRot, Quat, Vec3},
+    prelude::{Component, Transform},
 };
+use spjorts_core::swing::SwingSampler;
+
+/// How much lateral force is applied per radian per second of wrist roll at release
+pub const HOOK_SCALE: f32 = 0.05;
+
+/// Upper bound on the hook force in either direction
+pub const MAX_HOOK: f32 = 4.0;
+
+/// Slowest a swung ball can be released at
+pub const MIN_SPEED: f32 = 2.0;
+
+/// Fastest a swung medium ball can be released at
+pub const MAX_SPEED: f32 = 15.0;
+
+/// Scaling applied to the swing's angular velocity to get a release speed
+pub const SPEED_SCALE: f32 = 10.0;
+
+/// Widest the launch direction can be turned from straight down the lane, in radians
+pub const MAX_AIM_ANGLE: f32 = 0.15;
+
+/// Balls players can pick from before the game, lightest first
+pub const BALL_SPECS: [BallSpec; 3] = [
+    BallSpec {
+        name: "Light",
+        radius: 0.27,
+        density: 0.7,
+        max_speed: 17.0,
+    },
+    BallSpec {
+        name: "Medium",
+        radius: 0.3,
+        density: 1.0,
+        max_speed: MAX_SPEED,
+    },
+    BallSpec {
+        name: "Heavy",
+        radius: 0.33,
+        density: 1.4,
+        max_speed: 13.0,
+    },
+];
+
+/// Small, heavy-for-their-size balls used for candlepin and duckpin, lightest first
+pub const SMALL_BALL_SPECS: [BallSpec; 3] = [
+    BallSpec {
+        name: "Light",
+        radius: 0.15,
+        density: 1.6,
+        max_speed: 17.0,
+    },
+    BallSpec {
+        name: "Medium",
+        radius: 0.16,
+        density: 2.0,
+        max_speed: MAX_SPEED,
+    },
+    BallSpec {
+        name: "Heavy",
+        radius: 0.17,
+        density: 2.4,
+        max_speed: 13.0,
+    },
+];
+
+/// Where the finger and thumb holes point out from the ball's center, in the ball's own space
+const FINGER_HOLES: [Vec3; 3] = [
+    Vec3::new(-0.2, 0.95, 0.15),
+    Vec3::new(0.2, 0.95, 0.15),
+    Vec3::new(0.0, 0.85, -0.5),
+];
+
+/// Size of a finger hole as a fraction of the ball's radius
+const FINGER_HOLE_SCALE: f32 = 0.16;
+
+/// A finger or thumb hole on the ball, so it can be seen rolling
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FingerHole(Vec3);
+
+impl FingerHole {
+    /// Every hole drilled into a ball
+    pub fn all() -> impl Iterator<Item = Self> {
+        FINGER_HOLES
+            .into_iter()
+            .map(|direction| Self(direction.normalize()))
+    }
+
+    /// Where a hole made from a unit sphere sits on a ball of the given radius
+    pub fn transform(&self, radius: f32) -> Transform {
+        let size = radius * FINGER_HOLE_SCALE;
+        Transform::from_translation(self.0 * (radius - size * 0.5)).with_scale(Vec3::splat(size))
+    }
+}
+
+/// Size and weight of the ball in play
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct BallSpec {
+    /// Name shown while picking
+    pub name: &'static str,
+    /// Ball radius
+    pub radius: f32,
+    /// Multiplier on the projectile density, heavier balls carry more into the pins
+    pub density: f32,
+    /// Fastest this ball can be released at
+    pub max_speed: f32,
+}
+
+impl Default for BallSpec {
+    fn default() -> Self {
+        BALL_SPECS[1]
+    }
+}
+
+impl BallSpec {
+    /// The next heavier ball of the same kind, wrapping around to the lightest
+    pub fn next(&self) -> Self {
+        let specs = [&BALL_SPECS, &SMALL_BALL_SPECS]
+            .into_iter()
+            .find(|specs| specs.contains(self))
+            .unwrap_or(&BALL_SPECS);
+        let idx = specs
+            .iter()
+            .position(|spec| spec == self)
+            .unwrap_or_default();
+        specs[(idx + 1) % specs.len()]
+    }
+
+    /// Spin that rolls this ball along `linvel` without skidding
+    pub fn rolling_spin(&self, linvel: Vec3) -> Vec3 {
+        Vec3::Y.cross(linvel) / self.radius
+    }
+}
 
 /// Marks the ball entity
 #[derive(Component)]
 pub struct Ball {
-    /// Whether the ball has been “released”
-    pub released: bool,
     /// Current velocity
     pub velocity: Vec3,
-    /// Current rotation
-    pub rotations: Vec<Quat>,
+    /// Recent rotations, measured for release speed and hook
+    pub swing: SwingSampler,
+    /// Sideways force the ball curves with once released, positive hooks towards +X
+    pub hook: f32,
+    /// Whether the ball has dropped into a gutter this throw
+    pub in_gutter: bool,
+    /// How far in radians the launch direction is turned about the vertical, positive towards +X
+    pub aim: f32,
     /// If the ball is in X-axis toggle mode:
     /// * `None` if stopped,
     /// * `Some(true)` if moving positively towards (0 + LANE_WIDTH / 2)
@@ -24,38 +159,64 @@ pub struct Ball {
 impl Default for Ball {
     fn default() -> Self {
         Self {
-            released: Default::default(),
             velocity: Default::default(),
-            rotations: Default::default(),
+            swing: Default::default(),
+            hook: 0.0,
+            in_gutter: false,
+            aim: 0.0,
             moving: Some(true),
         }
     }
 }
 
 impl Ball {
-    /// Uses the ball's rotational history to get a speed it would have at release on that angle
-    pub fn get_speed(&self) -> f32 {
-        if self.rotations.len() < 2 {
+    /// Turns the launch direction to the controller's yaw, clamped to [`MAX_AIM_ANGLE`] either way
+    pub fn aim_at(&mut self, yaw: f32) {
+        self.aim = yaw.clamp(-MAX_AIM_ANGLE, MAX_AIM_ANGLE);
+    }
+
+    /// Direction the ball launches in when released with the given rotation
+    pub fn launch_direction(&self, rotation: Quat) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * (rotation * Vec3::Z)
+    }
+
+    /// Records a rotation read at `at` seconds since startup for measuring the swing
+    pub fn record_rotation(&mut self, rotation: Quat, at: f32) {
+        self.swing.record(rotation, at);
+    }
+
+    /// How fast the controller swung over the recent rotations in radians per second, if there's
+    /// enough to measure
+    pub fn angular_velocity(&self) -> Option<f32> {
+        self.swing.angular_velocity()
+    }
+
+    /// Uses how fast the ball swung over the recent rotations to get a speed it would have at
+    /// release on that angle, capped by how fast the chosen ball can go
+    pub fn get_speed(&self, spec: &BallSpec) -> f32 {
+        let Some(angular_velocity) = self.angular_velocity() else {
             return 1.0;
-        }
+        };
 
-        // TODO: Delta time would not be 60fps, but I'm not sure of the best way to get a timestamp
-        // unless we register rotations as (Quat, Timestamp) or something, lets just see how bad
-        // this is first
-        let delta_time = 1.0 / 60.0;
+        let speed = SPEED_SCALE * angular_velocity;
 
-        let q1 = self.rotations[self.rotations.len() - 2];
-        let q2 = self.rotations[self.rotations.len() - 1];
+        speed.clamp(MIN_SPEED, spec.max_speed)
+    }
 
-        let dot_product = q1.dot(q2).clamp(-1.0, 1.0);
-        let angular_velocity = (2.0 * dot_product.acos()) / delta_time;
+    /// How hard the ball would be thrown if released now, from 0.0 to 1.0
+    pub fn get_power(&self, spec: &BallSpec) -> f32 {
+        ((self.get_speed(spec) - MIN_SPEED) / (spec.max_speed - MIN_SPEED)).clamp(0.0, 1.0)
+    }
 
-        let scaling_factor = 10.0;
-        let min_speed = 2.0;
-        let max_speed = 15.0;
+    /// Uses how fast the wrist was rolling over the recent rotations to get a sideways hook force
+    pub fn get_hook(&self) -> f32 {
+        let (Some(span), Some((first, last))) = (self.swing.span(), self.swing.ends()) else {
+            return 0.0;
+        };
 
-        let speed = scaling_factor * angular_velocity;
+        let roll = |rotation: Quat| rotation.to_euler(EulerRot::XYZ).2;
+        let roll_rate = (roll(last) - roll(first)) / span;
 
-        speed.clamp(min_speed, max_speed)
+        (roll_rate * HOOK_SCALE).clamp(-MAX_HOOK, MAX_HOOK)
     }
 }
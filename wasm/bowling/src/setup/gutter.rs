@@ -0,0 +1,27 @@
+//! Gutters running along both sides of the lane
+
+use bevy::prelude::{Component, Event, Transform, Vec3};
+
+/// Marks a gutter entity
+#[derive(Component)]
+pub struct Gutter {
+    /// Half of the gutter's size along each axis
+    pub half_extents: Vec3,
+}
+
+impl Gutter {
+    /// Creates a gutter with the given half extents
+    pub fn new(half_extents: Vec3) -> Self {
+        Self { half_extents }
+    }
+
+    /// Whether a point lies over the gutter, ignoring height
+    pub fn contains(&self, transform: &Transform, point: Vec3) -> bool {
+        let offset = point - transform.translation;
+        offset.x.abs() <= self.half_extents.x && offset.z.abs() <= self.half_extents.z
+    }
+}
+
+/// Sent when a released ball drops into a gutter
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Gutterball;
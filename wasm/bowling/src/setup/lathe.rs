@@ -0,0 +1,60 @@
+//! Mesh generation by spinning a 2D profile around the Y axis
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    color::{ColorToComponents, LinearRgba},
+    math::{Vec2, Vec3},
+    render::mesh::{Indices, Mesh, PrimitiveTopology},
+};
+
+/// Spins a profile of `(radius, height)` points, ordered bottom to top, around the Y axis.
+/// `color` picks a vertex color for each profile point from its height
+pub fn lathe(profile: &[Vec2], segments: usize, color: impl Fn(f32) -> LinearRgba) -> Mesh {
+    let ring = segments + 1;
+    let mut positions = Vec::with_capacity(profile.len() * ring);
+    let mut normals = Vec::with_capacity(profile.len() * ring);
+    let mut uvs = Vec::with_capacity(profile.len() * ring);
+    let mut colors = Vec::with_capacity(profile.len() * ring);
+
+    for (idx, point) in profile.iter().enumerate() {
+        let prev = profile[idx.saturating_sub(1)];
+        let next = profile[(idx + 1).min(profile.len() - 1)];
+        let tangent = next - prev;
+        let normal = Vec2::new(tangent.y, -tangent.x).normalize_or_zero();
+        let vertex_color = color(point.y).to_f32_array();
+
+        for segment in 0..ring {
+            let angle = segment as f32 / segments as f32 * TAU;
+            let (sin, cos) = angle.sin_cos();
+
+            positions.push([point.x * cos, point.y, point.x * sin]);
+            normals.push(Vec3::new(normal.x * cos, normal.y, normal.x * sin).to_array());
+            uvs.push([
+                segment as f32 / segments as f32,
+                idx as f32 / (profile.len() - 1) as f32,
+            ]);
+            colors.push(vertex_color);
+        }
+    }
+
+    let mut indices = Vec::with_capacity((profile.len() - 1) * segments * 6);
+    for idx in 0..profile.len() - 1 {
+        for segment in 0..segments {
+            let a = (idx * ring + segment) as u32;
+            let b = a + ring as u32;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
@@ -0,0 +1,77 @@
+//! Lane oil patterns and the friction zones they're applied to
+
+use bevy::prelude::{Component, Resource};
+
+/// How many friction zones the lane is split into, from the foul line to the pins
+pub const ZONE_COUNT: usize = 6;
+
+/// Friction multiplier of a fully dry zone, where the ball hooks hardest
+pub const DRY_MULTIPLIER: f32 = 5.0;
+
+/// Oil pattern laid down on the lane. Oily zones let the ball skid straight while dry zones
+/// grip and let it hook
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OilPattern {
+    /// Forgiving pattern with a dry backend that helps the ball hook into the pocket
+    #[default]
+    House,
+    /// Long, flat pattern where the ball barely hooks
+    Sport,
+    /// Short pattern that lets the ball hook early and hard
+    Short,
+}
+
+impl OilPattern {
+    /// Every pattern, in selection order
+    pub const ALL: [Self; 3] = [Self::House, Self::Sport, Self::Short];
+
+    /// The pattern's display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::House => "House",
+            Self::Sport => "Sport",
+            Self::Short => "Short",
+        }
+    }
+
+    /// Friction multiplier of each zone, from the foul line to the pins
+    pub fn multipliers(&self) -> [f32; ZONE_COUNT] {
+        match self {
+            Self::House => [1.0, 1.0, 1.0, 2.0, 4.0, 5.0],
+            Self::Sport => [1.0, 1.0, 1.0, 1.0, 1.5, 3.0],
+            Self::Short => [1.0, 1.0, 3.0, 5.0, 5.0, 5.0],
+        }
+    }
+
+    /// Friction multiplier at a point along the lane, where `progress` runs from 0 at the foul
+    /// line to 1 at the pins
+    pub fn multiplier_at(&self, progress: f32) -> f32 {
+        let zone = (progress * ZONE_COUNT as f32).floor().max(0.0) as usize;
+        self.multipliers()[zone.min(ZONE_COUNT - 1)]
+    }
+
+    /// The next pattern in selection order, wrapping around
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|pattern| pattern == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// The previous pattern in selection order, wrapping around
+    pub fn previous(&self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|pattern| pattern == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// Marks one of the lane's friction zones
+#[derive(Component)]
+pub struct LaneZone {
+    /// Which zone this is, counting from the foul line
+    pub index: usize,
+}
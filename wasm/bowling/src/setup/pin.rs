@@ -1,7 +1,110 @@
-//! Pin struct and reset handling
+//! Pin struct and re-spotting
 
-use bevy::prelude::{Component, Transform};
-use bevy_rapier3d::prelude::Velocity;
+use bevy::{
+    color::{palettes::css::RED, LinearRgba},
+    math::{Quat, Vec2, Vec3},
+    prelude::{Component, Entity, Event, Mesh, Transform},
+};
+use bevy_rapier3d::prelude::{Collider, Velocity};
+
+use super::{lathe::lathe, LANE_LENGTH, LANE_START_Z, LANE_WIDTH};
+
+/// How far in radians a pin can lean from upright and still count as standing
+const TOPPLE_ANGLE: f32 = std::f32::consts::FRAC_PI_4;
+
+/// How far below its spot a pin has to drop to count as knocked off the deck
+const OFF_DECK_DROP: f32 = 0.2;
+
+/// Linear speed below which a pin counts as at rest
+const RESTING_SPEED: f32 = 0.05;
+
+/// Angular speed below which a pin counts as at rest
+const RESTING_SPIN: f32 = 0.1;
+
+/// Pin outline as `(radius, height)` fractions of the pin's max radius and height, from the
+/// base to the crown
+const PIN_PROFILE: [(f32, f32); 12] = [
+    (0.0, -0.5),
+    (0.6, -0.5),
+    (0.87, -0.38),
+    (1.0, -0.19),
+    (0.93, -0.02),
+    (0.67, 0.12),
+    (0.4, 0.22),
+    (0.43, 0.3),
+    (0.53, 0.39),
+    (0.47, 0.46),
+    (0.27, 0.494),
+    (0.0, 0.5),
+];
+
+/// Height fractions the red neck stripes span
+const PIN_STRIPES: [(f32, f32); 2] = [(0.13, 0.17), (0.2, 0.24)];
+
+/// Builds a lathed pin mesh centered on its origin
+pub fn pin_mesh(radius: f32, height: f32) -> Mesh {
+    let profile: Vec<Vec2> = PIN_PROFILE
+        .iter()
+        .map(|&(r, y)| Vec2::new(r * radius, y * height))
+        .collect();
+
+    lathe(&profile, 24, |y| {
+        let fraction = y / height;
+        if PIN_STRIPES
+            .iter()
+            .any(|&(low, high)| (low..=high).contains(&fraction))
+        {
+            RED.into()
+        } else {
+            LinearRgba::WHITE
+        }
+    })
+}
+
+/// Builds a collider roughly following the pin's belly, neck and head
+pub fn pin_collider(radius: f32, height: f32) -> Collider {
+    Collider::compound(vec![
+        (
+            Vec3::new(0.0, -0.19 * height, 0.0),
+            Quat::IDENTITY,
+            Collider::cylinder(0.31 * height, radius),
+        ),
+        (
+            Vec3::new(0.0, 0.31 * height, 0.0),
+            Quat::IDENTITY,
+            Collider::capsule_y(0.1 * height, 0.5 * radius),
+        ),
+    ])
+}
+
+/// Which column a pin stands in across the deck, counted in half pin spacings from the head pin
+/// so the 7 pin is at -3 and the 10 pin at 3
+fn pin_column(number: u8) -> i8 {
+    let mut row = 1;
+    while row * (row + 1) / 2 < number {
+        row += 1;
+    }
+    let index = number - row * (row - 1) / 2;
+    2 * index as i8 - row as i8 - 1
+}
+
+/// Whether the pins left standing are a split: the head pin is down and a pin is missing between
+/// or just ahead of two standing pins, like the 7-10, 4-6 or 5-6. Pins standing one behind the
+/// other, like the 2-8, aren't a split
+pub fn is_split(standing: &[u8]) -> bool {
+    if standing.len() < 2 || standing.contains(&1) {
+        return false;
+    }
+
+    let mut columns: Vec<i8> = standing.iter().map(|&pin| pin_column(pin)).collect();
+    columns.sort_unstable();
+    columns.dedup();
+    columns.windows(2).any(|pair| pair[1] - pair[0] > 1)
+}
+
+/// Sent when a pin that was hit comes to rest knocked down, or drops off the deck
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PinToppled(pub Entity);
 
 /// Marks a pin entity
 #[derive(Component)]
@@ -10,20 +113,63 @@ pub struct Pin {
     pub initial_coords: Transform,
     /// Is this pin toppled
     pub toppled: bool,
+    /// Where the pinsetter will set this pin back down
+    pub spot: Transform,
+    /// Standard pin number, 1 for the head pin through 10 for the back right corner
+    pub number: u8,
+    /// Whether the pin has been hit since it was last checked for toppling
+    pub disturbed: bool,
 }
 
 impl Pin {
-    /// Initializes a Pin with initial coordinates
-    pub fn new(initial_coords: Transform) -> Self {
+    /// Initializes a Pin with initial coordinates and its pin number
+    pub fn new(initial_coords: Transform, number: u8) -> Self {
         Self {
             initial_coords,
             toppled: false,
+            spot: initial_coords,
+            number,
+            disturbed: false,
         }
     }
-    /// Resets a transform and rotation and velocity according to an initial pin position
-    pub fn reset(&mut self, transform: &mut Transform, velocity: &mut Velocity) {
+    /// Stands the pin back up on its initial spot
+    pub fn reset(&mut self, transform: &mut Transform) {
         *transform = self.initial_coords;
-        *velocity = Velocity::zero();
+        self.spot = self.initial_coords;
         self.toppled = false;
+        self.disturbed = false;
+    }
+
+    /// Whether the pin counts as down: leaning too far from upright, or knocked off the pin deck.
+    /// Pins that slid around but are still standing on the deck count as standing
+    pub fn is_down(&self, transform: &Transform) -> bool {
+        let tilted = transform.up().dot(Vec3::Y) < TOPPLE_ANGLE.cos();
+
+        tilted || self.is_off_deck(transform)
+    }
+
+    /// Whether the pin has left the pin deck, from which it can't come back
+    pub fn is_off_deck(&self, transform: &Transform) -> bool {
+        let position = transform.translation;
+        position.x.abs() > LANE_WIDTH * 0.5
+            || position.z > LANE_START_Z + LANE_LENGTH
+            || self.initial_coords.translation.y - position.y > OFF_DECK_DROP
+    }
+
+    /// Whether a pin moving this fast has come to rest
+    pub fn is_resting(velocity: &Velocity) -> bool {
+        velocity.linvel.length() < RESTING_SPEED && velocity.angvel.length() < RESTING_SPIN
+    }
+
+    /// Marks where a standing pin should be set back down, upright on its current spot
+    pub fn mark_spot(&mut self, transform: &Transform) {
+        self.spot = Transform {
+            translation: Vec3::new(
+                transform.translation.x,
+                self.initial_coords.translation.y,
+                transform.translation.z,
+            ),
+            ..self.initial_coords
+        };
     }
 }
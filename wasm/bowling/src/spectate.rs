@@ -0,0 +1,194 @@
+//! Live state sync for spectators, so a TV or projector can show a game played in another browser
+//! without fighting over the controller
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::plugin::RapierConfiguration;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    diagnostics::DiagnosticSender,
+    menu::Menu,
+    snapshot::StateSync,
+    spectator::{is_playing, is_spectating},
+};
+
+use crate::{
+    environment::LaneEnvironment,
+    phase::BowlingPhase,
+    setup::{Ball, Pin},
+    turns::{BowlingState, BowlingStateWrapper},
+    variant::BowlingVariant,
+};
+
+/// Where a body is and whether it's drawn
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct Pose {
+    /// Position in the world
+    translation: [f32; 3],
+    /// Rotation as a quaternion
+    rotation: [f32; 4],
+    /// Whether the body is drawn
+    visible: bool,
+}
+
+impl Pose {
+    /// Captures a body's transform and visibility
+    fn capture(transform: &Transform, visibility: &Visibility) -> Self {
+        Self {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            visible: *visibility != Visibility::Hidden,
+        }
+    }
+
+    /// Moves a body to this pose
+    fn apply(&self, transform: &mut Transform, visibility: &mut Visibility) {
+        transform.translation = Vec3::from_array(self.translation);
+        transform.rotation = Quat::from_array(self.rotation);
+        *visibility = if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Everything a spectator needs to mirror one frame of the lane
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SyncFrame {
+    /// Where the game is, from picking a ball to the final score
+    phase: BowlingPhase,
+    /// Which kind of bowling is being played
+    variant: BowlingVariant,
+    /// Scenery around the lane
+    environment: LaneEnvironment,
+    /// Scores and player names
+    state: BowlingState,
+    /// The ball
+    ball: Option<Pose>,
+    /// Every pin by its number
+    pins: Vec<(u8, Pose)>,
+}
+
+/// The lane a spectator moves to match each synced frame
+#[derive(SystemParam)]
+struct SpectatorLane<'w, 's> {
+    /// Used to close the pre-game menus, the playing app picks for everyone
+    commands: Commands<'w, 's>,
+    /// Open pre-game menus
+    menus: Query<'w, 's, Entity, With<Menu>>,
+    /// The ball
+    ball: Query<
+        'w,
+        's,
+        (&'static mut Transform, &'static mut Visibility),
+        (With<Ball>, Without<Pin>),
+    >,
+    /// Every pin
+    pins: Query<
+        'w,
+        's,
+        (
+            &'static Pin,
+            &'static mut Transform,
+            &'static mut Visibility,
+        ),
+        Without<Ball>,
+    >,
+    /// Physics settings, kept paused since every body is placed by the playing app
+    physics: Query<'w, 's, &'static mut RapierConfiguration>,
+    /// Where the game is
+    phase: Res<'w, State<BowlingPhase>>,
+    /// Used to follow the playing app's phase
+    next_phase: ResMut<'w, NextState<BowlingPhase>>,
+    /// Which kind of bowling is being played
+    variant: ResMut<'w, BowlingVariant>,
+    /// Scenery around the lane
+    environment: ResMut<'w, LaneEnvironment>,
+}
+
+impl SpectatorLane<'_, '_> {
+    /// Moves the lane to match a synced frame
+    fn mirror(&mut self, frame: &SyncFrame) {
+        for menu in &self.menus {
+            self.commands.entity(menu).despawn_recursive();
+        }
+        for mut config in &mut self.physics {
+            config.physics_pipeline_active = false;
+        }
+
+        if *self.phase.get() != frame.phase {
+            self.next_phase.set(frame.phase);
+        }
+        self.variant.set_if_neq(frame.variant);
+        self.environment.set_if_neq(frame.environment);
+
+        if let (Some(pose), Ok((mut transform, mut visibility))) =
+            (frame.ball, self.ball.get_single_mut())
+        {
+            pose.apply(&mut transform, &mut visibility);
+        }
+        for (pin, mut transform, mut visibility) in &mut self.pins {
+            if let Some((_, pose)) = frame.pins.iter().find(|(number, _)| *number == pin.number) {
+                pose.apply(&mut transform, &mut visibility);
+            }
+        }
+    }
+}
+
+/// Plugin that publishes the lane for spectators, or mirrors it when spectating
+pub struct SpectatePlugin;
+
+impl Plugin for SpectatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (
+                publish_frame.run_if(is_playing),
+                mirror_frame.run_if(is_spectating),
+            ),
+        );
+    }
+}
+
+/// Publishes where everything is this frame for the page to relay to spectators
+fn publish_frame(
+    sync: Res<'_, StateSync>,
+    phase: Res<'_, State<BowlingPhase>>,
+    variant: Res<'_, BowlingVariant>,
+    environment: Res<'_, LaneEnvironment>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+    ball: Query<'_, '_, (&Transform, &Visibility), With<Ball>>,
+    pins: Query<'_, '_, (&Pin, &Transform, &Visibility)>,
+) {
+    sync.publish(&SyncFrame {
+        phase: *phase.get(),
+        variant: *variant,
+        environment: *environment,
+        state: bowling_state.get_state(),
+        ball: ball
+            .get_single()
+            .ok()
+            .map(|(transform, visibility)| Pose::capture(transform, visibility)),
+        pins: pins
+            .iter()
+            .map(|(pin, transform, visibility)| (pin.number, Pose::capture(transform, visibility)))
+            .collect(),
+    });
+}
+
+/// Mirrors the newest frame relayed from the playing app
+fn mirror_frame(
+    sync: Res<'_, StateSync>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+    diagnostics: Res<'_, DiagnosticSender>,
+    mut lane: SpectatorLane<'_, '_>,
+) {
+    match sync.take::<SyncFrame>() {
+        None => {}
+        Some(Ok(frame)) => {
+            lane.mirror(&frame);
+            bowling_state.load(frame.state);
+        }
+        Some(Err(err)) => diagnostics.warn(format!("Ignored an unreadable sync frame: {err}")),
+    }
+}
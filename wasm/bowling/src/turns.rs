@@ -7,67 +7,418 @@ use std::{
 
 use bevy::{
     app::{Plugin, Update},
-    prelude::{ParamSet, Query, Res, Resource, Text, Transform, Visibility},
+    prelude::{
+        resource_equals, DetectChanges, Event, EventReader, EventWriter, IntoSystemConfigs,
+        OnEnter, ParamSet, Query, Res, ResMut, Resource, Text, Visibility,
+    },
 };
-use bevy_rapier3d::prelude::Velocity;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    diagnostics::DiagnosticSender,
+    players::PlayerRegistry,
+    snapshot::{SaveSlot, StateSnapshot},
+    spectator::is_playing,
+    FeedbackSender,
+};
+
+use crate::{
+    audio::BowlingSound,
+    phase::BowlingPhase,
+    pinsetter::Pinsetter,
+    practice::GameMode,
+    setup::{pin::is_split, FinalScore, Gutterball, Hideable, Pin, PinToppled, ScorecardBg},
+    variant::BowlingVariant,
+};
+
+/// Number of frames in a game
+pub const FRAME_COUNT: usize = 10;
+
+/// Number of pins in a full rack
+const RACK_SIZE: u8 = 10;
 
-use crate::setup::{FinalScore, Hideable, Pin, ScorecardBg};
+/// Longest name a player can have, in characters
+pub const MAX_NAME_LEN: usize = 16;
 
-/// Type of score a score can be (strike, spare, normal)
-#[derive(Debug, Clone, Copy)]
+/// Mark shown on the scorecard for a single throw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Score {
     /// A non-special score
-    Normal(usize),
+    Normal(u8),
     /// A strike
     Strike,
     /// A spare
     Spare,
-    /// No score yet
-    None,
 }
 
 impl Display for Score {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let val = match self {
-            Self::Normal(0) => "--".to_string(),
+            Self::Normal(0) => "-".to_string(),
             Self::Normal(val) => format!("{}", val),
             Self::Strike => "X".to_string(),
             Self::Spare => "/".to_string(),
-            Self::None => "".to_string(),
         };
         write!(f, "{}", val)
     }
 }
 
-/// Displays a tuple of frame scores
-pub fn display_score_tuple(scores: &(Score, Score)) -> String {
-    let flattened = match scores {
-        (Score::Normal(_), Score::Normal(num)) => Score::Normal(*num),
-        (Score::Normal(num1), Score::None) => Score::Normal(*num1),
-        (_, Score::Spare) => Score::Spare,
-        (Score::Strike, _) => Score::Strike,
-        (Score::None, Score::None) => Score::None,
-        _ => unreachable!("Unrepresentable state"),
-    };
+/// A notable throw called out on screen
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Celebration {
+    /// Every pin down on a fresh rack
+    Strike,
+    /// Two strikes in a row
+    Double,
+    /// Three or more strikes in a row
+    Turkey,
+    /// The remaining pins cleared on the second throw
+    Spare,
+    /// The head pin down with a gap between the pins left standing
+    Split,
+    /// A split converted for a spare
+    Pickup,
+}
 
-    format!("{}", flattened)
+impl Celebration {
+    /// Picks the celebration for the last of a player's scorecard marks, if any
+    pub fn from_marks(marks: &[Score]) -> Option<Self> {
+        match marks.last()? {
+            Score::Strike => {
+                let streak = marks
+                    .iter()
+                    .rev()
+                    .take_while(|&&mark| mark == Score::Strike)
+                    .count();
+                Some(match streak {
+                    1 => Self::Strike,
+                    2 => Self::Double,
+                    _ => Self::Turkey,
+                })
+            }
+            Score::Spare => Some(Self::Spare),
+            Score::Normal(_) => None,
+        }
+    }
+
+    /// Whether this celebrates a strike
+    pub fn is_strike(&self) -> bool {
+        matches!(self, Self::Strike | Self::Double | Self::Turkey)
+    }
+
+    /// Banner text shown for this celebration
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::Strike => "STRIKE!",
+            Self::Double => "DOUBLE!",
+            Self::Turkey => "TURKEY!",
+            Self::Spare => "SPARE!",
+            Self::Split => "SPLIT!",
+            Self::Pickup => "PICKUP!",
+        }
+    }
+}
+
+/// Pinfall for every throw in a single frame
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    /// Pins knocked down by each throw, in order
+    throws: Vec<u8>,
+    /// Which throw left a split, if any
+    split: Option<usize>,
+}
+
+impl Frame {
+    /// Pins knocked down by each throw, in order
+    pub fn throws(&self) -> &[u8] {
+        &self.throws
+    }
+
+    /// Records the pins knocked down by a throw
+    pub fn record(&mut self, pins: u8) {
+        self.throws.push(pins);
+    }
+
+    /// Marks the last recorded throw as having left a split
+    pub fn mark_split(&mut self) {
+        self.split = self.throws.len().checked_sub(1);
+    }
+
+    /// Which mark is circled on the scorecard for leaving a split. It's only circled once the
+    /// split has been shot at, whether it was converted or missed
+    pub fn circled_split(&self) -> Option<usize> {
+        self.split.filter(|&throw| throw + 1 < self.throws.len())
+    }
+
+    /// How many throws have been made at the rack the next throw is at
+    fn rack_throws(&self) -> usize {
+        let mut standing = RACK_SIZE;
+        let mut rack_throws = 0;
+        for &pins in &self.throws {
+            standing = standing.saturating_sub(pins);
+            rack_throws += 1;
+            if standing == 0 {
+                standing = RACK_SIZE;
+                rack_throws = 0;
+            }
+        }
+        rack_throws
+    }
+
+    /// Whether the first throw knocked down every pin
+    pub fn is_strike(&self) -> bool {
+        self.throws.first() == Some(&RACK_SIZE)
+    }
+
+    /// Whether the first two throws knocked down every pin without a strike
+    pub fn is_spare(&self) -> bool {
+        !self.is_strike() && self.throws.len() >= 2 && self.throws[0] + self.throws[1] == RACK_SIZE
+    }
+
+    /// Pins knocked down across every throw
+    fn pinfall(&self) -> u8 {
+        self.throws.iter().sum()
+    }
+
+    /// Whether no more throws can be made in this frame under a variant's rules. A frame ends
+    /// early once every pin is down, and the 10th frame fills out to three throws after a strike
+    /// or spare
+    pub fn is_complete(&self, tenth: bool, variant: BowlingVariant) -> bool {
+        let throws = variant.throws_per_frame();
+        if tenth {
+            self.throws.len() == 3
+                || (self.throws.len() == throws && !self.is_strike() && !self.is_spare())
+        } else {
+            self.is_strike() || self.pinfall() >= RACK_SIZE || self.throws.len() == throws
+        }
+    }
+
+    /// The scorecard marks for every throw made so far. Only the second throw at a rack can be
+    /// a spare, clearing the rack on a third throw earns no bonus
+    pub fn marks(&self) -> Vec<Score> {
+        let mut standing = RACK_SIZE;
+        let mut rack_throws = 0;
+        self.throws
+            .iter()
+            .map(|&pins| {
+                let mark = if pins == RACK_SIZE && rack_throws == 0 {
+                    Score::Strike
+                } else if pins == standing && rack_throws == 1 {
+                    Score::Spare
+                } else {
+                    Score::Normal(pins)
+                };
+
+                standing = standing.saturating_sub(pins);
+                rack_throws += 1;
+                if standing == 0 {
+                    standing = RACK_SIZE;
+                    rack_throws = 0;
+                }
+
+                mark
+            })
+            .collect()
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for mark in self.marks() {
+            write!(f, "{}", mark)?;
+        }
+        Ok(())
+    }
+}
+
+/// A player's throws tallied across every game of the session
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    /// Throws made at a fresh rack
+    first_balls: u32,
+    /// Pins knocked down by first balls
+    first_ball_pinfall: u32,
+    /// First balls that knocked down every pin
+    strikes: u32,
+    /// Second throws made at a rack, each one a chance at a spare
+    spare_chances: u32,
+    /// Second throws that cleared the rack
+    spares: u32,
+    /// Finished frames without a strike or spare
+    open_frames: u32,
+    /// Balls that dropped into a gutter
+    gutterballs: u32,
+}
+
+impl PlayerStats {
+    /// Tallies a scored throw, given how many throws had already been made at its rack
+    fn record_throw(&mut self, rack_throws: usize, pinfall: u8, mark: Option<Score>) {
+        match rack_throws {
+            0 => {
+                self.first_balls += 1;
+                self.first_ball_pinfall += u32::from(pinfall);
+                if mark == Some(Score::Strike) {
+                    self.strikes += 1;
+                }
+            }
+            1 => {
+                self.spare_chances += 1;
+                if mark == Some(Score::Spare) {
+                    self.spares += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Share of first balls that were strikes, as a percentage
+    pub fn strike_rate(&self) -> f32 {
+        percentage(self.strikes, self.first_balls)
+    }
+
+    /// Share of spare chances that were converted, as a percentage
+    pub fn spare_conversion(&self) -> f32 {
+        percentage(self.spares, self.spare_chances)
+    }
+
+    /// Average pins knocked down by a first ball
+    pub fn first_ball_average(&self) -> f32 {
+        if self.first_balls == 0 {
+            0.0
+        } else {
+            self.first_ball_pinfall as f32 / self.first_balls as f32
+        }
+    }
+
+    /// Creates a JavaScript facing snapshot of these stats
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            strikes: self.strikes,
+            spares: self.spares,
+            open_frames: self.open_frames,
+            gutterballs: self.gutterballs,
+            strike_rate: self.strike_rate(),
+            spare_conversion: self.spare_conversion(),
+            first_ball_average: self.first_ball_average(),
+        }
+    }
+}
+
+impl Display for PlayerStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} X ({:.0}%)  {}/{} spares ({:.0}%)  {} open  {} gutter  {:.1} first ball avg",
+            self.strikes,
+            self.strike_rate(),
+            self.spares,
+            self.spare_chances,
+            self.spare_conversion(),
+            self.open_frames,
+            self.gutterballs,
+            self.first_ball_average()
+        )
+    }
+}
+
+/// `part` out of `whole` as a percentage, 0 when there's nothing to take a share of
+fn percentage(part: u32, whole: u32) -> f32 {
+    if whole == 0 {
+        0.0
+    } else {
+        100.0 * part as f32 / whole as f32
+    }
+}
+
+/// JavaScript facing snapshot of a player's session stats
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct StatsSnapshot {
+    /// First balls that knocked down every pin
+    pub strikes: u32,
+    /// Second throws that cleared the rack
+    pub spares: u32,
+    /// Finished frames without a strike or spare
+    pub open_frames: u32,
+    /// Balls that dropped into a gutter
+    pub gutterballs: u32,
+    /// Share of first balls that were strikes, as a percentage
+    pub strike_rate: f32,
+    /// Share of spare chances that were converted, as a percentage
+    pub spare_conversion: f32,
+    /// Average pins knocked down by a first ball
+    pub first_ball_average: f32,
+}
+
+/// What happens after a throw has been scored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrowOutcome {
+    /// The player throws again at the pins still standing
+    ThrowAgain,
+    /// The player throws again at a fresh rack, after a strike or spare in the 10th frame
+    BonusThrow,
+    /// The frame is over and the next player is up
+    NextTurn,
+    /// Every player has finished their 10th frame
+    GameOver,
+}
+
+impl ThrowOutcome {
+    /// Whether the pins need to be set back up before the next throw
+    pub fn resets_pins(&self) -> bool {
+        !matches!(self, Self::ThrowAgain)
+    }
 }
 
 /// Bowling game current state
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BowlingState {
     /// What is the current frame we're at
     frame_number: usize,
     /// Which throw in the frame are we on
     throw_num: u8,
-    /// Scores per frame for each player
-    player_frame_scores: Vec<[(Score, Score); 10]>,
-    /// Pins currently down
-    pins_down: u8,
+    /// Every frame for each player
+    player_frames: Vec<[Frame; FRAME_COUNT]>,
+    /// Numbers of the pins currently down
+    toppled: Vec<u8>,
+    /// Pins down that have already been scored since the rack was last set
+    pins_counted: u8,
     /// Is the current throw done
     throw_done: bool,
     /// Current player's turn
     turn: usize,
+    /// What the last scored throw earned, if anything
+    #[serde(skip)]
+    celebration: Option<Celebration>,
+    /// Whether every player has finished their 10th frame
+    game_over: bool,
+    /// Which kind of bowling is being played
+    variant: BowlingVariant,
+    /// Names given to players by index, empty if a player hasn't been named
+    names: Vec<String>,
+    /// Every player's stats across the session, kept through rematches
+    #[serde(default)]
+    stats: Vec<PlayerStats>,
+}
+
+/// JavaScript facing snapshot of the bowling state
+#[derive(Serialize, Debug, Clone)]
+pub struct BowlingSnapshot {
+    /// Current frame, starting at 1
+    pub frame: usize,
+    /// Current throw within the frame, starting at 1
+    pub throw: u8,
+    /// Whose turn it is, starting at 0
+    pub turn: usize,
+    /// Rendered marks for every frame, per player
+    pub frames: Vec<Vec<String>>,
+    /// Running total after every frame, per player. `None` until a frame's bonuses are known
+    pub totals: Vec<Vec<Option<usize>>>,
+    /// Which mark in every frame is circled for a split, per player
+    pub splits: Vec<Vec<Option<usize>>>,
+    /// Every player's name, `None` if they haven't been named
+    pub names: Vec<Option<String>>,
+    /// Every player's stats across the session
+    pub stats: Vec<StatsSnapshot>,
 }
 
 /// Send + Sync wrapper around BowlingState
@@ -75,25 +426,33 @@ pub struct BowlingState {
 pub struct BowlingStateWrapper(Arc<RwLock<BowlingState>>);
 
 impl BowlingState {
-    /// Returns the string representation of the state
+    /// Returns an ASCII scorecard of the state for logging, with each player's marks above their
+    /// running total
     pub fn render(&self) -> String {
+        let divider = format!("+-------+{}", "-----+".repeat(FRAME_COUNT));
         let scores: Vec<String> = self
-            .player_frame_scores
+            .player_frames
             .iter()
             .enumerate()
-            .map(|(player, score)| {
-                let renderables = score
+            .map(|(player, frames)| {
+                let marks = frames
                     .iter()
                     .enumerate()
-                    .map(|(idx, val)| {
-                        if idx + 1 <= self.frame_number {
-                            let rendered = format!("{}", display_score_tuple(val));
-                            format!("{:^2}", rendered)
+                    .map(|(idx, frame)| {
+                        if idx < self.frame_number {
+                            format!("{:^3}", frame.to_string())
                         } else {
-                            "##".to_string()
+                            "###".to_string()
                         }
                     })
                     .collect::<Vec<_>>();
+                let totals = running_totals(frames, self.variant)
+                    .into_iter()
+                    .map(|total| match total {
+                        Some(total) => format!("{:^3}", total),
+                        None => "   ".to_string(),
+                    })
+                    .collect::<Vec<_>>();
 
                 let player_icon = if player == self.turn {
                     format!(">{:^2}", player + 1)
@@ -102,28 +461,20 @@ impl BowlingState {
                 };
 
                 format!(
-                    r#"
-|   {:^2} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |
-+-------+----+----+----+----+----+----+----+----+----+----+"#,
+                    "\n|   {:^2} | {} |\n|       | {} |\n{}",
                     player_icon,
-                    renderables[0],
-                    renderables[1],
-                    renderables[2],
-                    renderables[3],
-                    renderables[4],
-                    renderables[5],
-                    renderables[6],
-                    renderables[7],
-                    renderables[8],
-                    renderables[9]
+                    marks.join(" | "),
+                    totals.join(" | "),
+                    divider
                 )
             })
             .collect();
 
-        let mut start_str = r#"+-------+----+----+----+----+----+----+----+----+----+----+
-| Plr # | 1  | 2  | 3  | 4  | 5  | 6  | 7  | 8  | 9  | 10 |
-+-------+----+----+----+----+----+----+----+----+----+----+"#
-            .to_string();
+        let header = (1..=FRAME_COUNT)
+            .map(|frame| format!("{:^3}", frame))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let mut start_str = format!("{divider}\n| Plr # | {header} |\n{divider}");
 
         for player in scores {
             start_str = format!("{}{}", start_str, player)
@@ -136,9 +487,28 @@ impl BowlingState {
         self.throw_done
     }
 
+    /// What the last scored throw earned, if anything
+    pub fn get_celebration(&self) -> Option<Celebration> {
+        self.celebration
+    }
+
     /// Gets the current amount of pins downed
     pub fn get_pins_down(&self) -> u8 {
-        self.pins_down
+        self.toppled.len() as u8
+    }
+
+    /// Records a pin being knocked down
+    pub fn topple_pin(&mut self, number: u8) {
+        if !self.toppled.contains(&number) {
+            self.toppled.push(number);
+        }
+    }
+
+    /// Numbers of the pins still standing
+    pub fn standing(&self) -> Vec<u8> {
+        (1..=RACK_SIZE)
+            .filter(|pin| !self.toppled.contains(pin))
+            .collect()
     }
 
     /// Returns the current throw
@@ -152,39 +522,69 @@ impl BowlingState {
         self.throw_done = true;
     }
 
-    /// Sets the current score for the current frame
-    pub fn set_score(&mut self, score: u8) {
-        if self.throw_num <= 2 {
-            self.player_frame_scores[self.turn][self.frame_number as usize - 1].0 =
-                Score::Normal(score as usize)
-        } else {
-            self.player_frame_scores[self.turn][self.frame_number as usize - 1].1 =
-                Score::Normal(score as usize)
+    /// Scores the finished throw against the current frame and moves on to the next throw,
+    /// frame or player
+    pub fn finish_throw(&mut self) -> ThrowOutcome {
+        let pinfall = self.get_pins_down().saturating_sub(self.pins_counted);
+        let standing = self.standing();
+        let tenth = self.frame_number == FRAME_COUNT;
+        let frames = &mut self.player_frames[self.turn][..self.frame_number];
+        let frame = &mut frames[self.frame_number - 1];
+        let rack_throws = frame.rack_throws();
+        let split = rack_throws == 0 && is_split(&standing);
+        frame.record(pinfall);
+        if split {
+            frame.mark_split();
         }
-    }
+        let complete = frame.is_complete(tenth, self.variant);
+        let open = complete && !frame.is_strike() && !frame.is_spare();
+        let mark = frame.marks().last().copied();
+        let shot_at_split = frame.circled_split().is_some();
 
-    /// Sets the current score for the current frame to a spare
-    pub fn set_spare(&mut self) {
-        self.player_frame_scores[self.turn][self.frame_number as usize - 1].1 = Score::Spare
-    }
+        let marks: Vec<Score> = frames.iter().flat_map(Frame::marks).collect();
+        self.celebration = match Celebration::from_marks(&marks) {
+            Some(Celebration::Spare) if shot_at_split => Some(Celebration::Pickup),
+            celebration => celebration.or(split.then_some(Celebration::Split)),
+        };
 
-    /// Sets the current score for the current frame to a strike
-    pub fn set_strike(&mut self) {
-        self.player_frame_scores[self.turn][self.frame_number as usize - 1].0 = Score::Strike
+        let stats = self.player_stats_mut(self.turn);
+        stats.record_throw(rack_throws, pinfall, mark);
+        if open {
+            stats.open_frames += 1;
+        }
+
+        self.throw_done = false;
+
+        if complete {
+            self.reset();
+            if self.inc_frame() {
+                self.game_over = true;
+                ThrowOutcome::GameOver
+            } else {
+                ThrowOutcome::NextTurn
+            }
+        } else if self.get_pins_down() >= RACK_SIZE {
+            self.toppled.clear();
+            self.pins_counted = 0;
+            ThrowOutcome::BonusThrow
+        } else {
+            self.pins_counted = self.get_pins_down();
+            ThrowOutcome::ThrowAgain
+        }
     }
 
     /// Increments the current frame with bounds
-    pub fn inc_frame(&mut self) -> bool {
-        if self.frame_number < 10 {
-            if self.turn >= self.player_frame_scores.len() - 1 {
+    fn inc_frame(&mut self) -> bool {
+        if self.frame_number < FRAME_COUNT {
+            if self.turn >= self.player_frames.len() - 1 {
                 self.turn = 0;
                 self.frame_number += 1;
             } else {
                 self.turn += 1
             }
             false
-        } else if self.frame_number == 10 {
-            if self.turn >= self.player_frame_scores.len() - 1 {
+        } else if self.frame_number == FRAME_COUNT {
+            if self.turn >= self.player_frames.len() - 1 {
                 true
             } else {
                 self.turn += 1;
@@ -196,35 +596,159 @@ impl BowlingState {
     }
 
     /// Resets all triggers for a new frame
-    pub fn reset(&mut self) {
-        self.pins_down = 0;
+    fn reset(&mut self) {
+        self.toppled.clear();
+        self.pins_counted = 0;
         self.throw_done = false;
         self.throw_num = 1;
     }
 
-    /// Toggles the throw status back
-    pub fn set_throw_not_done(&mut self) {
-        self.throw_done = false;
-    }
-
     /// Gets the total score
     pub fn get_score(&self) -> Vec<(usize, usize)> {
-        self.player_frame_scores
+        self.player_frames
             .iter()
             .enumerate()
-            .map(|(id, score)| (id, get_score(score)))
+            .map(|(id, frames)| (id, get_score(frames, self.variant)))
             .collect()
     }
 
-    /// Sets the number of players in a game
+    /// Gets every player's running total after each frame
+    pub fn get_running_totals(&self) -> Vec<Vec<Option<usize>>> {
+        self.player_frames
+            .iter()
+            .map(|frames| running_totals(frames, self.variant))
+            .collect()
+    }
+
+    /// A player's stats, tallied from scratch if they have none yet
+    fn player_stats_mut(&mut self, player: usize) -> &mut PlayerStats {
+        if self.stats.len() <= player {
+            self.stats.resize(player + 1, PlayerStats::default());
+        }
+        &mut self.stats[player]
+    }
+
+    /// Gets a player's stats across the session
+    pub fn get_player_stats(&self, player: usize) -> PlayerStats {
+        self.stats.get(player).copied().unwrap_or_default()
+    }
+
+    /// Counts a gutterball against the player whose turn it is
+    pub fn record_gutterball(&mut self) {
+        self.player_stats_mut(self.turn).gutterballs += 1;
+    }
+
+    /// Sets the number of players in a game, with fresh scorecards if the number changed. Keeping
+    /// them otherwise lets a resumed save survive its players being registered again
     pub fn set_players(&mut self, num: usize) {
-        self.player_frame_scores = vec![[(Score::None, Score::None); 10]; num]
+        if self.player_frames.len() != num {
+            self.player_frames = vec![Default::default(); num]
+        }
+    }
+
+    /// Switches to another kind of bowling, starting the game over
+    pub fn set_variant(&mut self, variant: BowlingVariant) {
+        self.variant = variant;
+        self.restart();
+    }
+
+    /// Which kind of bowling is being played
+    pub fn get_variant(&self) -> BowlingVariant {
+        self.variant
+    }
+
+    /// Names a player, trimmed and cut to [`MAX_NAME_LEN`]. An empty name clears it
+    pub fn set_player_name(&mut self, player: usize, name: &str) {
+        if self.names.len() <= player {
+            self.names.resize(player + 1, String::new());
+        }
+        self.names[player] = name.trim().chars().take(MAX_NAME_LEN).collect();
+    }
+
+    /// Gets a player's name, if they've been given one
+    pub fn get_player_name(&self, player: usize) -> Option<&str> {
+        self.names
+            .get(player)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Takes the pins knocked down by a throw without scoring it, ready for the next one
+    pub fn take_unscored_throw(&mut self) -> u8 {
+        let pinfall = self.get_pins_down();
+        self.toppled.clear();
+        self.pins_counted = 0;
+        self.throw_num = 1;
+        self.throw_done = false;
+        pinfall
+    }
+
+    /// Starts a fresh game with the same players
+    pub fn restart(&mut self) {
+        *self = Self {
+            player_frames: vec![Default::default(); self.player_frames.len()],
+            names: std::mem::take(&mut self.names),
+            stats: std::mem::take(&mut self.stats),
+            variant: self.variant,
+            ..Self::default()
+        }
+    }
+
+    /// Whether every player has finished their 10th frame
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Whether a loaded save describes a game that can be resumed, between throws of an
+    /// unfinished game
+    pub fn is_resumable(&self) -> bool {
+        !self.game_over
+            && !self.throw_done
+            && self.turn < self.player_frames.len()
+            && (1..=FRAME_COUNT).contains(&self.frame_number)
+            && self.toppled.iter().all(|pin| (1..=RACK_SIZE).contains(pin))
     }
 
     /// Gets who's turn it is
     pub fn get_turn(&self) -> usize {
         self.turn
     }
+
+    /// Whether any throw has been made yet
+    pub fn has_started(&self) -> bool {
+        self.throw_done
+            || self
+                .player_frames
+                .iter()
+                .flatten()
+                .any(|frame| !frame.throws().is_empty())
+    }
+
+    /// Creates a JavaScript facing snapshot of the current state
+    pub fn snapshot(&self) -> BowlingSnapshot {
+        BowlingSnapshot {
+            frame: self.frame_number,
+            throw: self.throw_num,
+            turn: self.turn,
+            frames: self
+                .player_frames
+                .iter()
+                .map(|frames| frames.iter().map(Frame::to_string).collect())
+                .collect(),
+            totals: self.get_running_totals(),
+            splits: self
+                .player_frames
+                .iter()
+                .map(|frames| frames.iter().map(Frame::circled_split).collect())
+                .collect(),
+            names: (0..self.player_frames.len())
+                .map(|player| self.get_player_name(player).map(str::to_string))
+                .collect(),
+            stats: (0..self.player_frames.len())
+                .map(|player| self.get_player_stats(player).snapshot())
+                .collect(),
+        }
+    }
 }
 
 impl BowlingStateWrapper {
@@ -233,20 +757,16 @@ impl BowlingStateWrapper {
         self.0.read().unwrap().get_turn()
     }
 
-    /// Sets the current score for the current frame to a spare
-    pub fn set_spare(&self) {
-        self.0.write().unwrap().set_spare()
+    /// Whether any throw has been made yet
+    pub fn has_started(&self) -> bool {
+        self.0.read().unwrap().has_started()
     }
 
-    /// Sets the current score for the current frame to a strike
-    pub fn set_strike(&self) {
-        self.0.write().unwrap().set_strike()
-    }
     /// Gets the total score
     pub fn get_score(&self) -> Vec<(usize, usize)> {
         self.0.read().unwrap().get_score()
     }
-    /// Renders the current state as a scorecard
+    /// Renders the current state as an ASCII scorecard for logging
     pub fn render(&self) -> String {
         self.0.read().unwrap().render()
     }
@@ -270,34 +790,98 @@ impl BowlingStateWrapper {
         self.0.write().unwrap().inc_throw_num()
     }
 
-    /// Sets the current score for the current frame
-    pub fn set_score(&self, score: u8) {
-        self.0.write().unwrap().set_score(score)
+    /// Scores the finished throw and moves on to the next throw, frame or player
+    pub fn finish_throw(&self) -> ThrowOutcome {
+        self.0.write().unwrap().finish_throw()
     }
 
-    /// Increments the current frame with bounds
-    pub fn inc_frame(&self) -> bool {
-        self.0.write().unwrap().inc_frame()
+    /// What the last scored throw earned, if anything
+    pub fn get_celebration(&self) -> Option<Celebration> {
+        self.0.read().unwrap().get_celebration()
     }
 
-    /// Resets all triggers for a new frame
-    pub fn reset(&self) {
-        self.0.write().unwrap().reset()
+    /// Records a pin being knocked down
+    pub fn topple_pin(&self, number: u8) {
+        self.0.write().unwrap().topple_pin(number)
+    }
+
+    /// Sets the number of players in the current game
+    pub fn set_players(&self, num: usize) {
+        self.0.write().unwrap().set_players(num)
     }
 
-    /// Increments the current amount of toppled pins
-    pub fn topple_pin(&self) {
-        self.0.write().unwrap().pins_down += 1
+    /// Takes the pins knocked down by a throw without scoring it, ready for the next one
+    pub fn take_unscored_throw(&self) -> u8 {
+        self.0.write().unwrap().take_unscored_throw()
     }
 
-    /// Toggles the throw status back
-    pub fn set_throw_not_done(&self) {
-        self.0.write().unwrap().set_throw_not_done()
+    /// Switches to another kind of bowling, starting the game over
+    pub fn set_variant(&self, variant: BowlingVariant) {
+        self.0.write().unwrap().set_variant(variant)
     }
 
-    /// Sets the number of players in the current game
-    pub fn set_players(&self, num: usize) {
-        self.0.write().unwrap().set_players(num)
+    /// Which kind of bowling is being played
+    pub fn get_variant(&self) -> BowlingVariant {
+        self.0.read().unwrap().get_variant()
+    }
+
+    /// Names a player, trimmed and cut to [`MAX_NAME_LEN`]. An empty name clears it
+    pub fn set_player_name(&self, player: usize, name: &str) {
+        self.0.write().unwrap().set_player_name(player, name)
+    }
+
+    /// Gets a player's name, if they've been given one
+    pub fn get_player_name(&self, player: usize) -> Option<String> {
+        self.0
+            .read()
+            .unwrap()
+            .get_player_name(player)
+            .map(str::to_string)
+    }
+
+    /// Gets a player's stats across the session
+    pub fn get_player_stats(&self, player: usize) -> PlayerStats {
+        self.0.read().unwrap().get_player_stats(player)
+    }
+
+    /// Counts a gutterball against the player whose turn it is
+    pub fn record_gutterball(&self) {
+        self.0.write().unwrap().record_gutterball()
+    }
+
+    /// Starts a fresh game with the same players
+    pub fn restart(&self) {
+        self.0.write().unwrap().restart()
+    }
+
+    /// Whether every player has finished their 10th frame
+    pub fn is_game_over(&self) -> bool {
+        self.0.read().unwrap().is_game_over()
+    }
+
+    /// Numbers of the pins still standing
+    pub fn standing(&self) -> Vec<u8> {
+        self.0.read().unwrap().standing()
+    }
+
+    /// Stores the current state as a save to resume from
+    pub fn save(&self, slot: &SaveSlot) {
+        slot.store(&*self.0.read().unwrap());
+    }
+
+    /// Gets a copy of the current state
+    pub fn get_state(&self) -> BowlingState {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Replaces the current state with a loaded save
+    pub fn load(&self, state: BowlingState) {
+        *self.0.write().unwrap() = state;
+    }
+
+    /// Creates a JavaScript facing snapshot of the current state
+    pub fn snapshot(&self) -> BowlingSnapshot {
+        self.0.read().unwrap().snapshot()
     }
 }
 
@@ -306,10 +890,16 @@ impl Default for BowlingState {
         Self {
             frame_number: 1,
             throw_num: 1,
-            player_frame_scores: vec![[(Score::None, Score::None); 10]],
+            player_frames: vec![Default::default()],
             turn: 0,
-            pins_down: 0,
+            toppled: vec![],
+            pins_counted: 0,
             throw_done: false,
+            celebration: None,
+            game_over: false,
+            variant: BowlingVariant::default(),
+            names: vec![],
+            stats: vec![],
         }
     }
 }
@@ -320,132 +910,425 @@ pub struct BowlingTurnPlugin;
 impl Plugin for BowlingTurnPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_resource::<BowlingStateWrapper>()
-            .add_systems(Update, update_frame_logic);
+            .add_systems(
+                Update,
+                (
+                    sync_players,
+                    (update_frame_logic, count_gutterballs)
+                        .run_if(resource_equals(GameMode::Standard))
+                        .run_if(is_playing),
+                    update_snapshot,
+                ),
+            )
+            .add_systems(
+                OnEnter(BowlingPhase::GameOver),
+                (show_final_score, submit_result.run_if(is_playing)),
+            );
     }
 }
 
-/// Check current amount of pins down if a throw is over and add that to the score, update current
-/// frame or throw and reset pins if need be
+/// Scores a throw once it is over and has the pinsetter get the deck ready for the next one
 fn update_frame_logic(
+    bowling_state: Res<'_, BowlingStateWrapper>,
+    mut sounds: EventWriter<'_, BowlingSound>,
+    mut celebrations: EventWriter<'_, Celebration>,
+    mut pinsetter: ResMut<'_, Pinsetter>,
+    diagnostics: Res<'_, DiagnosticSender>,
+) {
+    if bowling_state.is_throw_done() {
+        let outcome = bowling_state.finish_throw();
+        diagnostics.info(bowling_state.render());
+
+        if let Some(celebration) = bowling_state.get_celebration() {
+            if celebration.is_strike() {
+                sounds.send(BowlingSound::Strike);
+            }
+            celebrations.send(celebration);
+        }
+
+        if outcome == ThrowOutcome::ThrowAgain && !bowling_state.get_variant().clears_deadwood() {
+            // Fallen pins stay on the deck until the frame is over
+            pinsetter.stop();
+        } else if outcome != ThrowOutcome::GameOver {
+            pinsetter.start(outcome.resets_pins());
+        } else {
+            // The deck stays as it is behind the final score until a rematch re-racks it
+            pinsetter.stop();
+        }
+    }
+}
+
+/// Counts gutterballs against whoever threw them
+fn count_gutterballs(
+    mut gutterballs: EventReader<'_, '_, Gutterball>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+) {
+    for _ in gutterballs.read() {
+        bowling_state.record_gutterball();
+    }
+}
+
+/// Hides the lane and shows the winner, or everyone tied for the win, and everyone's session
+/// stats once the game is over
+fn show_final_score(
     bowling_state: Res<'_, BowlingStateWrapper>,
     mut queries: ParamSet<
         '_,
         '_,
         (
-            Query<'_, '_, (&mut Transform, &mut Pin, &mut Velocity)>,
             Query<'_, '_, (&Hideable, &mut Visibility)>,
             Query<'_, '_, (&mut Text, &FinalScore)>,
             Query<'_, '_, (&mut Visibility, &ScorecardBg)>,
         ),
     >,
+    mut sounds: EventWriter<'_, BowlingSound>,
 ) {
-    if bowling_state.is_throw_done() {
-        let game_over =
-            match (
-                bowling_state.get_throw_num() - 1,
-                bowling_state.get_pins_down(),
-            ) {
-                (1, 10) => {
-                    // Strike
-                    bowling_state.set_strike();
-                    bowling_state.reset();
-                    queries.p0().iter_mut().for_each(
-                        |(mut transformation, mut pin, mut velocity)| {
-                            pin.reset(&mut transformation, &mut velocity)
-                        },
-                    );
-                    Some(bowling_state.inc_frame())
-                }
-                (2, 10) => {
-                    // Spare
-                    bowling_state.set_spare();
-                    bowling_state.reset();
-                    queries.p0().iter_mut().for_each(
-                        |(mut transformation, mut pin, mut velocity)| {
-                            pin.reset(&mut transformation, &mut velocity)
-                        },
-                    );
-                    Some(bowling_state.inc_frame())
-                }
-                (1, val) => {
-                    bowling_state.set_score(val);
-                    bowling_state.set_throw_not_done();
-                    None
-                }
-                (_, val) => {
-                    bowling_state.set_score(val);
-                    bowling_state.reset();
-                    queries.p0().iter_mut().for_each(
-                        |(mut transformation, mut pin, mut velocity)| {
-                            pin.reset(&mut transformation, &mut velocity)
-                        },
-                    );
-                    Some(bowling_state.inc_frame())
-                }
-            };
+    sounds.send(BowlingSound::GameOver);
 
-        if let Some(true) = game_over {
-            for (_, mut vis) in queries.p1().iter_mut() {
-                *vis = Visibility::Hidden
-            }
+    for (_, mut vis) in queries.p0().iter_mut() {
+        *vis = Visibility::Hidden
+    }
 
-            if let Ok((mut vis, _)) = queries.p3().get_single_mut() {
-                *vis = Visibility::Visible
-            }
+    if let Ok((mut vis, _)) = queries.p2().get_single_mut() {
+        *vis = Visibility::Visible
+    }
 
-            if let Ok((mut text, _)) = queries.p2().get_single_mut() {
-                let scores = bowling_state.get_score();
-                let (winner, score) = scores
-                    .iter()
-                    .max_by(|(_, prev_score), (_, score)| prev_score.cmp(score))
-                    .unwrap();
-                let final_score = format!(
-                    "Game Over!\nPlayer {} wins with a final score of: {}\n\n\n\n\nPlease Restart the Page to Return Home :)",
-                winner + 1, score);
-                *text = Text::new(final_score);
-            }
+    if let Ok((mut text, _)) = queries.p1().get_single_mut() {
+        let scores = bowling_state.get_score();
+        let name = |player: usize| {
+            bowling_state
+                .get_player_name(player)
+                .unwrap_or_else(|| format!("Player {}", player + 1))
+        };
+        if let Some((leaders, score)) = leaders(&scores) {
+            let names: Vec<_> = leaders.into_iter().map(name).collect();
+            let result = match names.as_slice() {
+                [winner] => format!("{} wins with a final score of: {}", winner, score),
+                _ => format!(
+                    "{} tie with a final score of: {}",
+                    names.join(" and "),
+                    score
+                ),
+            };
+            let stats = (0..scores.len())
+                .map(|player| {
+                    format!(
+                        "{}: {}",
+                        name(player),
+                        bowling_state.get_player_stats(player)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let final_score = format!(
+                "Game Over!\n{}\n\n{}\n\n\nPress A for a rematch",
+                result, stats
+            );
+            *text = Text::new(final_score);
         }
     }
 }
 
-/// Returns the score for a completed scorecard
-pub fn get_score(scores: &[(Score, Score)]) -> usize {
-    let mut total_score = 0;
+/// Every player sharing the highest score, along with that score. `None` if nobody has played
+fn leaders(scores: &[(usize, usize)]) -> Option<(Vec<usize>, usize)> {
+    let top = scores.iter().map(|&(_, score)| score).max()?;
+    let leaders = scores
+        .iter()
+        .filter(|&&(_, score)| score == top)
+        .map(|&(player, _)| player)
+        .collect();
+    Some((leaders, top))
+}
 
-    for i in 0..scores.len() {
-        total_score += match scores[i] {
-            (Score::Normal(_), Score::Normal(pins)) => pins,
-            (_, Score::Spare) => 10 + next_roll_score(&scores, i + 1),
-            (Score::Strike, _) => 10 + next_two_rolls_score(&scores, i + 1),
-            _ => unreachable!("Score shouldn't be calculated until game is over"),
-        };
+/// Counts every toppled pin against the current throw
+pub fn count_toppled_pins(
+    mut toppled: EventReader<'_, '_, PinToppled>,
+    pins: Query<'_, '_, &Pin>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+) {
+    for PinToppled(entity) in toppled.read() {
+        if let Ok(pin) = pins.get(*entity) {
+            bowling_state.topple_pin(pin.number);
+        }
     }
+}
+
+/// Resets the scorecards for the registered number of players whenever it changes
+pub fn sync_players(
+    registry: Res<'_, PlayerRegistry>,
+    bowling_state: Res<'_, BowlingStateWrapper>,
+) {
+    if registry.is_changed() {
+        bowling_state.set_players(registry.total());
+    }
+}
+
+/// Sends the final scores back to the page once the game is over, so it can submit them to the
+/// server
+fn submit_result(bowling_state: Res<'_, BowlingStateWrapper>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = bowling_state
+        .get_score()
+        .into_iter()
+        .map(|(_, score)| score as u32)
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
 
-    total_score
+/// Publishes the current bowling state to JavaScript
+fn update_snapshot(bowling_state: Res<'_, BowlingStateWrapper>, snapshot: Res<'_, StateSnapshot>) {
+    snapshot.set(&bowling_state.snapshot());
 }
 
-fn next_roll_score(scores: &[(Score, Score)], frame_index: usize) -> usize {
-    if frame_index >= scores.len() {
-        return 0;
+/// Returns the score for a scorecard, counting every frame whose bonuses are known
+pub fn get_score(frames: &[Frame], variant: BowlingVariant) -> usize {
+    running_totals(frames, variant)
+        .into_iter()
+        .flatten()
+        .last()
+        .unwrap_or(0)
+}
+
+/// Returns the cumulative score after each frame. Strikes and spares stay `None` until the
+/// throws their bonus depends on have been made, as does every frame after them
+pub fn running_totals(frames: &[Frame], variant: BowlingVariant) -> Vec<Option<usize>> {
+    let rolls: Vec<usize> = frames
+        .iter()
+        .flat_map(|frame| frame.throws().iter().map(|&pins| pins as usize))
+        .collect();
+    let bonus = |from: usize, count: usize| -> Option<usize> {
+        rolls
+            .get(from..from + count)
+            .map(|bonus| bonus.iter().sum())
+    };
+
+    let mut total = Some(0);
+    let mut roll = 0;
+
+    frames
+        .iter()
+        .take(FRAME_COUNT)
+        .enumerate()
+        .map(|(idx, frame)| {
+            let frame_score = if frame.is_strike() {
+                bonus(roll + 1, 2).map(|bonus| 10 + bonus)
+            } else if frame.is_spare() {
+                bonus(roll + 2, 1).map(|bonus| 10 + bonus)
+            } else if frame.is_complete(idx + 1 == FRAME_COUNT, variant) {
+                Some(frame.throws().iter().map(|&pins| pins as usize).sum())
+            } else {
+                None
+            };
+
+            roll += if frame.is_strike() {
+                1
+            } else {
+                frame.throws().len().min(variant.throws_per_frame())
+            };
+            total = total.zip(frame_score).map(|(total, score)| total + score);
+            total
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_score, leaders, running_totals, BowlingState, Frame, Score, ThrowOutcome, FRAME_COUNT,
+    };
+    use crate::variant::BowlingVariant;
+
+    /// A frame with the given throws
+    fn frame(throws: &[u8]) -> Frame {
+        let mut frame = Frame::default();
+        for &pins in throws {
+            frame.record(pins);
+        }
+        frame
     }
 
-    match scores[frame_index].0 {
-        Score::Normal(pins) => pins,
-        Score::Spare => 10,
-        Score::Strike => 10,
-        _ => unreachable!("Score shouldn't be calculated until game is over"),
+    /// A scorecard with the given throws for each frame
+    fn scorecard(frames: &[&[u8]]) -> Vec<Frame> {
+        frames.iter().map(|throws| frame(throws)).collect()
     }
-}
 
-fn next_two_rolls_score(scores: &[(Score, Score)], frame_index: usize) -> usize {
-    if frame_index >= scores.len() {
-        return 0;
+    #[test]
+    fn perfect_game_scores_300() {
+        let mut frames = vec![&[10][..]; FRAME_COUNT - 1];
+        frames.push(&[10, 10, 10]);
+        let frames = scorecard(&frames);
+
+        let totals: Vec<_> = (1..=FRAME_COUNT).map(|frame| Some(frame * 30)).collect();
+        assert_eq!(running_totals(&frames, BowlingVariant::Tenpin), totals);
+        assert_eq!(get_score(&frames, BowlingVariant::Tenpin), 300);
+        assert_eq!(frames[FRAME_COUNT - 1].marks(), vec![Score::Strike; 3]);
+    }
+
+    #[test]
+    fn all_spares_scores_150() {
+        let mut frames = vec![&[5, 5][..]; FRAME_COUNT - 1];
+        frames.push(&[5, 5, 5]);
+        let frames = scorecard(&frames);
+
+        let totals: Vec<_> = (1..=FRAME_COUNT).map(|frame| Some(frame * 15)).collect();
+        assert_eq!(running_totals(&frames, BowlingVariant::Tenpin), totals);
+        assert_eq!(get_score(&frames, BowlingVariant::Tenpin), 150);
+        assert_eq!(frames[0].marks(), vec![Score::Normal(5), Score::Spare]);
+        assert_eq!(
+            frames[FRAME_COUNT - 1].marks(),
+            vec![Score::Normal(5), Score::Spare, Score::Normal(5)]
+        );
+    }
+
+    #[test]
+    fn frames_end_on_a_strike_or_after_two_throws() {
+        let tenpin = BowlingVariant::Tenpin;
+        assert!(frame(&[10]).is_complete(false, tenpin));
+        assert!(!frame(&[3]).is_complete(false, tenpin));
+        assert!(frame(&[3, 4]).is_complete(false, tenpin));
+        assert!(frame(&[3, 7]).is_complete(false, tenpin));
+    }
+
+    #[test]
+    fn tenth_frame_strike_gets_two_bonus_throws() {
+        let tenpin = BowlingVariant::Tenpin;
+        assert!(!frame(&[10]).is_complete(true, tenpin));
+        assert!(!frame(&[10, 3]).is_complete(true, tenpin));
+        assert!(frame(&[10, 3, 7]).is_complete(true, tenpin));
+        assert_eq!(
+            frame(&[10, 3, 7]).marks(),
+            vec![Score::Strike, Score::Normal(3), Score::Spare]
+        );
+
+        let mut frames = vec![&[0, 0][..]; FRAME_COUNT - 1];
+        frames.push(&[10, 3, 7]);
+        assert_eq!(get_score(&scorecard(&frames), tenpin), 20);
+    }
+
+    #[test]
+    fn open_tenth_frame_gets_no_bonus_throw() {
+        let tenpin = BowlingVariant::Tenpin;
+        assert!(frame(&[3, 4]).is_complete(true, tenpin));
+        assert_eq!(
+            frame(&[3, 4]).marks(),
+            vec![Score::Normal(3), Score::Normal(4)]
+        );
+
+        let mut frames = vec![&[0, 0][..]; FRAME_COUNT - 1];
+        frames.push(&[3, 4]);
+        assert_eq!(get_score(&scorecard(&frames), tenpin), 7);
+    }
+
+    #[test]
+    fn running_totals_wait_for_bonus_throws() {
+        let tenpin = BowlingVariant::Tenpin;
+
+        let frames = scorecard(&[&[10], &[3]]);
+        assert_eq!(running_totals(&frames, tenpin), vec![None, None]);
+        assert_eq!(get_score(&frames, tenpin), 0);
+
+        let frames = scorecard(&[&[10], &[3, 4]]);
+        assert_eq!(running_totals(&frames, tenpin), vec![Some(17), Some(24)]);
+
+        let frames = scorecard(&[&[2, 3], &[6, 4], &[]]);
+        assert_eq!(running_totals(&frames, tenpin), vec![Some(5), None, None]);
+        assert_eq!(get_score(&frames, tenpin), 5);
+
+        let frames = scorecard(&[&[2, 3], &[6, 4], &[8]]);
+        assert_eq!(
+            running_totals(&frames, tenpin),
+            vec![Some(5), Some(23), None]
+        );
+    }
+
+    /// Knocks down pins and scores the throw
+    fn throw(state: &mut BowlingState, pins: &[u8]) -> ThrowOutcome {
+        for &pin in pins {
+            state.topple_pin(pin);
+        }
+        state.inc_throw_num();
+        state.finish_throw()
+    }
+
+    #[test]
+    fn variant_frames_end_on_a_strike_or_after_three_throws() {
+        for variant in [BowlingVariant::Candlepin, BowlingVariant::Duckpin] {
+            assert!(frame(&[10]).is_complete(false, variant));
+            assert!(!frame(&[3, 4]).is_complete(false, variant));
+            assert!(frame(&[3, 7]).is_complete(false, variant));
+            assert!(frame(&[3, 4, 2]).is_complete(false, variant));
+
+            assert!(!frame(&[10, 3]).is_complete(true, variant));
+            assert!(!frame(&[3, 7]).is_complete(true, variant));
+            assert!(frame(&[3, 7, 5]).is_complete(true, variant));
+            assert!(!frame(&[3, 4]).is_complete(true, variant));
+            assert!(frame(&[3, 4, 2]).is_complete(true, variant));
+        }
+    }
+
+    #[test]
+    fn variant_racks_stay_up_for_three_throws() {
+        for variant in [BowlingVariant::Candlepin, BowlingVariant::Duckpin] {
+            let mut state = BowlingState::default();
+            state.set_variant(variant);
+
+            assert_eq!(throw(&mut state, &[1, 2, 3]), ThrowOutcome::ThrowAgain);
+            assert_eq!(state.standing(), vec![4, 5, 6, 7, 8, 9, 10]);
+            assert_eq!(throw(&mut state, &[4]), ThrowOutcome::ThrowAgain);
+            assert_eq!(state.standing(), vec![5, 6, 7, 8, 9, 10]);
+            assert_eq!(throw(&mut state, &[5]), ThrowOutcome::NextTurn);
+            assert_eq!(state.player_frames[0][0].throws(), &[3, 1, 1]);
+        }
+
+        let mut state = BowlingState::default();
+        assert_eq!(throw(&mut state, &[1, 2, 3]), ThrowOutcome::ThrowAgain);
+        assert_eq!(throw(&mut state, &[4]), ThrowOutcome::NextTurn);
+    }
+
+    #[test]
+    fn variant_scoring_counts_three_throws_a_frame() {
+        for variant in [BowlingVariant::Candlepin, BowlingVariant::Duckpin] {
+            let frames = scorecard(&[&[3, 4, 2][..]; FRAME_COUNT]);
+            assert_eq!(get_score(&frames, variant), 90);
+
+            let frames = scorecard(&[&[3, 7], &[5, 1, 1], &[10], &[2, 3, 4]]);
+            assert_eq!(
+                running_totals(&frames, variant),
+                vec![Some(15), Some(22), Some(37), Some(46)]
+            );
+
+            let frames = scorecard(&[&[3, 4], &[5, 1, 1]]);
+            assert_eq!(running_totals(&frames, variant), vec![None, None]);
+        }
+    }
+
+    #[test]
+    fn variant_rack_cleared_on_the_third_throw_earns_no_bonus() {
+        let cleared = frame(&[3, 4, 3]);
+        assert_eq!(
+            cleared.marks(),
+            vec![Score::Normal(3), Score::Normal(4), Score::Normal(3)]
+        );
+
+        let frames = scorecard(&[&[3, 4, 3], &[5, 0, 0]]);
+        assert_eq!(
+            running_totals(&frames, BowlingVariant::Candlepin),
+            vec![Some(10), Some(15)]
+        );
     }
 
-    match scores[frame_index] {
-        (Score::Normal(pins1), Score::Normal(pins2)) => pins1 + pins2,
-        (_, Score::Spare) => 10,
-        (Score::Strike, _) => 10,
-        _ => unreachable!("Score shouldn't be calculated until game is over"),
+    #[test]
+    fn leaders_are_everyone_tied_for_the_top_score() {
+        assert_eq!(
+            leaders(&[(0, 120), (1, 180), (2, 95)]),
+            Some((vec![1], 180))
+        );
+        assert_eq!(
+            leaders(&[(0, 150), (1, 90), (2, 150)]),
+            Some((vec![0, 2], 150))
+        );
+        assert_eq!(leaders(&[]), None);
     }
 }
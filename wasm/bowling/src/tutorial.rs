@@ -0,0 +1,187 @@
+//! Walkthrough for first-time bowlers that teaches aiming, swinging and releasing on their first
+//! throw, moving on as each step is actually done with the controller
+
+use bevy::prelude::*;
+use spjorts_core::spectator::is_playing;
+
+use crate::{phase::BowlingPhase, setup::ball::Ball, turns::BowlingStateWrapper};
+
+/// How far the controller has to be turned, in radians, for the aim step to count
+const AIM_MOVE_THRESHOLD: f32 = 0.05;
+/// How fast the controller has to swing, in radians per second, for the swing step to count
+const SWING_THRESHOLD: f32 = 0.6;
+/// Font size of the tutorial prompt
+const TUTORIAL_FONT_SIZE: f32 = 26.0;
+
+/// Step of the tutorial the player is on
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TutorialStep {
+    /// No tutorial is running
+    #[default]
+    Off,
+    /// Turning the controller to aim the throw
+    Aim,
+    /// Swinging the controller like a bowling arm
+    Swing,
+    /// Pressing A mid-swing to let go of the ball
+    Release,
+}
+
+impl TutorialStep {
+    /// What the player is asked to do on this step
+    fn prompt(&self) -> &'static str {
+        match self {
+            Self::Off => "",
+            Self::Aim => "Hold the remote like a ball and turn it to aim the arrow",
+            Self::Swing => "Now swing your arm back and forth like you're bowling",
+            Self::Release => "Press A mid-swing to let go of the ball",
+        }
+    }
+}
+
+/// Panel near the top of the screen the tutorial is shown in
+#[derive(Component)]
+struct TutorialPanel;
+
+/// Line of the panel that says what to do on the current step
+#[derive(Component)]
+struct TutorialPrompt;
+
+/// Plugin that runs the tutorial on a session's first throw
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<TutorialStep>()
+            .add_systems(Startup, setup_tutorial)
+            .add_systems(
+                OnEnter(BowlingPhase::Aiming),
+                start_tutorial.run_if(is_playing),
+            )
+            .add_systems(OnEnter(BowlingPhase::Rolling), finish_tutorial)
+            .add_systems(
+                Update,
+                (
+                    advance_tutorial.run_if(not(in_state(TutorialStep::Off))),
+                    show_prompt.run_if(state_changed::<TutorialStep>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Spawns the hidden tutorial prompt
+fn setup_tutorial(mut commands: Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(18.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            TutorialPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(TUTORIAL_FONT_SIZE),
+                        TextColor(Color::WHITE),
+                        TextLayout::new_with_justify(JustifyText::Center),
+                        TutorialPrompt,
+                    ));
+                    panel.spawn((
+                        Text::new("Press B to skip the tutorial"),
+                        TextFont::from_font_size(TUTORIAL_FONT_SIZE * 0.6),
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                    ));
+                });
+        });
+}
+
+/// Starts the tutorial the first time the lane is ready for a throw, holding the ball still so
+/// the player can focus on the controller
+fn start_tutorial(
+    mut shown: Local<'_, bool>,
+    state: Res<'_, BowlingStateWrapper>,
+    mut balls: Query<'_, '_, &mut Ball>,
+    mut next: ResMut<'_, NextState<TutorialStep>>,
+) {
+    if *shown || state.has_started() {
+        return;
+    }
+
+    *shown = true;
+    for mut ball in &mut balls {
+        ball.moving = None;
+    }
+    next.set(TutorialStep::Aim);
+}
+
+/// Moves to the next step once the controller has been turned far enough to aim, or swung fast
+/// enough to throw
+fn advance_tutorial(
+    step: Res<'_, State<TutorialStep>>,
+    mut next: ResMut<'_, NextState<TutorialStep>>,
+    balls: Query<'_, '_, &Ball>,
+) {
+    let Ok(ball) = balls.get_single() else {
+        return;
+    };
+
+    match step.get() {
+        TutorialStep::Aim if ball.aim.abs() > AIM_MOVE_THRESHOLD => {
+            next.set(TutorialStep::Swing);
+        }
+        TutorialStep::Swing
+            if ball
+                .angular_velocity()
+                .is_some_and(|speed| speed > SWING_THRESHOLD) =>
+        {
+            next.set(TutorialStep::Release);
+        }
+        _ => {}
+    }
+}
+
+/// Ends the tutorial once the ball has been let go, whichever step the player was on
+fn finish_tutorial(
+    step: Res<'_, State<TutorialStep>>,
+    mut next: ResMut<'_, NextState<TutorialStep>>,
+) {
+    if *step.get() != TutorialStep::Off {
+        next.set(TutorialStep::Off);
+    }
+}
+
+/// Shows the current step's prompt, hiding the panel once the tutorial is over
+fn show_prompt(
+    step: Res<'_, State<TutorialStep>>,
+    mut panels: Query<'_, '_, &mut Visibility, With<TutorialPanel>>,
+    mut prompts: Query<'_, '_, &mut Text, With<TutorialPrompt>>,
+) {
+    for mut visibility in &mut panels {
+        *visibility = if *step.get() == TutorialStep::Off {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+    }
+
+    for mut text in &mut prompts {
+        *text = Text::new(step.prompt());
+    }
+}
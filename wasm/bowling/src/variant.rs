@@ -0,0 +1,204 @@
+//! Tenpin, candlepin and duckpin rules and equipment, picked before a standard game
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Velocity};
+use serde::{Deserialize, Serialize};
+use spjorts_core::menu::{Menu, MenuSelected};
+
+use crate::{
+    setup::{
+        ball::{BALL_SPECS, SMALL_BALL_SPECS},
+        pin::{pin_collider, pin_mesh},
+        Ball, BallSpec, Pin, PIN_HEIGHT, PIN_RADIUS,
+    },
+    turns::BowlingStateWrapper,
+};
+
+/// Font size of the variant menu's options
+const MENU_FONT_SIZE: f32 = 36.0;
+/// Color of the highlighted variant
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+/// How far above the lane pin origins sit, on top of half their height
+const PIN_LIFT: f32 = 0.05;
+
+/// Which kind of bowling is played
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BowlingVariant {
+    /// Big balls and pins, two throws a frame
+    #[default]
+    Tenpin,
+    /// Thin pins and small balls, three throws a frame with fallen pins left on the deck
+    Candlepin,
+    /// Short squat pins and small balls, three throws a frame
+    Duckpin,
+}
+
+impl BowlingVariant {
+    /// Every variant, in menu order
+    const ALL: [Self; 3] = [Self::Tenpin, Self::Candlepin, Self::Duckpin];
+
+    /// Name shown in the variant menu
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Tenpin => "Tenpin",
+            Self::Candlepin => "Candlepin",
+            Self::Duckpin => "Duckpin",
+        }
+    }
+
+    /// Throws a player gets at each frame, outside of the 10th frame's bonus throws
+    pub fn throws_per_frame(&self) -> usize {
+        match self {
+            Self::Tenpin => 2,
+            Self::Candlepin | Self::Duckpin => 3,
+        }
+    }
+
+    /// Whether fallen pins are swept off the deck between throws in a frame
+    pub fn clears_deadwood(&self) -> bool {
+        *self != Self::Candlepin
+    }
+
+    /// Pin `(radius, height)`
+    pub fn pin_size(&self) -> (f32, f32) {
+        match self {
+            Self::Tenpin => (PIN_RADIUS, PIN_HEIGHT),
+            Self::Candlepin => (PIN_RADIUS * 0.6, PIN_HEIGHT * 1.05),
+            Self::Duckpin => (PIN_RADIUS * 0.85, PIN_HEIGHT * 0.6),
+        }
+    }
+
+    /// Balls players can pick from, lightest first
+    pub fn ball_specs(&self) -> &'static [BallSpec] {
+        match self {
+            Self::Tenpin => &BALL_SPECS,
+            Self::Candlepin | Self::Duckpin => &SMALL_BALL_SPECS,
+        }
+    }
+}
+
+/// Marks the variant menu
+#[derive(Component)]
+struct VariantMenu;
+
+/// An entry in the variant menu
+#[derive(Component)]
+struct VariantOption(usize);
+
+/// Plugin that adds the variant menu and applies the picked variant's equipment and rules
+pub struct VariantPlugin;
+
+impl Plugin for VariantPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BowlingVariant>().add_systems(
+            Update,
+            (highlight_variant_menu, choose_variant, apply_variant).chain(),
+        );
+    }
+}
+
+/// Spawns the variant menu, focused so it takes input straight away
+pub fn spawn_variant_menu(commands: &mut Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Menu::new(BowlingVariant::ALL.len()),
+            VariantMenu,
+        ))
+        .with_children(|menu| {
+            for (idx, variant) in BowlingVariant::ALL.iter().enumerate() {
+                menu.spawn((
+                    Text::new(variant.name()),
+                    TextFont::from_font_size(MENU_FONT_SIZE),
+                    TextColor::WHITE,
+                    VariantOption(idx),
+                ));
+            }
+        });
+}
+
+/// Colors the highlighted variant
+fn highlight_variant_menu(
+    menus: Query<'_, '_, &Menu, (With<VariantMenu>, Changed<Menu>)>,
+    mut options: Query<'_, '_, (&VariantOption, &mut TextColor)>,
+) {
+    for menu in &menus {
+        for (option, mut color) in &mut options {
+            color.0 = if option.0 == menu.selected {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+/// Sets the variant once one is picked and closes the variant menu
+fn choose_variant(
+    mut commands: Commands<'_, '_>,
+    mut selections: EventReader<'_, '_, MenuSelected>,
+    menus: Query<'_, '_, (), With<VariantMenu>>,
+    mut variant: ResMut<'_, BowlingVariant>,
+) {
+    for selection in selections.read() {
+        if menus.get(selection.menu).is_err() {
+            continue;
+        }
+
+        variant.set_if_neq(BowlingVariant::ALL[selection.index]);
+        commands.entity(selection.menu).despawn_recursive();
+    }
+}
+
+/// Swaps in the variant's pins and balls and switches the scorecards to its rules, unless they
+/// already follow them from a resumed save
+fn apply_variant(
+    variant: Res<'_, BowlingVariant>,
+    mut pins: Query<
+        '_,
+        '_,
+        (
+            &mut Pin,
+            &mut Transform,
+            &mut Mesh3d,
+            &mut Collider,
+            &mut Velocity,
+        ),
+    >,
+    mut ball: Query<'_, '_, &mut BallSpec, With<Ball>>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    state: Res<'_, BowlingStateWrapper>,
+) {
+    if !variant.is_changed() || variant.is_added() {
+        return;
+    }
+
+    let (radius, height) = variant.pin_size();
+    let mesh = meshes.add(pin_mesh(radius, height));
+    for (mut pin, mut transform, mut handle, mut collider, mut velocity) in &mut pins {
+        pin.initial_coords.translation.y = height * 0.5 + PIN_LIFT;
+        pin.reset(&mut transform);
+        *handle = Mesh3d(mesh.clone());
+        *collider = pin_collider(radius, height);
+        *velocity = Velocity::zero();
+    }
+
+    if let Ok(mut spec) = ball.get_single_mut() {
+        let specs = variant.ball_specs();
+        *spec = specs[specs.len() / 2];
+    }
+
+    if state.get_variant() != *variant {
+        state.set_variant(*variant);
+    }
+}
@@ -0,0 +1,217 @@
+//! The gym and its heavy bag, which hangs from a chain and swings, twists and jolts as punches
+//! land on it
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use spjorts_core::settings::GameSettings;
+
+use crate::{punch::Punch, workout::Thrown};
+
+/// Point the bag's chain hangs from
+const ANCHOR: Vec3 = Vec3::new(0.0, 2.7, 0.0);
+/// Length of the chain from the anchor to the top of the bag
+const CHAIN_LENGTH: f32 = 0.45;
+/// Height of the bag
+const BAG_HEIGHT: f32 = 1.1;
+/// Radius of the bag
+const BAG_RADIUS: f32 = 0.2;
+/// Distance from the anchor down to the bag's middle, the length of the pendulum it swings as
+const PENDULUM_LENGTH: f32 = CHAIN_LENGTH + BAG_HEIGHT / 2.0;
+/// Gravity pulling the bag back under its anchor, in meters per second squared
+const GRAVITY: f32 = 9.81;
+/// How quickly the bag's swing dies away, per second
+const SWING_DAMPING: f32 = 0.6;
+/// How stiffly the chain untwists the bag, per second squared
+const TWIST_STIFFNESS: f32 = 8.0;
+/// How quickly the bag's twist dies away, per second
+const TWIST_DAMPING: f32 = 1.5;
+/// How stiffly the chain pulls a jolted bag back down, per second squared
+const JOLT_STIFFNESS: f32 = 80.0;
+/// How quickly the bag's jolt dies away, per second
+const JOLT_DAMPING: f32 = 9.0;
+/// Swing speed each radian per second of a punch's turning speed gives the bag, in radians per
+/// second
+const PUNCH_KICK: f32 = 0.05;
+/// Widest the bag can swing from under its anchor, in radians
+const MAX_SWING: f32 = 0.6;
+/// Where the boxer stands, facing the bag
+const STANCE: Vec3 = Vec3::new(0.0, 1.6, 1.3);
+
+/// The heavy bag, swinging as a pendulum from its anchor
+#[derive(Component, Debug, Default)]
+pub struct HeavyBag {
+    /// How far it's swung away from the boxer and across to their right, in radians
+    swing: Vec2,
+    /// How fast it's swinging, in radians per second
+    swing_speed: Vec2,
+    /// How far it's twisted about its chain, in radians
+    twist: f32,
+    /// How fast it's twisting, in radians per second
+    twist_speed: f32,
+    /// How far it's been jolted up on its chain, in meters
+    jolt: f32,
+    /// How fast it's moving up or down on its chain, in meters per second
+    jolt_speed: f32,
+}
+
+impl HeavyBag {
+    /// Knocks the bag the way a punch thrown with a peak turning speed hits it: jabs drive it
+    /// straight back, hooks swing it across and twist it and uppercuts lift it as they push it
+    /// back. `handedness` mirrors hooks for left-handed boxers
+    pub fn hit(&mut self, punch: Punch, intensity: f32, handedness: f32) {
+        let kick = intensity * PUNCH_KICK;
+        match punch {
+            Punch::Jab => self.swing_speed.x += kick,
+            Punch::Hook => {
+                self.swing_speed.x += kick * 0.3;
+                self.swing_speed.y -= kick * handedness;
+                self.twist_speed -= kick * 2.0 * handedness;
+            }
+            Punch::Uppercut => {
+                self.swing_speed.x += kick * 0.5;
+                self.jolt_speed += kick * 0.8;
+            }
+        }
+    }
+
+    /// Moves the bag on by `secs`, letting gravity swing it back under its anchor and the chain
+    /// untwist it and pull it back down
+    fn step(&mut self, secs: f32) {
+        let pull = -GRAVITY / PENDULUM_LENGTH * Vec2::new(self.swing.x.sin(), self.swing.y.sin());
+        self.swing_speed += (pull - self.swing_speed * SWING_DAMPING) * secs;
+        self.swing += self.swing_speed * secs;
+        if self.swing.length() > MAX_SWING {
+            self.swing = self.swing.clamp_length_max(MAX_SWING);
+            self.swing_speed *= 0.5;
+        }
+
+        self.twist_speed +=
+            (-self.twist * TWIST_STIFFNESS - self.twist_speed * TWIST_DAMPING) * secs;
+        self.twist += self.twist_speed * secs;
+
+        self.jolt_speed += (-self.jolt * JOLT_STIFFNESS - self.jolt_speed * JOLT_DAMPING) * secs;
+        self.jolt = (self.jolt + self.jolt_speed * secs).max(0.0);
+    }
+
+    /// Where the bag hangs from right now, turned as it's swinging and twisting
+    fn transform(&self) -> Transform {
+        Transform::from_translation(ANCHOR + Vec3::Y * self.jolt).with_rotation(
+            Quat::from_rotation_x(self.swing.x)
+                * Quat::from_rotation_z(self.swing.y)
+                * Quat::from_rotation_y(self.twist),
+        )
+    }
+}
+
+/// Plugin that builds the gym and swings the heavy bag
+pub struct BagPlugin;
+
+impl Plugin for BagPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_gym)
+            .add_systems(Update, (hit_bag, swing_bag).chain());
+    }
+}
+
+/// Spawns the floor, the back wall, the heavy bag on its chain, the camera and the lights
+fn setup_gym(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(8.0, 8.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.22, 0.24, 0.3))),
+        Name::new("Mat"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(8.0, 4.0, 0.1))),
+        MeshMaterial3d(materials.add(Color::srgb(0.55, 0.5, 0.45))),
+        Transform::from_xyz(0.0, 2.0, -2.5),
+        Name::new("Wall"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.6, 0.08, 0.08))),
+        MeshMaterial3d(materials.add(Color::srgb(0.15, 0.15, 0.15))),
+        Transform::from_translation(ANCHOR + Vec3::Y * 0.04),
+        Name::new("Ceiling mount"),
+    ));
+
+    let bag = HeavyBag::default();
+    commands
+        .spawn((
+            bag.transform(),
+            Visibility::Visible,
+            Name::new("Heavy bag"),
+            bag,
+        ))
+        .with_children(|bag| {
+            bag.spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.012, CHAIN_LENGTH))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.7, 0.7, 0.72),
+                    metallic: 0.9,
+                    perceptual_roughness: 0.3,
+                    ..default()
+                })),
+                Transform::from_xyz(0.0, -CHAIN_LENGTH / 2.0, 0.0),
+            ));
+            bag.spawn((
+                Mesh3d(meshes.add(Cylinder::new(BAG_RADIUS, BAG_HEIGHT))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.6, 0.08, 0.06),
+                    perceptual_roughness: 0.6,
+                    ..default()
+                })),
+                Transform::from_xyz(0.0, -PENDULUM_LENGTH, 0.0),
+            ));
+            // A stripe down the front, so the bag's twist shows
+            bag.spawn((
+                Mesh3d(meshes.add(Cuboid::new(0.06, 0.3, 0.02))),
+                MeshMaterial3d(materials.add(Color::WHITE)),
+                Transform::from_xyz(0.0, -PENDULUM_LENGTH, BAG_RADIUS),
+            ));
+        });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(STANCE).looking_at(ANCHOR - Vec3::Y * PENDULUM_LENGTH, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(1.0, 4.0, 2.0).with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.7)),
+    ));
+    commands.spawn((
+        PointLight {
+            intensity: 200_000.0,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.5, 0.5),
+    ));
+}
+
+/// Knocks the bag with every punch thrown at it, whether it was prompted or not
+fn hit_bag(
+    mut thrown: EventReader<'_, '_, Thrown>,
+    mut bags: Query<'_, '_, &mut HeavyBag>,
+    settings: Res<'_, GameSettings>,
+) {
+    for punch in thrown.read() {
+        for mut bag in &mut bags {
+            bag.hit(punch.punch, punch.intensity, settings.handedness());
+        }
+    }
+}
+
+/// Swings the bag on its chain
+fn swing_bag(mut bags: Query<'_, '_, (&mut HeavyBag, &mut Transform)>, time: Res<'_, Time>) {
+    for (mut bag, mut transform) in &mut bags {
+        bag.step(time.delta_secs());
+        *transform = bag.transform();
+    }
+}
@@ -0,0 +1,101 @@
+//! Bevy boxing game
+
+use bag::BagPlugin;
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use phase::{BoxingPhase, BoxingPhasePlugin};
+use prompts::PromptsPlugin;
+use punch::{punch_thresholds, Punch};
+use spjorts_core::{
+    communication::JsMessage, gesture::GestureDetector, menu::MenuAction, settings::GameSettings,
+    spectator::is_playing, turns::TurnPlugin, ActionReader,
+};
+use workout::{NewGame, Thrown, WorkoutPlugin};
+
+pub mod bag;
+pub mod phase;
+pub mod prompts;
+pub mod punch;
+pub mod workout;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(BoxingPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(BagPlugin)
+    .add_plugins(PromptsPlugin)
+    .add_plugins(WorkoutPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.08, 0.08, 0.1)))
+    .init_resource::<Fists>()
+    .add_systems(Update, handle_input.run_if(is_playing));
+});
+
+/// Watches the controller for punches
+#[derive(Resource, Debug)]
+pub struct Fists {
+    /// Picks sharp movements out of the controller's rotations
+    detector: GestureDetector,
+}
+
+impl Default for Fists {
+    fn default() -> Self {
+        Self {
+            detector: GestureDetector::new(punch_thresholds()),
+        }
+    }
+}
+
+/// Everything input handling changes besides the fists themselves
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Punches thrown at the bag
+    thrown: EventWriter<'w, Thrown>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: jabbing, hooking or uppercutting with the controller throws that
+/// punch at the bag, scored against the prompts once the round is on. A starts a new workout
+/// once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut fists: ResMut<'_, Fists>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<BoxingPhase>>,
+    time: Res<'_, Time>,
+) {
+    let boxing = matches!(phase.get(), BoxingPhase::Ready | BoxingPhase::Round);
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == BoxingPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if boxing => {
+                let orientation = settings.apply_rotation(orientation);
+                let Some(detected) = fists.detector.update(orientation, time.elapsed_secs()) else {
+                    continue;
+                };
+                if let Some(punch) = Punch::classify(&detected) {
+                    effects.thrown.send(Thrown {
+                        punch,
+                        intensity: detected.intensity,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Phases a boxing workout moves through, from squaring up to the final scores
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the workout is in the flow of a round
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BoxingPhase {
+    /// The boxer up is squaring up to the bag before their round starts
+    #[default]
+    Ready,
+    /// Prompts are scrolling in and punches are being scored
+    Round,
+    /// The round is over and its score is up before the next boxer squares up
+    Resting,
+    /// Every boxer has had their round and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the workout is in
+pub struct BoxingPhasePlugin;
+
+impl Plugin for BoxingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<BoxingPhase>();
+    }
+}
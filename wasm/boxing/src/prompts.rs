@@ -0,0 +1,250 @@
+//! The round's prompts: combos of punches laid out on the beat, scrolling in along a track at
+//! the bottom of the screen to a hit line where each should land
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    phase::BoxingPhase,
+    punch::{punch_points, Punch, Timing, GOOD_WINDOW},
+};
+
+/// Seconds between beats
+pub const BEAT_SECS: f32 = 0.6;
+/// Beats before the first prompt lands, to get the rhythm
+const LEAD_IN_BEATS: usize = 4;
+/// Punches in a round
+const ROUND_PUNCHES: usize = 24;
+/// Combos a round is built from, in order, each thrown on consecutive beats with a beat's rest
+/// after it
+const COMBOS: [&[Punch]; 6] = [
+    &[Punch::Jab, Punch::Jab],
+    &[Punch::Jab, Punch::Hook],
+    &[Punch::Jab, Punch::Jab, Punch::Uppercut],
+    &[Punch::Hook, Punch::Uppercut],
+    &[Punch::Jab, Punch::Hook, Punch::Uppercut],
+    &[Punch::Uppercut, Punch::Hook, Punch::Jab, Punch::Jab],
+];
+/// How far across the screen the hit line is, in percent
+const HIT_LINE: f32 = 20.0;
+/// How far across the screen a prompt scrolls every second, in percent
+const SCROLL_SPEED: f32 = 25.0;
+/// Font size of the prompts
+const PROMPT_FONT_SIZE: f32 = 26.0;
+
+/// How a prompt turned out
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    /// The right punch landed close enough to the beat, for these points
+    Landed(Timing, u32),
+    /// A different punch was thrown at it
+    Wrong,
+    /// Nothing was thrown at it in time
+    Missed,
+}
+
+/// A punch to throw on a beat
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Prompt {
+    /// Which punch
+    pub punch: Punch,
+    /// When it should land, in seconds since the round started
+    pub at: f32,
+    /// How it turned out, once it has
+    pub outcome: Option<Outcome>,
+}
+
+/// The prompts of the round being boxed and how far into it the boxer is
+#[derive(Resource, Serialize, Debug, Clone, PartialEq)]
+pub struct Track {
+    /// Every prompt of the round, in the order they land
+    prompts: Vec<Prompt>,
+    /// Seconds since the round started
+    clock: f32,
+}
+
+impl Default for Track {
+    fn default() -> Self {
+        let mut prompts = Vec::with_capacity(ROUND_PUNCHES);
+        let mut beat = LEAD_IN_BEATS;
+        loop {
+            for combo in COMBOS.iter() {
+                for punch in combo.iter() {
+                    if prompts.len() == ROUND_PUNCHES {
+                        return Self {
+                            prompts,
+                            clock: 0.0,
+                        };
+                    }
+                    prompts.push(Prompt {
+                        punch: *punch,
+                        at: beat as f32 * BEAT_SECS,
+                        outcome: None,
+                    });
+                    beat += 1;
+                }
+                beat += 1;
+            }
+        }
+    }
+}
+
+impl Track {
+    /// Every prompt of the round, in the order they land
+    pub fn prompts(&self) -> &[Prompt] {
+        &self.prompts
+    }
+
+    /// Seconds since the round started
+    pub fn clock(&self) -> f32 {
+        self.clock
+    }
+
+    /// Moves the round on by `secs`, marking every prompt that went by without a punch as
+    /// missed. Returns how many were
+    pub fn tick(&mut self, secs: f32) -> usize {
+        self.clock += secs;
+        let clock = self.clock;
+        let mut missed = 0;
+        for prompt in &mut self.prompts {
+            if prompt.outcome.is_none() && clock > prompt.at + GOOD_WINDOW {
+                prompt.outcome = Some(Outcome::Missed);
+                missed += 1;
+            }
+        }
+        missed
+    }
+
+    /// Scores a punch thrown now against the next prompt close enough to the beat to take it,
+    /// returning that prompt, or `None` if it was thrown between prompts
+    pub fn throw(&mut self, punch: Punch, intensity: f32) -> Option<Prompt> {
+        let clock = self.clock;
+        let prompt = self
+            .prompts
+            .iter_mut()
+            .find(|prompt| prompt.outcome.is_none() && (clock - prompt.at).abs() <= GOOD_WINDOW)?;
+        prompt.outcome = Some(match Timing::from_offset(clock - prompt.at) {
+            Some(timing) if prompt.punch == punch => {
+                Outcome::Landed(timing, punch_points(timing, intensity))
+            }
+            _ => Outcome::Wrong,
+        });
+        Some(*prompt)
+    }
+
+    /// Whether every prompt has turned out one way or another
+    pub fn is_done(&self) -> bool {
+        self.prompts.iter().all(|prompt| prompt.outcome.is_some())
+    }
+}
+
+/// The track the prompts scroll along
+#[derive(Component)]
+struct TrackUi;
+
+/// A prompt on the track, by its place in the round
+#[derive(Component)]
+struct PromptLabel(usize);
+
+/// Plugin that lays out each round's prompts and scrolls them along the track
+pub struct PromptsPlugin;
+
+impl Plugin for PromptsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Track>()
+            .add_systems(
+                OnEnter(BoxingPhase::Ready),
+                (reset_track, spawn_track).chain(),
+            )
+            .add_systems(OnEnter(BoxingPhase::GameOver), despawn_track)
+            .add_systems(Update, scroll_prompts);
+    }
+}
+
+/// Lays out a fresh round of prompts
+fn reset_track(mut track: ResMut<'_, Track>) {
+    *track = Track::default();
+}
+
+/// Removes the track and every prompt on it
+fn despawn_track(mut commands: Commands<'_, '_>, tracks: Query<'_, '_, Entity, With<TrackUi>>) {
+    for track in &tracks {
+        commands.entity(track).despawn_recursive();
+    }
+}
+
+/// Spawns the track along the bottom of the screen with its hit line and a label for every
+/// prompt of the round
+fn spawn_track(
+    mut commands: Commands<'_, '_>,
+    track: Res<'_, Track>,
+    tracks: Query<'_, '_, Entity, With<TrackUi>>,
+) {
+    for old in &tracks {
+        commands.entity(old).despawn_recursive();
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                bottom: Val::Percent(8.0),
+                height: Val::Px(56.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            TrackUi,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(HIT_LINE),
+                    width: Val::Px(4.0),
+                    top: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+                BackgroundColor(Color::WHITE),
+            ));
+            for (index, prompt) in track.prompts().iter().enumerate() {
+                parent.spawn((
+                    Text::new(prompt.punch.name()),
+                    TextFont {
+                        font_size: PROMPT_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(prompt.punch.color()),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(12.0),
+                        ..default()
+                    },
+                    Visibility::Hidden,
+                    PromptLabel(index),
+                ));
+            }
+        });
+}
+
+/// Slides every prompt toward the hit line as the round goes on, hiding those that have turned
+/// out or are still off screen
+fn scroll_prompts(
+    track: Res<'_, Track>,
+    mut labels: Query<'_, '_, (&PromptLabel, &mut Node, &mut Visibility)>,
+) {
+    for (label, mut node, mut visibility) in &mut labels {
+        let Some(prompt) = track.prompts().get(label.0) else {
+            continue;
+        };
+        let left = HIT_LINE + (prompt.at - track.clock()) * SCROLL_SPEED;
+        node.left = Val::Percent(left);
+        visibility.set_if_neq(if prompt.outcome.is_none() && left <= 100.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
@@ -0,0 +1,127 @@
+//! Punches: telling a jab, hook and uppercut apart from the way a gesture turned the controller,
+//! and scoring one thrown at a prompt by how close to the beat and how hard it landed
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::gesture::{DetectedGesture, Gesture, GestureThresholds};
+
+/// Closest to the beat a punch can land and still be perfect, either side, in seconds
+const PERFECT_WINDOW: f32 = 0.08;
+/// Closest to the beat a punch can land and still be great, in seconds
+const GREAT_WINDOW: f32 = 0.16;
+/// Furthest from the beat a punch can land and still count for its prompt, in seconds
+pub const GOOD_WINDOW: f32 = 0.25;
+/// Turning speed a punch has to reach to earn any power bonus, in radians per second
+const SOFT_PUNCH_SPEED: f32 = 6.0;
+/// Turning speed that earns the full power bonus, in radians per second
+const HARD_PUNCH_SPEED: f32 = 16.0;
+/// Most a punch's power can add to its timing points
+const MAX_POWER_POINTS: u32 = 50;
+
+/// A punch the boxer can throw
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Punch {
+    /// A short straight punch, snapping the controller forward
+    Jab,
+    /// A looping punch from the side, sweeping the controller across
+    Hook,
+    /// A rising punch from below, tipping the controller up
+    Uppercut,
+}
+
+impl Punch {
+    /// Name shown on the prompts
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Jab => "JAB",
+            Self::Hook => "HOOK",
+            Self::Uppercut => "UPPERCUT",
+        }
+    }
+
+    /// Color of this punch's prompts
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Jab => Color::srgb(0.3, 0.75, 1.0),
+            Self::Hook => Color::srgb(1.0, 0.55, 0.15),
+            Self::Uppercut => Color::srgb(0.55, 1.0, 0.3),
+        }
+    }
+
+    /// Tells which punch a gesture was: a hook turns the controller mostly about its yaw, an
+    /// uppercut tips it up and anything else sharp enough is a jab
+    pub fn classify(detected: &DetectedGesture) -> Option<Self> {
+        if !matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+            return None;
+        }
+        let turned = detected.turned;
+        Some(
+            if turned.yaw.abs() > turned.pitch.abs().max(turned.roll.abs()) {
+                Self::Hook
+            } else if turned.pitch > turned.roll.abs() {
+                Self::Uppercut
+            } else {
+                Self::Jab
+            },
+        )
+    }
+}
+
+/// Gesture thresholds for punching. Hooks sweep about yaw, so twists are turned off to keep them
+/// from being read as one
+pub fn punch_thresholds() -> GestureThresholds {
+    GestureThresholds {
+        twist_angle: f32::INFINITY,
+        ..default()
+    }
+}
+
+/// How close to its beat a punch landed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    /// Right on the beat
+    Perfect,
+    /// Just off it
+    Great,
+    /// Off it, but close enough to count
+    Good,
+}
+
+impl Timing {
+    /// How close a punch landed `offset` seconds from its beat was, or `None` if it was too far
+    /// off to count
+    pub fn from_offset(offset: f32) -> Option<Self> {
+        match offset.abs() {
+            offset if offset <= PERFECT_WINDOW => Some(Self::Perfect),
+            offset if offset <= GREAT_WINDOW => Some(Self::Great),
+            offset if offset <= GOOD_WINDOW => Some(Self::Good),
+            _ => None,
+        }
+    }
+
+    /// Name shown when a punch lands
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Perfect => "Perfect",
+            Self::Great => "Great",
+            Self::Good => "Good",
+        }
+    }
+
+    /// Points for landing a punch this close to its beat
+    fn points(&self) -> u32 {
+        match self {
+            Self::Perfect => 100,
+            Self::Great => 70,
+            Self::Good => 40,
+        }
+    }
+}
+
+/// Points for a punch landed with a timing and thrown with a peak turning speed, in radians per
+/// second: the timing's points plus up to [`MAX_POWER_POINTS`] the harder it was thrown
+pub fn punch_points(timing: Timing, intensity: f32) -> u32 {
+    let power =
+        ((intensity - SOFT_PUNCH_SPEED) / (HARD_PUNCH_SPEED - SOFT_PUNCH_SPEED)).clamp(0.0, 1.0);
+    timing.points() + (power * MAX_POWER_POINTS as f32).round() as u32
+}
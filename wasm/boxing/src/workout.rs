@@ -0,0 +1,385 @@
+//! The workout: each boxer squares up to the bag and boxes a round of prompts in turn, every
+//! punch landed on the beat adding to their score on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::BoxingPhase,
+    prompts::{Outcome, Track},
+    punch::Punch,
+};
+
+/// Seconds a boxer gets to square up before their round starts
+const READY_SECS: f32 = 3.0;
+/// Seconds a round's score stays up before the next boxer squares up
+const REST_SECS: f32 = 3.0;
+/// Strongest rumble a landed punch sends back to the controller, out of 255
+const MAX_RUMBLE: f32 = 220.0;
+/// How long a landed punch rumbles the controller for, in milliseconds
+const RUMBLE_MILLIS: u16 = 90;
+/// Turning speed of a punch that rumbles at full strength, in radians per second
+const FULL_RUMBLE_SPEED: f32 = 16.0;
+
+/// A punch thrown at the bag
+#[derive(Event, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Thrown {
+    /// Which punch
+    pub punch: Punch,
+    /// How hard, as the controller's peak turning speed in radians per second
+    pub intensity: f32,
+}
+
+/// Asks for the workout to be started over from the first boxer's round
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Every boxer's score and how the boxer up is doing in their round
+#[derive(Resource, Serialize, Debug, Clone, PartialEq)]
+pub struct Workout {
+    /// Each boxer's score
+    scores: Vec<u32>,
+    /// Prompts the boxer up has landed in a row
+    streak: u32,
+    /// Most prompts the boxer up landed in a row this round
+    best_streak: u32,
+    /// How the boxer up's last prompt turned out, if one has
+    last: Option<Outcome>,
+    /// Counts down squaring up and resting between rounds
+    #[serde(skip)]
+    countdown: Timer,
+}
+
+impl Default for Workout {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Workout {
+    /// Starts a workout for a number of boxers with nothing scored
+    pub fn new(players: usize) -> Self {
+        Self {
+            scores: vec![0; players.max(1)],
+            streak: 0,
+            best_streak: 0,
+            last: None,
+            countdown: Timer::from_seconds(READY_SECS, TimerMode::Once),
+        }
+    }
+
+    /// How many boxers are in the workout
+    pub fn players(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// A boxer's score
+    pub fn score(&self, player: usize) -> u32 {
+        self.scores.get(player).copied().unwrap_or_default()
+    }
+
+    /// Adds how a prompt turned out to a boxer's score and streak
+    pub fn record(&mut self, player: usize, outcome: Outcome) {
+        if let Outcome::Landed(_, points) = outcome {
+            if let Some(score) = self.scores.get_mut(player) {
+                *score += points;
+            }
+            self.streak += 1;
+            self.best_streak = self.best_streak.max(self.streak);
+        } else {
+            self.streak = 0;
+        }
+        self.last = Some(outcome);
+    }
+
+    /// Clears the streaks for the next boxer's round
+    fn start_round(&mut self) {
+        self.streak = 0;
+        self.best_streak = 0;
+        self.last = None;
+    }
+
+    /// Boxers with the best score, more than one if they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = self.scores.iter().max().copied().unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.score(*player) == best)
+            .collect()
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct BoxingSnapshot<'a> {
+    /// Boxer up
+    player: usize,
+    /// Every boxer's score and the streak of the boxer up
+    workout: &'a Workout,
+    /// The prompts of the round being boxed
+    track: &'a Track,
+    /// Where the workout is at
+    phase: BoxingPhase,
+}
+
+/// Plugin that runs the rounds, scores punches and shows the scores on the scorecard HUD
+pub struct WorkoutPlugin;
+
+impl Plugin for WorkoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Workout>()
+            .add_event::<Thrown>()
+            .add_event::<NewGame>()
+            .add_systems(OnEnter(BoxingPhase::Ready), square_up)
+            .add_systems(OnEnter(BoxingPhase::Resting), end_round)
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    count_down.run_if(in_state(BoxingPhase::Ready)),
+                    (score_punch, run_clock)
+                        .chain()
+                        .run_if(in_state(BoxingPhase::Round)),
+                    rest.run_if(in_state(BoxingPhase::Resting)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(BoxingPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(BoxingPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh workout whenever the number of boxers changes
+fn fit_game(turns: Res<'_, TurnManager>, mut workout: ResMut<'_, Workout>) {
+    if workout.players() != turns.players() {
+        *workout = Workout::new(turns.players());
+    }
+}
+
+/// Gives the boxer up a few seconds to square up to the bag
+fn square_up(
+    mut workout: ResMut<'_, Workout>,
+    mut banner: ResMut<'_, Banner>,
+    turns: Res<'_, TurnManager>,
+) {
+    workout.start_round();
+    workout.countdown = Timer::from_seconds(READY_SECS, TimerMode::Once);
+    banner.show(format!("Player {}, square up!", turns.current() + 1));
+}
+
+/// Rings the bell once the boxer up has had time to square up
+fn count_down(
+    mut workout: ResMut<'_, Workout>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<BoxingPhase>>,
+    time: Res<'_, Time>,
+) {
+    if workout.countdown.tick(time.delta()).just_finished() {
+        banner.show("Box!");
+        next_phase.set(BoxingPhase::Round);
+    }
+}
+
+/// Scores every punch thrown against the prompt it was closest to, buzzing the controller for
+/// the ones that land
+fn score_punch(
+    mut thrown: EventReader<'_, '_, Thrown>,
+    mut track: ResMut<'_, Track>,
+    mut workout: ResMut<'_, Workout>,
+    mut banner: ResMut<'_, Banner>,
+    turns: Res<'_, TurnManager>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    for punch in thrown.read() {
+        let Some(prompt) = track.throw(punch.punch, punch.intensity) else {
+            continue;
+        };
+        let Some(outcome) = prompt.outcome else {
+            continue;
+        };
+        workout.record(turns.current(), outcome);
+
+        match outcome {
+            Outcome::Landed(timing, points) => {
+                banner.show(format!(
+                    "{} {}! +{points}",
+                    timing.name(),
+                    prompt.punch.name().to_lowercase()
+                ));
+                let strength = (punch.intensity / FULL_RUMBLE_SPEED).clamp(0.3, 1.0);
+                feedback.send(GameEvent::Rumble {
+                    intensity: (strength * MAX_RUMBLE) as u8,
+                    millis: RUMBLE_MILLIS,
+                });
+            }
+            Outcome::Wrong => banner.show(format!(
+                "That was a {}, not a {}",
+                punch.punch.name().to_lowercase(),
+                prompt.punch.name().to_lowercase()
+            )),
+            Outcome::Missed => {}
+        }
+    }
+}
+
+/// Moves the round on, breaking the streak for every prompt that goes by without a punch and
+/// ending the round once every prompt has turned out
+fn run_clock(
+    mut track: ResMut<'_, Track>,
+    mut workout: ResMut<'_, Workout>,
+    mut next_phase: ResMut<'_, NextState<BoxingPhase>>,
+    turns: Res<'_, TurnManager>,
+    time: Res<'_, Time>,
+) {
+    for _ in 0..track.tick(time.delta_secs()) {
+        workout.record(turns.current(), Outcome::Missed);
+    }
+    if track.is_done() {
+        next_phase.set(BoxingPhase::Resting);
+    }
+}
+
+/// Rings the bell on the round and puts its score up while the boxer rests
+fn end_round(
+    mut workout: ResMut<'_, Workout>,
+    mut banner: ResMut<'_, Banner>,
+    turns: Res<'_, TurnManager>,
+) {
+    workout.countdown = Timer::from_seconds(REST_SECS, TimerMode::Once);
+    banner.show(format!(
+        "Round over! {} points, best streak {}",
+        workout.score(turns.current()),
+        workout.best_streak
+    ));
+}
+
+/// Hands the bag to the next boxer once the rest is over, ending the workout once everyone has
+/// had their round
+fn rest(
+    mut workout: ResMut<'_, Workout>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<BoxingPhase>>,
+    time: Res<'_, Time>,
+) {
+    if !workout.countdown.tick(time.delta()).just_finished() {
+        return;
+    }
+    turns.advance();
+    next_phase.set(if turns.round() >= 1 {
+        BoxingPhase::GameOver
+    } else {
+        BoxingPhase::Ready
+    });
+}
+
+/// Starts the workout over from the first boxer's round
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut workout: ResMut<'_, Workout>,
+    mut next_phase: ResMut<'_, NextState<BoxingPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *workout = Workout::new(turns.players());
+    next_phase.set(BoxingPhase::Ready);
+}
+
+/// Fills in the scorecard HUD with every boxer's score and how the boxer up is doing
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    workout: Res<'_, Workout>,
+    track: Res<'_, Track>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..workout.players())
+        .map(|player| format!("Player {}: {}", player + 1, workout.score(player)))
+        .collect();
+    let resolved = track
+        .prompts()
+        .iter()
+        .filter(|prompt| prompt.outcome.is_some())
+        .count();
+    let last = match workout.last {
+        Some(Outcome::Landed(timing, points)) => {
+            format!("\nLast punch: {} +{points}", timing.name())
+        }
+        Some(Outcome::Wrong) => "\nLast punch: wrong punch".to_string(),
+        Some(Outcome::Missed) => "\nLast punch: missed".to_string(),
+        None => String::new(),
+    };
+
+    hud.set_if_neq(ScorecardHud {
+        title: "Boxing".to_string(),
+        rows,
+        footer: format!(
+            "Player {} boxing, punch {} of {}, streak {}{last}",
+            turns.current() + 1,
+            (resolved + 1).min(track.prompts().len()),
+            track.prompts().len(),
+            workout.streak
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Puts every boxer's score up once the workout is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, workout: Res<'_, Workout>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    match workout.leaders()[..] {
+        [winner] if workout.players() > 1 => lines.push(format!("Player {} wins!", winner + 1)),
+        [_, _, ..] => lines.push("It's a tie!".to_string()),
+        _ => {}
+    }
+    for player in 0..workout.players() {
+        lines.push(format!("Player {}: {}", player + 1, workout.score(player)));
+    }
+    lines.push("Press A to box again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new workout starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every boxer's score once the workout is over, so the page can submit them to the server
+fn submit_result(workout: Res<'_, Workout>, feedback: Res<'_, FeedbackSender>) {
+    feedback.send(GameEvent::GameResult {
+        players: workout.players(),
+        scores: workout.scores.clone(),
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    workout: Res<'_, Workout>,
+    track: Res<'_, Track>,
+    phase: Res<'_, State<BoxingPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&BoxingSnapshot {
+        player: turns.current(),
+        workout: &workout,
+        track: &track,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,266 @@
+//! The board: a sloped ramp with a hole near its top end, the foul line bags are tossed from and
+//! where a bag lying still ended up
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody};
+
+/// Half the width of the board's playing surface
+pub const BOARD_HALF_WIDTH: f32 = 0.305;
+/// Half the length of the board's playing surface, along its slope
+pub const BOARD_HALF_LENGTH: f32 = 0.61;
+/// How high the front edge of the board's surface is off the grass
+const FRONT_HEIGHT: f32 = 0.076;
+/// How high the back edge of the board's surface is off the grass
+const BACK_HEIGHT: f32 = 0.305;
+/// Thickness of the board's top
+const TOP_THICKNESS: f32 = 0.02;
+/// Width of the hole. The collider's hole is square, drawn as a circle this wide
+const HOLE_SIZE: f32 = 0.16;
+/// How far the middle of the hole is from the back edge, along the slope
+const HOLE_FROM_BACK: f32 = 0.229;
+/// How far in front of the board the foul line is
+pub const PITCHING_DISTANCE: f32 = 8.23;
+/// Friction of the board's surface, low enough for bags to slide up it
+const BOARD_FRICTION: f32 = 0.6;
+/// How far below the surface a bag has to be to have dropped through the hole
+const SUNK_DEPTH: f32 = 0.04;
+
+/// How steep the board's surface is, in radians
+fn slope() -> f32 {
+    ((BACK_HEIGHT - FRONT_HEIGHT) / (BOARD_HALF_LENGTH * 2.0)).asin()
+}
+
+/// Where the middle of the board's surface is and how it's tilted. The surface's +z faces the
+/// foul line and its +y points up out of it
+pub fn surface() -> Transform {
+    let tilt = slope();
+    Transform::from_xyz(
+        0.0,
+        (FRONT_HEIGHT + BACK_HEIGHT) / 2.0,
+        -BOARD_HALF_LENGTH * tilt.cos(),
+    )
+    .with_rotation(Quat::from_rotation_x(tilt))
+}
+
+/// Middle of the hole, on the board's surface
+pub fn hole() -> Vec3 {
+    surface().transform_point(Vec3::new(0.0, 0.0, HOLE_FROM_BACK - BOARD_HALF_LENGTH))
+}
+
+/// Where a bag lying still ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lie {
+    /// Dropped through the hole
+    InTheHole,
+    /// Resting on the board, or on a bag that is
+    OnTheBoard,
+    /// Anywhere else
+    Off,
+}
+
+impl Lie {
+    /// Points a bag lying here is worth before cancelling
+    pub fn points(&self) -> u32 {
+        match self {
+            Self::InTheHole => 3,
+            Self::OnTheBoard => 1,
+            Self::Off => 0,
+        }
+    }
+}
+
+/// Works out where a bag lying still at `position` ended up
+pub fn lie(position: Vec3) -> Lie {
+    let local = surface()
+        .compute_affine()
+        .inverse()
+        .transform_point3(position);
+    let over = local.x.abs() <= BOARD_HALF_WIDTH && local.z.abs() <= BOARD_HALF_LENGTH;
+    if !over {
+        Lie::Off
+    } else if local.y < -SUNK_DEPTH {
+        Lie::InTheHole
+    } else {
+        Lie::OnTheBoard
+    }
+}
+
+/// Plugin that sets up the board, the grass and the view from the foul line
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_board);
+    }
+}
+
+/// The pieces of the board's top around the hole, as the middle and half size of each in the
+/// surface's space
+fn top_pieces() -> [(Vec3, Vec3); 4] {
+    let hole_z = HOLE_FROM_BACK - BOARD_HALF_LENGTH;
+    let half_hole = HOLE_SIZE / 2.0;
+    let y = -TOP_THICKNESS / 2.0;
+    let front = (hole_z + half_hole, BOARD_HALF_LENGTH);
+    let back = (-BOARD_HALF_LENGTH, hole_z - half_hole);
+    let side = (BOARD_HALF_WIDTH - half_hole) / 2.0;
+    [
+        (
+            Vec3::new(0.0, y, (front.0 + front.1) / 2.0),
+            Vec3::new(
+                BOARD_HALF_WIDTH,
+                TOP_THICKNESS / 2.0,
+                (front.1 - front.0) / 2.0,
+            ),
+        ),
+        (
+            Vec3::new(0.0, y, (back.0 + back.1) / 2.0),
+            Vec3::new(
+                BOARD_HALF_WIDTH,
+                TOP_THICKNESS / 2.0,
+                (back.1 - back.0) / 2.0,
+            ),
+        ),
+        (
+            Vec3::new(-half_hole - side, y, hole_z),
+            Vec3::new(side, TOP_THICKNESS / 2.0, half_hole),
+        ),
+        (
+            Vec3::new(half_hole + side, y, hole_z),
+            Vec3::new(side, TOP_THICKNESS / 2.0, half_hole),
+        ),
+    ]
+}
+
+/// Spawns the grass, the board with its hole and skirts, the foul line, the camera and the
+/// light
+fn setup_board(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let length = PITCHING_DISTANCE + 8.0;
+    let centre = PITCHING_DISTANCE / 2.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, length))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.58, 0.22))),
+        Transform::from_xyz(0.0, 0.0, centre),
+        Name::new("Grass"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, centre),
+        RigidBody::Fixed,
+        Collider::cuboid(6.0, 0.1, length / 2.0),
+        Friction::coefficient(1.0),
+        Restitution::coefficient(0.0),
+        Name::new("Ground"),
+    ));
+
+    let surface = surface();
+    let wood = materials.add(Color::srgb(0.85, 0.72, 0.5));
+    let paint = materials.add(Color::srgb(0.1, 0.25, 0.6));
+    commands
+        .spawn((
+            surface,
+            Visibility::Visible,
+            RigidBody::Fixed,
+            Collider::compound(
+                top_pieces()
+                    .into_iter()
+                    .map(|(at, half)| {
+                        (at, Quat::IDENTITY, Collider::cuboid(half.x, half.y, half.z))
+                    })
+                    .collect(),
+            ),
+            Friction::coefficient(BOARD_FRICTION),
+            Restitution::coefficient(0.0),
+            Name::new("Board"),
+        ))
+        .with_children(|board| {
+            // The top is drawn whole with the hole painted over it, as the collider's square
+            // hole would show its corners
+            board.spawn((
+                Mesh3d(meshes.add(Cuboid::new(
+                    BOARD_HALF_WIDTH * 2.0,
+                    TOP_THICKNESS,
+                    BOARD_HALF_LENGTH * 2.0,
+                ))),
+                MeshMaterial3d(paint),
+                Transform::from_xyz(0.0, -TOP_THICKNESS / 2.0, 0.0),
+            ));
+            board.spawn((
+                Mesh3d(meshes.add(Circle::new(HOLE_SIZE / 2.0))),
+                MeshMaterial3d(materials.add(Color::srgb(0.02, 0.02, 0.02))),
+                Transform::from_xyz(0.0, 0.001, HOLE_FROM_BACK - BOARD_HALF_LENGTH)
+                    .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+            ));
+        });
+
+    // The skirt boxes in the space under the top, so a bag through the hole stays there
+    let skirt = TOP_THICKNESS / 2.0;
+    let back_z = surface.translation.z * 2.0;
+    let sides = [
+        (
+            Vec3::new(0.0, FRONT_HEIGHT / 2.0, skirt),
+            Vec3::new(BOARD_HALF_WIDTH, FRONT_HEIGHT / 2.0, skirt),
+        ),
+        (
+            Vec3::new(0.0, BACK_HEIGHT / 2.0, back_z - skirt),
+            Vec3::new(BOARD_HALF_WIDTH, BACK_HEIGHT / 2.0, skirt),
+        ),
+    ];
+    for (at, half) in sides {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(half * 2.0))),
+            MeshMaterial3d(wood.clone()),
+            Transform::from_translation(at),
+            RigidBody::Fixed,
+            Collider::cuboid(half.x, half.y, half.z),
+        ));
+    }
+    // The side skirts run down from the top's edges and into the grass at the front
+    let rail = meshes.add(Cuboid::new(
+        skirt * 2.0,
+        BACK_HEIGHT,
+        BOARD_HALF_LENGTH * 2.0,
+    ));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(rail.clone()),
+            MeshMaterial3d(wood.clone()),
+            surface.with_translation(surface.transform_point(Vec3::new(
+                side * (BOARD_HALF_WIDTH + skirt),
+                -BACK_HEIGHT / 2.0,
+                0.0,
+            ))),
+            RigidBody::Fixed,
+            Collider::cuboid(skirt, BACK_HEIGHT / 2.0, BOARD_HALF_LENGTH),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(BOARD_HALF_WIDTH * 4.0, 0.04))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, 0.005, PITCHING_DISTANCE),
+        Name::new("Foul line"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: 0.45,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 2.0, PITCHING_DISTANCE + 2.0).looking_at(hole(), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 10.0, PITCHING_DISTANCE)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.7)),
+    ));
+}
@@ -0,0 +1,411 @@
+//! Frames: the pitchers at the foul line take turns tossing their bags, then the frame is
+//! scored with cancellation, only the team ahead on the board scoring the difference, and the
+//! totals go on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    board::{lie, Lie},
+    phase::CornholePhase,
+    Bag,
+};
+
+/// Teams in a game, with players joining them in turn order
+pub const TEAMS: usize = 2;
+/// Name of each team, matching its bags
+pub const TEAM_NAMES: [&str; TEAMS] = ["Red", "Blue"];
+/// Bags each pitcher tosses in a frame
+pub const BAGS_PER_PITCHER: usize = 4;
+/// Points that win the game, once a frame ends with a team on or past them
+pub const WINNING_POINTS: u32 = 21;
+/// Frames played before the team ahead wins even if nobody has reached [`WINNING_POINTS`]
+pub const MAX_FRAMES: usize = 20;
+/// How long the bags stay down once a frame is scored, in seconds
+const SCORING_SECS: f32 = 2.5;
+
+/// The team a player is on. Players alternate teams in turn order, so teammates are two apart
+pub fn team(player: usize) -> usize {
+    player % TEAMS
+}
+
+/// Asks for the game to be started over from the first frame
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Frame by frame progress of a game
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Frames {
+    /// How many players are in the game
+    players: usize,
+    /// Each team's total
+    points: [u32; TEAMS],
+    /// Frames scored so far
+    played: usize,
+    /// Bags tossed so far this frame
+    bags: usize,
+    /// Team that tosses first this frame, the last one to score
+    lead: usize,
+    /// The team that scored last frame and what they scored, or `None` if it washed out
+    last: Option<(usize, u32)>,
+}
+
+impl Default for Frames {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Frames {
+    /// Starts a game for a number of players with nothing scored
+    pub fn new(players: usize) -> Self {
+        Self {
+            players: players.max(1),
+            points: [0; TEAMS],
+            played: 0,
+            bags: 0,
+            lead: 0,
+            last: None,
+        }
+    }
+
+    /// How many players are in the game
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Whether teammates stand at opposite boards, which they do when every team has two
+    /// players. Only the pair at the foul line pitches each frame, and the pairs swap every
+    /// frame
+    fn doubles(&self) -> bool {
+        self.players == TEAMS * 2
+    }
+
+    /// Whether a player is at the foul line this frame
+    pub fn in_frame(&self, player: usize) -> bool {
+        !self.doubles() || player / TEAMS == self.played % 2
+    }
+
+    /// How many players are at the foul line this frame
+    fn pitchers(&self) -> usize {
+        (0..self.players)
+            .filter(|player| self.in_frame(*player))
+            .count()
+    }
+
+    /// Counts a tossed bag, returning whether it was the last of the frame
+    pub fn add_bag(&mut self) -> bool {
+        self.bags += 1;
+        self.bags >= self.pitchers() * BAGS_PER_PITCHER
+    }
+
+    /// Which of their bags the player at the foul line is tossing, starting from zero
+    pub fn bag(&self) -> usize {
+        self.bags / self.pitchers().max(1)
+    }
+
+    /// Whether a player on the team tossing first this frame is at the foul line, so they can
+    /// be the one to start the frame
+    pub fn leads(&self, player: usize) -> bool {
+        self.in_frame(player) && team(player) == self.lead
+    }
+
+    /// Scores a frame from the points each team's bags are worth: the team with more scores the
+    /// difference and tosses first next frame. Returns who scored and what
+    pub fn score(&mut self, bag_points: [u32; TEAMS]) -> Option<(usize, u32)> {
+        let (best, most) = bag_points
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by_key(|(_, points)| *points)?;
+        let next = bag_points
+            .iter()
+            .enumerate()
+            .filter(|(team, _)| *team != best)
+            .map(|(_, points)| *points)
+            .max()
+            .unwrap_or_default();
+        self.played += 1;
+        self.bags = 0;
+        self.last = (most > next).then_some((best, most - next));
+        if let Some((team, points)) = self.last {
+            self.points[team] += points;
+            self.lead = team;
+        }
+        self.last
+    }
+
+    /// How many frames have been scored
+    pub fn played(&self) -> usize {
+        self.played
+    }
+
+    /// A team's total
+    pub fn total(&self, team: usize) -> u32 {
+        self.points.get(team).copied().unwrap_or_default()
+    }
+
+    /// The team ahead, or `None` while they're level
+    pub fn leader(&self) -> Option<usize> {
+        let best = self.points.iter().max().copied().unwrap_or_default();
+        let mut leaders = (0..TEAMS).filter(|team| self.total(*team) == best);
+        match (leaders.next(), leaders.next()) {
+            (Some(team), None) => Some(team),
+            _ => None,
+        }
+    }
+
+    /// Whether the game is over: a team on or past [`WINNING_POINTS`], or every frame played
+    pub fn is_over(&self) -> bool {
+        let won = self.points.iter().any(|points| *points >= WINNING_POINTS);
+        won || self.played >= MAX_FRAMES
+    }
+}
+
+/// Counts down before the bags are picked up for the next frame
+#[derive(Resource, Debug)]
+struct Scoring(Timer);
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SCORING_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct CornholeSnapshot<'a> {
+    /// Pitcher at the foul line
+    player: usize,
+    /// Team of the pitcher at the foul line
+    team: usize,
+    /// The game so far
+    frames: &'a Frames,
+    /// Where the game is at
+    phase: CornholePhase,
+}
+
+/// Plugin that scores frames and shows them on the scorecard HUD
+pub struct FramesPlugin;
+
+impl Plugin for FramesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Frames>()
+            .init_resource::<Scoring>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    pick_up_bags.run_if(in_state(CornholePhase::Scoring)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(CornholePhase::Scoring),
+                (reset_scoring, score_frame.run_if(is_playing)),
+            )
+            .add_systems(
+                OnEnter(CornholePhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(CornholePhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut frames: ResMut<'_, Frames>) {
+    if frames.players() != turns.players() {
+        *frames = Frames::new(turns.players());
+    }
+}
+
+/// Gives players a moment to look at the board once the frame is scored
+fn reset_scoring(mut scoring: ResMut<'_, Scoring>) {
+    scoring.0.reset();
+}
+
+/// Scores the frame from where every bag came to rest
+fn score_frame(
+    bags: Query<'_, '_, (&Transform, &Bag)>,
+    mut frames: ResMut<'_, Frames>,
+    mut banner: ResMut<'_, Banner>,
+) {
+    let mut bag_points = [0; TEAMS];
+    let mut holes = 0;
+    for (transform, bag) in &bags {
+        let lie = lie(transform.translation);
+        if lie == Lie::InTheHole {
+            holes += 1;
+        }
+        if let Some(points) = bag_points.get_mut(bag.team) {
+            *points += lie.points();
+        }
+    }
+
+    let scored = match frames.score(bag_points) {
+        Some((team, points)) => format!("{} scores {points}", TEAM_NAMES[team]),
+        None => "Wash, no score".to_string(),
+    };
+    banner.show(match holes {
+        0 => scored,
+        1 => format!("Cornhole! {scored}"),
+        holes => format!("{holes} in the hole! {scored}"),
+    });
+}
+
+/// Picks the bags up once the frame has been looked at, handing the foul line to the team that
+/// scored for the next frame or ending the game
+fn pick_up_bags(
+    mut commands: Commands<'_, '_>,
+    mut scoring: ResMut<'_, Scoring>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<CornholePhase>>,
+    bags: Query<'_, '_, Entity, With<Bag>>,
+    frames: Res<'_, Frames>,
+    time: Res<'_, Time>,
+) {
+    if !scoring.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for bag in &bags {
+        commands.entity(bag).despawn_recursive();
+    }
+    if frames.is_over() {
+        next_phase.set(CornholePhase::GameOver);
+        return;
+    }
+    if turns
+        .advance_until(|player| !frames.leads(player))
+        .is_none()
+    {
+        turns.advance_until(|player| !frames.in_frame(player));
+    }
+    next_phase.set(CornholePhase::Aiming);
+}
+
+/// Starts the game over from the first frame
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut frames: ResMut<'_, Frames>,
+    mut next_phase: ResMut<'_, NextState<CornholePhase>>,
+    bags: Query<'_, '_, Entity, With<Bag>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for bag in &bags {
+        commands.entity(bag).despawn_recursive();
+    }
+    turns.restart();
+    *frames = Frames::new(turns.players());
+    next_phase.set(CornholePhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with each team's total and players, what last frame scored and
+/// who's tossing which bag
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    frames: Res<'_, Frames>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..TEAMS)
+        .filter(|team| *team < frames.players())
+        .map(|team| {
+            let players: Vec<String> = (team..frames.players())
+                .step_by(TEAMS)
+                .map(|player| (player + 1).to_string())
+                .collect();
+            format!(
+                "{}: {} (Player {})",
+                TEAM_NAMES[team],
+                frames.total(team),
+                players.join(" & ")
+            )
+        })
+        .collect();
+    let last = match frames.last {
+        _ if frames.played() == 0 => String::new(),
+        Some((team, points)) => format!("\nLast frame: {} +{points}", TEAM_NAMES[team]),
+        None => "\nLast frame: wash".to_string(),
+    };
+    let player = turns.current();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!(
+            "Cornhole to {WINNING_POINTS}, frame {}",
+            frames.played() + 1
+        ),
+        rows,
+        footer: format!(
+            "Player {} ({}) tossing bag {} of {BAGS_PER_PITCHER}{last}",
+            player + 1,
+            TEAM_NAMES[team(player)],
+            (frames.bag() + 1).min(BAGS_PER_PITCHER)
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists each team's total once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, frames: Res<'_, Frames>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    match frames.leader() {
+        Some(team) if frames.players() > 1 => lines.push(format!("{} wins!", TEAM_NAMES[team])),
+        None => lines.push("It's a tie!".to_string()),
+        Some(_) => {}
+    }
+    for team in (0..TEAMS).filter(|team| *team < frames.players()) {
+        lines.push(format!("{}: {}", TEAM_NAMES[team], frames.total(team)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player their team's total once the game is over, so the page can submit them
+/// to the server
+fn submit_result(frames: Res<'_, Frames>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..frames.players())
+        .map(|player| frames.total(team(player)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    frames: Res<'_, Frames>,
+    phase: Res<'_, State<CornholePhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&CornholeSnapshot {
+        player: turns.current(),
+        team: team(turns.current()),
+        frames: &frames,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,360 @@
+//! Bevy cornhole game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{
+        ActiveEvents, Ccd, Collider, ColliderMassProperties, CollisionEvent, Damping, Friction,
+        Restitution, RigidBody, Velocity,
+    },
+};
+use board::{hole, BoardPlugin, PITCHING_DISTANCE};
+use frames::{team, Frames, FramesPlugin, NewGame};
+use phase::{CornholePhase, CornholePhasePlugin};
+use spjorts_core::{
+    communication::JsMessage,
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod board;
+pub mod frames;
+pub mod phase;
+
+/// How far a radian of controller yaw turns the toss, in radians
+const AIM_SCALE: f32 = 0.05;
+/// Furthest a toss can be turned from straight at the board, in radians
+const MAX_AIM: f32 = 0.06;
+/// How far the controller has to come up from the bottom of a swing for it to count as an
+/// underhand toss, in radians
+const UNDERHAND_RISE: f32 = 0.8;
+/// Flattest and loftiest arc a bag can be tossed with, taken from how far the controller is
+/// pitched up at release, in radians
+const ARC_RANGE: (f32, f32) = (0.2, 1.2);
+/// Speed a bag leaves the hand with from the gentlest swing that counts, in meters per second
+const BASE_SPEED: f32 = 6.0;
+/// How much faster every radian per second of swing sends the bag, in meters per second
+const SPEED_PER_SWING: f32 = 0.4;
+/// Fastest a bag can be tossed, in meters per second
+const MAX_SPEED: f32 = 13.0;
+/// Where bags leave the hand, at the end of an underhand swing on the foul line
+const RELEASE: Vec3 = Vec3::new(0.0, 0.9, PITCHING_DISTANCE);
+/// How fast a bag spins flat in the air, in radians per second
+const FLAT_SPIN: f32 = 3.0;
+/// Half the size of a bag's collider, a little inside its look so a bag turned any way fits
+/// through the hole
+const BAG_HALF_SIZE: Vec3 = Vec3::new(0.055, 0.02, 0.055);
+/// Size of a bag's look
+const BAG_SIZE: Vec3 = Vec3::new(0.15, 0.04, 0.15);
+/// Mass of a bag, in kilograms
+const BAG_MASS: f32 = 0.45;
+/// Damping on a bag once it has come down, the give of its filling soaking up the slide
+const LANDED_DAMPING: f32 = 4.0;
+/// Speed below which a bag counts as lying still, in meters per second
+const REST_SPEED: f32 = 0.05;
+/// How long every bag has to lie still before the next pitcher steps up, in seconds
+const REST_SECS: f32 = 0.5;
+/// Longest a toss is watched for before the next pitcher steps up anyway, in seconds
+const MAX_TOSS_SECS: f32 = 6.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(CornholePhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(BoardPlugin)
+    .add_plugins(FramesPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Stance>()
+    .add_event::<Toss>()
+    .add_systems(Startup, setup_bag_model)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            toss_bag.run_if(in_state(CornholePhase::Aiming)),
+            (land_bags, settle_bags)
+                .chain()
+                .run_if(in_state(CornholePhase::Flying)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        draw_aim_guide.run_if(in_state(CornholePhase::Aiming)),
+    );
+});
+
+/// How the pitcher at the foul line is lining up, and how long the last toss has been watched
+#[derive(Resource, Debug, Default)]
+pub struct Stance {
+    /// How far the toss is turned from straight at the board, in radians. Positive turns left
+    pub aim: f32,
+    /// Lowest the controller has been pitched since it was last at rest, in radians
+    bottom_pitch: f32,
+    /// Watches the controller for underhand swings
+    detector: GestureDetector,
+    /// Seconds since the last bag was tossed
+    watched_for: f32,
+    /// How long every bag has been lying still, in seconds
+    still_for: f32,
+}
+
+/// A bag tossed from the foul line
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Toss {
+    /// How far the toss is turned from straight at the board, in radians
+    pub aim: f32,
+    /// How far above level the bag leaves the hand, in radians
+    pub arc: f32,
+    /// How fast the bag leaves the hand, in meters per second
+    pub speed: f32,
+}
+
+impl Toss {
+    /// Tosses from an underhand swing, lofted by how far the controller is pitched up as it
+    /// lets go and sent harder the faster it's swung
+    fn from_swing(aim: f32, release_pitch: f32, swing: f32) -> Self {
+        Self {
+            aim,
+            arc: release_pitch.clamp(ARC_RANGE.0, ARC_RANGE.1),
+            speed: (BASE_SPEED + swing * SPEED_PER_SWING).min(MAX_SPEED),
+        }
+    }
+
+    /// Velocity the bag leaves the hand with
+    fn velocity(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Quat::from_rotation_x(self.arc) * Vec3::NEG_Z * self.speed
+    }
+}
+
+/// A bag tossed this frame
+#[derive(Component, Debug)]
+pub struct Bag {
+    /// Team whose player tossed it
+    pub team: usize,
+    /// Whether it has come down yet
+    landed: bool,
+}
+
+/// Mesh and materials every bag is built from
+#[derive(Resource)]
+struct BagModel {
+    /// A bag's look
+    mesh: Handle<Mesh>,
+    /// Each team's bag color
+    colors: Vec<Handle<StandardMaterial>>,
+}
+
+impl BagModel {
+    /// Spawns a bag's look around the parent's origin in a team's color
+    fn build(&self, bag: &mut ChildBuilder<'_>, team: usize) {
+        bag.spawn((
+            Mesh3d(self.mesh.clone()),
+            MeshMaterial3d(self.colors[team % self.colors.len()].clone()),
+        ));
+    }
+}
+
+/// Builds the mesh and materials bags are spawned with
+fn setup_bag_model(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(BagModel {
+        mesh: meshes.add(Cuboid::from_size(BAG_SIZE)),
+        colors: [Color::srgb(0.8, 0.12, 0.1), Color::srgb(0.12, 0.25, 0.8)]
+            .into_iter()
+            .map(|color| {
+                materials.add(StandardMaterial {
+                    base_color: color,
+                    perceptual_roughness: 0.95,
+                    ..default()
+                })
+            })
+            .collect(),
+    });
+}
+
+/// Everything input handling changes besides the stance itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Bags to toss
+    tosses: EventWriter<'w, Toss>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims while the controller is still, and an underhand swing
+/// tosses the bag, lofted by how far the controller is pitched up as it lets go and harder the
+/// faster it's swung. A starts a new game once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut stance: ResMut<'_, Stance>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<CornholePhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == CornholePhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == CornholePhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                let detected = stance.detector.update(orientation, time.elapsed_secs());
+                let underhand = orientation.pitch - stance.bottom_pitch >= UNDERHAND_RISE;
+                if stance.detector.speed() < stance.detector.thresholds.rest_speed {
+                    stance.aim = (orientation.yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                    stance.bottom_pitch = orientation.pitch;
+                } else {
+                    stance.bottom_pitch = stance.bottom_pitch.min(orientation.pitch);
+                }
+
+                if let Some(detected) = detected.filter(|detected| {
+                    underhand && matches!(detected.gesture, Gesture::Swing | Gesture::Flick)
+                }) {
+                    effects.tosses.send(Toss::from_swing(
+                        stance.aim,
+                        orientation.pitch,
+                        detected.intensity,
+                    ));
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets a bag go from the foul line, spinning flat in its pitcher's team color
+fn toss_bag(
+    mut commands: Commands<'_, '_>,
+    mut tosses: EventReader<'_, '_, Toss>,
+    mut stance: ResMut<'_, Stance>,
+    mut next_phase: ResMut<'_, NextState<CornholePhase>>,
+    model: Res<'_, BagModel>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(toss) = tosses.read().last().copied() else {
+        return;
+    };
+    let team = team(turns.current());
+
+    commands
+        .spawn((
+            Transform::from_translation(RELEASE).with_rotation(Quat::from_rotation_y(toss.aim)),
+            Visibility::Visible,
+            RigidBody::Dynamic,
+            Collider::cuboid(BAG_HALF_SIZE.x, BAG_HALF_SIZE.y, BAG_HALF_SIZE.z),
+            ColliderMassProperties::Mass(BAG_MASS),
+            Friction::coefficient(0.5),
+            Restitution::coefficient(0.0),
+            Damping::default(),
+            Ccd::enabled(),
+            ActiveEvents::COLLISION_EVENTS,
+            Velocity {
+                linvel: toss.velocity(),
+                angvel: Vec3::Y * FLAT_SPIN,
+            },
+            Bag {
+                team,
+                landed: false,
+            },
+        ))
+        .with_children(|bag| model.build(bag, team));
+    stance.watched_for = 0.0;
+    stance.still_for = 0.0;
+    next_phase.set(CornholePhase::Flying);
+}
+
+/// Slows a bag down from the moment it first comes down, so it slides a little way rather than
+/// skating off like a hard block would
+fn land_bags(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut bags: Query<'_, '_, (&mut Bag, &mut Damping)>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        for entity in [first, second] {
+            let Ok((mut bag, mut damping)) = bags.get_mut(entity) else {
+                continue;
+            };
+            if !bag.landed {
+                bag.landed = true;
+                *damping = Damping {
+                    linear_damping: LANDED_DAMPING,
+                    angular_damping: LANDED_DAMPING,
+                };
+            }
+        }
+    }
+}
+
+/// Waits for every bag to lie still, then hands the foul line to the next pitcher, or scores
+/// the frame once every bag has been tossed
+fn settle_bags(
+    bags: Query<'_, '_, &Velocity, With<Bag>>,
+    mut stance: ResMut<'_, Stance>,
+    mut frames: ResMut<'_, Frames>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<CornholePhase>>,
+    time: Res<'_, Time>,
+) {
+    stance.watched_for += time.delta_secs();
+    stance.still_for = if bags
+        .iter()
+        .all(|velocity| velocity.linvel.length() < REST_SPEED)
+    {
+        stance.still_for + time.delta_secs()
+    } else {
+        0.0
+    };
+    if stance.still_for < REST_SECS && stance.watched_for < MAX_TOSS_SECS {
+        return;
+    }
+
+    if frames.add_bag() {
+        next_phase.set(CornholePhase::Scoring);
+    } else {
+        turns.advance_until(|player| !frames.in_frame(player));
+        next_phase.set(CornholePhase::Aiming);
+    }
+}
+
+/// Draws the line a toss the way the stance is aimed takes up the board
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    stance: Res<'_, Stance>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let start = RELEASE.with_y(0.01);
+    let reach = (start.z - hole().z) / stance.aim.cos();
+    let end = start + Quat::from_rotation_y(stance.aim) * Vec3::NEG_Z * reach;
+    gizmos.line(start, end.with_y(hole().y + 0.01), Color::WHITE);
+}
@@ -0,0 +1,27 @@
+//! Phases a cornhole game moves through, from lining up a toss to the final scores
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of a frame
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CornholePhase {
+    /// The pitcher at the foul line is lining up their next bag
+    #[default]
+    Aiming,
+    /// A tossed bag is in the air or still sliding
+    Flying,
+    /// Every bag of the frame is down and the frame is being scored
+    Scoring,
+    /// A team has won and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct CornholePhasePlugin;
+
+impl Plugin for CornholePhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CornholePhase>();
+    }
+}
@@ -0,0 +1,221 @@
+//! Recentering, so readings are measured from however the controller rests in the player's hand.
+//! Pressing A and B together starts it for that controller, and the resting orientation is
+//! captured once the controller has been held still
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use spjorts_core::communication::Orientation;
+
+use crate::{mode::CubeMode, ControllerAction, ControllerInput};
+
+/// Longest gap between the A and B presses that start recentering, in seconds
+const CHORD_SECS: f32 = 0.4;
+/// How long the controller has to be held still before its orientation is captured, in seconds
+const SETTLE_SECS: f32 = 1.5;
+/// Largest change on any axis between readings, in radians, that still counts as holding still
+const STILL_THRESHOLD: f32 = 0.01;
+/// How long the recentered message stays up, in seconds
+const DONE_SECS: f32 = 2.0;
+/// Font size of the recentering prompt
+const PROMPT_FONT_SIZE: f32 = 24.0;
+
+/// Resting orientation each player's readings are measured from
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Calibration {
+    /// Orientation captured when each player's controller was last recentered
+    offsets: HashMap<usize, Orientation>,
+}
+
+impl Calibration {
+    /// Measures a player's raw reading from their captured resting orientation
+    pub fn apply(&self, player: usize, raw: Orientation) -> Orientation {
+        let offset = self.offsets.get(&player).copied().unwrap_or_default();
+        Orientation::new(
+            raw.pitch - offset.pitch,
+            raw.roll - offset.roll,
+            raw.yaw - offset.yaw,
+        )
+    }
+}
+
+/// Where the recentering flow is
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+enum Recenter {
+    /// Not recentering
+    #[default]
+    Idle,
+    /// Waiting for a controller to be held still
+    Settling {
+        /// Player slot of the controller being recentered
+        player: usize,
+        /// When the controller last moved
+        still_since: f32,
+        /// The last reading, to tell whether it moved
+        last: Option<Orientation>,
+    },
+    /// Just captured a new resting orientation
+    Done {
+        /// When it was captured
+        at: f32,
+    },
+}
+
+impl Recenter {
+    /// What the player is told on this step
+    fn prompt(&self) -> &'static str {
+        match self {
+            Self::Idle => "",
+            Self::Settling { .. } => "Hold the controller still in its resting position...",
+            Self::Done { .. } => "Recentered!",
+        }
+    }
+}
+
+/// When each button was last pressed, to spot them pressed together
+#[derive(Debug, Default)]
+struct Chord {
+    /// When A was last pressed
+    a: Option<f32>,
+    /// When B was last pressed
+    b: Option<f32>,
+}
+
+impl Chord {
+    /// Records a press, returning whether both buttons have now been pressed together
+    fn press(&mut self, action: ControllerAction, now: f32) -> bool {
+        match action {
+            ControllerAction::ButtonA => self.a = Some(now),
+            ControllerAction::ButtonB => self.b = Some(now),
+            ControllerAction::ReleaseA
+            | ControllerAction::ReleaseB
+            | ControllerAction::Rotate { .. } => return false,
+        }
+
+        let together = self
+            .a
+            .zip(self.b)
+            .is_some_and(|(a, b)| (a - b).abs() <= CHORD_SECS);
+        if together {
+            *self = Self::default();
+        }
+        together
+    }
+}
+
+/// The recentering prompt at the bottom of the screen
+#[derive(Component)]
+struct RecenterPrompt;
+
+/// Plugin that adds recentering
+pub struct CalibrationPlugin;
+
+impl Plugin for CalibrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Calibration>()
+            .init_resource::<Recenter>()
+            .add_systems(Startup, spawn_prompt)
+            .add_systems(
+                Update,
+                (
+                    recenter.run_if(resource_equals(CubeMode::Diagnostics)),
+                    show_prompt.run_if(resource_changed::<Recenter>),
+                )
+                    .chain()
+                    .after(crate::read_input),
+            );
+    }
+}
+
+/// Spawns the hidden recentering prompt
+fn spawn_prompt(mut commands: Commands<'_, '_>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(PROMPT_FONT_SIZE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+        RecenterPrompt,
+    ));
+}
+
+/// Starts recentering a controller when its A and B are pressed together, then captures its
+/// resting orientation once it has been still for [`SETTLE_SECS`]
+fn recenter(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut calibration: ResMut<'_, Calibration>,
+    mut flow: ResMut<'_, Recenter>,
+    mut chords: Local<'_, HashMap<usize, Chord>>,
+    time: Res<'_, Time>,
+) {
+    let now = time.elapsed_secs();
+    for msg in input.read() {
+        if chords.entry(msg.player).or_default().press(msg.action, now) {
+            *flow = Recenter::Settling {
+                player: msg.player,
+                still_since: now,
+                last: None,
+            };
+            continue;
+        }
+
+        let (
+            ControllerAction::Rotate { raw, .. },
+            Recenter::Settling {
+                player,
+                still_since,
+                last,
+            },
+        ) = (msg.action, &mut *flow)
+        else {
+            continue;
+        };
+        if *player != msg.player {
+            continue;
+        }
+
+        let moved = last.is_some_and(|last| {
+            (raw.pitch - last.pitch)
+                .abs()
+                .max((raw.roll - last.roll).abs())
+                .max((raw.yaw - last.yaw).abs())
+                > STILL_THRESHOLD
+        });
+        if moved {
+            *still_since = now;
+        }
+        *last = Some(raw);
+
+        if now - *still_since >= SETTLE_SECS {
+            calibration.offsets.insert(msg.player, raw);
+            *flow = Recenter::Done { at: now };
+        }
+    }
+
+    if let Recenter::Done { at } = *flow {
+        if now - at >= DONE_SECS {
+            *flow = Recenter::Idle;
+        }
+    }
+}
+
+/// Shows what the recentering flow needs from the player
+fn show_prompt(
+    flow: Res<'_, Recenter>,
+    mut prompt: Query<'_, '_, (&mut Text, &mut Visibility), With<RecenterPrompt>>,
+) {
+    for (mut text, mut visibility) in &mut prompt {
+        *text = Text::new(flow.prompt());
+        *visibility = if *flow == Recenter::Idle {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+    }
+}
@@ -0,0 +1,142 @@
+//! Free-look camera, so the cube's rotation can be inspected from the side and top. Dragging with
+//! the mouse orbits, the wheel zooms and V cycles through preset views
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+};
+
+/// Radians the camera orbits per pixel dragged
+const ORBIT_SPEED: f32 = 0.008;
+/// Fraction the camera's distance changes per line scrolled
+const ZOOM_SPEED: f32 = 0.1;
+/// Pixels of a pixel-based scroll that count as one line
+const PIXELS_PER_LINE: f32 = 40.0;
+/// Closest the camera can get to the cube
+const MIN_DISTANCE: f32 = 3.0;
+/// Furthest the camera can get from the cube
+const MAX_DISTANCE: f32 = 40.0;
+/// Furthest the camera can tilt over or under the cube, short of straight on so it never flips
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+/// A view the camera can snap to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum View {
+    /// Facing the cube head on, a little above
+    #[default]
+    Front,
+    /// From the right of the cube, to see pitch
+    Side,
+    /// From straight above, to see yaw
+    Top,
+}
+
+impl View {
+    /// The view after this one
+    fn next(&self) -> Self {
+        match self {
+            Self::Front => Self::Side,
+            Self::Side => Self::Top,
+            Self::Top => Self::Front,
+        }
+    }
+
+    /// Where the camera sits for this view
+    fn orbit(&self) -> OrbitCamera {
+        let (yaw, pitch) = match self {
+            Self::Front => (0.0, 0.2),
+            Self::Side => (FRAC_PI_2, 0.0),
+            Self::Top => (0.0, MAX_PITCH),
+        };
+        OrbitCamera {
+            yaw,
+            pitch,
+            distance: 10.0,
+        }
+    }
+}
+
+/// Camera orbiting the origin
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    /// Angle around the vertical axis, in radians
+    pub yaw: f32,
+    /// Angle above the ground, in radians
+    pub pitch: f32,
+    /// Distance from the origin
+    pub distance: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        View::default().orbit()
+    }
+}
+
+impl OrbitCamera {
+    /// Where the camera sits and faces
+    pub fn transform(&self) -> Transform {
+        let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, -self.pitch, 0.0);
+        Transform::from_translation(rotation * Vec3::Z * self.distance)
+            .looking_at(Vec3::ZERO, Vec3::Y)
+    }
+}
+
+/// Plugin that adds the free-look camera controls
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (orbit_camera, cycle_views, place_camera).chain());
+    }
+}
+
+/// Orbits the camera while the mouse is dragged and zooms it with the wheel
+fn orbit_camera(
+    mut cameras: Query<'_, '_, &mut OrbitCamera>,
+    buttons: Res<'_, ButtonInput<MouseButton>>,
+    motion: Res<'_, AccumulatedMouseMotion>,
+    scroll: Res<'_, AccumulatedMouseScroll>,
+) {
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_LINE,
+    };
+    let dragged = buttons.pressed(MouseButton::Left).then_some(motion.delta);
+
+    for mut camera in &mut cameras {
+        if let Some(delta) = dragged {
+            camera.yaw -= delta.x * ORBIT_SPEED;
+            camera.pitch = (camera.pitch + delta.y * ORBIT_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+        if lines != 0.0 {
+            camera.distance =
+                (camera.distance * (1.0 - lines * ZOOM_SPEED)).clamp(MIN_DISTANCE, MAX_DISTANCE);
+        }
+    }
+}
+
+/// Snaps the camera to the next preset view when V is pressed
+fn cycle_views(
+    mut cameras: Query<'_, '_, &mut OrbitCamera>,
+    keys: Res<'_, ButtonInput<KeyCode>>,
+    mut view: Local<'_, View>,
+) {
+    if !keys.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+
+    *view = view.next();
+    for mut camera in &mut cameras {
+        *camera = view.orbit();
+    }
+}
+
+/// Moves the camera to wherever it has been orbited to
+fn place_camera(mut cameras: Query<'_, '_, (&mut Transform, &OrbitCamera), Changed<OrbitCamera>>) {
+    for (mut transform, camera) in &mut cameras {
+        *transform = camera.transform();
+    }
+}
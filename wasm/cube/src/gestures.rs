@@ -0,0 +1,474 @@
+//! Gesture trainer, for tuning detection thresholds before building gesture driven games. Runs the
+//! shared gesture detector on the first player's controller, showing what fired, how hard, and a
+//! timeline of the readings it saw. B picks a threshold and A raises it, wrapping back to its
+//! lowest setting
+
+use std::{collections::VecDeque, f32::consts::PI};
+
+use bevy::prelude::*;
+use spjorts_core::{
+    communication::Orientation,
+    gesture::{DetectedGesture, GestureDetector, GestureThresholds},
+};
+
+use crate::{mode::CubeMode, ControllerAction, ControllerInput};
+
+/// Player slot whose controller is trained on
+const TRAINED_PLAYER: usize = 0;
+/// How many of the latest readings the timeline shows
+const TIMELINE_LEN: usize = 60;
+/// Height of the orientation timeline
+const TIMELINE_HEIGHT: f32 = 90.0;
+/// Height of the speed timeline
+const SPEED_HEIGHT: f32 = 50.0;
+/// Turning speed that fills a speed bar, in radians per second
+const MAX_PLOTTED_SPEED: f32 = 20.0;
+/// Width of a slider's bar
+const SLIDER_WIDTH: f32 = 140.0;
+/// Font size of the trainer's text
+const FONT_SIZE: f32 = 18.0;
+/// Color of the selected slider's name
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+/// Colors of the pitch, roll and yaw lines on the timeline
+const AXIS_COLORS: [Color; 3] = [
+    Color::srgb(0.9, 0.3, 0.3),
+    Color::srgb(0.3, 0.85, 0.4),
+    Color::srgb(0.3, 0.5, 0.95),
+];
+/// Color of a speed bar
+const SPEED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+/// Color of a speed bar for a reading that finished a gesture
+const FIRED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+
+/// A threshold that can be adjusted from the controller
+struct Slider {
+    /// Label shown next to the slider
+    name: &'static str,
+    /// Unit the value is shown in
+    unit: &'static str,
+    /// Lowest setting, wrapped back to after the highest
+    min: f32,
+    /// Highest setting
+    max: f32,
+    /// How much a press of A raises it by
+    step: f32,
+    /// The threshold this slider adjusts
+    value: fn(&mut GestureThresholds) -> &mut f32,
+}
+
+impl Slider {
+    /// Reads this slider's threshold
+    fn get(&self, thresholds: &GestureThresholds) -> f32 {
+        let mut thresholds = *thresholds;
+        *(self.value)(&mut thresholds)
+    }
+
+    /// Raises this slider's threshold a step, wrapping back to the lowest setting past the highest
+    fn raise(&self, thresholds: &mut GestureThresholds) {
+        let value = (self.value)(thresholds);
+        *value = if *value + self.step > self.max + f32::EPSILON {
+            self.min
+        } else {
+            *value + self.step
+        };
+    }
+}
+
+/// Every adjustable threshold, in the order B moves through them
+const SLIDERS: [Slider; 5] = [
+    Slider {
+        name: "Swing speed",
+        unit: "rad/s",
+        min: 1.0,
+        max: 12.0,
+        step: 0.5,
+        value: |thresholds| &mut thresholds.swing_speed,
+    },
+    Slider {
+        name: "Flick speed",
+        unit: "rad/s",
+        min: 2.0,
+        max: 20.0,
+        step: 1.0,
+        value: |thresholds| &mut thresholds.flick_speed,
+    },
+    Slider {
+        name: "Flick length",
+        unit: "s",
+        min: 0.05,
+        max: 0.5,
+        step: 0.05,
+        value: |thresholds| &mut thresholds.flick_max_secs,
+    },
+    Slider {
+        name: "Twist angle",
+        unit: "rad",
+        min: 0.2,
+        max: 3.0,
+        step: 0.2,
+        value: |thresholds| &mut thresholds.twist_angle,
+    },
+    Slider {
+        name: "Rest speed",
+        unit: "rad/s",
+        min: 0.2,
+        max: 3.0,
+        step: 0.2,
+        value: |thresholds| &mut thresholds.rest_speed,
+    },
+];
+
+/// A reading on the timeline
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    /// Orientation read
+    orientation: Orientation,
+    /// How fast the controller was turning, in radians per second
+    speed: f32,
+    /// Whether this reading finished a gesture
+    fired: bool,
+}
+
+/// The detector being trained and what it has seen
+#[derive(Resource, Debug, Default)]
+struct Trainer {
+    /// The shared detector, run with the thresholds being tuned
+    detector: GestureDetector,
+    /// The latest [`TIMELINE_LEN`] readings, oldest first
+    samples: VecDeque<Sample>,
+    /// Index into [`SLIDERS`] of the threshold A raises
+    selected: usize,
+    /// The last gesture detected
+    last: Option<DetectedGesture>,
+}
+
+/// Readout of the last gesture detected
+#[derive(Component)]
+struct GestureReadout;
+
+/// A point on the orientation timeline, for one axis of a reading counting back from the newest
+#[derive(Component)]
+struct TimelineDot {
+    /// Which axis, as an index into pitch, roll and yaw
+    axis: usize,
+    /// How many readings back from the newest
+    age: usize,
+}
+
+/// A bar on the speed timeline, counting back from the newest reading
+#[derive(Component)]
+struct SpeedBar(usize);
+
+/// Line on the speed timeline marking a speed threshold
+#[derive(Component)]
+struct ThresholdMarker(usize);
+
+/// A slider's label, fill and value, by index into [`SLIDERS`]
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SliderPart {
+    /// The slider's name
+    Label(usize),
+    /// The slider's bar fill
+    Fill(usize),
+    /// The slider's value
+    Value(usize),
+}
+
+/// Plugin that adds the gesture trainer
+pub struct GesturePlugin;
+
+impl Plugin for GesturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trainer>().add_systems(
+            Update,
+            (
+                spawn_trainer.run_if(resource_changed::<CubeMode>),
+                (train, show_trainer.run_if(resource_changed::<Trainer>))
+                    .chain()
+                    .after(crate::read_input)
+                    .run_if(resource_equals(CubeMode::Gestures)),
+            ),
+        );
+    }
+}
+
+/// Spawns the trainer's panel in the top right corner once the trainer is picked
+fn spawn_trainer(
+    mut commands: Commands<'_, '_>,
+    mode: Res<'_, CubeMode>,
+    mut trainer: ResMut<'_, Trainer>,
+) {
+    if *mode != CubeMode::Gestures {
+        return;
+    }
+    // Fill in the sliders as soon as they're spawned, rather than on the first reading
+    trainer.set_changed();
+
+    let font = TextFont::from_font_size(FONT_SIZE);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ))
+        .with_children(|panel| {
+            panel.spawn((Text::new("Make a gesture..."), font.clone(), GestureReadout));
+
+            panel
+                .spawn(Node {
+                    height: Val::Px(TIMELINE_HEIGHT),
+                    column_gap: Val::Px(1.0),
+                    ..default()
+                })
+                .with_children(|timeline| {
+                    for age in (0..TIMELINE_LEN).rev() {
+                        timeline
+                            .spawn(Node {
+                                width: Val::Px(3.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            })
+                            .with_children(|column| {
+                                for (axis, color) in AXIS_COLORS.into_iter().enumerate() {
+                                    column.spawn((
+                                        Node {
+                                            position_type: PositionType::Absolute,
+                                            top: Val::Percent(50.0),
+                                            width: Val::Px(3.0),
+                                            height: Val::Px(3.0),
+                                            ..default()
+                                        },
+                                        BackgroundColor(color),
+                                        Visibility::Hidden,
+                                        TimelineDot { axis, age },
+                                    ));
+                                }
+                            });
+                    }
+                });
+
+            panel
+                .spawn(Node {
+                    height: Val::Px(SPEED_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    column_gap: Val::Px(1.0),
+                    ..default()
+                })
+                .with_children(|speeds| {
+                    for age in (0..TIMELINE_LEN).rev() {
+                        speeds.spawn((
+                            Node {
+                                width: Val::Px(3.0),
+                                height: Val::Percent(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(SPEED_COLOR),
+                            SpeedBar(age),
+                        ));
+                    }
+                    for slider in [0, 1, 4] {
+                        speeds.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                width: Val::Percent(100.0),
+                                height: Val::Px(1.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::WHITE.with_alpha(0.5)),
+                            ThresholdMarker(slider),
+                        ));
+                    }
+                });
+
+            for (idx, slider) in SLIDERS.iter().enumerate() {
+                panel
+                    .spawn(Node {
+                        column_gap: Val::Px(8.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::new(slider.name),
+                            font.clone(),
+                            Node {
+                                width: Val::Px(120.0),
+                                ..default()
+                            },
+                            SliderPart::Label(idx),
+                        ));
+                        row.spawn((
+                            Node {
+                                width: Val::Px(SLIDER_WIDTH),
+                                height: Val::Px(10.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                        ))
+                        .with_child((
+                            Node {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(SPEED_COLOR),
+                            SliderPart::Fill(idx),
+                        ));
+                        row.spawn((Text::new(""), font.clone(), SliderPart::Value(idx)));
+                    });
+            }
+
+            panel.spawn((
+                Text::new("B: next threshold  A: raise it"),
+                TextFont::from_font_size(FONT_SIZE * 0.75),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Runs the detector on the trained controller's readings and adjusts thresholds on presses
+fn train(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut trainer: ResMut<'_, Trainer>,
+    time: Res<'_, Time>,
+) {
+    for msg in input.read().filter(|msg| msg.player == TRAINED_PLAYER) {
+        match msg.action {
+            ControllerAction::Rotate { orientation, .. } => {
+                let detected = trainer.detector.update(orientation, time.elapsed_secs());
+                let speed = trainer.detector.speed();
+                trainer.samples.push_back(Sample {
+                    orientation,
+                    speed,
+                    fired: detected.is_some(),
+                });
+                if trainer.samples.len() > TIMELINE_LEN {
+                    trainer.samples.pop_front();
+                }
+                if detected.is_some() {
+                    trainer.last = detected;
+                }
+            }
+            ControllerAction::ButtonA => {
+                let slider = &SLIDERS[trainer.selected];
+                slider.raise(&mut trainer.detector.thresholds);
+            }
+            ControllerAction::ButtonB => {
+                trainer.selected = (trainer.selected + 1) % SLIDERS.len();
+            }
+            ControllerAction::ReleaseA | ControllerAction::ReleaseB => {}
+        }
+    }
+}
+
+/// Shows the last gesture, the timeline and the sliders
+fn show_trainer(
+    trainer: Res<'_, Trainer>,
+    mut readout: Query<'_, '_, &mut Text, (With<GestureReadout>, Without<SliderPart>)>,
+    mut dots: Query<'_, '_, (&mut Node, &mut Visibility, &TimelineDot)>,
+    mut bars: Query<
+        '_,
+        '_,
+        (&mut Node, &mut BackgroundColor, &SpeedBar),
+        (Without<TimelineDot>, Without<SliderPart>),
+    >,
+    mut markers: Query<
+        '_,
+        '_,
+        (&mut Node, &ThresholdMarker),
+        (Without<TimelineDot>, Without<SpeedBar>, Without<SliderPart>),
+    >,
+    mut sliders: Query<
+        '_,
+        '_,
+        (
+            &SliderPart,
+            Option<&mut Node>,
+            Option<&mut Text>,
+            Option<&mut TextColor>,
+        ),
+        (
+            Without<TimelineDot>,
+            Without<SpeedBar>,
+            Without<ThresholdMarker>,
+        ),
+    >,
+) {
+    if let (Some(last), Ok(mut text)) = (trainer.last, readout.get_single_mut()) {
+        *text = Text::new(format!(
+            "{}: {:.1} rad/s over {:.2}s",
+            last.gesture.name(),
+            last.intensity,
+            last.duration
+        ));
+    }
+
+    let sample = |age: usize| {
+        trainer
+            .samples
+            .len()
+            .checked_sub(age + 1)
+            .and_then(|idx| trainer.samples.get(idx))
+    };
+
+    for (mut node, mut visibility, dot) in &mut dots {
+        let Some(sample) = sample(dot.age) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let angle = match dot.axis {
+            0 => sample.orientation.pitch,
+            1 => sample.orientation.roll,
+            _ => sample.orientation.yaw,
+        };
+        node.top = Val::Percent(50.0 - angle.clamp(-PI, PI) / PI * 50.0);
+        *visibility = Visibility::Inherited;
+    }
+
+    for (mut node, mut color, SpeedBar(age)) in &mut bars {
+        let (speed, fired) =
+            sample(*age).map_or((0.0, false), |sample| (sample.speed, sample.fired));
+        node.height = Val::Percent((speed / MAX_PLOTTED_SPEED).clamp(0.0, 1.0) * 100.0);
+        *color = BackgroundColor(if fired { FIRED_COLOR } else { SPEED_COLOR });
+    }
+
+    let thresholds = trainer.detector.thresholds;
+    for (mut node, ThresholdMarker(slider)) in &mut markers {
+        let speed = SLIDERS[*slider].get(&thresholds);
+        node.bottom = Val::Percent((speed / MAX_PLOTTED_SPEED).clamp(0.0, 1.0) * 100.0);
+    }
+
+    for (part, node, text, color) in &mut sliders {
+        match *part {
+            SliderPart::Label(idx) => {
+                if let Some(mut color) = color {
+                    color.0 = if idx == trainer.selected {
+                        SELECTED_COLOR
+                    } else {
+                        Color::WHITE
+                    };
+                }
+            }
+            SliderPart::Fill(idx) => {
+                let slider = &SLIDERS[idx];
+                if let Some(mut node) = node {
+                    let filled = (slider.get(&thresholds) - slider.min) / (slider.max - slider.min);
+                    node.width = Val::Percent(filled.clamp(0.0, 1.0) * 100.0);
+                }
+            }
+            SliderPart::Value(idx) => {
+                let slider = &SLIDERS[idx];
+                if let Some(mut text) = text {
+                    *text = Text::new(format!("{:.2} {}", slider.get(&thresholds), slider.unit));
+                }
+            }
+        }
+    }
+}
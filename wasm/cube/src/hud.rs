@@ -0,0 +1,317 @@
+//! Live diagnostics for checking a controller before playing: per-axis readouts and bar graphs,
+//! button press lights and how fast the readings drift. These follow the first player's
+//! controller
+
+use std::{collections::VecDeque, f32::consts::PI};
+
+use bevy::prelude::*;
+use spjorts_core::communication::Orientation;
+
+use crate::{ControllerAction, ControllerInput};
+
+/// How long a button light takes to fade after a press, in seconds
+const BUTTON_FADE_SECS: f32 = 0.4;
+/// How far back drift is measured over, in seconds
+const DRIFT_WINDOW_SECS: f32 = 5.0;
+/// Width of an axis bar graph
+const BAR_WIDTH: f32 = 200.0;
+/// Font size of the readouts
+const HUD_FONT_SIZE: f32 = 18.0;
+/// Color of a bar's fill and a lit button
+const LIT_COLOR: Color = Color::srgb(0.2, 0.8, 0.3);
+/// Color of an unlit button
+const UNLIT_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+/// Player slot whose controller the HUD shows
+const SHOWN_PLAYER: usize = 0;
+
+/// One of the controller's rotation axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    /// Rotation about the X axis
+    Pitch,
+    /// Rotation about the Y axis
+    Roll,
+    /// Rotation about the Z axis
+    Yaw,
+}
+
+impl Axis {
+    /// Every axis, in readout order
+    const ALL: [Self; 3] = [Self::Pitch, Self::Roll, Self::Yaw];
+
+    /// Label shown next to the axis' readout
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Pitch => "Pitch",
+            Self::Roll => "Roll",
+            Self::Yaw => "Yaw",
+        }
+    }
+
+    /// This axis' angle in an orientation
+    fn of(&self, orientation: &Orientation) -> f32 {
+        match self {
+            Self::Pitch => orientation.pitch,
+            Self::Roll => orientation.roll,
+            Self::Yaw => orientation.yaw,
+        }
+    }
+}
+
+/// A controller button with a light on the HUD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Button {
+    /// The A button
+    A,
+    /// The B button
+    B,
+}
+
+/// Numeric readout of an axis, in degrees
+#[derive(Component)]
+struct AxisReadout(Axis);
+
+/// Fill of an axis' bar graph, growing from the middle either way
+#[derive(Component)]
+struct AxisBar(Axis);
+
+/// Light that flashes when a button is pressed
+#[derive(Component)]
+struct ButtonLight {
+    /// Which button it shows
+    button: Button,
+    /// Seconds left before it fades out
+    lit: f32,
+}
+
+/// Readout of how fast each axis is drifting
+#[derive(Component)]
+struct DriftReadout;
+
+/// Raw readings over the last [`DRIFT_WINDOW_SECS`], to measure drift from
+#[derive(Resource, Debug, Default)]
+struct DriftMeter {
+    /// Readings with the seconds since startup they were read at, oldest first
+    samples: VecDeque<(f32, Orientation)>,
+}
+
+impl DriftMeter {
+    /// Records a reading, dropping those that have fallen out of the window
+    fn record(&mut self, at: f32, raw: Orientation) {
+        self.samples.push_back((at, raw));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(read_at, _)| at - read_at > DRIFT_WINDOW_SECS)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// How fast an axis has moved across the window in radians per second. With the controller
+    /// at rest, this is how fast it's drifting
+    fn rate(&self, axis: Axis) -> Option<f32> {
+        let ((first_at, first), (last_at, last)) = (self.samples.front()?, self.samples.back()?);
+        let span = last_at - first_at;
+        (span > f32::EPSILON).then(|| (axis.of(last) - axis.of(first)) / span)
+    }
+}
+
+/// Plugin that adds the diagnostics HUD
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DriftMeter>()
+            .add_systems(Startup, spawn_hud)
+            .add_systems(
+                Update,
+                (update_readouts, update_buttons, update_drift).after(crate::read_input),
+            );
+    }
+}
+
+/// Spawns the readouts, bar graphs and button lights in the top left corner
+fn spawn_hud(mut commands: Commands<'_, '_>) {
+    let font = TextFont::from_font_size(HUD_FONT_SIZE);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                left: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ))
+        .with_children(|hud| {
+            for axis in Axis::ALL {
+                hud.spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        Text::new(format!("{:<5}", axis.name())),
+                        font.clone(),
+                        Node {
+                            width: Val::Px(50.0),
+                            ..default()
+                        },
+                    ));
+                    row.spawn((
+                        Text::new("+0.0°"),
+                        font.clone(),
+                        Node {
+                            width: Val::Px(70.0),
+                            ..default()
+                        },
+                        AxisReadout(axis),
+                    ));
+                    row.spawn((
+                        Node {
+                            width: Val::Px(BAR_WIDTH),
+                            height: Val::Px(12.0),
+                            ..default()
+                        },
+                        BackgroundColor(UNLIT_COLOR),
+                    ))
+                    .with_children(|bar| {
+                        bar.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(50.0),
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(LIT_COLOR),
+                            AxisBar(axis),
+                        ));
+                    });
+                });
+            }
+
+            hud.spawn(Node {
+                column_gap: Val::Px(8.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for button in [Button::A, Button::B] {
+                    row.spawn((
+                        Node {
+                            width: Val::Px(32.0),
+                            height: Val::Px(32.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(UNLIT_COLOR),
+                        ButtonLight { button, lit: 0.0 },
+                    ))
+                    .with_child((Text::new(format!("{button:?}")), font.clone()));
+                }
+            });
+
+            hud.spawn((Text::new("Drift: measuring..."), font.clone(), DriftReadout));
+            hud.spawn((
+                Text::new(
+                    "Press A and B together to recenter\nDrag to orbit, scroll to zoom, V for views",
+                ),
+                TextFont::from_font_size(HUD_FONT_SIZE * 0.75),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Shows the latest orientation as degrees and as bars out from the middle
+fn update_readouts(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut readouts: Query<'_, '_, (&mut Text, &AxisReadout)>,
+    mut bars: Query<'_, '_, (&mut Node, &AxisBar)>,
+) {
+    let Some(orientation) = input
+        .read()
+        .filter(|msg| msg.player == SHOWN_PLAYER)
+        .filter_map(|msg| match msg.action {
+            ControllerAction::Rotate { orientation, .. } => Some(orientation),
+            _ => None,
+        })
+        .last()
+    else {
+        return;
+    };
+
+    for (mut text, AxisReadout(axis)) in &mut readouts {
+        *text = Text::new(format!("{:+.1}°", axis.of(&orientation).to_degrees()));
+    }
+
+    for (mut node, AxisBar(axis)) in &mut bars {
+        let half = axis.of(&orientation).clamp(-PI, PI) / PI * 50.0;
+        node.left = Val::Percent(50.0 + half.min(0.0));
+        node.width = Val::Percent(half.abs());
+    }
+}
+
+/// Lights a button's indicator when it's pressed, fading it back out over [`BUTTON_FADE_SECS`]
+fn update_buttons(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut lights: Query<'_, '_, (&mut BackgroundColor, &mut ButtonLight)>,
+    time: Res<'_, Time>,
+) {
+    let pressed: Vec<Button> = input
+        .read()
+        .filter(|msg| msg.player == SHOWN_PLAYER)
+        .filter_map(|msg| match msg.action {
+            ControllerAction::ButtonA => Some(Button::A),
+            ControllerAction::ButtonB => Some(Button::B),
+            ControllerAction::ReleaseA
+            | ControllerAction::ReleaseB
+            | ControllerAction::Rotate { .. } => None,
+        })
+        .collect();
+
+    for (mut color, mut light) in &mut lights {
+        if pressed.contains(&light.button) {
+            light.lit = BUTTON_FADE_SECS;
+        } else {
+            light.lit = (light.lit - time.delta_secs()).max(0.0);
+        }
+        *color = BackgroundColor(UNLIT_COLOR.mix(&LIT_COLOR, light.lit / BUTTON_FADE_SECS));
+    }
+}
+
+/// Measures how fast each raw axis is drifting over the last [`DRIFT_WINDOW_SECS`]
+fn update_drift(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut meter: ResMut<'_, DriftMeter>,
+    mut readout: Query<'_, '_, &mut Text, With<DriftReadout>>,
+    time: Res<'_, Time>,
+) {
+    let mut read = false;
+    for msg in input.read().filter(|msg| msg.player == SHOWN_PLAYER) {
+        if let ControllerAction::Rotate { raw, .. } = msg.action {
+            meter.record(time.elapsed_secs(), raw);
+            read = true;
+        }
+    }
+    if !read {
+        return;
+    }
+
+    let rates: Option<Vec<String>> = Axis::ALL
+        .iter()
+        .map(|axis| {
+            meter
+                .rate(*axis)
+                .map(|rate| format!("{} {:+.2}", &axis.name()[..1], rate.to_degrees()))
+        })
+        .collect();
+    if let (Some(rates), Ok(mut text)) = (rates, readout.get_single_mut()) {
+        *text = Text::new(format!("Drift: {} °/s", rates.join("  ")));
+    }
+}
@@ -0,0 +1,205 @@
+//! Latency meter, measuring how long a reading takes to get from the controller, through the
+//! server and browser, to the frame the cube is turned in. Readings are stamped by the controller,
+//! so its clock has to be in sync with the browser's
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use spjorts_core::js_sys::Date;
+
+use crate::{mode::CubeMode, ControllerAction, ControllerInput};
+
+/// How far back the rolling average and worst case are measured over, in seconds
+const WINDOW_SECS: f32 = 5.0;
+/// How many of the latest readings are plotted
+const PLOTTED: usize = 60;
+/// Latency that fills a plot bar, in milliseconds
+const PLOT_MAX_MS: f64 = 200.0;
+/// Height of the plot
+const PLOT_HEIGHT: f32 = 80.0;
+/// Font size of the overlay's text
+const FONT_SIZE: f32 = 18.0;
+/// Latency up to which a bar is drawn as good, in milliseconds
+const GOOD_MS: f64 = 50.0;
+/// Latency up to which a bar is drawn as fair, in milliseconds
+const FAIR_MS: f64 = 100.0;
+
+/// Latency of recent readings
+#[derive(Resource, Debug, Default)]
+struct LatencyMeter {
+    /// Latency in milliseconds with the seconds since startup it was measured at, oldest first
+    samples: VecDeque<(f32, f64)>,
+    /// The latest [`PLOTTED`] latencies, oldest first
+    recent: VecDeque<f64>,
+}
+
+impl LatencyMeter {
+    /// Records a reading's latency, dropping those that have fallen out of the window
+    fn record(&mut self, at: f32, latency_ms: f64) {
+        self.samples.push_back((at, latency_ms));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(read_at, _)| at - read_at > WINDOW_SECS)
+        {
+            self.samples.pop_front();
+        }
+
+        self.recent.push_back(latency_ms);
+        if self.recent.len() > PLOTTED {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Average latency across the window
+    fn average(&self) -> Option<f64> {
+        (!self.samples.is_empty())
+            .then(|| self.samples.iter().map(|(_, ms)| ms).sum::<f64>() / self.samples.len() as f64)
+    }
+
+    /// Worst latency across the window
+    fn worst(&self) -> Option<f64> {
+        self.samples.iter().map(|(_, ms)| *ms).reduce(f64::max)
+    }
+}
+
+/// Color a bar is drawn in for a latency
+fn latency_color(latency_ms: f64) -> Color {
+    if latency_ms <= GOOD_MS {
+        Color::srgb(0.2, 0.8, 0.3)
+    } else if latency_ms <= FAIR_MS {
+        Color::srgb(0.95, 0.75, 0.2)
+    } else {
+        Color::srgb(0.9, 0.25, 0.25)
+    }
+}
+
+/// Readout of the rolling average and worst case
+#[derive(Component)]
+struct LatencyReadout;
+
+/// One bar of the plot, counting back from the newest reading
+#[derive(Component)]
+struct PlotBar(usize);
+
+/// Plugin that adds the latency meter
+pub struct LatencyPlugin;
+
+impl Plugin for LatencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LatencyMeter>().add_systems(
+            Update,
+            (
+                spawn_overlay.run_if(resource_changed::<CubeMode>),
+                (measure_latency, update_overlay)
+                    .chain()
+                    .after(crate::read_input)
+                    .run_if(resource_equals(CubeMode::Latency)),
+            ),
+        );
+    }
+}
+
+/// Spawns the overlay in the top right corner once the latency meter is picked
+fn spawn_overlay(mut commands: Commands<'_, '_>, mode: Res<'_, CubeMode>) {
+    if *mode != CubeMode::Latency {
+        return;
+    }
+
+    let font = TextFont::from_font_size(FONT_SIZE);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                Text::new("Waiting for timestamped readings..."),
+                font.clone(),
+                LatencyReadout,
+            ));
+            overlay
+                .spawn(Node {
+                    height: Val::Px(PLOT_HEIGHT),
+                    align_items: AlignItems::FlexEnd,
+                    column_gap: Val::Px(1.0),
+                    ..default()
+                })
+                .with_children(|plot| {
+                    for age in (0..PLOTTED).rev() {
+                        plot.spawn((
+                            Node {
+                                width: Val::Px(3.0),
+                                height: Val::Percent(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(latency_color(0.0)),
+                            PlotBar(age),
+                        ));
+                    }
+                });
+            overlay.spawn((
+                Text::new(format!("Bars fill at {PLOT_MAX_MS} ms")),
+                TextFont::from_font_size(FONT_SIZE * 0.75),
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// Measures the time from when each stamped reading was read to the frame it's applied in
+fn measure_latency(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut meter: ResMut<'_, LatencyMeter>,
+    time: Res<'_, Time>,
+) {
+    let mut now = None;
+    for msg in input.read() {
+        if let ControllerAction::Rotate {
+            sent_at_ms: Some(sent_at_ms),
+            ..
+        } = msg.action
+        {
+            let now = *now.get_or_insert_with(Date::now);
+            meter.record(time.elapsed_secs(), now - sent_at_ms);
+        }
+    }
+}
+
+/// Shows the rolling average and worst case, and plots the latest readings
+fn update_overlay(
+    meter: Res<'_, LatencyMeter>,
+    mut readout: Query<'_, '_, &mut Text, With<LatencyReadout>>,
+    mut bars: Query<'_, '_, (&mut Node, &mut BackgroundColor, &PlotBar)>,
+) {
+    if !meter.is_changed() {
+        return;
+    }
+
+    if let (Some(average), Some(worst), Ok(mut text)) =
+        (meter.average(), meter.worst(), readout.get_single_mut())
+    {
+        *text = Text::new(format!(
+            "Latency: {average:.0} ms avg, {worst:.0} ms worst over {WINDOW_SECS:.0}s"
+        ));
+    }
+
+    for (mut node, mut color, PlotBar(age)) in &mut bars {
+        let latency = meter
+            .recent
+            .len()
+            .checked_sub(age + 1)
+            .and_then(|idx| meter.recent.get(idx))
+            .copied()
+            .unwrap_or_default();
+        node.height = Val::Percent((latency / PLOT_MAX_MS).clamp(0.0, 1.0) as f32 * 100.0);
+        *color = BackgroundColor(latency_color(latency));
+    }
+}
@@ -1,66 +1,231 @@
-//! A basic cube WASM app
+//! A basic cube WASM app, doubling as a tool for checking a controller works before playing
 
 use bevy::prelude::*;
-use crossbeam_channel::Sender;
-use spjorts_core::{communication::JsMessage, ActionReader, ActionSender, Communication};
-use wasm_bindgen::prelude::wasm_bindgen;
-
-/// An app instance with internal JavaScript communications
-#[wasm_bindgen]
-pub struct Runner {
-    app: App,
-    write: Sender<Communication>,
+use bevy_rapier3d::plugin::{NoUserData, RapierPhysicsPlugin};
+use calibration::{Calibration, CalibrationPlugin};
+use camera::{CameraPlugin, OrbitCamera};
+use gestures::GesturePlugin;
+use hud::HudPlugin;
+use latency::LatencyPlugin;
+use mode::{CubeMode, ModePlugin};
+use playground::{PlaygroundPlugin, Tossed};
+use serde::Serialize;
+use spjorts_core::{
+    capture::{CaptureRow, MotionCapture},
+    communication::{JsMessage, Orientation},
+    js_sys::Date,
+    menu::{Menu, MenuAction},
+    settings::GameSettings,
+    snapshot::StateSnapshot,
+    ActionReader,
+};
+use trail::TrailPlugin;
+
+pub mod calibration;
+pub mod camera;
+pub mod gestures;
+pub mod hud;
+pub mod latency;
+pub mod mode;
+pub mod playground;
+pub mod trail;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins)
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        .add_event::<ControllerInput>()
+        .add_plugins((
+            CalibrationPlugin,
+            CameraPlugin,
+            GesturePlugin,
+            HudPlugin,
+            LatencyPlugin,
+            ModePlugin,
+            PlaygroundPlugin,
+            TrailPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                (read_input, spawn_cubes, move_cube, ease_cube).chain(),
+                record_motion.after(read_input),
+            ),
+        );
+});
+
+/// JavaScript facing snapshot of the first player's cube orientation
+#[derive(Serialize)]
+pub struct CubeSnapshot {
+    /// Current pitch
+    pub pitch: f32,
+    /// Current roll
+    pub roll: f32,
+    /// Current yaw
+    pub yaw: f32,
 }
 
+/// Longest gap between rotations the cube eases over, in seconds. Anything slower is treated as
+/// a fresh start rather than stretched into a slow drift
+const MAX_SAMPLE_INTERVAL_SECS: f32 = 0.25;
+
+/// Gap between rotations the cube eases over before it has seen two, about the firmware's 20 Hz
+const DEFAULT_SAMPLE_INTERVAL_SECS: f32 = 0.05;
+
+/// Distance between neighbouring players' cubes
+const CUBE_SPACING: f32 = 2.5;
+
+/// How long B has to be held before it counts as a long press rather than a tap, in seconds
+const LONG_PRESS_SECS: f32 = 0.5;
+
+/// How fast a cube grows while B is held past a long press, in scale per second
+const GROW_RATE: f32 = 1.0;
+
+/// Largest a held cube grows to
+const MAX_SCALE: f32 = 2.5;
+
+/// Each player's cube color, so it's clear which controller drives which cube
+const PLAYER_COLORS: [Color; 6] = [
+    Color::WHITE,
+    Color::srgb(0.9, 0.3, 0.3),
+    Color::srgb(0.3, 0.5, 0.95),
+    Color::srgb(0.35, 0.85, 0.4),
+    Color::srgb(0.95, 0.8, 0.2),
+    Color::srgb(0.75, 0.4, 0.9),
+];
+
 /// Cube state
-#[derive(Default, Component)]
+#[derive(Component)]
 pub struct Cube {
-    /// The previous cube's rotation
+    /// Player slot of the controller driving this cube
+    pub player: usize,
+    /// Where the cube sits before it's slid or tossed
+    pub home: Vec3,
+    /// The previous cube's rotation, eased from towards the target
     pub prev_rot: Quat,
+    /// The latest rotation read from the controller
+    pub target_rot: Quat,
+    /// Seconds since startup the latest rotation arrived at
+    pub received_at: f32,
+    /// Seconds between the last two rotations, which the cube takes to ease to the target
+    pub interval: f32,
+    /// Index into the player colors of the cube's current color, cycled by A
+    pub color: usize,
+    /// Seconds since startup B was pressed at, while it's held
+    pub b_held_since: Option<f32>,
 }
 
-#[wasm_bindgen]
-impl Runner {
-    /// Creates a new runner instance and initializes the message channel
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let (write, read) = crossbeam_channel::unbounded();
-        let mut app = App::new();
-        app.add_plugins(DefaultPlugins)
-            .insert_resource(ActionReader(read))
-            .add_systems(Startup, setup)
-            .add_systems(Update, move_cube);
-
-        Runner { app, write }
+impl Default for Cube {
+    fn default() -> Self {
+        Self {
+            player: 0,
+            home: Vec3::ZERO,
+            prev_rot: Quat::IDENTITY,
+            target_rot: Quat::IDENTITY,
+            received_at: 0.0,
+            interval: DEFAULT_SAMPLE_INTERVAL_SECS,
+            color: 0,
+            b_held_since: None,
+        }
+    }
+}
+
+impl Cube {
+    /// Creates the cube for a player slot, laid out alternating either side of the first
+    /// player's cube
+    pub fn for_player(player: usize) -> Self {
+        let side = if player % 2 == 1 { 1.0 } else { -1.0 };
+        let step = player.div_ceil(2) as f32;
+        Self {
+            player,
+            home: Vec3::X * side * step * CUBE_SPACING,
+            color: player,
+            ..default()
+        }
+    }
+
+    /// This cube's color, starting as its player's
+    pub fn color(&self) -> Color {
+        PLAYER_COLORS[self.color % PLAYER_COLORS.len()]
+    }
+
+    /// How big the cube should be `now`, growing once B has been held past a long press
+    pub fn scale_at(&self, now: f32) -> f32 {
+        self.b_held_since.map_or(1.0, |since| {
+            let held = now - since - LONG_PRESS_SECS;
+            (1.0 + held.max(0.0) * GROW_RATE).min(MAX_SCALE)
+        })
     }
 
-    /// Gets the runner's send channel
-    pub fn get_send(&self) -> ActionSender {
-        ActionSender::new(self.write.clone())
+    /// Starts easing from the current rotation to a newly read one, over the time since the
+    /// last reading so the cube arrives just as the next is due
+    pub fn retarget(&mut self, current: Quat, target: Quat, now: f32) {
+        let since_last = now - self.received_at;
+        if since_last > f32::EPSILON {
+            self.interval = since_last.min(MAX_SAMPLE_INTERVAL_SECS);
+        }
+        self.prev_rot = current;
+        self.target_rot = target;
+        self.received_at = now;
     }
 
-    /// Runs the app as a blocking task
-    pub fn run(&mut self) {
-        self.app.run();
+    /// Where the cube should be turned to `now`, part way from the previous rotation to the
+    /// target
+    pub fn rotation_at(&self, now: f32) -> Quat {
+        let t = ((now - self.received_at) / self.interval).clamp(0.0, 1.0);
+        self.prev_rot.slerp(self.target_rot, t)
     }
 }
 
-/// System that spawns the cube, lighting and camera view
+/// Controller input read this frame, shared by the cubes and the diagnostics around them
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct ControllerInput {
+    /// Player slot of the controller it came from
+    pub player: usize,
+    /// What the controller did
+    pub action: ControllerAction,
+}
+
+/// Something a controller did
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerAction {
+    /// The controller turned
+    Rotate {
+        /// Orientation as the controller sent it
+        raw: Orientation,
+        /// Orientation after recentering and the player's settings
+        orientation: Orientation,
+        /// When the controller read it in milliseconds since the Unix epoch, if it was stamped
+        sent_at_ms: Option<f64>,
+    },
+    /// The A button was pressed
+    ButtonA,
+    /// The B button was pressed
+    ButtonB,
+    /// The A button was released
+    ReleaseA,
+    /// The B button was released
+    ReleaseB,
+}
+
+/// System that spawns the first player's cube, lighting and camera view
 fn setup(
     mut commands: Commands<'_, '_>,
     mut meshes: ResMut<'_, Assets<Mesh>>,
     mut materials: ResMut<'_, Assets<StandardMaterial>>,
 ) {
+    let cube = Cube::default();
     commands.spawn((
         Mesh3d(meshes.add(Cuboid::default())),
-        MeshMaterial3d(materials.add(Color::WHITE)),
-        Transform::from_translation(Vec3::ZERO),
-        Cube::default(),
+        MeshMaterial3d(materials.add(cube.color())),
+        Transform::from_translation(cube.home),
+        cube,
     ));
 
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 2.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        OrbitCamera::default().transform(),
+        OrbitCamera::default(),
     ));
 
     commands.spawn((
@@ -69,27 +234,174 @@ fn setup(
     ));
 }
 
-/// Moves a cube with respect to position
-fn move_cube(
-    mut cubes: Query<'_, '_, (&Mesh3d, &mut Transform, &mut Cube)>,
+/// Reads every message JavaScript sent since the last frame, passing controller input on with
+/// each player's recentering and the settings applied to rotations. While a menu is open, A
+/// selects and B moves down instead
+fn read_input(
     read: Res<'_, ActionReader>,
+    settings: Res<'_, GameSettings>,
+    calibration: Res<'_, Calibration>,
+    mut input: EventWriter<'_, ControllerInput>,
+    menus: Query<'_, '_, &Menu>,
+    mut menu: EventWriter<'_, MenuAction>,
 ) {
-    if let Ok(msg) = read.0.try_recv() {
-        for (_, mut transform, mut cube_info) in &mut cubes {
-            match msg {
-                JsMessage::ButtonA => {
+    let menu_open = menus.iter().any(|menu| menu.focused);
+    while let Ok(msg) = read.0.try_recv() {
+        let (player, msg) = msg.untag();
+        let mut send = |action| {
+            input.send(ControllerInput { player, action });
+        };
+        match msg {
+            JsMessage::ButtonA if menu_open => {
+                menu.send(MenuAction::Select);
+            }
+            JsMessage::ButtonB if menu_open => {
+                menu.send(MenuAction::Down);
+            }
+            JsMessage::ButtonA => send(ControllerAction::ButtonA),
+            JsMessage::ButtonB => send(ControllerAction::ButtonB),
+            JsMessage::ReleaseA => send(ControllerAction::ReleaseA),
+            JsMessage::ReleaseB => send(ControllerAction::ReleaseB),
+            JsMessage::Rotate(raw) => send(ControllerAction::Rotate {
+                raw,
+                orientation: settings.apply_rotation(calibration.apply(player, raw)),
+                sent_at_ms: None,
+            }),
+            JsMessage::TimedRotate(raw, sent_at_ms) => send(ControllerAction::Rotate {
+                raw,
+                orientation: settings.apply_rotation(calibration.apply(player, raw)),
+                sent_at_ms: Some(sent_at_ms),
+            }),
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Records every raw rotation for `export_csv`, so firmware developers can analyze their
+/// filtering offline
+fn record_motion(mut input: EventReader<'_, '_, ControllerInput>, capture: Res<'_, MotionCapture>) {
+    let mut received_at_ms = None;
+    for msg in input.read() {
+        if let ControllerAction::Rotate {
+            raw, sent_at_ms, ..
+        } = msg.action
+        {
+            capture.record(CaptureRow {
+                player: msg.player,
+                received_at_ms: *received_at_ms.get_or_insert_with(Date::now),
+                sent_at_ms,
+                orientation: raw,
+            });
+        }
+    }
+}
+
+/// Spawns a cube in the player's color the first time a controller in a new player slot is heard
+/// from
+fn spawn_cubes(
+    mut commands: Commands<'_, '_>,
+    mut input: EventReader<'_, '_, ControllerInput>,
+    cubes: Query<'_, '_, &Cube>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let mut known: Vec<usize> = cubes.iter().map(|cube| cube.player).collect();
+    for msg in input.read() {
+        if known.contains(&msg.player) {
+            continue;
+        }
+
+        let cube = Cube::for_player(msg.player);
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::default())),
+            MeshMaterial3d(materials.add(cube.color())),
+            Transform::from_translation(cube.home),
+            cube,
+        ));
+        known.push(msg.player);
+    }
+}
+
+/// Moves each cube with respect to position from its own player's controller. The buttons only
+/// act in diagnostics, the playground uses them to toss and reset it. There, pressing A slides the
+/// cube right and cycles its color, tapping B slides it left on release, and holding B grows it
+/// until it's let go
+fn move_cube(
+    mut cubes: Query<'_, '_, (&mut Transform, &mut Cube, &MeshMaterial3d<StandardMaterial>)>,
+    mut input: EventReader<'_, '_, ControllerInput>,
+    snapshot: Res<'_, StateSnapshot>,
+    time: Res<'_, Time>,
+    mode: Res<'_, CubeMode>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let sliding = *mode == CubeMode::Diagnostics;
+    let now = time.elapsed_secs();
+    for msg in input.read() {
+        for (mut transform, mut cube_info, material) in &mut cubes {
+            if cube_info.player != msg.player {
+                continue;
+            }
+
+            match msg.action {
+                ControllerAction::ButtonA if sliding => {
                     transform.translation += Vec3::new(1f32, 0f32, 0f32);
+                    cube_info.color += 1;
+                    if let Some(material) = materials.get_mut(material) {
+                        material.base_color = cube_info.color();
+                    }
+                }
+                ControllerAction::ButtonB if sliding => {
+                    cube_info.b_held_since = Some(now);
                 }
-                JsMessage::ButtonB => {
-                    transform.translation += Vec3::new(-1f32, 0f32, 0f32);
+                ControllerAction::ReleaseB if sliding => {
+                    let tapped = cube_info
+                        .b_held_since
+                        .take()
+                        .is_some_and(|since| now - since < LONG_PRESS_SECS);
+                    if tapped {
+                        transform.translation += Vec3::new(-1f32, 0f32, 0f32);
+                    }
                 }
-                JsMessage::Rotate(pitch, roll, yaw) => {
-                    let new_rot = Quat::from_euler(EulerRot::XYZ, pitch, roll, yaw);
-                    transform.rotation = new_rot;
-                    cube_info.prev_rot = new_rot;
+                ControllerAction::ButtonA
+                | ControllerAction::ButtonB
+                | ControllerAction::ReleaseA
+                | ControllerAction::ReleaseB => {}
+                ControllerAction::Rotate { orientation, .. } => {
+                    cube_info.retarget(
+                        transform.rotation,
+                        orientation.to_quat(),
+                        time.elapsed_secs(),
+                    );
+                    if msg.player == 0 {
+                        snapshot.set(&CubeSnapshot {
+                            pitch: orientation.pitch,
+                            roll: orientation.roll,
+                            yaw: orientation.yaw,
+                        });
+                    }
                 }
-                _ => {}
             }
         }
     }
+
+    if sliding {
+        for (mut transform, cube_info, _) in &mut cubes {
+            transform.scale = Vec3::splat(cube_info.scale_at(now));
+        }
+    }
+}
+
+/// Eases every cube towards its latest rotation, so 20 Hz readings look smooth at the frame rate.
+/// Tossed cubes are left to physics
+fn ease_cube(
+    mut cubes: Query<'_, '_, (&mut Transform, &Cube), Without<Tossed>>,
+    time: Res<'_, Time>,
+) {
+    for (mut transform, cube) in &mut cubes {
+        transform.rotation = cube.rotation_at(time.elapsed_secs());
+    }
 }
@@ -0,0 +1,123 @@
+//! Picking what the cube app is used for, from checking a controller to throwing the cube around
+
+use bevy::prelude::*;
+use spjorts_core::menu::{Menu, MenuSelected};
+
+/// Font size of the mode menu's options
+const MENU_FONT_SIZE: f32 = 36.0;
+/// Color of the highlighted mode
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+
+/// What the cube app is being used for
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CubeMode {
+    /// Live readouts and recentering for checking a controller
+    #[default]
+    Diagnostics,
+    /// A floor and props to toss the cube at, for checking swings turn into sensible impulses
+    Playground,
+    /// How long readings take to get from the controller to the cube
+    Latency,
+    /// Live gesture detection with adjustable thresholds
+    Gestures,
+}
+
+impl CubeMode {
+    /// Every mode, in menu order
+    const ALL: [Self; 4] = [
+        Self::Diagnostics,
+        Self::Playground,
+        Self::Latency,
+        Self::Gestures,
+    ];
+
+    /// Name shown in the mode menu
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Diagnostics => "Controller Diagnostics",
+            Self::Playground => "Physics Playground",
+            Self::Latency => "Latency Meter",
+            Self::Gestures => "Gesture Trainer",
+        }
+    }
+}
+
+/// Marks the mode menu
+#[derive(Component)]
+struct ModeMenu;
+
+/// An entry in the mode menu
+#[derive(Component)]
+struct ModeOption(usize);
+
+/// Plugin that adds the mode menu
+pub struct ModePlugin;
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CubeMode>()
+            .add_systems(Startup, spawn_mode_menu)
+            .add_systems(Update, (highlight_mode_menu, choose_mode));
+    }
+}
+
+/// Spawns the mode menu, focused so it is the first thing testers see
+fn spawn_mode_menu(mut commands: Commands<'_, '_>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Menu::new(CubeMode::ALL.len()),
+            ModeMenu,
+        ))
+        .with_children(|menu| {
+            for (idx, mode) in CubeMode::ALL.iter().enumerate() {
+                menu.spawn((
+                    Text::new(mode.name()),
+                    TextFont::from_font_size(MENU_FONT_SIZE),
+                    TextColor::WHITE,
+                    ModeOption(idx),
+                ));
+            }
+        });
+}
+
+/// Colors the highlighted mode
+fn highlight_mode_menu(
+    menus: Query<'_, '_, &Menu, (With<ModeMenu>, Changed<Menu>)>,
+    mut options: Query<'_, '_, (&ModeOption, &mut TextColor)>,
+) {
+    for menu in &menus {
+        for (option, mut color) in &mut options {
+            color.0 = if option.0 == menu.selected {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+/// Sets the mode once one is picked and closes the mode menu
+fn choose_mode(
+    mut commands: Commands<'_, '_>,
+    mut selections: EventReader<'_, '_, MenuSelected>,
+    menus: Query<'_, '_, (), With<ModeMenu>>,
+    mut mode: ResMut<'_, CubeMode>,
+) {
+    for selection in selections.read() {
+        if menus.get(selection.menu).is_ok() {
+            *mode = CubeMode::ALL[selection.index];
+            commands.entity(selection.menu).despawn_recursive();
+        }
+    }
+}
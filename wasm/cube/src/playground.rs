@@ -0,0 +1,275 @@
+//! A small physics sandbox for checking that swings turn into sensible impulses: A tosses a
+//! player's cube with the velocity of their last swing, B puts it and the props back
+
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    Collider, ColliderMassProperties, Friction, GravityScale, Restitution, RigidBody, Velocity,
+};
+use spjorts_core::physics::{BodyTuning, PhysicsTuning};
+
+use crate::{mode::CubeMode, ControllerAction, ControllerInput, Cube};
+
+/// How far back the swing is measured over when tossing, in seconds
+const SWING_WINDOW_SECS: f32 = 0.15;
+/// Metres per second of toss speed for every radian per second of swing
+const TOSS_SCALE: f32 = 1.5;
+/// Fastest the cube can be tossed, in metres per second
+const MAX_TOSS_SPEED: f32 = 20.0;
+/// How much the toss is angled up from where the cube faces
+const TOSS_LIFT: f32 = 0.5;
+/// Height of the floor's top surface
+const FLOOR_Y: f32 = -1.5;
+/// Half the width and depth of the floor
+const FLOOR_HALF_SIZE: f32 = 12.0;
+/// Half the size of a prop
+const PROP_HALF_SIZE: f32 = 0.4;
+
+/// Rotations read over the last [`SWING_WINDOW_SECS`], to toss a cube with
+#[derive(Debug, Default)]
+struct Swing {
+    /// Rotations with the seconds since startup they were read at, oldest first
+    samples: VecDeque<(Quat, f32)>,
+}
+
+impl Swing {
+    /// Records a rotation, dropping those that have fallen out of the window but always keeping
+    /// the one before the newest
+    fn record(&mut self, rotation: Quat, at: f32) {
+        self.samples.push_back((rotation, at));
+        while self.samples.len() > 2
+            && self
+                .samples
+                .front()
+                .is_some_and(|(_, read_at)| at - read_at > SWING_WINDOW_SECS)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Angular velocity across the window in radians per second, as an axis scaled by the rate
+    fn angular_velocity(&self) -> Vec3 {
+        let (Some((first, first_at)), Some((last, last_at))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return Vec3::ZERO;
+        };
+        let span = last_at - first_at;
+        if span <= f32::EPSILON {
+            return Vec3::ZERO;
+        }
+
+        let (axis, angle) = (*last * first.inverse()).to_axis_angle();
+        axis * (angle / span)
+    }
+}
+
+/// Each player's recent swing
+#[derive(Resource, Debug, Default)]
+struct Swings(HashMap<usize, Swing>);
+
+/// A prop the cube can be tossed at
+#[derive(Component)]
+struct Prop {
+    /// Where the prop starts and is put back to
+    home: Transform,
+}
+
+/// Marks a cube that has been tossed and is left to physics until it's reset
+#[derive(Component)]
+pub struct Tossed;
+
+/// Plugin that adds the physics playground
+pub struct PlaygroundPlugin;
+
+impl Plugin for PlaygroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Swings>().add_systems(
+            Update,
+            (
+                spawn_playground.run_if(resource_changed::<CubeMode>),
+                hold_new_cubes.run_if(resource_equals(CubeMode::Playground)),
+                (record_swing, toss_or_reset)
+                    .chain()
+                    .after(crate::read_input)
+                    .run_if(resource_equals(CubeMode::Playground)),
+            ),
+        );
+    }
+}
+
+/// Rapier components for a body with the given tuning
+fn tuned(tuning: BodyTuning) -> (Friction, Restitution, GravityScale, ColliderMassProperties) {
+    (
+        Friction::coefficient(tuning.friction),
+        Restitution::coefficient(tuning.restitution),
+        GravityScale(tuning.gravity_scale),
+        ColliderMassProperties::Density(tuning.density),
+    )
+}
+
+/// Lays out the floor and props and makes the cube a held physics body once the playground is
+/// picked
+fn spawn_playground(
+    mut commands: Commands<'_, '_>,
+    mode: Res<'_, CubeMode>,
+    cubes: Query<'_, '_, Entity, With<Cube>>,
+    physics: Res<'_, PhysicsTuning>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    if *mode != CubeMode::Playground {
+        return;
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            FLOOR_HALF_SIZE * 2.0,
+            0.2,
+            FLOOR_HALF_SIZE * 2.0,
+        ))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.35, 0.3))),
+        Transform::from_xyz(0.0, FLOOR_Y - 0.1, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(FLOOR_HALF_SIZE, 0.1, FLOOR_HALF_SIZE),
+        tuned(physics.ground),
+    ));
+
+    let prop_mesh = meshes.add(Cuboid::from_length(PROP_HALF_SIZE * 2.0));
+    let prop_material = materials.add(Color::srgb(0.8, 0.4, 0.2));
+    for x in [-3.0, 0.0, 3.0] {
+        for level in 0..2 {
+            let home = Transform::from_xyz(
+                x,
+                FLOOR_Y + PROP_HALF_SIZE * (1.0 + 2.0 * level as f32),
+                -5.0,
+            );
+            commands.spawn((
+                Mesh3d(prop_mesh.clone()),
+                MeshMaterial3d(prop_material.clone()),
+                home,
+                RigidBody::Dynamic,
+                Collider::cuboid(PROP_HALF_SIZE, PROP_HALF_SIZE, PROP_HALF_SIZE),
+                Velocity::zero(),
+                tuned(physics.target),
+                Prop { home },
+            ));
+        }
+    }
+
+    for cube in &cubes {
+        commands.entity(cube).insert(held(&physics));
+    }
+}
+
+/// Rapier components for a cube held by its controller until it's tossed
+fn held(
+    physics: &PhysicsTuning,
+) -> (
+    RigidBody,
+    Collider,
+    Velocity,
+    (Friction, Restitution, GravityScale, ColliderMassProperties),
+) {
+    (
+        RigidBody::KinematicPositionBased,
+        Collider::cuboid(0.5, 0.5, 0.5),
+        Velocity::zero(),
+        tuned(physics.projectile),
+    )
+}
+
+/// Makes cubes for controllers paired after the playground was picked held physics bodies too
+fn hold_new_cubes(
+    mut commands: Commands<'_, '_>,
+    cubes: Query<'_, '_, Entity, Added<Cube>>,
+    physics: Res<'_, PhysicsTuning>,
+) {
+    for cube in &cubes {
+        commands.entity(cube).insert(held(&physics));
+    }
+}
+
+/// Keeps the last few rotations of each player's controller to toss their cube with
+fn record_swing(
+    mut input: EventReader<'_, '_, ControllerInput>,
+    mut swings: ResMut<'_, Swings>,
+    time: Res<'_, Time>,
+) {
+    for msg in input.read() {
+        if let ControllerAction::Rotate { orientation, .. } = msg.action {
+            swings
+                .0
+                .entry(msg.player)
+                .or_default()
+                .record(orientation.to_quat(), time.elapsed_secs());
+        }
+    }
+}
+
+/// Tosses a player's held cube on A with their last swing's velocity, and puts their cube and
+/// the props back on B
+fn toss_or_reset(
+    mut commands: Commands<'_, '_>,
+    mut input: EventReader<'_, '_, ControllerInput>,
+    swings: Res<'_, Swings>,
+    mut cubes: Query<
+        '_,
+        '_,
+        (
+            Entity,
+            &Cube,
+            &mut Transform,
+            &mut RigidBody,
+            &mut Velocity,
+            Has<Tossed>,
+        ),
+        Without<Prop>,
+    >,
+    mut props: Query<'_, '_, (&Prop, &mut Transform, &mut Velocity), Without<Cube>>,
+) {
+    for msg in input.read() {
+        let own_cubes = cubes
+            .iter_mut()
+            .filter(|(_, cube, ..)| cube.player == msg.player);
+        match msg.action {
+            ControllerAction::ButtonA => {
+                for (entity, _, transform, mut rigid, mut velocity, tossed) in own_cubes {
+                    if tossed {
+                        continue;
+                    }
+
+                    let angvel = swings
+                        .0
+                        .get(&msg.player)
+                        .map_or(Vec3::ZERO, Swing::angular_velocity);
+                    let speed = (angvel.length() * TOSS_SCALE).min(MAX_TOSS_SPEED);
+                    let direction = (transform.rotation * Vec3::NEG_Z + Vec3::Y * TOSS_LIFT)
+                        .normalize_or_zero();
+                    *rigid = RigidBody::Dynamic;
+                    *velocity = Velocity {
+                        linvel: direction * speed,
+                        angvel,
+                    };
+                    commands.entity(entity).insert(Tossed);
+                }
+            }
+            ControllerAction::ButtonB => {
+                for (entity, cube, mut transform, mut rigid, mut velocity, _) in own_cubes {
+                    transform.translation = cube.home;
+                    *rigid = RigidBody::KinematicPositionBased;
+                    *velocity = Velocity::zero();
+                    commands.entity(entity).remove::<Tossed>();
+                }
+                for (prop, mut transform, mut velocity) in &mut props {
+                    *transform = prop.home;
+                    *velocity = Velocity::zero();
+                }
+            }
+            ControllerAction::ReleaseA
+            | ControllerAction::ReleaseB
+            | ControllerAction::Rotate { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Fading trails behind the ends of each cube's local axes, so drift and jitter show up as a
+//! smeared path instead of numbers to stare at. Shown while diagnosing a controller
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{mode::CubeMode, Cube};
+
+/// How long a point stays on the trail, in seconds
+const TRAIL_SECS: f32 = 3.0;
+/// How far from the cube's centre the traced axis ends are
+const AXIS_LENGTH: f32 = 0.9;
+/// Colors of the X, Y and Z axis trails
+const AXIS_COLORS: [Color; 3] = [
+    Color::srgb(0.95, 0.3, 0.3),
+    Color::srgb(0.3, 0.9, 0.4),
+    Color::srgb(0.3, 0.55, 1.0),
+];
+
+/// Where the ends of a cube's local axes have been, oldest first
+#[derive(Component, Debug, Default)]
+pub struct Trail {
+    /// The X, Y and Z axis ends with the seconds since startup they were there at
+    points: VecDeque<(f32, [Vec3; 3])>,
+}
+
+impl Trail {
+    /// Records where the axis ends are for a transform, dropping points older than
+    /// [`TRAIL_SECS`]
+    fn record(&mut self, transform: &Transform, at: f32) {
+        let ends = [Vec3::X, Vec3::Y, Vec3::Z]
+            .map(|axis| transform.translation + transform.rotation * axis * AXIS_LENGTH);
+        self.points.push_back((at, ends));
+        while self
+            .points
+            .front()
+            .is_some_and(|(recorded_at, _)| at - recorded_at > TRAIL_SECS)
+        {
+            self.points.pop_front();
+        }
+    }
+}
+
+/// Plugin that adds the rotation trails
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (record_trails, draw_trails)
+                .chain()
+                .after(crate::ease_cube)
+                .run_if(resource_equals(CubeMode::Diagnostics)),
+        );
+    }
+}
+
+/// Records where every cube's axis ends are this frame, giving new cubes a trail
+fn record_trails(
+    mut commands: Commands<'_, '_>,
+    mut cubes: Query<'_, '_, (Entity, &Transform, Option<&mut Trail>), With<Cube>>,
+    time: Res<'_, Time>,
+) {
+    for (entity, transform, trail) in &mut cubes {
+        match trail {
+            Some(mut trail) => trail.record(transform, time.elapsed_secs()),
+            None => {
+                commands.entity(entity).insert(Trail::default());
+            }
+        }
+    }
+}
+
+/// Draws each axis end's trail, fading out towards its oldest point
+fn draw_trails(mut gizmos: Gizmos<'_, '_>, trails: Query<'_, '_, &Trail>, time: Res<'_, Time>) {
+    let now = time.elapsed_secs();
+    for trail in &trails {
+        for (axis, color) in AXIS_COLORS.into_iter().enumerate() {
+            gizmos.linestrip_gradient(trail.points.iter().map(|(at, ends)| {
+                let fade = 1.0 - (now - at) / TRAIL_SECS;
+                (ends[axis], color.with_alpha(fade.clamp(0.0, 1.0)))
+            }));
+        }
+    }
+}
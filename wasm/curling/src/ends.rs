@@ -0,0 +1,361 @@
+//! Ends of a match: waiting for every stone to settle, handing the hack to the next player,
+//! scoring the house once every stone is thrown, all shown on the shared scorecard HUD
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    menu::MenuAction,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::CurlingPhase,
+    scoreboard::{house_score, EndCount, Scoreboard},
+    sheet::Stone,
+    Delivery,
+};
+
+/// Speed below which a stone counts as still, in meters per second
+const REST_SPEED: f32 = 0.05;
+/// How long every stone has to stay still before the throw is over, in seconds
+const SETTLE_SECS: f32 = 0.6;
+/// How long the end's score stays up before the house is cleared, in seconds
+const END_PAUSE_SECS: f32 = 3.0;
+
+/// Asks for the match to be started over from the first end
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts how long every stone has been still
+#[derive(Resource, Debug)]
+struct Settling(Timer);
+
+impl Default for Settling {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SETTLE_SECS, TimerMode::Once))
+    }
+}
+
+/// Counts down before the house is cleared for the next end
+#[derive(Resource, Debug)]
+struct EndPause(Timer);
+
+impl Default for EndPause {
+    fn default() -> Self {
+        Self(Timer::from_seconds(END_PAUSE_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct CurlingSnapshot<'a> {
+    /// Player in the hack
+    player: usize,
+    /// How many ends the match is played over
+    ends: usize,
+    /// Every finished end's score and the stones thrown this end
+    scoreboard: &'a Scoreboard,
+    /// Where the match is at
+    phase: CurlingPhase,
+}
+
+/// Plugin that runs each end and shows the score on the scorecard HUD
+pub struct EndsPlugin;
+
+impl Plugin for EndsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<EndCount>()
+            .init_resource::<Settling>()
+            .init_resource::<EndPause>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    pick_end_count,
+                    finish_throw.run_if(in_state(CurlingPhase::Sliding)),
+                    next_end.run_if(in_state(CurlingPhase::EndOver)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(CurlingPhase::Sliding), reset_settling)
+            .add_systems(OnEnter(CurlingPhase::EndOver), reset_end_pause)
+            .add_systems(
+                OnEnter(CurlingPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(CurlingPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh match whenever the number of players changes
+fn fit_match(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Lets players change how many ends to play with the menu buttons before the first stone
+fn pick_end_count(
+    mut actions: EventReader<'_, '_, MenuAction>,
+    mut end_count: ResMut<'_, EndCount>,
+    mut banner: ResMut<'_, Banner>,
+    scoreboard: Res<'_, Scoreboard>,
+) {
+    for action in actions.read() {
+        if scoreboard.has_started() {
+            continue;
+        }
+        *end_count = match action {
+            MenuAction::Up => end_count.more(),
+            MenuAction::Down => end_count.fewer(),
+            MenuAction::Select | MenuAction::Back => continue,
+        };
+        banner.show(format!("Ends: {}", end_count.0));
+    }
+}
+
+/// Everything the end of a throw changes besides the stones themselves
+#[derive(SystemParam)]
+struct ThrowEffects<'w> {
+    /// The match score
+    scoreboard: ResMut<'w, Scoreboard>,
+    /// Whose throw is next
+    turns: ResMut<'w, TurnManager>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the match goes next
+    next_phase: ResMut<'w, NextState<CurlingPhase>>,
+}
+
+/// Once every stone has stopped, takes stones that came up short of the hog line out of play
+/// and hands the hack to the next player, or scores the house if every stone has been thrown
+fn finish_throw(
+    mut commands: Commands<'_, '_>,
+    stones: Query<'_, '_, (Entity, &Transform, &Velocity, &Stone)>,
+    mut settling: ResMut<'_, Settling>,
+    mut effects: ThrowEffects<'_>,
+    time: Res<'_, Time>,
+) {
+    if stones
+        .iter()
+        .any(|(_, _, velocity, _)| velocity.linvel.length() > REST_SPEED)
+    {
+        settling.0.reset();
+        return;
+    }
+    if !settling.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut in_play = Vec::new();
+    for (entity, transform, _, stone) in &stones {
+        if Stone::past_hog_line(transform.translation) {
+            in_play.push((stone.owner, transform.translation));
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let ThrowEffects {
+        scoreboard,
+        turns,
+        banner,
+        next_phase,
+    } = &mut effects;
+    scoreboard.record_throw(turns.current());
+    if turns
+        .advance_until(|player| scoreboard.stones_left(player) == 0)
+        .is_some()
+    {
+        next_phase.set(CurlingPhase::Aiming);
+        return;
+    }
+
+    let score = house_score(&in_play);
+    match score {
+        Some((scorer, points)) => banner.show(format!(
+            "Player {} scores {points} {}",
+            scorer + 1,
+            if points == 1 { "stone" } else { "stones" }
+        )),
+        None => banner.show("Blank end"),
+    }
+    scoreboard.finish_end(score);
+    next_phase.set(CurlingPhase::EndOver);
+}
+
+/// Starts waiting for every stone to settle after a delivery
+fn reset_settling(mut settling: ResMut<'_, Settling>) {
+    settling.0.reset();
+}
+
+/// Keeps the end's score up for a moment before clearing the house
+fn reset_end_pause(mut pause: ResMut<'_, EndPause>) {
+    pause.0.reset();
+}
+
+/// Clears the house and starts the next end with the last end's scorer throwing first, or
+/// finishes the match once every end has been played
+fn next_end(
+    mut commands: Commands<'_, '_>,
+    mut pause: ResMut<'_, EndPause>,
+    mut effects: ThrowEffects<'_>,
+    stones: Query<'_, '_, Entity, With<Stone>>,
+    end_count: Res<'_, EndCount>,
+    time: Res<'_, Time>,
+) {
+    if !pause.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for stone in &stones {
+        commands.entity(stone).despawn_recursive();
+    }
+    if effects.scoreboard.ends_played() >= end_count.0 {
+        effects.next_phase.set(CurlingPhase::GameOver);
+        return;
+    }
+    match effects.scoreboard.last_scorer() {
+        Some(scorer) => {
+            effects.turns.advance_until(|player| player != scorer);
+        }
+        None => {
+            effects.turns.advance();
+        }
+    }
+    effects.next_phase.set(CurlingPhase::Aiming);
+}
+
+/// Starts the match over from the first player's first stone
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut next_phase: ResMut<'_, NextState<CurlingPhase>>,
+    stones: Query<'_, '_, Entity, With<Stone>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for stone in &stones {
+        commands.entity(stone).despawn_recursive();
+    }
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    next_phase.set(CurlingPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every player's ends and total, the stones left to throw,
+/// the handle being put on and whether the stone is being swept
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    end_count: Res<'_, EndCount>,
+    turns: Res<'_, TurnManager>,
+    delivery: Res<'_, Delivery>,
+    time: Res<'_, Time>,
+) {
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            let ends: Vec<String> = (0..end_count.0)
+                .map(|end| {
+                    scoreboard
+                        .end(player, end)
+                        .map_or("-".to_string(), |points| points.to_string())
+                })
+                .collect();
+            format!(
+                "Player {}: {} ({})",
+                player + 1,
+                scoreboard.total(player),
+                ends.join(" ")
+            )
+        })
+        .collect();
+
+    let mut footer = vec![
+        format!("Stones left: {}", scoreboard.stones_left(turns.current())),
+        format!("Handle: {}", delivery.handle_name()),
+    ];
+    if delivery.is_sweeping(time.elapsed_secs()) {
+        footer.push("Sweeping!".to_string());
+    }
+    if !scoreboard.has_started() {
+        footer.push("Menu up/down to change ends".to_string());
+    }
+
+    let end = (scoreboard.ends_played() + 1).min(end_count.0);
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Curling, end {end} of {}", end_count.0),
+        rows,
+        footer: footer.join("\n"),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's total once the match is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scoreboard: Res<'_, Scoreboard>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    let leaders = scoreboard.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if scoreboard.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..scoreboard.players() {
+        lines.push(format!(
+            "Player {}: {}",
+            player + 1,
+            scoreboard.total(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new match starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's total back to the page once the match is over, so it can submit them
+/// to the server
+fn submit_result(scoreboard: Res<'_, Scoreboard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scoreboard.players())
+        .map(|player| scoreboard.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    end_count: Res<'_, EndCount>,
+    phase: Res<'_, State<CurlingPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&CurlingSnapshot {
+        player: turns.current(),
+        ends: end_count.0,
+        scoreboard: &scoreboard,
+        phase: *phase.get(),
+    });
+}
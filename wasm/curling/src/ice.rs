@@ -0,0 +1,87 @@
+//! How stones slide: the ice slows them down, the handle's turn curls them, and sweeping ahead of
+//! a stone lets it run further and straighter
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use spjorts_core::spectator::is_playing;
+
+use crate::{phase::CurlingPhase, sheet::Stone, Delivery};
+
+/// How quickly the ice slows a stone down, in meters per second per second
+const ICE_DECEL: f32 = 0.7;
+/// How much of the ice's drag is left ahead of a swept stone
+const SWEPT_DECEL: f32 = 0.75;
+/// How hard a fully turned handle curls a stone, in meters per second per second at a crawl
+const CURL_ACCEL: f32 = 0.1;
+/// How much of the curl is left on a swept stone
+const SWEPT_CURL: f32 = 0.6;
+/// Speed below which a stone stops dead, in meters per second
+const STOP_SPEED: f32 = 0.02;
+/// How fast a fully turned handle spins the stone, in radians per second
+const SPIN_RATE: f32 = 1.5;
+
+/// Steps a stone's velocity along by `secs` of sliding. A stone curls the way its handle was
+/// turned, more so as it slows down, and both the drag and the curl ease off while it's swept
+pub fn glide(velocity: Vec3, spin: f32, swept: bool, secs: f32) -> Vec3 {
+    let speed = velocity.length();
+    if speed < STOP_SPEED {
+        return Vec3::ZERO;
+    }
+    let (decel, curl) = if swept {
+        (ICE_DECEL * SWEPT_DECEL, CURL_ACCEL * SWEPT_CURL)
+    } else {
+        (ICE_DECEL, CURL_ACCEL)
+    };
+
+    let heading = velocity / speed;
+    // Clockwise stones drift to the right of the way they're going
+    let right = heading.cross(Vec3::Y);
+    let slowed = (speed - decel * secs).max(0.0);
+    heading * slowed + right * curl * spin / (1.0 + speed) * secs
+}
+
+/// Plugin that slides stones across the ice and takes stones out of play
+pub struct IcePlugin;
+
+impl Plugin for IcePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (slide_stones, remove_out_of_bounds)
+                .chain()
+                .run_if(in_state(CurlingPhase::Sliding))
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Slows and curls every moving stone, sweeping ahead of the delivered one while its thrower
+/// sweeps
+fn slide_stones(
+    mut stones: Query<'_, '_, (Entity, &mut Velocity, &Stone)>,
+    delivery: Res<'_, Delivery>,
+    time: Res<'_, Time>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, mut velocity, stone) in &mut stones {
+        let swept = delivery.stone == Some(entity) && delivery.is_sweeping(now);
+        velocity.linvel = glide(velocity.linvel, stone.spin, swept, time.delta_secs());
+        velocity.angvel = if velocity.linvel == Vec3::ZERO {
+            Vec3::ZERO
+        } else {
+            Vec3::Y * -stone.spin * SPIN_RATE
+        };
+    }
+}
+
+/// Takes stones that run out the back of the house or into the side boards off the sheet
+fn remove_out_of_bounds(
+    mut commands: Commands<'_, '_>,
+    stones: Query<'_, '_, (Entity, &Transform), With<Stone>>,
+) {
+    for (entity, transform) in &stones {
+        if Stone::out_of_bounds(transform.translation) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
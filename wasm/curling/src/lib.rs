@@ -0,0 +1,279 @@
+//! Bevy curling game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::Velocity,
+};
+use ends::{EndsPlugin, NewGame};
+use ice::IcePlugin;
+use phase::{CurlingPhase, CurlingPhasePlugin};
+use sheet::{SheetPlugin, Stone, StoneModel, DELIVERY_Z, TEE};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod ends;
+pub mod ice;
+pub mod phase;
+pub mod scoreboard;
+pub mod sheet;
+
+/// How far a radian of controller yaw turns the line of delivery, in radians
+const AIM_SCALE: f32 = 0.1;
+/// Widest the line of delivery can be turned from the centre line, in radians
+const MAX_AIM: f32 = 0.08;
+/// How far the controller has to be rolled to put a full turn on the handle, in radians
+const FULL_TURN_ROLL: f32 = 0.8;
+/// How fast the controller has to swing forward to throw the heaviest weight, in radians per
+/// second
+const FULL_SWING_SPEED: f32 = 12.0;
+/// Speed the lightest delivery leaves the hack at, in meters per second
+const MIN_WEIGHT: f32 = 5.0;
+/// Speed the heaviest delivery leaves the hack at, in meters per second
+const MAX_WEIGHT: f32 = 9.0;
+/// How fast the controller has to be shaken to sweep, in radians per second
+const SWEEP_SPEED: f32 = 4.0;
+/// How long a single shake keeps the brooms going, in seconds
+const SWEEP_SECS: f32 = 0.3;
+/// How far behind a sliding stone the camera follows
+const CAMERA_BACK: f32 = 5.0;
+/// How far above the ice the camera sits
+const CAMERA_UP: f32 = 2.0;
+/// How quickly the camera catches up with the stone, higher is snappier
+const CAMERA_SMOOTHING: f32 = 3.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(CurlingPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(SheetPlugin)
+    .add_plugins(IcePlugin)
+    .add_plugins(EndsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.2, 0.22, 0.28)))
+    .init_resource::<Delivery>()
+    .add_event::<Deliver>()
+    .add_systems(OnEnter(CurlingPhase::Aiming), ready_delivery)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            deliver_stone.run_if(in_state(CurlingPhase::Aiming)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (follow_stone, draw_aim_guide));
+});
+
+/// The player in the hack's line, handle and brooms
+#[derive(Resource, Debug, Default)]
+pub struct Delivery {
+    /// Line of delivery, in radians from the centre line. Positive turns left
+    pub aim: f32,
+    /// Turn on the handle, from -1 for counter-clockwise to 1 for clockwise
+    pub spin: f32,
+    /// The stone that was delivered last, while it's sliding
+    pub stone: Option<Entity>,
+    /// Watches the controller for the forward swing that throws and the shaking that sweeps
+    detector: GestureDetector,
+    /// Whether B is held down to sweep
+    broom_down: bool,
+    /// Seconds since startup the controller was last shaken hard enough to sweep
+    last_shake: Option<f32>,
+}
+
+impl Delivery {
+    /// Whether the brooms are going ahead of the delivered stone
+    pub fn is_sweeping(&self, now: f32) -> bool {
+        self.broom_down && self.last_shake.is_some_and(|at| now - at <= SWEEP_SECS)
+    }
+
+    /// What the turn on the handle is called
+    pub fn handle_name(&self) -> &'static str {
+        if self.spin > 0.1 {
+            "Clockwise"
+        } else if self.spin < -0.1 {
+            "Counter-clockwise"
+        } else {
+            "None"
+        }
+    }
+}
+
+/// A stone pushed out of the hack
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Deliver {
+    /// Line of delivery, in radians from the centre line. Positive turns left
+    pub aim: f32,
+    /// Speed the stone leaves the hack at, in meters per second
+    pub weight: f32,
+    /// Turn on the handle, from -1 for counter-clockwise to 1 for clockwise
+    pub spin: f32,
+}
+
+impl Deliver {
+    /// Velocity the stone leaves the hack with
+    pub fn velocity(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Vec3::NEG_Z * self.weight
+    }
+}
+
+/// Forgets the last stone and lifts the brooms ready for the next delivery
+fn ready_delivery(mut delivery: ResMut<'_, Delivery>) {
+    delivery.stone = None;
+    delivery.broom_down = false;
+    delivery.last_shake = None;
+}
+
+/// Everything input handling changes besides the delivery itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Stones to deliver
+    deliveries: EventWriter<'w, Deliver>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw sets the line and roll the handle, and a forward swing throws.
+/// Once the stone is sliding, holding B and shaking the controller sweeps ahead of it. A starts
+/// a new match once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut delivery: ResMut<'_, Delivery>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<CurlingPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == CurlingPhase::Aiming;
+    let sliding = *phase.get() == CurlingPhase::Sliding;
+    let now = time.elapsed_secs();
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == CurlingPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonB if sliding => {
+                delivery.broom_down = true;
+            }
+            JsMessage::ReleaseB => {
+                delivery.broom_down = false;
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _)
+                if aiming || sliding =>
+            {
+                let orientation @ Orientation { roll, yaw, .. } =
+                    settings.apply_rotation(orientation);
+                let detected = delivery.detector.update(orientation, now);
+                if sliding {
+                    if delivery.detector.speed() >= SWEEP_SPEED {
+                        delivery.last_shake = Some(now);
+                    }
+                    continue;
+                }
+
+                delivery.aim = (yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                delivery.spin = (roll / FULL_TURN_ROLL).clamp(-1.0, 1.0);
+                if let Some(detected) = detected
+                    .filter(|detected| matches!(detected.gesture, Gesture::Swing | Gesture::Flick))
+                {
+                    let power = (detected.intensity / FULL_SWING_SPEED).clamp(0.0, 1.0);
+                    effects.deliveries.send(Deliver {
+                        aim: delivery.aim,
+                        weight: MIN_WEIGHT + (MAX_WEIGHT - MIN_WEIGHT) * power,
+                        spin: delivery.spin,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Sends a stone down the sheet for the player in the hack
+fn deliver_stone(
+    mut commands: Commands<'_, '_>,
+    mut deliveries: EventReader<'_, '_, Deliver>,
+    mut delivery: ResMut<'_, Delivery>,
+    mut next_phase: ResMut<'_, NextState<CurlingPhase>>,
+    model: Res<'_, StoneModel>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(deliver) = deliveries.read().last().copied() else {
+        return;
+    };
+
+    let stone = model.spawn(&mut commands, turns.current(), Vec3::Z * DELIVERY_Z);
+    commands.entity(stone).insert((
+        Velocity::linear(deliver.velocity()),
+        Stone {
+            owner: turns.current(),
+            spin: deliver.spin,
+        },
+    ));
+    delivery.stone = Some(stone);
+    next_phase.set(CurlingPhase::Sliding);
+}
+
+/// Follows the delivered stone down the sheet, settling back behind the hack otherwise
+fn follow_stone(
+    mut camera: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Stone>)>,
+    stones: Query<'_, '_, &Transform, With<Stone>>,
+    delivery: Res<'_, Delivery>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let behind = delivery
+        .stone
+        .and_then(|stone| stones.get(stone).ok())
+        .map_or(DELIVERY_Z + 4.0, |stone| {
+            (stone.translation.z + CAMERA_BACK).min(DELIVERY_Z + 4.0)
+        });
+
+    let goal = Vec3::new(0.0, CAMERA_UP, behind);
+    let blend = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(TEE, Vec3::Y);
+}
+
+/// Draws the line of delivery down the sheet to the house while aiming, when the aim guide is on
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    delivery: Res<'_, Delivery>,
+    phase: Res<'_, State<CurlingPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() != CurlingPhase::Aiming {
+        return;
+    }
+    let start = Vec3::new(0.0, 0.01, DELIVERY_Z);
+    let direction = Quat::from_rotation_y(delivery.aim) * Vec3::NEG_Z;
+    gizmos.line(
+        start,
+        start + direction * DELIVERY_Z,
+        Color::srgb(0.9, 0.2, 0.2),
+    );
+}
@@ -0,0 +1,27 @@
+//! Phases a curling match moves through, from lining up a stone to the final score
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the match is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CurlingPhase {
+    /// The player in the hack is lining up their next stone
+    #[default]
+    Aiming,
+    /// Stones are sliding down the sheet
+    Sliding,
+    /// Every stone of the end has been thrown and the end's score is up
+    EndOver,
+    /// Every end has been played and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the match is in
+pub struct CurlingPhasePlugin;
+
+impl Plugin for CurlingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CurlingPhase>();
+    }
+}
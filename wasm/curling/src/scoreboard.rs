@@ -0,0 +1,152 @@
+//! House scoring: at the end of every end, whoever has the stone nearest the button scores one
+//! for each of their stones closer than anyone else's best
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::sheet::{Stone, TEE};
+
+/// Stones each player throws every end
+pub const STONES_PER_PLAYER: usize = 4;
+/// Fewest ends a match can be set to
+const MIN_ENDS: usize = 1;
+/// Most ends a match can be set to
+const MAX_ENDS: usize = 10;
+
+/// How many ends the match is played over, picked before the first stone
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndCount(pub usize);
+
+impl Default for EndCount {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
+impl EndCount {
+    /// One more end, up to the most a match can have
+    pub fn more(self) -> Self {
+        Self((self.0 + 1).min(MAX_ENDS))
+    }
+
+    /// One fewer end, down to the fewest a match can have
+    pub fn fewer(self) -> Self {
+        Self(self.0.saturating_sub(1).max(MIN_ENDS))
+    }
+}
+
+/// Works out an end's score from where every stone left in play lies, as the player who scores
+/// and how many. `None` is a blank end, with nothing in the house
+pub fn house_score(stones: &[(usize, Vec3)]) -> Option<(usize, u32)> {
+    let mut counting: Vec<(usize, f32)> = stones
+        .iter()
+        .filter(|(_, at)| Stone::in_house(*at))
+        .map(|(owner, at)| (*owner, (*at - TEE).with_y(0.0).length()))
+        .collect();
+    counting.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let (scorer, _) = *counting.first()?;
+    let points = counting
+        .iter()
+        .take_while(|(owner, _)| *owner == scorer)
+        .count();
+    Some((scorer, points as u32))
+}
+
+/// Every end's score and the stones thrown in the end being played
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    /// Points each player scored in every finished end, in turn order
+    ends: Vec<Vec<u32>>,
+    /// Stones each player has thrown so far in the end being played
+    thrown: Vec<usize>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a match for a number of players with nothing thrown
+    pub fn new(players: usize) -> Self {
+        Self {
+            ends: Vec::new(),
+            thrown: vec![0; players.max(1)],
+        }
+    }
+
+    /// How many players are in the match
+    pub fn players(&self) -> usize {
+        self.thrown.len()
+    }
+
+    /// How many ends have been finished
+    pub fn ends_played(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Whether the match has got underway, after which the number of ends is fixed
+    pub fn has_started(&self) -> bool {
+        self.thrown.iter().any(|thrown| *thrown > 0) || !self.ends.is_empty()
+    }
+
+    /// Counts a stone a player has thrown this end
+    pub fn record_throw(&mut self, player: usize) {
+        if let Some(thrown) = self.thrown.get_mut(player) {
+            *thrown += 1;
+        }
+    }
+
+    /// Stones a player has left to throw in the end being played
+    pub fn stones_left(&self, player: usize) -> usize {
+        self.thrown
+            .get(player)
+            .map_or(0, |thrown| STONES_PER_PLAYER.saturating_sub(*thrown))
+    }
+
+    /// Finishes the end with a score from [`house_score`], ready for the next
+    pub fn finish_end(&mut self, score: Option<(usize, u32)>) {
+        let mut points = vec![0; self.players()];
+        if let Some((scorer, scored)) = score {
+            if let Some(slot) = points.get_mut(scorer) {
+                *slot = scored;
+            }
+        }
+        self.ends.push(points);
+        self.thrown.fill(0);
+    }
+
+    /// The player who scored the last finished end, or `None` after a blank end
+    pub fn last_scorer(&self) -> Option<usize> {
+        self.ends.last()?.iter().position(|points| *points > 0)
+    }
+
+    /// Points a player scored in a finished end
+    pub fn end(&self, player: usize, end: usize) -> Option<u32> {
+        self.ends
+            .get(end)
+            .and_then(|points| points.get(player))
+            .copied()
+    }
+
+    /// A player's total across every finished end
+    pub fn total(&self, player: usize) -> u32 {
+        self.ends
+            .iter()
+            .filter_map(|points| points.get(player))
+            .sum()
+    }
+
+    /// The players on the highest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+}
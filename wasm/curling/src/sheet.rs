@@ -0,0 +1,198 @@
+//! The sheet: the ice, the house at the far end and the lines stones have to cross, plus the
+//! stones themselves
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    Collider, ColliderMassProperties, Friction, GravityScale, LockedAxes, Restitution, RigidBody,
+    Velocity,
+};
+
+/// Centre of the house, the button stones are drawn to
+pub const TEE: Vec3 = Vec3::ZERO;
+/// Radius of the house, out to the edge of the twelve foot ring
+pub const HOUSE_RADIUS: f32 = 1.829;
+/// How far in front of the tee the far hog line is. Stones have to reach it to stay in play
+pub const HOG_LINE: f32 = 6.401;
+/// How far behind the tee the back line is. Stones past it are out of play
+pub const BACK_LINE: f32 = 1.829;
+/// Where stones are released, on the near hog line
+pub const DELIVERY_Z: f32 = 28.35;
+/// Half the width of the sheet between the side boards
+pub const HALF_WIDTH: f32 = 2.375;
+/// Radius of a stone
+pub const STONE_RADIUS: f32 = 0.145;
+/// Height of a stone
+const STONE_HEIGHT: f32 = 0.115;
+/// Mass of a stone, in kilograms
+const STONE_MASS: f32 = 19.0;
+/// How far past the back line the ice runs
+const ICE_RUNOFF: f32 = 2.0;
+/// Width of the painted lines
+const LINE_WIDTH: f32 = 0.03;
+
+/// A stone, sliding or at rest on the sheet
+#[derive(Component, Debug)]
+pub struct Stone {
+    /// The player who threw it
+    pub owner: usize,
+    /// Which way the handle was turned on release, from -1 for counter-clockwise to 1 for
+    /// clockwise. Knocked stones lose it
+    pub spin: f32,
+}
+
+impl Stone {
+    /// Whether a stone at a spot has gone out the back of the house or into the side boards
+    pub fn out_of_bounds(position: Vec3) -> bool {
+        position.z < -BACK_LINE - STONE_RADIUS || position.x.abs() > HALF_WIDTH - STONE_RADIUS
+    }
+
+    /// Whether a stone at a spot is all the way past the far hog line, so it stays in play
+    /// once it stops
+    pub fn past_hog_line(position: Vec3) -> bool {
+        position.z < HOG_LINE - STONE_RADIUS
+    }
+
+    /// Whether a stone at a spot touches the house, so it can score
+    pub fn in_house(position: Vec3) -> bool {
+        (position - TEE).with_y(0.0).length() <= HOUSE_RADIUS + STONE_RADIUS
+    }
+}
+
+/// Meshes and materials every stone is built from
+#[derive(Resource)]
+pub struct StoneModel {
+    /// The granite body
+    body: Handle<Mesh>,
+    /// The handle on top
+    handle: Handle<Mesh>,
+    /// Material of the granite
+    granite: Handle<StandardMaterial>,
+    /// Each player's handle color, cycled through for more players than colors
+    colors: Vec<Handle<StandardMaterial>>,
+}
+
+impl StoneModel {
+    /// Spawns a player's stone at rest on the sheet
+    pub fn spawn(&self, commands: &mut Commands<'_, '_>, owner: usize, at: Vec3) -> Entity {
+        commands
+            .spawn((
+                Mesh3d(self.body.clone()),
+                MeshMaterial3d(self.granite.clone()),
+                Transform::from_translation(at.with_y(STONE_HEIGHT / 2.0)),
+                RigidBody::Dynamic,
+                Collider::cylinder(STONE_HEIGHT / 2.0, STONE_RADIUS),
+                ColliderMassProperties::Mass(STONE_MASS),
+                Friction::coefficient(0.0),
+                Restitution::coefficient(0.8),
+                GravityScale(0.0),
+                // Stones only slide and turn on the ice, the sliding itself is worked out in
+                // `ice`
+                LockedAxes::ROTATION_LOCKED_X
+                    | LockedAxes::ROTATION_LOCKED_Z
+                    | LockedAxes::TRANSLATION_LOCKED_Y,
+                Velocity::zero(),
+                Stone { owner, spin: 0.0 },
+            ))
+            .with_children(|stone| {
+                stone.spawn((
+                    Mesh3d(self.handle.clone()),
+                    MeshMaterial3d(self.colors[owner % self.colors.len()].clone()),
+                    Transform::from_xyz(0.0, STONE_HEIGHT / 2.0 + 0.02, 0.0),
+                ));
+            })
+            .id()
+    }
+}
+
+/// Plugin that lays out the sheet and gets the stone model ready
+pub struct SheetPlugin;
+
+impl Plugin for SheetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_sheet);
+    }
+}
+
+/// Spawns the ice, the house, the lines, the camera and the light, and builds the stone model
+fn setup_sheet(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let length = DELIVERY_Z + BACK_LINE + ICE_RUNOFF * 2.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(HALF_WIDTH * 2.0, length))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.92, 0.95, 1.0),
+            perceptual_roughness: 0.15,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 0.0, (DELIVERY_Z - BACK_LINE) / 2.0),
+        Name::new("Sheet"),
+    ));
+
+    // Rings of the house, outermost first, each painted just above the last
+    let rings = [
+        (HOUSE_RADIUS, Color::srgb(0.1, 0.3, 0.8)),
+        (1.219, Color::srgb(0.92, 0.95, 1.0)),
+        (0.610, Color::srgb(0.8, 0.1, 0.1)),
+        (0.152, Color::srgb(0.92, 0.95, 1.0)),
+    ];
+    for (layer, (radius, color)) in rings.into_iter().enumerate() {
+        commands.spawn((
+            Mesh3d(meshes.add(Circle::new(radius))),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(TEE + Vec3::Y * 0.001 * (layer + 1) as f32)
+                .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+        ));
+    }
+
+    let line = materials.add(Color::srgb(0.15, 0.15, 0.2));
+    let red = materials.add(Color::srgb(0.8, 0.1, 0.1));
+    let across = meshes.add(Plane3d::default().mesh().size(HALF_WIDTH * 2.0, LINE_WIDTH));
+    for (z, material) in [
+        (HOG_LINE, red.clone()),
+        (DELIVERY_Z, red),
+        (0.0, line.clone()),
+        (-BACK_LINE, line.clone()),
+    ] {
+        commands.spawn((
+            Mesh3d(across.clone()),
+            MeshMaterial3d(material),
+            Transform::from_xyz(0.0, 0.006, z),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(LINE_WIDTH, length))),
+        MeshMaterial3d(line),
+        Transform::from_xyz(0.0, 0.006, (DELIVERY_Z - BACK_LINE) / 2.0),
+    ));
+
+    commands.insert_resource(StoneModel {
+        body: meshes.add(Cylinder::new(STONE_RADIUS, STONE_HEIGHT)),
+        handle: meshes.add(Cuboid::new(0.05, 0.04, 0.16)),
+        granite: materials.add(Color::srgb(0.45, 0.45, 0.5)),
+        colors: [
+            Color::srgb(0.85, 0.15, 0.1),
+            Color::srgb(0.95, 0.8, 0.1),
+            Color::srgb(0.15, 0.45, 0.85),
+            Color::srgb(0.2, 0.7, 0.3),
+        ]
+        .into_iter()
+        .map(|color| materials.add(color))
+        .collect(),
+    });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.0, DELIVERY_Z + 4.0).looking_at(TEE, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 10.0, 10.0).looking_at(TEE, Vec3::Y),
+    ));
+}
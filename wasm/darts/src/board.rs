@@ -0,0 +1,281 @@
+//! The dartboard: its standard layout, the score of any spot on it, and the oche it's thrown at
+//! from
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, RigidBody};
+use serde::{Deserialize, Serialize};
+
+/// Centre of the bullseye, at regulation height on a wall facing the thrower
+pub const BOARD_CENTRE: Vec3 = Vec3::new(0.0, 1.73, 0.0);
+/// How far in front of the board the oche is
+pub const OCHE_DISTANCE: f32 = 2.37;
+/// Radius of the whole board, including the ring outside the doubles that scores nothing
+pub const BOARD_RADIUS: f32 = 0.2255;
+
+/// Numbers around the board, clockwise from the top
+const SECTORS: [u32; 20] = [
+    20, 1, 18, 4, 13, 6, 10, 15, 2, 17, 3, 19, 7, 16, 8, 11, 14, 9, 12, 5,
+];
+/// Angle each number's sector covers, in radians
+const SECTOR_ANGLE: f32 = TAU / SECTORS.len() as f32;
+/// Radius of the bullseye
+const BULLSEYE_RADIUS: f32 = 0.00635;
+/// Outer radius of the 25 ring around the bullseye
+const OUTER_BULL_RADIUS: f32 = 0.0159;
+/// Inner radius of the triple ring
+const TRIPLE_INNER_RADIUS: f32 = 0.099;
+/// Outer radius of the triple ring
+const TRIPLE_OUTER_RADIUS: f32 = 0.107;
+/// Inner radius of the double ring
+const DOUBLE_INNER_RADIUS: f32 = 0.162;
+/// Outer radius of the double ring, past which nothing scores
+const DOUBLE_OUTER_RADIUS: f32 = 0.170;
+/// How thick the board is
+const BOARD_THICKNESS: f32 = 0.04;
+
+/// Which ring of a numbered sector a dart landed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ring {
+    /// Either of the wide single areas
+    Single,
+    /// The outer thin ring, worth double
+    Double,
+    /// The inner thin ring, worth triple
+    Triple,
+}
+
+impl Ring {
+    /// How many times the sector's number this ring is worth
+    fn multiplier(&self) -> u32 {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+        }
+    }
+}
+
+/// Where a dart landed, as far as scoring goes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    /// Off the scoring area, or not on the board at all
+    Miss,
+    /// Somewhere in a numbered sector
+    Segment {
+        /// The sector's number
+        number: u32,
+        /// The ring within the sector
+        ring: Ring,
+    },
+    /// The ring around the bullseye, worth 25
+    OuterBull,
+    /// The bullseye, worth 50 and counting as a double
+    Bullseye,
+}
+
+impl Hit {
+    /// Scores a spot on the board, in meters from the bullseye with up being positive y
+    pub fn at(spot: Vec2) -> Self {
+        let radius = spot.length();
+        if radius <= BULLSEYE_RADIUS {
+            return Self::Bullseye;
+        }
+        if radius <= OUTER_BULL_RADIUS {
+            return Self::OuterBull;
+        }
+        if radius > DOUBLE_OUTER_RADIUS {
+            return Self::Miss;
+        }
+
+        // Clockwise from straight up, shifted by half a sector so the 20 is centred on the top
+        let angle = spot.x.atan2(spot.y).rem_euclid(TAU);
+        let sector = ((angle + SECTOR_ANGLE / 2.0) / SECTOR_ANGLE) as usize % SECTORS.len();
+        let ring = if radius >= DOUBLE_INNER_RADIUS {
+            Ring::Double
+        } else if (TRIPLE_INNER_RADIUS..=TRIPLE_OUTER_RADIUS).contains(&radius) {
+            Ring::Triple
+        } else {
+            Ring::Single
+        };
+
+        Self::Segment {
+            number: SECTORS[sector],
+            ring,
+        }
+    }
+
+    /// How many points the hit scores
+    pub fn points(&self) -> u32 {
+        match self {
+            Self::Miss => 0,
+            Self::Segment { number, ring } => number * ring.multiplier(),
+            Self::OuterBull => 25,
+            Self::Bullseye => 50,
+        }
+    }
+
+    /// Whether the hit counts as a double, so it can finish a leg
+    pub fn is_double(&self) -> bool {
+        matches!(
+            self,
+            Self::Bullseye
+                | Self::Segment {
+                    ring: Ring::Double,
+                    ..
+                }
+        )
+    }
+
+    /// Short label for the hit, like `T20`, `D16`, `5`, `25` or `Bull`
+    pub fn label(&self) -> String {
+        match self {
+            Self::Miss => "Miss".to_string(),
+            Self::Segment {
+                number,
+                ring: Ring::Single,
+            } => number.to_string(),
+            Self::Segment {
+                number,
+                ring: Ring::Double,
+            } => format!("D{number}"),
+            Self::Segment {
+                number,
+                ring: Ring::Triple,
+            } => format!("T{number}"),
+            Self::OuterBull => "25".to_string(),
+            Self::Bullseye => "Bull".to_string(),
+        }
+    }
+
+    /// The middle of the area the hit covers, in meters from the bullseye, for aiming at it
+    pub fn target(&self) -> Vec2 {
+        let (sector, radius) = match self {
+            Self::Miss | Self::Bullseye => return Vec2::ZERO,
+            Self::OuterBull => return Vec2::Y * (BULLSEYE_RADIUS + OUTER_BULL_RADIUS) / 2.0,
+            Self::Segment { number, ring } => {
+                let sector = SECTORS
+                    .iter()
+                    .position(|sector| sector == number)
+                    .unwrap_or_default();
+                let radius = match ring {
+                    Ring::Single => (TRIPLE_OUTER_RADIUS + DOUBLE_INNER_RADIUS) / 2.0,
+                    Ring::Double => (DOUBLE_INNER_RADIUS + DOUBLE_OUTER_RADIUS) / 2.0,
+                    Ring::Triple => (TRIPLE_INNER_RADIUS + TRIPLE_OUTER_RADIUS) / 2.0,
+                };
+                (sector, radius)
+            }
+        };
+        let angle = sector as f32 * SECTOR_ANGLE;
+        Vec2::new(angle.sin(), angle.cos()) * radius
+    }
+}
+
+/// Marks the board's collider, which darts stick into and get scored from
+#[derive(Component, Debug)]
+pub struct Dartboard;
+
+/// Plugin that builds the board, the wall it hangs on and the oche
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_board);
+    }
+}
+
+/// Spawns the board's painted areas, its collider, the wall, the floor and the lights
+fn setup_board(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let black = materials.add(Color::srgb(0.08, 0.08, 0.08));
+    let cream = materials.add(Color::srgb(0.93, 0.88, 0.75));
+    let red = materials.add(Color::srgb(0.8, 0.1, 0.1));
+    let green = materials.add(Color::srgb(0.1, 0.55, 0.2));
+
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Circle::new(BOARD_RADIUS))),
+            MeshMaterial3d(black.clone()),
+            Transform::from_translation(BOARD_CENTRE),
+            Name::new("Dartboard"),
+        ))
+        .with_children(|board| {
+            // Each layer is a smaller set of sectors painted just in front of the last, so every
+            // ring shows through where the next one stops
+            let layers = [
+                (DOUBLE_OUTER_RADIUS, [&red, &green]),
+                (DOUBLE_INNER_RADIUS, [&black, &cream]),
+                (TRIPLE_OUTER_RADIUS, [&red, &green]),
+                (TRIPLE_INNER_RADIUS, [&black, &cream]),
+            ];
+            for (layer, (radius, colors)) in layers.into_iter().enumerate() {
+                let sector_mesh = meshes.add(CircularSector::new(radius, SECTOR_ANGLE / 2.0));
+                for sector in 0..SECTORS.len() {
+                    board.spawn((
+                        Mesh3d(sector_mesh.clone()),
+                        MeshMaterial3d(colors[sector % 2].clone()),
+                        Transform::from_xyz(0.0, 0.0, 0.001 * (layer + 1) as f32)
+                            .with_rotation(Quat::from_rotation_z(-(sector as f32) * SECTOR_ANGLE)),
+                    ));
+                }
+            }
+
+            board.spawn((
+                Mesh3d(meshes.add(Circle::new(OUTER_BULL_RADIUS))),
+                MeshMaterial3d(green.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.005),
+            ));
+            board.spawn((
+                Mesh3d(meshes.add(Circle::new(BULLSEYE_RADIUS))),
+                MeshMaterial3d(red.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.006),
+            ));
+        });
+
+    commands.spawn((
+        Transform::from_translation(BOARD_CENTRE - Vec3::Z * BOARD_THICKNESS / 2.0)
+            .with_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+        RigidBody::Fixed,
+        Collider::cylinder(BOARD_THICKNESS / 2.0, BOARD_RADIUS),
+        Dartboard,
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(4.0, 3.0, 0.1))),
+        MeshMaterial3d(materials.add(Color::srgb(0.35, 0.22, 0.15))),
+        Transform::from_xyz(0.0, 1.5, -BOARD_THICKNESS - 0.05),
+        RigidBody::Fixed,
+        Collider::cuboid(2.0, 1.5, 0.05),
+        Name::new("Wall"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(4.0, 0.1, 4.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.25, 0.25, 0.28))),
+        Transform::from_xyz(0.0, -0.05, 1.8),
+        RigidBody::Fixed,
+        Collider::cuboid(2.0, 0.05, 2.0),
+        Name::new("Floor"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.6, 0.01, 0.04))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, 0.005, OCHE_DISTANCE),
+        Name::new("Oche"),
+    ));
+
+    commands.spawn((
+        PointLight {
+            intensity: 400_000.0,
+            range: 6.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 2.6, 1.0),
+    ));
+}
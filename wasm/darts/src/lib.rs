@@ -0,0 +1,395 @@
+//! Bevy darts game
+
+use std::collections::VecDeque;
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{
+        ActiveEvents, Ccd, Collider, CollisionEvent, CollisionGroups, Group, LockedAxes, RigidBody,
+        Velocity,
+    },
+};
+use board::{BoardPlugin, Dartboard, Hit, BOARD_CENTRE, BOARD_RADIUS, OCHE_DISTANCE};
+use phase::{DartsPhase, DartsPhasePlugin};
+use spjorts_core::{
+    communication::JsMessage, gesture::GestureDetector, menu::MenuAction, settings::GameSettings,
+    spectator::is_playing, turns::TurnPlugin, ActionReader,
+};
+use visit::{score_dart, NewGame, VisitPlugin};
+
+pub mod board;
+pub mod phase;
+pub mod visit;
+pub mod x01;
+
+/// How far across the board a radian of controller rotation moves the aim, in meters
+const AIM_SCALE: f32 = 0.35;
+/// How far past the board's edge the aim can wander
+const AIM_MARGIN: f32 = 0.1;
+/// How far back the flick that powers a throw is looked for, in seconds
+const FLICK_WINDOW: f32 = 0.3;
+/// How fast the controller has to turn for a full power flick, in radians per second
+const FULL_FLICK_SPEED: f32 = 10.0;
+/// Speed a dart thrown with no power leaves the hand at, in meters per second
+const MIN_DART_SPEED: f32 = 7.0;
+/// Speed a dart thrown with full power leaves the hand at, in meters per second
+const MAX_DART_SPEED: f32 = 15.0;
+/// Power a throw needs to land exactly where it was aimed, any softer drops low and any harder
+/// flies high
+pub const IDEAL_POWER: f32 = 0.5;
+/// Where darts are thrown from, just right of the player's eye line
+const HAND: Vec3 = Vec3::new(0.12, 1.62, OCHE_DISTANCE - 0.25);
+/// Radius of a dart's point
+const DART_POINT_RADIUS: f32 = 0.004;
+/// Longest a dart can fly before it's counted as a miss, in seconds
+const MAX_FLIGHT_SECS: f32 = 2.0;
+/// Acceleration darts fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(DartsPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(BoardPlugin)
+    .add_plugins(VisitPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.1, 0.09, 0.08)))
+    .init_resource::<Oche>()
+    .add_event::<Throw>()
+    .add_event::<DartLanded>()
+    .add_systems(Startup, setup)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            throw_dart.run_if(in_state(DartsPhase::Aiming)),
+            (point_darts, stick_darts, lose_darts).run_if(in_state(DartsPhase::Flying)),
+        )
+            .chain()
+            .before(score_dart)
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        (place_held_dart, draw_reticle).run_if(in_state(DartsPhase::Aiming)),
+    );
+});
+
+/// Where the player at the oche is aiming and how hard they've been flicking
+#[derive(Resource, Debug, Default)]
+pub struct Oche {
+    /// Where on the board the next dart is aimed, in meters from the bullseye
+    pub aim: Vec2,
+    /// Watches the controller's turning speed
+    detector: GestureDetector,
+    /// Recent turning speeds with the seconds they were read at, oldest first
+    speeds: VecDeque<(f32, f32)>,
+}
+
+impl Oche {
+    /// Records how fast the controller is turning, forgetting readings older than
+    /// [`FLICK_WINDOW`]
+    fn record_speed(&mut self, speed: f32, at: f32) {
+        self.speeds.push_back((at, speed));
+        while self
+            .speeds
+            .front()
+            .is_some_and(|(read_at, _)| at - read_at > FLICK_WINDOW)
+        {
+            self.speeds.pop_front();
+        }
+    }
+
+    /// Power of the fastest recent flick, from 0 to 1
+    pub fn flick_power(&self, now: f32) -> f32 {
+        let fastest = self
+            .speeds
+            .iter()
+            .filter(|(read_at, _)| now - read_at <= FLICK_WINDOW)
+            .map(|(_, speed)| *speed)
+            .fold(0.0, f32::max);
+        (fastest / FULL_FLICK_SPEED).clamp(0.0, 1.0)
+    }
+}
+
+/// A dart let go of at the oche
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Throw {
+    /// Where on the board it was aimed, in meters from the bullseye
+    pub aim: Vec2,
+    /// How hard it was thrown, from 0 to 1
+    pub power: f32,
+}
+
+impl Throw {
+    /// Velocity the dart leaves the hand with. It's lobbed just enough to drop onto the aim
+    /// at [`IDEAL_POWER`], so softer throws land low and harder ones high
+    fn velocity(&self) -> Vec3 {
+        let target = BOARD_CENTRE + self.aim.extend(0.0);
+        let speed = MIN_DART_SPEED + (MAX_DART_SPEED - MIN_DART_SPEED) * self.power.clamp(0.0, 1.0);
+        let ideal_speed = MIN_DART_SPEED + (MAX_DART_SPEED - MIN_DART_SPEED) * IDEAL_POWER;
+        let flight_secs = HAND.distance(target) / ideal_speed;
+        let lob = Vec3::Y * GRAVITY * flight_secs.powi(2) / 2.0;
+
+        (target + lob - HAND).normalize() * speed
+    }
+}
+
+/// A dart came to rest, scoring where it landed
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DartLanded(pub Hit);
+
+/// A thrown dart
+#[derive(Component, Debug)]
+pub struct Dart {
+    /// Seconds since startup the dart was thrown at, while it's still in the air
+    flying_since: Option<f32>,
+}
+
+/// Marks the dart in the player's hand while they aim
+#[derive(Component)]
+struct HeldDart;
+
+/// Meshes and materials every dart is built from
+#[derive(Resource)]
+struct DartModel {
+    /// The barrel, running back from the point
+    barrel: Handle<Mesh>,
+    /// A flight fin, with one spawned flat and one upright
+    flight: Handle<Mesh>,
+    /// Material of the barrel
+    barrel_material: Handle<StandardMaterial>,
+    /// Material of the flights
+    flight_material: Handle<StandardMaterial>,
+}
+
+impl DartModel {
+    /// Spawns a dart's barrel and flights behind a point at the parent's origin, facing the
+    /// parent's forward
+    fn build(&self, dart: &mut ChildBuilder<'_>) {
+        dart.spawn((
+            Mesh3d(self.barrel.clone()),
+            MeshMaterial3d(self.barrel_material.clone()),
+            Transform::from_xyz(0.0, 0.0, 0.06)
+                .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        ));
+        for roll in [0.0, std::f32::consts::FRAC_PI_2] {
+            dart.spawn((
+                Mesh3d(self.flight.clone()),
+                MeshMaterial3d(self.flight_material.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.125).with_rotation(Quat::from_rotation_z(roll)),
+            ));
+        }
+    }
+}
+
+/// Spawns the camera and the dart in the player's hand
+fn setup(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: 0.45,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 1.7, OCHE_DISTANCE + 0.4).looking_at(BOARD_CENTRE, Vec3::Y),
+    ));
+
+    let model = DartModel {
+        barrel: meshes.add(Cylinder::new(DART_POINT_RADIUS, 0.12)),
+        flight: meshes.add(Cuboid::new(0.03, 0.001, 0.035)),
+        barrel_material: materials.add(Color::srgb(0.75, 0.75, 0.8)),
+        flight_material: materials.add(Color::srgb(0.95, 0.75, 0.1)),
+    };
+    commands
+        .spawn((
+            Transform::from_translation(HAND).looking_at(BOARD_CENTRE, Vec3::Y),
+            Visibility::Visible,
+            HeldDart,
+        ))
+        .with_children(|dart| model.build(dart));
+    commands.insert_resource(model);
+}
+
+/// Everything input handling changes besides the aim itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Darts to throw
+    throws: EventWriter<'w, Throw>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: pitch and yaw aim, flicking the controller builds power and A throws
+/// with it. A starts a new leg once someone has checked out
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut oche: ResMut<'_, Oche>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<DartsPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == DartsPhase::Aiming;
+    let now = time.elapsed_secs();
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == DartsPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if aiming => {
+                effects.throws.send(Throw {
+                    aim: oche.aim,
+                    power: oche.flick_power(now),
+                });
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                let reach = BOARD_RADIUS + AIM_MARGIN;
+                oche.aim = (Vec2::new(-orientation.yaw, orientation.pitch) * AIM_SCALE)
+                    .clamp(Vec2::splat(-reach), Vec2::splat(reach));
+
+                oche.detector.update(orientation, now);
+                let speed = oche.detector.speed();
+                oche.record_speed(speed, now);
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Launches a dart from the hand, hiding the held one while it flies
+fn throw_dart(
+    mut commands: Commands<'_, '_>,
+    mut throws: EventReader<'_, '_, Throw>,
+    mut held: Query<'_, '_, &mut Visibility, With<HeldDart>>,
+    mut next_phase: ResMut<'_, NextState<DartsPhase>>,
+    model: Res<'_, DartModel>,
+    time: Res<'_, Time>,
+) {
+    let Some(throw) = throws.read().last().copied() else {
+        return;
+    };
+    let velocity = throw.velocity();
+
+    commands
+        .spawn((
+            Transform::from_translation(HAND).looking_to(velocity, Vec3::Y),
+            Visibility::Visible,
+            RigidBody::Dynamic,
+            Collider::ball(DART_POINT_RADIUS),
+            Ccd::enabled(),
+            LockedAxes::ROTATION_LOCKED,
+            Velocity::linear(velocity),
+            ActiveEvents::COLLISION_EVENTS,
+            // Darts only hit the board, wall and floor, never each other
+            CollisionGroups::new(Group::GROUP_2, Group::GROUP_1),
+            Dart {
+                flying_since: Some(time.elapsed_secs()),
+            },
+        ))
+        .with_children(|dart| model.build(dart));
+
+    for mut visibility in &mut held {
+        *visibility = Visibility::Hidden;
+    }
+    next_phase.set(DartsPhase::Flying);
+}
+
+/// Turns flying darts to face the way they're going
+fn point_darts(mut darts: Query<'_, '_, (&mut Transform, &Velocity, &Dart)>) {
+    for (mut transform, velocity, dart) in &mut darts {
+        if dart.flying_since.is_some() && velocity.linvel.length_squared() > f32::EPSILON {
+            transform.look_to(velocity.linvel, Vec3::Y);
+        }
+    }
+}
+
+/// Sticks a flying dart where it hits, scoring it if that was the board
+fn stick_darts(
+    mut commands: Commands<'_, '_>,
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut darts: Query<'_, '_, (&Transform, &mut Dart)>,
+    boards: Query<'_, '_, (), With<Dartboard>>,
+    mut landed: EventWriter<'_, DartLanded>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let (dart, other) = if darts.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let Ok((transform, mut state)) = darts.get_mut(dart) else {
+            continue;
+        };
+        if state.flying_since.take().is_none() {
+            continue;
+        }
+
+        let hit = if boards.contains(other) {
+            Hit::at((transform.translation - BOARD_CENTRE).truncate())
+        } else {
+            Hit::Miss
+        };
+        commands
+            .entity(dart)
+            .insert((RigidBody::Fixed, Velocity::zero()));
+        landed.send(DartLanded(hit));
+    }
+}
+
+/// Counts a dart that never hit anything as a miss
+fn lose_darts(
+    mut darts: Query<'_, '_, &mut Dart>,
+    mut landed: EventWriter<'_, DartLanded>,
+    time: Res<'_, Time>,
+) {
+    for mut dart in &mut darts {
+        if dart
+            .flying_since
+            .is_some_and(|since| time.elapsed_secs() - since > MAX_FLIGHT_SECS)
+        {
+            dart.flying_since = None;
+            landed.send(DartLanded(Hit::Miss));
+        }
+    }
+}
+
+/// Points the dart in hand at the aim and shows it again
+fn place_held_dart(
+    mut held: Query<'_, '_, (&mut Transform, &mut Visibility), With<HeldDart>>,
+    oche: Res<'_, Oche>,
+) {
+    for (mut transform, mut visibility) in &mut held {
+        transform.look_at(BOARD_CENTRE + oche.aim.extend(0.0), Vec3::Y);
+        *visibility = Visibility::Visible;
+    }
+}
+
+/// Rings the spot on the board the next dart is aimed at
+fn draw_reticle(mut gizmos: Gizmos<'_, '_>, oche: Res<'_, Oche>, settings: Res<'_, GameSettings>) {
+    if settings.aim_guide {
+        let spot = BOARD_CENTRE + oche.aim.extend(0.01);
+        gizmos.circle(Isometry3d::from_translation(spot), 0.012, Color::WHITE);
+    }
+}
@@ -0,0 +1,27 @@
+//! Phases a darts leg moves through, from lining up a dart to the winner being declared
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the leg is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DartsPhase {
+    /// The player at the oche is lining up their next dart
+    #[default]
+    Aiming,
+    /// A dart is on its way to the board
+    Flying,
+    /// The visit is over and its darts are being pulled out of the board
+    Collecting,
+    /// Someone has checked out and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct DartsPhasePlugin;
+
+impl Plugin for DartsPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<DartsPhase>();
+    }
+}
@@ -0,0 +1,237 @@
+//! Visits to the oche: scoring each dart, pulling them out of the board and handing over to the
+//! next player, all shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::DartsPhase,
+    x01::{Outcome, DARTS_PER_VISIT, STARTING_SCORE, X01},
+    Dart, DartLanded, Oche,
+};
+
+/// How long the darts stay in the board after a visit, in seconds
+const COLLECT_SECS: f32 = 1.5;
+
+/// Asks for the leg to be started over from the first player's first visit
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts down before the darts are pulled out of the board
+#[derive(Resource, Debug)]
+struct Collection(Timer);
+
+impl Default for Collection {
+    fn default() -> Self {
+        Self(Timer::from_seconds(COLLECT_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct DartsSnapshot<'a> {
+    /// Player at the oche
+    player: usize,
+    /// Every player's remaining score and the visit so far
+    x01: &'a X01,
+    /// Where the leg is at
+    phase: DartsPhase,
+}
+
+/// Plugin that scores visits and shows them on the scorecard HUD
+pub struct VisitPlugin;
+
+impl Plugin for VisitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<X01>()
+            .init_resource::<Collection>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_leg.run_if(resource_changed::<TurnManager>),
+                    score_dart.run_if(in_state(DartsPhase::Flying)),
+                    collect_darts.run_if(in_state(DartsPhase::Collecting)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(DartsPhase::Collecting), reset_collection)
+            .add_systems(
+                OnEnter(DartsPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(DartsPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh leg whenever the number of players changes
+fn fit_leg(turns: Res<'_, TurnManager>, mut x01: ResMut<'_, X01>) {
+    if x01.players() != turns.players() {
+        *x01 = X01::new(turns.players());
+    }
+}
+
+/// Scores the dart that just landed, moving on to the next dart, the end of the visit or the
+/// end of the leg
+pub fn score_dart(
+    mut landed: EventReader<'_, '_, DartLanded>,
+    mut x01: ResMut<'_, X01>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<DartsPhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    for DartLanded(hit) in landed.read() {
+        let player = turns.current();
+        match x01.throw(player, *hit) {
+            Outcome::Scored => next_phase.set(DartsPhase::Aiming),
+            Outcome::VisitOver => {
+                let scored: u32 = x01.visit().iter().map(|hit| hit.points()).sum();
+                if scored == 180 {
+                    banner.show("One hundred and eighty!");
+                }
+                next_phase.set(DartsPhase::Collecting);
+            }
+            Outcome::Bust => {
+                banner.show("Bust!");
+                next_phase.set(DartsPhase::Collecting);
+            }
+            Outcome::Checkout => {
+                banner.show(format!("Game shot! Player {} checks out", player + 1));
+                next_phase.set(DartsPhase::GameOver);
+            }
+        }
+    }
+}
+
+/// Gives players a moment to see where the visit's darts landed
+fn reset_collection(mut collection: ResMut<'_, Collection>) {
+    collection.0.reset();
+}
+
+/// Pulls the visit's darts out of the board and hands the oche to the next player
+fn collect_darts(
+    mut commands: Commands<'_, '_>,
+    mut collection: ResMut<'_, Collection>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut x01: ResMut<'_, X01>,
+    mut next_phase: ResMut<'_, NextState<DartsPhase>>,
+    darts: Query<'_, '_, Entity, With<Dart>>,
+    time: Res<'_, Time>,
+) {
+    if !collection.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for dart in &darts {
+        commands.entity(dart).despawn_recursive();
+    }
+    let next = turns.advance();
+    x01.start_visit(next);
+    next_phase.set(DartsPhase::Aiming);
+}
+
+/// Starts the leg over from the first player's first visit
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut x01: ResMut<'_, X01>,
+    mut next_phase: ResMut<'_, NextState<DartsPhase>>,
+    darts: Query<'_, '_, Entity, With<Dart>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for dart in &darts {
+        commands.entity(dart).despawn_recursive();
+    }
+    turns.restart();
+    *x01 = X01::new(turns.players());
+    next_phase.set(DartsPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every player's remaining score, the visit so far and the
+/// power of the last flick
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    x01: Res<'_, X01>,
+    oche: Res<'_, Oche>,
+    time: Res<'_, Time>,
+) {
+    let rows = (0..x01.players())
+        .map(|player| format!("Player {}: {}", player + 1, x01.remaining(player)))
+        .collect();
+    let mut visit: Vec<String> = x01.visit().iter().map(|hit| hit.label()).collect();
+    visit.resize(DARTS_PER_VISIT, "-".to_string());
+    let power = oche.flick_power(time.elapsed_secs());
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("{STARTING_SCORE}, double out"),
+        rows,
+        footer: format!(
+            "Visit: {}\nFlick power {:.0}%",
+            visit.join("  "),
+            power * 100.0
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's remaining score once someone has checked out
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, x01: Res<'_, X01>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    for player in 0..x01.players() {
+        let result = if x01.winner() == Some(player) {
+            "checked out".to_string()
+        } else {
+            format!("{} left", x01.remaining(player))
+        };
+        lines.push(format!("Player {}: {result}", player + 1));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new leg starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends the points each player scored back to the page once someone has checked out, so it
+/// can submit them to the server
+fn submit_result(x01: Res<'_, X01>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..x01.players())
+        .map(|player| x01.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    x01: Res<'_, X01>,
+    phase: Res<'_, State<DartsPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&DartsSnapshot {
+        player: turns.current(),
+        x01: &x01,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,113 @@
+//! 501 scoring: every player counts down from 501, three darts a visit, and has to finish on
+//! exactly zero with a double. Going under, or leaving one, is a bust
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::board::Hit;
+
+/// Score every player starts the leg on
+pub const STARTING_SCORE: u32 = 501;
+/// Darts a player throws each visit to the oche
+pub const DARTS_PER_VISIT: usize = 3;
+
+/// What a dart did to the player's visit
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Scored, and the player has darts left to throw this visit
+    Scored,
+    /// Scored with the visit's last dart
+    VisitOver,
+    /// Went under zero, left one, or reached zero without a double. The visit's score is
+    /// wiped and it ends
+    Bust,
+    /// Finished on a double, winning the leg
+    Checkout,
+}
+
+/// Every player's remaining score and the visit in progress
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct X01 {
+    /// Score each player has left, in turn order
+    remaining: Vec<u32>,
+    /// Darts thrown so far this visit
+    visit: Vec<Hit>,
+    /// What the current player had left when their visit started, restored on a bust
+    visit_start: u32,
+    /// The player who checked out, once someone has
+    winner: Option<usize>,
+}
+
+impl Default for X01 {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl X01 {
+    /// Starts a leg for a number of players, with the first player's visit underway
+    pub fn new(players: usize) -> Self {
+        Self {
+            remaining: vec![STARTING_SCORE; players.max(1)],
+            visit: Vec::with_capacity(DARTS_PER_VISIT),
+            visit_start: STARTING_SCORE,
+            winner: None,
+        }
+    }
+
+    /// How many players are in the leg
+    pub fn players(&self) -> usize {
+        self.remaining.len()
+    }
+
+    /// Score a player has left
+    pub fn remaining(&self, player: usize) -> u32 {
+        self.remaining.get(player).copied().unwrap_or_default()
+    }
+
+    /// Points a player has scored towards checking out. Higher is better, like the scores the
+    /// server ranks
+    pub fn points(&self, player: usize) -> u32 {
+        STARTING_SCORE - self.remaining(player)
+    }
+
+    /// Darts thrown so far this visit
+    pub fn visit(&self) -> &[Hit] {
+        &self.visit
+    }
+
+    /// The player who checked out, once someone has
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    /// Starts a player's visit to the oche
+    pub fn start_visit(&mut self, player: usize) {
+        self.visit.clear();
+        self.visit_start = self.remaining(player);
+    }
+
+    /// Scores a dart for the player whose visit it is
+    pub fn throw(&mut self, player: usize, hit: Hit) -> Outcome {
+        let Some(remaining) = self.remaining.get_mut(player) else {
+            return Outcome::Bust;
+        };
+        self.visit.push(hit);
+
+        let left = i64::from(*remaining) - i64::from(hit.points());
+        if left < 0 || left == 1 || (left == 0 && !hit.is_double()) {
+            *remaining = self.visit_start;
+            return Outcome::Bust;
+        }
+
+        *remaining = left as u32;
+        if left == 0 {
+            self.winner = Some(player);
+            Outcome::Checkout
+        } else if self.visit.len() >= DARTS_PER_VISIT {
+            Outcome::VisitOver
+        } else {
+            Outcome::Scored
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! The course: three holes laid out side by side across one field, each with a tee pad, a
+//! chain basket and trees in the way, all of which the disc bounces off
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    Ccd, Collider, ColliderMassProperties, Damping, ExternalForce, Friction, LockedAxes,
+    Restitution, RigidBody, Velocity,
+};
+
+use crate::flight::AIR_DAMPING;
+
+/// Radius of the disc, scaled up from a real one so it can be followed down the fairway
+pub const DISC_RADIUS: f32 = 0.14;
+/// Half the thickness of the disc
+pub const DISC_HALF_HEIGHT: f32 = 0.02;
+/// Mass of the disc, in kilograms
+pub const DISC_MASS: f32 = 0.175;
+/// How high the disc leaves the hand
+pub const RELEASE_HEIGHT: f32 = 1.2;
+/// Damping on a disc skidding along the grass
+pub const GROUND_DAMPING: f32 = 3.0;
+
+/// Height of the basket's tray above the ground
+const TRAY_HEIGHT: f32 = 0.8;
+/// Radius of the basket's tray and chains
+const BASKET_RADIUS: f32 = 0.3;
+/// Height of the top of the basket's chains
+const CHAINS_TOP: f32 = 1.4;
+/// How far from the pole a disc can be and still count as in the chains
+const CATCH_RADIUS: f32 = 0.35;
+/// Radius of a tree's trunk
+const TRUNK_RADIUS: f32 = 0.3;
+/// Height of a tree's trunk
+const TRUNK_HEIGHT: f32 = 4.0;
+/// Radius of a tree's canopy
+const CANOPY_RADIUS: f32 = 2.0;
+/// Edges of the field along x, past which the disc is out of bounds
+const FIELD_X: (f32, f32) = (-30.0, 160.0);
+/// Edges of the field along z, past which the disc is out of bounds
+const FIELD_Z: (f32, f32) = (-100.0, 20.0);
+
+/// One hole of the course
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hole {
+    /// Where every player throws their first disc from, on the ground
+    pub tee: Vec3,
+    /// Where the basket's pole stands, on the ground
+    pub basket: Vec3,
+    /// Throws a good player takes to hole out
+    pub par: u32,
+    /// Where the trees stand along the hole, across and along the ground
+    pub trees: &'static [Vec2],
+}
+
+impl Hole {
+    /// Where the disc is thrown from when lying at a spot on the ground
+    pub fn release(lie: Vec3) -> Vec3 {
+        lie.with_y(RELEASE_HEIGHT)
+    }
+
+    /// Which way along the ground the basket lies from a spot
+    pub fn toward_basket(&self, from: Vec3) -> Vec3 {
+        (self.basket - from).with_y(0.0).normalize_or(Vec3::NEG_Z)
+    }
+
+    /// Whether the disc at a spot is caught in the basket's chains
+    pub fn in_basket(&self, position: Vec3) -> bool {
+        position.xz().distance(self.basket.xz()) <= CATCH_RADIUS
+            && position.y > TRAY_HEIGHT
+            && position.y < CHAINS_TOP
+    }
+}
+
+/// Every hole on the course, in the order they're played
+pub const HOLES: [Hole; 3] = [
+    // A short, open warm up with a few trees either side
+    Hole {
+        tee: Vec3::ZERO,
+        basket: Vec3::new(0.0, 0.0, -40.0),
+        par: 3,
+        trees: &[
+            Vec2::new(-7.0, -12.0),
+            Vec2::new(8.0, -18.0),
+            Vec2::new(-6.0, -27.0),
+            Vec2::new(6.0, -33.0),
+        ],
+    },
+    // A stand of trees right on the line, to be curved around with hyzer or anhyzer
+    Hole {
+        tee: Vec3::new(60.0, 0.0, 0.0),
+        basket: Vec3::new(52.0, 0.0, -50.0),
+        par: 3,
+        trees: &[
+            Vec2::new(56.0, -24.0),
+            Vec2::new(54.0, -26.0),
+            Vec2::new(57.5, -27.5),
+            Vec2::new(55.0, -30.0),
+            Vec2::new(46.0, -40.0),
+        ],
+    },
+    // A long hole through a scattered wood, too far to reach in one throw
+    Hole {
+        tee: Vec3::new(120.0, 0.0, 0.0),
+        basket: Vec3::new(128.0, 0.0, -78.0),
+        par: 4,
+        trees: &[
+            Vec2::new(116.0, -18.0),
+            Vec2::new(127.0, -26.0),
+            Vec2::new(119.0, -38.0),
+            Vec2::new(131.0, -47.0),
+            Vec2::new(122.0, -56.0),
+            Vec2::new(134.0, -64.0),
+        ],
+    },
+];
+
+/// Par for the whole course
+pub fn course_par() -> u32 {
+    HOLES.iter().map(|hole| hole.par).sum()
+}
+
+/// Whether a spot has left the field, costing a penalty throw
+pub fn out_of_bounds(position: Vec3) -> bool {
+    position.x < FIELD_X.0
+        || position.x > FIELD_X.1
+        || position.z < FIELD_Z.0
+        || position.z > FIELD_Z.1
+        || position.y < -1.0
+}
+
+/// Whether the disc is down on the grass rather than in the air
+pub fn on_ground(position: Vec3) -> bool {
+    position.y <= DISC_RADIUS
+}
+
+/// Damping on a disc in the air
+pub fn air_damping() -> Damping {
+    Damping {
+        linear_damping: AIR_DAMPING,
+        angular_damping: 0.0,
+    }
+}
+
+/// A thrown disc
+#[derive(Component, Debug)]
+pub struct Disc {
+    /// How far the disc is tipped over, in radians. Positive drops the left edge
+    pub bank: f32,
+    /// How fast it left the hand, in meters per second
+    pub launch_speed: f32,
+    /// Which way the thrower's throws fade, `1.0` to the left
+    pub hand: f32,
+}
+
+/// Plugin that lays out the course
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_course);
+    }
+}
+
+/// Spawns the field, each hole's tee pad, basket and trees, the disc and the light
+fn setup_course(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let size = Vec2::new(FIELD_X.1 - FIELD_X.0, FIELD_Z.1 - FIELD_Z.0);
+    let centre = Vec2::new(FIELD_X.0 + FIELD_X.1, FIELD_Z.0 + FIELD_Z.1) / 2.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(size.x, size.y))),
+        MeshMaterial3d(materials.add(Color::srgb(0.25, 0.5, 0.18))),
+        Transform::from_xyz(centre.x, 0.0, centre.y),
+        Name::new("Field"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(centre.x, -0.1, centre.y),
+        RigidBody::Fixed,
+        Collider::cuboid(size.x / 2.0, 0.1, size.y / 2.0),
+        Friction::coefficient(0.9),
+        Restitution::coefficient(0.1),
+        Name::new("Ground"),
+    ));
+
+    let pad = meshes.add(Cuboid::new(1.5, 0.04, 3.0));
+    let concrete = materials.add(Color::srgb(0.7, 0.7, 0.68));
+    let steel = materials.add(Color::srgb(0.75, 0.75, 0.8));
+    let chains = materials.add(Color::srgba(0.8, 0.8, 0.85, 0.5));
+    let pole = meshes.add(Cylinder::new(0.03, CHAINS_TOP));
+    let tray = meshes.add(Cylinder::new(BASKET_RADIUS, 0.2));
+    let curtain = meshes.add(Cylinder::new(
+        BASKET_RADIUS * 0.8,
+        CHAINS_TOP - TRAY_HEIGHT - 0.1,
+    ));
+    let trunk = meshes.add(Cylinder::new(TRUNK_RADIUS, TRUNK_HEIGHT));
+    let canopy = meshes.add(Sphere::new(CANOPY_RADIUS));
+    let bark = materials.add(Color::srgb(0.4, 0.27, 0.15));
+    let leaves = materials.add(Color::srgb(0.12, 0.35, 0.1));
+
+    for (number, hole) in HOLES.iter().enumerate() {
+        commands.spawn((
+            Mesh3d(pad.clone()),
+            MeshMaterial3d(concrete.clone()),
+            Transform::from_translation(hole.tee + Vec3::new(0.0, 0.02, 0.5))
+                .looking_to(hole.toward_basket(hole.tee), Vec3::Y),
+            Name::new(format!("Tee {}", number + 1)),
+        ));
+
+        // The chains have no collider, so a disc flying into them is caught rather than
+        // bouncing off
+        commands
+            .spawn((
+                Mesh3d(pole.clone()),
+                MeshMaterial3d(steel.clone()),
+                Transform::from_translation(hole.basket + Vec3::Y * CHAINS_TOP / 2.0),
+                RigidBody::Fixed,
+                Collider::cylinder(CHAINS_TOP / 2.0, 0.03),
+                Name::new(format!("Basket {}", number + 1)),
+            ))
+            .with_children(|basket| {
+                basket.spawn((
+                    Mesh3d(tray.clone()),
+                    MeshMaterial3d(steel.clone()),
+                    Transform::from_xyz(0.0, TRAY_HEIGHT - 0.1 - CHAINS_TOP / 2.0, 0.0),
+                    Collider::cylinder(0.1, BASKET_RADIUS),
+                    Restitution::coefficient(0.2),
+                ));
+                basket.spawn((
+                    Mesh3d(curtain.clone()),
+                    MeshMaterial3d(chains.clone()),
+                    Transform::from_xyz(0.0, (TRAY_HEIGHT + 0.1) / 2.0, 0.0),
+                ));
+            });
+
+        for tree in hole.trees {
+            commands
+                .spawn((
+                    Mesh3d(trunk.clone()),
+                    MeshMaterial3d(bark.clone()),
+                    Transform::from_xyz(tree.x, TRUNK_HEIGHT / 2.0, tree.y),
+                    RigidBody::Fixed,
+                    Collider::cylinder(TRUNK_HEIGHT / 2.0, TRUNK_RADIUS),
+                    Restitution::coefficient(0.3),
+                    Name::new("Tree"),
+                ))
+                .with_children(|tree| {
+                    tree.spawn((
+                        Mesh3d(canopy.clone()),
+                        MeshMaterial3d(leaves.clone()),
+                        Transform::from_xyz(0.0, TRUNK_HEIGHT / 2.0 + CANOPY_RADIUS * 0.7, 0.0),
+                        Collider::ball(CANOPY_RADIUS),
+                        Restitution::coefficient(0.1),
+                    ));
+                });
+        }
+    }
+
+    let tee = Hole::release(HOLES[0].tee);
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(DISC_RADIUS, DISC_HALF_HEIGHT * 2.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.95, 0.4, 0.1))),
+        Transform::from_translation(tee),
+        RigidBody::KinematicPositionBased,
+        Collider::cylinder(DISC_HALF_HEIGHT, DISC_RADIUS),
+        ColliderMassProperties::Mass(DISC_MASS),
+        LockedAxes::ROTATION_LOCKED,
+        Restitution::coefficient(0.2),
+        Friction::coefficient(0.6),
+        Ccd::enabled(),
+        Velocity::zero(),
+        ExternalForce::default(),
+        air_damping(),
+        Disc {
+            bank: 0.0,
+            launch_speed: 0.0,
+            hand: 1.0,
+        },
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(40.0, 30.0, 20.0).looking_at(Vec3::new(60.0, 0.0, -40.0), Vec3::Y),
+    ));
+}
@@ -0,0 +1,63 @@
+//! The disc's flight model: lift that grows with the square of its speed, tilted off vertical by
+//! the disc's bank so hyzer and anhyzer throws curve, and fade that tips the disc further over
+//! as it slows down
+
+use bevy::prelude::*;
+
+/// Acceleration the disc falls at, matching the physics' gravity
+pub const GRAVITY: f32 = 9.81;
+/// Speed a full power throw leaves the hand at, in meters per second
+pub const MAX_SPEED: f32 = 28.0;
+/// Damping on a disc in the air
+pub const AIR_DAMPING: f32 = 0.25;
+/// Lift on the disc for every square meter per second squared of its speed, as an acceleration
+const LIFT: f32 = 0.02;
+/// How fast the disc tips over toward its fade side once it has lost all its speed, in radians
+/// per second
+const FADE_RATE: f32 = 0.5;
+/// Time step flights are predicted with, matching the physics' fixed step
+const STEP_SECS: f32 = 1.0 / 60.0;
+/// Longest a flight is predicted for, in seconds
+const MAX_FLIGHT_SECS: f32 = 10.0;
+
+/// Acceleration lift gives a disc flying at `velocity` banked by `bank` radians. A positive bank
+/// drops the left edge and tilts the lift to the left, curving the disc that way
+pub fn lift(velocity: Vec3, bank: f32) -> Vec3 {
+    let along = velocity.with_y(0.0).normalize_or_zero();
+    let right = along.cross(Vec3::Y);
+    (Vec3::Y * bank.cos() - right * bank.sin()) * LIFT * velocity.length_squared()
+}
+
+/// The disc's bank after `secs` more of flight. It tips toward the thrower's fade side, left
+/// for `hand` of `1.0`, faster the more of its launch speed it has lost
+pub fn fade(bank: f32, speed: f32, launch_speed: f32, hand: f32, secs: f32) -> f32 {
+    let slowed = if launch_speed > 0.0 {
+        (1.0 - speed / launch_speed).max(0.0)
+    } else {
+        0.0
+    };
+    bank + hand * FADE_RATE * slowed * secs
+}
+
+/// Velocity a disc leaves the hand with, thrown along `direction` with a share of a full throw's
+/// power and nose angled up by `angle` radians
+pub fn launch_velocity(direction: Vec3, power: f32, angle: f32) -> Vec3 {
+    let speed = MAX_SPEED * power.clamp(0.0, 1.0);
+    direction * speed * angle.cos() + Vec3::Y * speed * angle.sin()
+}
+
+/// Where a disc thrown from `from` with `velocity` and `bank` first comes down, stepping the
+/// same forces the physics applies
+pub fn landing(from: Vec3, velocity: Vec3, bank: f32, hand: f32) -> Vec3 {
+    let launch_speed = velocity.length();
+    let (mut position, mut velocity, mut bank) = (from, velocity, bank);
+    let mut secs = 0.0;
+    while position.y > 0.0 && secs < MAX_FLIGHT_SECS {
+        velocity += (lift(velocity, bank) - Vec3::Y * GRAVITY) * STEP_SECS;
+        velocity /= 1.0 + STEP_SECS * AIR_DAMPING;
+        position += velocity * STEP_SECS;
+        bank = fade(bank, velocity.length(), launch_speed, hand, STEP_SECS);
+        secs += STEP_SECS;
+    }
+    position.with_y(0.0)
+}
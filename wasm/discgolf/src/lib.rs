@@ -0,0 +1,477 @@
+//! Bevy disc golf game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{Damping, ExternalForce, RigidBody, Velocity},
+};
+use course::{
+    air_damping, on_ground, out_of_bounds, CoursePlugin, Disc, Hole, DISC_MASS, GROUND_DAMPING,
+    HOLES,
+};
+use flight::{fade, launch_velocity, lift};
+use phase::{DiscGolfPhase, DiscGolfPhasePlugin};
+use scorecard::{Scorecard, ScorecardPlugin};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod course;
+pub mod flight;
+pub mod phase;
+pub mod scorecard;
+
+/// Widest a throw can be aimed away from the basket, in radians
+const MAX_AIM: f32 = 0.8;
+/// Furthest the wrist can roll the disc over for hyzer or anhyzer, in radians
+pub const MAX_BANK: f32 = 0.6;
+/// Angle the disc's nose leaves the hand at with the controller held level, in radians
+pub const BASE_ANGLE: f32 = 0.12;
+/// Steepest the disc's nose can leave the hand at, in radians
+const MAX_ANGLE: f32 = 0.4;
+/// How fast the controller has to turn for a full power throw, in radians per second
+const FULL_FLICK_SPEED: f32 = 16.0;
+/// Weakest a detected flick throws the disc, as a fraction of a full throw
+const MIN_POWER: f32 = 0.15;
+/// Speed below which the disc counts as stopped, in meters per second
+const REST_SPEED: f32 = 0.2;
+/// How long the disc has to stay stopped before the throw is over, in seconds
+const REST_SECS: f32 = 0.5;
+/// Length of the aim arrow drawn from the disc while aiming
+const AIM_ARROW_LENGTH: f32 = 4.0;
+/// How far behind the disc the camera sits
+const CAMERA_BACK: f32 = 6.0;
+/// How far above the disc the camera sits
+const CAMERA_UP: f32 = 2.0;
+/// How quickly the camera catches up with the disc, higher is snappier
+const CAMERA_SMOOTHING: f32 = 4.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(DiscGolfPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(CoursePlugin)
+    .add_plugins(ScorecardPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Throw>()
+    .add_event::<Release>()
+    .add_event::<NewGame>()
+    .add_systems(Startup, setup_camera)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            throw_disc.run_if(in_state(DiscGolfPhase::Aiming)),
+            fly_disc.run_if(in_state(DiscGolfPhase::Flying)),
+            track_disc.run_if(in_state(DiscGolfPhase::Flying)),
+            start_new_game,
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        (
+            follow_disc,
+            draw_aim_guide.run_if(in_state(DiscGolfPhase::Aiming)),
+        ),
+    );
+});
+
+/// The throw the player whose turn it is is lining up, or has just made
+#[derive(Resource, Debug)]
+pub struct Throw {
+    /// How far the throw is aimed away from the basket, in radians
+    pub aim: f32,
+    /// Angle the disc's nose leaves the hand at, in radians
+    pub angle: f32,
+    /// How far the wrist rolls the disc over, in radians. Positive drops the left edge
+    pub bank: f32,
+    /// Which way the thrower's throws fade, `1.0` to the left
+    pub hand: f32,
+    /// Where the disc was last thrown from, on the ground, so it can be brought back there when
+    /// it goes out of bounds
+    lie: Vec3,
+    /// Watches the controller for flicks
+    detector: GestureDetector,
+    /// How long the disc has been stopped for, in seconds
+    stopped_for: f32,
+}
+
+impl Default for Throw {
+    fn default() -> Self {
+        Self::from(HOLES[0].tee)
+    }
+}
+
+impl From<Vec3> for Throw {
+    fn from(lie: Vec3) -> Self {
+        Self {
+            aim: 0.0,
+            angle: BASE_ANGLE,
+            bank: 0.0,
+            hand: 1.0,
+            lie,
+            detector: GestureDetector::default(),
+            stopped_for: 0.0,
+        }
+    }
+}
+
+impl Throw {
+    /// Which way the disc heads along the ground when thrown, from the line to the basket turned
+    /// by the aim
+    pub fn direction(&self, hole: &Hole) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * hole.toward_basket(self.lie)
+    }
+}
+
+/// A flick that let the disc go
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Release {
+    /// How hard the disc was thrown, as a fraction of a full throw
+    pub power: f32,
+}
+
+/// Asks for the round to be started over from the first player's throw off the first tee
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Marks the camera following the disc
+#[derive(Component)]
+struct FollowCamera;
+
+/// Spawns the camera behind the first tee
+fn setup_camera(mut commands: Commands<'_, '_>) {
+    let tee = Hole::release(HOLES[0].tee);
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(tee + Vec3::new(0.0, CAMERA_UP, CAMERA_BACK))
+            .looking_at(tee, Vec3::Y),
+        FollowCamera,
+    ));
+}
+
+/// Everything input handling changes besides the throw itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Flicks to let the disc go with
+    release: EventWriter<'w, Release>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims, pitch angles the disc's nose, wrist roll banks it for hyzer
+/// or anhyzer and a flick throws it. A starts a new game once the round is done
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut throw: ResMut<'_, Throw>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<DiscGolfPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == DiscGolfPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == DiscGolfPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation @ Orientation { pitch, roll, yaw } =
+                    settings.apply_rotation(orientation);
+                throw.aim = yaw.clamp(-MAX_AIM, MAX_AIM);
+                throw.angle = (BASE_ANGLE + pitch).clamp(0.0, MAX_ANGLE);
+                throw.bank = roll.clamp(-MAX_BANK, MAX_BANK);
+                throw.hand = settings.handedness();
+
+                let Some(detected) = throw.detector.update(orientation, time.elapsed_secs()) else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Flick | Gesture::Swing) {
+                    let power = detected.intensity / FULL_FLICK_SPEED;
+                    effects.release.send(Release {
+                        power: power.clamp(MIN_POWER, 1.0),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets the disc go the way the throw is lined up, counting the throw
+fn throw_disc(
+    mut releases: EventReader<'_, '_, Release>,
+    mut disc: Query<'_, '_, (&mut Velocity, &mut RigidBody, &mut Damping, &mut Disc)>,
+    mut throw: ResMut<'_, Throw>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut next_phase: ResMut<'_, NextState<DiscGolfPhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(release) = releases.read().last().copied() else {
+        return;
+    };
+    let Ok((mut velocity, mut body, mut damping, mut disc)) = disc.get_single_mut() else {
+        return;
+    };
+
+    let launch = launch_velocity(
+        throw.direction(scorecard.hole()),
+        release.power,
+        throw.angle,
+    );
+    *body = RigidBody::Dynamic;
+    *velocity = Velocity::linear(launch);
+    *damping = air_damping();
+    *disc = Disc {
+        bank: throw.bank,
+        launch_speed: launch.length(),
+        hand: throw.hand,
+    };
+    throw.stopped_for = 0.0;
+
+    scorecard.add_throw(turns.current());
+    next_phase.set(DiscGolfPhase::Flying);
+}
+
+/// Holds the disc up with lift tilted by its bank and tips it over as it fades, until it comes
+/// down onto the grass and skids to a stop
+fn fly_disc(
+    mut disc: Query<
+        '_,
+        '_,
+        (
+            &mut Transform,
+            &Velocity,
+            &mut ExternalForce,
+            &mut Damping,
+            &mut Disc,
+        ),
+    >,
+    time: Res<'_, Time>,
+) {
+    for (mut transform, velocity, mut force, mut damping, mut disc) in &mut disc {
+        if on_ground(transform.translation) {
+            force.force = Vec3::ZERO;
+            damping.linear_damping = GROUND_DAMPING;
+            continue;
+        }
+
+        let linvel = velocity.linvel;
+        force.force = lift(linvel, disc.bank) * DISC_MASS;
+        disc.bank = fade(
+            disc.bank,
+            linvel.length(),
+            disc.launch_speed,
+            disc.hand,
+            time.delta_secs(),
+        );
+        let along = linvel.with_y(0.0);
+        if along != Vec3::ZERO {
+            transform.rotation = Transform::IDENTITY.looking_to(along, Vec3::Y).rotation
+                * Quat::from_rotation_z(disc.bank);
+        }
+    }
+}
+
+/// Everything watching the disc changes once a throw is over
+#[derive(SystemParam)]
+struct ThrowOutcome<'w> {
+    /// The card throws and penalties are counted on
+    scorecard: ResMut<'w, Scorecard>,
+    /// Whose turn it is
+    turns: ResMut<'w, TurnManager>,
+    /// Messages shown to players
+    banner: ResMut<'w, Banner>,
+    /// Phase to move on to
+    next_phase: ResMut<'w, NextState<DiscGolfPhase>>,
+}
+
+impl ThrowOutcome<'_> {
+    /// Moves on once a throw is over: the same player goes again from where the disc lies, or if
+    /// they're done with the hole the next player throws off the tee, or everyone moves on to the
+    /// next hole, or the game ends. Returns where the disc should lie next
+    fn finish(&mut self, disc: Vec3) -> Vec3 {
+        let player = self.turns.current();
+        if self.scorecard.out_of_throws(player) {
+            self.scorecard.hole_out(player);
+            self.banner.show("Picked up");
+        }
+
+        if !self.scorecard.is_holed(player) {
+            self.next_phase.set(DiscGolfPhase::Aiming);
+            return disc;
+        }
+
+        let scorecard = &self.scorecard;
+        if self
+            .turns
+            .advance_until(|player| scorecard.is_holed(player))
+            .is_some()
+        {
+            self.next_phase.set(DiscGolfPhase::Aiming);
+        } else if self.scorecard.next_hole() {
+            self.turns.restart();
+            let hole = self.scorecard.hole();
+            self.banner.show(format!(
+                "Hole {}, par {}",
+                self.scorecard.hole_number(),
+                hole.par
+            ));
+            self.next_phase.set(DiscGolfPhase::Aiming);
+        } else {
+            self.next_phase.set(DiscGolfPhase::GameOver);
+        }
+        self.scorecard.hole().tee
+    }
+}
+
+/// Follows the disc after a throw, watching for it landing in the basket, leaving the field or
+/// coming to rest
+fn track_disc(
+    mut disc: Query<
+        '_,
+        '_,
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut RigidBody,
+            &mut ExternalForce,
+        ),
+        With<Disc>,
+    >,
+    mut throw: ResMut<'_, Throw>,
+    mut outcome: ThrowOutcome<'_>,
+    time: Res<'_, Time>,
+) {
+    let Ok((mut transform, mut velocity, mut body, mut force)) = disc.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+    let player = outcome.turns.current();
+
+    let next = if out_of_bounds(position) {
+        outcome.scorecard.add_throw(player);
+        outcome.banner.show("Out of bounds! +1 throw");
+        Some(throw.lie)
+    } else if outcome.scorecard.hole().in_basket(position) {
+        outcome.scorecard.hole_out(player);
+        let throws = outcome.scorecard.throws(player);
+        let par = outcome.scorecard.hole().par;
+        outcome.banner.show(match throws {
+            1 => "Ace!".to_string(),
+            _ if throws < par => format!("Chains! {throws} throws, under par"),
+            _ => format!("Chains! {throws} throws"),
+        });
+        Some(position)
+    } else {
+        throw.stopped_for = if velocity.linvel.length() < REST_SPEED {
+            throw.stopped_for + time.delta_secs()
+        } else {
+            0.0
+        };
+        (throw.stopped_for >= REST_SECS).then_some(position)
+    };
+
+    if let Some(lie) = next {
+        let lie = outcome.finish(lie.with_y(0.0));
+        transform.translation = Hole::release(lie);
+        transform.rotation = Quat::IDENTITY;
+        *velocity = Velocity::zero();
+        *body = RigidBody::KinematicPositionBased;
+        *force = ExternalForce::default();
+        *throw = Throw::from(lie);
+    }
+}
+
+/// Starts the round over from the first player's throw off the first tee with a fresh card
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut disc: Query<'_, '_, (&mut Transform, &mut Velocity, &mut RigidBody), With<Disc>>,
+    mut throw: ResMut<'_, Throw>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<DiscGolfPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for (mut transform, mut velocity, mut body) in &mut disc {
+        transform.translation = Hole::release(HOLES[0].tee);
+        transform.rotation = Quat::IDENTITY;
+        *velocity = Velocity::zero();
+        *body = RigidBody::KinematicPositionBased;
+    }
+    *throw = Throw::default();
+    turns.restart();
+    *scorecard = Scorecard::new(turns.players());
+    next_phase.set(DiscGolfPhase::Aiming);
+}
+
+/// Keeps the camera behind the disc, looking the way the throw is aimed
+fn follow_disc(
+    mut camera: Query<'_, '_, &mut Transform, (With<FollowCamera>, Without<Disc>)>,
+    disc: Query<'_, '_, &Transform, With<Disc>>,
+    throw: Res<'_, Throw>,
+    scorecard: Res<'_, Scorecard>,
+    time: Res<'_, Time>,
+) {
+    let (Ok(mut camera), Ok(disc)) = (camera.get_single_mut(), disc.get_single()) else {
+        return;
+    };
+    let target = disc.translation;
+    let wanted = target - throw.direction(scorecard.hole()) * CAMERA_BACK + Vec3::Y * CAMERA_UP;
+    let blend = 1.0 - (-CAMERA_SMOOTHING * time.delta_secs()).exp();
+
+    camera.translation = camera.translation.lerp(wanted, blend);
+    camera.look_at(target, Vec3::Y);
+}
+
+/// Draws an arrow along the ground the way the throw is aimed, tipped over by the disc's bank
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    disc: Query<'_, '_, &Transform, With<Disc>>,
+    throw: Res<'_, Throw>,
+    scorecard: Res<'_, Scorecard>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let direction = throw.direction(scorecard.hole());
+    let right = direction.cross(Vec3::Y);
+    for disc in &disc {
+        let start = disc.translation.with_y(0.05);
+        gizmos.arrow(
+            start,
+            start + direction * AIM_ARROW_LENGTH,
+            Color::srgb(1.0, 0.9, 0.2),
+        );
+        // A short line up from the tail leans the way the lift will, showing the disc's bank
+        let tilt = (Vec3::Y * throw.bank.cos() - right * throw.bank.sin()) * 0.5;
+        gizmos.line(start, start + tilt, Color::srgb(1.0, 0.5, 0.2));
+    }
+}
@@ -0,0 +1,25 @@
+//! Phases a disc golf round moves through, from lining up a throw to the final scorecard
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the round is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DiscGolfPhase {
+    /// The player whose turn it is is lining up and throwing
+    #[default]
+    Aiming,
+    /// The disc is in the air or skidding after a throw
+    Flying,
+    /// Every player has finished the last hole, and the final scorecard is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the round is in
+pub struct DiscGolfPhasePlugin;
+
+impl Plugin for DiscGolfPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<DiscGolfPhase>();
+    }
+}
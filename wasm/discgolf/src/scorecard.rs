@@ -0,0 +1,304 @@
+//! Throws each player has taken on every hole, tracked against par and shown on the shared
+//! scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    course::{course_par, Disc, Hole, HOLES},
+    phase::DiscGolfPhase,
+    Throw,
+};
+
+/// Most throws a player can take on a hole before picking up their disc, scored as that many
+pub const MAX_THROWS: u32 = 8;
+
+/// Throws each player has taken on every hole, in turn order
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scorecard {
+    /// Throws taken by each player on each hole played so far, penalties included
+    throws: Vec<Vec<u32>>,
+    /// Whether each player has finished the hole being played, by holing out or picking up
+    holed: Vec<bool>,
+    /// Index of the hole being played
+    hole: usize,
+}
+
+impl Scorecard {
+    /// Starts a fresh card on the first hole for a number of players
+    pub fn new(players: usize) -> Self {
+        Self {
+            throws: vec![vec![0]; players],
+            holed: vec![false; players],
+            hole: 0,
+        }
+    }
+
+    /// How many players are on the card
+    pub fn players(&self) -> usize {
+        self.throws.len()
+    }
+
+    /// The hole being played
+    pub fn hole(&self) -> &'static Hole {
+        &HOLES[self.hole.min(HOLES.len() - 1)]
+    }
+
+    /// Number of the hole being played, starting from one
+    pub fn hole_number(&self) -> usize {
+        self.hole + 1
+    }
+
+    /// Throws a player has taken on the hole being played
+    pub fn throws(&self, player: usize) -> u32 {
+        self.throws
+            .get(player)
+            .and_then(|holes| holes.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Throws a player has taken across every hole so far
+    pub fn total(&self, player: usize) -> u32 {
+        self.throws
+            .get(player)
+            .map(|holes| holes.iter().sum())
+            .unwrap_or_default()
+    }
+
+    /// Whether a player has finished the hole being played
+    pub fn is_holed(&self, player: usize) -> bool {
+        self.holed.get(player).copied().unwrap_or(true)
+    }
+
+    /// Adds a throw, or a penalty throw, to a player's card
+    pub fn add_throw(&mut self, player: usize) {
+        if let Some(throws) = self
+            .throws
+            .get_mut(player)
+            .and_then(|holes| holes.last_mut())
+        {
+            *throws += 1;
+        }
+    }
+
+    /// Whether a player has used up their throws without holing out, and has to pick up
+    pub fn out_of_throws(&self, player: usize) -> bool {
+        !self.is_holed(player) && self.throws(player) >= MAX_THROWS
+    }
+
+    /// Marks a player as done with the hole being played
+    pub fn hole_out(&mut self, player: usize) {
+        if let Some(holed) = self.holed.get_mut(player) {
+            *holed = true;
+        }
+    }
+
+    /// Moves everyone on to the next hole, returning `false` once the last hole is done
+    pub fn next_hole(&mut self) -> bool {
+        if self.hole + 1 >= HOLES.len() {
+            return false;
+        }
+        self.hole += 1;
+        for holes in &mut self.throws {
+            holes.push(0);
+        }
+        self.holed.fill(false);
+        true
+    }
+
+    /// Par for every hole played so far, the one being played included
+    fn par_so_far(&self) -> u32 {
+        HOLES[..=self.hole.min(HOLES.len() - 1)]
+            .iter()
+            .map(|hole| hole.par)
+            .sum()
+    }
+
+    /// A player's throws relative to par over the holes played so far, like `E`, `-1` or `+2`
+    pub fn to_par(&self, player: usize) -> String {
+        match i64::from(self.total(player)) - i64::from(self.par_so_far()) {
+            0 => "E".to_string(),
+            diff if diff > 0 => format!("+{diff}"),
+            diff => diff.to_string(),
+        }
+    }
+
+    /// Stableford points for a player's round: two for each par, one more for every throw under
+    /// and one fewer for every throw over, never below zero on a hole. Higher is better, like
+    /// the scores the server ranks
+    pub fn points(&self, player: usize) -> u32 {
+        self.throws
+            .get(player)
+            .map(|holes| {
+                holes
+                    .iter()
+                    .zip(HOLES)
+                    .map(|(throws, hole)| (hole.par + 2).saturating_sub(*throws))
+                    .sum()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct DiscGolfSnapshot<'a> {
+    /// Player whose turn it is
+    player: usize,
+    /// The card so far
+    scorecard: &'a Scorecard,
+    /// Number of the hole being played, starting from one
+    hole: usize,
+    /// Throws a good player takes on the hole being played
+    par: u32,
+    /// Where the round is at
+    phase: DiscGolfPhase,
+}
+
+/// Plugin that keeps the scorecard, shows it and reports the final result
+pub struct ScorecardPlugin;
+
+impl Plugin for ScorecardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scorecard>()
+            .add_systems(
+                Update,
+                (
+                    fit_scorecard.run_if(resource_changed::<TurnManager>),
+                    update_hud,
+                    update_snapshot,
+                ),
+            )
+            .add_systems(
+                OnEnter(DiscGolfPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(DiscGolfPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh card whenever the number of players changes
+fn fit_scorecard(turns: Res<'_, TurnManager>, mut scorecard: ResMut<'_, Scorecard>) {
+    if scorecard.players() != turns.players() {
+        *scorecard = Scorecard::new(turns.players());
+    }
+}
+
+/// What a disc's bank is called for the thrower whose throws fade toward `hand`
+fn release_name(bank: f32, hand: f32) -> &'static str {
+    let toward_fade = bank * hand;
+    if toward_fade > 0.05 {
+        "Hyzer"
+    } else if toward_fade < -0.05 {
+        "Anhyzer"
+    } else {
+        "Flat"
+    }
+}
+
+/// Fills in the scorecard HUD with every player's throws on the hole and against par, the
+/// distance to the basket and the release angle lined up
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    disc: Query<'_, '_, &Transform, With<Disc>>,
+    scorecard: Res<'_, Scorecard>,
+    throw: Res<'_, Throw>,
+) {
+    let hole = scorecard.hole();
+    let rows = (0..scorecard.players())
+        .map(|player| {
+            let status = if scorecard.is_holed(player) {
+                "holed"
+            } else {
+                "playing"
+            };
+            format!(
+                "Player {}: {} ({}) {status}",
+                player + 1,
+                scorecard.throws(player),
+                scorecard.to_par(player)
+            )
+        })
+        .collect();
+    let footer = disc
+        .get_single()
+        .map(|transform| {
+            format!(
+                "{:.0} m to the basket\n{} release",
+                transform.translation.xz().distance(hole.basket.xz()),
+                release_name(throw.bank, throw.hand)
+            )
+        })
+        .unwrap_or_default();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!(
+            "Hole {} of {}, par {}",
+            scorecard.hole_number(),
+            HOLES.len(),
+            hole.par
+        ),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's throws and points once the round is done
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scorecard: Res<'_, Scorecard>) {
+    let mut lines = vec![format!("Final Scorecard, par {}", course_par())];
+    for player in 0..scorecard.players() {
+        lines.push(format!(
+            "Player {}: {} throws ({}), {} points",
+            player + 1,
+            scorecard.total(player),
+            scorecard.to_par(player),
+            scorecard.points(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scorecard when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends each player's Stableford points back to the page once the round is done, so it can
+/// submit them to the server
+fn submit_result(scorecard: Res<'_, Scorecard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scorecard.players())
+        .map(|player| scorecard.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scorecard: Res<'_, Scorecard>,
+    phase: Res<'_, State<DiscGolfPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&DiscGolfSnapshot {
+        player: turns.current(),
+        scorecard: &scorecard,
+        hole: scorecard.hole_number(),
+        par: scorecard.hole().par,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,294 @@
+//! Refereeing the bout: starting each phrase from the en garde lines, watching the blades for
+//! parries and touches, giving the call once the scoring box locks out and starting bouts over
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::CollisionEvent;
+use spjorts_core::{
+    communication::GameEvent, scorecard::Banner, spectator::is_playing, FeedbackSender,
+};
+
+use crate::{
+    fencer::{Action, Blade, Fencer},
+    lineup::Lineup,
+    phase::FencingPhase,
+    piste::Side,
+    rules::{Call, RightOfWay},
+    score::FencingScore,
+};
+
+/// How long fencers stand en garde before the referee starts them, in seconds
+const EN_GARDE_SECS: f32 = 1.5;
+/// How long the referee holds the bout after a touch, in seconds
+const HALT_SECS: f32 = 2.0;
+/// How hard controllers rumble when a touch lands, out of 255
+const TOUCH_RUMBLE: u8 = 200;
+/// How long controllers rumble when a touch lands, in milliseconds
+const RUMBLE_MILLIS: u16 = 150;
+
+/// The state of the phrase being fenced
+#[derive(Resource, Debug)]
+pub struct Bout {
+    /// The referee's last call, `None` until the first touch
+    pub last_call: Option<Call>,
+    /// Whether the blades are in contact
+    engaged: bool,
+    /// Counts down standing en garde, then the halt after a touch
+    pause: Timer,
+}
+
+impl Default for Bout {
+    fn default() -> Self {
+        Self {
+            last_call: None,
+            engaged: false,
+            pause: Timer::from_seconds(EN_GARDE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// Asks for the bout to be started over
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewBout;
+
+/// Plugin that referees each phrase and the flow between touches
+pub struct BoutPlugin;
+
+impl Plugin for BoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bout>()
+            .init_resource::<RightOfWay>()
+            .init_resource::<FencingScore>()
+            .add_event::<NewBout>()
+            .add_systems(OnEnter(FencingPhase::EnGarde), en_garde)
+            .add_systems(OnEnter(FencingPhase::Halt), reset_pause)
+            .add_systems(
+                Update,
+                (
+                    allez.run_if(in_state(FencingPhase::EnGarde)),
+                    (watch_blades, check_parries, give_call)
+                        .run_if(in_state(FencingPhase::Fencing)),
+                    restart_phrase.run_if(in_state(FencingPhase::Halt)),
+                    new_bout_for_lineup.run_if(resource_changed::<Lineup>),
+                    start_new_bout,
+                    // A new bout started while en garde doesn't leave the phase, so it's set up
+                    // here
+                    en_garde
+                        .run_if(in_state(FencingPhase::EnGarde))
+                        .run_if(resource_changed::<FencingScore>),
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, draw_priority);
+    }
+}
+
+/// Sends both fencers back to their en garde lines with priority cleared for a fresh phrase
+fn en_garde(
+    mut fencers: Query<'_, '_, &mut Fencer>,
+    mut bout: ResMut<'_, Bout>,
+    mut rules: ResMut<'_, RightOfWay>,
+    mut banner: ResMut<'_, Banner>,
+) {
+    for mut fencer in &mut fencers {
+        fencer.action = Action::Guard;
+    }
+    bout.engaged = false;
+    bout.pause = Timer::from_seconds(EN_GARDE_SECS, TimerMode::Once);
+    *rules = RightOfWay::default();
+    banner.show("En garde!");
+}
+
+/// Starts the phrase once the fencers have stood en garde for a moment
+fn allez(
+    mut bout: ResMut<'_, Bout>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<FencingPhase>>,
+    time: Res<'_, Time>,
+) {
+    if bout.pause.tick(time.delta()).just_finished() {
+        banner.show("Allez!");
+        next_phase.set(FencingPhase::Fencing);
+    }
+}
+
+/// Watches the blades' colliders: whether the two blades are crossed, and a lunging fencer's
+/// blade landing on their opponent's torso for a touch
+fn watch_blades(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    blades: Query<'_, '_, &Blade>,
+    fencers: Query<'_, '_, &Fencer>,
+    mut bout: ResMut<'_, Bout>,
+    mut rules: ResMut<'_, RightOfWay>,
+    time: Res<'_, Time>,
+) {
+    for collision in collisions.read() {
+        let (first, second, started) = match *collision {
+            CollisionEvent::Started(first, second, _) => (first, second, true),
+            CollisionEvent::Stopped(first, second, _) => (first, second, false),
+        };
+        if blades.contains(first) && blades.contains(second) {
+            bout.engaged = started;
+            continue;
+        }
+        if !started {
+            continue;
+        }
+        let (blade, body) = if blades.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let (Ok(blade), Ok(target)) = (blades.get(blade), fencers.get(body)) else {
+            continue;
+        };
+        let lunging = fencers
+            .iter()
+            .any(|fencer| fencer.side == blade.side && fencer.action.lunging());
+        if target.side != blade.side && lunging {
+            rules.touch(blade.side, time.elapsed_secs());
+        }
+    }
+}
+
+/// Knocks an attack aside when a fencer's parry meets the lunging blade, ending the lunge and
+/// handing the parrying fencer priority for their riposte
+fn check_parries(
+    mut fencers: Query<'_, '_, &mut Fencer>,
+    bout: Res<'_, Bout>,
+    mut rules: ResMut<'_, RightOfWay>,
+    mut banner: ResMut<'_, Banner>,
+    lineup: Res<'_, Lineup>,
+    time: Res<'_, Time>,
+) {
+    if !bout.engaged || rules.touched() {
+        return;
+    }
+    let Some(parrier) = fencers
+        .iter()
+        .find(|fencer| fencer.action.parrying())
+        .map(|fencer| fencer.side)
+    else {
+        return;
+    };
+
+    for mut fencer in &mut fencers {
+        if fencer.side == parrier.opponent() && fencer.action.lunging() {
+            fencer.action = Action::Recover(0.0);
+            rules.parry(parrier, time.elapsed_secs());
+            banner.show(format!("Parry! Riposte, {}", lineup.name(parrier)));
+        }
+    }
+}
+
+/// Everything that changes once the referee gives a call
+#[derive(SystemParam)]
+struct Referee<'w> {
+    /// The bout score
+    score: ResMut<'w, FencingScore>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the bout goes next
+    next_phase: ResMut<'w, NextState<FencingPhase>>,
+    /// Who fences on each end, to name the scorer
+    lineup: Res<'w, Lineup>,
+    /// Rumbles the controllers on a touch
+    feedback: Res<'w, FeedbackSender>,
+}
+
+/// Calls halt once the scoring box has locked out after a touch, awarding it under right of way
+/// and ending the bout on the winning touch
+fn give_call(
+    rules: Res<'_, RightOfWay>,
+    mut bout: ResMut<'_, Bout>,
+    mut referee: Referee<'_>,
+    time: Res<'_, Time>,
+) {
+    let Some(call) = rules.call(time.elapsed_secs()) else {
+        return;
+    };
+    bout.last_call = Some(call);
+
+    let Some(scorer) = call.scorer() else {
+        referee.banner.show(call.label());
+        referee.next_phase.set(FencingPhase::Halt);
+        return;
+    };
+    referee.feedback.send(GameEvent::Rumble {
+        intensity: TOUCH_RUMBLE,
+        millis: RUMBLE_MILLIS,
+    });
+    let name = referee.lineup.name(scorer);
+    if referee.score.touch_scored(scorer) {
+        referee.banner.show(format!("{name} wins the bout!"));
+        referee.next_phase.set(FencingPhase::BoutOver);
+        return;
+    }
+    referee.banner.show(format!(
+        "{}, {name}! {}-{}",
+        call.label(),
+        referee.score.touches(scorer),
+        referee.score.touches(scorer.opponent())
+    ));
+    referee.next_phase.set(FencingPhase::Halt);
+}
+
+/// Restarts the halt after a touch
+fn reset_pause(mut bout: ResMut<'_, Bout>) {
+    bout.pause = Timer::from_seconds(HALT_SECS, TimerMode::Once);
+}
+
+/// Sends the fencers back en garde once the referee has given the call
+fn restart_phrase(
+    mut bout: ResMut<'_, Bout>,
+    mut next_phase: ResMut<'_, NextState<FencingPhase>>,
+    time: Res<'_, Time>,
+) {
+    if bout.pause.tick(time.delta()).just_finished() {
+        next_phase.set(FencingPhase::EnGarde);
+    }
+}
+
+/// Starts a fresh bout whenever a second controller pairs or leaves
+fn new_bout_for_lineup(mut requests: EventWriter<'_, NewBout>) {
+    requests.send(NewBout);
+}
+
+/// Starts the bout over at nil all
+fn start_new_bout(
+    mut requests: EventReader<'_, '_, NewBout>,
+    mut score: ResMut<'_, FencingScore>,
+    mut bout: ResMut<'_, Bout>,
+    mut next_phase: ResMut<'_, NextState<FencingPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+    *score = FencingScore::default();
+    bout.last_call = None;
+    next_phase.set(FencingPhase::EnGarde);
+}
+
+/// Lights a lamp over the fencer holding priority, like a referee's raised hand
+fn draw_priority(
+    mut gizmos: Gizmos<'_, '_>,
+    fencers: Query<'_, '_, (&Transform, &Fencer)>,
+    rules: Res<'_, RightOfWay>,
+) {
+    let Some(holder) = rules.priority else {
+        return;
+    };
+    for (transform, fencer) in &fencers {
+        if fencer.side == holder {
+            let color = match holder {
+                Side::Left => Color::srgb(0.3, 0.5, 1.0),
+                Side::Right => Color::srgb(1.0, 0.3, 0.3),
+            };
+            gizmos.sphere(
+                Isometry3d::from_translation(transform.translation + Vec3::Y * 0.95),
+                0.08,
+                color,
+            );
+        }
+    }
+}
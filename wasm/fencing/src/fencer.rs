@@ -0,0 +1,322 @@
+//! The fencers: their bodies, which are the target area touches land on, their blades, and the
+//! lunges and parries they make with them
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, RigidBody, Sensor};
+use serde::{Deserialize, Serialize};
+use spjorts_core::spectator::is_playing;
+
+use crate::{phase::FencingPhase, piste::Side, rules::RightOfWay};
+
+/// Height of the middle of a fencer's torso above the piste
+const TORSO_HEIGHT: f32 = 1.2;
+/// Radius of a fencer's torso, which is the target area
+const TORSO_RADIUS: f32 = 0.22;
+/// Half the height of the straight part of a fencer's torso
+const TORSO_HALF_HEIGHT: f32 = 0.3;
+/// Height of the sword hand above the middle of the torso
+const HAND_RISE: f32 = -0.05;
+/// How far in front of the torso the sword hand is held en garde
+const ARM_REACH: f32 = 0.35;
+/// How much further the arm reaches at the full stretch of a lunge
+const ARM_EXTENSION: f32 = 0.25;
+/// Length of a blade, from the guard to the point
+pub const BLADE_LENGTH: f32 = 0.8;
+/// Half the thickness of a blade's collider, thicker than the steel so quick parries don't slip
+/// through it between physics steps
+const BLADE_HALF_THICKNESS: f32 = 0.03;
+/// How far a lunge carries a fencer toward their opponent
+const LUNGE_REACH: f32 = 1.1;
+/// How long a lunge takes to reach its full stretch, in seconds
+const LUNGE_SECS: f32 = 0.35;
+/// How long a lunge holds at its full stretch before recovering, in seconds
+const LUNGE_HOLD_SECS: f32 = 0.2;
+/// How long a fencer takes to recover back to en garde after a lunge, in seconds
+const RECOVER_SECS: f32 = 0.45;
+/// How long a parry's sweep takes, in seconds
+const PARRY_SECS: f32 = 0.3;
+/// How far a parry sweeps the blade across, in radians
+const PARRY_ARC: f32 = 1.1;
+/// Furthest the blade can be turned off the line to either side, in radians
+pub const MAX_AIM_YAW: f32 = 0.6;
+/// Furthest the blade can be raised or dropped, in radians
+pub const MAX_AIM_PITCH: f32 = 0.6;
+/// How far the point is raised en garde, dropping level as the arm extends, in radians
+const GUARD_RISE: f32 = 0.3;
+
+/// What a fencer is doing
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Action {
+    /// Standing en garde, ready to attack or parry
+    #[default]
+    Guard,
+    /// Lunging at the opponent, with the seconds since it started
+    Lunge(f32),
+    /// Pulling back to en garde after a lunge, with the seconds since it started
+    Recover(f32),
+    /// Sweeping the blade across to knock an attack aside
+    Parry {
+        /// Seconds since the parry started
+        secs: f32,
+        /// Yaw the blade started the sweep from, in radians
+        from: f32,
+        /// Which way the blade sweeps, positive to the fencer's left
+        sweep: f32,
+    },
+}
+
+impl Action {
+    /// How far through a lunge the fencer is stretched, from 0 en garde to 1 at full stretch
+    pub fn extension(&self) -> f32 {
+        match *self {
+            Self::Lunge(secs) => (secs / LUNGE_SECS).min(1.0),
+            Self::Recover(secs) => 1.0 - (secs / RECOVER_SECS).min(1.0),
+            Self::Guard | Self::Parry { .. } => 0.0,
+        }
+    }
+
+    /// Whether the fencer is lunging, so their point can land a touch
+    pub fn lunging(&self) -> bool {
+        matches!(self, Self::Lunge(_))
+    }
+
+    /// Whether the fencer is sweeping their blade across in a parry
+    pub fn parrying(&self) -> bool {
+        matches!(self, Self::Parry { .. })
+    }
+}
+
+/// A fencer on the piste. Their torso is a sensor the opponent's blade touches
+#[derive(Component, Debug)]
+pub struct Fencer {
+    /// The end they fence from
+    pub side: Side,
+    /// Where the blade is pointed, as yaw to the fencer's left and pitch up, in radians
+    pub aim: Vec2,
+    /// What they're doing
+    pub action: Action,
+}
+
+/// A fencer's blade
+#[derive(Component, Debug)]
+pub struct Blade {
+    /// The end of the fencer holding it
+    pub side: Side,
+}
+
+/// A fencer lunging at their opponent
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Lunge {
+    /// The end lunging
+    pub side: Side,
+}
+
+/// A fencer parrying with a sweep of their blade
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Parry {
+    /// The end parrying
+    pub side: Side,
+    /// Which way the blade sweeps, positive to the fencer's left
+    pub sweep: f32,
+}
+
+/// Where a fencer's torso is, stretched some way through a lunge
+fn stance(side: Side, extension: f32) -> Vec3 {
+    // Lunges spring out fast and ease into their full stretch
+    let eased = 1.0 - (1.0 - extension).powi(2);
+    side.home() + Vec3::Y * TORSO_HEIGHT - Vec3::X * side.sign() * LUNGE_REACH * eased
+}
+
+/// Plugin that spawns the fencers and plays out their lunges and parries
+pub struct FencerPlugin;
+
+impl Plugin for FencerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Lunge>()
+            .add_event::<Parry>()
+            .add_systems(Startup, spawn_fencers)
+            .add_systems(
+                Update,
+                (
+                    (
+                        start_actions.run_if(in_state(FencingPhase::Fencing)),
+                        advance_actions,
+                    )
+                        .run_if(is_playing),
+                    pose_fencers,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Spawns each fencer's body and blade on their en garde line. Everything is moved by hand, so
+/// the kinematic bodies are set to report touching one another
+fn spawn_fencers(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let torso = meshes.add(Capsule3d::new(TORSO_RADIUS, TORSO_HALF_HEIGHT * 2.0));
+    let head = meshes.add(Sphere::new(0.14));
+    let leg = meshes.add(Capsule3d::new(0.08, 0.7));
+    let steel = meshes.add(Cuboid::new(0.012, 0.012, BLADE_LENGTH));
+    let bell = meshes.add(Sphere::new(0.06));
+    let whites = materials.add(Color::srgb(0.95, 0.95, 0.92));
+    let mask = materials.add(Color::srgb(0.12, 0.12, 0.14));
+    let metal = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.8, 0.82, 0.85),
+        metallic: 0.9,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let kinematic = ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_KINEMATIC;
+
+    for (side, lame) in [
+        (Side::Left, Color::srgb(0.2, 0.35, 0.85)),
+        (Side::Right, Color::srgb(0.85, 0.2, 0.2)),
+    ] {
+        commands
+            .spawn((
+                Mesh3d(torso.clone()),
+                MeshMaterial3d(materials.add(lame)),
+                Transform::from_translation(stance(side, 0.0)),
+                RigidBody::KinematicPositionBased,
+                Collider::capsule_y(TORSO_HALF_HEIGHT, TORSO_RADIUS),
+                Sensor,
+                kinematic,
+                Fencer {
+                    side,
+                    aim: Vec2::ZERO,
+                    action: Action::Guard,
+                },
+            ))
+            .with_children(|fencer| {
+                fencer.spawn((
+                    Mesh3d(head.clone()),
+                    MeshMaterial3d(mask.clone()),
+                    Transform::from_xyz(0.0, TORSO_HALF_HEIGHT + TORSO_RADIUS + 0.12, 0.0),
+                ));
+                for offset in [-0.12, 0.12] {
+                    fencer.spawn((
+                        Mesh3d(leg.clone()),
+                        MeshMaterial3d(whites.clone()),
+                        Transform::from_xyz(offset, -TORSO_HEIGHT / 2.0 - 0.1, 0.0),
+                    ));
+                }
+            });
+
+        commands
+            .spawn((
+                Mesh3d(steel.clone()),
+                MeshMaterial3d(metal.clone()),
+                Transform::default(),
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(
+                    BLADE_HALF_THICKNESS,
+                    BLADE_HALF_THICKNESS,
+                    BLADE_LENGTH / 2.0,
+                ),
+                ActiveEvents::COLLISION_EVENTS,
+                kinematic,
+                Blade { side },
+            ))
+            .with_child((
+                Mesh3d(bell.clone()),
+                MeshMaterial3d(metal.clone()),
+                Transform::from_xyz(0.0, 0.0, BLADE_LENGTH / 2.0),
+            ));
+    }
+}
+
+/// Starts lunges and parries for fencers standing en garde. A lunge takes priority under the
+/// rules of right of way unless it's a counter-attack
+fn start_actions(
+    mut lunges: EventReader<'_, '_, Lunge>,
+    mut parries: EventReader<'_, '_, Parry>,
+    mut fencers: Query<'_, '_, &mut Fencer>,
+    mut rules: ResMut<'_, RightOfWay>,
+    time: Res<'_, Time>,
+) {
+    for lunge in lunges.read() {
+        let Some(mut fencer) = fencers.iter_mut().find(|fencer| fencer.side == lunge.side) else {
+            continue;
+        };
+        if fencer.action == Action::Guard {
+            fencer.action = Action::Lunge(0.0);
+            rules.lunge(lunge.side, time.elapsed_secs());
+        }
+    }
+    for parry in parries.read() {
+        let Some(mut fencer) = fencers.iter_mut().find(|fencer| fencer.side == parry.side) else {
+            continue;
+        };
+        if fencer.action == Action::Guard {
+            fencer.action = Action::Parry {
+                secs: 0.0,
+                from: fencer.aim.x,
+                sweep: parry.sweep.signum(),
+            };
+        }
+    }
+}
+
+/// Plays each fencer's lunge or parry on, recovering from a lunge once it has held at full
+/// stretch and losing its priority if it fell short
+fn advance_actions(
+    mut fencers: Query<'_, '_, &mut Fencer>,
+    mut rules: ResMut<'_, RightOfWay>,
+    time: Res<'_, Time>,
+) {
+    let delta = time.delta_secs();
+    for mut fencer in &mut fencers {
+        fencer.action = match fencer.action {
+            Action::Lunge(secs) if secs + delta >= LUNGE_SECS + LUNGE_HOLD_SECS => {
+                rules.fall_short(fencer.side);
+                Action::Recover(0.0)
+            }
+            Action::Lunge(secs) => Action::Lunge(secs + delta),
+            Action::Recover(secs) if secs + delta >= RECOVER_SECS => Action::Guard,
+            Action::Recover(secs) => Action::Recover(secs + delta),
+            Action::Parry { secs, .. } if secs + delta >= PARRY_SECS => Action::Guard,
+            Action::Parry { secs, from, sweep } => Action::Parry {
+                secs: secs + delta,
+                from,
+                sweep,
+            },
+            Action::Guard => Action::Guard,
+        };
+    }
+}
+
+/// Moves each fencer through their lunge and points their blade: where they aim it en garde,
+/// levelling out as the arm extends, and swept across the line through a parry
+fn pose_fencers(
+    mut fencers: Query<'_, '_, (&mut Transform, &Fencer), Without<Blade>>,
+    mut blades: Query<'_, '_, (&mut Transform, &Blade), Without<Fencer>>,
+) {
+    for (mut body, fencer) in &mut fencers {
+        let extension = fencer.action.extension();
+        body.translation = stance(fencer.side, extension);
+
+        let (yaw, pitch) = match fencer.action {
+            Action::Parry { secs, from, sweep } => {
+                let swept = from + sweep * PARRY_ARC * (secs / PARRY_SECS).min(1.0);
+                (swept.clamp(-PARRY_ARC, PARRY_ARC), 0.0)
+            }
+            _ => (fencer.aim.x, fencer.aim.y + GUARD_RISE * (1.0 - extension)),
+        };
+        let rotation = fencer.side.facing() * Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+        let forward = -Vec3::X * fencer.side.sign();
+        let hand = body.translation
+            + Vec3::Y * HAND_RISE
+            + forward * (ARM_REACH + ARM_EXTENSION * extension);
+
+        for (mut transform, blade) in &mut blades {
+            if blade.side == fencer.side {
+                transform.rotation = rotation;
+                transform.translation = hand + rotation * Vec3::NEG_Z * BLADE_LENGTH / 2.0;
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Bevy fencing game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::plugin::{NoUserData, RapierPhysicsPlugin};
+use bout::{BoutPlugin, NewBout};
+use fencer::{Fencer, FencerPlugin, Lunge, Parry, MAX_AIM_PITCH, MAX_AIM_YAW};
+use lineup::{Lineup, LineupPlugin};
+use phase::{FencingPhase, FencingPhasePlugin};
+use piste::PistePlugin;
+use scoreboard::ScoreboardPlugin;
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    ActionReader,
+};
+
+pub mod bout;
+pub mod fencer;
+pub mod lineup;
+pub mod phase;
+pub mod piste;
+pub mod rules;
+pub mod score;
+pub mod scoreboard;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(FencingPhasePlugin)
+    .add_plugins(LineupPlugin)
+    .add_plugins(PistePlugin)
+    .add_plugins(FencerPlugin)
+    .add_plugins(BoutPlugin)
+    .add_plugins(ScoreboardPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.13)))
+    .init_resource::<Grips>()
+    .add_systems(Update, handle_input.run_if(is_playing));
+});
+
+/// Watches each end's controller for lunges and parries, left first. Both controllers are read
+/// at once, so either fencer can act at any moment
+#[derive(Resource, Debug, Default)]
+pub struct Grips([GestureDetector; 2]);
+
+/// Everything input handling sends or reads besides the fencers
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Lunges to start
+    lunges: EventWriter<'w, Lunge>,
+    /// Parries to start
+    parries: EventWriter<'w, Parry>,
+    /// Requests to start over
+    new_bout: EventWriter<'w, NewBout>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+    /// Player settings rotations are adjusted by
+    settings: Res<'w, GameSettings>,
+}
+
+/// Reads controller input from both paired controllers: each player's rotation points their
+/// blade, a thrust lunges and a sweep or twist parries, and A starts a new bout once one is over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut grips: ResMut<'_, Grips>,
+    mut fencers: Query<'_, '_, &mut Fencer>,
+    mut effects: InputEffects<'_>,
+    phase: Res<'_, State<FencingPhase>>,
+    lineup: Res<'_, Lineup>,
+    time: Res<'_, Time>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (player, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_bout.send(NewBout);
+            }
+            JsMessage::ButtonA if *phase.get() == FencingPhase::BoutOver => {
+                effects.new_bout.send(NewBout);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let Some(side) = lineup.side_of(player) else {
+                    continue;
+                };
+                let orientation @ Orientation { pitch, yaw, .. } =
+                    effects.settings.apply_rotation(orientation);
+                if let Some(mut fencer) = fencers.iter_mut().find(|fencer| fencer.side == side) {
+                    fencer.aim = Vec2::new(
+                        yaw.clamp(-MAX_AIM_YAW, MAX_AIM_YAW),
+                        pitch.clamp(-MAX_AIM_PITCH, MAX_AIM_PITCH),
+                    );
+                }
+                let Some(detected) = grips.0[side.index()].update(orientation, time.elapsed_secs())
+                else {
+                    continue;
+                };
+                let turned = detected.turned;
+                match detected.gesture {
+                    // A thrust dips the point straight at the opponent
+                    Gesture::Swing | Gesture::Flick if turned.pitch.abs() >= turned.yaw.abs() => {
+                        effects.lunges.send(Lunge { side });
+                    }
+                    // A sweep carries the blade across the line, and so does turning the wrist
+                    // over
+                    _ => {
+                        effects.parries.send(Parry {
+                            side,
+                            sweep: turned.yaw,
+                        });
+                    }
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
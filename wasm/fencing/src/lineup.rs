@@ -0,0 +1,57 @@
+//! Who fences on each end of the piste: the first player always takes the left, and the right
+//! goes to the second player's controller once one is paired
+
+use bevy::prelude::*;
+use spjorts_core::players::PlayerRegistry;
+
+use crate::piste::Side;
+
+/// Which player fences on each end, left first. `None` is an end no controller is paired to yet
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lineup([Option<usize>; 2]);
+
+impl Default for Lineup {
+    fn default() -> Self {
+        Self([Some(0), None])
+    }
+}
+
+impl Lineup {
+    /// The player fencing on an end, or `None` if no one is
+    pub fn player(&self, side: Side) -> Option<usize> {
+        self.0[side.index()]
+    }
+
+    /// The end a player fences on, if they're fencing
+    pub fn side_of(&self, player: usize) -> Option<Side> {
+        Side::BOTH
+            .into_iter()
+            .find(|side| self.player(*side) == Some(player))
+    }
+
+    /// What to call the fencer on an end
+    pub fn name(&self, side: Side) -> String {
+        match self.player(side) {
+            Some(player) => format!("Player {}", player + 1),
+            None => format!("Player {}", side.index() + 1),
+        }
+    }
+}
+
+/// Plugin that keeps the [`Lineup`] in step with the players
+pub struct LineupPlugin;
+
+impl Plugin for LineupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lineup>().add_systems(
+            Update,
+            sync_lineup.run_if(resource_changed::<PlayerRegistry>),
+        );
+    }
+}
+
+/// Gives the right end to a second player when there is one
+fn sync_lineup(registry: Res<'_, PlayerRegistry>, mut lineup: ResMut<'_, Lineup>) {
+    let right = (registry.count() >= 2).then_some(1);
+    lineup.set_if_neq(Lineup([Some(0), right]));
+}
@@ -0,0 +1,27 @@
+//! Phases a fencing bout moves through, from the call of en garde to the last touch
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the bout is in the flow of a touch
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FencingPhase {
+    /// The fencers are back on their en garde lines, waiting for the referee to start them
+    #[default]
+    EnGarde,
+    /// The referee has called "Allez!" and the fencers are free to attack
+    Fencing,
+    /// The referee has called halt over a touch and is giving the call
+    Halt,
+    /// A fencer has reached the winning touch and the final score is up
+    BoutOver,
+}
+
+/// Plugin that tracks which phase the bout is in
+pub struct FencingPhasePlugin;
+
+impl Plugin for FencingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<FencingPhase>();
+    }
+}
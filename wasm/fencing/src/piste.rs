@@ -0,0 +1,154 @@
+//! The piste: the strip the bout is fenced on, its centre and en garde lines, the warning zones
+//! at either end and the hall around it
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Half the length of the piste, from the centre line to an end
+pub const PISTE_HALF_LENGTH: f32 = 7.0;
+/// Half the width of the piste
+const PISTE_HALF_WIDTH: f32 = 0.9;
+/// How far either side of the centre line fencers stand en garde. Closer than a real piste so a
+/// lunge from the line reaches
+pub const EN_GARDE_X: f32 = 1.3;
+/// How far from either end the warning zone starts
+const WARNING_LENGTH: f32 = 2.0;
+/// Width of the lines painted across the piste
+const LINE_WIDTH: f32 = 0.05;
+/// Half the size of the hall floor around the piste
+const FLOOR_HALF_SIZE: f32 = 12.0;
+
+/// One end of the piste
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The fencer on the left of the camera
+    Left,
+    /// The fencer on the right of the camera
+    Right,
+}
+
+impl Side {
+    /// Both ends, left first
+    pub const BOTH: [Self; 2] = [Self::Left, Self::Right];
+
+    /// The fencer across the centre line from this one
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Which way along x this end lies from the centre line, negative for the left
+    pub fn sign(self) -> f32 {
+        match self {
+            Self::Left => -1.0,
+            Self::Right => 1.0,
+        }
+    }
+
+    /// Index of the end, left first, for per-side arrays
+    pub fn index(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+        }
+    }
+
+    /// Where a fencer on this end stands en garde
+    pub fn home(self) -> Vec3 {
+        Vec3::X * EN_GARDE_X * self.sign()
+    }
+
+    /// Which way a fencer on this end faces, turning forward toward their opponent
+    pub fn facing(self) -> Quat {
+        Quat::from_rotation_y(FRAC_PI_2 * self.sign())
+    }
+}
+
+/// Plugin that lays out the piste and the hall
+pub struct PistePlugin;
+
+impl Plugin for PistePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_piste);
+    }
+}
+
+/// Spawns the hall floor, the piste with its lines and warning zones, the camera and the light
+fn setup_piste(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(FLOOR_HALF_SIZE * 2.0, FLOOR_HALF_SIZE * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.45, 0.32, 0.22))),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(PISTE_HALF_LENGTH * 2.0, PISTE_HALF_WIDTH * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.55, 0.57, 0.6))),
+        Transform::from_xyz(0.0, 0.01, 0.0),
+        Name::new("Piste"),
+    ));
+
+    let warning = materials.add(Color::srgb(0.75, 0.25, 0.2));
+    let zone = meshes.add(
+        Plane3d::default()
+            .mesh()
+            .size(WARNING_LENGTH, PISTE_HALF_WIDTH * 2.0),
+    );
+    for side in Side::BOTH {
+        commands.spawn((
+            Mesh3d(zone.clone()),
+            MeshMaterial3d(warning.clone()),
+            Transform::from_xyz(
+                (PISTE_HALF_LENGTH - WARNING_LENGTH / 2.0) * side.sign(),
+                0.015,
+                0.0,
+            ),
+        ));
+    }
+
+    let white = materials.add(Color::WHITE);
+    let line = meshes.add(
+        Plane3d::default()
+            .mesh()
+            .size(LINE_WIDTH, PISTE_HALF_WIDTH * 2.0),
+    );
+    for x in [0.0, -EN_GARDE_X, EN_GARDE_X] {
+        commands.spawn((
+            Mesh3d(line.clone()),
+            MeshMaterial3d(white.clone()),
+            Transform::from_xyz(x, 0.02, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.8, 4.2).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
@@ -0,0 +1,121 @@
+//! Right of way: which fencer has priority as attacks, counter-attacks and parries play out, and
+//! who the point goes to once the scoring box locks out after a touch
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::piste::Side;
+
+/// How long after the first touch an opponent's touch still registers, in seconds
+pub const LOCKOUT_SECS: f32 = 0.3;
+/// How close together two lunges have to start to be called a simultaneous attack, in seconds
+const SIMULTANEOUS_SECS: f32 = 0.12;
+
+/// The referee's call once the scoring box has locked out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Call {
+    /// Only one fencer touched, so the touch is theirs
+    Touch(Side),
+    /// Both touched and the point goes to the fencer with priority
+    Priority(Side),
+    /// Both touched attacking at the same time, so neither scores
+    Simultaneous,
+}
+
+impl Call {
+    /// The fencer the call awards the touch to, if either
+    pub fn scorer(self) -> Option<Side> {
+        match self {
+            Self::Touch(side) | Self::Priority(side) => Some(side),
+            Self::Simultaneous => None,
+        }
+    }
+
+    /// What the referee calls out
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Touch(_) => "Touch",
+            Self::Priority(_) => "Double touch, on priority",
+            Self::Simultaneous => "Simultaneous! No touch",
+        }
+    }
+}
+
+/// Priority and the touches landed in the current phrase of fencing
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RightOfWay {
+    /// The fencer whose attack or riposte has priority, `None` when neither has
+    pub priority: Option<Side>,
+    /// When the attack with priority started, in seconds of game time
+    attack_at: f32,
+    /// Which fencers have touched, left first
+    touches: [bool; 2],
+    /// When the first touch landed, starting the lockout
+    first_touch_at: Option<f32>,
+}
+
+impl RightOfWay {
+    /// Records a fencer starting a lunge. An attack into an opponent without priority takes it,
+    /// while one that starts straight after the opponent's is called simultaneous and neither
+    /// holds priority. Lunging into an attack with priority is a counter-attack and leaves it
+    pub fn lunge(&mut self, side: Side, at: f32) {
+        match self.priority {
+            None => {
+                self.priority = Some(side);
+                self.attack_at = at;
+            }
+            Some(holder)
+                if holder == side.opponent() && at - self.attack_at < SIMULTANEOUS_SECS =>
+            {
+                self.priority = None;
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Records a fencer parrying their opponent's blade, earning priority for the riposte
+    pub fn parry(&mut self, side: Side, at: f32) {
+        self.priority = Some(side);
+        self.attack_at = at;
+    }
+
+    /// Records a fencer's lunge ending, losing any priority it held if it fell short of a touch
+    pub fn fall_short(&mut self, side: Side) {
+        if self.priority == Some(side) && !self.touches[side.index()] {
+            self.priority = None;
+        }
+    }
+
+    /// Records a fencer's blade landing on their opponent, once per phrase, ignored after the
+    /// lockout
+    pub fn touch(&mut self, side: Side, at: f32) {
+        if self
+            .first_touch_at
+            .is_some_and(|first| at - first > LOCKOUT_SECS)
+        {
+            return;
+        }
+        self.touches[side.index()] = true;
+        self.first_touch_at.get_or_insert(at);
+    }
+
+    /// Whether a touch has landed and the box is waiting on the lockout
+    pub fn touched(&self) -> bool {
+        self.first_touch_at.is_some()
+    }
+
+    /// The call, once the lockout after the first touch has run out
+    pub fn call(&self, at: f32) -> Option<Call> {
+        if at - self.first_touch_at? < LOCKOUT_SECS {
+            return None;
+        }
+        Some(match self.touches {
+            [true, true] => match self.priority {
+                Some(side) => Call::Priority(side),
+                None => Call::Simultaneous,
+            },
+            [true, false] => Call::Touch(Side::Left),
+            _ => Call::Touch(Side::Right),
+        })
+    }
+}
@@ -0,0 +1,39 @@
+//! Bout scoring: the first fencer to five touches wins
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::piste::Side;
+
+/// Touches a fencer has to land to win the bout
+pub const TOUCHES_TO_WIN: u8 = 5;
+
+/// Touches each fencer has landed, left first
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FencingScore {
+    /// Touches landed in the bout
+    touches: [u8; 2],
+    /// The fencer that won the bout, once one has
+    winner: Option<Side>,
+}
+
+impl FencingScore {
+    /// Touches a fencer has landed
+    pub fn touches(&self, side: Side) -> u8 {
+        self.touches[side.index()]
+    }
+
+    /// The fencer that won the bout, once one has
+    pub fn winner(&self) -> Option<Side> {
+        self.winner
+    }
+
+    /// Awards a touch to a fencer, returning whether it won the bout
+    pub fn touch_scored(&mut self, side: Side) -> bool {
+        self.touches[side.index()] += 1;
+        if self.touches[side.index()] >= TOUCHES_TO_WIN {
+            self.winner = Some(side);
+        }
+        self.winner.is_some()
+    }
+}
@@ -0,0 +1,132 @@
+//! The scoreboard: each fencer's touches, who holds priority and the referee's last call on the
+//! shared scorecard HUD, plus the bout result sent back to the page
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    FeedbackSender,
+};
+
+use crate::{
+    bout::Bout,
+    lineup::Lineup,
+    phase::FencingPhase,
+    piste::Side,
+    rules::{Call, RightOfWay},
+    score::{FencingScore, TOUCHES_TO_WIN},
+};
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct FencingSnapshot<'a> {
+    /// Touches each fencer has landed
+    score: &'a FencingScore,
+    /// The fencer holding priority, if either
+    priority: Option<Side>,
+    /// The referee's last call
+    last_call: Option<Call>,
+    /// Where the bout is at
+    phase: FencingPhase,
+}
+
+/// Plugin that shows the score on the scorecard HUD and reports the result
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(FencingPhase::BoutOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(FencingPhase::BoutOver), hide_final_card);
+    }
+}
+
+/// Fills in the scorecard HUD with each fencer's touches, marking the one with priority
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, FencingScore>,
+    rules: Res<'_, RightOfWay>,
+    lineup: Res<'_, Lineup>,
+) {
+    let rows = Side::BOTH
+        .into_iter()
+        .map(|side| {
+            let priority = if rules.priority == Some(side) {
+                " (priority)"
+            } else {
+                ""
+            };
+            format!("{}: {}{priority}", lineup.name(side), score.touches(side))
+        })
+        .collect();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Fencing, first to {TOUCHES_TO_WIN} touches"),
+        rows,
+        footer: "Thrust to lunge, sweep or twist to parry".to_string(),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Shows the touches each fencer landed once the bout is over
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, FencingScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    let mut lines = vec!["Final Score".to_string()];
+    if let Some(winner) = score.winner() {
+        lines.push(format!("{} wins!", lineup.name(winner)));
+    }
+    for side in Side::BOTH {
+        lines.push(format!("{}: {}", lineup.name(side), score.touches(side)));
+    }
+    lines.push("Press A to fence again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final score when a new bout starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends the touches each player landed back to the page once the bout is over, so it can
+/// submit them to the server
+fn submit_result(
+    score: Res<'_, FencingScore>,
+    lineup: Res<'_, Lineup>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = Side::BOTH
+        .into_iter()
+        .filter(|side| lineup.player(*side).is_some())
+        .map(|side| u32::from(score.touches(side)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    score: Res<'_, FencingScore>,
+    rules: Res<'_, RightOfWay>,
+    bout: Res<'_, Bout>,
+    phase: Res<'_, State<FencingPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&FencingSnapshot {
+        score: &score,
+        priority: rules.priority,
+        last_call: bout.last_call,
+        phase: *phase.get(),
+    });
+}
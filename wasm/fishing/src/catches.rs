@@ -0,0 +1,308 @@
+//! Catches: each angler gets three casts in turn, every fish they land adding its weight to
+//! their score on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{fish::Fish, line::Line, phase::FishingPhase};
+
+/// Casts each angler gets
+pub const CASTS_PER_PLAYER: usize = 3;
+
+/// A cast is over, with whatever it landed
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct CastOver {
+    /// The fish landed, or `None` if nothing was
+    pub caught: Option<Fish>,
+}
+
+/// Asks for the game to be started over from the first cast
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Every angler's catches and how many casts the angler up has made
+#[derive(Resource, Serialize, Debug, Clone, PartialEq)]
+pub struct Catches {
+    /// The fish each angler has landed
+    landed: Vec<Vec<Fish>>,
+    /// Casts the angler up has made so far
+    casts: usize,
+    /// How the last cast went, if one has been made
+    last: Option<CastResult>,
+}
+
+/// How a cast went, as shown on the HUD
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+enum CastResult {
+    /// Landed this fish
+    Landed(Fish),
+    /// Came back empty
+    Empty,
+}
+
+impl Default for Catches {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Catches {
+    /// Starts a game for a number of anglers with nothing caught
+    pub fn new(players: usize) -> Self {
+        Self {
+            landed: vec![Vec::new(); players.max(1)],
+            casts: 0,
+            last: None,
+        }
+    }
+
+    /// How many anglers are in the game
+    pub fn players(&self) -> usize {
+        self.landed.len()
+    }
+
+    /// A player's score: the total weight of everything they've landed, in grams
+    pub fn score(&self, player: usize) -> u32 {
+        self.landed
+            .get(player)
+            .map(|fish| fish.iter().map(Fish::grams).sum())
+            .unwrap_or_default()
+    }
+
+    /// The heaviest fish a player has landed, if they've landed any
+    pub fn biggest(&self, player: usize) -> Option<Fish> {
+        self.landed
+            .get(player)?
+            .iter()
+            .copied()
+            .max_by(|a, b| a.weight.total_cmp(&b.weight))
+    }
+
+    /// Casts the angler up has made so far
+    pub fn casts(&self) -> usize {
+        self.casts
+    }
+
+    /// Adds whatever a cast landed to a player's catches, returning whether it was their last
+    /// cast
+    pub fn record(&mut self, player: usize, caught: Option<Fish>) -> bool {
+        if let (Some(fish), Some(landed)) = (caught, self.landed.get_mut(player)) {
+            landed.push(fish);
+        }
+        self.last = Some(caught.map_or(CastResult::Empty, CastResult::Landed));
+        self.casts += 1;
+        if self.casts < CASTS_PER_PLAYER {
+            return false;
+        }
+        self.casts = 0;
+        true
+    }
+
+    /// Players with the heaviest catch, more than one if they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.score(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.score(*player) == best)
+            .collect()
+    }
+}
+
+/// Weight in kilograms to show for a number of grams
+fn kilograms(grams: u32) -> String {
+    format!("{:.2} kg", grams as f32 / 1000.0)
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct FishingSnapshot<'a> {
+    /// Angler up
+    player: usize,
+    /// Every angler's catches and the casts made
+    catches: &'a Catches,
+    /// The line out to the fish being fought, if one is
+    line: &'a Line,
+    /// Where the game is at
+    phase: FishingPhase,
+}
+
+/// Plugin that weighs catches and shows them on the scorecard HUD
+pub struct CatchesPlugin;
+
+impl Plugin for CatchesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Catches>()
+            .add_event::<CastOver>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    score_cast,
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(FishingPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(FishingPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of anglers changes
+fn fit_game(turns: Res<'_, TurnManager>, mut catches: ResMut<'_, Catches>) {
+    if catches.players() != turns.players() {
+        *catches = Catches::new(turns.players());
+    }
+}
+
+/// Adds whatever a finished cast landed to the catches of the angler who made it, handing the
+/// rod to the next angler once they're out of casts and ending the game once everyone is
+fn score_cast(
+    mut casts: EventReader<'_, '_, CastOver>,
+    mut catches: ResMut<'_, Catches>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<FishingPhase>>,
+) {
+    let Some(cast) = casts.read().last().copied() else {
+        return;
+    };
+
+    if !catches.record(turns.current(), cast.caught) {
+        next_phase.set(FishingPhase::Casting);
+        return;
+    }
+    turns.advance();
+    next_phase.set(if turns.round() >= 1 {
+        FishingPhase::GameOver
+    } else {
+        FishingPhase::Casting
+    });
+}
+
+/// Starts the game over from the first angler's first cast
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut catches: ResMut<'_, Catches>,
+    mut line: ResMut<'_, Line>,
+    mut next_phase: ResMut<'_, NextState<FishingPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *catches = Catches::new(turns.players());
+    *line = Line::default();
+    next_phase.set(FishingPhase::Casting);
+}
+
+/// Fills in the scorecard HUD with every angler's catch and which cast is up, with the line out
+/// while a fish is on
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    catches: Res<'_, Catches>,
+    line: Res<'_, Line>,
+    turns: Res<'_, TurnManager>,
+    phase: Res<'_, State<FishingPhase>>,
+) {
+    let rows = (0..catches.players())
+        .map(|player| {
+            let count = catches.landed[player].len();
+            format!(
+                "Player {}: {} ({count} fish)",
+                player + 1,
+                kilograms(catches.score(player))
+            )
+        })
+        .collect();
+    let last = match (phase.get(), catches.last) {
+        (FishingPhase::Reeling, _) => format!("\nLine out: {:.1} m", line.out),
+        (_, Some(CastResult::Landed(fish))) => {
+            format!("\nLast cast: {} {}", kilograms(fish.grams()), fish.name)
+        }
+        (_, Some(CastResult::Empty)) => "\nLast cast: nothing".to_string(),
+        (_, None) => String::new(),
+    };
+
+    hud.set_if_neq(ScorecardHud {
+        title: "Fishing".to_string(),
+        rows,
+        footer: format!(
+            "Player {} on cast {} of {CASTS_PER_PLAYER}{last}",
+            turns.current() + 1,
+            (catches.casts() + 1).min(CASTS_PER_PLAYER)
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Weighs every angler's catch once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, catches: Res<'_, Catches>) {
+    let mut lines = vec!["Final Weigh-In".to_string()];
+    match catches.leaders()[..] {
+        [winner] if catches.players() > 1 => lines.push(format!("Player {} wins!", winner + 1)),
+        [_, _, ..] => lines.push("It's a tie!".to_string()),
+        _ => {}
+    }
+    for player in 0..catches.players() {
+        let biggest = catches.biggest(player).map_or(String::new(), |fish| {
+            format!(", biggest a {} {}", kilograms(fish.grams()), fish.name)
+        });
+        lines.push(format!(
+            "Player {}: {}{biggest}",
+            player + 1,
+            kilograms(catches.score(player))
+        ));
+    }
+    lines.push("Press A to fish again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final weigh-in when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every angler's catch in grams once the game is over, so the page can submit them to
+/// the server
+fn submit_result(catches: Res<'_, Catches>, feedback: Res<'_, FeedbackSender>) {
+    feedback.send(GameEvent::GameResult {
+        players: catches.players(),
+        scores: (0..catches.players())
+            .map(|player| catches.score(player))
+            .collect(),
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    catches: Res<'_, Catches>,
+    line: Res<'_, Line>,
+    phase: Res<'_, State<FishingPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&FishingSnapshot {
+        player: turns.current(),
+        catches: &catches,
+        line: &line,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,119 @@
+//! The fish in the lake: which can bite at what distance out, how heavy they run and how hard
+//! they fight once hooked
+
+use serde::Serialize;
+
+/// A kind of fish in the lake
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Species {
+    /// Name shown when one is landed
+    name: &'static str,
+    /// Lightest one can be, in kilograms
+    min_weight: f32,
+    /// Heaviest one can be, in kilograms
+    max_weight: f32,
+    /// How hard it pulls against the line, from 0.0 to 1.0
+    strength: f32,
+    /// How fast it surges against the line, in radians per second
+    temper: f32,
+    /// Shortest cast that reaches water deep enough for it, in meters
+    depth: f32,
+}
+
+/// Every kind of fish in the lake, from the shallows out
+const SPECIES: [Species; 5] = [
+    Species {
+        name: "perch",
+        min_weight: 0.1,
+        max_weight: 0.6,
+        strength: 0.3,
+        temper: 2.5,
+        depth: 0.0,
+    },
+    Species {
+        name: "bass",
+        min_weight: 0.5,
+        max_weight: 2.5,
+        strength: 0.45,
+        temper: 2.0,
+        depth: 8.0,
+    },
+    Species {
+        name: "carp",
+        min_weight: 1.5,
+        max_weight: 8.0,
+        strength: 0.5,
+        temper: 0.8,
+        depth: 14.0,
+    },
+    Species {
+        name: "pike",
+        min_weight: 2.0,
+        max_weight: 10.0,
+        strength: 0.65,
+        temper: 2.2,
+        depth: 18.0,
+    },
+    Species {
+        name: "catfish",
+        min_weight: 4.0,
+        max_weight: 20.0,
+        strength: 0.7,
+        temper: 1.0,
+        depth: 24.0,
+    },
+];
+
+/// Fastest a fish can take line when it runs at full strength, in meters per second
+const RUN_SPEED: f32 = 0.6;
+
+/// A fish on the line
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Fish {
+    /// What kind of fish it is
+    pub name: &'static str,
+    /// How heavy it is, in kilograms
+    pub weight: f32,
+    /// How hard it pulls against the line, from 0.0 to 1.0
+    strength: f32,
+    /// How fast it surges against the line, in radians per second
+    temper: f32,
+}
+
+impl Fish {
+    /// The fish that bites a cast `distance` meters out, picked from every kind deep enough
+    /// there by `kind` and weighed by `size`, both from 0.0 to 1.0. Heavier fish fight harder
+    pub fn bite(distance: f32, kind: f32, size: f32) -> Self {
+        let reachable = SPECIES
+            .iter()
+            .filter(|species| species.depth <= distance)
+            .count()
+            .max(1);
+        let species =
+            SPECIES[((kind.clamp(0.0, 1.0) * reachable as f32) as usize).min(reachable - 1)];
+        let size = size.clamp(0.0, 1.0);
+        Self {
+            name: species.name,
+            weight: species.min_weight + (species.max_weight - species.min_weight) * size,
+            strength: species.strength * (0.8 + 0.4 * size),
+            temper: species.temper,
+        }
+    }
+
+    /// How hard the fish is pulling against the line `fought_for` seconds into the fight, from
+    /// 0.0 to 1.0. It surges and tires in waves, never quite letting up
+    pub fn pull(&self, fought_for: f32) -> f32 {
+        let surge = (fought_for * self.temper).sin() * (fought_for * self.temper * 0.37).cos();
+        (self.strength * (0.7 + 0.45 * surge)).clamp(0.05, 1.0)
+    }
+
+    /// How fast the fish takes line while pulling `pull` hard, in meters per second
+    pub fn run_speed(&self, pull: f32) -> f32 {
+        pull * RUN_SPEED
+    }
+
+    /// Weight of the fish in grams, which is what it scores
+    pub fn grams(&self) -> u32 {
+        (self.weight * 1000.0).round() as u32
+    }
+}
@@ -0,0 +1,442 @@
+//! Bevy fishing game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use catches::{CastOver, CatchesPlugin, NewGame};
+use fish::Fish;
+use line::{reel_in, FightOver, Fought, Line, LinePlugin, Reel};
+use phase::{FishingPhase, FishingPhasePlugin};
+use spjorts_core::{
+    communication::{GameEvent, JsMessage},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader, FeedbackSender,
+};
+use water::{cast_point, rod_tip, Bobber, Rod, WaterPlugin, BOBBER_RADIUS};
+
+pub mod catches;
+pub mod fish;
+pub mod line;
+pub mod phase;
+pub mod water;
+
+/// Meters a cast flies for each radian per second the rod was flicked forward at
+const CAST_SCALE: f32 = 2.0;
+/// Shortest a cast can land, in meters
+pub const MIN_CAST: f32 = 4.0;
+/// Furthest a cast can land, in meters
+pub const MAX_CAST: f32 = 32.0;
+/// Widest a cast can be aimed from straight out, in radians
+const MAX_AIM: f32 = 0.4;
+/// How far the rod tip has to drop during a flick for it to count as a cast, in radians
+const CAST_PITCH: f32 = 0.3;
+/// How far the rod tip has to rise during a jerk for it to set the hook, in radians
+const HOOK_PITCH: f32 = 0.3;
+/// Shortest wait for a bite once the bobber has landed, in seconds
+const MIN_BITE_SECS: f32 = 2.0;
+/// Longest wait for a bite once the bobber has landed, in seconds
+const MAX_BITE_SECS: f32 = 7.0;
+/// How long a fish holds the bait before it lets go, in seconds
+pub const HOOK_WINDOW: f32 = 1.0;
+/// Seconds a cast spends in the air before it's gone any distance
+const BASE_FLIGHT_SECS: f32 = 0.5;
+/// Seconds each meter of a cast adds to its flight
+const FLIGHT_SECS_PER_METER: f32 = 0.03;
+/// How high a cast arcs for each meter it flies
+const ARC_PER_METER: f32 = 0.25;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(FishingPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(WaterPlugin)
+    .add_plugins(LinePlugin)
+    .add_plugins(CatchesPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.6, 0.78, 0.92)))
+    .init_resource::<Angler>()
+    .init_resource::<Cast>()
+    .add_event::<CastOut>()
+    .add_event::<Strike>()
+    .add_systems(OnEnter(FishingPhase::Casting), ready_cast)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            cast_line.run_if(in_state(FishingPhase::Casting)),
+            wait_for_bite.run_if(in_state(FishingPhase::Waiting)),
+            set_hook.run_if(in_state(FishingPhase::Hooking)),
+            (reel_in, end_fight)
+                .chain()
+                .run_if(in_state(FishingPhase::Reeling)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, float_bobber);
+});
+
+/// How the angler up is holding the rod
+#[derive(Resource, Debug, Default)]
+pub struct Angler {
+    /// How far the rod is turned from straight out, in radians. Positive turns left
+    pub aim: f32,
+    /// How far the rod tip is raised from level, in radians
+    pub pitch: f32,
+    /// Picks casting flicks and hooking jerks out of the controller's rotations
+    detector: GestureDetector,
+}
+
+/// The rod flicked forward, sending the bobber out
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct CastOut {
+    /// How far the cast is turned from straight out, in radians
+    pub aim: f32,
+    /// How far out it lands, in meters
+    pub distance: f32,
+}
+
+impl CastOut {
+    /// A cast aimed at `aim` from a flick that turned the rod at up to `speed` radians per second
+    fn from_flick(aim: f32, speed: f32) -> Self {
+        Self {
+            aim: aim.clamp(-MAX_AIM, MAX_AIM),
+            distance: (speed * CAST_SCALE).clamp(MIN_CAST, MAX_CAST),
+        }
+    }
+}
+
+/// The rod jerked up to set the hook
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strike;
+
+/// The cast in the water and what's happening at the end of it
+#[derive(Resource, Debug)]
+pub struct Cast {
+    /// How far it's turned from straight out, in radians
+    pub aim: f32,
+    /// How far out it landed, in meters
+    pub distance: f32,
+    /// Seconds since it was cast
+    flown: f32,
+    /// Counts down to a fish biting once the bobber has landed
+    bite: Timer,
+    /// Counts down how long the fish that bit holds the bait
+    window: Timer,
+    /// The fish that bit, once one has
+    fish: Option<Fish>,
+    /// Xorshift state used for bite times and which fish bite
+    seed: u32,
+}
+
+impl Default for Cast {
+    fn default() -> Self {
+        Self {
+            aim: 0.0,
+            distance: MIN_CAST,
+            flown: 0.0,
+            bite: Timer::from_seconds(MAX_BITE_SECS, TimerMode::Once),
+            window: Timer::from_seconds(HOOK_WINDOW, TimerMode::Once),
+            fish: None,
+            seed: 0x2545_F491,
+        }
+    }
+}
+
+impl Cast {
+    /// A pseudo random number from 0.0 to 1.0
+    fn roll(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+
+    /// Sends the bobber out, with a fresh wait for a bite once it lands
+    fn start(&mut self, cast: CastOut) {
+        self.aim = cast.aim;
+        self.distance = cast.distance;
+        self.flown = 0.0;
+        self.fish = None;
+        self.wait_again();
+    }
+
+    /// Starts a fresh wait for a bite
+    fn wait_again(&mut self) {
+        let wait = MIN_BITE_SECS + (MAX_BITE_SECS - MIN_BITE_SECS) * self.roll();
+        self.bite = Timer::from_seconds(wait, TimerMode::Once);
+    }
+
+    /// Seconds the bobber spends in the air
+    fn flight_secs(&self) -> f32 {
+        BASE_FLIGHT_SECS + self.distance * FLIGHT_SECS_PER_METER
+    }
+
+    /// Whether the bobber has landed on the water
+    fn landed(&self) -> bool {
+        self.flown >= self.flight_secs()
+    }
+
+    /// Where the bobber is on its way out from `from`
+    fn bobber_in_flight(&self, from: Vec3) -> Vec3 {
+        let progress = (self.flown / self.flight_secs()).clamp(0.0, 1.0);
+        let arc = 4.0 * progress * (1.0 - progress) * self.distance * ARC_PER_METER;
+        from.lerp(cast_point(self.aim, self.distance), progress) + Vec3::Y * arc
+    }
+}
+
+/// Banners and rumbles that tell the angler up what's happening at the end of their line
+#[derive(SystemParam)]
+struct Callouts<'w> {
+    /// Banner across the screen
+    banner: ResMut<'w, Banner>,
+    /// Rumbles back to the controller
+    feedback: Res<'w, FeedbackSender>,
+}
+
+impl Callouts<'_> {
+    /// Rumbles the controller of the angler up
+    fn rumble(&self, intensity: u8, millis: u16) {
+        self.feedback.send(GameEvent::Rumble { intensity, millis });
+    }
+}
+
+/// The line and the reel that winds it in
+#[derive(SystemParam)]
+struct Tackle<'w> {
+    /// The line out to the fish
+    line: ResMut<'w, Line>,
+    /// The reel winding it in
+    reel: ResMut<'w, Reel>,
+}
+
+/// Everything input handling changes besides the angler
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// The reel, wound by circling the wrist
+    reel: ResMut<'w, Reel>,
+    /// Casts sent out
+    casts: EventWriter<'w, CastOut>,
+    /// Jerks of the rod to set the hook
+    strikes: EventWriter<'w, Strike>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims the rod and a flick forward casts as far as it was flicked
+/// hard, a jerk up sets the hook once a fish bites and circling the wrist reels it in. A starts
+/// a new game once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut angler: ResMut<'_, Angler>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<FishingPhase>>,
+    time: Res<'_, Time>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == FishingPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let orientation = settings.apply_rotation(orientation);
+                let at = time.elapsed_secs();
+                if *phase.get() == FishingPhase::Reeling {
+                    effects.reel.record(orientation, at);
+                    continue;
+                }
+                if *phase.get() == FishingPhase::Casting {
+                    angler.aim = orientation.yaw.clamp(-MAX_AIM, MAX_AIM);
+                    angler.pitch = orientation.pitch;
+                }
+                let Some(detected) = angler.detector.update(orientation, at) else {
+                    continue;
+                };
+                if !matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+                    continue;
+                }
+                match phase.get() {
+                    FishingPhase::Casting if detected.turned.pitch < -CAST_PITCH => {
+                        effects
+                            .casts
+                            .send(CastOut::from_flick(angler.aim, detected.intensity));
+                    }
+                    FishingPhase::Waiting | FishingPhase::Hooking
+                        if detected.turned.pitch > HOOK_PITCH =>
+                    {
+                        effects.strikes.send(Strike);
+                    }
+                    _ => {}
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Reels the last cast's line all the way in and forgets any motion left over from it, ready
+/// for the next cast
+fn ready_cast(mut angler: ResMut<'_, Angler>, mut line: ResMut<'_, Line>) {
+    angler.detector = GestureDetector::default();
+    *line = Line::default();
+}
+
+/// Sends the bobber out on a cast
+fn cast_line(
+    mut casts: EventReader<'_, '_, CastOut>,
+    mut cast: ResMut<'_, Cast>,
+    mut next_phase: ResMut<'_, NextState<FishingPhase>>,
+) {
+    let Some(cast_out) = casts.read().last().copied() else {
+        return;
+    };
+    cast.start(cast_out);
+    next_phase.set(FishingPhase::Waiting);
+}
+
+/// Flies the bobber out and waits for a bite once it lands. Striking before anything bites
+/// spooks the fish, starting the wait over
+fn wait_for_bite(
+    mut strikes: EventReader<'_, '_, Strike>,
+    mut cast: ResMut<'_, Cast>,
+    mut callouts: Callouts<'_>,
+    mut next_phase: ResMut<'_, NextState<FishingPhase>>,
+    time: Res<'_, Time>,
+) {
+    cast.flown += time.delta_secs();
+    let struck = strikes.read().count() > 0;
+    if !cast.landed() {
+        return;
+    }
+    if struck {
+        callouts.banner.show("Too early! Wait for a bite");
+        cast.wait_again();
+        return;
+    }
+    if !cast.bite.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let (kind, size) = (cast.roll(), cast.roll());
+    cast.fish = Some(Fish::bite(cast.distance, kind, size * size));
+    cast.window = Timer::from_seconds(HOOK_WINDOW, TimerMode::Once);
+    callouts.banner.show("Bite! Jerk the rod up!");
+    callouts.rumble(200, 250);
+    next_phase.set(FishingPhase::Hooking);
+}
+
+/// Sets the hook if the rod is jerked up before the fish lets go of the bait
+fn set_hook(
+    mut strikes: EventReader<'_, '_, Strike>,
+    mut cast: ResMut<'_, Cast>,
+    mut tackle: Tackle<'_>,
+    mut casts_over: EventWriter<'_, CastOver>,
+    mut callouts: Callouts<'_>,
+    mut next_phase: ResMut<'_, NextState<FishingPhase>>,
+    time: Res<'_, Time>,
+) {
+    let Some(fish) = cast.fish else {
+        return;
+    };
+    if strikes.read().count() > 0 {
+        *tackle.line = Line::hook(fish, cast.distance);
+        tackle.reel.clear();
+        callouts.banner.show("Fish on! Circle your wrist to reel");
+        callouts.rumble(255, 150);
+        next_phase.set(FishingPhase::Reeling);
+    } else if cast.window.tick(time.delta()).just_finished() {
+        cast.fish = None;
+        callouts.banner.show("Too slow, it got away");
+        casts_over.send(CastOver { caught: None });
+    }
+}
+
+/// Calls how the fight ended and finishes the cast with whatever it landed
+fn end_fight(
+    mut fought: EventReader<'_, '_, Fought>,
+    mut casts_over: EventWriter<'_, CastOver>,
+    mut callouts: Callouts<'_>,
+) {
+    let Some(Fought(over)) = fought.read().last().copied() else {
+        return;
+    };
+    let caught = match over {
+        FightOver::Landed(fish) => {
+            callouts
+                .banner
+                .show(format!("Landed a {:.2} kg {}!", fish.weight, fish.name));
+            callouts.rumble(120, 300);
+            Some(fish)
+        }
+        FightOver::Snapped => {
+            callouts.banner.show("Snap! The line broke");
+            callouts.rumble(255, 400);
+            None
+        }
+        FightOver::ShookFree => {
+            callouts
+                .banner
+                .show("The line went slack and it shook the hook");
+            None
+        }
+    };
+    casts_over.send(CastOver { caught });
+}
+
+/// Moves the bobber along with the cast: arcing out through the air, bobbing on the water while
+/// it waits, pulled under by a bite and dragged around by a hooked fish as it's reeled in
+fn float_bobber(
+    cast: Res<'_, Cast>,
+    line: Res<'_, Line>,
+    phase: Res<'_, State<FishingPhase>>,
+    mut bobbers: Query<'_, '_, (&mut Transform, &mut Visibility), With<Bobber>>,
+    rods: Query<'_, '_, &Transform, (With<Rod>, Without<Bobber>)>,
+    time: Res<'_, Time>,
+) {
+    let Ok((mut bobber, mut visibility)) = bobbers.get_single_mut() else {
+        return;
+    };
+    let now = time.elapsed_secs();
+    let bob = Vec3::Y * (now * 2.5).sin() * BOBBER_RADIUS * 0.3;
+    let position = match phase.get() {
+        FishingPhase::Casting | FishingPhase::GameOver => None,
+        FishingPhase::Waiting if !cast.landed() => {
+            let from = rods.get_single().map_or(Vec3::ZERO, rod_tip);
+            Some(cast.bobber_in_flight(from))
+        }
+        FishingPhase::Waiting => Some(cast_point(cast.aim, cast.distance) + bob),
+        FishingPhase::Hooking => {
+            Some(cast_point(cast.aim, cast.distance) - Vec3::Y * BOBBER_RADIUS * 1.5)
+        }
+        FishingPhase::Reeling => {
+            let wander = (now * 0.7).sin() * 0.15 * line.pull;
+            let tug = Vec3::Y * -line.pull * BOBBER_RADIUS;
+            Some(cast_point(cast.aim + wander, line.out) + tug)
+        }
+    };
+
+    visibility.set_if_neq(if position.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if let Some(position) = position {
+        bobber.translation = position;
+    }
+}
@@ -0,0 +1,313 @@
+//! The line and reel: winding in a hooked fish by circling the wrist, against a tension that
+//! snaps the line when it runs too high and lets the fish shake free when it runs slack, shown
+//! on a gauge at the side of the screen
+
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::communication::Orientation;
+
+use crate::{fish::Fish, phase::FishingPhase};
+
+/// Crank speed that counts as reeling flat out, in radians per second
+pub const FAST_CRANK: f32 = 12.0;
+/// Tension reeling flat out adds on top of the fish's pull
+pub const REEL_TENSION: f32 = 0.6;
+/// Line wound in by each radian the reel turns, in meters
+const LINE_PER_RADIAN: f32 = 0.2;
+/// How quickly the crank speed follows the wrist, per second
+const CRANK_RESPONSE: f32 = 8.0;
+/// Tension the line snaps above, if held there for long
+pub const SNAP_TENSION: f32 = 1.0;
+/// Longest the line can be held above its snapping tension, in seconds
+const SNAP_SECS: f32 = 0.6;
+/// Tension below which the line counts as slack
+const SLACK_TENSION: f32 = 0.15;
+/// Longest the line can be left slack before the fish shakes the hook, in seconds
+const SLACK_SECS: f32 = 2.5;
+/// Most line there is on the reel, in meters
+const LINE_LENGTH: f32 = 45.0;
+/// How close in a fish has to be reeled to be landed, in meters
+const LANDING_DISTANCE: f32 = 1.5;
+/// How far the wrist has to be held from the middle of its circle for circling to count, in
+/// radians
+const MIN_CIRCLE_RADIUS: f32 = 0.04;
+/// How quickly the middle of the wrist's circle follows it, per second
+const CIRCLE_CENTRING: f32 = 2.0;
+/// Highest tension the gauge shows
+const GAUGE_MAX: f32 = 1.25;
+/// Height of the tension gauge, in pixels
+const GAUGE_HEIGHT: f32 = 260.0;
+
+/// Turns circles drawn by the wrist into turns of the reel, by following which way the
+/// controller points around the middle of the circle it's drawing
+#[derive(Resource, Debug, Default)]
+pub struct Reel {
+    /// Middle of the circle the wrist is drawing, as yaw and pitch in radians
+    centre: Option<Vec2>,
+    /// Angle around that middle the wrist last pointed at, and when, in seconds
+    last: Option<(f32, f32)>,
+    /// Radians the reel has turned since the line last took them
+    wound: f32,
+}
+
+impl Reel {
+    /// Follows the wrist around its circle with an orientation read at `at` seconds, winding the
+    /// reel by however far around it went either way
+    pub fn record(&mut self, orientation: Orientation, at: f32) {
+        let point = Vec2::new(orientation.yaw, orientation.pitch);
+        let centre = self.centre.get_or_insert(point);
+        if let Some((_, last_at)) = self.last {
+            let follow = 1.0 - (-(at - last_at).max(0.0) * CIRCLE_CENTRING).exp();
+            *centre = centre.lerp(point, follow);
+        }
+
+        let offset = point - *centre;
+        if offset.length() < MIN_CIRCLE_RADIUS {
+            self.last = None;
+            return;
+        }
+        let angle = offset.to_angle();
+        if let Some((last_angle, _)) = self.last {
+            let turned = (angle - last_angle + PI).rem_euclid(TAU) - PI;
+            self.wound += turned.abs();
+        }
+        self.last = Some((angle, at));
+    }
+
+    /// Radians the reel has turned since this was last called
+    fn take(&mut self) -> f32 {
+        std::mem::take(&mut self.wound)
+    }
+
+    /// Forgets the wrist's circle, for the next fight
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// How a fight ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FightOver {
+    /// The fish was reeled all the way in
+    Landed(Fish),
+    /// The line was held too tight for too long
+    Snapped,
+    /// The line was left slack long enough for the fish to throw the hook
+    ShookFree,
+}
+
+/// The line out to a hooked fish
+#[derive(Resource, Serialize, Debug, Default, Clone, PartialEq)]
+pub struct Line {
+    /// The fish on the end, once one is hooked
+    pub fish: Option<Fish>,
+    /// How much line is out, in meters
+    pub out: f32,
+    /// How tight the line is, where [`SNAP_TENSION`] is where it starts to give
+    pub tension: f32,
+    /// How hard the fish is pulling right now
+    pub pull: f32,
+    /// How fast the reel is being cranked, in radians per second
+    pub crank: f32,
+    /// Seconds the fish has been fought for
+    fought_for: f32,
+    /// Seconds the line has been held above its snapping tension
+    strain: f32,
+    /// Seconds the line has been slack
+    slack: f32,
+}
+
+impl Line {
+    /// Hooks a fish `out` meters away
+    pub fn hook(fish: Fish, out: f32) -> Self {
+        Self {
+            fish: Some(fish),
+            out,
+            pull: fish.pull(0.0),
+            tension: fish.pull(0.0),
+            ..default()
+        }
+    }
+
+    /// Fights the fish for `secs` with the reel turning `wound` radians, returning how the fight
+    /// ended if it did
+    pub fn fight(&mut self, secs: f32, wound: f32) -> Option<FightOver> {
+        let fish = self.fish?;
+        if secs <= f32::EPSILON {
+            return None;
+        }
+
+        self.fought_for += secs;
+        let response = 1.0 - (-secs * CRANK_RESPONSE).exp();
+        self.crank += (wound / secs - self.crank) * response;
+        self.pull = fish.pull(self.fought_for);
+        self.tension = self.pull + self.crank / FAST_CRANK * REEL_TENSION;
+        self.out = (self.out + (fish.run_speed(self.pull) - self.crank * LINE_PER_RADIAN) * secs)
+            .min(LINE_LENGTH);
+
+        self.strain = if self.tension > SNAP_TENSION {
+            self.strain + secs
+        } else {
+            (self.strain - secs).max(0.0)
+        };
+        self.slack = if self.tension < SLACK_TENSION {
+            self.slack + secs
+        } else {
+            0.0
+        };
+
+        if self.out <= LANDING_DISTANCE {
+            Some(FightOver::Landed(fish))
+        } else if self.strain > SNAP_SECS {
+            Some(FightOver::Snapped)
+        } else if self.slack > SLACK_SECS {
+            Some(FightOver::ShookFree)
+        } else {
+            None
+        }
+    }
+}
+
+/// A fight with a hooked fish just ended
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Fought(pub FightOver);
+
+/// The tension gauge
+#[derive(Component)]
+struct TensionGauge;
+
+/// The fill of the tension gauge, rising with the tension
+#[derive(Component)]
+struct TensionFill;
+
+/// Plugin that fights hooked fish and shows the line's tension
+pub struct LinePlugin;
+
+impl Plugin for LinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Reel>()
+            .init_resource::<Line>()
+            .add_event::<Fought>()
+            .add_systems(Startup, spawn_gauge)
+            .add_systems(OnEnter(FishingPhase::Reeling), show_gauge)
+            .add_systems(OnExit(FishingPhase::Reeling), hide_gauge)
+            .add_systems(Update, fill_gauge.run_if(in_state(FishingPhase::Reeling)));
+    }
+}
+
+/// Fights the hooked fish with however far the reel turned since last frame
+pub fn reel_in(
+    mut reel: ResMut<'_, Reel>,
+    mut line: ResMut<'_, Line>,
+    mut fought: EventWriter<'_, Fought>,
+    time: Res<'_, Time>,
+) {
+    let wound = reel.take();
+    if let Some(over) = line.fight(time.delta_secs(), wound) {
+        line.fish = None;
+        fought.send(Fought(over));
+    }
+}
+
+/// Spawns the tension gauge up the right of the screen, hidden until a fish is hooked
+fn spawn_gauge(mut commands: Commands<'_, '_>) {
+    let snap_from_top = (1.0 - SNAP_TENSION / GAUGE_MAX) * 100.0;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(24.0),
+                top: Val::Percent(25.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            TensionGauge,
+        ))
+        .with_children(|gauge| {
+            gauge.spawn((
+                Text::new("Tension"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+            gauge
+                .spawn((
+                    Node {
+                        width: Val::Px(28.0),
+                        height: Val::Px(GAUGE_HEIGHT),
+                        border: UiRect::all(Val::Px(2.0)),
+                        justify_content: JustifyContent::FlexEnd,
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(0.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.3, 0.85, 0.3)),
+                        TensionFill,
+                    ));
+                    // Where the line starts to give
+                    bar.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            top: Val::Percent(snap_from_top),
+                            left: Val::Px(-6.0),
+                            right: Val::Px(-6.0),
+                            height: Val::Px(2.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(1.0, 0.2, 0.2)),
+                    ));
+                });
+        });
+}
+
+/// Shows the tension gauge once a fish is on
+fn show_gauge(mut gauges: Query<'_, '_, &mut Visibility, With<TensionGauge>>) {
+    for mut visibility in &mut gauges {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Hides the tension gauge once the fight is over
+fn hide_gauge(mut gauges: Query<'_, '_, &mut Visibility, With<TensionGauge>>) {
+    for mut visibility in &mut gauges {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Fills the gauge up to the line's tension, going from green through yellow to red as it nears
+/// snapping
+fn fill_gauge(
+    line: Res<'_, Line>,
+    mut fills: Query<'_, '_, (&mut Node, &mut BackgroundColor), With<TensionFill>>,
+) {
+    let level = (line.tension / GAUGE_MAX).clamp(0.0, 1.0);
+    let danger = (line.tension / SNAP_TENSION).clamp(0.0, 1.0);
+    let color = if line.tension < SLACK_TENSION {
+        Color::srgb(0.4, 0.6, 1.0)
+    } else {
+        Color::srgb(
+            (danger * 2.0).min(1.0),
+            ((1.0 - danger) * 2.0).min(1.0) * 0.85,
+            0.2,
+        )
+    };
+    for (mut node, mut background) in &mut fills {
+        node.height = Val::Percent(level * 100.0);
+        background.0 = color;
+    }
+}
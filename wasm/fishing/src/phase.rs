@@ -0,0 +1,29 @@
+//! Phases each cast moves through, from winding up at the water's edge to the final catches
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of a cast
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FishingPhase {
+    /// The angler up is lining up and flicking out their next cast
+    #[default]
+    Casting,
+    /// The bobber is flying out or floating, waiting for a bite
+    Waiting,
+    /// A fish has bitten and the angler has a moment to jerk the hook in
+    Hooking,
+    /// A fish is hooked and being reeled in against the line's tension
+    Reeling,
+    /// Every angler has had their casts and the catches are weighed
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct FishingPhasePlugin;
+
+impl Plugin for FishingPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<FishingPhase>();
+    }
+}
@@ -0,0 +1,222 @@
+//! The lake: open water running out from the end of a wooden dock, the rod the angler holds
+//! over it and the bobber and line they cast
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{line::Line, phase::FishingPhase, Angler};
+
+/// Where on the water's surface, at the end of the dock, casts are measured from
+pub const DOCK_END: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+/// Height of the dock's boards above the water
+const DOCK_HEIGHT: f32 = 0.5;
+/// Length of the dock, running back to the bank
+const DOCK_LENGTH: f32 = 6.0;
+/// How far the lake reaches out from the dock
+const LAKE_LENGTH: f32 = 60.0;
+/// Where the rod's handle is held
+const ROD_GRIP: Vec3 = Vec3::new(0.35, 1.3, 1.2);
+/// Length of the rod from grip to tip
+const ROD_LENGTH: f32 = 2.2;
+/// How far the rod tips up from level while the angler waits on a bite, in radians
+const ROD_REST: f32 = 0.5;
+/// Radius of the bobber
+pub const BOBBER_RADIUS: f32 = 0.06;
+
+/// Where on the water a cast `distance` meters out, turned `aim` radians from straight out
+/// (positive turning left), lands
+pub fn cast_point(aim: f32, distance: f32) -> Vec3 {
+    DOCK_END + Quat::from_rotation_y(aim) * Vec3::NEG_Z * distance
+}
+
+/// The angler's rod, pivoting at the grip
+#[derive(Component, Debug)]
+pub struct Rod;
+
+/// The bobber the line is tied to
+#[derive(Component, Debug)]
+pub struct Bobber;
+
+/// Plugin that builds the lake and poses the rod, bobber and line over it
+pub struct WaterPlugin;
+
+impl Plugin for WaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_lake)
+            .add_systems(Update, (pose_rod, draw_line).chain());
+    }
+}
+
+/// Spawns the water, the bank, the dock, a tree line on the far shore, the rod, the bobber, the
+/// camera and the sun
+fn setup_lake(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(LAKE_LENGTH * 1.5, LAKE_LENGTH),
+            ),
+        ),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.12, 0.3, 0.42),
+            perceptual_roughness: 0.15,
+            reflectance: 0.6,
+            ..default()
+        })),
+        Transform::from_translation(DOCK_END - Vec3::Z * (LAKE_LENGTH / 2.0 - DOCK_LENGTH)),
+        Name::new("Lake"),
+    ));
+    let grass = materials.add(Color::srgb(0.28, 0.5, 0.22));
+    for z in [DOCK_LENGTH + 5.0, -LAKE_LENGTH + DOCK_LENGTH - 5.0] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(LAKE_LENGTH * 1.5, 0.4, 10.0))),
+            MeshMaterial3d(grass.clone()),
+            Transform::from_xyz(0.0, 0.1, z),
+            Name::new("Bank"),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(1.6, 0.1, DOCK_LENGTH))),
+        MeshMaterial3d(materials.add(Color::srgb(0.5, 0.36, 0.22))),
+        Transform::from_translation(DOCK_END + Vec3::new(0.0, DOCK_HEIGHT, DOCK_LENGTH / 2.0)),
+        Name::new("Dock"),
+    ));
+    let post = meshes.add(Cylinder::new(0.08, DOCK_HEIGHT + 0.6));
+    let wood = materials.add(Color::srgb(0.35, 0.25, 0.15));
+    for side in [-0.75, 0.75] {
+        for along in [0.1, DOCK_LENGTH / 2.0] {
+            commands.spawn((
+                Mesh3d(post.clone()),
+                MeshMaterial3d(wood.clone()),
+                Transform::from_translation(DOCK_END + Vec3::new(side, 0.0, along)),
+            ));
+        }
+    }
+
+    // Pines along the far shore
+    let trunk = meshes.add(Cylinder::new(0.25, 2.0));
+    let crown = meshes.add(Cone {
+        radius: 1.6,
+        height: 5.0,
+    });
+    let needles = materials.add(Color::srgb(0.12, 0.32, 0.15));
+    for index in 0..14 {
+        let x = -32.0 + index as f32 * 5.0 + (index % 3) as f32;
+        let z = -LAKE_LENGTH + DOCK_LENGTH - 3.0 - (index % 4) as f32 * 1.5;
+        commands.spawn((
+            Mesh3d(trunk.clone()),
+            MeshMaterial3d(wood.clone()),
+            Transform::from_xyz(x, 1.0, z),
+        ));
+        commands.spawn((
+            Mesh3d(crown.clone()),
+            MeshMaterial3d(needles.clone()),
+            Transform::from_xyz(x, 4.5, z),
+        ));
+    }
+
+    commands
+        .spawn((
+            Transform::from_translation(ROD_GRIP),
+            Visibility::Visible,
+            Rod,
+            Name::new("Rod"),
+        ))
+        .with_children(|rod| {
+            rod.spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.012, ROD_LENGTH))),
+                MeshMaterial3d(materials.add(Color::srgb(0.15, 0.15, 0.18))),
+                Transform::from_xyz(0.0, 0.0, -ROD_LENGTH / 2.0)
+                    .with_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+            ));
+            rod.spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.04, 0.05))),
+                MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.72))),
+                Transform::from_xyz(0.05, -0.03, -0.25)
+                    .with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+                Name::new("Reel"),
+            ));
+        });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BOBBER_RADIUS).mesh().uv(16, 12))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.95, 0.15, 0.1),
+            emissive: LinearRgba::rgb(0.3, 0.02, 0.0),
+            ..default()
+        })),
+        Transform::from_translation(ROD_GRIP),
+        Visibility::Hidden,
+        Bobber,
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.1, 3.2).looking_at(cast_point(0.0, 14.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 12.0, 6.0).with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.6)),
+    ));
+}
+
+/// Where the tip of a rod posed like this is
+pub fn rod_tip(rod: &Transform) -> Vec3 {
+    rod.translation + rod.rotation * Vec3::NEG_Z * ROD_LENGTH
+}
+
+/// Points the rod where the angler is aiming, following their wrist while they line up a cast,
+/// resting up while they wait and bending up against the line's tension while they reel
+fn pose_rod(
+    angler: Res<'_, Angler>,
+    line: Res<'_, Line>,
+    phase: Res<'_, State<FishingPhase>>,
+    mut rods: Query<'_, '_, &mut Transform, With<Rod>>,
+) {
+    let raise = match phase.get() {
+        FishingPhase::Casting => angler.pitch.clamp(-0.3, 1.4),
+        FishingPhase::Reeling => ROD_REST + line.tension * 0.4,
+        _ => ROD_REST,
+    };
+    for mut rod in &mut rods {
+        rod.rotation = Quat::from_rotation_y(angler.aim) * Quat::from_rotation_x(raise);
+    }
+}
+
+/// Draws the line from the rod tip down to the bobber, sagging the slacker it is
+fn draw_line(
+    mut gizmos: Gizmos<'_, '_>,
+    line: Res<'_, Line>,
+    rods: Query<'_, '_, &Transform, With<Rod>>,
+    bobbers: Query<'_, '_, (&Transform, &Visibility), With<Bobber>>,
+) {
+    let (Ok(rod), Ok((bobber, visibility))) = (rods.get_single(), bobbers.get_single()) else {
+        return;
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    let tip = rod_tip(rod);
+    let end = bobber.translation;
+    let sag = (1.0 - line.tension.clamp(0.0, 1.0)) * tip.distance(end) * 0.08;
+    let middle = tip.lerp(end, 0.5) - Vec3::Y * sag;
+    gizmos.linestrip(
+        (0..=16).map(|step| {
+            let t = step as f32 / 16.0;
+            tip.lerp(middle, t).lerp(middle.lerp(end, t), t)
+        }),
+        Color::srgba(0.9, 0.9, 0.9, 0.8),
+    );
+}
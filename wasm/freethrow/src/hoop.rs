@@ -0,0 +1,235 @@
+//! The half court: the backboard and rim the ball bounces off, the net it drops through, and the
+//! spots shots are taken from
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody};
+
+/// Height of the top of the rim off the floor
+pub const RIM_HEIGHT: f32 = 3.05;
+/// Inside radius of the rim
+pub const RIM_RADIUS: f32 = 0.2286;
+/// Radius of the rim's tube
+const RIM_TUBE: f32 = 0.01;
+/// Gap between the backboard and the inside of the rim
+const BOARD_TO_RIM: f32 = 0.15;
+/// Middle of the rim, out in front of the backboard whose face is at `z = 0`
+pub const RIM_CENTRE: Vec3 = Vec3::new(0.0, RIM_HEIGHT, BOARD_TO_RIM + RIM_RADIUS + RIM_TUBE);
+/// How far the free-throw line is from the backboard
+const FREE_THROW_LINE: f32 = 4.57;
+/// Angles round from the free-throw line of each around-the-world spot, right baseline to left
+/// baseline, in radians. Every spot is as far from the rim as the free-throw line
+const SPOT_ANGLES: [f32; 5] = [-1.3, -0.7, 0.0, 0.7, 1.3];
+/// How many spots there are to work round in around the world
+pub const SPOTS: usize = SPOT_ANGLES.len();
+/// Half the width of the backboard
+const BOARD_HALF_WIDTH: f32 = 0.915;
+/// Half the height of the backboard
+const BOARD_HALF_HEIGHT: f32 = 0.535;
+/// Height of the bottom edge of the backboard
+const BOARD_BOTTOM: f32 = 2.9;
+/// Thickness of the backboard
+const BOARD_THICKNESS: f32 = 0.05;
+/// Segments the rim's collider is built from
+const RIM_SEGMENTS: usize = 24;
+/// Length of the net hanging under the rim
+const NET_LENGTH: f32 = 0.45;
+/// Bounciness of the rim, chosen so that averaged with the ball's it matches a ball coming off a
+/// real rim
+const RIM_RESTITUTION: f32 = 0.5;
+/// Bounciness of the backboard, averaged with the ball's the same way
+const BOARD_RESTITUTION: f32 = 0.6;
+/// Bounciness of the hardwood floor, averaged with the ball's the same way
+const FLOOR_RESTITUTION: f32 = 0.8;
+
+/// Where on the floor a spot is, from its place round the key
+pub fn spot(index: usize) -> Vec3 {
+    let angle = SPOT_ANGLES[index.min(SPOTS - 1)];
+    let reach = FREE_THROW_LINE - RIM_CENTRE.z;
+    Vec3::new(
+        RIM_CENTRE.x + reach * angle.sin(),
+        0.0,
+        RIM_CENTRE.z + reach * angle.cos(),
+    )
+}
+
+/// Where on the floor the free-throw line is, straight out from the rim
+pub fn free_throw_spot() -> Vec3 {
+    Vec3::new(RIM_CENTRE.x, 0.0, FREE_THROW_LINE)
+}
+
+/// The rim
+#[derive(Component, Debug)]
+pub struct Rim;
+
+/// The backboard
+#[derive(Component, Debug)]
+pub struct Backboard;
+
+/// Plugin that lays out the court, the hoop, the camera and the light
+pub struct HoopPlugin;
+
+impl Plugin for HoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_hoop);
+    }
+}
+
+/// The rim's collider, a ring of short capsules running round the tube
+fn rim_collider() -> Collider {
+    let ring = RIM_RADIUS + RIM_TUBE;
+    let point = |segment: usize| {
+        let angle = TAU * segment as f32 / RIM_SEGMENTS as f32;
+        Vec3::new(ring * angle.cos(), 0.0, ring * angle.sin())
+    };
+    Collider::compound(
+        (0..RIM_SEGMENTS)
+            .map(|segment| {
+                (
+                    Vec3::ZERO,
+                    Quat::IDENTITY,
+                    Collider::capsule(point(segment), point(segment + 1), RIM_TUBE),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Spawns the floor and its paint, the pole, backboard, rim and net, spot markers, the camera and
+/// the light
+fn setup_hoop(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(16.0, 16.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.8, 0.6, 0.35),
+            perceptual_roughness: 0.3,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, 5.0),
+        RigidBody::Fixed,
+        Collider::cuboid(8.0, 0.1, 8.0),
+        Restitution::coefficient(FLOOR_RESTITUTION),
+        Friction::coefficient(0.6),
+    ));
+
+    // The painted lane runs from under the board out to the free-throw line
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(4.9, FREE_THROW_LINE + 1.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.25, 0.15))),
+        Transform::from_xyz(0.0, 0.002, (FREE_THROW_LINE - 1.2) / 2.0),
+    ));
+    let paint = materials.add(Color::WHITE);
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(4.9, 0.05))),
+        MeshMaterial3d(paint.clone()),
+        Transform::from_xyz(0.0, 0.004, FREE_THROW_LINE),
+    ));
+    let marker = meshes.add(Cylinder::new(0.2, 0.004));
+    for index in 0..SPOTS {
+        commands.spawn((
+            Mesh3d(marker.clone()),
+            MeshMaterial3d(paint.clone()),
+            Transform::from_translation(spot(index).with_y(0.004)),
+        ));
+    }
+
+    let steel = materials.add(Color::srgb(0.3, 0.3, 0.35));
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.08, BOARD_BOTTOM + BOARD_HALF_HEIGHT))),
+        MeshMaterial3d(steel.clone()),
+        Transform::from_xyz(0.0, (BOARD_BOTTOM + BOARD_HALF_HEIGHT) / 2.0, -1.0),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.1, 0.1, 1.0))),
+        MeshMaterial3d(steel),
+        Transform::from_xyz(0.0, BOARD_BOTTOM + BOARD_HALF_HEIGHT, -0.5),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            BOARD_HALF_WIDTH * 2.0,
+            BOARD_HALF_HEIGHT * 2.0,
+            BOARD_THICKNESS,
+        ))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.9, 0.95, 1.0, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        Transform::from_xyz(
+            0.0,
+            BOARD_BOTTOM + BOARD_HALF_HEIGHT,
+            -BOARD_THICKNESS / 2.0,
+        ),
+        RigidBody::Fixed,
+        Collider::cuboid(BOARD_HALF_WIDTH, BOARD_HALF_HEIGHT, BOARD_THICKNESS / 2.0),
+        Restitution::coefficient(BOARD_RESTITUTION),
+        Friction::coefficient(0.4),
+        Backboard,
+    ));
+    // The shooter's square painted on the board above the rim
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::new(Vec3::Z, Vec2::new(0.3, 0.23)))),
+        MeshMaterial3d(materials.add(Color::srgb(0.9, 0.3, 0.1))),
+        Transform::from_xyz(0.0, RIM_HEIGHT + 0.23, 0.001),
+    ));
+
+    let orange = materials.add(Color::srgb(0.95, 0.4, 0.05));
+    commands.spawn((
+        Mesh3d(meshes.add(Torus::new(RIM_RADIUS, RIM_RADIUS + RIM_TUBE * 2.0))),
+        MeshMaterial3d(orange.clone()),
+        Transform::from_translation(RIM_CENTRE),
+        RigidBody::Fixed,
+        rim_collider(),
+        Restitution::coefficient(RIM_RESTITUTION),
+        Friction::coefficient(0.4),
+        Rim,
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.1, 0.02, BOARD_TO_RIM))),
+        MeshMaterial3d(orange),
+        Transform::from_xyz(0.0, RIM_HEIGHT, BOARD_TO_RIM / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(0.05, 0.01, BOARD_TO_RIM / 2.0),
+        Restitution::coefficient(RIM_RESTITUTION),
+        Rim,
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(ConicalFrustum {
+            radius_top: RIM_RADIUS,
+            radius_bottom: RIM_RADIUS * 0.6,
+            height: NET_LENGTH,
+        })),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 1.0, 1.0, 0.4),
+            alpha_mode: AlphaMode::Blend,
+            cull_mode: None,
+            ..default()
+        })),
+        Transform::from_translation(RIM_CENTRE - Vec3::Y * NET_LENGTH / 2.0),
+        Name::new("Net"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(free_throw_spot() + Vec3::new(0.0, 2.8, 3.0))
+            .looking_at(RIM_CENTRE, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(2.0, 10.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
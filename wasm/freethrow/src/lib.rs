@@ -0,0 +1,260 @@
+//! Bevy basketball free-throw game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::plugin::{NoUserData, RapierPhysicsPlugin};
+use hoop::{HoopPlugin, RIM_CENTRE};
+use mode::{FreeThrowMode, ModePlugin};
+use phase::{FreeThrowPhase, FreeThrowPhasePlugin};
+use rules::{NewGame, RulesPlugin, Scoreboard};
+use shot::{rack_ball, release_point, shoot_ball, Ball, Shot, ShotPlugin};
+use spjorts_core::{
+    communication::JsMessage,
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod hoop;
+pub mod mode;
+pub mod phase;
+pub mod rules;
+pub mod shot;
+
+/// Flattest a shot can be, in radians above level
+pub const MIN_ARC: f32 = 0.6;
+/// Steepest a shot can be, in radians above level
+pub const MAX_ARC: f32 = 1.3;
+/// Slowest the ball can leave the hand, in meters per second
+pub const MIN_SPEED: f32 = 6.0;
+/// Fastest the ball can leave the hand, in meters per second
+pub const MAX_SPEED: f32 = 9.0;
+/// Arc of a shot with the controller held level, a textbook free throw, in radians above level
+const LEVEL_ARC: f32 = 0.9;
+/// How far a radian of controller pitch steepens the arc
+const ARC_SCALE: f32 = 0.5;
+/// How far a radian of controller yaw turns the shot
+const AIM_SCALE: f32 = 0.2;
+/// Widest a shot can be turned from straight at the rim, in radians
+const MAX_AIM: f32 = 0.15;
+/// How far the controller has to snap forward during a flick for it to count as a release, in
+/// radians
+const RELEASE_PITCH: f32 = 0.3;
+/// How fast the wrist has to flick to shoot the hardest, in radians per second
+const FULL_FLICK_SPEED: f32 = 16.0;
+/// Turning speed below which the controller counts as held still, so its angle sets the arc, in
+/// radians per second
+const HELD_SPEED: f32 = 1.0;
+/// How far behind the shooter the camera sits
+const CAMERA_BACK: f32 = 3.0;
+/// How far above the floor the camera sits
+const CAMERA_UP: f32 = 2.8;
+/// How quickly the camera moves to a new spot, higher is snappier
+const CAMERA_SMOOTHING: f32 = 3.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(FreeThrowPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(ModePlugin)
+    .add_plugins(HoopPlugin)
+    .add_plugins(ShotPlugin)
+    .add_plugins(RulesPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.12, 0.12, 0.16)))
+    .init_resource::<Shooter>()
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            (hold_ball, take_shot)
+                .chain()
+                .run_if(in_state(FreeThrowPhase::Aiming)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (follow_shooter, draw_aim_guide));
+});
+
+/// How the player up is holding the controller to line up their shot
+#[derive(Resource, Debug)]
+pub struct Shooter {
+    /// Arc of the shot, in radians above level
+    pub arc: f32,
+    /// How far the shot is turned from straight at the rim, in radians. Positive turns left
+    pub aim: f32,
+    /// Watches the controller for the wrist flick that lets the ball go
+    detector: GestureDetector,
+}
+
+impl Default for Shooter {
+    fn default() -> Self {
+        Self {
+            arc: LEVEL_ARC,
+            aim: 0.0,
+            detector: GestureDetector::default(),
+        }
+    }
+}
+
+/// Everything input handling changes besides the shooter's stance
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Shots to take
+    shots: EventWriter<'w, Shot>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+    /// Where the game goes next, for going back to the mode menu
+    next_phase: ResMut<'w, NextState<FreeThrowPhase>>,
+}
+
+/// Reads controller input: the angle the controller is held at sets the arc and line, and a
+/// forward wrist flick shoots, harder the faster the flick. A plays again once it's over and B
+/// goes back to the mode menu
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut shooter: ResMut<'_, Shooter>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<FreeThrowPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == FreeThrowPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart if *phase.get() != FreeThrowPhase::ModeSelect => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == FreeThrowPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonB if *phase.get() == FreeThrowPhase::GameOver => {
+                effects.next_phase.set(FreeThrowPhase::ModeSelect);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                let detected = shooter.detector.update(orientation, time.elapsed_secs());
+                if shooter.detector.speed() < HELD_SPEED {
+                    shooter.arc =
+                        (LEVEL_ARC + orientation.pitch * ARC_SCALE).clamp(MIN_ARC, MAX_ARC);
+                    shooter.aim = (orientation.yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                }
+
+                let Some(detected) = detected else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Flick | Gesture::Swing)
+                    && detected.turned.pitch < -RELEASE_PITCH
+                {
+                    let power = (detected.intensity / FULL_FLICK_SPEED).clamp(0.0, 1.0);
+                    effects.shots.send(Shot {
+                        arc: shooter.arc,
+                        aim: shooter.aim,
+                        speed: MIN_SPEED + (MAX_SPEED - MIN_SPEED) * power,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Racks a ball for the player up if there isn't one, keeping it at their release point
+fn hold_ball(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+    mut balls: Query<'_, '_, (&mut Transform, &Ball)>,
+    scoreboard: Res<'_, Scoreboard>,
+    mode: Res<'_, FreeThrowMode>,
+    turns: Res<'_, TurnManager>,
+) {
+    let at = release_point(scoreboard.shooting_spot(*mode, turns.current()));
+    if balls.is_empty() {
+        rack_ball(&mut commands, &mut meshes, &mut materials, at);
+        return;
+    }
+    for (mut transform, ball) in &mut balls {
+        if ball.shot_for.is_none() {
+            transform.translation = at;
+        }
+    }
+}
+
+/// Lets the held ball go along the shot taken
+fn take_shot(
+    mut commands: Commands<'_, '_>,
+    mut shots: EventReader<'_, '_, Shot>,
+    mut balls: Query<'_, '_, (Entity, &Transform, &mut Ball)>,
+    mut next_phase: ResMut<'_, NextState<FreeThrowPhase>>,
+) {
+    let Some(shot) = shots.read().last().copied() else {
+        return;
+    };
+    let Some((entity, transform, mut ball)) = balls
+        .iter_mut()
+        .find(|(_, _, ball)| ball.shot_for.is_none())
+    else {
+        return;
+    };
+
+    shoot_ball(&mut commands, entity, &mut ball, transform, &shot);
+    next_phase.set(FreeThrowPhase::Flying);
+}
+
+/// Keeps the camera behind the player up's spot, looking up at the rim
+fn follow_shooter(
+    mut camera: Query<'_, '_, &mut Transform, With<Camera3d>>,
+    scoreboard: Res<'_, Scoreboard>,
+    mode: Res<'_, FreeThrowMode>,
+    turns: Res<'_, TurnManager>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let spot = scoreboard.shooting_spot(*mode, turns.current());
+    let away = (spot - RIM_CENTRE).with_y(0.0).normalize_or(Vec3::Z);
+    let goal = spot + away * CAMERA_BACK + Vec3::Y * CAMERA_UP;
+
+    let blend = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(RIM_CENTRE, Vec3::Y);
+}
+
+/// Draws the line the ball will leave the hand along while lining up, when the aim guide is on
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    shooter: Res<'_, Shooter>,
+    phase: Res<'_, State<FreeThrowPhase>>,
+    settings: Res<'_, GameSettings>,
+    balls: Query<'_, '_, (&Transform, &Ball)>,
+) {
+    if !settings.aim_guide || *phase.get() != FreeThrowPhase::Aiming {
+        return;
+    }
+    let Some((transform, _)) = balls.iter().find(|(_, ball)| ball.shot_for.is_none()) else {
+        return;
+    };
+    let start = transform.translation;
+    let direction = Shot {
+        arc: shooter.arc,
+        aim: shooter.aim,
+        speed: 1.0,
+    }
+    .velocity(start);
+    gizmos.line(start, start + direction, Color::srgb(0.9, 0.2, 0.2));
+}
@@ -0,0 +1,132 @@
+//! Picking how the game is played: as many makes as possible against a shot clock, or working
+//! round five spots about the key
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::menu::{Menu, MenuSelected};
+
+use crate::{phase::FreeThrowPhase, rules::NewGame};
+
+/// Font size of the mode menu's options
+const MENU_FONT_SIZE: f32 = 36.0;
+/// Color of the highlighted mode
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+
+/// How the current game is played
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FreeThrowMode {
+    /// Each player shoots from the free-throw line for as many makes as they can before their
+    /// clock runs out
+    #[default]
+    ShotClock,
+    /// Players work round five spots, moving on with every make and handing the ball over with
+    /// every miss, and the first round wins
+    AroundTheWorld,
+}
+
+impl FreeThrowMode {
+    /// Every mode, in menu order
+    const ALL: [Self; 2] = [Self::ShotClock, Self::AroundTheWorld];
+
+    /// Name shown in the mode menu and on the scorecard
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ShotClock => "60 Second Shootout",
+            Self::AroundTheWorld => "Around the World",
+        }
+    }
+}
+
+/// Marks the mode menu
+#[derive(Component)]
+struct ModeMenu;
+
+/// An entry in the mode menu
+#[derive(Component)]
+struct ModeOption(usize);
+
+/// Plugin that adds the mode menu
+pub struct ModePlugin;
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FreeThrowMode>()
+            .add_systems(OnEnter(FreeThrowPhase::ModeSelect), spawn_mode_menu)
+            .add_systems(OnExit(FreeThrowPhase::ModeSelect), despawn_mode_menu)
+            .add_systems(Update, (highlight_mode_menu, choose_mode));
+    }
+}
+
+/// Spawns the mode menu, focused and with the current mode highlighted
+fn spawn_mode_menu(mut commands: Commands<'_, '_>, mode: Res<'_, FreeThrowMode>) {
+    let mut menu = Menu::new(FreeThrowMode::ALL.len());
+    menu.selected = FreeThrowMode::ALL
+        .iter()
+        .position(|option| option == &*mode)
+        .unwrap_or_default();
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            menu,
+            ModeMenu,
+        ))
+        .with_children(|menu| {
+            for (idx, mode) in FreeThrowMode::ALL.iter().enumerate() {
+                menu.spawn((
+                    Text::new(mode.name()),
+                    TextFont::from_font_size(MENU_FONT_SIZE),
+                    TextColor::WHITE,
+                    ModeOption(idx),
+                ));
+            }
+        });
+}
+
+/// Takes the mode menu down once a game starts
+fn despawn_mode_menu(mut commands: Commands<'_, '_>, menus: Query<'_, '_, Entity, With<ModeMenu>>) {
+    for menu in &menus {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+/// Colors the highlighted mode
+fn highlight_mode_menu(
+    menus: Query<'_, '_, &Menu, (With<ModeMenu>, Changed<Menu>)>,
+    mut options: Query<'_, '_, (&ModeOption, &mut TextColor)>,
+) {
+    for menu in &menus {
+        for (option, mut color) in &mut options {
+            color.0 = if option.0 == menu.selected {
+                SELECTED_COLOR
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+/// Sets the mode once one is picked and starts a game in it
+fn choose_mode(
+    mut selections: EventReader<'_, '_, MenuSelected>,
+    mut new_game: EventWriter<'_, NewGame>,
+    menus: Query<'_, '_, (), With<ModeMenu>>,
+    mut mode: ResMut<'_, FreeThrowMode>,
+) {
+    for selection in selections.read() {
+        if menus.get(selection.menu).is_ok() {
+            *mode = FreeThrowMode::ALL[selection.index];
+            new_game.send(NewGame);
+        }
+    }
+}
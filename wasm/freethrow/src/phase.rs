@@ -0,0 +1,27 @@
+//! Phases a free-throw game moves through, from picking a mode to the final score
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of play
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FreeThrowPhase {
+    /// The mode menu is up
+    #[default]
+    ModeSelect,
+    /// The shooter up has the ball and is lining up their shot
+    Aiming,
+    /// The ball is in the air or coming off the rim
+    Flying,
+    /// The game is finished and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct FreeThrowPhasePlugin;
+
+impl Plugin for FreeThrowPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<FreeThrowPhase>();
+    }
+}
@@ -0,0 +1,413 @@
+//! Running a game in either mode: the shot clock each player shoots against, working round the
+//! spots in around the world, and showing every player's progress on the shared scorecard HUD
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    hoop::{free_throw_spot, spot, SPOTS},
+    mode::FreeThrowMode,
+    phase::FreeThrowPhase,
+    shot::{Ball, ShotOver},
+};
+
+/// How long each player's shot clock runs, in seconds
+pub const SHOT_CLOCK_SECS: f32 = 60.0;
+
+/// Asks for the game to be started over in the current mode
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// One player's shooting so far
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Card {
+    /// Shots they've made
+    pub made: u32,
+    /// Shots they've taken
+    pub taken: u32,
+    /// The around-the-world spot they're shooting from, or how many they've cleared
+    pub spot: usize,
+    /// Whether their shot clock has run out
+    pub done: bool,
+}
+
+/// Every player's shooting in the game being played
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    /// Each player's card, in turn order
+    cards: Vec<Card>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a game for a number of players with nothing shot
+    pub fn new(players: usize) -> Self {
+        Self {
+            cards: vec![Card::default(); players.max(1)],
+        }
+    }
+
+    /// How many players are in the game
+    pub fn players(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// A player's card
+    pub fn card(&self, player: usize) -> Card {
+        self.cards.get(player).copied().unwrap_or_default()
+    }
+
+    /// Records a shot a player took, moving them on to the next spot if they made it
+    pub fn record(&mut self, player: usize, made: bool) {
+        if let Some(card) = self.cards.get_mut(player) {
+            card.taken += 1;
+            if made {
+                card.made += 1;
+                card.spot = (card.spot + 1).min(SPOTS);
+            }
+        }
+    }
+
+    /// Marks a player's shot clock as run out
+    pub fn finish(&mut self, player: usize) {
+        if let Some(card) = self.cards.get_mut(player) {
+            card.done = true;
+        }
+    }
+
+    /// Where on the floor a player shoots from in a mode
+    pub fn shooting_spot(&self, mode: FreeThrowMode, player: usize) -> Vec3 {
+        match mode {
+            FreeThrowMode::ShotClock => free_throw_spot(),
+            FreeThrowMode::AroundTheWorld => spot(self.card(player).spot),
+        }
+    }
+
+    /// A player's score in a mode: makes against the clock, or spots cleared round the world
+    pub fn score(&self, mode: FreeThrowMode, player: usize) -> u32 {
+        let card = self.card(player);
+        match mode {
+            FreeThrowMode::ShotClock => card.made,
+            FreeThrowMode::AroundTheWorld => card.spot as u32,
+        }
+    }
+
+    /// The player who's won, once the game is decided: the most makes once every clock has run
+    /// out, the first of them on a tie, or the first to clear every spot
+    pub fn winner(&self, mode: FreeThrowMode) -> Option<usize> {
+        match mode {
+            FreeThrowMode::ShotClock => {
+                if !self.cards.iter().all(|card| card.done) {
+                    return None;
+                }
+                (0..self.players())
+                    .rev()
+                    .max_by_key(|player| self.score(mode, *player))
+            }
+            FreeThrowMode::AroundTheWorld => {
+                (0..self.players()).find(|player| self.card(*player).spot >= SPOTS)
+            }
+        }
+    }
+}
+
+/// Time left on the shot clock of the player up
+#[derive(Resource, Debug)]
+pub struct ShotClock(pub Timer);
+
+impl Default for ShotClock {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SHOT_CLOCK_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct FreeThrowSnapshot<'a> {
+    /// How the game is played
+    mode: FreeThrowMode,
+    /// Player up to shoot
+    player: usize,
+    /// Seconds left on the player up's shot clock
+    clock: f32,
+    /// Every player's shooting so far
+    scoreboard: &'a Scoreboard,
+    /// Where the game is at
+    phase: FreeThrowPhase,
+}
+
+/// Plugin that runs the game in the chosen mode and shows the score on the scorecard HUD
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<ShotClock>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    run_shot_clock
+                        .run_if(resource_equals(FreeThrowMode::ShotClock))
+                        .run_if(
+                            in_state(FreeThrowPhase::Aiming).or(in_state(FreeThrowPhase::Flying)),
+                        ),
+                    finish_shot,
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(FreeThrowPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(FreeThrowPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Everything the end of a shot or a turn changes
+#[derive(SystemParam)]
+struct TurnEffects<'w> {
+    /// Every player's shooting
+    scoreboard: ResMut<'w, Scoreboard>,
+    /// Whose shot is next
+    turns: ResMut<'w, TurnManager>,
+    /// The player up's shot clock
+    clock: ResMut<'w, ShotClock>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the game goes next
+    next_phase: ResMut<'w, NextState<FreeThrowPhase>>,
+}
+
+impl TurnEffects<'_> {
+    /// Ends the player up's shot clock, handing the ball to the next player with time left or
+    /// finishing the game once everyone has shot
+    fn end_clock(&mut self) {
+        let player = self.turns.current();
+        self.scoreboard.finish(player);
+        self.banner.show(format!(
+            "Time! Player {} made {}",
+            player + 1,
+            self.scoreboard.card(player).made
+        ));
+        let scoreboard = &self.scoreboard;
+        if self
+            .turns
+            .advance_until(|player| scoreboard.card(player).done)
+            .is_some()
+        {
+            self.clock.0.reset();
+            self.next_phase.set(FreeThrowPhase::Aiming);
+        } else {
+            self.next_phase.set(FreeThrowPhase::GameOver);
+        }
+    }
+}
+
+/// Runs the player up's shot clock down, ending their turn if it runs out while they're lining
+/// up. A shot already in the air when it runs out still counts
+fn run_shot_clock(
+    mut effects: TurnEffects<'_>,
+    phase: Res<'_, State<FreeThrowPhase>>,
+    time: Res<'_, Time>,
+) {
+    effects.clock.0.tick(time.delta());
+    if effects.clock.0.finished() && *phase.get() == FreeThrowPhase::Aiming {
+        effects.end_clock();
+    }
+}
+
+/// Scores the shot just taken and works out who shoots next and from where
+fn finish_shot(
+    mut shots: EventReader<'_, '_, ShotOver>,
+    mut effects: TurnEffects<'_>,
+    mode: Res<'_, FreeThrowMode>,
+) {
+    let Some(shot) = shots.read().last().copied() else {
+        return;
+    };
+
+    let player = effects.turns.current();
+    effects.scoreboard.record(player, shot.made);
+    effects.banner.show(shot.describe());
+    match *mode {
+        FreeThrowMode::ShotClock if effects.clock.0.finished() => effects.end_clock(),
+        FreeThrowMode::ShotClock => effects.next_phase.set(FreeThrowPhase::Aiming),
+        FreeThrowMode::AroundTheWorld => {
+            if effects.scoreboard.winner(*mode).is_some() {
+                effects.next_phase.set(FreeThrowPhase::GameOver);
+                return;
+            }
+            if !shot.made {
+                effects.turns.advance();
+            }
+            effects.next_phase.set(FreeThrowPhase::Aiming);
+        }
+    }
+}
+
+/// Starts the game over in the current mode from the first player's first shot
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut clock: ResMut<'_, ShotClock>,
+    mut next_phase: ResMut<'_, NextState<FreeThrowPhase>>,
+    balls: Query<'_, '_, Entity, With<Ball>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for ball in &balls {
+        commands.entity(ball).despawn_recursive();
+    }
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    clock.0.reset();
+    next_phase.set(FreeThrowPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every player's shooting and the player up's clock or spot
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    turns: Res<'_, TurnManager>,
+    clock: Res<'_, ShotClock>,
+    mode: Res<'_, FreeThrowMode>,
+) {
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            let card = scoreboard.card(player);
+            match *mode {
+                FreeThrowMode::ShotClock => {
+                    format!("Player {}: {} of {}", player + 1, card.made, card.taken)
+                }
+                FreeThrowMode::AroundTheWorld => format!(
+                    "Player {}: spot {} of {SPOTS} ({} shots)",
+                    player + 1,
+                    (card.spot + 1).min(SPOTS),
+                    card.taken
+                ),
+            }
+        })
+        .collect();
+    let player = turns.current() + 1;
+    let footer = match *mode {
+        FreeThrowMode::ShotClock => format!(
+            "Player {player} shooting, {}s left",
+            clock.0.remaining_secs().ceil()
+        ),
+        FreeThrowMode::AroundTheWorld => {
+            format!("Player {player} shooting, make it to move on")
+        }
+    };
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Free Throws, {}", mode.name()),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's score once the game is over, calling the winner
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    mode: Res<'_, FreeThrowMode>,
+) {
+    let mut lines = vec!["Final Scores".to_string()];
+    if let Some(winner) = scoreboard
+        .winner(*mode)
+        .filter(|_| scoreboard.players() > 1)
+    {
+        lines.push(format!("Player {} wins!", winner + 1));
+    }
+    for player in 0..scoreboard.players() {
+        let card = scoreboard.card(player);
+        lines.push(match *mode {
+            FreeThrowMode::ShotClock => {
+                format!(
+                    "Player {}: {} made of {}",
+                    player + 1,
+                    card.made,
+                    card.taken
+                )
+            }
+            FreeThrowMode::AroundTheWorld => format!(
+                "Player {}: {} spots in {} shots",
+                player + 1,
+                card.spot,
+                card.taken
+            ),
+        });
+    }
+    lines.push("Press A to play again, B to change mode".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's score back to the page once the game is over, so it can submit them to
+/// the server
+fn submit_result(
+    scoreboard: Res<'_, Scoreboard>,
+    mode: Res<'_, FreeThrowMode>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = (0..scoreboard.players())
+        .map(|player| scoreboard.score(*mode, player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    clock: Res<'_, ShotClock>,
+    mode: Res<'_, FreeThrowMode>,
+    phase: Res<'_, State<FreeThrowPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&FreeThrowSnapshot {
+        mode: *mode,
+        player: turns.current(),
+        clock: clock.0.remaining_secs(),
+        scoreboard: &scoreboard,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,217 @@
+//! The ball: held at the release point while lining up, then shot at the rim and watched until
+//! it drops through the net or falls away, noting what it touched on the way
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    ActiveEvents, Ccd, Collider, ColliderMassProperties, CollisionEvent, Friction, Restitution,
+    RigidBody, Velocity,
+};
+use spjorts_core::spectator::is_playing;
+
+use crate::{
+    hoop::{Backboard, Rim, RIM_CENTRE, RIM_HEIGHT, RIM_RADIUS},
+    phase::FreeThrowPhase,
+};
+
+/// Radius of the ball
+pub const BALL_RADIUS: f32 = 0.12;
+/// Height the ball leaves the hand at, up above the shooter's head
+pub const RELEASE_HEIGHT: f32 = 2.2;
+/// Gravity pulling the ball down, matching the physics' gravity
+pub const GRAVITY: f32 = 9.81;
+/// Mass of the ball, in kilograms
+const BALL_MASS: f32 = 0.62;
+/// Bounciness of the ball. Rapier averages it with whatever it hits, so the rim, backboard and
+/// floor carry the rest
+const BALL_RESTITUTION: f32 = 0.8;
+/// How far in front of the shooter's feet the ball is let go
+const RELEASE_REACH: f32 = 0.3;
+/// How far below the rim the ball has to fall before the shot is over
+const FALLEN_BELOW: f32 = 1.0;
+/// Longest a shot is watched for before it's called a miss, in seconds
+const MAX_SHOT_SECS: f32 = 5.0;
+
+/// Where the ball leaves the hand for a shot from a spot on the floor
+pub fn release_point(spot: Vec3) -> Vec3 {
+    let toward = (RIM_CENTRE - spot).with_y(0.0).normalize_or_zero();
+    spot + toward * RELEASE_REACH + Vec3::Y * RELEASE_HEIGHT
+}
+
+/// A shot at the rim
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Shot {
+    /// Angle the ball leaves the hand at, in radians above level
+    pub arc: f32,
+    /// How far the shot is turned from straight at the rim, in radians. Positive turns left
+    pub aim: f32,
+    /// How fast the ball leaves the hand, in meters per second
+    pub speed: f32,
+}
+
+impl Shot {
+    /// Velocity the ball leaves the hand with when let go from `from`
+    pub fn velocity(&self, from: Vec3) -> Vec3 {
+        let toward = (RIM_CENTRE - from).with_y(0.0).normalize_or(Vec3::NEG_Z);
+        let level = Quat::from_rotation_y(self.aim) * toward;
+        (level * self.arc.cos() + Vec3::Y * self.arc.sin()) * self.speed
+    }
+}
+
+/// How a shot ended
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShotOver {
+    /// Whether it dropped through the rim
+    pub made: bool,
+    /// Whether it touched the rim on the way
+    pub rim: bool,
+    /// Whether it came off the backboard on the way
+    pub board: bool,
+}
+
+impl ShotOver {
+    /// The call to show players
+    pub fn describe(&self) -> &'static str {
+        match (self.made, self.rim, self.board) {
+            (true, false, false) => "Swish!",
+            (true, _, true) => "Off the glass and in!",
+            (true, true, false) => "In off the rim!",
+            (false, false, false) => "Airball!",
+            (false, true, _) => "Rimmed out",
+            (false, false, true) => "Off the glass",
+        }
+    }
+}
+
+/// The ball, held or shot
+#[derive(Component, Debug, Default)]
+pub struct Ball {
+    /// Seconds since it was shot, or `None` while it's still in hand
+    pub shot_for: Option<f32>,
+    /// Height it was at last frame, to catch it dropping through the rim
+    last_y: f32,
+    /// Whether it's dropped through the rim
+    made: bool,
+    /// Whether it's touched the rim
+    rim: bool,
+    /// Whether it's come off the backboard
+    board: bool,
+}
+
+/// Plugin that racks, shoots and watches the ball
+pub struct ShotPlugin;
+
+impl Plugin for ShotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Shot>().add_event::<ShotOver>().add_systems(
+            Update,
+            (note_touches, watch_ball)
+                .chain()
+                .run_if(in_state(FreeThrowPhase::Flying))
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Puts a fresh ball at the release point, held still until it's shot
+pub fn rack_ball(
+    commands: &mut Commands<'_, '_>,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    at: Vec3,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS).mesh().uv(24, 16))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.85, 0.4, 0.1),
+            perceptual_roughness: 0.8,
+            ..default()
+        })),
+        Transform::from_translation(at),
+        RigidBody::KinematicPositionBased,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Mass(BALL_MASS),
+        Friction::coefficient(0.6),
+        Restitution::coefficient(BALL_RESTITUTION),
+        Ccd::enabled(),
+        ActiveEvents::COLLISION_EVENTS,
+        Velocity::zero(),
+        Ball::default(),
+    ));
+}
+
+/// Lets the held ball go along a shot
+pub fn shoot_ball(
+    commands: &mut Commands<'_, '_>,
+    entity: Entity,
+    ball: &mut Ball,
+    transform: &Transform,
+    shot: &Shot,
+) {
+    *ball = Ball {
+        shot_for: Some(0.0),
+        last_y: transform.translation.y,
+        ..default()
+    };
+    commands.entity(entity).insert((
+        RigidBody::Dynamic,
+        Velocity::linear(shot.velocity(transform.translation)),
+    ));
+}
+
+/// Notes the ball touching the rim or the backboard
+fn note_touches(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut balls: Query<'_, '_, &mut Ball>,
+    rims: Query<'_, '_, (), With<Rim>>,
+    boards: Query<'_, '_, (), With<Backboard>>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let (ball, other) = if balls.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let Ok(mut ball) = balls.get_mut(ball) else {
+            continue;
+        };
+        ball.rim |= rims.contains(other);
+        ball.board |= boards.contains(other);
+    }
+}
+
+/// Catches the ball dropping down through the rim, and calls the shot once it's fallen well
+/// below the rim or been in play too long
+fn watch_ball(
+    mut commands: Commands<'_, '_>,
+    mut balls: Query<'_, '_, (Entity, &Transform, &Velocity, &mut Ball)>,
+    mut over: EventWriter<'_, ShotOver>,
+    time: Res<'_, Time>,
+) {
+    for (entity, transform, velocity, mut ball) in &mut balls {
+        let Some(shot_for) = &mut ball.shot_for else {
+            continue;
+        };
+        *shot_for += time.delta_secs();
+        let timed_out = *shot_for > MAX_SHOT_SECS;
+
+        let at = transform.translation;
+        let through = (at - RIM_CENTRE).with_y(0.0).length() < RIM_RADIUS;
+        if through && ball.last_y >= RIM_HEIGHT && at.y < RIM_HEIGHT {
+            ball.made = true;
+        }
+        ball.last_y = at.y;
+
+        let fallen = at.y < RIM_HEIGHT - FALLEN_BELOW && velocity.linvel.y < 0.0;
+        if fallen || timed_out {
+            commands.entity(entity).despawn_recursive();
+            over.send(ShotOver {
+                made: ball.made,
+                rim: ball.rim,
+                board: ball.board,
+            });
+        }
+    }
+}
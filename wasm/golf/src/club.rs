@@ -0,0 +1,109 @@
+//! The clubs a player can pick from, each trading distance for height and touch
+
+use serde::{Deserialize, Serialize};
+use spjorts_core::gesture::GestureThresholds;
+
+/// A golf club, cycled through with B while aiming
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Club {
+    /// Long and low, for getting off the tee
+    #[default]
+    Driver,
+    /// The middle of the bag, for approaching the green
+    Iron,
+    /// High and short, for chipping onto the green
+    Wedge,
+    /// Rolls the ball along the ground, for finishing on the green
+    Putter,
+}
+
+impl Club {
+    /// Name of the club to show players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Driver => "Driver",
+            Self::Iron => "Iron",
+            Self::Wedge => "Wedge",
+            Self::Putter => "Putter",
+        }
+    }
+
+    /// The next club in the bag, wrapping back around to the driver
+    pub fn next(self) -> Self {
+        match self {
+            Self::Driver => Self::Iron,
+            Self::Iron => Self::Wedge,
+            Self::Wedge => Self::Putter,
+            Self::Putter => Self::Driver,
+        }
+    }
+
+    /// Angle the ball leaves the club at above the ground, in radians
+    pub fn loft(&self) -> f32 {
+        match self {
+            Self::Driver => 0.22,
+            Self::Iron => 0.45,
+            Self::Wedge => 0.85,
+            Self::Putter => 0.0,
+        }
+    }
+
+    /// Speed the ball leaves the club at from a full swing, in meters per second
+    pub fn max_speed(&self) -> f32 {
+        match self {
+            Self::Driver => 32.0,
+            Self::Iron => 20.0,
+            Self::Wedge => 15.0,
+            Self::Putter => 7.0,
+        }
+    }
+
+    /// Roughly how far a full swing sends the ball on the fairway, carry and roll together, in
+    /// meters
+    pub fn reach(&self) -> f32 {
+        match self {
+            Self::Driver => 65.0,
+            Self::Iron => 40.0,
+            Self::Wedge => 22.0,
+            Self::Putter => 12.0,
+        }
+    }
+
+    /// How fast the controller has to turn for a full swing, in radians per second. Anything
+    /// slower hits the ball proportionally softer
+    pub fn full_swing_speed(&self) -> f32 {
+        match self {
+            Self::Driver => 12.0,
+            Self::Iron => 10.0,
+            Self::Wedge => 8.0,
+            Self::Putter => 4.0,
+        }
+    }
+
+    /// Thresholds swings with this club are detected with. Putts are gentle enough that they
+    /// need far lower ones than a full swing
+    pub fn thresholds(&self) -> GestureThresholds {
+        match self {
+            Self::Putter => GestureThresholds {
+                swing_speed: 1.0,
+                flick_speed: 3.0,
+                rest_speed: 0.4,
+                ..Default::default()
+            },
+            _ => GestureThresholds::default(),
+        }
+    }
+
+    /// The club a player would reach for first from a spot a distance away from the cup
+    pub fn for_distance(distance: f32, on_green: bool) -> Self {
+        if on_green {
+            Self::Putter
+        } else if distance > Self::Iron.reach() {
+            Self::Driver
+        } else if distance > Self::Wedge.reach() {
+            Self::Iron
+        } else {
+            Self::Wedge
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! The hole being played: a tee, a fairway running up to a round green with the cup, and rough
+//! everywhere else. Each zone slows a rolling ball down differently
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Ccd, Collider, Damping, Friction, Restitution, RigidBody, Velocity};
+use serde::{Deserialize, Serialize};
+
+/// Radius of the ball, scaled up from a real one so it can be followed down the fairway
+pub const BALL_RADIUS: f32 = 0.15;
+/// Where every player tees off from
+pub const TEE: Vec3 = Vec3::new(0.0, BALL_RADIUS, 0.0);
+/// Where the cup is, on the ground at the middle of the green
+pub const CUP: Vec3 = Vec3::new(3.0, 0.0, -80.0);
+/// Strokes a good player takes to hole out
+pub const PAR: u32 = 4;
+
+/// Half the width of the fairway
+const FAIRWAY_HALF_WIDTH: f32 = 7.0;
+/// Radius of the green around the cup
+const GREEN_RADIUS: f32 = 8.0;
+/// Radius of the cup, wide enough to catch a well hit putt
+pub const CUP_RADIUS: f32 = 0.3;
+/// Half the width of the course, past which the ball is out of bounds
+const COURSE_HALF_WIDTH: f32 = 30.0;
+/// How far behind the tee the course goes
+const COURSE_BACK: f32 = 10.0;
+/// How far past the cup the course goes
+const COURSE_PAST_CUP: f32 = 20.0;
+/// Height of the flagstick planted in the cup
+const FLAG_HEIGHT: f32 = 2.5;
+/// Damping on a ball in the air, barely any so wind has something to push
+pub const AIR_DAMPING: f32 = 0.05;
+
+/// Marks the golf ball
+#[derive(Component, Debug, Default)]
+pub struct Ball;
+
+/// A part of the course, slowing a rolling ball by its own amount
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// Short grass along the line from the tee to the green
+    Fairway,
+    /// Shortest grass around the cup, where the ball rolls furthest
+    Green,
+    /// Long grass off the fairway that grabs the ball
+    Rough,
+}
+
+impl Zone {
+    /// Finds which zone a spot on the course is in
+    pub fn at(position: Vec3) -> Self {
+        if position.xz().distance(CUP.xz()) <= GREEN_RADIUS {
+            return Self::Green;
+        }
+
+        // The fairway runs straight from the tee to the cup
+        let along = (position.z / CUP.z).clamp(0.0, 1.0);
+        let centre_x = TEE.x + (CUP.x - TEE.x) * along;
+        let before_green = position.z <= TEE.z + 2.0 && position.z >= CUP.z;
+        if before_green && (position.x - centre_x).abs() <= FAIRWAY_HALF_WIDTH {
+            Self::Fairway
+        } else {
+            Self::Rough
+        }
+    }
+
+    /// Name of the zone to show players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fairway => "Fairway",
+            Self::Green => "Green",
+            Self::Rough => "Rough",
+        }
+    }
+
+    /// Damping on a ball rolling through the zone
+    pub fn damping(&self) -> Damping {
+        let (linear_damping, angular_damping) = match self {
+            Self::Fairway => (0.8, 0.8),
+            Self::Green => (0.45, 0.6),
+            Self::Rough => (2.5, 2.5),
+        };
+        Damping {
+            linear_damping,
+            angular_damping,
+        }
+    }
+}
+
+/// Whether a spot has left the course, costing a penalty stroke
+pub fn out_of_bounds(position: Vec3) -> bool {
+    position.x.abs() > COURSE_HALF_WIDTH
+        || position.z > TEE.z + COURSE_BACK
+        || position.z < CUP.z - COURSE_PAST_CUP
+        || position.y < -1.0
+}
+
+/// Whether a ball is resting on the ground rather than in the air
+pub fn on_ground(position: Vec3) -> bool {
+    position.y <= BALL_RADIUS + 0.05
+}
+
+/// Plugin that lays out the hole
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_course);
+    }
+}
+
+/// Spawns the ground, the zones painted on it, the flag, the ball and the lights
+fn setup_course(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let course_length = COURSE_BACK + TEE.z - CUP.z + COURSE_PAST_CUP;
+    let course_centre_z = TEE.z + COURSE_BACK - course_length / 2.0;
+
+    // Rough covers the whole course, with the fairway and green painted just above it
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(COURSE_HALF_WIDTH * 2.0, course_length),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.16, 0.38, 0.12))),
+        Transform::from_xyz(0.0, 0.0, course_centre_z),
+        Name::new("Rough"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, course_centre_z),
+        RigidBody::Fixed,
+        Collider::cuboid(COURSE_HALF_WIDTH, 0.1, course_length / 2.0),
+        Friction::coefficient(0.8),
+        Name::new("Ground"),
+    ));
+
+    let fairway_length = TEE.xz().distance(CUP.xz()) + 2.0;
+    let fairway_centre = (TEE + CUP) / 2.0;
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(FAIRWAY_HALF_WIDTH * 2.0, fairway_length),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.28, 0.6, 0.2))),
+        Transform::from_xyz(fairway_centre.x, 0.005, fairway_centre.z).looking_to(
+            Vec3::new(CUP.x - TEE.x, 0.0, CUP.z - TEE.z).normalize(),
+            Vec3::Y,
+        ),
+        Name::new("Fairway"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(GREEN_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(0.4, 0.75, 0.3))),
+        Transform::from_xyz(CUP.x, 0.01, CUP.z)
+            .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+        Name::new("Green"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(CUP_RADIUS))),
+        MeshMaterial3d(materials.add(Color::BLACK)),
+        Transform::from_xyz(CUP.x, 0.015, CUP.z)
+            .with_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+        Name::new("Cup"),
+    ));
+
+    // The flagstick has no collider, so a ball running at the cup drops in rather than bouncing
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Cylinder::new(0.03, FLAG_HEIGHT))),
+            MeshMaterial3d(materials.add(Color::WHITE)),
+            Transform::from_translation(CUP + Vec3::Y * FLAG_HEIGHT / 2.0),
+            Name::new("Flagstick"),
+        ))
+        .with_children(|pole| {
+            pole.spawn((
+                Mesh3d(meshes.add(Cuboid::new(0.8, 0.5, 0.02))),
+                MeshMaterial3d(materials.add(Color::srgb(0.9, 0.15, 0.15))),
+                Transform::from_xyz(0.4, FLAG_HEIGHT / 2.0 - 0.25, 0.0),
+            ));
+        });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_translation(TEE),
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        Restitution::coefficient(0.35),
+        Friction::coefficient(0.6),
+        Ccd::enabled(),
+        Velocity::zero(),
+        Zone::at(TEE).damping(),
+        Ball,
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(10.0, 20.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
@@ -0,0 +1,418 @@
+//! Bevy golf game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{Damping, Velocity},
+};
+use club::Club;
+use course::{
+    on_ground, out_of_bounds, Ball, CoursePlugin, Zone, AIR_DAMPING, CUP, CUP_RADIUS, TEE,
+};
+use phase::{GolfPhase, GolfPhasePlugin};
+use scorecard::{Scorecard, ScorecardPlugin};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+use wind::WindPlugin;
+
+pub mod club;
+pub mod course;
+pub mod phase;
+pub mod scorecard;
+pub mod wind;
+
+/// Widest a shot can be aimed away from the cup, in radians
+const MAX_AIM: f32 = 0.8;
+/// Weakest a detected swing hits the ball, as a fraction of a full swing
+const MIN_POWER: f32 = 0.1;
+/// Speed below which the ball counts as stopped, in meters per second
+const REST_SPEED: f32 = 0.1;
+/// How long the ball has to stay stopped before the shot is over, in seconds
+const REST_SECS: f32 = 0.5;
+/// Fastest the ball can be going over the cup and still drop in, in meters per second
+const CUP_CAPTURE_SPEED: f32 = 3.0;
+/// Length of the aim arrow drawn from the ball while aiming
+const AIM_ARROW_LENGTH: f32 = 3.0;
+/// How far behind the ball the camera sits
+const CAMERA_BACK: f32 = 6.0;
+/// How far above the ball the camera sits
+const CAMERA_UP: f32 = 2.5;
+/// How quickly the camera catches up with the ball, higher is snappier
+const CAMERA_SMOOTHING: f32 = 4.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(GolfPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(CoursePlugin)
+    .add_plugins(WindPlugin)
+    .add_plugins(ScorecardPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Shot>()
+    .add_event::<Swing>()
+    .add_event::<NewGame>()
+    .add_systems(Startup, setup_camera)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            strike_ball.run_if(in_state(GolfPhase::Aiming)),
+            track_ball.run_if(in_state(GolfPhase::Flying)),
+            start_new_game,
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        (
+            follow_ball,
+            draw_aim_guide.run_if(in_state(GolfPhase::Aiming)),
+        ),
+    );
+});
+
+/// The shot the player whose turn it is is lining up, or has just hit
+#[derive(Resource, Debug)]
+pub struct Shot {
+    /// The club in hand
+    pub club: Club,
+    /// How far the shot is aimed away from the cup, in radians
+    pub aim: f32,
+    /// Where the ball was last hit from, so it can be dropped back there when it goes out of
+    /// bounds
+    lie: Vec3,
+    /// Watches the controller for swings
+    detector: GestureDetector,
+    /// How long the ball has been stopped for, in seconds
+    stopped_for: f32,
+}
+
+impl Default for Shot {
+    fn default() -> Self {
+        Self::from(TEE)
+    }
+}
+
+impl From<Vec3> for Shot {
+    fn from(lie: Vec3) -> Self {
+        let club = Club::for_distance(lie.xz().distance(CUP.xz()), Zone::at(lie) == Zone::Green);
+        Self {
+            club,
+            aim: 0.0,
+            lie,
+            detector: GestureDetector::new(club.thresholds()),
+            stopped_for: 0.0,
+        }
+    }
+}
+
+impl Shot {
+    /// Puts a club in hand, detecting swings with its thresholds
+    pub fn set_club(&mut self, club: Club) {
+        self.club = club;
+        self.detector = GestureDetector::new(club.thresholds());
+    }
+
+    /// Which way the ball heads along the ground when hit, from the line to the cup turned by
+    /// the aim
+    fn direction(&self) -> Vec3 {
+        let to_cup = (CUP - self.lie).with_y(0.0).normalize_or(Vec3::NEG_Z);
+        Quat::from_rotation_y(self.aim) * to_cup
+    }
+}
+
+/// A swing that connected with the ball
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Swing {
+    /// How hard the ball was hit, as a fraction of the club's full swing
+    pub power: f32,
+}
+
+/// Asks for the hole to be started over from the first player's tee shot
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Marks the camera following the ball
+#[derive(Component)]
+struct FollowCamera;
+
+/// Spawns the camera behind the tee
+fn setup_camera(mut commands: Commands<'_, '_>) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(TEE + Vec3::new(0.0, CAMERA_UP, CAMERA_BACK))
+            .looking_at(TEE, Vec3::Y),
+        FollowCamera,
+    ));
+}
+
+/// Everything input handling changes besides the shot itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Swings to strike the ball with
+    swing: EventWriter<'w, Swing>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: rotation aims and swings, B changes club, and A starts a new game
+/// once the hole is done
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut shot: ResMut<'_, Shot>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<GolfPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == GolfPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == GolfPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonB if aiming => {
+                let next = shot.club.next();
+                shot.set_club(next);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation @ Orientation { yaw, .. } = settings.apply_rotation(orientation);
+                shot.aim = yaw.clamp(-MAX_AIM, MAX_AIM);
+
+                let Some(detected) = shot.detector.update(orientation, time.elapsed_secs()) else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+                    let power = detected.intensity / shot.club.full_swing_speed();
+                    effects.swing.send(Swing {
+                        power: power.clamp(MIN_POWER, 1.0),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Hits the ball with the club in hand, counting the stroke
+fn strike_ball(
+    mut swings: EventReader<'_, '_, Swing>,
+    mut ball: Query<'_, '_, (&Transform, &mut Velocity, &mut Damping), With<Ball>>,
+    mut shot: ResMut<'_, Shot>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut next_phase: ResMut<'_, NextState<GolfPhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(swing) = swings.read().last().copied() else {
+        return;
+    };
+    let Ok((transform, mut velocity, mut damping)) = ball.get_single_mut() else {
+        return;
+    };
+
+    shot.lie = transform.translation;
+    shot.stopped_for = 0.0;
+    let speed = shot.club.max_speed() * swing.power.clamp(0.0, 1.0);
+    let loft = shot.club.loft();
+    velocity.linvel = shot.direction() * speed * loft.cos() + Vec3::Y * speed * loft.sin();
+    velocity.angvel = Vec3::ZERO;
+    *damping = if loft > 0.0 {
+        Damping {
+            linear_damping: AIR_DAMPING,
+            angular_damping: AIR_DAMPING,
+        }
+    } else {
+        Zone::at(transform.translation).damping()
+    };
+
+    scorecard.add_stroke(turns.current());
+    next_phase.set(GolfPhase::Flying);
+}
+
+/// Everything watching the ball changes once a shot is over
+#[derive(SystemParam)]
+struct ShotOutcome<'w> {
+    /// The card strokes and penalties are counted on
+    scorecard: ResMut<'w, Scorecard>,
+    /// Whose turn it is
+    turns: ResMut<'w, TurnManager>,
+    /// Messages shown to players
+    banner: ResMut<'w, Banner>,
+    /// Phase to move on to
+    next_phase: ResMut<'w, NextState<GolfPhase>>,
+}
+
+impl ShotOutcome<'_> {
+    /// Moves on once a shot is over: the same player goes again from where the ball lies, or if
+    /// they're done with the hole the next player tees off, or the game ends. Returns where the
+    /// ball should be placed next
+    fn finish(&mut self, ball: Vec3) -> Vec3 {
+        let player = self.turns.current();
+        if self.scorecard.out_of_strokes(player) {
+            self.scorecard.hole_out(player);
+            self.banner.show("Picked up");
+        }
+
+        if !self.scorecard.is_holed(player) {
+            self.next_phase.set(GolfPhase::Aiming);
+            return ball;
+        }
+
+        let scorecard = &self.scorecard;
+        if self
+            .turns
+            .advance_until(|player| scorecard.is_holed(player))
+            .is_some()
+        {
+            self.next_phase.set(GolfPhase::Aiming);
+        } else {
+            self.next_phase.set(GolfPhase::GameOver);
+        }
+        TEE
+    }
+}
+
+/// Follows the ball after a shot, slowing it by the zone it rolls through and watching for it
+/// dropping into the cup, leaving the course or coming to rest
+fn track_ball(
+    mut ball: Query<'_, '_, (&mut Transform, &mut Velocity, &mut Damping), With<Ball>>,
+    mut shot: ResMut<'_, Shot>,
+    mut outcome: ShotOutcome<'_>,
+    time: Res<'_, Time>,
+) {
+    let Ok((mut transform, mut velocity, mut damping)) = ball.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+    let speed = velocity.linvel.length();
+    let player = outcome.turns.current();
+
+    let next = if out_of_bounds(position) {
+        outcome.scorecard.add_stroke(player);
+        outcome.banner.show("Out of bounds! +1 stroke");
+        Some(shot.lie)
+    } else if on_ground(position)
+        && position.xz().distance(CUP.xz()) <= CUP_RADIUS
+        && speed <= CUP_CAPTURE_SPEED
+    {
+        outcome.scorecard.hole_out(player);
+        outcome
+            .banner
+            .show(match outcome.scorecard.strokes(player) {
+                1 => "Hole in one!".to_string(),
+                strokes => format!("Holed in {strokes} ({})", outcome.scorecard.to_par(player)),
+            });
+        Some(position)
+    } else {
+        shot.stopped_for = if speed < REST_SPEED {
+            shot.stopped_for + time.delta_secs()
+        } else {
+            0.0
+        };
+        (shot.stopped_for >= REST_SECS).then_some(position)
+    };
+
+    match next {
+        Some(lie) => {
+            let lie = outcome.finish(lie.with_y(TEE.y));
+            transform.translation = lie;
+            *velocity = Velocity::zero();
+            *damping = Zone::at(lie).damping();
+            *shot = Shot::from(lie);
+        }
+        None => {
+            *damping = if on_ground(position) {
+                Zone::at(position).damping()
+            } else {
+                Damping {
+                    linear_damping: AIR_DAMPING,
+                    angular_damping: AIR_DAMPING,
+                }
+            };
+        }
+    }
+}
+
+/// Starts the hole over from the first player's tee shot with a fresh card
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut ball: Query<'_, '_, (&mut Transform, &mut Velocity), With<Ball>>,
+    mut shot: ResMut<'_, Shot>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<GolfPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for (mut transform, mut velocity) in &mut ball {
+        transform.translation = TEE;
+        *velocity = Velocity::zero();
+    }
+    *shot = Shot::default();
+    turns.restart();
+    *scorecard = Scorecard::new(turns.players());
+    next_phase.set(GolfPhase::Aiming);
+}
+
+/// Keeps the camera behind the ball, looking the way the shot is aimed
+fn follow_ball(
+    mut camera: Query<'_, '_, &mut Transform, (With<FollowCamera>, Without<Ball>)>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    shot: Res<'_, Shot>,
+    time: Res<'_, Time>,
+) {
+    let (Ok(mut camera), Ok(ball)) = (camera.get_single_mut(), ball.get_single()) else {
+        return;
+    };
+    let target = ball.translation;
+    let wanted = target - shot.direction() * CAMERA_BACK + Vec3::Y * CAMERA_UP;
+    let blend = 1.0 - (-CAMERA_SMOOTHING * time.delta_secs()).exp();
+
+    camera.translation = camera.translation.lerp(wanted, blend);
+    camera.look_at(target, Vec3::Y);
+}
+
+/// Draws an arrow from the ball the way the shot is aimed
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    shot: Res<'_, Shot>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    for ball in &ball {
+        let start = ball.translation.with_y(0.05);
+        gizmos.arrow(
+            start,
+            start + shot.direction() * AIM_ARROW_LENGTH,
+            Color::srgb(1.0, 0.9, 0.2),
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Phases a golf game moves through, from lining up a shot to the final scorecard
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the hole is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GolfPhase {
+    /// The player whose turn it is is lining up and swinging
+    #[default]
+    Aiming,
+    /// The ball is in the air or rolling after a shot
+    Flying,
+    /// Every player has holed out or picked up, and the final scorecard is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct GolfPhasePlugin;
+
+impl Plugin for GolfPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GolfPhase>();
+    }
+}
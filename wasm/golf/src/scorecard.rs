@@ -0,0 +1,232 @@
+//! Strokes each player has taken, shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    club::Club,
+    course::{Ball, Zone, PAR},
+    phase::GolfPhase,
+    wind::Wind,
+    Shot,
+};
+
+/// Most strokes a player can take before picking up their ball, scored as that many
+pub const MAX_STROKES: u32 = 10;
+
+/// Strokes each player has taken on the hole, in turn order
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scorecard {
+    /// Strokes taken by each player, penalties included
+    strokes: Vec<u32>,
+    /// Whether each player has finished the hole, by holing out or picking up
+    holed: Vec<bool>,
+}
+
+impl Scorecard {
+    /// Starts a fresh card for a number of players
+    pub fn new(players: usize) -> Self {
+        Self {
+            strokes: vec![0; players],
+            holed: vec![false; players],
+        }
+    }
+
+    /// How many players are on the card
+    pub fn players(&self) -> usize {
+        self.strokes.len()
+    }
+
+    /// Strokes a player has taken so far
+    pub fn strokes(&self, player: usize) -> u32 {
+        self.strokes.get(player).copied().unwrap_or_default()
+    }
+
+    /// Whether a player has finished the hole
+    pub fn is_holed(&self, player: usize) -> bool {
+        self.holed.get(player).copied().unwrap_or(true)
+    }
+
+    /// Adds a stroke, or a penalty stroke, to a player's card
+    pub fn add_stroke(&mut self, player: usize) {
+        if let Some(strokes) = self.strokes.get_mut(player) {
+            *strokes += 1;
+        }
+    }
+
+    /// Whether a player has used up their strokes without holing out, and has to pick up
+    pub fn out_of_strokes(&self, player: usize) -> bool {
+        !self.is_holed(player) && self.strokes(player) >= MAX_STROKES
+    }
+
+    /// Marks a player as done with the hole
+    pub fn hole_out(&mut self, player: usize) {
+        if let Some(holed) = self.holed.get_mut(player) {
+            *holed = true;
+        }
+    }
+
+    /// Stableford points for a player's strokes: two for par, one more for every stroke under
+    /// and one fewer for every stroke over, never below zero. Higher is better, like the scores
+    /// the server ranks
+    pub fn points(&self, player: usize) -> u32 {
+        (PAR + 2).saturating_sub(self.strokes(player))
+    }
+
+    /// A player's strokes relative to par, like `E`, `-1` or `+2`
+    pub fn to_par(&self, player: usize) -> String {
+        match i64::from(self.strokes(player)) - i64::from(PAR) {
+            0 => "E".to_string(),
+            diff if diff > 0 => format!("+{diff}"),
+            diff => diff.to_string(),
+        }
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct GolfSnapshot<'a> {
+    /// Player whose turn it is
+    player: usize,
+    /// The card so far
+    scorecard: &'a Scorecard,
+    /// Strokes a good player takes to hole out
+    par: u32,
+    /// The club in hand
+    club: Club,
+    /// Wind blowing this turn
+    wind: &'a Wind,
+    /// Where the game is at
+    phase: GolfPhase,
+}
+
+/// Plugin that keeps the scorecard, shows it and reports the final result
+pub struct ScorecardPlugin;
+
+impl Plugin for ScorecardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scorecard>()
+            .add_systems(
+                Update,
+                (
+                    fit_scorecard.run_if(resource_changed::<TurnManager>),
+                    update_hud,
+                    update_snapshot,
+                ),
+            )
+            .add_systems(
+                OnEnter(GolfPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(GolfPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh card whenever the number of players changes
+fn fit_scorecard(turns: Res<'_, TurnManager>, mut scorecard: ResMut<'_, Scorecard>) {
+    if scorecard.players() != turns.players() {
+        *scorecard = Scorecard::new(turns.players());
+    }
+}
+
+/// Fills in the scorecard HUD with every player's strokes, the club in hand, the lie and the wind
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    scorecard: Res<'_, Scorecard>,
+    shot: Res<'_, Shot>,
+    wind: Res<'_, Wind>,
+) {
+    let lie = ball
+        .get_single()
+        .map(|ball| Zone::at(ball.translation).name())
+        .unwrap_or_default();
+    let rows = (0..scorecard.players())
+        .map(|player| {
+            let status = if scorecard.is_holed(player) {
+                "holed"
+            } else {
+                "playing"
+            };
+            format!(
+                "Player {}: {} ({}) {status}",
+                player + 1,
+                scorecard.strokes(player),
+                scorecard.to_par(player)
+            )
+        })
+        .collect();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Par {PAR}"),
+        rows,
+        footer: format!(
+            "{} from the {lie}\nWind {:.1} m/s {}",
+            shot.club.name(),
+            wind.speed(),
+            wind.direction_name()
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's strokes and points once the hole is done
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scorecard: Res<'_, Scorecard>) {
+    let mut lines = vec!["Final Scorecard".to_string()];
+    for player in 0..scorecard.players() {
+        lines.push(format!(
+            "Player {}: {} strokes ({}), {} points",
+            player + 1,
+            scorecard.strokes(player),
+            scorecard.to_par(player),
+            scorecard.points(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scorecard when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends each player's Stableford points back to the page once the hole is done, so it can
+/// submit them to the server
+fn submit_result(scorecard: Res<'_, Scorecard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scorecard.players())
+        .map(|player| scorecard.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scorecard: Res<'_, Scorecard>,
+    shot: Res<'_, Shot>,
+    wind: Res<'_, Wind>,
+    phase: Res<'_, State<GolfPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&GolfSnapshot {
+        player: turns.current(),
+        scorecard: &scorecard,
+        par: PAR,
+        club: shot.club,
+        wind: &wind,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,111 @@
+//! Wind that pushes the ball around while it's in the air, picked afresh for every turn
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{spectator::is_playing, turns::TurnManager};
+
+use crate::{
+    course::{on_ground, Ball},
+    phase::GolfPhase,
+};
+
+/// Strongest wind a turn can get, in meters per second
+const MAX_WIND_SPEED: f32 = 6.0;
+/// How much of the wind's speed the ball picks up every second it's in the air
+const WIND_PUSH: f32 = 0.15;
+
+/// The wind blowing across the course
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Wind {
+    /// Which way the wind blows and how hard, across the ground in meters per second
+    pub velocity: Vec2,
+    /// Xorshift state the wind is picked from
+    #[serde(skip)]
+    seed: u32,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            seed: 0x2545_F491,
+        }
+    }
+}
+
+impl Wind {
+    /// How hard the wind blows, in meters per second
+    pub fn speed(&self) -> f32 {
+        self.velocity.length()
+    }
+
+    /// Describes where the wind is blowing relative to a player looking down the hole
+    pub fn direction_name(&self) -> &'static str {
+        let Vec2 { x, y: z } = self.velocity;
+        if self.speed() < 0.5 {
+            "Calm"
+        } else if z.abs() >= x.abs() {
+            if z < 0.0 {
+                "Tailwind"
+            } else {
+                "Headwind"
+            }
+        } else if x > 0.0 {
+            "Left to right"
+        } else {
+            "Right to left"
+        }
+    }
+
+    /// Picks a new wind, stirring `entropy` into the seed so every game blows differently
+    pub fn reroll(&mut self, entropy: u32) {
+        self.seed ^= entropy | 1;
+        let speed = self.next_random() * MAX_WIND_SPEED;
+        let angle = self.next_random() * std::f32::consts::TAU;
+        self.velocity = Vec2::from_angle(angle) * speed;
+    }
+
+    /// Steps the xorshift state, returning a value from 0 to 1
+    pub fn next_random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32
+    }
+}
+
+/// Plugin that adds the wind, picking a new one each turn and pushing the ball with it
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wind>().add_systems(
+            Update,
+            (
+                reroll_wind.run_if(resource_changed::<TurnManager>),
+                blow_ball.run_if(in_state(GolfPhase::Flying)),
+            )
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Picks a new wind for the player whose turn just started
+fn reroll_wind(mut wind: ResMut<'_, Wind>, time: Res<'_, Time<Real>>) {
+    wind.reroll(time.elapsed().subsec_nanos());
+}
+
+/// Pushes the ball along with the wind while it's in the air
+fn blow_ball(
+    mut ball: Query<'_, '_, (&Transform, &mut Velocity), With<Ball>>,
+    wind: Res<'_, Wind>,
+    time: Res<'_, Time>,
+) {
+    for (transform, mut velocity) in &mut ball {
+        if !on_ground(transform.translation) {
+            let push = wind.velocity * WIND_PUSH * time.delta_secs();
+            velocity.linvel += Vec3::new(push.x, 0.0, push.y);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! The field: the throwing circle, the landing sector fanning out in front of it banded every ten
+//! meters, the cage behind, the camera and the light
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::prelude::*;
+
+/// Radius of the throwing circle, marks are measured from its edge
+pub const CIRCLE_RADIUS: f32 = 1.0675;
+/// Half the angle the landing sector fans out over, either side of straight down the field, in
+/// radians. The whole sector spans 34.92 degrees
+pub const SECTOR_HALF_ANGLE: f32 = 34.92 * PI / 360.0;
+/// How far out the sector is painted
+const SECTOR_LENGTH: f32 = 100.0;
+/// Distance between the bands painted across the sector, in meters
+const BAND_SPACING: f32 = 10.0;
+/// Width of the sector lines
+const LINE_WIDTH: f32 = 0.05;
+/// Radius of the cage round the back of the circle
+const CAGE_RADIUS: f32 = 3.5;
+/// Height of the cage
+const CAGE_HEIGHT: f32 = 5.0;
+/// How much of the way round the circle the cage runs, in radians, leaving the front open
+const CAGE_SWEEP: f32 = 4.4;
+/// Posts holding up the cage netting
+const CAGE_POSTS: usize = 9;
+
+/// Wraps an angle into `-PI..PI`
+pub fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Bearing of a spot from the middle of the circle, in radians from straight down the field.
+/// Positive is toward the left of the sector, the way the hammer turns counter-clockwise
+pub fn bearing(at: Vec3) -> f32 {
+    (-at.x).atan2(-at.z)
+}
+
+/// Whether a spot is inside the sector lines
+pub fn in_sector(at: Vec3) -> bool {
+    bearing(at).abs() <= SECTOR_HALF_ANGLE
+}
+
+/// Plugin that lays out the field, the camera and the light
+pub struct FieldPlugin;
+
+impl Plugin for FieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_field);
+    }
+}
+
+/// Spawns the grass, the banded sector and its lines, the circle, the cage, the camera and the
+/// light
+fn setup_field(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    // Flat shapes are built facing +z with their middle toward +y, this lays them down facing up
+    // with their middle pointing down the field
+    let flat = Quat::from_rotation_x(-FRAC_PI_2);
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(260.0, 260.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.25, 0.5, 0.2))),
+        Transform::from_xyz(0.0, 0.0, -SECTOR_LENGTH / 2.0),
+        Name::new("Grass"),
+    ));
+
+    // Bands from the far end in, each a little higher so the nearer ones paint over the further
+    let light = materials.add(Color::srgb(0.35, 0.62, 0.28));
+    let dark = materials.add(Color::srgb(0.3, 0.56, 0.24));
+    let bands = (SECTOR_LENGTH / BAND_SPACING) as usize;
+    for band in (1..=bands).rev() {
+        let reach = band as f32 * BAND_SPACING;
+        commands.spawn((
+            Mesh3d(meshes.add(CircularSector::new(reach, SECTOR_HALF_ANGLE))),
+            MeshMaterial3d(if band % 2 == 0 {
+                light.clone()
+            } else {
+                dark.clone()
+            }),
+            Transform::from_xyz(0.0, 0.002 + 0.0002 * (bands - band) as f32, 0.0)
+                .with_rotation(flat),
+        ));
+    }
+
+    let paint = materials.add(Color::WHITE);
+    for side in [-1.0, 1.0] {
+        let angle = side * SECTOR_HALF_ANGLE;
+        let along = Quat::from_rotation_y(angle) * Vec3::NEG_Z;
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(LINE_WIDTH, 0.01, SECTOR_LENGTH))),
+            MeshMaterial3d(paint.clone()),
+            Transform::from_translation(along * SECTOR_LENGTH / 2.0 + Vec3::Y * 0.005)
+                .with_rotation(Quat::from_rotation_y(angle)),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(CIRCLE_RADIUS + LINE_WIDTH))),
+        MeshMaterial3d(paint),
+        Transform::from_xyz(0.0, 0.01, 0.0).with_rotation(flat),
+        Name::new("Circle rim"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(CIRCLE_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(0.45, 0.45, 0.45))),
+        Transform::from_xyz(0.0, 0.012, 0.0).with_rotation(flat),
+        Name::new("Throwing circle"),
+    ));
+
+    // The cage wraps round behind the circle, open toward the sector
+    let post = meshes.add(Cylinder::new(0.05, CAGE_HEIGHT));
+    let steel = materials.add(Color::srgb(0.35, 0.35, 0.4));
+    let netting = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.1, 0.1, 0.1, 0.35),
+        alpha_mode: AlphaMode::Blend,
+        cull_mode: None,
+        ..default()
+    });
+    let round =
+        |post: usize| PI - CAGE_SWEEP / 2.0 + CAGE_SWEEP * post as f32 / (CAGE_POSTS - 1) as f32;
+    let at = |angle: f32| Quat::from_rotation_y(angle) * Vec3::NEG_Z * CAGE_RADIUS;
+    let panel = meshes.add(Plane3d::new(
+        Vec3::Z,
+        Vec2::new(
+            CAGE_RADIUS * (CAGE_SWEEP / (CAGE_POSTS - 1) as f32 / 2.0).sin(),
+            CAGE_HEIGHT / 2.0,
+        ),
+    ));
+    for index in 0..CAGE_POSTS {
+        let angle = round(index);
+        commands.spawn((
+            Mesh3d(post.clone()),
+            MeshMaterial3d(steel.clone()),
+            Transform::from_translation(at(angle) + Vec3::Y * CAGE_HEIGHT / 2.0),
+        ));
+        if index + 1 < CAGE_POSTS {
+            let middle = (at(angle) + at(round(index + 1))) / 2.0;
+            commands.spawn((
+                Mesh3d(panel.clone()),
+                MeshMaterial3d(netting.clone()),
+                Transform::from_translation(middle + Vec3::Y * CAGE_HEIGHT / 2.0)
+                    .looking_at(Vec3::Y * CAGE_HEIGHT / 2.0, Vec3::Y),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(4.0, 4.0, 8.0).looking_at(Vec3::new(0.0, 0.0, -20.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(10.0, 20.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
@@ -0,0 +1,275 @@
+//! The hammer: swung round the thrower on its wire as fast as the turns build it up, then let go
+//! off the tangent to fly out over the field until it lands
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use spjorts_core::{settings::GameSettings, spectator::is_playing, turns::TurnManager};
+
+use crate::{field::wrap_angle, phase::HammerPhase};
+
+/// Radius of the hammer's head
+pub const HEAD_RADIUS: f32 = 0.06;
+/// How far the head swings out from the middle of the circle, arms and wire together
+pub const ORBIT_RADIUS: f32 = 1.9;
+/// Height the head swings round at and is let go from
+pub const RELEASE_HEIGHT: f32 = 1.5;
+/// Angle the hammer leaves the hand at, in radians above level
+pub const LAUNCH_ANGLE: f32 = 0.75;
+/// Gravity pulling the hammer down, in meters per second squared
+pub const GRAVITY: f32 = 9.81;
+/// Fastest the hammer can be swung round, in radians per second
+pub const MAX_SPIN: f32 = 15.0;
+/// Slowest the hammer can be swung round and still be let go, in radians per second
+pub const MIN_RELEASE_SPIN: f32 = 3.0;
+/// How many radians per second of hammer speed each radian per second of controller yaw is
+/// worth
+const SPIN_GAIN: f32 = 2.5;
+/// Fastest the turns can speed the hammer up, in radians per second every second
+const SPIN_UP: f32 = 4.0;
+/// Fastest the hammer slows down once the turns ease off, in radians per second every second
+const SPIN_DRAG: f32 = 3.0;
+/// How much of each new yaw rate reading is blended into the smoothed rate
+const RATE_SMOOTHING: f32 = 0.3;
+/// How quickly the measured yaw rate fades once the controller stops turning, per second
+const RATE_DECAY: f32 = 4.0;
+/// Height of the thrower
+const THROWER_HEIGHT: f32 = 1.8;
+/// Height of the thrower's hands, where the wire starts
+const HANDS_HEIGHT: f32 = 1.2;
+/// How far out in front of the thrower their hands are
+const HANDS_REACH: f32 = 0.45;
+/// Singlet colors of each thrower in turn
+const SINGLET_COLORS: [Color; 4] = [
+    Color::srgb(0.85, 0.15, 0.15),
+    Color::srgb(0.15, 0.35, 0.85),
+    Color::srgb(0.95, 0.75, 0.1),
+    Color::srgb(0.15, 0.7, 0.3),
+];
+
+/// The hammer swinging round the thrower, sped up by the controller turning
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Spin {
+    /// Where round the circle the head is, in radians from straight down the field
+    pub heading: f32,
+    /// How fast the head is going round, in radians per second
+    pub speed: f32,
+    /// Which way the hammer turns: `1.0` counter-clockwise seen from above, `-1.0` clockwise for
+    /// left-handed throwers
+    pub direction: f32,
+    /// How fast the controller has been turning about its yaw, smoothed, in radians per second
+    yaw_rate: f32,
+    /// The last yaw read off the controller and when, in seconds
+    last_yaw: Option<(f32, f32)>,
+}
+
+impl Default for Spin {
+    fn default() -> Self {
+        Self {
+            heading: 0.0,
+            speed: 0.0,
+            direction: 1.0,
+            yaw_rate: 0.0,
+            last_yaw: None,
+        }
+    }
+}
+
+impl Spin {
+    /// A hammer hanging still, ready for the next throw, turning the given way
+    pub fn new(direction: f32) -> Self {
+        Self {
+            direction,
+            ..default()
+        }
+    }
+
+    /// Measures how fast the controller is turning from a yaw read at `at` seconds. Circles
+    /// drawn either way count the same
+    pub fn record(&mut self, yaw: f32, at: f32) {
+        if let Some((last_yaw, last_at)) = self.last_yaw {
+            let secs = at - last_at;
+            if secs > f32::EPSILON {
+                let rate = wrap_angle(yaw - last_yaw).abs() / secs;
+                self.yaw_rate += (rate - self.yaw_rate) * RATE_SMOOTHING;
+            }
+        }
+        self.last_yaw = Some((yaw, at));
+    }
+
+    /// How fast the hammer is heading for at the rate the controller is turning
+    pub fn target_speed(&self) -> f32 {
+        (self.yaw_rate * SPIN_GAIN).min(MAX_SPIN)
+    }
+
+    /// Swings the hammer round for `secs` seconds, speeding it up or letting it slow toward the
+    /// speed the controller is turning for
+    pub fn turn(&mut self, secs: f32) {
+        let target = self.target_speed();
+        self.speed = if target > self.speed {
+            (self.speed + SPIN_UP * secs).min(target)
+        } else {
+            (self.speed - SPIN_DRAG * secs).max(target)
+        };
+        self.yaw_rate *= (-RATE_DECAY * secs).exp();
+        self.heading = wrap_angle(self.heading + self.direction * self.speed * secs);
+    }
+
+    /// Where the head is on its way round
+    pub fn head(&self) -> Vec3 {
+        Quat::from_rotation_y(self.heading) * Vec3::NEG_Z * ORBIT_RADIUS + Vec3::Y * RELEASE_HEIGHT
+    }
+
+    /// Velocity the hammer leaves with if it's let go now, off the tangent of its circle
+    pub fn release_velocity(&self) -> Vec3 {
+        let along = Quat::from_rotation_y(self.heading + self.direction * FRAC_PI_2) * Vec3::NEG_Z;
+        (along * LAUNCH_ANGLE.cos() + Vec3::Y * LAUNCH_ANGLE.sin()) * self.speed * ORBIT_RADIUS
+    }
+}
+
+/// The thrower in the circle, turning to face the hammer
+#[derive(Component, Debug)]
+pub struct Thrower;
+
+/// The hammer's head
+#[derive(Component, Debug, Default)]
+pub struct Hammer {
+    /// How fast it's flying, in meters per second
+    pub velocity: Vec3,
+    /// Whether it's been let go and is in the air
+    pub flying: bool,
+}
+
+/// The hammer came down on the field
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Landed {
+    /// Where it came down
+    pub at: Vec3,
+}
+
+/// Plugin that spawns the thrower and the hammer, swings it round and flies it once it's let go
+pub struct HammerPlugin;
+
+impl Plugin for HammerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Spin>()
+            .add_event::<Landed>()
+            .add_systems(Startup, spawn_thrower)
+            .add_systems(OnEnter(HammerPhase::Spinning), dress_thrower)
+            .add_systems(
+                Update,
+                (
+                    swing.run_if(in_state(HammerPhase::Spinning)),
+                    fly.run_if(in_state(HammerPhase::Flying)),
+                )
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, draw_wire);
+    }
+}
+
+/// Spawns the thrower in the middle of the circle and the hammer hanging off their hands
+fn spawn_thrower(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Capsule3d::new(0.25, THROWER_HEIGHT - 0.5))),
+        MeshMaterial3d(materials.add(SINGLET_COLORS[0])),
+        Transform::from_xyz(0.0, THROWER_HEIGHT / 2.0, 0.0),
+        Thrower,
+        Name::new("Thrower"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(HEAD_RADIUS))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.6, 0.6, 0.65),
+            metallic: 0.8,
+            perceptual_roughness: 0.3,
+            ..default()
+        })),
+        Transform::from_translation(Spin::default().head()),
+        Hammer::default(),
+        Name::new("Hammer"),
+    ));
+}
+
+/// Puts the thrower up in their singlet and hands them the hammer, hanging still. Left-handed
+/// players turn clockwise
+fn dress_thrower(
+    mut spin: ResMut<'_, Spin>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+    mut hammers: Query<'_, '_, (&mut Transform, &mut Hammer)>,
+    throwers: Query<'_, '_, &MeshMaterial3d<StandardMaterial>, With<Thrower>>,
+    settings: Res<'_, GameSettings>,
+    turns: Res<'_, TurnManager>,
+) {
+    *spin = Spin::new(settings.handedness());
+    for (mut transform, mut hammer) in &mut hammers {
+        *hammer = Hammer::default();
+        transform.translation = spin.head();
+    }
+    for singlet in &throwers {
+        if let Some(singlet) = materials.get_mut(&singlet.0) {
+            singlet.base_color = SINGLET_COLORS[turns.current() % SINGLET_COLORS.len()];
+        }
+    }
+}
+
+/// Swings the hammer round, keeping the thrower facing it
+pub fn swing(
+    mut spin: ResMut<'_, Spin>,
+    mut hammers: Query<'_, '_, &mut Transform, (With<Hammer>, Without<Thrower>)>,
+    mut throwers: Query<'_, '_, &mut Transform, With<Thrower>>,
+    time: Res<'_, Time>,
+) {
+    spin.turn(time.delta_secs());
+    for mut transform in &mut hammers {
+        transform.translation = spin.head();
+    }
+    for mut transform in &mut throwers {
+        transform.rotation = Quat::from_rotation_y(spin.heading);
+    }
+}
+
+/// Flies the hammer under gravity and reports where it comes down
+fn fly(
+    mut hammers: Query<'_, '_, (&mut Transform, &mut Hammer)>,
+    mut landings: EventWriter<'_, Landed>,
+    time: Res<'_, Time>,
+) {
+    let secs = time.delta_secs();
+    for (mut transform, mut hammer) in &mut hammers {
+        if !hammer.flying {
+            continue;
+        }
+        hammer.velocity.y -= GRAVITY * secs;
+        transform.translation += hammer.velocity * secs;
+
+        if transform.translation.y <= HEAD_RADIUS && hammer.velocity.y < 0.0 {
+            transform.translation.y = HEAD_RADIUS;
+            hammer.flying = false;
+            landings.send(Landed {
+                at: transform.translation,
+            });
+        }
+    }
+}
+
+/// Draws the wire from the thrower's hands to the head while it's still in hand
+fn draw_wire(
+    mut gizmos: Gizmos<'_, '_>,
+    phase: Res<'_, State<HammerPhase>>,
+    spin: Res<'_, Spin>,
+    hammers: Query<'_, '_, &Transform, With<Hammer>>,
+) {
+    if *phase.get() != HammerPhase::Spinning {
+        return;
+    }
+    let hands =
+        Quat::from_rotation_y(spin.heading) * Vec3::NEG_Z * HANDS_REACH + Vec3::Y * HANDS_HEIGHT;
+    for transform in &hammers {
+        gizmos.line(hands, transform.translation, Color::srgb(0.8, 0.8, 0.8));
+    }
+}
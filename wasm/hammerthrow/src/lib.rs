@@ -0,0 +1,186 @@
+//! Bevy hammer throw
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use field::{bearing, FieldPlugin, SECTOR_HALF_ANGLE};
+use hammer::{swing, Hammer, HammerPlugin, Spin, MAX_SPIN, MIN_RELEASE_SPIN};
+use phase::{HammerPhase, HammerPhasePlugin};
+use scoring::{NewGame, ScoringPlugin};
+use spjorts_core::{
+    communication::{GameEvent, JsMessage},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader, FeedbackSender,
+};
+
+pub mod field;
+pub mod hammer;
+pub mod phase;
+pub mod scoring;
+
+/// Where the camera watches the thrower from while they turn
+const CIRCLE_VIEW: Vec3 = Vec3::new(4.0, 4.0, 8.0);
+/// Where the camera sits relative to the hammer while it flies
+const FOLLOW_OFFSET: Vec3 = Vec3::new(8.0, 5.0, 10.0);
+/// How quickly the camera catches up with where it's headed, per second
+const CAMERA_FOLLOW: f32 = 4.0;
+/// How far along the aim guide reaches from the head, in meters
+const GUIDE_LENGTH: f32 = 20.0;
+/// Rumble strength for a throw at the fastest spin, out of 255
+const MAX_RUMBLE: f32 = 200.0;
+/// How long the controller rumbles when the hammer is let go, in milliseconds
+const RUMBLE_MILLIS: u16 = 80;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(HammerPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(FieldPlugin)
+    .add_plugins(HammerPlugin)
+    .add_plugins(ScoringPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .add_event::<Release>()
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            release_hammer.run_if(in_state(HammerPhase::Spinning)),
+        )
+            .chain()
+            .before(swing)
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (follow_hammer, draw_aim_guide));
+});
+
+/// The thrower up let go of the hammer
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Release;
+
+/// Everything input handling changes besides the hammer's spin
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Releases of the hammer
+    releases: EventWriter<'w, Release>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: turning the controller round in circles speeds the hammer up, faster
+/// the faster it turns, and A lets it go. A starts a new competition once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut spin: ResMut<'_, Spin>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<HammerPhase>>,
+    time: Res<'_, Time>,
+) {
+    let spinning = *phase.get() == HammerPhase::Spinning;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == HammerPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if spinning => {
+                effects.releases.send(Release);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if spinning => {
+                let orientation = settings.apply_rotation(orientation);
+                spin.record(orientation.yaw, time.elapsed_secs());
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets the hammer go off the tangent of its circle, as long as it's been turned up enough to
+/// fly
+fn release_hammer(
+    mut releases: EventReader<'_, '_, Release>,
+    mut hammers: Query<'_, '_, &mut Hammer>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<HammerPhase>>,
+    spin: Res<'_, Spin>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    if releases.read().last().is_none() {
+        return;
+    }
+    if spin.speed < MIN_RELEASE_SPIN {
+        banner.show("Spin the hammer up first!");
+        return;
+    }
+
+    for mut hammer in &mut hammers {
+        hammer.velocity = spin.release_velocity();
+        hammer.flying = true;
+    }
+    feedback.send(GameEvent::Rumble {
+        intensity: (spin.speed / MAX_SPIN * MAX_RUMBLE) as u8,
+        millis: RUMBLE_MILLIS,
+    });
+    next_phase.set(HammerPhase::Flying);
+}
+
+/// Watches the circle while the thrower turns, then follows the hammer out over the field
+fn follow_hammer(
+    mut camera: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Hammer>)>,
+    hammers: Query<'_, '_, &Transform, With<Hammer>>,
+    phase: Res<'_, State<HammerPhase>>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let Ok(hammer) = hammers.get_single() else {
+        return;
+    };
+    let (goal, target) = match phase.get() {
+        HammerPhase::Flying | HammerPhase::Measured => (
+            hammer.translation.with_y(0.0) + FOLLOW_OFFSET,
+            hammer.translation,
+        ),
+        HammerPhase::Spinning | HammerPhase::GameOver => (CIRCLE_VIEW, Vec3::new(0.0, 0.0, -20.0)),
+    };
+
+    let blend = (CAMERA_FOLLOW * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(target, Vec3::Y);
+}
+
+/// Draws the line the hammer would fly out along if it were let go now, green when it heads
+/// into the sector, when the aim guide is on
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    spin: Res<'_, Spin>,
+    phase: Res<'_, State<HammerPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() != HammerPhase::Spinning {
+        return;
+    }
+    let start = spin.head();
+    let along = spin.release_velocity().with_y(0.0).normalize_or_zero() * GUIDE_LENGTH;
+    let color = if bearing(along).abs() <= SECTOR_HALF_ANGLE {
+        Color::srgb(0.2, 0.9, 0.2)
+    } else {
+        Color::srgb(0.9, 0.2, 0.2)
+    };
+    gizmos.line(start, start + along, color);
+}
@@ -0,0 +1,28 @@
+//! Phases a hammer throw competition moves through, from winding up in the circle to the final
+//! marks
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the competition is in the flow of play
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HammerPhase {
+    /// The thrower up is turning in the circle, building up speed on the hammer
+    #[default]
+    Spinning,
+    /// The hammer has been let go and is flying out over the field
+    Flying,
+    /// The hammer is down and its mark is up
+    Measured,
+    /// Every attempt has been thrown and the final marks are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the competition is in
+pub struct HammerPhasePlugin;
+
+impl Plugin for HammerPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<HammerPhase>();
+    }
+}
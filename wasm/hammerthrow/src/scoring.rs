@@ -0,0 +1,358 @@
+//! Measuring each throw or calling it a foul, handing the hammer to the next thrower, and showing
+//! every thrower's best mark on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    field::{bearing, in_sector, CIRCLE_RADIUS},
+    hammer::{Landed, Spin},
+    phase::HammerPhase,
+};
+
+/// Throws each player gets in a competition
+pub const ATTEMPTS: usize = 3;
+/// How long a mark stays up before the next throw, in seconds
+const MEASURED_SECS: f32 = 2.5;
+
+/// Asks for the competition to be started over from the first throw
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// The call on a throw, from where the hammer came down
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Call {
+    /// It landed in the sector this many meters from the edge of the circle
+    Fair(f32),
+    /// It was let go before the hammer came round to the sector, landing outside it on the near
+    /// side
+    Early,
+    /// It was let go after the hammer came round past the sector, landing outside it on the far
+    /// side
+    Late,
+}
+
+impl Call {
+    /// Calls a throw from where the hammer came down, for a hammer turning `direction`
+    pub fn of(at: Vec3, direction: f32) -> Self {
+        if in_sector(at) {
+            // A hammer dropping inside the circle is too feeble to reach the sector lines, and is
+            // called on which side of them it was headed
+            let mark = at.with_y(0.0).length() - CIRCLE_RADIUS;
+            if mark > 0.0 {
+                return Self::Fair(mark);
+            }
+        }
+        if bearing(at) * direction < 0.0 {
+            Self::Early
+        } else {
+            Self::Late
+        }
+    }
+
+    /// The mark the throw counts for, or `None` for a foul
+    pub fn mark(&self) -> Option<f32> {
+        match self {
+            Self::Fair(mark) => Some(*mark),
+            Self::Early | Self::Late => None,
+        }
+    }
+
+    /// What to show players
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Fair(mark) => format_mark(Some(*mark)),
+            Self::Early => "Foul! Let go too early".to_string(),
+            Self::Late => "Foul! Let go too late".to_string(),
+        }
+    }
+}
+
+/// A mark to show players
+pub fn format_mark(mark: Option<f32>) -> String {
+    mark.map_or("no mark".to_string(), |mark| format!("{mark:.2} m"))
+}
+
+/// Every mark each player has thrown
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Scoreboard {
+    /// Marks from each throw, per player in turn order, with `None` for fouls
+    marks: Vec<Vec<Option<f32>>>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a competition for a number of players with no throws taken
+    pub fn new(players: usize) -> Self {
+        Self {
+            marks: vec![Vec::new(); players.max(1)],
+        }
+    }
+
+    /// How many players are in the competition
+    pub fn players(&self) -> usize {
+        self.marks.len()
+    }
+
+    /// How many throws a player has taken
+    pub fn throws_taken(&self, player: usize) -> usize {
+        self.marks.get(player).map_or(0, Vec::len)
+    }
+
+    /// Throws a player has left to take
+    pub fn throws_left(&self, player: usize) -> usize {
+        ATTEMPTS.saturating_sub(self.throws_taken(player))
+    }
+
+    /// Records the mark a player threw, `None` for a foul
+    pub fn record(&mut self, player: usize, mark: Option<f32>) {
+        if let Some(marks) = self.marks.get_mut(player) {
+            marks.push(mark);
+        }
+    }
+
+    /// A player's longest fair throw, if they've had one
+    pub fn best(&self, player: usize) -> Option<f32> {
+        self.marks
+            .get(player)?
+            .iter()
+            .flatten()
+            .copied()
+            .reduce(f32::max)
+    }
+
+    /// A player's best mark in whole centimeters, the way results are submitted
+    pub fn score(&self, player: usize) -> u32 {
+        self.best(player)
+            .map_or(0, |mark| (mark * 100.0).round() as u32)
+    }
+
+    /// The player with the longest throw, the first of them on a tie
+    pub fn leader(&self) -> Option<usize> {
+        (0..self.players())
+            .rev()
+            .max_by_key(|player| self.score(*player))
+    }
+}
+
+/// Counts down before the hammer goes to the next thrower
+#[derive(Resource, Debug)]
+struct MeasuredPause(Timer);
+
+impl Default for MeasuredPause {
+    fn default() -> Self {
+        Self(Timer::from_seconds(MEASURED_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct HammerSnapshot<'a> {
+    /// Player up to throw
+    player: usize,
+    /// How fast the hammer is going round, in radians per second
+    spin: f32,
+    /// Marks from every throw taken so far
+    scoreboard: &'a Scoreboard,
+    /// Where the competition is at
+    phase: HammerPhase,
+}
+
+/// Plugin that measures throws and shows the marks on the scorecard HUD
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<MeasuredPause>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    call_throw,
+                    next_throw.run_if(in_state(HammerPhase::Measured)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(HammerPhase::Measured), reset_measured_pause)
+            .add_systems(
+                OnEnter(HammerPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(HammerPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh competition whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Calls the throw once the hammer comes down, recording its mark for the thrower up
+fn call_throw(
+    mut landings: EventReader<'_, '_, Landed>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<HammerPhase>>,
+    spin: Res<'_, Spin>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(landed) = landings.read().last() else {
+        return;
+    };
+
+    let call = Call::of(landed.at, spin.direction);
+    banner.show(call.describe());
+    scoreboard.record(turns.current(), call.mark());
+    next_phase.set(HammerPhase::Measured);
+}
+
+/// Starts counting down before the next throw
+fn reset_measured_pause(mut pause: ResMut<'_, MeasuredPause>) {
+    pause.0.reset();
+}
+
+/// Hands the hammer to the next player with throws left, or finishes the competition once
+/// everyone has thrown all of theirs
+fn next_throw(
+    mut pause: ResMut<'_, MeasuredPause>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<HammerPhase>>,
+    scoreboard: Res<'_, Scoreboard>,
+    time: Res<'_, Time>,
+) {
+    if !pause.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if turns
+        .advance_until(|player| scoreboard.throws_left(player) == 0)
+        .is_some()
+    {
+        next_phase.set(HammerPhase::Spinning);
+    } else {
+        next_phase.set(HammerPhase::GameOver);
+    }
+}
+
+/// Starts the competition over from the first player's first throw
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut next_phase: ResMut<'_, NextState<HammerPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    next_phase.set(HammerPhase::Spinning);
+}
+
+/// Fills in the scorecard HUD with every player's best mark and throws left, and how fast the
+/// hammer is going round
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    spin: Res<'_, Spin>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            format!(
+                "Player {}: {} ({} throws left)",
+                player + 1,
+                format_mark(scoreboard.best(player)),
+                scoreboard.throws_left(player)
+            )
+        })
+        .collect();
+    let player = turns.current();
+    let attempt = (scoreboard.throws_taken(player) + 1).min(ATTEMPTS);
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Hammer Throw, attempt {attempt} of {ATTEMPTS}"),
+        rows,
+        footer: format!(
+            "Player {} to throw: spin in circles, A to let go (hammer at {:.1} turns a second)",
+            player + 1,
+            spin.speed / std::f32::consts::TAU
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's best mark once the competition is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scoreboard: Res<'_, Scoreboard>) {
+    let mut lines = vec!["Final Marks".to_string()];
+    if let Some(leader) = scoreboard
+        .leader()
+        .filter(|leader| scoreboard.players() > 1 && scoreboard.best(*leader).is_some())
+    {
+        lines.push(format!("Player {} wins!", leader + 1));
+    }
+    for player in 0..scoreboard.players() {
+        lines.push(format!(
+            "Player {}: {}",
+            player + 1,
+            format_mark(scoreboard.best(player))
+        ));
+    }
+    lines.push("Press A to throw again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final marks when a new competition starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's best mark in centimeters back to the page once the competition is over,
+/// so it can submit them to the server
+fn submit_result(scoreboard: Res<'_, Scoreboard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scoreboard.players())
+        .map(|player| scoreboard.score(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    spin: Res<'_, Spin>,
+    phase: Res<'_, State<HammerPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&HammerSnapshot {
+        player: turns.current(),
+        spin: spin.speed,
+        scoreboard: &scoreboard,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,334 @@
+//! Innings: every pitcher tosses their shoes in turn, then the inning is called from where the
+//! shoes came to rest and the points go on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    phase::HorseshoesPhase,
+    shoe::{call, inning_points, Call, Shoe},
+};
+
+/// Shoes each pitcher tosses in an inning, taking turns shoe by shoe
+pub const SHOES_PER_INNING: usize = 2;
+/// Points that win the game, once an inning ends with someone alone on or past them
+pub const WINNING_POINTS: u32 = 21;
+/// Innings played before the highest score wins even if nobody has reached
+/// [`WINNING_POINTS`]
+pub const MAX_INNINGS: usize = 12;
+/// How long the shoes stay in the pit once an inning is called, in seconds
+const SCORING_SECS: f32 = 2.5;
+
+/// Asks for the game to be started over from the first pitcher's first shoe
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Every pitcher's points, inning by inning
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Innings {
+    /// Each pitcher's points for every inning called so far, in turn order
+    points: Vec<Vec<u32>>,
+}
+
+impl Default for Innings {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Innings {
+    /// Starts a game for a number of pitchers with nothing scored
+    pub fn new(players: usize) -> Self {
+        Self {
+            points: vec![Vec::with_capacity(MAX_INNINGS); players.max(1)],
+        }
+    }
+
+    /// How many pitchers are in the game
+    pub fn players(&self) -> usize {
+        self.points.len()
+    }
+
+    /// How many innings have been called
+    pub fn played(&self) -> usize {
+        self.points.first().map_or(0, Vec::len)
+    }
+
+    /// Records an inning's points, one for each pitcher
+    pub fn record(&mut self, points: &[u32]) {
+        for (player, innings) in self.points.iter_mut().enumerate() {
+            innings.push(points.get(player).copied().unwrap_or_default());
+        }
+    }
+
+    /// A pitcher's points for the last inning called
+    pub fn last(&self, player: usize) -> u32 {
+        self.points
+            .get(player)
+            .and_then(|innings| innings.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// A pitcher's total so far
+    pub fn total(&self, player: usize) -> u32 {
+        self.points
+            .get(player)
+            .map_or(0, |innings| innings.iter().sum())
+    }
+
+    /// The pitchers on the highest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+
+    /// Whether the game is over: one pitcher alone on or past [`WINNING_POINTS`], or every
+    /// inning played
+    pub fn is_over(&self) -> bool {
+        let leaders = self.leaders();
+        let won = leaders.len() == 1 && self.total(leaders[0]) >= WINNING_POINTS;
+        won || self.played() >= MAX_INNINGS
+    }
+}
+
+/// Counts down before the shoes are picked up for the next inning
+#[derive(Resource, Debug)]
+struct Scoring(Timer);
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SCORING_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct HorseshoesSnapshot<'a> {
+    /// Pitcher at the foul line
+    player: usize,
+    /// The inning being pitched, starting from zero
+    inning: usize,
+    /// Every pitcher's points so far
+    innings: &'a Innings,
+    /// Where the game is at
+    phase: HorseshoesPhase,
+}
+
+/// Plugin that calls innings and shows them on the scorecard HUD
+pub struct InningsPlugin;
+
+impl Plugin for InningsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Innings>()
+            .init_resource::<Scoring>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    pick_up_shoes.run_if(in_state(HorseshoesPhase::Scoring)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(HorseshoesPhase::Scoring),
+                (reset_scoring, call_inning.run_if(is_playing)),
+            )
+            .add_systems(
+                OnEnter(HorseshoesPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(HorseshoesPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of pitchers changes
+fn fit_game(turns: Res<'_, TurnManager>, mut innings: ResMut<'_, Innings>) {
+    if innings.players() != turns.players() {
+        *innings = Innings::new(turns.players());
+    }
+}
+
+/// Gives pitchers a moment to look at the pit once the inning is called
+fn reset_scoring(mut scoring: ResMut<'_, Scoring>) {
+    scoring.0.reset();
+}
+
+/// What a call is shown as on the banner
+fn call_name(call: Call) -> &'static str {
+    match call {
+        Call::Ringer => "ringer",
+        Call::Leaner => "leaner",
+        Call::Away(_) => "shoe",
+    }
+}
+
+/// Calls every shoe where it came to rest and scores the inning
+fn call_inning(
+    shoes: Query<'_, '_, (&Transform, &Shoe)>,
+    mut innings: ResMut<'_, Innings>,
+    mut banner: ResMut<'_, Banner>,
+) {
+    let calls: Vec<(usize, Call)> = shoes
+        .iter()
+        .map(|(transform, shoe)| (shoe.player, call(transform)))
+        .collect();
+    let points = inning_points(innings.players(), &calls);
+    innings.record(&points);
+
+    let mut lines: Vec<String> = calls
+        .iter()
+        .filter(|(_, call)| !matches!(call, Call::Away(_)))
+        .map(|(player, call)| format!("Player {} {}!", player + 1, call_name(*call)))
+        .collect();
+    if lines.is_empty() {
+        lines.push(if points.iter().all(|points| *points == 0) {
+            "No count".to_string()
+        } else {
+            "Closest shoe scores".to_string()
+        });
+    }
+    banner.show(lines.join("\n"));
+}
+
+/// Picks the shoes up once the inning has been looked at, starting the next inning or ending
+/// the game
+fn pick_up_shoes(
+    mut commands: Commands<'_, '_>,
+    mut scoring: ResMut<'_, Scoring>,
+    mut next_phase: ResMut<'_, NextState<HorseshoesPhase>>,
+    shoes: Query<'_, '_, Entity, With<Shoe>>,
+    innings: Res<'_, Innings>,
+    time: Res<'_, Time>,
+) {
+    if !scoring.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for shoe in &shoes {
+        commands.entity(shoe).despawn_recursive();
+    }
+    if innings.is_over() {
+        next_phase.set(HorseshoesPhase::GameOver);
+    } else {
+        next_phase.set(HorseshoesPhase::Aiming);
+    }
+}
+
+/// Starts the game over from the first pitcher's first shoe
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut innings: ResMut<'_, Innings>,
+    mut next_phase: ResMut<'_, NextState<HorseshoesPhase>>,
+    shoes: Query<'_, '_, Entity, With<Shoe>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for shoe in &shoes {
+        commands.entity(shoe).despawn_recursive();
+    }
+    turns.restart();
+    *innings = Innings::new(turns.players());
+    next_phase.set(HorseshoesPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every pitcher's total and last inning, and the shoe being
+/// pitched
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    innings: Res<'_, Innings>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..innings.players())
+        .map(|player| {
+            let last = if innings.played() > 0 {
+                format!(" (+{} last inning)", innings.last(player))
+            } else {
+                String::new()
+            };
+            format!("Player {}: {}{last}", player + 1, innings.total(player))
+        })
+        .collect();
+    let inning = innings.played().min(MAX_INNINGS - 1) + 1;
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Horseshoes to {WINNING_POINTS}, inning {inning} of {MAX_INNINGS}"),
+        rows,
+        footer: format!(
+            "Player {} pitching shoe {} of {SHOES_PER_INNING}",
+            turns.current() + 1,
+            turns.round() % SHOES_PER_INNING + 1
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every pitcher's total once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, innings: Res<'_, Innings>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    let leaders = innings.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if innings.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..innings.players() {
+        lines.push(format!("Player {}: {}", player + 1, innings.total(player)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every pitcher's total back to the page once the game is over, so it can submit them
+/// to the server
+fn submit_result(innings: Res<'_, Innings>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..innings.players())
+        .map(|player| innings.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    innings: Res<'_, Innings>,
+    phase: Res<'_, State<HorseshoesPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&HorseshoesSnapshot {
+        player: turns.current(),
+        inning: innings.played(),
+        innings: &innings,
+        phase: *phase.get(),
+    });
+}
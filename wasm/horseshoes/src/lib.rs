@@ -0,0 +1,334 @@
+//! Bevy horseshoes game
+
+use std::f32::consts::PI;
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{Ccd, ColliderMassProperties, Friction, Restitution, RigidBody, Velocity},
+};
+use innings::{InningsPlugin, NewGame, SHOES_PER_INNING};
+use phase::{HorseshoesPhase, HorseshoesPhasePlugin};
+use pit::{PitPlugin, PITCHING_DISTANCE, STAKE};
+use shoe::{bar_meshes, shoe_collider, Shoe, SHOE_HALF_THICKNESS};
+use spjorts_core::{
+    communication::JsMessage,
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod innings;
+pub mod phase;
+pub mod pit;
+pub mod shoe;
+
+/// How far a radian of controller yaw turns the toss, in radians
+const AIM_SCALE: f32 = 0.05;
+/// Furthest a toss can be turned from straight at the stake, in radians
+const MAX_AIM: f32 = 0.08;
+/// How far the controller has to come up from the bottom of a swing for it to count as an
+/// underhand toss, in radians
+const UNDERHAND_RISE: f32 = 0.8;
+/// Swing speed that carries a shoe to just past the stake, in radians per second
+pub const IDEAL_SWING_SPEED: f32 = 8.0;
+/// How much further every radian per second of swing carries the shoe, in meters
+const REACH_PER_SWING_SPEED: f32 = 0.3;
+/// How far past the stake a shoe tossed with the ideal swing comes down, so it drops round the
+/// stake rather than onto it
+const RINGER_CARRY: f32 = 0.1;
+/// Shortest and longest a toss can carry, in meters
+const CARRY_RANGE: (f32, f32) = (2.0, PITCHING_DISTANCE + 4.0);
+/// How far above level the shoe leaves the hand, in radians
+const LAUNCH_ANGLE: f32 = 0.55;
+/// Where shoes leave the hand, at the end of an underhand swing on the foul line
+const RELEASE: Vec3 = Vec3::new(0.0, 0.5, STAKE.z + PITCHING_DISTANCE);
+/// Mass of a shoe, in kilograms
+const SHOE_MASS: f32 = 1.1;
+/// Acceleration shoes fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// Speed below which a shoe counts as lying still, in meters per second
+const REST_SPEED: f32 = 0.05;
+/// How long every shoe has to lie still before the next pitcher steps up, in seconds
+const REST_SECS: f32 = 0.5;
+/// Longest a toss is watched for before the next pitcher steps up anyway, in seconds
+const MAX_TOSS_SECS: f32 = 6.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(HorseshoesPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(PitPlugin)
+    .add_plugins(InningsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Pitch>()
+    .add_event::<Toss>()
+    .add_systems(Startup, setup_shoe_model)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            toss_shoe.run_if(in_state(HorseshoesPhase::Aiming)),
+            settle_shoes.run_if(in_state(HorseshoesPhase::Flying)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        draw_aim_guide.run_if(in_state(HorseshoesPhase::Aiming)),
+    );
+});
+
+/// How the pitcher at the foul line is lining up, and how long the last toss has been watched
+#[derive(Resource, Debug, Default)]
+pub struct Pitch {
+    /// How far the toss is turned from straight at the stake, in radians. Positive turns left
+    pub aim: f32,
+    /// Lowest the controller has been pitched since it was last at rest, in radians
+    bottom_pitch: f32,
+    /// Watches the controller for underhand swings
+    detector: GestureDetector,
+    /// Seconds since the last shoe was tossed
+    watched_for: f32,
+    /// How long every shoe has been lying still, in seconds
+    still_for: f32,
+}
+
+/// A shoe tossed from the foul line
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Toss {
+    /// How far the toss is turned from straight at the stake, in radians
+    pub aim: f32,
+    /// How fast the underhand swing turned the controller, in radians per second
+    pub swing: f32,
+}
+
+impl Toss {
+    /// How far along the ground the shoe carries before it comes down, further the harder the
+    /// swing
+    fn carry(&self) -> f32 {
+        let past_ideal = (self.swing - IDEAL_SWING_SPEED) * REACH_PER_SWING_SPEED;
+        (PITCHING_DISTANCE + RINGER_CARRY + past_ideal).clamp(CARRY_RANGE.0, CARRY_RANGE.1)
+    }
+
+    /// Velocity the shoe leaves the hand with to come down after its carry
+    fn velocity(&self) -> Vec3 {
+        let carry = self.carry();
+        let drop = RELEASE.y - SHOE_HALF_THICKNESS;
+        let (sin, cos) = LAUNCH_ANGLE.sin_cos();
+        let speed = carry / cos * (GRAVITY / (2.0 * (drop + carry * sin / cos))).sqrt();
+        Quat::from_rotation_y(self.aim) * Quat::from_rotation_x(LAUNCH_ANGLE) * Vec3::NEG_Z * speed
+    }
+
+    /// Where the shoe comes down on the clay, if nothing is in its way
+    pub fn landing(&self) -> Vec3 {
+        let along = Quat::from_rotation_y(self.aim) * Vec3::NEG_Z * self.carry();
+        (RELEASE + along).with_y(SHOE_HALF_THICKNESS)
+    }
+}
+
+/// Meshes and materials every shoe is built from
+#[derive(Resource)]
+struct ShoeModel {
+    /// Each bar's mesh and where it sits in the shoe
+    bars: Vec<(Handle<Mesh>, Vec3)>,
+    /// Each pitcher's shoe color, in turn order, repeating past the last
+    colors: Vec<Handle<StandardMaterial>>,
+}
+
+impl ShoeModel {
+    /// Spawns a shoe's bars around the parent's origin in a pitcher's color
+    fn build(&self, shoe: &mut ChildBuilder<'_>, player: usize) {
+        let color = &self.colors[player % self.colors.len()];
+        for (mesh, at) in &self.bars {
+            shoe.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(color.clone()),
+                Transform::from_translation(*at),
+            ));
+        }
+    }
+}
+
+/// Builds the meshes and materials shoes are spawned with
+fn setup_shoe_model(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ShoeModel {
+        bars: bar_meshes()
+            .into_iter()
+            .map(|(bar, at)| (meshes.add(bar), at))
+            .collect(),
+        colors: [
+            Color::srgb(0.8, 0.15, 0.1),
+            Color::srgb(0.15, 0.3, 0.8),
+            Color::srgb(0.95, 0.75, 0.1),
+            Color::srgb(0.2, 0.6, 0.25),
+        ]
+        .into_iter()
+        .map(|color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                metallic: 0.6,
+                perceptual_roughness: 0.5,
+                ..default()
+            })
+        })
+        .collect(),
+    });
+}
+
+/// Everything input handling changes besides the pitch itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Shoes to toss
+    tosses: EventWriter<'w, Toss>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims while the controller is still, and an underhand swing
+/// tosses the shoe further the faster it's swung. A starts a new game once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut pitch: ResMut<'_, Pitch>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<HorseshoesPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == HorseshoesPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == HorseshoesPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                let detected = pitch.detector.update(orientation, time.elapsed_secs());
+                let underhand = orientation.pitch - pitch.bottom_pitch >= UNDERHAND_RISE;
+                if pitch.detector.speed() < pitch.detector.thresholds.rest_speed {
+                    pitch.aim = (orientation.yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                    pitch.bottom_pitch = orientation.pitch;
+                } else {
+                    pitch.bottom_pitch = pitch.bottom_pitch.min(orientation.pitch);
+                }
+
+                if let Some(detected) = detected.filter(|detected| {
+                    underhand && matches!(detected.gesture, Gesture::Swing | Gesture::Flick)
+                }) {
+                    effects.tosses.send(Toss {
+                        aim: pitch.aim,
+                        swing: detected.intensity,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets a shoe go from the foul line, lying flat with its open end toward the stake
+fn toss_shoe(
+    mut commands: Commands<'_, '_>,
+    mut tosses: EventReader<'_, '_, Toss>,
+    mut pitch: ResMut<'_, Pitch>,
+    mut next_phase: ResMut<'_, NextState<HorseshoesPhase>>,
+    model: Res<'_, ShoeModel>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(toss) = tosses.read().last().copied() else {
+        return;
+    };
+    let player = turns.current();
+
+    commands
+        .spawn((
+            Transform::from_translation(RELEASE)
+                .with_rotation(Quat::from_rotation_y(toss.aim + PI)),
+            Visibility::Visible,
+            RigidBody::Dynamic,
+            shoe_collider(),
+            ColliderMassProperties::Mass(SHOE_MASS),
+            Friction::coefficient(0.8),
+            Restitution::coefficient(0.1),
+            Ccd::enabled(),
+            Velocity::linear(toss.velocity()),
+            Shoe { player },
+        ))
+        .with_children(|shoe| model.build(shoe, player));
+    pitch.watched_for = 0.0;
+    pitch.still_for = 0.0;
+    next_phase.set(HorseshoesPhase::Flying);
+}
+
+/// Waits for every shoe in the pit to lie still, then hands the foul line to the next pitcher,
+/// or calls the inning once everyone has tossed their shoes
+fn settle_shoes(
+    shoes: Query<'_, '_, &Velocity, With<Shoe>>,
+    mut pitch: ResMut<'_, Pitch>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<HorseshoesPhase>>,
+    time: Res<'_, Time>,
+) {
+    pitch.watched_for += time.delta_secs();
+    pitch.still_for = if shoes
+        .iter()
+        .all(|velocity| velocity.linvel.length() < REST_SPEED)
+    {
+        pitch.still_for + time.delta_secs()
+    } else {
+        0.0
+    };
+    if pitch.still_for < REST_SECS && pitch.watched_for < MAX_TOSS_SECS {
+        return;
+    }
+
+    turns.advance();
+    if turns.current() == 0 && turns.round().is_multiple_of(SHOES_PER_INNING) {
+        next_phase.set(HorseshoesPhase::Scoring);
+    } else {
+        next_phase.set(HorseshoesPhase::Aiming);
+    }
+}
+
+/// Rings where a shoe tossed the way the pitch is aimed comes down with the ideal swing
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    pitch: Res<'_, Pitch>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let spot = Toss {
+        aim: pitch.aim,
+        swing: IDEAL_SWING_SPEED,
+    }
+    .landing();
+    gizmos.circle(
+        Isometry3d::new(spot + Vec3::Y * 0.02, Quat::from_rotation_x(-PI / 2.0)),
+        0.12,
+        Color::WHITE,
+    );
+}
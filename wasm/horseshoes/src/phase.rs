@@ -0,0 +1,27 @@
+//! Phases a horseshoes game moves through, from lining up a toss to the final scores
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of an inning
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HorseshoesPhase {
+    /// The pitcher at the foul line is lining up their next toss
+    #[default]
+    Aiming,
+    /// A tossed shoe is in the air or still sliding in the pit
+    Flying,
+    /// Every shoe of the inning is down and the inning is being called
+    Scoring,
+    /// Someone has won and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct HorseshoesPhasePlugin;
+
+impl Plugin for HorseshoesPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<HorseshoesPhase>();
+    }
+}
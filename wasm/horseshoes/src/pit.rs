@@ -0,0 +1,120 @@
+//! The pitch: a clay pit around a steel stake, the foul line the shoes are pitched from and the
+//! grass around them
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody};
+
+/// Foot of the stake, where it comes out of the clay
+pub const STAKE: Vec3 = Vec3::ZERO;
+/// Radius of the stake
+pub const STAKE_RADIUS: f32 = 0.0125;
+/// How far the stake stands out of the clay
+pub const STAKE_HEIGHT: f32 = 0.38;
+/// How far from the stake the foul line is
+pub const PITCHING_DISTANCE: f32 = 8.0;
+/// Half the width of the clay pit
+const PIT_HALF_WIDTH: f32 = 0.45;
+/// Half the length of the clay pit, along the pitch
+const PIT_HALF_LENGTH: f32 = 0.9;
+
+/// Marks the stake's collider
+#[derive(Component, Debug)]
+pub struct Stake;
+
+/// Plugin that lays out the pitch
+pub struct PitPlugin;
+
+impl Plugin for PitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_pitch);
+    }
+}
+
+/// Spawns the grass, the pit, the stake, the foul line, the camera and the light
+fn setup_pitch(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let length = PITCHING_DISTANCE + 8.0;
+    let centre = STAKE.z + PITCHING_DISTANCE / 2.0;
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, length))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.55, 0.22))),
+        Transform::from_xyz(0.0, 0.0, centre),
+        Name::new("Grass"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, centre),
+        RigidBody::Fixed,
+        Collider::cuboid(5.0, 0.1, length / 2.0),
+        Friction::coefficient(0.9),
+        Restitution::coefficient(0.1),
+        Name::new("Ground"),
+    ));
+
+    // The clay sits a hair above the grass so it draws over it, and grips a landing shoe harder
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            PIT_HALF_WIDTH * 2.0,
+            0.01,
+            PIT_HALF_LENGTH * 2.0,
+        ))),
+        MeshMaterial3d(materials.add(Color::srgb(0.62, 0.45, 0.3))),
+        Transform::from_translation(STAKE + Vec3::Y * 0.005),
+        RigidBody::Fixed,
+        Collider::cuboid(PIT_HALF_WIDTH, 0.005, PIT_HALF_LENGTH),
+        Friction::coefficient(1.2),
+        Restitution::coefficient(0.0),
+        Name::new("Pit"),
+    ));
+    let boards = materials.add(Color::srgb(0.45, 0.3, 0.18));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(0.05, 0.06, PIT_HALF_LENGTH * 2.0))),
+            MeshMaterial3d(boards.clone()),
+            Transform::from_translation(
+                STAKE + Vec3::new(side * (PIT_HALF_WIDTH + 0.025), 0.03, 0.0),
+            ),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(STAKE_RADIUS, STAKE_HEIGHT))),
+        MeshMaterial3d(materials.add(Color::srgb(0.6, 0.6, 0.65))),
+        Transform::from_translation(STAKE + Vec3::Y * STAKE_HEIGHT / 2.0),
+        RigidBody::Fixed,
+        Collider::cylinder(STAKE_HEIGHT / 2.0, STAKE_RADIUS),
+        Friction::coefficient(0.4),
+        Restitution::coefficient(0.2),
+        Name::new("Stake"),
+        Stake,
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(PIT_HALF_WIDTH * 2.0, 0.04))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, 0.005, STAKE.z + PITCHING_DISTANCE),
+        Name::new("Foul line"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: 0.5,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 2.4, STAKE.z + PITCHING_DISTANCE + 2.0).looking_at(STAKE, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 10.0, PITCHING_DISTANCE)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.7)),
+    ));
+}
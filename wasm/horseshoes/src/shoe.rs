@@ -0,0 +1,179 @@
+//! The horseshoe: its U shape, built from two legs and a toe bar, and how a shoe lying still in
+//! the pit is called, as a ringer, a leaner or just how close it got
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Collider;
+
+use crate::pit::{STAKE, STAKE_HEIGHT, STAKE_RADIUS};
+
+/// Half the width and thickness of the shoe's bars
+pub const BAR_HALF_WIDTH: f32 = 0.015;
+/// Half the thickness of the shoe, lying flat
+pub const SHOE_HALF_THICKNESS: f32 = 0.008;
+/// How far either leg runs from the middle of the shoe, across it
+const LEG_X: f32 = 0.09;
+/// Where the legs run along the shoe, from the toe to the calks at the open end
+const LEG_Z: (f32, f32) = (-0.11, 0.12);
+/// Where the toe bar runs along the shoe
+const TOE_Z: f32 = -0.1;
+/// Points for a ringer
+pub const RINGER_POINTS: u32 = 3;
+/// Points for a leaner
+pub const LEANER_POINTS: u32 = 2;
+/// Points for the closest shoe, when it's near enough to count
+pub const CLOSEST_POINTS: u32 = 1;
+/// Furthest a shoe can lie from the stake and still count as the closest, in meters
+pub const COUNT_DISTANCE: f32 = 0.15;
+/// How far a shoe can tip over and still lie flat around the stake, as the cosine of the angle
+const FLAT_COSINE: f32 = 0.9;
+/// Widest gap between a tipped up shoe and the stake for it to count as leaning on it
+const LEAN_GAP: f32 = 0.02;
+/// Lowest the middle of a leaning shoe sits off the clay
+const LEAN_HEIGHT: f32 = 0.03;
+
+/// A shoe pitched this inning
+#[derive(Component, Debug)]
+pub struct Shoe {
+    /// Player who pitched it
+    pub player: usize,
+}
+
+/// The shoe's bars as segments along their middles, in the shoe's own space: the two legs and
+/// the toe. The open end faces the shoe's +z
+fn bars() -> [(Vec3, Vec3); 3] {
+    [
+        (
+            Vec3::new(-LEG_X, 0.0, LEG_Z.0),
+            Vec3::new(-LEG_X, 0.0, LEG_Z.1),
+        ),
+        (
+            Vec3::new(LEG_X, 0.0, LEG_Z.0),
+            Vec3::new(LEG_X, 0.0, LEG_Z.1),
+        ),
+        (Vec3::new(-LEG_X, 0.0, TOE_Z), Vec3::new(LEG_X, 0.0, TOE_Z)),
+    ]
+}
+
+/// Collider of a shoe, matching its bars
+pub fn shoe_collider() -> Collider {
+    Collider::compound(
+        bars()
+            .into_iter()
+            .map(|(start, end)| {
+                let half = (end - start).abs() / 2.0;
+                (
+                    (start + end) / 2.0,
+                    Quat::IDENTITY,
+                    Collider::cuboid(
+                        half.x.max(BAR_HALF_WIDTH),
+                        SHOE_HALF_THICKNESS,
+                        half.z.max(BAR_HALF_WIDTH),
+                    ),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Each bar's mesh and where it sits in the shoe, to build a shoe's look from
+pub fn bar_meshes() -> [(Cuboid, Vec3); 3] {
+    bars().map(|(start, end)| {
+        let size = (end - start).abs()
+            + Vec3::new(BAR_HALF_WIDTH, SHOE_HALF_THICKNESS, BAR_HALF_WIDTH) * 2.0;
+        (Cuboid::from_size(size), (start + end) / 2.0)
+    })
+}
+
+/// How a shoe lying still was called
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Call {
+    /// Flat around the stake, with a straight edge across the calks clearing it
+    Ringer,
+    /// Tipped up against the stake
+    Leaner,
+    /// Anywhere else, this far from the stake at its nearest, in meters
+    Away(f32),
+}
+
+/// Shortest distance across the ground from a point to a segment
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let along = end - start;
+    let t = ((point - start).dot(along) / along.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(start + along * t)
+}
+
+/// How far across the ground a shoe's nearest edge is from the stake's side
+fn gap(shoe: &Transform) -> f32 {
+    let stake = STAKE.xz();
+    bars()
+        .into_iter()
+        .map(|(start, end)| {
+            distance_to_segment(
+                stake,
+                shoe.transform_point(start).xz(),
+                shoe.transform_point(end).xz(),
+            )
+        })
+        .fold(f32::INFINITY, f32::min)
+        - BAR_HALF_WIDTH
+        - STAKE_RADIUS
+}
+
+/// Calls a shoe lying still, from where it ended up
+pub fn call(shoe: &Transform) -> Call {
+    let flat = (shoe.rotation * Vec3::Y).y.abs() >= FLAT_COSINE;
+    let gap = gap(shoe).max(0.0);
+    if flat && shoe.translation.y < STAKE.y + STAKE_HEIGHT {
+        let stake = shoe
+            .rotation
+            .inverse()
+            .mul_vec3(STAKE.with_y(shoe.translation.y) - shoe.translation);
+        let inside = LEG_X - BAR_HALF_WIDTH - STAKE_RADIUS;
+        if stake.x.abs() < inside
+            && stake.z > TOE_Z + BAR_HALF_WIDTH + STAKE_RADIUS
+            && stake.z < LEG_Z.1 - STAKE_RADIUS
+        {
+            return Call::Ringer;
+        }
+    }
+    if !flat && gap <= LEAN_GAP && shoe.translation.y - STAKE.y >= LEAN_HEIGHT {
+        return Call::Leaner;
+    }
+    Call::Away(gap)
+}
+
+/// Points each player scores for an inning from every shoe's call and who pitched it: three for
+/// each ringer, two for each leaner and one for whoever has the single closest of the other
+/// shoes, if it's within [`COUNT_DISTANCE`]
+pub fn inning_points(players: usize, calls: &[(usize, Call)]) -> Vec<u32> {
+    let mut points = vec![0; players];
+    for (player, call) in calls {
+        let Some(points) = points.get_mut(*player) else {
+            continue;
+        };
+        match call {
+            Call::Ringer => *points += RINGER_POINTS,
+            Call::Leaner => *points += LEANER_POINTS,
+            Call::Away(_) => {}
+        }
+    }
+
+    let mut away: Vec<(usize, f32)> = calls
+        .iter()
+        .filter_map(|(player, call)| match call {
+            Call::Away(gap) if *gap <= COUNT_DISTANCE => Some((*player, *gap)),
+            _ => None,
+        })
+        .collect();
+    away.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let tied = match away[..] {
+        [(first, closest), (second, next), ..] => first != second && next - closest < 0.001,
+        _ => false,
+    };
+    if let Some((player, _)) = away.first().filter(|_| !tied) {
+        if let Some(points) = points.get_mut(*player) {
+            *points += CLOSEST_POINTS;
+        }
+    }
+    points
+}
@@ -0,0 +1,253 @@
+//! The course: a stretch of river with buoy gates set alternately left and right of the middle,
+//! patches of current that push boats across or along it, and the finish line at the far end
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+/// How many gates the course has
+pub const GATE_COUNT: usize = 10;
+/// Half the gap between a gate's buoys, in meters
+pub const GATE_HALF_WIDTH: f32 = 2.5;
+/// How far down the river the first gate is
+const FIRST_GATE_Z: f32 = -25.0;
+/// Distance between one gate and the next down the river, in meters
+const GATE_SPACING: f32 = 25.0;
+/// How far either side of the middle of the river gates are set, in meters
+const GATE_OFFSET: f32 = 3.5;
+/// How far down the river the finish line is
+pub const FINISH_Z: f32 = FIRST_GATE_Z - GATE_SPACING * GATE_COUNT as f32;
+/// Half the width of the river, bank to bank
+pub const RIVER_HALF_WIDTH: f32 = 12.0;
+/// How far the river runs back behind the start line
+const BEHIND_START: f32 = 20.0;
+/// How far the river runs on past the finish line
+const PAST_FINISH: f32 = 40.0;
+/// Radius of a buoy
+const BUOY_RADIUS: f32 = 0.35;
+/// How far buoys bob up and down on the water
+const BUOY_BOB: f32 = 0.06;
+/// How fast buoys bob, in radians per second
+const BUOY_BOB_RATE: f32 = 2.2;
+
+/// A patch of the river where the water flows one way
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Current {
+    /// Middle of the patch, as X across and Z down the river
+    pub centre: Vec2,
+    /// Half the patch's size across and down the river, in meters
+    pub half_size: Vec2,
+    /// How fast and which way the water flows, as X across and Z down the river, in meters per
+    /// second
+    pub flow: Vec2,
+}
+
+impl Current {
+    /// Whether a spot on the water is inside the patch
+    pub fn contains(&self, at: Vec3) -> bool {
+        let offset = (Vec2::new(at.x, at.z) - self.centre).abs();
+        offset.x <= self.half_size.x && offset.y <= self.half_size.y
+    }
+}
+
+/// Every patch of current on the course: a push toward the right bank, a stretch flowing back
+/// against the boats, a push toward the left bank and a run flowing with them to the finish
+pub const CURRENTS: [Current; 4] = [
+    Current {
+        centre: Vec2::new(0.0, -75.0),
+        half_size: Vec2::new(RIVER_HALF_WIDTH, 15.0),
+        flow: Vec2::new(0.9, 0.0),
+    },
+    Current {
+        centre: Vec2::new(-4.0, -140.0),
+        half_size: Vec2::new(8.0, 18.0),
+        flow: Vec2::new(0.0, 0.8),
+    },
+    Current {
+        centre: Vec2::new(0.0, -200.0),
+        half_size: Vec2::new(RIVER_HALF_WIDTH, 12.0),
+        flow: Vec2::new(-1.1, 0.0),
+    },
+    Current {
+        centre: Vec2::new(3.0, -252.0),
+        half_size: Vec2::new(6.0, 18.0),
+        flow: Vec2::new(0.0, -0.7),
+    },
+];
+
+/// Where the middle of a gate sits on the river, as X across and Z down it
+pub fn gate_at(index: usize) -> Vec2 {
+    let side = if index.is_multiple_of(2) { -1.0 } else { 1.0 };
+    // Some gates are set further out than others so the line through them isn't a steady weave
+    let shift = 1.5 * (index as f32 * 2.3).sin();
+    Vec2::new(
+        side * (GATE_OFFSET + shift),
+        FIRST_GATE_Z - GATE_SPACING * index as f32,
+    )
+}
+
+/// Whether going from one Z to another crosses a line across the river at `line`
+pub fn crossed(line: f32, from: f32, to: f32) -> bool {
+    from > line && to <= line
+}
+
+/// Whether a boat crossing a gate's line at X went between its buoys
+pub fn through_gate(index: usize, x: f32) -> bool {
+    (x - gate_at(index).x).abs() <= GATE_HALF_WIDTH
+}
+
+/// How fast and which way the water is flowing at a spot, adding up every patch of current it's
+/// in, in meters per second
+pub fn current_at(at: Vec3) -> Vec3 {
+    CURRENTS
+        .iter()
+        .filter(|current| current.contains(at))
+        .map(|current| Vec3::new(current.flow.x, 0.0, current.flow.y))
+        .sum()
+}
+
+/// A buoy bobbing on the water
+#[derive(Component, Debug)]
+struct Buoy {
+    /// Where through its bob the buoy starts, in radians
+    phase: f32,
+}
+
+/// Plugin that lays out the river, the gates, the currents and the finish line
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_course)
+            .add_systems(Update, (bob_buoys, draw_currents));
+    }
+}
+
+/// Spawns the water and its banks, every gate's buoys, the current patches, the finish line,
+/// the camera and the light
+fn setup_course(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let length = BEHIND_START - FINISH_Z + PAST_FINISH;
+    let middle = (BEHIND_START + FINISH_Z - PAST_FINISH) / 2.0;
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(RIVER_HALF_WIDTH * 2.0, length),
+            ),
+        ),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.15, 0.4, 0.55),
+            perceptual_roughness: 0.15,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 0.0, middle),
+        Name::new("River"),
+    ));
+    let grass = materials.add(Color::srgb(0.3, 0.5, 0.2));
+    let bank = meshes.add(Cuboid::new(30.0, 1.0, length));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(bank.clone()),
+            MeshMaterial3d(grass.clone()),
+            Transform::from_xyz(side * (RIVER_HALF_WIDTH + 15.0), 0.3, middle),
+        ));
+    }
+
+    let patch = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.6, 0.85, 0.95, 0.25),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    for current in CURRENTS {
+        commands.spawn((
+            Mesh3d(
+                meshes.add(
+                    Plane3d::default()
+                        .mesh()
+                        .size(current.half_size.x * 2.0, current.half_size.y * 2.0),
+                ),
+            ),
+            MeshMaterial3d(patch.clone()),
+            Transform::from_xyz(current.centre.x, 0.01, current.centre.y),
+        ));
+    }
+
+    let buoy = meshes.add(Sphere::new(BUOY_RADIUS));
+    let colors = [
+        materials.add(Color::srgb(0.95, 0.45, 0.05)),
+        materials.add(Color::srgb(0.95, 0.85, 0.1)),
+    ];
+    for index in 0..GATE_COUNT {
+        let centre = gate_at(index);
+        let color = &colors[index % colors.len()];
+        for side in [-1.0, 1.0] {
+            commands.spawn((
+                Mesh3d(buoy.clone()),
+                MeshMaterial3d(color.clone()),
+                Transform::from_xyz(centre.x + side * GATE_HALF_WIDTH, 0.0, centre.y),
+                Buoy {
+                    phase: index as f32 + side,
+                },
+            ));
+        }
+    }
+
+    let finish = materials.add(Color::srgb(0.15, 0.15, 0.2));
+    let post = meshes.add(Cuboid::new(0.3, 4.0, 0.3));
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(post.clone()),
+            MeshMaterial3d(finish.clone()),
+            Transform::from_xyz(side * RIVER_HALF_WIDTH, 2.0, FINISH_Z),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(RIVER_HALF_WIDTH * 2.0 + 0.3, 0.8, 0.3))),
+        MeshMaterial3d(finish),
+        Transform::from_xyz(0.0, 4.4, FINISH_Z),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(RIVER_HALF_WIDTH * 2.0, 0.2))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_xyz(0.0, 0.02, FINISH_Z),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 7.0).looking_at(Vec3::new(0.0, 0.0, -10.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(8.0, 20.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+/// Bobs every buoy up and down on the water
+fn bob_buoys(mut buoys: Query<'_, '_, (&mut Transform, &Buoy)>, time: Res<'_, Time>) {
+    for (mut transform, buoy) in &mut buoys {
+        transform.translation.y =
+            (time.elapsed_secs() * BUOY_BOB_RATE + buoy.phase).sin() * BUOY_BOB;
+    }
+}
+
+/// Draws arrows across every patch of current, showing which way and how hard it flows
+fn draw_currents(mut gizmos: Gizmos<'_, '_>) {
+    for current in CURRENTS {
+        let flow = Vec3::new(current.flow.x, 0.0, current.flow.y);
+        let centre = Vec3::new(current.centre.x, 0.05, current.centre.y);
+        let across = Quat::from_rotation_y(FRAC_PI_2) * flow.normalize_or_zero();
+        for offset in [-0.5, 0.0, 0.5] {
+            let start = centre + across * offset * current.half_size.min_element();
+            gizmos.arrow(start, start + flow * 3.0, Color::srgb(0.85, 0.95, 1.0));
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Bevy kayak sprint
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use course::{current_at, gate_at, CoursePlugin, GATE_COUNT, RIVER_HALF_WIDTH};
+use phase::{KayakPhase, KayakPhasePlugin};
+use race::{NewGame, Race, RacePlugin};
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::{GameEvent, JsMessage},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader, FeedbackSender,
+};
+
+pub mod course;
+pub mod phase;
+pub mod race;
+pub mod timesheet;
+
+/// Speed a full-strength stroke adds to the boat, in meters per second
+const STROKE_PUSH: f32 = 0.9;
+/// How much of its push a stroke keeps when it's on the same side as the one before
+const SAME_SIDE_PUSH: f32 = 0.5;
+/// How fast a full-strength stroke sets the boat turning away from its side, in radians per
+/// second
+const STROKE_TURN: f32 = 0.35;
+/// How quickly the boat stops turning once the strokes even out, per second
+pub const YAW_DAMPING: f32 = 2.0;
+/// How hard the water holds the boat back in proportion to its speed, per second
+const LINEAR_DRAG: f32 = 0.05;
+/// How hard the water holds the boat back in proportion to its speed squared, per meter
+const QUADRATIC_DRAG: f32 = 0.06;
+/// Fastest the boat can go through the water, in meters per second
+const MAX_SPEED: f32 = 6.0;
+/// How much speed the boat loses every second it scrapes along a bank, in meters per second
+const BANK_DRAG: f32 = 3.0;
+/// Half the width of the boat
+const HULL_HALF_WIDTH: f32 = 0.3;
+/// Length of the boat
+const HULL_LENGTH: f32 = 4.0;
+/// How far the controller has to sweep round during a motion for it to count as a stroke, in
+/// radians
+const STROKE_SWEEP: f32 = 0.3;
+/// How fast the controller has to sweep for the strongest stroke, in radians per second
+const FULL_STROKE_SPEED: f32 = 10.0;
+/// How quickly the paddle comes back level after a stroke, per second
+const PADDLE_RECOVERY: f32 = 3.0;
+/// Furthest the paddle dips toward the side it's stroking on, in radians
+const PADDLE_DIP: f32 = 0.6;
+/// Rumble strength for a full-strength stroke, out of 255
+const MAX_RUMBLE: f32 = 120.0;
+/// How long the controller rumbles for a stroke, in milliseconds
+const RUMBLE_MILLIS: u16 = 40;
+/// How far behind the boat the camera follows
+const CAMERA_BACK: f32 = 7.0;
+/// How far above the water the camera sits
+const CAMERA_UP: f32 = 3.0;
+/// How quickly the camera catches up with the boat, higher is snappier
+const CAMERA_SMOOTHING: f32 = 4.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(KayakPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(CoursePlugin)
+    .add_plugins(RacePlugin)
+    .insert_resource(ClearColor(Color::srgb(0.6, 0.78, 0.95)))
+    .init_resource::<Paddler>()
+    .add_event::<Start>()
+    .add_event::<Stroke>()
+    .add_systems(Startup, spawn_kayak)
+    .add_systems(OnEnter(KayakPhase::Ready), return_to_start)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            start_race.run_if(in_state(KayakPhase::Ready)),
+            (take_strokes, paddle).run_if(in_state(KayakPhase::Racing)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (pose_paddle, follow_kayak, draw_line));
+});
+
+/// Which side of the boat a stroke is taken on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The left side, which turns the boat to the right
+    Left,
+    /// The right side, which turns the boat to the left
+    Right,
+}
+
+impl Side {
+    /// The other side
+    pub fn other(&self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// `1.0` for the left and `-1.0` for the right, matching headings that turn positive to the
+    /// left
+    fn sign(&self) -> f32 {
+        match self {
+            Self::Left => 1.0,
+            Self::Right => -1.0,
+        }
+    }
+}
+
+/// Watches the paddler's controller for strokes
+#[derive(Resource, Debug, Default)]
+pub struct Paddler {
+    /// Watches the controller for the sweep of each stroke
+    detector: GestureDetector,
+}
+
+/// The paddler on the start line set off down the course
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Start;
+
+/// A paddle stroke
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    /// Which side of the boat it was taken on
+    pub side: Side,
+    /// How hard it was pulled, from 0 to 1
+    pub power: f32,
+}
+
+/// The kayak, paddled down the river and carried along by the current
+#[derive(Component, Debug, Default)]
+pub struct Kayak {
+    /// Which way the bow points, in radians from straight down the river. Positive turns left
+    pub heading: f32,
+    /// How fast the boat is turning, in radians per second. Positive turns left
+    pub yaw_rate: f32,
+    /// How fast the boat is moving through the water, in meters per second
+    pub speed: f32,
+    /// The side of the last stroke taken, if one has been
+    pub last_side: Option<Side>,
+    /// How far the paddle is dipped toward the side of the last stroke, from -1 right to 1 left
+    dip: f32,
+}
+
+impl Kayak {
+    /// Which way across the water the bow points
+    pub fn direction(&self) -> Vec3 {
+        Quat::from_rotation_y(self.heading) * Vec3::NEG_Z
+    }
+
+    /// Pulls a stroke: pushing the boat on, by less when it's on the same side as the one before,
+    /// and turning it away from the side it was taken on
+    pub fn stroke(&mut self, stroke: &Stroke) {
+        let power = stroke.power.clamp(0.0, 1.0);
+        let push = if self.last_side == Some(stroke.side) {
+            STROKE_PUSH * SAME_SIDE_PUSH
+        } else {
+            STROKE_PUSH
+        };
+        self.speed = (self.speed + push * power).min(MAX_SPEED);
+        self.yaw_rate -= stroke.side.sign() * STROKE_TURN * power;
+        self.last_side = Some(stroke.side);
+        self.dip = stroke.side.sign();
+    }
+}
+
+/// The paddle the paddler holds across the boat
+#[derive(Component, Debug)]
+struct Paddle;
+
+/// Spawns the kayak, its paddler and their paddle on the start line
+fn spawn_kayak(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn((
+            Transform::default(),
+            Visibility::Visible,
+            Kayak::default(),
+            Name::new("Kayak"),
+        ))
+        .with_children(|kayak| {
+            kayak.spawn((
+                Mesh3d(meshes.add(Capsule3d::new(HULL_HALF_WIDTH, HULL_LENGTH - 0.6))),
+                MeshMaterial3d(materials.add(Color::srgb(0.9, 0.2, 0.1))),
+                Transform::from_xyz(0.0, 0.1, 0.0)
+                    .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))
+                    .with_scale(Vec3::new(1.0, 1.0, 0.5)),
+            ));
+            kayak.spawn((
+                Mesh3d(meshes.add(Capsule3d::new(0.2, 0.5))),
+                MeshMaterial3d(materials.add(Color::srgb(0.95, 0.8, 0.15))),
+                Transform::from_xyz(0.0, 0.6, 0.2),
+            ));
+            kayak
+                .spawn((
+                    Transform::from_xyz(0.0, 0.75, 0.0),
+                    Visibility::Inherited,
+                    Paddle,
+                ))
+                .with_children(|paddle| {
+                    paddle.spawn((
+                        Mesh3d(meshes.add(Cylinder::new(0.02, 2.2))),
+                        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.2, 0.2))),
+                        Transform::from_rotation(Quat::from_rotation_z(
+                            std::f32::consts::FRAC_PI_2,
+                        )),
+                    ));
+                    let blade = meshes.add(Cuboid::new(0.4, 0.02, 0.18));
+                    let blue = materials.add(Color::srgb(0.1, 0.4, 0.8));
+                    for side in [-1.0, 1.0] {
+                        paddle.spawn((
+                            Mesh3d(blade.clone()),
+                            MeshMaterial3d(blue.clone()),
+                            Transform::from_xyz(side * 1.1, 0.0, 0.0),
+                        ));
+                    }
+                });
+        });
+}
+
+/// Puts the kayak back on the start line, sitting still and pointing down the river
+fn return_to_start(mut kayaks: Query<'_, '_, (&mut Transform, &mut Kayak)>) {
+    for (mut transform, mut kayak) in &mut kayaks {
+        *kayak = Kayak::default();
+        *transform = Transform::default();
+    }
+}
+
+/// Everything input handling changes besides the paddler's stroke detection
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Paddlers to set off from the start line
+    starts: EventWriter<'w, Start>,
+    /// Strokes to pull
+    strokes: EventWriter<'w, Stroke>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: sweeping the controller round one way pulls a stroke on the left and
+/// the other way on the right, harder the faster the sweep, and A sets off from the start line.
+/// A starts a new sprint once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut paddler: ResMut<'_, Paddler>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<KayakPhase>>,
+    time: Res<'_, Time>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == KayakPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == KayakPhase::Ready => {
+                effects.starts.send(Start);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _)
+                if *phase.get() == KayakPhase::Racing =>
+            {
+                let orientation = settings.apply_rotation(orientation);
+                let Some(detected) = paddler.detector.update(orientation, time.elapsed_secs())
+                else {
+                    continue;
+                };
+                if detected.turned.yaw.abs() < STROKE_SWEEP {
+                    continue;
+                }
+                if matches!(
+                    detected.gesture,
+                    Gesture::Swing | Gesture::Flick | Gesture::Twist
+                ) {
+                    // Pulling the left blade back swings the controller round to the left
+                    let side = if detected.turned.yaw > 0.0 {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    };
+                    effects.strokes.send(Stroke {
+                        side,
+                        power: (detected.intensity / FULL_STROKE_SPEED).clamp(0.0, 1.0),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Sets the paddler on the start line off down the course
+fn start_race(
+    mut starts: EventReader<'_, '_, Start>,
+    mut next_phase: ResMut<'_, NextState<KayakPhase>>,
+) {
+    if starts.read().last().is_some() {
+        next_phase.set(KayakPhase::Racing);
+    }
+}
+
+/// Pulls every stroke taken this frame, with a little rumble for each
+fn take_strokes(
+    mut strokes: EventReader<'_, '_, Stroke>,
+    mut kayaks: Query<'_, '_, &mut Kayak>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    for stroke in strokes.read() {
+        for mut kayak in &mut kayaks {
+            kayak.stroke(stroke);
+        }
+        feedback.send(GameEvent::Rumble {
+            intensity: (stroke.power.clamp(0.0, 1.0) * MAX_RUMBLE) as u8,
+            millis: RUMBLE_MILLIS,
+        });
+    }
+}
+
+/// Turns the boat as its strokes left it turning, slows it with the drag of the water, carries
+/// it along with the current and keeps it off the banks
+fn paddle(mut kayaks: Query<'_, '_, (&mut Transform, &mut Kayak)>, time: Res<'_, Time>) {
+    let secs = time.delta_secs();
+    for (mut transform, mut kayak) in &mut kayaks {
+        kayak.yaw_rate *= (-YAW_DAMPING * secs).exp();
+        kayak.heading += kayak.yaw_rate * secs;
+        let drag = LINEAR_DRAG * kayak.speed + QUADRATIC_DRAG * kayak.speed * kayak.speed;
+        kayak.speed = (kayak.speed - drag * secs).max(0.0);
+
+        let velocity = kayak.direction() * kayak.speed + current_at(transform.translation);
+        transform.translation += velocity * secs;
+
+        let reach = RIVER_HALF_WIDTH - HULL_HALF_WIDTH;
+        if transform.translation.x.abs() > reach {
+            transform.translation.x = transform.translation.x.clamp(-reach, reach);
+            kayak.speed = (kayak.speed - BANK_DRAG * secs).max(0.0);
+        }
+        transform.rotation = Quat::from_rotation_y(kayak.heading);
+    }
+}
+
+/// Dips the paddle toward the side of the last stroke, bringing it back level between strokes
+fn pose_paddle(
+    mut kayaks: Query<'_, '_, &mut Kayak>,
+    mut paddles: Query<'_, '_, &mut Transform, With<Paddle>>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut kayak) = kayaks.get_single_mut() else {
+        return;
+    };
+    kayak.dip *= (-PADDLE_RECOVERY * time.delta_secs()).exp();
+    for mut paddle in &mut paddles {
+        paddle.rotation = Quat::from_rotation_z(kayak.dip * PADDLE_DIP)
+            * Quat::from_rotation_y(-kayak.dip * PADDLE_DIP);
+    }
+}
+
+/// Follows the boat from behind and above, looking down the river
+fn follow_kayak(
+    mut cameras: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Kayak>)>,
+    kayaks: Query<'_, '_, (&Transform, &Kayak)>,
+    time: Res<'_, Time>,
+) {
+    let (Ok(mut camera), Ok((transform, kayak))) = (cameras.get_single_mut(), kayaks.get_single())
+    else {
+        return;
+    };
+    // Straight down the river behind a boat turned across it keeps more of the course in view
+    let behind = (kayak.direction() + Vec3::NEG_Z).normalize_or(Vec3::NEG_Z) * -CAMERA_BACK;
+    let goal = transform.translation + behind + Vec3::Y * CAMERA_UP;
+    let blend = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(transform.translation, Vec3::Y);
+}
+
+/// Marks the middle of the next gate while racing, when the aim guide is on
+fn draw_line(
+    mut gizmos: Gizmos<'_, '_>,
+    race: Res<'_, Race>,
+    phase: Res<'_, State<KayakPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() == KayakPhase::GameOver || race.next_gate >= GATE_COUNT {
+        return;
+    }
+    let gate = gate_at(race.next_gate);
+    gizmos.circle(
+        Isometry3d::new(
+            Vec3::new(gate.x, 0.05, gate.y),
+            Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+        ),
+        0.5,
+        Color::srgb(1.0, 0.8, 0.1),
+    );
+}
@@ -0,0 +1,27 @@
+//! Phases a kayak sprint moves through, from the start line to the final leaderboard
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the sprint is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KayakPhase {
+    /// The paddler up next is sitting on the start line
+    #[default]
+    Ready,
+    /// The paddler is on their way down the course against the clock
+    Racing,
+    /// The paddler crossed the finish line and their time is up
+    Finished,
+    /// Every paddler has raced and the final leaderboard is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the sprint is in
+pub struct KayakPhasePlugin;
+
+impl Plugin for KayakPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<KayakPhase>();
+    }
+}
@@ -0,0 +1,311 @@
+//! Races down the course: timing the paddler, checking them through every gate and across the
+//! finish line, and handing the start line to the next paddler, all shown on the shared
+//! scorecard HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    course::{crossed, gate_at, through_gate, FINISH_Z, GATE_COUNT},
+    phase::KayakPhase,
+    timesheet::{place, RaceTime, Timesheet, MISSED_GATE_SECS},
+    Kayak,
+};
+
+/// How long a finished race's time stays up before the next paddler goes, in seconds
+const FINISHED_SECS: f32 = 3.0;
+/// Longest a race can run before the paddler is called in, in seconds. Every gate they hadn't
+/// reached counts as missed
+const MAX_RACE_SECS: f32 = 180.0;
+/// Meters per second in a kilometer per hour
+const KMH: f32 = 3.6;
+
+/// Asks for the sprint to be started over from the first paddler
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// The race in progress
+#[derive(Resource, Serialize, Debug, Clone, Default)]
+pub struct Race {
+    /// Seconds on the clock since the paddler left the start line
+    pub secs: f32,
+    /// The gate the paddler has to go through next
+    pub next_gate: usize,
+    /// Gates the paddler has gone the wrong side of so far
+    pub missed: usize,
+    /// How far down the river the boat was when gates were last checked
+    last_z: f32,
+}
+
+/// Counts down before the next paddler takes the start line
+#[derive(Resource, Debug)]
+struct Finishing(Timer);
+
+impl Default for Finishing {
+    fn default() -> Self {
+        Self(Timer::from_seconds(FINISHED_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct KayakSnapshot<'a> {
+    /// Paddler on the course
+    player: usize,
+    /// Every paddler's finished race
+    timesheet: &'a Timesheet,
+    /// The race in progress
+    current: &'a Race,
+    /// Where the sprint is at
+    phase: KayakPhase,
+}
+
+/// Plugin that times races and shows the leaderboard on the scorecard HUD
+pub struct RacePlugin;
+
+impl Plugin for RacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Timesheet>()
+            .init_resource::<Race>()
+            .init_resource::<Finishing>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    (time_race, check_gates).run_if(in_state(KayakPhase::Racing)),
+                    next_race.run_if(in_state(KayakPhase::Finished)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(KayakPhase::Ready), reset_race)
+            .add_systems(OnEnter(KayakPhase::Finished), reset_finishing)
+            .add_systems(
+                OnEnter(KayakPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(KayakPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh sprint whenever the number of paddlers changes
+fn fit_match(turns: Res<'_, TurnManager>, mut timesheet: ResMut<'_, Timesheet>) {
+    if timesheet.players() != turns.players() {
+        *timesheet = Timesheet::new(turns.players());
+    }
+}
+
+/// Clears the clock and the gates for the paddler on the start line
+fn reset_race(mut race: ResMut<'_, Race>) {
+    *race = Race::default();
+}
+
+/// Runs the clock while the paddler is on the course
+fn time_race(mut race: ResMut<'_, Race>, time: Res<'_, Time>) {
+    race.secs += time.delta_secs();
+}
+
+/// Checks the paddler through each gate as they pass its line, adding a penalty for every one
+/// they go the wrong side of, and stops the clock at the finish line or once the race has run
+/// too long
+fn check_gates(
+    mut race: ResMut<'_, Race>,
+    mut timesheet: ResMut<'_, Timesheet>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<KayakPhase>>,
+    kayaks: Query<'_, '_, &Transform, With<Kayak>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Ok(kayak) = kayaks.get_single() else {
+        return;
+    };
+    let Vec3 { x, z, .. } = kayak.translation;
+
+    while race.next_gate < GATE_COUNT && crossed(gate_at(race.next_gate).y, race.last_z, z) {
+        if !through_gate(race.next_gate, x) {
+            race.missed += 1;
+            banner.show(format!("Missed gate! +{MISSED_GATE_SECS:.0}s"));
+        }
+        race.next_gate += 1;
+    }
+
+    let timed_out = race.secs >= MAX_RACE_SECS;
+    if crossed(FINISH_Z, race.last_z, z) || timed_out {
+        // Gates paddled around entirely count as missed too
+        race.missed += GATE_COUNT - race.next_gate;
+        race.next_gate = GATE_COUNT;
+        let finished = RaceTime {
+            secs: race.secs.min(MAX_RACE_SECS),
+            missed: race.missed,
+        };
+        timesheet.record(turns.current(), finished);
+        banner.show(if timed_out {
+            format!(
+                "Time's up! Player {}: {}",
+                turns.current() + 1,
+                finished.label()
+            )
+        } else {
+            format!("Player {}: {}", turns.current() + 1, finished.label())
+        });
+        next_phase.set(KayakPhase::Finished);
+    }
+    race.last_z = z;
+}
+
+/// Gives paddlers a moment to see the race's time
+fn reset_finishing(mut finishing: ResMut<'_, Finishing>) {
+    finishing.0.reset();
+}
+
+/// Hands the start line to the next paddler yet to race, finishing the sprint once everyone has
+fn next_race(
+    mut finishing: ResMut<'_, Finishing>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<KayakPhase>>,
+    timesheet: Res<'_, Timesheet>,
+    time: Res<'_, Time>,
+) {
+    if !finishing.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if turns
+        .advance_until(|player| timesheet.time(player).is_some())
+        .is_some()
+    {
+        next_phase.set(KayakPhase::Ready);
+    } else {
+        next_phase.set(KayakPhase::GameOver);
+    }
+}
+
+/// Starts the sprint over from the first paddler
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut timesheet: ResMut<'_, Timesheet>,
+    mut next_phase: ResMut<'_, NextState<KayakPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *timesheet = Timesheet::new(turns.players());
+    next_phase.set(KayakPhase::Ready);
+}
+
+/// Fills in the scorecard HUD with the leaderboard so far and those still to race, and the
+/// clock, gates and speed of the race in progress
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    timesheet: Res<'_, Timesheet>,
+    race: Res<'_, Race>,
+    turns: Res<'_, TurnManager>,
+    phase: Res<'_, State<KayakPhase>>,
+    kayaks: Query<'_, '_, &Kayak>,
+) {
+    let mut rows: Vec<String> = timesheet
+        .leaderboard()
+        .iter()
+        .enumerate()
+        .map(|(index, (player, time))| {
+            format!("{} Player {}: {}", place(index), player + 1, time.label())
+        })
+        .collect();
+    rows.extend(
+        (0..timesheet.players())
+            .filter(|player| timesheet.time(*player).is_none())
+            .map(|player| format!("Player {}: to race", player + 1)),
+    );
+
+    let speed = kayaks.get_single().map_or(0.0, |kayak| kayak.speed);
+    let mut footer = format!(
+        "Time: {:.2}s\nGates: {} of {GATE_COUNT}, missed {}\nSpeed: {:.1} km/h",
+        race.secs,
+        race.next_gate,
+        race.missed,
+        speed * KMH
+    );
+    match phase.get() {
+        KayakPhase::Ready => footer.push_str("\nPress A to start"),
+        KayakPhase::Racing => footer.push_str("\nStroke left and right in turn to paddle"),
+        KayakPhase::Finished | KayakPhase::GameOver => {}
+    }
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Kayak Sprint, Player {} on the water", turns.current() + 1),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists the final leaderboard once the sprint is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, timesheet: Res<'_, Timesheet>) {
+    let mut lines = vec!["Final Leaderboard".to_string()];
+    let leaderboard = timesheet.leaderboard();
+    match leaderboard[..] {
+        [(first, a), (_, b), ..] if a.total() == b.total() => {
+            lines.push(format!("Player {} ties for the win!", first + 1));
+        }
+        [(winner, _), _, ..] => lines.push(format!("Player {} wins!", winner + 1)),
+        _ => {}
+    }
+    for (index, (player, time)) in leaderboard.iter().enumerate() {
+        lines.push(format!(
+            "{} Player {}: {}",
+            place(index),
+            player + 1,
+            time.label()
+        ));
+    }
+    lines.push("Press A to race again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final leaderboard when a new sprint starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every paddler's points back to the page once the sprint is over, so it can submit them
+/// to the server
+fn submit_result(timesheet: Res<'_, Timesheet>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..timesheet.players())
+        .map(|player| timesheet.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    timesheet: Res<'_, Timesheet>,
+    race: Res<'_, Race>,
+    phase: Res<'_, State<KayakPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&KayakSnapshot {
+        player: turns.current(),
+        timesheet: &timesheet,
+        current: &race,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,110 @@
+//! Race times: every paddler's finish time, the seconds added for missed gates, the leaderboard
+//! they're ranked on and the points sent to the server once the sprint is over
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Seconds added to a race for every gate missed
+pub const MISSED_GATE_SECS: f32 = 5.0;
+/// Time that earns no points, in seconds
+const PAR_SECS: f32 = 150.0;
+/// Points earned for every second under par
+const POINTS_PER_SEC: f32 = 100.0;
+
+/// One paddler's race down the course
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RaceTime {
+    /// Seconds from the start line to the finish line
+    pub secs: f32,
+    /// Gates the paddler went the wrong side of
+    pub missed: usize,
+}
+
+impl RaceTime {
+    /// The race's time with the penalties for missed gates added
+    pub fn total(&self) -> f32 {
+        self.secs + self.missed as f32 * MISSED_GATE_SECS
+    }
+
+    /// How the race reads on the leaderboard, with any penalty seconds after it
+    pub fn label(&self) -> String {
+        match self.missed {
+            0 => format!("{:.2}s", self.secs),
+            missed => format!(
+                "{:.2}s ({:.2}+{:.0})",
+                self.total(),
+                self.secs,
+                missed as f32 * MISSED_GATE_SECS
+            ),
+        }
+    }
+}
+
+/// Every paddler's race so far
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Timesheet {
+    /// Each paddler's finished race, in turn order, `None` until they've raced
+    times: Vec<Option<RaceTime>>,
+}
+
+impl Default for Timesheet {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Timesheet {
+    /// Starts a sprint for a number of paddlers with nobody raced
+    pub fn new(players: usize) -> Self {
+        Self {
+            times: vec![None; players.max(1)],
+        }
+    }
+
+    /// How many paddlers are in the sprint
+    pub fn players(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Records a paddler's finished race
+    pub fn record(&mut self, player: usize, time: RaceTime) {
+        if let Some(slot) = self.times.get_mut(player) {
+            *slot = Some(time);
+        }
+    }
+
+    /// A paddler's finished race, if they've raced
+    pub fn time(&self, player: usize) -> Option<RaceTime> {
+        self.times.get(player).copied().flatten()
+    }
+
+    /// Paddlers who've raced, fastest first, with earlier paddlers ahead on a tie
+    pub fn leaderboard(&self) -> Vec<(usize, RaceTime)> {
+        let mut finished: Vec<(usize, RaceTime)> = (0..self.players())
+            .filter_map(|player| Some((player, self.time(player)?)))
+            .collect();
+        finished.sort_by(|a, b| a.1.total().total_cmp(&b.1.total()));
+        finished
+    }
+
+    /// Points for a paddler's time, higher for faster so the server ranks them like every other
+    /// game's scores
+    pub fn points(&self, player: usize) -> u32 {
+        self.time(player).map_or(0, |time| {
+            ((PAR_SECS - time.total()) * POINTS_PER_SEC).max(0.0) as u32
+        })
+    }
+}
+
+/// A place on the leaderboard to show players, counting from first
+pub fn place(index: usize) -> String {
+    let place = index + 1;
+    let suffix = match (place % 10, place % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{place}{suffix}")
+}
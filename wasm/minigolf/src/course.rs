@@ -0,0 +1,550 @@
+//! The putt-putt course: three themed holes laid out side by side, each a felt lane boxed in by
+//! wooden walls. The first putts up and over a ramp, the second through a spinning windmill and
+//! the third round a banked bend
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Ccd, Collider, Damping, Friction, Restitution, RigidBody, Velocity};
+use serde::{Deserialize, Serialize};
+
+/// Radius of the ball
+pub const BALL_RADIUS: f32 = 0.06;
+/// Radius of the cup
+pub const CUP_RADIUS: f32 = 0.12;
+/// Damping on a rolling ball, slowing it like the felt does
+pub const FELT_DAMPING: f32 = 0.5;
+/// Height of the walls around each lane
+const WALL_HEIGHT: f32 = 0.15;
+/// Thickness of the walls around each lane
+const WALL_THICKNESS: f32 = 0.1;
+/// Thickness of ramps and banks
+const SLAB_THICKNESS: f32 = 0.1;
+/// Height of the flagstick planted in each cup
+const FLAG_HEIGHT: f32 = 0.8;
+
+/// Half the width of the ramp hole's lane
+const RAMP_HALF_WIDTH: f32 = 1.0;
+/// Where the ramp hole's lane ends past the tee
+const RAMP_LENGTH: f32 = 14.0;
+/// Where the ramp up starts and ends past the tee
+const RAMP_UP: (f32, f32) = (4.0, 6.0);
+/// Where the ramp down starts and ends past the tee, with the plateau between the ramps
+const RAMP_DOWN: (f32, f32) = (8.0, 10.0);
+/// Height of the plateau between the ramps
+const RAMP_RISE: f32 = 0.4;
+
+/// Half the width of the windmill hole's lane
+const WINDMILL_HALF_WIDTH: f32 = 1.2;
+/// Where the windmill hole's lane ends past the tee
+const WINDMILL_LENGTH: f32 = 16.0;
+/// How far past the tee the windmill stands
+const WINDMILL_AT: f32 = 8.0;
+/// Depth of the windmill's tower
+const TOWER_DEPTH: f32 = 1.0;
+/// Height of the windmill's tower
+const TOWER_HEIGHT: f32 = 1.2;
+/// Half the width of the doorway through the tower
+const DOOR_HALF_WIDTH: f32 = 0.3;
+/// Height of the doorway through the tower
+const DOOR_HEIGHT: f32 = 0.35;
+/// Height of the hub the sails turn on
+const HUB_HEIGHT: f32 = 1.35;
+/// Length of each sail, long enough to sweep across the doorway
+const SAIL_LENGTH: f32 = 1.4;
+/// Half the width of each sail
+const SAIL_HALF_WIDTH: f32 = 0.12;
+/// How fast the sails turn, in radians per second
+const SAIL_SPEED: f32 = 0.8;
+
+/// Half the width of the bank hole's lanes
+const BANK_HALF_WIDTH: f32 = 1.2;
+/// How far past the tee the bend starts
+const BEND_AT: f32 = 8.0;
+/// Radius of the bend along the middle of the lane
+const BEND_RADIUS: f32 = 3.0;
+/// How far the bank rises at its outside edge at the middle of the bend
+const BANK_RISE: f32 = 0.5;
+/// How many straight pieces the bend is built from
+const BEND_PIECES: usize = 8;
+/// How far the lane runs on after the bend
+const BANK_RUN_OUT: f32 = 10.0;
+
+/// Marks the golf ball
+#[derive(Component, Debug, Default)]
+pub struct Ball;
+
+/// Marks the windmill's turning sails
+#[derive(Component, Debug)]
+pub struct Sails;
+
+/// A hole on the course
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hole {
+    /// Straight up a ramp, over a plateau and down to the cup
+    Ramp,
+    /// Through the doorway of a windmill between its turning sails
+    Windmill,
+    /// Round a banked bend to a cup tucked down the far lane
+    Bank,
+}
+
+impl Hole {
+    /// Every hole, in the order they're played
+    pub const ALL: [Self; 3] = [Self::Ramp, Self::Windmill, Self::Bank];
+
+    /// Name of the hole to show players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ramp => "Up and Over",
+            Self::Windmill => "The Windmill",
+            Self::Bank => "Banked Bend",
+        }
+    }
+
+    /// Strokes a good player takes to hole out
+    pub fn par(&self) -> u32 {
+        match self {
+            Self::Ramp => 2,
+            Self::Windmill | Self::Bank => 3,
+        }
+    }
+
+    /// Where the hole's tee is on the ground, with the rest of the hole laid out from it
+    fn origin(&self) -> Vec3 {
+        match self {
+            Self::Ramp => Vec3::ZERO,
+            Self::Windmill => Vec3::X * 8.0,
+            Self::Bank => Vec3::X * 24.0,
+        }
+    }
+
+    /// Where the ball sits on the tee
+    pub fn tee(&self) -> Vec3 {
+        self.origin() + Vec3::Y * BALL_RADIUS
+    }
+
+    /// Where the cup is, on the ground
+    pub fn cup(&self) -> Vec3 {
+        self.origin()
+            + match self {
+                Self::Ramp => Vec3::new(0.0, 0.0, -12.0),
+                Self::Windmill => Vec3::new(0.0, 0.0, -13.5),
+                Self::Bank => Vec3::new(-BEND_RADIUS - 7.0, 0.0, -BEND_AT - BEND_RADIUS),
+            }
+    }
+
+    /// Middle of the bend on the bank hole
+    fn bend_centre(&self) -> Vec3 {
+        self.origin() + Vec3::new(-BEND_RADIUS, 0.0, -BEND_AT)
+    }
+
+    /// Which way the lane runs toward the cup from a spot on the hole, along the ground
+    pub fn heading(&self, at: Vec3) -> Vec3 {
+        if *self != Self::Bank {
+            return Vec3::NEG_Z;
+        }
+        let from_bend = (at - self.bend_centre()).with_y(0.0);
+        if from_bend.z > 0.0 {
+            Vec3::NEG_Z
+        } else if from_bend.x < 0.0 {
+            Vec3::NEG_X
+        } else {
+            // Round the bend, along the circle about its middle
+            Vec3::new(from_bend.z, 0.0, -from_bend.x).normalize_or(Vec3::NEG_Z)
+        }
+    }
+
+    /// Whether a spot has left the hole, costing a penalty stroke
+    pub fn out_of_bounds(&self, at: Vec3) -> bool {
+        let local = at - self.origin();
+        let (min, max) = match self {
+            Self::Ramp => (
+                Vec2::new(-RAMP_HALF_WIDTH, -RAMP_LENGTH),
+                Vec2::new(RAMP_HALF_WIDTH, 1.0),
+            ),
+            Self::Windmill => (
+                Vec2::new(-WINDMILL_HALF_WIDTH, -WINDMILL_LENGTH),
+                Vec2::new(WINDMILL_HALF_WIDTH, 1.0),
+            ),
+            Self::Bank => (
+                Vec2::new(
+                    -BEND_RADIUS - BANK_RUN_OUT,
+                    -BEND_AT - BEND_RADIUS - BANK_HALF_WIDTH,
+                ),
+                Vec2::new(BANK_HALF_WIDTH, 1.0),
+            ),
+        };
+        let margin = Vec2::splat(WALL_THICKNESS * 2.0);
+        local.y < -0.5
+            || local.xz().cmplt(min - margin).any()
+            || local.xz().cmpgt(max + margin).any()
+    }
+}
+
+/// Whether a ball is resting on the ground rather than up on a ramp or in the air
+pub fn on_ground(position: Vec3) -> bool {
+    position.y <= BALL_RADIUS + 0.03
+}
+
+/// Plugin that lays out the course and turns the windmill
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_course)
+            .add_systems(Update, turn_sails);
+    }
+}
+
+/// Meshes and materials the holes are built from
+struct Builder<'a, 'w, 's> {
+    /// Commands to spawn the pieces with
+    commands: &'a mut Commands<'w, 's>,
+    /// Meshes for each piece
+    meshes: &'a mut Assets<Mesh>,
+    /// Felt the lanes are covered in
+    felt: Handle<StandardMaterial>,
+    /// Wood the walls, ramps and windmill are built from
+    wood: Handle<StandardMaterial>,
+}
+
+impl Builder<'_, '_, '_> {
+    /// Spawns a solid block of a size, placed and turned by `transform`
+    fn block(&mut self, size: Vec3, transform: Transform, material: Handle<StandardMaterial>) {
+        self.commands.spawn((
+            Mesh3d(self.meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(material),
+            transform,
+            RigidBody::Fixed,
+            Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+            Friction::coefficient(0.5),
+            Restitution::coefficient(0.6),
+        ));
+    }
+
+    /// Spawns a wall running from `from` to `to` on the ground, standing `height` tall
+    fn wall(&mut self, from: Vec3, to: Vec3, height: f32) {
+        let run = (to - from).with_y(0.0);
+        let transform = Transform::from_translation((from + to) / 2.0 + Vec3::Y * height / 2.0)
+            .looking_to(run.normalize_or(Vec3::NEG_Z), Vec3::Y);
+        let size = Vec3::new(WALL_THICKNESS, height, run.length() + WALL_THICKNESS);
+        self.block(size, transform, self.wood.clone());
+    }
+
+    /// Lays felt over a patch of lane `size` across, centred on `at`
+    fn felt(&mut self, at: Vec3, size: Vec2) {
+        self.commands.spawn((
+            Mesh3d(
+                self.meshes
+                    .add(Plane3d::default().mesh().size(size.x, size.y)),
+            ),
+            MeshMaterial3d(self.felt.clone()),
+            Transform::from_translation(at + Vec3::Y * 0.005),
+        ));
+    }
+
+    /// Puts a slab of felt down as a slope rising `rise` over `from` to `to` along the lane
+    fn slope(&mut self, origin: Vec3, from: f32, to: f32, rise: f32, half_width: f32) {
+        let run = to - from;
+        let angle = (rise / run).atan();
+        let length = run.hypot(rise);
+        let centre_height = rise.abs() / 2.0 - SLAB_THICKNESS / 2.0 / angle.cos();
+        let transform =
+            Transform::from_translation(origin + Vec3::new(0.0, centre_height, -(from + to) / 2.0))
+                .with_rotation(Quat::from_rotation_x(angle));
+        self.block(
+            Vec3::new(half_width * 2.0, SLAB_THICKNESS, length),
+            transform,
+            self.felt.clone(),
+        );
+    }
+
+    /// Boxes in a straight lane running from the tee to `length` past it, with a wall behind the
+    /// tee and across the far end
+    fn straight_lane(&mut self, origin: Vec3, half_width: f32, length: f32) {
+        let back = origin + Vec3::Z;
+        let end = origin - Vec3::Z * length;
+        for side in [-1.0, 1.0] {
+            let offset = Vec3::X * side * (half_width + WALL_THICKNESS / 2.0);
+            self.wall(back + offset, end + offset, WALL_HEIGHT);
+        }
+        let across = Vec3::X * (half_width + WALL_THICKNESS);
+        self.wall(back - across, back + across, WALL_HEIGHT);
+        self.wall(end - across, end + across, WALL_HEIGHT);
+        self.felt(
+            (back + end) / 2.0,
+            Vec2::new(half_width * 2.0, length + 1.0),
+        );
+    }
+}
+
+/// Spawns the grass, every hole with its tee, cup and flag, the ball and the lights
+fn setup_course(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(60.0, 40.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.55, 0.25))),
+        Transform::from_xyz(12.0, -0.001, -8.0),
+        Name::new("Grass"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(12.0, -0.1, -8.0),
+        RigidBody::Fixed,
+        Collider::cuboid(30.0, 0.1, 20.0),
+        Friction::coefficient(0.5),
+        Name::new("Ground"),
+    ));
+
+    let felt = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.1, 0.45, 0.2),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    let wood = materials.add(Color::srgb(0.55, 0.36, 0.2));
+    let cup = materials.add(Color::BLACK);
+    let flag = materials.add(Color::srgb(0.9, 0.15, 0.15));
+    let pole = materials.add(Color::WHITE);
+    let mut builder = Builder {
+        commands: &mut commands,
+        meshes: &mut meshes,
+        felt,
+        wood,
+    };
+
+    build_ramp(&mut builder);
+    build_windmill(&mut builder, materials.add(Color::srgb(0.92, 0.88, 0.8)));
+    build_bank(&mut builder);
+
+    for hole in Hole::ALL {
+        commands.spawn((
+            Mesh3d(meshes.add(Circle::new(CUP_RADIUS))),
+            MeshMaterial3d(cup.clone()),
+            Transform::from_translation(hole.cup() + Vec3::Y * 0.01)
+                .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+            Name::new(hole.name()),
+        ));
+        // The flagstick has no collider, so a ball running at the cup drops in rather than
+        // bouncing
+        commands
+            .spawn((
+                Mesh3d(meshes.add(Cylinder::new(0.015, FLAG_HEIGHT))),
+                MeshMaterial3d(pole.clone()),
+                Transform::from_translation(hole.cup() + Vec3::Y * FLAG_HEIGHT / 2.0),
+            ))
+            .with_children(|pole| {
+                pole.spawn((
+                    Mesh3d(meshes.add(Cuboid::new(0.3, 0.2, 0.01))),
+                    MeshMaterial3d(flag.clone()),
+                    Transform::from_xyz(0.15, FLAG_HEIGHT / 2.0 - 0.1, 0.0),
+                ));
+            });
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS).mesh().uv(16, 12))),
+        MeshMaterial3d(materials.add(Color::WHITE)),
+        Transform::from_translation(Hole::Ramp.tee()),
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        Restitution::coefficient(0.5),
+        Friction::coefficient(0.6),
+        Ccd::enabled(),
+        Velocity::zero(),
+        Damping {
+            linear_damping: FELT_DAMPING,
+            angular_damping: FELT_DAMPING,
+        },
+        Ball,
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(10.0, 20.0, 10.0).looking_at(Vec3::new(12.0, 0.0, -8.0), Vec3::Y),
+    ));
+}
+
+/// Builds the first hole: a straight lane with a ramp up to a plateau and back down to the cup
+fn build_ramp(builder: &mut Builder<'_, '_, '_>) {
+    let origin = Hole::Ramp.origin();
+    builder.straight_lane(origin, RAMP_HALF_WIDTH, RAMP_LENGTH);
+    builder.slope(origin, RAMP_UP.0, RAMP_UP.1, RAMP_RISE, RAMP_HALF_WIDTH);
+    builder.slope(
+        origin,
+        RAMP_DOWN.0,
+        RAMP_DOWN.1,
+        -RAMP_RISE,
+        RAMP_HALF_WIDTH,
+    );
+    let plateau = RAMP_DOWN.0 - RAMP_UP.1;
+    builder.block(
+        Vec3::new(RAMP_HALF_WIDTH * 2.0, RAMP_RISE, plateau),
+        Transform::from_translation(
+            origin + Vec3::new(0.0, RAMP_RISE / 2.0, -(RAMP_UP.1 + RAMP_DOWN.0) / 2.0),
+        ),
+        builder.felt.clone(),
+    );
+}
+
+/// Builds the second hole: a straight lane blocked by a windmill, with a doorway through its
+/// tower that the turning sails sweep across
+fn build_windmill(builder: &mut Builder<'_, '_, '_>, plaster: Handle<StandardMaterial>) {
+    let origin = Hole::Windmill.origin();
+    builder.straight_lane(origin, WINDMILL_HALF_WIDTH, WINDMILL_LENGTH);
+
+    let tower = origin - Vec3::Z * WINDMILL_AT;
+    let side_width = WINDMILL_HALF_WIDTH - DOOR_HALF_WIDTH;
+    for side in [-1.0, 1.0] {
+        builder.block(
+            Vec3::new(side_width, TOWER_HEIGHT, TOWER_DEPTH),
+            Transform::from_translation(
+                tower
+                    + Vec3::new(
+                        side * (DOOR_HALF_WIDTH + side_width / 2.0),
+                        TOWER_HEIGHT / 2.0,
+                        0.0,
+                    ),
+            ),
+            plaster.clone(),
+        );
+    }
+    builder.block(
+        Vec3::new(
+            DOOR_HALF_WIDTH * 2.0,
+            TOWER_HEIGHT - DOOR_HEIGHT,
+            TOWER_DEPTH,
+        ),
+        Transform::from_translation(tower + Vec3::Y * (TOWER_HEIGHT + DOOR_HEIGHT) / 2.0),
+        plaster,
+    );
+    builder.block(
+        Vec3::new(WINDMILL_HALF_WIDTH * 2.0, 0.4, TOWER_DEPTH + 0.2),
+        Transform::from_translation(tower + Vec3::Y * (TOWER_HEIGHT + 0.2)),
+        builder.wood.clone(),
+    );
+
+    // The sails turn on a hub out in front of the tower, each one reaching down past the doorway
+    let sail = builder
+        .meshes
+        .add(Cuboid::new(SAIL_HALF_WIDTH * 2.0, SAIL_LENGTH, 0.04));
+    let offsets = (0..4).map(|index| {
+        let turn = Quat::from_rotation_z(index as f32 * FRAC_PI_2);
+        (turn * Vec3::NEG_Y * SAIL_LENGTH / 2.0, turn)
+    });
+    let colliders = offsets
+        .clone()
+        .map(|(offset, turn)| {
+            (
+                offset,
+                turn,
+                Collider::cuboid(SAIL_HALF_WIDTH, SAIL_LENGTH / 2.0, 0.05),
+            )
+        })
+        .collect();
+    let wood = builder.wood.clone();
+    builder
+        .commands
+        .spawn((
+            Transform::from_translation(
+                tower + Vec3::new(0.0, HUB_HEIGHT, TOWER_DEPTH / 2.0 + 0.1),
+            ),
+            Visibility::default(),
+            RigidBody::KinematicPositionBased,
+            Collider::compound(colliders),
+            Restitution::coefficient(0.6),
+            Sails,
+            Name::new("Sails"),
+        ))
+        .with_children(|hub| {
+            for (offset, turn) in offsets {
+                hub.spawn((
+                    Mesh3d(sail.clone()),
+                    MeshMaterial3d(wood.clone()),
+                    Transform::from_translation(offset).with_rotation(turn),
+                ));
+            }
+        });
+}
+
+/// Builds the third hole: a lane running into a bend banked up on its outside edge, turning
+/// onto a second lane with the cup down the end of it
+fn build_bank(builder: &mut Builder<'_, '_, '_>) {
+    let hole = Hole::Bank;
+    let origin = hole.origin();
+    let centre = hole.bend_centre();
+    let (inner, outer) = (BEND_RADIUS - BANK_HALF_WIDTH, BEND_RADIUS + BANK_HALF_WIDTH);
+
+    // The lane in, running from the tee to the start of the bend
+    let back = origin + Vec3::Z;
+    let bend_start = origin - Vec3::Z * BEND_AT;
+    for side in [-1.0, 1.0] {
+        let offset = Vec3::X * side * (BANK_HALF_WIDTH + WALL_THICKNESS / 2.0);
+        builder.wall(back + offset, bend_start + offset, WALL_HEIGHT);
+    }
+    let across = Vec3::X * (BANK_HALF_WIDTH + WALL_THICKNESS);
+    builder.wall(back - across, back + across, WALL_HEIGHT);
+    builder.felt(
+        (back + bend_start) / 2.0,
+        Vec2::new(BANK_HALF_WIDTH * 2.0, BEND_AT + 1.0),
+    );
+
+    // The lane out, running from the end of the bend to the cup
+    let bend_end = centre - Vec3::Z * BEND_RADIUS;
+    let far_end = bend_end - Vec3::X * BANK_RUN_OUT;
+    for side in [-1.0, 1.0] {
+        let offset = Vec3::Z * side * (BANK_HALF_WIDTH + WALL_THICKNESS / 2.0);
+        builder.wall(bend_end + offset, far_end + offset, WALL_HEIGHT);
+    }
+    let across = Vec3::Z * (BANK_HALF_WIDTH + WALL_THICKNESS);
+    builder.wall(far_end - across, far_end + across, WALL_HEIGHT);
+    builder.felt(
+        (bend_end + far_end) / 2.0,
+        Vec2::new(BANK_RUN_OUT, BANK_HALF_WIDTH * 2.0),
+    );
+
+    // The bend, built from straight pieces each tipped up toward the outside, steepest through
+    // the middle of the bend and flattening out onto the lanes either side
+    let step = FRAC_PI_2 / BEND_PIECES as f32;
+    for piece in 0..BEND_PIECES {
+        let angle = (piece as f32 + 0.5) * step;
+        let outward = Vec3::new(angle.cos(), 0.0, -angle.sin());
+        let rise = BANK_RISE * (2.0 * angle).sin();
+        let tilt = (rise / (outer - inner)).atan();
+        let turn = Quat::from_rotation_y(angle);
+        let length = 2.0 * outer * (step / 2.0).tan() + 0.05;
+        builder.block(
+            Vec3::new((outer - inner) / tilt.cos(), SLAB_THICKNESS, length),
+            Transform::from_translation(
+                centre + outward * BEND_RADIUS + Vec3::Y * (rise / 2.0 - SLAB_THICKNESS / 2.0),
+            )
+            .with_rotation(turn * Quat::from_rotation_z(tilt)),
+            builder.felt.clone(),
+        );
+
+        let (from, to) = (piece as f32 * step, (piece + 1) as f32 * step);
+        let along =
+            |radius: f32, angle: f32| centre + Vec3::new(angle.cos(), 0.0, -angle.sin()) * radius;
+        let wall_out = outer + WALL_THICKNESS / 2.0;
+        builder.wall(
+            along(wall_out, from),
+            along(wall_out, to),
+            WALL_HEIGHT + rise,
+        );
+        let wall_in = inner - WALL_THICKNESS / 2.0;
+        builder.wall(along(wall_in, from), along(wall_in, to), WALL_HEIGHT);
+    }
+}
+
+/// Turns the windmill's sails, sweeping them across the doorway
+fn turn_sails(mut sails: Query<'_, '_, &mut Transform, With<Sails>>, time: Res<'_, Time>) {
+    for mut transform in &mut sails {
+        transform.rotation = Quat::from_rotation_z(time.elapsed_secs() * SAIL_SPEED);
+    }
+}
@@ -0,0 +1,399 @@
+//! Bevy mini golf game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::Velocity,
+};
+use course::{on_ground, Ball, CoursePlugin, Hole, CUP_RADIUS};
+use phase::{MiniGolfPhase, MiniGolfPhasePlugin};
+use scorecard::{Scorecard, ScorecardPlugin};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector, GestureThresholds},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod course;
+pub mod phase;
+pub mod scorecard;
+
+/// Widest a putt can be aimed away from the way the lane runs, in radians
+const MAX_AIM: f32 = 1.3;
+/// Putt speed each radian per second of swing is worth, in meters per second
+const SPEED_SCALE: f32 = 2.5;
+/// Softest a detected swing putts the ball, in meters per second
+pub const MIN_SPEED: f32 = 0.5;
+/// Hardest a putt can be struck, in meters per second
+pub const MAX_SPEED: f32 = 8.0;
+/// Speed below which the ball counts as stopped, in meters per second
+const REST_SPEED: f32 = 0.05;
+/// How long the ball has to stay stopped before the putt is over, in seconds
+const REST_SECS: f32 = 0.5;
+/// Fastest the ball can be going over the cup and still drop in, in meters per second
+const CUP_CAPTURE_SPEED: f32 = 1.5;
+/// Length of the aim arrow drawn from the ball while aiming
+const AIM_ARROW_LENGTH: f32 = 1.0;
+/// How far behind the ball the camera sits
+const CAMERA_BACK: f32 = 3.0;
+/// How far above the ball the camera sits
+const CAMERA_UP: f32 = 1.6;
+/// How quickly the camera catches up with the ball, higher is snappier
+const CAMERA_SMOOTHING: f32 = 4.0;
+/// Thresholds for picking gentle putting strokes out of the controller's motion
+const PUTT_THRESHOLDS: GestureThresholds = GestureThresholds {
+    swing_speed: 1.0,
+    flick_speed: 3.0,
+    flick_max_secs: 0.2,
+    twist_angle: 1.2,
+    rest_speed: 0.4,
+};
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(MiniGolfPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(CoursePlugin)
+    .add_plugins(ScorecardPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Putt>()
+    .add_event::<Stroke>()
+    .add_event::<NewGame>()
+    .add_systems(Startup, setup_camera)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            strike_ball.run_if(in_state(MiniGolfPhase::Aiming)),
+            track_ball.run_if(in_state(MiniGolfPhase::Rolling)),
+            start_new_game,
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        (
+            follow_ball,
+            draw_aim_guide.run_if(in_state(MiniGolfPhase::Aiming)),
+        ),
+    );
+});
+
+/// The putt the player whose turn it is is lining up, or has just struck
+#[derive(Resource, Debug)]
+pub struct Putt {
+    /// How far the putt is aimed away from the way the lane runs, in radians
+    pub aim: f32,
+    /// Where the ball was last putted from, so it can be put back there when it leaves the hole
+    lie: Vec3,
+    /// Watches the controller for putting strokes
+    detector: GestureDetector,
+    /// How long the ball has been stopped for, in seconds
+    stopped_for: f32,
+}
+
+impl Default for Putt {
+    fn default() -> Self {
+        Self::from(Hole::Ramp.tee())
+    }
+}
+
+impl From<Vec3> for Putt {
+    fn from(lie: Vec3) -> Self {
+        Self {
+            aim: 0.0,
+            lie,
+            detector: GestureDetector::new(PUTT_THRESHOLDS),
+            stopped_for: 0.0,
+        }
+    }
+}
+
+impl Putt {
+    /// Which way the ball heads along the ground when struck on a hole, from the way the lane
+    /// runs at the ball turned by the aim
+    fn direction(&self, hole: Hole) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * hole.heading(self.lie)
+    }
+}
+
+/// A putting stroke that connected with the ball
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Stroke {
+    /// How fast the ball leaves the putter, in meters per second
+    pub speed: f32,
+}
+
+/// Asks for the round to be started over from the first player's tee shot on the first hole
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Marks the camera following the ball
+#[derive(Component)]
+struct FollowCamera;
+
+/// Spawns the camera behind the first tee
+fn setup_camera(mut commands: Commands<'_, '_>) {
+    let tee = Hole::Ramp.tee();
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(tee + Vec3::new(0.0, CAMERA_UP, CAMERA_BACK))
+            .looking_at(tee, Vec3::Y),
+        FollowCamera,
+    ));
+}
+
+/// Everything input handling changes besides the putt itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Strokes to putt the ball with
+    strokes: EventWriter<'w, Stroke>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw aims and a gentle swing putts as hard as it was swung, and A
+/// starts a new game once the round is done
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut putt: ResMut<'_, Putt>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<MiniGolfPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == MiniGolfPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == MiniGolfPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation @ Orientation { yaw, .. } = settings.apply_rotation(orientation);
+                putt.aim = yaw.clamp(-MAX_AIM, MAX_AIM);
+
+                let Some(detected) = putt.detector.update(orientation, time.elapsed_secs()) else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+                    effects.strokes.send(Stroke {
+                        speed: (detected.intensity * SPEED_SCALE).clamp(MIN_SPEED, MAX_SPEED),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Putts the ball, counting the stroke
+fn strike_ball(
+    mut strokes: EventReader<'_, '_, Stroke>,
+    mut ball: Query<'_, '_, (&Transform, &mut Velocity), With<Ball>>,
+    mut putt: ResMut<'_, Putt>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut next_phase: ResMut<'_, NextState<MiniGolfPhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(stroke) = strokes.read().last().copied() else {
+        return;
+    };
+    let Ok((transform, mut velocity)) = ball.get_single_mut() else {
+        return;
+    };
+
+    putt.lie = transform.translation;
+    putt.stopped_for = 0.0;
+    velocity.linvel = putt.direction(scorecard.hole()) * stroke.speed.clamp(0.0, MAX_SPEED);
+    velocity.angvel = Vec3::ZERO;
+
+    scorecard.add_stroke(turns.current());
+    next_phase.set(MiniGolfPhase::Rolling);
+}
+
+/// Everything watching the ball changes once a putt is over
+#[derive(SystemParam)]
+struct PuttOutcome<'w> {
+    /// The card strokes and penalties are counted on
+    scorecard: ResMut<'w, Scorecard>,
+    /// Whose turn it is
+    turns: ResMut<'w, TurnManager>,
+    /// Messages shown to players
+    banner: ResMut<'w, Banner>,
+    /// Phase to move on to
+    next_phase: ResMut<'w, NextState<MiniGolfPhase>>,
+}
+
+impl PuttOutcome<'_> {
+    /// Moves on once a putt is over: the same player goes again from where the ball lies, or if
+    /// they're done with the hole the next player tees off, or once everyone is done everyone
+    /// moves on to the next hole, or the game ends. Returns where the ball should be placed next
+    fn finish(&mut self, ball: Vec3) -> Vec3 {
+        let player = self.turns.current();
+        if self.scorecard.out_of_strokes(player) {
+            self.scorecard.hole_out(player);
+            self.banner.show("Picked up");
+        }
+
+        if !self.scorecard.is_holed(player) {
+            self.next_phase.set(MiniGolfPhase::Aiming);
+            return ball;
+        }
+
+        let scorecard = &self.scorecard;
+        if self
+            .turns
+            .advance_until(|player| scorecard.is_holed(player))
+            .is_some()
+        {
+            self.next_phase.set(MiniGolfPhase::Aiming);
+            return self.scorecard.hole().tee();
+        }
+
+        let Some(next) = self.scorecard.next_hole() else {
+            self.next_phase.set(MiniGolfPhase::GameOver);
+            return ball;
+        };
+        self.turns.restart();
+        self.banner.show(format!(
+            "Hole {}: {} (Par {})",
+            self.scorecard.hole_number(),
+            next.name(),
+            next.par()
+        ));
+        self.next_phase.set(MiniGolfPhase::Aiming);
+        next.tee()
+    }
+}
+
+/// Follows the ball after a putt, watching for it dropping into the cup, leaving the hole or
+/// coming to rest
+fn track_ball(
+    mut ball: Query<'_, '_, (&mut Transform, &mut Velocity), With<Ball>>,
+    mut putt: ResMut<'_, Putt>,
+    mut outcome: PuttOutcome<'_>,
+    time: Res<'_, Time>,
+) {
+    let Ok((mut transform, mut velocity)) = ball.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+    let speed = velocity.linvel.length();
+    let player = outcome.turns.current();
+    let hole = outcome.scorecard.hole();
+
+    let next = if hole.out_of_bounds(position) {
+        outcome.scorecard.add_stroke(player);
+        outcome.banner.show("Off the course! +1 stroke");
+        Some(putt.lie)
+    } else if on_ground(position)
+        && position.xz().distance(hole.cup().xz()) <= CUP_RADIUS
+        && speed <= CUP_CAPTURE_SPEED
+    {
+        outcome.scorecard.hole_out(player);
+        outcome
+            .banner
+            .show(match outcome.scorecard.strokes(player) {
+                1 => "Hole in one!".to_string(),
+                strokes => format!("Holed in {strokes}"),
+            });
+        Some(position)
+    } else {
+        putt.stopped_for = if speed < REST_SPEED {
+            putt.stopped_for + time.delta_secs()
+        } else {
+            0.0
+        };
+        (putt.stopped_for >= REST_SECS).then_some(position)
+    };
+
+    if let Some(lie) = next {
+        let lie = outcome.finish(lie);
+        transform.translation = lie;
+        *velocity = Velocity::zero();
+        *putt = Putt::from(lie);
+    }
+}
+
+/// Starts the round over from the first player's tee shot on the first hole with a fresh card
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut ball: Query<'_, '_, (&mut Transform, &mut Velocity), With<Ball>>,
+    mut putt: ResMut<'_, Putt>,
+    mut scorecard: ResMut<'_, Scorecard>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<MiniGolfPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for (mut transform, mut velocity) in &mut ball {
+        transform.translation = Hole::Ramp.tee();
+        *velocity = Velocity::zero();
+    }
+    *putt = Putt::default();
+    turns.restart();
+    *scorecard = Scorecard::new(turns.players());
+    next_phase.set(MiniGolfPhase::Aiming);
+}
+
+/// Keeps the camera behind the ball, looking the way the putt is aimed
+fn follow_ball(
+    mut camera: Query<'_, '_, &mut Transform, (With<FollowCamera>, Without<Ball>)>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    putt: Res<'_, Putt>,
+    scorecard: Res<'_, Scorecard>,
+    time: Res<'_, Time>,
+) {
+    let (Ok(mut camera), Ok(ball)) = (camera.get_single_mut(), ball.get_single()) else {
+        return;
+    };
+    let target = ball.translation;
+    let wanted = target - putt.direction(scorecard.hole()) * CAMERA_BACK + Vec3::Y * CAMERA_UP;
+    let blend = 1.0 - (-CAMERA_SMOOTHING * time.delta_secs()).exp();
+
+    camera.translation = camera.translation.lerp(wanted, blend);
+    camera.look_at(target, Vec3::Y);
+}
+
+/// Draws an arrow from the ball the way the putt is aimed
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    putt: Res<'_, Putt>,
+    scorecard: Res<'_, Scorecard>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    for ball in &ball {
+        let start = ball.translation;
+        gizmos.arrow(
+            start,
+            start + putt.direction(scorecard.hole()) * AIM_ARROW_LENGTH,
+            Color::srgb(1.0, 0.9, 0.2),
+        );
+    }
+}
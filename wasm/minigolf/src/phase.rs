@@ -0,0 +1,25 @@
+//! Phases a mini golf game moves through, from lining up a putt to the final scorecard
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the round is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MiniGolfPhase {
+    /// The player whose turn it is is lining up and putting
+    #[default]
+    Aiming,
+    /// The ball is rolling after a putt
+    Rolling,
+    /// Every hole has been played, and the final scorecard is up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct MiniGolfPhasePlugin;
+
+impl Plugin for MiniGolfPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<MiniGolfPhase>();
+    }
+}
@@ -0,0 +1,271 @@
+//! Strokes each player has taken on every hole, shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{course::Hole, phase::MiniGolfPhase};
+
+/// Most strokes a player can take on a hole before picking up their ball, scored as that many
+pub const MAX_STROKES: u32 = 6;
+
+/// Strokes each player has taken on every hole, in turn order
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scorecard {
+    /// Strokes taken by each player on each hole, penalties included
+    strokes: Vec<[u32; 3]>,
+    /// Whether each player has finished the hole being played, by holing out or picking up
+    holed: Vec<bool>,
+    /// Which hole is being played, as an index into [`Hole::ALL`]
+    hole: usize,
+}
+
+impl Scorecard {
+    /// Starts a fresh card on the first hole for a number of players
+    pub fn new(players: usize) -> Self {
+        Self {
+            strokes: vec![[0; 3]; players],
+            holed: vec![false; players],
+            hole: 0,
+        }
+    }
+
+    /// How many players are on the card
+    pub fn players(&self) -> usize {
+        self.strokes.len()
+    }
+
+    /// The hole being played
+    pub fn hole(&self) -> Hole {
+        Hole::ALL[self.hole.min(Hole::ALL.len() - 1)]
+    }
+
+    /// Which hole is being played, counting from one
+    pub fn hole_number(&self) -> usize {
+        self.hole + 1
+    }
+
+    /// Strokes a player has taken on a hole
+    fn strokes_on(&self, player: usize, hole: usize) -> u32 {
+        self.strokes
+            .get(player)
+            .and_then(|strokes| strokes.get(hole))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Strokes a player has taken on the hole being played
+    pub fn strokes(&self, player: usize) -> u32 {
+        self.strokes_on(player, self.hole)
+    }
+
+    /// Strokes a player has taken over every hole played so far
+    pub fn total(&self, player: usize) -> u32 {
+        (0..=self.hole.min(Hole::ALL.len() - 1))
+            .map(|hole| self.strokes_on(player, hole))
+            .sum()
+    }
+
+    /// Whether a player has finished the hole being played
+    pub fn is_holed(&self, player: usize) -> bool {
+        self.holed.get(player).copied().unwrap_or(true)
+    }
+
+    /// Adds a stroke, or a penalty stroke, to a player's card on the hole being played
+    pub fn add_stroke(&mut self, player: usize) {
+        if let Some(strokes) = self
+            .strokes
+            .get_mut(player)
+            .and_then(|strokes| strokes.get_mut(self.hole))
+        {
+            *strokes += 1;
+        }
+    }
+
+    /// Whether a player has used up their strokes on the hole without holing out, and has to
+    /// pick up
+    pub fn out_of_strokes(&self, player: usize) -> bool {
+        !self.is_holed(player) && self.strokes(player) >= MAX_STROKES
+    }
+
+    /// Marks a player as done with the hole being played
+    pub fn hole_out(&mut self, player: usize) {
+        if let Some(holed) = self.holed.get_mut(player) {
+            *holed = true;
+        }
+    }
+
+    /// Moves everyone on to the next hole, returning it, or `None` once the last has been played
+    pub fn next_hole(&mut self) -> Option<Hole> {
+        let next = *Hole::ALL.get(self.hole + 1)?;
+        self.hole += 1;
+        self.holed.fill(false);
+        Some(next)
+    }
+
+    /// Par over every hole played so far
+    fn par(&self) -> u32 {
+        Hole::ALL[..=self.hole.min(Hole::ALL.len() - 1)]
+            .iter()
+            .map(Hole::par)
+            .sum()
+    }
+
+    /// Stableford points over every hole: two for par on each, one more for every stroke under
+    /// and one fewer for every stroke over, never below zero. Higher is better, like the scores
+    /// the server ranks
+    pub fn points(&self, player: usize) -> u32 {
+        Hole::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, hole)| (hole.par() + 2).saturating_sub(self.strokes_on(player, index)))
+            .sum()
+    }
+
+    /// A player's strokes relative to par over every hole played so far, like `E`, `-1` or `+2`
+    pub fn to_par(&self, player: usize) -> String {
+        match i64::from(self.total(player)) - i64::from(self.par()) {
+            0 => "E".to_string(),
+            diff if diff > 0 => format!("+{diff}"),
+            diff => diff.to_string(),
+        }
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct MiniGolfSnapshot<'a> {
+    /// Player whose turn it is
+    player: usize,
+    /// The card so far
+    scorecard: &'a Scorecard,
+    /// The hole being played
+    hole: Hole,
+    /// Strokes a good player takes to hole out
+    par: u32,
+    /// Where the game is at
+    phase: MiniGolfPhase,
+}
+
+/// Plugin that keeps the scorecard, shows it and reports the final result
+pub struct ScorecardPlugin;
+
+impl Plugin for ScorecardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scorecard>()
+            .add_systems(
+                Update,
+                (
+                    fit_scorecard.run_if(resource_changed::<TurnManager>),
+                    update_hud,
+                    update_snapshot,
+                ),
+            )
+            .add_systems(
+                OnEnter(MiniGolfPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(MiniGolfPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh card whenever the number of players changes
+fn fit_scorecard(turns: Res<'_, TurnManager>, mut scorecard: ResMut<'_, Scorecard>) {
+    if scorecard.players() != turns.players() {
+        *scorecard = Scorecard::new(turns.players());
+    }
+}
+
+/// Fills in the scorecard HUD with the hole being played and every player's strokes on it
+fn update_hud(mut hud: ResMut<'_, ScorecardHud>, scorecard: Res<'_, Scorecard>) {
+    let hole = scorecard.hole();
+    let rows = (0..scorecard.players())
+        .map(|player| {
+            let status = if scorecard.is_holed(player) {
+                "holed"
+            } else {
+                "playing"
+            };
+            format!(
+                "Player {}: {} this hole, {} total ({}) {status}",
+                player + 1,
+                scorecard.strokes(player),
+                scorecard.total(player),
+                scorecard.to_par(player)
+            )
+        })
+        .collect();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!(
+            "Hole {} of {}: {}",
+            scorecard.hole_number(),
+            Hole::ALL.len(),
+            hole.name()
+        ),
+        rows,
+        footer: format!("Par {}", hole.par()),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's strokes on each hole and their points once the round is done
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scorecard: Res<'_, Scorecard>) {
+    let mut lines = vec!["Final Scorecard".to_string()];
+    for player in 0..scorecard.players() {
+        let holes = (0..Hole::ALL.len())
+            .map(|hole| scorecard.strokes_on(player, hole).to_string())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        lines.push(format!(
+            "Player {}: {holes} = {} ({}), {} points",
+            player + 1,
+            scorecard.total(player),
+            scorecard.to_par(player),
+            scorecard.points(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scorecard when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends each player's Stableford points back to the page once the round is done, so it can
+/// submit them to the server
+fn submit_result(scorecard: Res<'_, Scorecard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scorecard.players())
+        .map(|player| scorecard.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scorecard: Res<'_, Scorecard>,
+    phase: Res<'_, State<MiniGolfPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&MiniGolfSnapshot {
+        player: turns.current(),
+        scorecard: &scorecard,
+        hole: scorecard.hole(),
+        par: scorecard.hole().par(),
+        phase: *phase.get(),
+    });
+}
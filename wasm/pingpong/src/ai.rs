@@ -0,0 +1,189 @@
+//! Computer opponent for an end no one is playing: it serves after a moment and swings at
+//! returns once they've bounced, mixing topspin and backspin in and mistiming, misdirecting and
+//! whiffing more the lower its skill
+
+use bevy::prelude::*;
+use spjorts_core::{
+    players::{Jitter, PlayerRegistry, MAX_BOT_SKILL},
+    spectator::is_playing,
+};
+
+use crate::{
+    lineup::Lineup,
+    phase::PingPongPhase,
+    rally::Rally,
+    score::PingPongScore,
+    table::{Ball, Paddle, Side, HALF_WIDTH},
+    Swing, AIM_SWAY, HIT_REACH, MAX_SPIN,
+};
+
+/// Skill the computer plays at when no skill has been picked for it
+const DEFAULT_SKILL: u8 = 5;
+/// How long the computer waits before serving, in seconds
+const SERVE_SECS: f32 = 1.0;
+/// Power the computer serves and returns with
+const POWER: f32 = 0.55;
+/// How much of the table's width the computer aims within
+const AIM_WIDTH: f32 = 0.6;
+/// Most spin the computer puts on a shot, as a share of [`MAX_SPIN`]
+const SPIN_SHARE: f32 = 0.6;
+/// Chance the least skilled computer misses the ball entirely
+const MAX_WHIFF_CHANCE: f32 = 0.3;
+/// Furthest the least skilled computer's timing is off, as a fraction of [`HIT_REACH`]
+const MAX_TIMING_ERROR: f32 = 0.6;
+/// Furthest the least skilled computer's paddle is off, in radians
+const MAX_ANGLE_ERROR: f32 = 0.35;
+
+/// How the computer means to play the ball coming its way
+#[derive(Debug, Clone, Copy)]
+struct Plan {
+    /// The end returning the ball
+    side: Side,
+    /// How early or late the computer swings, as a fraction of [`HIT_REACH`]
+    timing: f32,
+    /// The swing it makes, or `None` if it's going to whiff
+    swing: Option<Swing>,
+    /// Whether it has swung yet
+    done: bool,
+}
+
+/// Computer opponent state
+#[derive(Resource, Debug)]
+pub struct PingPongAi {
+    /// Delay before the computer serves
+    serve: Timer,
+    /// How it means to play the ball coming its way
+    plan: Option<Plan>,
+    /// Where the computer's mistakes come from
+    jitter: Jitter,
+}
+
+impl Default for PingPongAi {
+    fn default() -> Self {
+        Self {
+            serve: Timer::from_seconds(SERVE_SECS, TimerMode::Once),
+            plan: None,
+            jitter: Jitter::default(),
+        }
+    }
+}
+
+impl PingPongAi {
+    /// A swing from an end aimed somewhere on the other half with some spin, off by up to
+    /// `sloppiness` of the least skilled computer's error
+    fn swing(&mut self, side: Side, sloppiness: f32) -> Swing {
+        let across = self.jitter.sample() * HALF_WIDTH * AIM_WIDTH;
+        Swing {
+            side,
+            power: POWER + self.jitter.sample() * 0.2,
+            aim: -across / AIM_SWAY + self.jitter.sample() * MAX_ANGLE_ERROR * sloppiness,
+            lift: self.jitter.sample() * MAX_ANGLE_ERROR * sloppiness,
+            spin: self.jitter.sample() * MAX_SPIN * SPIN_SHARE,
+        }
+    }
+}
+
+/// How far the computer's play is from perfect, from just above 0 at top skill to 1
+fn sloppiness(registry: &PlayerRegistry) -> f32 {
+    let skill = registry.bot().unwrap_or(DEFAULT_SKILL);
+    f32::from(MAX_BOT_SKILL - skill + 1) / f32::from(MAX_BOT_SKILL)
+}
+
+/// Plugin that adds the computer opponent
+pub struct PingPongAiPlugin;
+
+impl Plugin for PingPongAiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PingPongAi>()
+            .add_systems(OnEnter(PingPongPhase::Serving), reset_ai)
+            .add_systems(
+                Update,
+                (
+                    serve.run_if(in_state(PingPongPhase::Serving)),
+                    return_ball.run_if(in_state(PingPongPhase::Rally)),
+                )
+                    .run_if(is_playing),
+            );
+    }
+}
+
+/// Gives the computer a moment before it serves and forgets any return it had planned
+fn reset_ai(mut ai: ResMut<'_, PingPongAi>) {
+    ai.serve.reset();
+    ai.plan = None;
+}
+
+/// Serves once the computer has had a moment, when it's its serve
+fn serve(
+    mut ai: ResMut<'_, PingPongAi>,
+    mut swings: EventWriter<'_, Swing>,
+    score: Res<'_, PingPongScore>,
+    lineup: Res<'_, Lineup>,
+    registry: Res<'_, PlayerRegistry>,
+    time: Res<'_, Time>,
+) {
+    let server = score.server();
+    if lineup.player(server).is_some() || !ai.serve.tick(time.delta()).just_finished() {
+        return;
+    }
+    let swing = ai.swing(server, sloppiness(&registry));
+    swings.send(swing);
+}
+
+/// Plans a return for each ball coming the computer's way and swings once it has bounced and
+/// reached the planned timing
+fn return_ball(
+    mut ai: ResMut<'_, PingPongAi>,
+    mut swings: EventWriter<'_, Swing>,
+    ball: Query<'_, '_, &Transform, With<Ball>>,
+    paddles: Query<'_, '_, (&Transform, &Paddle), Without<Ball>>,
+    rally: Res<'_, Rally>,
+    lineup: Res<'_, Lineup>,
+    registry: Res<'_, PlayerRegistry>,
+) {
+    let Ok(ball) = ball.get_single() else {
+        return;
+    };
+    if ai.plan.is_some_and(|plan| !rally.incoming_to(plan.side)) {
+        ai.plan = None;
+    }
+    if ai.plan.is_none() {
+        let Some(side) = [Side::Near, Side::Far]
+            .into_iter()
+            .find(|side| lineup.player(*side).is_none() && rally.incoming_to(*side))
+        else {
+            return;
+        };
+        let sloppiness = sloppiness(&registry);
+        let whiff = (ai.jitter.sample() + 1.0) / 2.0 < MAX_WHIFF_CHANCE * sloppiness;
+        let timing = ai.jitter.sample() * MAX_TIMING_ERROR * sloppiness;
+        let swing = ai.swing(side, sloppiness);
+        ai.plan = Some(Plan {
+            side,
+            timing,
+            swing: (!whiff).then_some(swing),
+            done: false,
+        });
+    }
+
+    let Some(plan) = ai.plan.as_mut().filter(|plan| !plan.done) else {
+        return;
+    };
+    if !rally.returnable_by(plan.side) {
+        return;
+    }
+    let Some(contact) = paddles
+        .iter()
+        .find(|(_, paddle)| paddle.side == plan.side)
+        .map(|(transform, _)| transform.translation)
+    else {
+        return;
+    };
+    let progress = (ball.translation.z - contact.z) * plan.side.sign() / HIT_REACH;
+    if progress >= plan.timing {
+        plan.done = true;
+        if let Some(swing) = plan.swing {
+            swings.send(swing);
+        }
+    }
+}
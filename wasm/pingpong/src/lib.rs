@@ -0,0 +1,319 @@
+//! Bevy table tennis game
+
+use ai::PingPongAiPlugin;
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{ExternalForce, RigidBody, Velocity},
+};
+use lineup::{Lineup, LineupPlugin};
+use phase::{PingPongPhase, PingPongPhasePlugin};
+use rally::{NewMatch, Rally, RallyPlugin};
+use score::PingPongScore;
+use scoreboard::ScoreboardPlugin;
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    ActionReader,
+};
+use table::{Ball, Paddle, Side, TablePlugin, BALL_MASS, BALL_RADIUS, TABLE_HEIGHT};
+
+pub mod ai;
+pub mod lineup;
+pub mod phase;
+pub mod rally;
+pub mod score;
+pub mod scoreboard;
+pub mod table;
+
+/// How fast the controller has to turn for a full power swing, in radians per second
+const FULL_SWING_SPEED: f32 = 14.0;
+/// Weakest a detected swing hits the ball, as a fraction of a full swing
+const MIN_POWER: f32 = 0.2;
+/// Furthest the ball can be from a paddle along the ground and still be hit, in meters
+pub const HIT_REACH: f32 = 0.45;
+/// How far across the table a radian of paddle yaw sends the ball, in meters
+pub const AIM_SWAY: f32 = 0.8;
+/// How far across the table a swing a full reach early or late pulls the ball, in meters
+const TIMING_SWAY: f32 = 0.3;
+/// How far past the net a flat shot lands, in meters
+const BASE_DEPTH: f32 = 0.85;
+/// How much further a radian of paddle pitch sends the ball, in meters
+const DEPTH_PER_PITCH: f32 = 0.4;
+/// How long the softest shot takes to land, in seconds
+const SLOW_FLIGHT_SECS: f32 = 0.75;
+/// How long the hardest shot takes to land, in seconds
+const FAST_FLIGHT_SECS: f32 = 0.35;
+/// Most spin a flick puts on the ball, in radians per second
+pub const MAX_SPIN: f32 = 150.0;
+/// How strongly a spinning ball curves through the air, as newtons per unit of spin crossed
+/// with velocity
+const MAGNUS: f32 = 1.4e-5;
+/// Acceleration the ball falls at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// Least a shot is planned to fall at, so heavy backspin floats long rather than flying flat
+/// into the net
+const MIN_FALL: f32 = GRAVITY * 0.6;
+/// How fast paddles move across the end of the table, in meters per second
+const MOVE_SPEED: f32 = 3.5;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(PingPongPhasePlugin)
+    .add_plugins(LineupPlugin)
+    .add_plugins(TablePlugin)
+    .add_plugins(RallyPlugin)
+    .add_plugins(ScoreboardPlugin)
+    .add_plugins(PingPongAiPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.12, 0.12, 0.16)))
+    .init_resource::<Paddles>()
+    .add_event::<Swing>()
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            resolve_swings,
+            move_paddles,
+            tilt_paddles,
+            apply_magnus,
+        )
+            .chain()
+            .run_if(is_playing),
+    );
+});
+
+/// How a player holds the paddle on their end
+#[derive(Debug, Default)]
+pub struct PaddleControl {
+    /// Watches the controller for swings
+    detector: GestureDetector,
+    /// Which way the controller is turned, mirrored by the paddle
+    face: Quat,
+}
+
+/// How each end's paddle is held, near first
+#[derive(Resource, Debug, Default)]
+pub struct Paddles([PaddleControl; 2]);
+
+/// A swing of the paddle from one end of the table
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Swing {
+    /// The end swinging
+    pub side: Side,
+    /// How hard the swing was, from 0 to 1
+    pub power: f32,
+    /// Yaw of the paddle, in radians. Turning it one way sends the ball across the table
+    pub aim: f32,
+    /// Pitch of the paddle, in radians. Opening the face up sends the ball deeper
+    pub lift: f32,
+    /// Spin put on the ball, in radians per second, positive for topspin
+    pub spin: f32,
+}
+
+impl Swing {
+    /// Where on the other half the swing sends the ball. Meeting the ball early or late pulls
+    /// it across the table, by how far `timing` is from a perfect contact as a fraction of
+    /// [`HIT_REACH`]
+    fn target(&self, timing: f32) -> Vec3 {
+        let across = -self.aim * AIM_SWAY + timing * TIMING_SWAY;
+        let depth = BASE_DEPTH + self.lift * DEPTH_PER_PITCH;
+        let sign = self.side.sign();
+        Vec3::new(across * sign, TABLE_HEIGHT + BALL_RADIUS, -depth * sign)
+    }
+
+    /// How long the ball takes to land, shorter the harder the swing
+    fn flight_secs(&self) -> f32 {
+        SLOW_FLIGHT_SECS + (FAST_FLIGHT_SECS - SLOW_FLIGHT_SECS) * self.power.clamp(0.0, 1.0)
+    }
+
+    /// Velocity and spin that carry the ball from `from` to land on `to`. Topspin pulls the
+    /// ball down, so it's sent up in a loop that dives onto the table, while backspin holds it
+    /// up so it's sent flatter
+    fn launch(&self, from: Vec3, to: Vec3) -> Velocity {
+        let secs = self.flight_secs();
+        let along = (to - from).with_y(0.0);
+        let dive = MAGNUS * self.spin * along.length() / secs / BALL_MASS;
+        let fall = (GRAVITY + dive).max(MIN_FALL);
+        Velocity {
+            linvel: (to - from) / secs + Vec3::Y * fall * secs / 2.0,
+            angvel: Vec3::Y.cross(along.normalize_or_zero()) * self.spin,
+        }
+    }
+}
+
+/// Where the ball will cross the line a paddle meets it on, if it's heading that way
+pub fn crossing_x(ball: Vec3, velocity: Vec3, contact_z: f32) -> Option<f32> {
+    let secs = (contact_z - ball.z) / velocity.z;
+    (secs.is_finite() && secs > 0.0).then_some(ball.x + velocity.x * secs)
+}
+
+/// Everything input handling changes besides the paddles
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Swings to hit the ball with
+    swings: EventWriter<'w, Swing>,
+    /// Requests to start over
+    new_match: EventWriter<'w, NewMatch>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: each player's rotation turns the paddle on their end and swings it,
+/// with a flick brushing spin onto the ball, and A starts a new match once one is over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut paddles: ResMut<'_, Paddles>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<PingPongPhase>>,
+    lineup: Res<'_, Lineup>,
+    time: Res<'_, Time>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (player, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_match.send(NewMatch);
+            }
+            JsMessage::ButtonA if *phase.get() == PingPongPhase::MatchOver => {
+                effects.new_match.send(NewMatch);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let Some(side) = lineup.side_of(player) else {
+                    continue;
+                };
+                let orientation @ Orientation { pitch, roll, yaw } =
+                    settings.apply_rotation(orientation);
+                let control = &mut paddles.0[side.index()];
+                control.face = Quat::from_euler(EulerRot::YXZ, yaw, pitch, roll);
+                let Some(detected) = control.detector.update(orientation, time.elapsed_secs())
+                else {
+                    continue;
+                };
+                let power = (detected.intensity / FULL_SWING_SPEED).clamp(MIN_POWER, 1.0);
+                let spin = match detected.gesture {
+                    Gesture::Swing => 0.0,
+                    // Brushing up with the face closed rolls topspin on, and chopping down
+                    // with it open cuts backspin
+                    Gesture::Flick if pitch < 0.0 => power * MAX_SPIN,
+                    Gesture::Flick => -power * MAX_SPIN,
+                    Gesture::Twist => continue,
+                };
+                effects.swings.send(Swing {
+                    side,
+                    power,
+                    aim: yaw,
+                    lift: pitch,
+                    spin,
+                });
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Hits the ball for swings that meet it: the server's swing serves the held ball, and in a rally
+/// a swing hits the ball back once it has bounced on that end's half and is within reach
+fn resolve_swings(
+    mut swings: EventReader<'_, '_, Swing>,
+    mut ball: Query<'_, '_, (&Transform, &mut Velocity, &mut RigidBody), With<Ball>>,
+    paddles: Query<'_, '_, (&Transform, &Paddle), Without<Ball>>,
+    mut rally: ResMut<'_, Rally>,
+    mut next_phase: ResMut<'_, NextState<PingPongPhase>>,
+    phase: Res<'_, State<PingPongPhase>>,
+    score: Res<'_, PingPongScore>,
+) {
+    let Ok((transform, mut velocity, mut body)) = ball.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+
+    for swing in swings.read() {
+        let target = match *phase.get() {
+            PingPongPhase::Serving if swing.side == score.server() => swing.target(0.0),
+            PingPongPhase::Rally if rally.returnable_by(swing.side) => {
+                let Some(contact) = paddles
+                    .iter()
+                    .find(|(_, paddle)| paddle.side == swing.side)
+                    .map(|(transform, _)| transform.translation)
+                else {
+                    continue;
+                };
+                // Players reach up or down for the ball, so only how far off it is along the
+                // floor matters
+                if (position - contact).with_y(0.0).length() > HIT_REACH {
+                    continue;
+                }
+                swing.target((position.z - contact.z) * swing.side.sign() / HIT_REACH)
+            }
+            _ => continue,
+        };
+
+        *body = RigidBody::Dynamic;
+        *velocity = swing.launch(position, target);
+        rally.hit_by(swing.side, swing.spin);
+        next_phase.set(PingPongPhase::Rally);
+        return;
+    }
+}
+
+/// Slides each paddle across its end to meet the ball, heading back to the middle when it's
+/// going the other way
+fn move_paddles(
+    mut paddles: Query<'_, '_, (&mut Transform, &Paddle), Without<Ball>>,
+    ball: Query<'_, '_, (&Transform, &Velocity), With<Ball>>,
+    rally: Res<'_, Rally>,
+    phase: Res<'_, State<PingPongPhase>>,
+    time: Res<'_, Time>,
+) {
+    if *phase.get() != PingPongPhase::Rally {
+        return;
+    }
+    let Ok((ball, velocity)) = ball.get_single() else {
+        return;
+    };
+
+    for (mut transform, paddle) in &mut paddles {
+        let target = if rally.incoming_to(paddle.side) {
+            crossing_x(ball.translation, velocity.linvel, transform.translation.z)
+        } else {
+            None
+        }
+        .unwrap_or(paddle.side.home().x);
+
+        let step = MOVE_SPEED * time.delta_secs();
+        transform.translation.x += (target - transform.translation.x).clamp(-step, step);
+    }
+}
+
+/// Turns each player's paddle to mirror their controller
+fn tilt_paddles(
+    mut paddles: Query<'_, '_, (&mut Transform, &Paddle)>,
+    controls: Res<'_, Paddles>,
+    lineup: Res<'_, Lineup>,
+) {
+    for (mut transform, paddle) in &mut paddles {
+        if lineup.player(paddle.side).is_some() {
+            transform.rotation = paddle.side.facing() * controls.0[paddle.side.index()].face;
+        }
+    }
+}
+
+/// Curves the ball with the Magnus force from its spin, pushing it at right angles to both the
+/// way it's spinning and the way it's flying
+fn apply_magnus(mut ball: Query<'_, '_, (&Velocity, &mut ExternalForce), With<Ball>>) {
+    for (velocity, mut force) in &mut ball {
+        force.force = MAGNUS * velocity.angvel.cross(velocity.linvel);
+    }
+}
@@ -0,0 +1,27 @@
+//! Phases a table tennis match moves through, from each serve to match point
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the match is in the flow of a point
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PingPongPhase {
+    /// The server is holding the ball over their paddle, waiting to swing
+    #[default]
+    Serving,
+    /// The ball is in play
+    Rally,
+    /// The point has been decided and the ball is being fetched for the next serve
+    PointOver,
+    /// An end has won the game and the final score is up
+    MatchOver,
+}
+
+/// Plugin that tracks which phase the match is in
+pub struct PingPongPhasePlugin;
+
+impl Plugin for PingPongPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<PingPongPhase>();
+    }
+}
@@ -0,0 +1,252 @@
+//! Rules of a rally: every shot has to clear the net and bounce on the other half of the table,
+//! and the point goes to the hitter once it bounces a second time. Also sets each serve up and
+//! starts matches over
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::{CollisionEvent, ExternalForce, RigidBody, Velocity};
+use spjorts_core::{scorecard::Banner, spectator::is_playing};
+
+use crate::{
+    lineup::Lineup,
+    phase::PingPongPhase,
+    score::PingPongScore,
+    table::{Ball, Floor, Paddle, Side, TableTop},
+};
+
+/// How long the ball takes to be fetched between points, in seconds
+const POINT_PAUSE_SECS: f32 = 1.2;
+/// How far above the server's paddle the ball is held
+const TOSS_HEIGHT: f32 = 0.15;
+/// How far from the table a ball can go before it's counted where it is
+const LOST_DISTANCE: f32 = 7.0;
+
+/// The state of the point being played
+#[derive(Resource, Debug)]
+pub struct Rally {
+    /// The end that hit the ball last, `None` until the serve
+    pub last_hitter: Option<Side>,
+    /// Spin put on the last shot, positive for topspin, in radians per second
+    pub last_spin: f32,
+    /// How many times the ball has bounced since it was last hit
+    bounces: u32,
+    /// Counts down the pause after a point
+    pause: Timer,
+}
+
+impl Default for Rally {
+    fn default() -> Self {
+        Self {
+            last_hitter: None,
+            last_spin: 0.0,
+            bounces: 0,
+            pause: Timer::from_seconds(POINT_PAUSE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Rally {
+    /// Records that an end just hit the ball with some spin
+    pub fn hit_by(&mut self, side: Side, spin: f32) {
+        self.last_hitter = Some(side);
+        self.last_spin = spin;
+        self.bounces = 0;
+    }
+
+    /// Whether the ball is on its way to an end, to be hit back
+    pub fn incoming_to(&self, side: Side) -> bool {
+        self.last_hitter == Some(side.opponent())
+    }
+
+    /// Whether an end can hit the ball back: it's coming their way and has bounced on their
+    /// half, since hitting it on the full isn't allowed
+    pub fn returnable_by(&self, side: Side) -> bool {
+        self.incoming_to(side) && self.bounces == 1
+    }
+
+    /// Works out who, if anyone, wins the point from the ball bouncing at a spot, either on the
+    /// table's top or off it. The first bounce after a hit has to be on the table on the far
+    /// half from the hitter, and a second bounce anywhere wins the hitter the point
+    fn bounce(&mut self, at: Vec3, on_table: bool) -> Option<(Side, &'static str)> {
+        let hitter = self.last_hitter?;
+        self.bounces += 1;
+        if self.bounces > 1 {
+            return Some((hitter, "Winner!"));
+        }
+        if !on_table {
+            Some((hitter.opponent(), "Out!"))
+        } else if Side::of(at) == hitter {
+            Some((hitter.opponent(), "Net!"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Asks for the match to be started over
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewMatch;
+
+/// Plugin that runs the rules of each rally and the flow between points
+pub struct RallyPlugin;
+
+impl Plugin for RallyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rally>()
+            .init_resource::<PingPongScore>()
+            .add_event::<NewMatch>()
+            .add_systems(OnEnter(PingPongPhase::Serving), prepare_serve)
+            .add_systems(OnEnter(PingPongPhase::PointOver), reset_pause)
+            .add_systems(
+                Update,
+                (
+                    watch_ball.run_if(in_state(PingPongPhase::Rally)),
+                    finish_point.run_if(in_state(PingPongPhase::PointOver)),
+                    new_match_for_lineup.run_if(resource_changed::<Lineup>),
+                    start_new_match,
+                    // A new match started mid serve doesn't leave the phase, so it's set up here
+                    prepare_serve
+                        .run_if(in_state(PingPongPhase::Serving))
+                        .run_if(resource_changed::<PingPongScore>),
+                )
+                    .chain()
+                    .run_if(is_playing),
+            );
+    }
+}
+
+/// Holds the ball up over the server's paddle and sends the paddles back to their marks
+fn prepare_serve(
+    mut ball: Query<
+        '_,
+        '_,
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut RigidBody,
+            &mut ExternalForce,
+        ),
+        With<Ball>,
+    >,
+    mut paddles: Query<'_, '_, (&mut Transform, &Paddle), Without<Ball>>,
+    mut rally: ResMut<'_, Rally>,
+    score: Res<'_, PingPongScore>,
+) {
+    *rally = Rally::default();
+    let server = score.server();
+    for (mut transform, paddle) in &mut paddles {
+        transform.translation = paddle.side.home();
+    }
+
+    for (mut transform, mut velocity, mut body, mut force) in &mut ball {
+        transform.translation = server.home() + Vec3::Y * TOSS_HEIGHT;
+        *velocity = Velocity::zero();
+        *force = ExternalForce::default();
+        *body = RigidBody::KinematicPositionBased;
+    }
+}
+
+/// Everything that changes once a point is decided
+#[derive(SystemParam)]
+struct Umpire<'w> {
+    /// The game score
+    score: ResMut<'w, PingPongScore>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the match goes next
+    next_phase: ResMut<'w, NextState<PingPongPhase>>,
+    /// Who plays each end, to name the winner
+    lineup: Res<'w, Lineup>,
+}
+
+impl Umpire<'_> {
+    /// Awards a point, calling it and moving on to the next point or the end of the match
+    fn award(&mut self, winner: Side, call: &str) {
+        if self.score.point_won(winner) {
+            let name = self.lineup.name(winner);
+            self.banner.show(format!("Game, {name}"));
+            self.next_phase.set(PingPongPhase::MatchOver);
+            return;
+        }
+
+        let server = self.score.server();
+        self.banner.show(format!(
+            "{call} {}-{}",
+            self.score.points(server),
+            self.score.points(server.opponent())
+        ));
+        self.next_phase.set(PingPongPhase::PointOver);
+    }
+}
+
+/// Watches the ball bounce, awarding the point once a bounce decides it
+fn watch_ball(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    ball: Query<'_, '_, (Entity, &Transform), With<Ball>>,
+    tables: Query<'_, '_, (), With<TableTop>>,
+    floors: Query<'_, '_, (), With<Floor>>,
+    mut rally: ResMut<'_, Rally>,
+    mut umpire: Umpire<'_>,
+) {
+    let Ok((ball, transform)) = ball.get_single() else {
+        return;
+    };
+    let position = transform.translation;
+
+    let mut decided = None;
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        if (first != ball && second != ball) || decided.is_some() {
+            continue;
+        }
+        let other = if first == ball { second } else { first };
+        if tables.contains(other) {
+            decided = rally.bounce(position, true);
+        } else if floors.contains(other) {
+            decided = rally.bounce(position, false);
+        }
+    }
+    // A ball knocked clear of the room lands wherever it left
+    if decided.is_none() && position.xz().length() > LOST_DISTANCE {
+        decided = rally.bounce(position, false);
+    }
+
+    if let Some((winner, call)) = decided {
+        umpire.award(winner, call);
+    }
+}
+
+/// Restarts the pause between points
+fn reset_pause(mut rally: ResMut<'_, Rally>) {
+    rally.pause.reset();
+}
+
+/// Moves on to the next serve once the ball has been fetched
+fn finish_point(
+    mut rally: ResMut<'_, Rally>,
+    mut next_phase: ResMut<'_, NextState<PingPongPhase>>,
+    time: Res<'_, Time>,
+) {
+    if rally.pause.tick(time.delta()).just_finished() {
+        next_phase.set(PingPongPhase::Serving);
+    }
+}
+
+/// Starts a fresh match whenever a second player joins or leaves
+fn new_match_for_lineup(mut requests: EventWriter<'_, NewMatch>) {
+    requests.send(NewMatch);
+}
+
+/// Starts the match over with the near end serving first
+fn start_new_match(
+    mut requests: EventReader<'_, '_, NewMatch>,
+    mut score: ResMut<'_, PingPongScore>,
+    mut next_phase: ResMut<'_, NextState<PingPongPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+    *score = PingPongScore::default();
+    next_phase.set(PingPongPhase::Serving);
+}
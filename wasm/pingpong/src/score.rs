@@ -0,0 +1,77 @@
+//! Game scoring: the first end to 11 points wins, as long as it's two clear. The serve passes
+//! across the net every two points, and every point once both ends reach 10
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::table::Side;
+
+/// Points an end has to reach to win the game
+pub const POINTS_TO_WIN: u8 = 11;
+/// Serves in a row each end gets before the serve passes over
+const SERVES_EACH: u8 = 2;
+
+/// Points on each end, near first
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PingPongScore {
+    /// Points won in the game
+    points: [u8; 2],
+    /// The end that served first
+    first_server: Side,
+    /// The end that won the game, once one has
+    winner: Option<Side>,
+}
+
+impl Default for PingPongScore {
+    fn default() -> Self {
+        Self::new(Side::Near)
+    }
+}
+
+impl PingPongScore {
+    /// Starts a game with an end serving first
+    pub fn new(first_server: Side) -> Self {
+        Self {
+            points: [0; 2],
+            first_server,
+            winner: None,
+        }
+    }
+
+    /// Points an end has won
+    pub fn points(&self, side: Side) -> u8 {
+        self.points[side.index()]
+    }
+
+    /// The end that won the game, once one has
+    pub fn winner(&self) -> Option<Side> {
+        self.winner
+    }
+
+    /// The end serving the next point
+    pub fn server(&self) -> Side {
+        let played = self.points[0] + self.points[1];
+        let deuce = (POINTS_TO_WIN - 1) * 2;
+        let changes = if played >= deuce {
+            // Past 10 all the serve alternates every point
+            played - deuce + deuce / SERVES_EACH
+        } else {
+            played / SERVES_EACH
+        };
+        if changes.is_multiple_of(2) {
+            self.first_server
+        } else {
+            self.first_server.opponent()
+        }
+    }
+
+    /// Awards a point to an end, returning whether it won the game
+    pub fn point_won(&mut self, side: Side) -> bool {
+        let (won, lost) = (side.index(), side.opponent().index());
+        self.points[won] += 1;
+        if self.points[won] >= POINTS_TO_WIN && self.points[won] >= self.points[lost] + 2 {
+            self.winner = Some(side);
+        }
+        self.winner.is_some()
+    }
+}
@@ -0,0 +1,138 @@
+//! The scoreboard: points on each end, who is serving and the spin on the last shot on the
+//! shared scorecard HUD, plus the match result sent back to the page
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    FeedbackSender,
+};
+
+use crate::{
+    lineup::Lineup,
+    phase::PingPongPhase,
+    rally::Rally,
+    score::{PingPongScore, POINTS_TO_WIN},
+    table::Side,
+};
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct PingPongSnapshot<'a> {
+    /// Points and who served first
+    score: &'a PingPongScore,
+    /// The end serving the next point
+    server: Side,
+    /// Spin on the last shot, positive for topspin
+    spin: f32,
+    /// Where the match is at
+    phase: PingPongPhase,
+}
+
+/// Plugin that shows the score on the scorecard HUD and reports the result
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(PingPongPhase::MatchOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(PingPongPhase::MatchOver), hide_final_card);
+    }
+}
+
+/// What the spin on a shot is called
+fn spin_name(spin: f32) -> &'static str {
+    if spin > 0.0 {
+        "Topspin"
+    } else if spin < 0.0 {
+        "Backspin"
+    } else {
+        "Flat"
+    }
+}
+
+/// Fills in the scorecard HUD with points on each end, who is serving and the last shot's spin
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, PingPongScore>,
+    rally: Res<'_, Rally>,
+    lineup: Res<'_, Lineup>,
+) {
+    let rows = [Side::Near, Side::Far]
+        .into_iter()
+        .map(|side| format!("{}: {}", lineup.name(side), score.points(side)))
+        .collect();
+    let mut footer = format!("{} to serve", lineup.name(score.server()));
+    if rally.last_hitter.is_some() {
+        footer.push_str(&format!("\nLast shot: {}", spin_name(rally.last_spin)));
+    }
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Table Tennis, first to {POINTS_TO_WIN}"),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Shows the points each end won once the match is over
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, PingPongScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    let mut lines = vec!["Final Score".to_string()];
+    if let Some(winner) = score.winner() {
+        lines.push(format!("{} wins!", lineup.name(winner)));
+    }
+    for side in [Side::Near, Side::Far] {
+        lines.push(format!("{}: {}", lineup.name(side), score.points(side)));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final score when a new match starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends the points each player won back to the page once the match is over, so it can submit
+/// them to the server
+fn submit_result(
+    score: Res<'_, PingPongScore>,
+    lineup: Res<'_, Lineup>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = [Side::Near, Side::Far]
+        .into_iter()
+        .filter(|side| lineup.player(*side).is_some())
+        .map(|side| u32::from(score.points(side)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    score: Res<'_, PingPongScore>,
+    rally: Res<'_, Rally>,
+    phase: Res<'_, State<PingPongPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&PingPongSnapshot {
+        score: &score,
+        server: score.server(),
+        spin: rally.last_spin,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,262 @@
+//! The table: its top, the net, the floor around it, the ball and a paddle at either end
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    ActiveEvents, Ccd, Collider, ColliderMassProperties, ExternalForce, Friction, Restitution,
+    RigidBody, Velocity,
+};
+use serde::{Deserialize, Serialize};
+
+/// Half the length of the table, from the net to an end line
+pub const HALF_LENGTH: f32 = 1.37;
+/// Half the width of the table
+pub const HALF_WIDTH: f32 = 0.7625;
+/// Height of the table's top above the floor
+pub const TABLE_HEIGHT: f32 = 0.76;
+/// Height of the net above the table's top
+pub const NET_HEIGHT: f32 = 0.1525;
+/// Radius of the ball, scaled up from a real one so it can be followed across the table
+pub const BALL_RADIUS: f32 = 0.03;
+/// Mass of a ball, in kilograms
+pub const BALL_MASS: f32 = 0.0027;
+/// How far behind the end line paddles meet the ball
+pub const PADDLE_GAP: f32 = 0.3;
+/// How high above the table's top paddles meet the ball
+pub const CONTACT_HEIGHT: f32 = 0.25;
+
+/// Thickness of the table's top
+const TOP_THICKNESS: f32 = 0.03;
+/// Width of the white lines painted around the table's edge and down its middle
+const LINE_WIDTH: f32 = 0.02;
+/// How far the net overhangs each side of the table
+const NET_OVERHANG: f32 = 0.15;
+/// Half the size of the floor around the table
+const FLOOR_HALF_SIZE: f32 = 8.0;
+/// Radius of a paddle's blade
+const BLADE_RADIUS: f32 = 0.085;
+
+/// One end of the table
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The end nearest the camera
+    Near,
+    /// The end across the net
+    Far,
+}
+
+impl Side {
+    /// The end across the net from this one
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Near => Self::Far,
+            Self::Far => Self::Near,
+        }
+    }
+
+    /// Which way along z this end lies from the net, positive for the near end
+    pub fn sign(self) -> f32 {
+        match self {
+            Self::Near => 1.0,
+            Self::Far => -1.0,
+        }
+    }
+
+    /// Which end of the table a spot is on
+    pub fn of(position: Vec3) -> Self {
+        if position.z >= 0.0 {
+            Self::Near
+        } else {
+            Self::Far
+        }
+    }
+
+    /// Index of the end, near first, for per-side arrays
+    pub fn index(self) -> usize {
+        match self {
+            Self::Near => 0,
+            Self::Far => 1,
+        }
+    }
+
+    /// Where a paddle on this end waits for the ball, behind the middle of the end line
+    pub fn home(self) -> Vec3 {
+        Vec3::new(
+            0.0,
+            TABLE_HEIGHT + CONTACT_HEIGHT,
+            (HALF_LENGTH + PADDLE_GAP) * self.sign(),
+        )
+    }
+
+    /// Which way a paddle on this end faces with the controller held level, toward the net
+    pub fn facing(self) -> Quat {
+        match self {
+            Self::Near => Quat::IDENTITY,
+            Self::Far => Quat::from_rotation_y(PI),
+        }
+    }
+}
+
+/// Marks the ball
+#[derive(Component, Debug, Default)]
+pub struct Ball;
+
+/// Marks the table's top, which the ball has to bounce on
+#[derive(Component, Debug)]
+pub struct TableTop;
+
+/// Marks the floor, where any bounce ends the point
+#[derive(Component, Debug)]
+pub struct Floor;
+
+/// A paddle at one end of the table
+#[derive(Component, Debug)]
+pub struct Paddle {
+    /// The end it plays from
+    pub side: Side,
+}
+
+/// Plugin that lays out the table, the ball and the paddles
+pub struct TablePlugin;
+
+impl Plugin for TablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_table);
+    }
+}
+
+/// Spawns the floor, table, net, ball, paddles, camera and light
+fn setup_table(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(FLOOR_HALF_SIZE * 2.0, FLOOR_HALF_SIZE * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.55, 0.2, 0.15))),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(FLOOR_HALF_SIZE, 0.1, FLOOR_HALF_SIZE),
+        Restitution::coefficient(0.6),
+        Floor,
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            HALF_WIDTH * 2.0,
+            TOP_THICKNESS,
+            HALF_LENGTH * 2.0,
+        ))),
+        MeshMaterial3d(materials.add(Color::srgb(0.1, 0.3, 0.55))),
+        Transform::from_xyz(0.0, TABLE_HEIGHT - TOP_THICKNESS / 2.0, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(HALF_WIDTH, TOP_THICKNESS / 2.0, HALF_LENGTH),
+        Restitution::coefficient(0.9),
+        Friction::coefficient(0.3),
+        TableTop,
+        Name::new("Table"),
+    ));
+    let legs = meshes.add(Cuboid::new(0.06, TABLE_HEIGHT - TOP_THICKNESS, 0.06));
+    let steel = materials.add(Color::srgb(0.2, 0.2, 0.22));
+    for (x, z) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+        commands.spawn((
+            Mesh3d(legs.clone()),
+            MeshMaterial3d(steel.clone()),
+            Transform::from_xyz(
+                (HALF_WIDTH - 0.1) * x,
+                (TABLE_HEIGHT - TOP_THICKNESS) / 2.0,
+                (HALF_LENGTH - 0.2) * z,
+            ),
+        ));
+    }
+
+    // End lines, side lines and the centre line
+    let line = materials.add(Color::WHITE);
+    let mut paint = |size: Vec2, at: Vec2| {
+        commands.spawn((
+            Mesh3d(meshes.add(Plane3d::default().mesh().size(size.x, size.y))),
+            MeshMaterial3d(line.clone()),
+            Transform::from_xyz(at.x, TABLE_HEIGHT + 0.001, at.y),
+        ));
+    };
+    for sign in [-1.0, 1.0] {
+        paint(
+            Vec2::new(HALF_WIDTH * 2.0, LINE_WIDTH),
+            Vec2::new(0.0, (HALF_LENGTH - LINE_WIDTH / 2.0) * sign),
+        );
+        paint(
+            Vec2::new(LINE_WIDTH, HALF_LENGTH * 2.0),
+            Vec2::new((HALF_WIDTH - LINE_WIDTH / 2.0) * sign, 0.0),
+        );
+    }
+    paint(Vec2::new(LINE_WIDTH / 2.0, HALF_LENGTH * 2.0), Vec2::ZERO);
+
+    let net_half_width = HALF_WIDTH + NET_OVERHANG;
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(net_half_width * 2.0, NET_HEIGHT, 0.01))),
+        MeshMaterial3d(materials.add(Color::srgba(0.95, 0.95, 0.95, 0.6))),
+        Transform::from_xyz(0.0, TABLE_HEIGHT + NET_HEIGHT / 2.0, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(net_half_width, NET_HEIGHT / 2.0, 0.005),
+        Restitution::coefficient(0.1),
+        Name::new("Net"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(1.0, 0.6, 0.1))),
+        Transform::from_translation(Side::Near.home()),
+        RigidBody::KinematicPositionBased,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Mass(BALL_MASS),
+        Restitution::coefficient(0.9),
+        Ccd::enabled(),
+        Velocity::zero(),
+        ExternalForce::default(),
+        ActiveEvents::COLLISION_EVENTS,
+        Ball,
+    ));
+
+    // The blade is a flat disc, turned to face the net with the controller held level
+    let blade = meshes.add(
+        Cylinder::new(BLADE_RADIUS, 0.01)
+            .mesh()
+            .build()
+            .rotated_by(Quat::from_rotation_x(FRAC_PI_2)),
+    );
+    for (side, color) in [
+        (Side::Near, Color::srgb(0.85, 0.15, 0.15)),
+        (Side::Far, Color::srgb(0.15, 0.15, 0.15)),
+    ] {
+        commands.spawn((
+            Mesh3d(blade.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(side.home()).with_rotation(side.facing()),
+            Paddle { side },
+        ));
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, TABLE_HEIGHT + 1.1, HALF_LENGTH + 1.9)
+            .looking_at(Vec3::new(0.0, TABLE_HEIGHT, -0.4), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(1.5, 6.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
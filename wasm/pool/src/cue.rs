@@ -0,0 +1,163 @@
+//! The cue: yaw swings it round the cue ball to aim, and drawing it back then driving it forward
+//! with a pitch stroke strikes as hard as the stroke came through
+
+use bevy::prelude::*;
+use spjorts_core::settings::GameSettings;
+
+use crate::{
+    phase::PoolPhase,
+    table::{PoolBall, BALL_RADIUS},
+};
+
+/// How far the controller has to be tipped up before the cue counts as drawn back, in radians
+const DRAW_BACK: f32 = 0.35;
+/// Shortest a stroke is timed over, so a jumpy reading can't strike at full power
+const MIN_STROKE_SECS: f32 = 0.05;
+/// Slowest a forward stroke can come through and still strike, in radians per second. Anything
+/// gentler is the cue being lowered, not played
+const MIN_STROKE_RATE: f32 = 0.8;
+/// How far back from the cue ball the tip rests while aiming
+const TIP_GAP: f32 = 0.02;
+/// How far back the tip goes with the cue drawn all the way
+const MAX_DRAW: f32 = 0.25;
+/// Length of the cue
+const CUE_LENGTH: f32 = 1.45;
+/// How steeply the cue is tilted up toward its butt, in radians
+const CUE_TILT: f32 = 0.08;
+/// Length of the aim line drawn from the cue ball
+const AIM_LINE_LENGTH: f32 = 1.5;
+
+/// Watches the controller's pitch for a stroke: tipped up to draw the cue back, then driven
+/// forward through level
+#[derive(Debug, Default)]
+pub struct CueStroke {
+    /// Highest the controller was tipped while drawn back, and when, once it's been drawn back
+    drawn: Option<(f32, f32)>,
+}
+
+impl CueStroke {
+    /// Feeds in a pitch reading at a time, returning how fast the stroke came through in radians
+    /// per second when the cue is driven forward through level after being drawn back
+    pub fn update(&mut self, pitch: f32, at: f32) -> Option<f32> {
+        if pitch >= DRAW_BACK {
+            if self.drawn.is_none_or(|(peak, _)| pitch > peak) {
+                self.drawn = Some((pitch, at));
+            }
+            return None;
+        }
+        if pitch > 0.0 {
+            return None;
+        }
+        let (peak, from) = self.drawn.take()?;
+        let rate = (peak - pitch) / (at - from).max(MIN_STROKE_SECS);
+        (rate >= MIN_STROKE_RATE).then_some(rate)
+    }
+
+    /// Forgets any stroke in progress
+    pub fn clear(&mut self) {
+        self.drawn = None;
+    }
+}
+
+/// The cue of the player whose turn it is
+#[derive(Resource, Debug, Default)]
+pub struct Cue {
+    /// Which way the cue points round the cue ball, in radians. Zero shoots up the table toward
+    /// the foot rail
+    pub aim: f32,
+    /// How far the controller is tipped up from level, in radians, drawing the cue back
+    pub pitch: f32,
+    /// Watches for the stroke that strikes
+    pub stroke: CueStroke,
+}
+
+impl Cue {
+    /// Which way the cue ball heads along the cloth when struck
+    pub fn direction(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Vec3::NEG_Z
+    }
+
+    /// How far back the tip is drawn from its resting spot, from 0 to 1
+    fn draw(&self) -> f32 {
+        (self.pitch / DRAW_BACK).clamp(0.0, 1.0)
+    }
+}
+
+/// Marks the cue stick
+#[derive(Component, Debug)]
+struct CueStick;
+
+/// Plugin that adds the cue and its aim line
+pub struct CuePlugin;
+
+impl Plugin for CuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cue>()
+            .add_systems(Startup, setup_cue)
+            .add_systems(
+                Update,
+                (place_cue, draw_aim_line.run_if(in_state(PoolPhase::Aiming))),
+            );
+    }
+}
+
+/// Spawns the cue, hidden until there's a shot to line up
+fn setup_cue(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cylinder::new(0.012, CUE_LENGTH))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.65, 0.4))),
+        Transform::default(),
+        Visibility::Hidden,
+        CueStick,
+        Name::new("Cue"),
+    ));
+}
+
+/// Lays the cue behind the cue ball along the aim, pulled back as far as it's drawn, while a shot
+/// is being lined up
+fn place_cue(
+    mut stick: Query<'_, '_, (&mut Transform, &mut Visibility), With<CueStick>>,
+    balls: Query<'_, '_, (&Transform, &PoolBall), Without<CueStick>>,
+    cue: Res<'_, Cue>,
+    phase: Res<'_, State<PoolPhase>>,
+) {
+    let Ok((mut transform, mut visibility)) = stick.get_single_mut() else {
+        return;
+    };
+    let cue_ball = balls.iter().find(|(_, ball)| ball.is_cue());
+    let (Some((ball, _)), PoolPhase::Aiming) = (cue_ball, phase.get()) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let back = -cue.direction();
+    let along = (back * CUE_TILT.cos() + Vec3::Y * CUE_TILT.sin()).normalize();
+    let tip = ball.translation + back * (BALL_RADIUS + TIP_GAP + cue.draw() * MAX_DRAW);
+    *transform = Transform::from_translation(tip + along * CUE_LENGTH / 2.0)
+        .with_rotation(Quat::from_rotation_arc(Vec3::Y, along));
+    *visibility = Visibility::Visible;
+}
+
+/// Draws a line from the cue ball the way the shot is aimed
+fn draw_aim_line(
+    mut gizmos: Gizmos<'_, '_>,
+    balls: Query<'_, '_, (&Transform, &PoolBall)>,
+    cue: Res<'_, Cue>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    for (ball, _) in balls.iter().filter(|(_, ball)| ball.is_cue()) {
+        let start = ball.translation;
+        gizmos.line(
+            start,
+            start + cue.direction() * AIM_LINE_LENGTH,
+            Color::srgba(1.0, 1.0, 1.0, 0.6),
+        );
+    }
+}
@@ -0,0 +1,384 @@
+//! Bevy 8-ball pool game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{CollisionEvent, Velocity},
+};
+use cue::{Cue, CuePlugin};
+use phase::{PoolPhase, PoolPhasePlugin};
+use rules::{Frame, RulesPlugin, ShotRecord, Verdict, CUE};
+use spjorts_core::{
+    communication::{GameEvent, JsMessage},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader, FeedbackSender,
+};
+use table::{off_table, BallKit, Pocket, PoolBall, TablePlugin, BALL_RADIUS, HEAD_SPOT};
+
+pub mod cue;
+pub mod phase;
+pub mod rules;
+pub mod table;
+
+/// Cue ball speed each radian per second of stroke is worth, in meters per second
+const SPEED_SCALE: f32 = 1.2;
+/// Softest a stroke strikes the cue ball, in meters per second
+pub const MIN_SPEED: f32 = 0.3;
+/// Hardest the cue ball can be struck, in meters per second
+pub const MAX_SPEED: f32 = 7.0;
+/// Speed below which a ball counts as stopped, in meters per second
+const REST_SPEED: f32 = 0.02;
+/// How long every ball has to stay stopped before the shot is over, in seconds
+const REST_SECS: f32 = 0.4;
+/// Longest a shot can go on before it's called over, in case a ball never quite settles
+const MAX_ROLL_SECS: f32 = 20.0;
+/// How far behind the cue ball the camera sits while aiming
+const CAMERA_BACK: f32 = 0.9;
+/// How far above the cue ball the camera sits while aiming
+const CAMERA_UP: f32 = 0.45;
+/// How far past the cue ball the camera looks while aiming
+const CAMERA_AHEAD: f32 = 0.5;
+/// Where the camera watches the whole table from while the balls roll
+const OVERVIEW: Vec3 = Vec3::new(0.0, 2.8, 1.6);
+/// How quickly the camera moves to where it wants to be, higher is snappier
+const CAMERA_SMOOTHING: f32 = 3.0;
+/// Rumble strength for a strike at full power, out of 255
+const MAX_RUMBLE: f32 = 200.0;
+/// How long the controller rumbles when the cue strikes, in milliseconds
+const RUMBLE_MILLIS: u16 = 80;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(PoolPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(TablePlugin)
+    .add_plugins(CuePlugin)
+    .add_plugins(RulesPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.12, 0.1, 0.1)))
+    .init_resource::<Settling>()
+    .add_event::<Strike>()
+    .add_event::<NewGame>()
+    .add_systems(Startup, setup_camera)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            strike_cue_ball.run_if(in_state(PoolPhase::Aiming)),
+            (watch_table, settle).run_if(in_state(PoolPhase::Rolling)),
+            start_new_game,
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, follow_play);
+});
+
+/// The cue connected with the cue ball
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Strike {
+    /// How fast the cue ball leaves the tip, in meters per second
+    pub speed: f32,
+}
+
+/// Asks for the balls to be racked again for a fresh frame, with the first player to break
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// How long the balls have been rolling, and resting, since the last shot
+#[derive(Resource, Debug, Default)]
+struct Settling {
+    /// Seconds since the shot was struck
+    rolling_for: f32,
+    /// Seconds every ball has been stopped for
+    stopped_for: f32,
+}
+
+/// Marks the camera watching the table
+#[derive(Component)]
+struct TableCamera;
+
+/// Spawns the camera looking down the table from the head rail
+fn setup_camera(mut commands: Commands<'_, '_>) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(OVERVIEW).looking_at(Vec3::ZERO, Vec3::Y),
+        TableCamera,
+    ));
+}
+
+/// Everything input handling changes besides the cue itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Strokes that strike the cue ball
+    strikes: EventWriter<'w, Strike>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw swings the cue round the cue ball, tipping the controller up draws
+/// it back and driving it forward through level strikes as hard as the stroke came through, and A
+/// racks a new frame once one is won
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut cue: ResMut<'_, Cue>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<PoolPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == PoolPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == PoolPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation = settings.apply_rotation(orientation);
+                cue.aim = orientation.yaw;
+                cue.pitch = orientation.pitch;
+                if let Some(rate) = cue.stroke.update(orientation.pitch, time.elapsed_secs()) {
+                    effects.strikes.send(Strike {
+                        speed: (rate * SPEED_SCALE).clamp(MIN_SPEED, MAX_SPEED),
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Sends the cue ball off the way the cue is aimed, buzzing the controller as hard as it was
+/// struck, and starts watching the table
+fn strike_cue_ball(
+    mut strikes: EventReader<'_, '_, Strike>,
+    mut balls: Query<'_, '_, (&PoolBall, &mut Velocity)>,
+    mut cue: ResMut<'_, Cue>,
+    mut record: ResMut<'_, ShotRecord>,
+    mut settling: ResMut<'_, Settling>,
+    mut next_phase: ResMut<'_, NextState<PoolPhase>>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let Some(strike) = strikes.read().last().copied() else {
+        return;
+    };
+    let Some((_, mut velocity)) = balls.iter_mut().find(|(ball, _)| ball.is_cue()) else {
+        return;
+    };
+
+    let speed = strike.speed.clamp(0.0, MAX_SPEED);
+    velocity.linvel = cue.direction() * speed;
+    velocity.angvel = Vec3::ZERO;
+    cue.stroke.clear();
+    *record = ShotRecord::default();
+    *settling = Settling::default();
+    feedback.send(GameEvent::Rumble {
+        intensity: (speed / MAX_SPEED * MAX_RUMBLE) as u8,
+        millis: RUMBLE_MILLIS,
+    });
+    next_phase.set(PoolPhase::Rolling);
+}
+
+/// Notes the first ball the cue ball touches and takes balls off the table as they drop into the
+/// pockets
+fn watch_table(
+    mut commands: Commands<'_, '_>,
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut record: ResMut<'_, ShotRecord>,
+    balls: Query<'_, '_, &PoolBall>,
+    pockets: Query<'_, '_, (), With<Pocket>>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        for (entity, other) in [(first, second), (second, first)] {
+            let Ok(ball) = balls.get(entity) else {
+                continue;
+            };
+            if pockets.contains(other) && !record.potted.contains(&ball.number) {
+                record.pot(ball.number);
+                commands.entity(entity).despawn_recursive();
+            } else if let (true, Ok(hit)) = (ball.is_cue(), balls.get(other)) {
+                record.hit(hit.number);
+            }
+        }
+    }
+}
+
+/// Everything the end of a shot changes
+#[derive(SystemParam)]
+struct ShotOutcome<'w, 's> {
+    /// Commands to put the cue ball back with after a scratch
+    commands: Commands<'w, 's>,
+    /// Meshes and materials to put the cue ball back with
+    kit: Res<'w, BallKit>,
+    /// The frame the shot is judged against
+    frame: ResMut<'w, Frame>,
+    /// Whose turn it is
+    turns: ResMut<'w, TurnManager>,
+    /// Messages shown to players
+    banner: ResMut<'w, Banner>,
+    /// Phase to move on to
+    next_phase: ResMut<'w, NextState<PoolPhase>>,
+}
+
+impl ShotOutcome<'_, '_> {
+    /// Judges a shot once the balls have stopped: the shooter goes again after a clean pot of
+    /// their own, otherwise the turn passes, or the frame ends if the 8-ball went down. A
+    /// scratched cue ball goes back behind the head string, clear of the balls at `others`
+    fn finish(&mut self, record: &ShotRecord, others: &[Vec3]) {
+        let player = self.turns.current();
+        let side = Frame::side(player);
+        let had_group = self.frame.group(side).is_some();
+
+        match self.frame.judge(side, record) {
+            Verdict::Continue => {
+                if let (false, Some(group)) = (had_group, self.frame.group(side)) {
+                    self.banner
+                        .show(format!("Player {} is on {}", player + 1, group.name()));
+                }
+                self.next_phase.set(PoolPhase::Aiming);
+            }
+            Verdict::Pass(foul) => {
+                if let Some(foul) = foul {
+                    self.banner.show(foul.describe());
+                }
+                self.turns.advance();
+                self.next_phase.set(PoolPhase::Aiming);
+            }
+            Verdict::Won => {
+                self.banner.show(format!("Player {} wins!", player + 1));
+                self.next_phase.set(PoolPhase::GameOver);
+                return;
+            }
+            Verdict::Lost => {
+                self.banner
+                    .show(format!("Player {} loses on the 8-ball!", player + 1));
+                self.next_phase.set(PoolPhase::GameOver);
+                return;
+            }
+        }
+
+        if record.scratched() {
+            self.kit.spawn(&mut self.commands, CUE, free_spot(others));
+        }
+    }
+}
+
+/// The spot on the head string nearest the head spot that no ball is sitting on
+fn free_spot(others: &[Vec3]) -> Vec3 {
+    (0..20)
+        .map(|step| {
+            let side = if step % 2 == 0 { 1.0 } else { -1.0 };
+            HEAD_SPOT + Vec3::X * side * (step / 2) as f32 * BALL_RADIUS * 2.5
+        })
+        .find(|spot| {
+            others
+                .iter()
+                .all(|other| other.distance(*spot) > BALL_RADIUS * 2.5)
+        })
+        .unwrap_or(HEAD_SPOT)
+}
+
+/// Takes balls that jumped the table off it, and judges the shot once every ball has come to rest
+fn settle(
+    balls: Query<'_, '_, (Entity, &Transform, &Velocity, &PoolBall)>,
+    mut record: ResMut<'_, ShotRecord>,
+    mut settling: ResMut<'_, Settling>,
+    mut outcome: ShotOutcome<'_, '_>,
+    time: Res<'_, Time>,
+) {
+    let mut fastest = 0.0f32;
+    let mut resting = Vec::new();
+    for (entity, transform, velocity, ball) in &balls {
+        if off_table(transform.translation) {
+            record.pot(ball.number);
+            outcome.commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if !record.potted.contains(&ball.number) {
+            fastest = fastest.max(velocity.linvel.length());
+            resting.push(transform.translation);
+        }
+    }
+
+    settling.rolling_for += time.delta_secs();
+    settling.stopped_for = if fastest < REST_SPEED {
+        settling.stopped_for + time.delta_secs()
+    } else {
+        0.0
+    };
+    if settling.stopped_for >= REST_SECS || settling.rolling_for >= MAX_ROLL_SECS {
+        *settling = Settling::default();
+        outcome.finish(&record, &resting);
+    }
+}
+
+/// Racks the balls for a fresh frame with the first player to break
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut commands: Commands<'_, '_>,
+    balls: Query<'_, '_, Entity, With<PoolBall>>,
+    kit: Res<'_, BallKit>,
+    mut frame: ResMut<'_, Frame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<PoolPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for ball in &balls {
+        commands.entity(ball).despawn_recursive();
+    }
+    kit.rack(&mut commands);
+    *frame = Frame::default();
+    turns.restart();
+    next_phase.set(PoolPhase::Aiming);
+}
+
+/// Sits the camera behind the cue ball looking along the cue while a shot is lined up, and pulls
+/// back over the table to watch the balls roll
+fn follow_play(
+    mut camera: Query<'_, '_, &mut Transform, (With<TableCamera>, Without<PoolBall>)>,
+    balls: Query<'_, '_, (&Transform, &PoolBall)>,
+    cue: Res<'_, Cue>,
+    phase: Res<'_, State<PoolPhase>>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let cue_ball = balls.iter().find(|(_, ball)| ball.is_cue());
+    let wanted = match (phase.get(), cue_ball) {
+        (PoolPhase::Aiming, Some((ball, _))) => {
+            let at = ball.translation;
+            let direction = cue.direction();
+            Transform::from_translation(at - direction * CAMERA_BACK + Vec3::Y * CAMERA_UP)
+                .looking_at(at + direction * CAMERA_AHEAD, Vec3::Y)
+        }
+        _ => Transform::from_translation(OVERVIEW).looking_at(Vec3::ZERO, Vec3::Y),
+    };
+    let blend = 1.0 - (-CAMERA_SMOOTHING * time.delta_secs()).exp();
+
+    camera.translation = camera.translation.lerp(wanted.translation, blend);
+    camera.rotation = camera.rotation.slerp(wanted.rotation, blend);
+}
@@ -0,0 +1,25 @@
+//! Phases a game of pool moves through, from lining up a shot to the frame being won
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the frame is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PoolPhase {
+    /// The player whose turn it is is lining up and striking the cue ball
+    #[default]
+    Aiming,
+    /// Balls are rolling after a shot
+    Rolling,
+    /// The 8-ball has gone down and the frame is decided
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct PoolPhasePlugin;
+
+impl Plugin for PoolPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<PoolPhase>();
+    }
+}
@@ -0,0 +1,365 @@
+//! 8-ball rules: who is on solids and who is on stripes, which shots are fouls and who wins the
+//! frame, shown on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::phase::PoolPhase;
+
+/// Number on the cue ball
+pub const CUE: u8 = 0;
+/// Number on the 8-ball, which has to go down last
+pub const EIGHT: u8 = 8;
+/// Points the winner of the frame gets on top of the balls they potted
+const WIN_POINTS: u32 = 8;
+
+/// The set of object balls a side is shooting for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    /// Balls 1 to 7
+    Solids,
+    /// Balls 9 to 15
+    Stripes,
+}
+
+impl Group {
+    /// Which group a ball belongs to, or `None` for the cue ball and the 8-ball
+    pub fn of(number: u8) -> Option<Self> {
+        match number {
+            1..=7 => Some(Self::Solids),
+            9..=15 => Some(Self::Stripes),
+            _ => None,
+        }
+    }
+
+    /// The other group
+    pub fn other(self) -> Self {
+        match self {
+            Self::Solids => Self::Stripes,
+            Self::Stripes => Self::Solids,
+        }
+    }
+
+    /// Name shown to players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Solids => "Solids",
+            Self::Stripes => "Stripes",
+        }
+    }
+}
+
+/// What happened on the table during a shot
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShotRecord {
+    /// The first ball the cue ball touched, if it touched any
+    pub first_hit: Option<u8>,
+    /// Every ball that went down, the cue ball included, in the order they dropped
+    pub potted: Vec<u8>,
+}
+
+impl ShotRecord {
+    /// Notes the cue ball touching a ball, if it's the first it touched this shot
+    pub fn hit(&mut self, number: u8) {
+        self.first_hit.get_or_insert(number);
+    }
+
+    /// Notes a ball going down
+    pub fn pot(&mut self, number: u8) {
+        if !self.potted.contains(&number) {
+            self.potted.push(number);
+        }
+    }
+
+    /// Whether the cue ball went down
+    pub fn scratched(&self) -> bool {
+        self.potted.contains(&CUE)
+    }
+}
+
+/// Why a shot was a foul
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Foul {
+    /// The cue ball went down
+    Scratch,
+    /// The cue ball didn't touch another ball
+    NoContact,
+    /// The cue ball touched a ball outside the shooter's group first
+    WrongBallFirst,
+}
+
+impl Foul {
+    /// What the banner says about the foul
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Scratch => "Foul: scratch!",
+            Self::NoContact => "Foul: no ball hit!",
+            Self::WrongBallFirst => "Foul: wrong ball first!",
+        }
+    }
+}
+
+/// How a shot turned out for the side that played it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// A ball of theirs went down cleanly, so they shoot again
+    Continue,
+    /// The turn passes to the other side, with the foul that gave it away if there was one
+    Pass(Option<Foul>),
+    /// They potted the 8-ball legally and won the frame
+    Won,
+    /// They potted the 8-ball early or fouled while potting it and lost the frame
+    Lost,
+}
+
+/// The frame being played: which balls are left and who is shooting for which
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Group each side is shooting for, or `None` while the table is open
+    groups: Option<[Group; 2]>,
+    /// Object balls still on the table
+    on_table: Vec<u8>,
+    /// Whether the next shot is the break
+    breaking: bool,
+    /// Side that won the frame, once it's over
+    winner: Option<usize>,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            groups: None,
+            on_table: (1..=15).collect(),
+            breaking: true,
+            winner: None,
+        }
+    }
+}
+
+impl Frame {
+    /// Which side a player shoots for, players alternating between the two
+    pub fn side(player: usize) -> usize {
+        player % 2
+    }
+
+    /// The group a side is shooting for, or `None` while the table is open
+    pub fn group(&self, side: usize) -> Option<Group> {
+        self.groups.map(|groups| groups[side % 2])
+    }
+
+    /// Whether the next shot is the break
+    pub fn breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// How many balls of a group are still on the table
+    pub fn remaining(&self, group: Group) -> usize {
+        self.on_table
+            .iter()
+            .filter(|number| Group::of(**number) == Some(group))
+            .count()
+    }
+
+    /// Whether a side has potted all of its group and is on the 8-ball
+    pub fn on_the_eight(&self, side: usize) -> bool {
+        self.group(side)
+            .is_some_and(|group| self.remaining(group) == 0)
+    }
+
+    /// Whether a side may legally hit a ball first
+    fn may_hit_first(&self, side: usize, number: u8) -> bool {
+        if self.on_the_eight(side) {
+            return number == EIGHT;
+        }
+        match self.group(side) {
+            Some(group) => Group::of(number) == Some(group),
+            None => number != EIGHT,
+        }
+    }
+
+    /// Takes the balls that went down off the table and decides how a side's shot turned out,
+    /// handing out groups the first time someone pots a ball after the break
+    pub fn judge(&mut self, side: usize, shot: &ShotRecord) -> Verdict {
+        let breaking = std::mem::take(&mut self.breaking);
+        let on_the_eight = self.on_the_eight(side);
+        let foul = if shot.scratched() {
+            Some(Foul::Scratch)
+        } else {
+            match shot.first_hit {
+                None => Some(Foul::NoContact),
+                Some(number) if !breaking && !self.may_hit_first(side, number) => {
+                    Some(Foul::WrongBallFirst)
+                }
+                Some(_) => None,
+            }
+        };
+        self.on_table.retain(|number| !shot.potted.contains(number));
+
+        if shot.potted.contains(&EIGHT) {
+            // The 8-ball on the break wins outright unless the cue ball followed it down
+            let won = foul.is_none() && (breaking || on_the_eight);
+            self.winner = Some(if won { side % 2 } else { (side + 1) % 2 });
+            return if won { Verdict::Won } else { Verdict::Lost };
+        }
+        if foul.is_some() {
+            return Verdict::Pass(foul);
+        }
+
+        if self.groups.is_none() && !breaking {
+            if let Some(group) = shot.potted.iter().find_map(|number| Group::of(*number)) {
+                let mut groups = [group; 2];
+                groups[(side + 1) % 2] = group.other();
+                self.groups = Some(groups);
+            }
+        }
+        let potted_own = shot.potted.iter().any(|number| match self.group(side) {
+            Some(group) => Group::of(*number) == Some(group),
+            None => Group::of(*number).is_some(),
+        });
+        if potted_own {
+            Verdict::Continue
+        } else {
+            Verdict::Pass(None)
+        }
+    }
+
+    /// Points a side finished the frame with: one for every ball of their group they potted, and
+    /// more for winning. Higher is better, like the scores the server ranks
+    pub fn points(&self, side: usize) -> u32 {
+        let potted = self
+            .group(side)
+            .map_or(0, |group| 7 - self.remaining(group) as u32);
+        let won = if self.winner == Some(side % 2) {
+            WIN_POINTS
+        } else {
+            0
+        };
+        potted + won
+    }
+
+    /// How a side's frame is going, like `Solids, 3 left`
+    fn status(&self, side: usize) -> String {
+        match self.group(side) {
+            _ if self.winner == Some(side % 2) => "won the frame".to_string(),
+            Some(_) if self.on_the_eight(side) => "on the 8-ball".to_string(),
+            Some(group) => format!("{}, {} left", group.name(), self.remaining(group)),
+            None => "open table".to_string(),
+        }
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct PoolSnapshot<'a> {
+    /// Player whose turn it is
+    player: usize,
+    /// The frame so far
+    frame: &'a Frame,
+    /// Where the game is at
+    phase: PoolPhase,
+}
+
+/// Plugin that keeps the frame, shows it and reports the final result
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Frame>()
+            .init_resource::<ShotRecord>()
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(PoolPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(PoolPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Fills in the scorecard HUD with every player's group and who is at the table
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    frame: Res<'_, Frame>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..turns.players())
+        .map(|player| {
+            format!(
+                "Player {}: {}",
+                player + 1,
+                frame.status(Frame::side(player))
+            )
+        })
+        .collect();
+    let shot = if frame.breaking() { "break" } else { "shoot" };
+
+    hud.set_if_neq(ScorecardHud {
+        title: "8-Ball".to_string(),
+        rows,
+        footer: format!("Player {} to {shot}", turns.current() + 1),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Shows who won the frame and every player's points once it's over
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    frame: Res<'_, Frame>,
+    turns: Res<'_, TurnManager>,
+) {
+    let mut lines = vec!["Frame Over".to_string()];
+    for player in 0..turns.players() {
+        let side = Frame::side(player);
+        lines.push(format!(
+            "Player {}: {}, {} points",
+            player + 1,
+            frame.status(side),
+            frame.points(side)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final card when a new frame starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends each player's points back to the page once the frame is over, so it can submit them to
+/// the server
+fn submit_result(
+    frame: Res<'_, Frame>,
+    turns: Res<'_, TurnManager>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = (0..turns.players())
+        .map(|player| frame.points(Frame::side(player)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    frame: Res<'_, Frame>,
+    phase: Res<'_, State<PoolPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&PoolSnapshot {
+        player: turns.current(),
+        frame: &frame,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,329 @@
+//! The pool table: a cloth bed boxed in by cushions with six pockets cut into the rails, and the
+//! fifteen object balls racked in a triangle with the cue ball behind the head string
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    ActiveEvents, Ccd, Collider, Damping, Friction, Restitution, RigidBody, Sensor, Velocity,
+};
+
+use crate::rules::{Group, CUE, EIGHT};
+
+/// Radius of every ball
+pub const BALL_RADIUS: f32 = 0.0286;
+/// Half the width of the playing surface, across the table
+pub const HALF_WIDTH: f32 = 0.635;
+/// Half the length of the playing surface, from the head rail to the foot rail
+pub const HALF_LENGTH: f32 = 1.27;
+/// Where the cue ball is placed for the break and after a scratch, a quarter of the way down
+/// from the head rail
+pub const HEAD_SPOT: Vec3 = Vec3::new(0.0, BALL_RADIUS, HALF_LENGTH / 2.0);
+/// Where the apex ball of the rack sits, a quarter of the way up from the foot rail
+const FOOT_SPOT: Vec3 = Vec3::new(0.0, BALL_RADIUS, -HALF_LENGTH / 2.0);
+/// Damping on a rolling ball, slowing it like the cloth does
+pub const CLOTH_DAMPING: f32 = 0.4;
+/// Radius of each pocket's mouth. A ball whose centre gets this close to a pocket drops in
+pub const POCKET_RADIUS: f32 = 0.07;
+/// How far the cushions stop short of each corner, leaving the corner pocket's mouth open
+const CORNER_GAP: f32 = 0.11;
+/// Half the width of the gap the cushions leave for each side pocket
+const SIDE_GAP: f32 = 0.07;
+/// How far a side pocket sits out past the cushion line
+const SIDE_POCKET_SETBACK: f32 = 0.02;
+/// How tall the cushions stand above the cloth, a little above the middle of a ball
+const CUSHION_HEIGHT: f32 = 0.04;
+/// How deep the cushions are, from the cloth's edge to the rail
+const CUSHION_DEPTH: f32 = 0.05;
+/// Width of the wooden rails around the cushions
+const RAIL_WIDTH: f32 = 0.12;
+/// Height of the table's top above the floor
+const TABLE_HEIGHT: f32 = 0.76;
+/// Gap left between racked balls so they don't start out pressed together
+const RACK_GAP: f32 = 0.0005;
+/// Order the balls are racked in, row by row from the apex: the 8-ball in the middle of the
+/// third row and a solid and a stripe in the back corners
+const RACK: [u8; 15] = [1, 9, 2, 10, 8, 3, 11, 4, 12, 5, 6, 14, 7, 15, 13];
+
+/// A ball on the table, with the number painted on it
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolBall {
+    /// 0 for the cue ball, otherwise the object ball's number
+    pub number: u8,
+}
+
+impl PoolBall {
+    /// Whether this is the cue ball
+    pub fn is_cue(&self) -> bool {
+        self.number == CUE
+    }
+}
+
+/// One of the six pockets
+#[derive(Component, Debug)]
+pub struct Pocket;
+
+/// Where the middle of each pocket sits on the cloth: the four corners and the middle of each
+/// long rail
+pub fn pockets() -> [Vec3; 6] {
+    let side = HALF_WIDTH + SIDE_POCKET_SETBACK;
+    [
+        Vec3::new(-HALF_WIDTH, 0.0, -HALF_LENGTH),
+        Vec3::new(HALF_WIDTH, 0.0, -HALF_LENGTH),
+        Vec3::new(-side, 0.0, 0.0),
+        Vec3::new(side, 0.0, 0.0),
+        Vec3::new(-HALF_WIDTH, 0.0, HALF_LENGTH),
+        Vec3::new(HALF_WIDTH, 0.0, HALF_LENGTH),
+    ]
+}
+
+/// Whether a ball has jumped off the table
+pub fn off_table(position: Vec3) -> bool {
+    position.y < -BALL_RADIUS * 2.0
+        || position.x.abs() > HALF_WIDTH + CUSHION_DEPTH + RAIL_WIDTH
+        || position.z.abs() > HALF_LENGTH + CUSHION_DEPTH + RAIL_WIDTH
+}
+
+/// Colour painted on a ball: the same seven colours for the solids and the stripes, black for
+/// the 8-ball and white for the cue ball
+fn ball_colour(number: u8) -> Color {
+    match number {
+        CUE => Color::srgb(0.95, 0.95, 0.9),
+        EIGHT => Color::srgb(0.05, 0.05, 0.05),
+        number => match number % 8 {
+            1 => Color::srgb(0.95, 0.8, 0.1),
+            2 => Color::srgb(0.1, 0.25, 0.8),
+            3 => Color::srgb(0.85, 0.1, 0.1),
+            4 => Color::srgb(0.45, 0.15, 0.6),
+            5 => Color::srgb(0.95, 0.45, 0.1),
+            6 => Color::srgb(0.1, 0.55, 0.25),
+            _ => Color::srgb(0.5, 0.1, 0.1),
+        },
+    }
+}
+
+/// Meshes and materials every ball is made from
+#[derive(Resource, Debug)]
+pub struct BallKit {
+    /// The ball itself
+    sphere: Handle<Mesh>,
+    /// The coloured band round a stripe
+    band: Handle<Mesh>,
+    /// Material for each ball, by number
+    colours: Vec<Handle<StandardMaterial>>,
+}
+
+impl BallKit {
+    /// Spawns a ball at rest at `at`. Stripes are white with a band of their colour
+    pub fn spawn(&self, commands: &mut Commands<'_, '_>, number: u8, at: Vec3) {
+        let striped = Group::of(number) == Some(Group::Stripes);
+        let colour = if striped { CUE } else { number };
+        let mut ball = commands.spawn((
+            Mesh3d(self.sphere.clone()),
+            MeshMaterial3d(self.colours[usize::from(colour)].clone()),
+            Transform::from_translation(at),
+            RigidBody::Dynamic,
+            Collider::ball(BALL_RADIUS),
+            Restitution::coefficient(0.95),
+            Friction::coefficient(0.2),
+            Ccd::enabled(),
+            Velocity::zero(),
+            Damping {
+                linear_damping: CLOTH_DAMPING,
+                angular_damping: CLOTH_DAMPING,
+            },
+            PoolBall { number },
+            Name::new(format!("Ball {number}")),
+        ));
+        // Only the cue ball reports what it touches, to find the first ball it hit
+        if number == CUE {
+            ball.insert(ActiveEvents::COLLISION_EVENTS);
+        }
+        if striped {
+            ball.with_children(|ball| {
+                ball.spawn((
+                    Mesh3d(self.band.clone()),
+                    MeshMaterial3d(self.colours[usize::from(number)].clone()),
+                ));
+            });
+        }
+    }
+
+    /// Racks the fifteen object balls in a triangle on the foot spot and puts the cue ball on
+    /// the head spot
+    pub fn rack(&self, commands: &mut Commands<'_, '_>) {
+        let spacing = BALL_RADIUS * 2.0 + RACK_GAP;
+        let mut numbers = RACK.into_iter();
+        for row in 0..5 {
+            for place in 0..=row {
+                let Some(number) = numbers.next() else {
+                    return;
+                };
+                let across = (place as f32 - row as f32 / 2.0) * spacing;
+                let back = row as f32 * spacing * 3f32.sqrt() / 2.0;
+                self.spawn(commands, number, FOOT_SPOT + Vec3::new(across, 0.0, -back));
+            }
+        }
+        self.spawn(commands, CUE, HEAD_SPOT);
+    }
+}
+
+/// Plugin that builds the table and racks the balls
+pub struct TablePlugin;
+
+impl Plugin for TablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_table);
+    }
+}
+
+/// Spawns a fixed block of a size at `at`
+fn block(
+    commands: &mut Commands<'_, '_>,
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardMaterial>,
+    size: Vec3,
+    at: Vec3,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_size(size))),
+        MeshMaterial3d(material),
+        Transform::from_translation(at),
+        RigidBody::Fixed,
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+        Friction::coefficient(0.2),
+        Restitution::coefficient(0.75),
+    ));
+}
+
+/// Spawns the floor, the table with its cushions, rails and pockets, the racked balls and the
+/// lights
+fn setup_table(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, 12.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.35, 0.22, 0.15))),
+        Transform::from_xyz(0.0, -TABLE_HEIGHT, 0.0),
+        Name::new("Floor"),
+    ));
+
+    let cloth = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.05, 0.4, 0.2),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    let wood = materials.add(Color::srgb(0.4, 0.2, 0.1));
+    let outer = Vec2::new(
+        HALF_WIDTH + CUSHION_DEPTH + RAIL_WIDTH,
+        HALF_LENGTH + CUSHION_DEPTH + RAIL_WIDTH,
+    );
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(outer.x * 2.0, 0.1, outer.y * 2.0))),
+        MeshMaterial3d(cloth.clone()),
+        Transform::from_xyz(0.0, -0.05, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(outer.x, 0.05, outer.y),
+        Friction::coefficient(0.2),
+        Restitution::coefficient(0.1),
+        Name::new("Bed"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            outer.x * 1.8,
+            TABLE_HEIGHT - 0.1,
+            outer.y * 1.8,
+        ))),
+        MeshMaterial3d(wood.clone()),
+        Transform::from_xyz(0.0, -TABLE_HEIGHT / 2.0 - 0.05, 0.0),
+        Name::new("Cabinet"),
+    ));
+
+    // Cushions run along each rail between the pockets: one on each short rail, and two on each
+    // long rail either side of the side pocket
+    let long = HALF_LENGTH - CORNER_GAP - SIDE_GAP;
+    let short = HALF_WIDTH - CORNER_GAP;
+    for x in [-1.0, 1.0] {
+        for z in [-1.0, 1.0] {
+            block(
+                &mut commands,
+                &mut meshes,
+                cloth.clone(),
+                Vec3::new(CUSHION_DEPTH, CUSHION_HEIGHT, long),
+                Vec3::new(
+                    x * (HALF_WIDTH + CUSHION_DEPTH / 2.0),
+                    CUSHION_HEIGHT / 2.0,
+                    z * (SIDE_GAP + long / 2.0),
+                ),
+            );
+        }
+        block(
+            &mut commands,
+            &mut meshes,
+            cloth.clone(),
+            Vec3::new(short * 2.0, CUSHION_HEIGHT, CUSHION_DEPTH),
+            Vec3::new(
+                0.0,
+                CUSHION_HEIGHT / 2.0,
+                x * (HALF_LENGTH + CUSHION_DEPTH / 2.0),
+            ),
+        );
+    }
+    for x in [-1.0, 1.0] {
+        block(
+            &mut commands,
+            &mut meshes,
+            wood.clone(),
+            Vec3::new(RAIL_WIDTH, CUSHION_HEIGHT * 1.5, outer.y * 2.0),
+            Vec3::new(x * (outer.x - RAIL_WIDTH / 2.0), CUSHION_HEIGHT * 0.75, 0.0),
+        );
+        block(
+            &mut commands,
+            &mut meshes,
+            wood.clone(),
+            Vec3::new(outer.x * 2.0, CUSHION_HEIGHT * 1.5, RAIL_WIDTH),
+            Vec3::new(0.0, CUSHION_HEIGHT * 0.75, x * (outer.y - RAIL_WIDTH / 2.0)),
+        );
+    }
+
+    // Pockets are sensors sized so a ball touches one once its centre is inside the mouth
+    let hole = materials.add(Color::BLACK);
+    for pocket in pockets() {
+        commands.spawn((
+            Mesh3d(meshes.add(Cylinder::new(POCKET_RADIUS, 0.002))),
+            MeshMaterial3d(hole.clone()),
+            Transform::from_translation(pocket + Vec3::Y * 0.001),
+            Collider::cylinder(BALL_RADIUS, POCKET_RADIUS - BALL_RADIUS),
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            Pocket,
+            Name::new("Pocket"),
+        ));
+    }
+
+    let colours = (0..=15)
+        .map(|number| materials.add(ball_colour(number)))
+        .collect();
+    let kit = BallKit {
+        sphere: meshes.add(Sphere::new(BALL_RADIUS).mesh().uv(24, 16)),
+        band: meshes.add(Cylinder::new(BALL_RADIUS * 1.003, BALL_RADIUS)),
+        colours,
+    };
+    kit.rack(&mut commands);
+    commands.insert_resource(kit);
+
+    commands.spawn((
+        PointLight {
+            intensity: 400_000.0,
+            range: 6.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 1.4, 0.0),
+    ));
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 300.0,
+    });
+}
@@ -0,0 +1,245 @@
+//! The board: a long waxed table with the scoring zones painted at the far end and gutters all
+//! round, plus the pucks slid down it
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    Collider, ColliderMassProperties, Friction, GravityScale, LockedAxes, Restitution, RigidBody,
+    Velocity,
+};
+
+/// Length of the board, from the thrower's end to the far edge
+pub const BOARD_LENGTH: f32 = 6.7;
+/// Half the width of the board between the gutters
+pub const HALF_WIDTH: f32 = 0.255;
+/// Where pucks are let go, just in from the thrower's end
+pub const RELEASE_Z: f32 = BOARD_LENGTH - 0.3;
+/// How far from the far edge the foul line is. Pucks have to get all the way past it to stay in
+/// play
+pub const FOUL_LINE: f32 = 1.83;
+/// How far from the far edge the line into the 1 zone is
+pub const ONE_LINE: f32 = 1.2;
+/// How far from the far edge the line into the 2 zone is
+pub const TWO_LINE: f32 = 0.45;
+/// How far from the far edge the line into the 3 zone is
+pub const THREE_LINE: f32 = 0.15;
+/// Points for a puck hanging over the far edge
+pub const HANGER_POINTS: u32 = 4;
+/// Radius of a puck
+pub const PUCK_RADIUS: f32 = 0.033;
+/// Height of a puck
+const PUCK_HEIGHT: f32 = 0.022;
+/// Mass of a puck, in kilograms
+const PUCK_MASS: f32 = 0.35;
+/// Thickness of the board
+const BOARD_THICKNESS: f32 = 0.08;
+/// Width of the gutters round the board
+const GUTTER_WIDTH: f32 = 0.1;
+/// Width of the painted lines
+const LINE_WIDTH: f32 = 0.01;
+
+/// A puck, sliding or at rest on the board
+#[derive(Component, Debug)]
+pub struct Puck {
+    /// The player who threw it
+    pub owner: usize,
+}
+
+impl Puck {
+    /// Whether a puck at a spot has slid off the far edge or either side into the gutter. A puck
+    /// tips off once its middle passes the edge
+    pub fn off_board(position: Vec3) -> bool {
+        position.z < 0.0 || position.x.abs() > HALF_WIDTH
+    }
+
+    /// Whether a puck at a spot is all the way past the foul line, so it stays in play once it
+    /// stops
+    pub fn past_foul_line(position: Vec3) -> bool {
+        position.z + PUCK_RADIUS < FOUL_LINE
+    }
+
+    /// Whether a puck at a spot hangs over the far edge without falling off
+    pub fn hanging(position: Vec3) -> bool {
+        !Self::off_board(position) && position.z < PUCK_RADIUS
+    }
+
+    /// Points a puck at rest at a spot is worth: the zone it's all the way inside, or more
+    /// still for hanging over the far edge
+    pub fn points(position: Vec3) -> u32 {
+        let front = position.z + PUCK_RADIUS;
+        if Self::off_board(position) {
+            0
+        } else if Self::hanging(position) {
+            HANGER_POINTS
+        } else if front < THREE_LINE {
+            3
+        } else if front < TWO_LINE {
+            2
+        } else if front < ONE_LINE {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// Meshes and materials every puck is built from
+#[derive(Resource)]
+pub struct PuckModel {
+    /// The puck itself
+    body: Handle<Mesh>,
+    /// Each player's colour, cycled through for more players than colours
+    colors: Vec<Handle<StandardMaterial>>,
+}
+
+impl PuckModel {
+    /// Spawns a player's puck at rest on the board
+    pub fn spawn(&self, commands: &mut Commands<'_, '_>, owner: usize, at: Vec3) -> Entity {
+        commands
+            .spawn((
+                Mesh3d(self.body.clone()),
+                MeshMaterial3d(self.colors[owner % self.colors.len()].clone()),
+                Transform::from_translation(at.with_y(PUCK_HEIGHT / 2.0)),
+                RigidBody::Dynamic,
+                Collider::cylinder(PUCK_HEIGHT / 2.0, PUCK_RADIUS),
+                ColliderMassProperties::Mass(PUCK_MASS),
+                Friction::coefficient(0.0),
+                Restitution::coefficient(0.85),
+                GravityScale(0.0),
+                // Pucks only slide and turn on the board, the sliding itself is worked out in
+                // `glide`
+                LockedAxes::ROTATION_LOCKED_X
+                    | LockedAxes::ROTATION_LOCKED_Z
+                    | LockedAxes::TRANSLATION_LOCKED_Y,
+                Velocity::zero(),
+                Puck { owner },
+            ))
+            .id()
+    }
+}
+
+/// Plugin that lays out the board and gets the puck model ready
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_board);
+    }
+}
+
+/// Spawns the board, the scoring zones, the lines, the gutters, the camera and the light, and
+/// builds the puck model
+fn setup_board(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.25, 0.22))),
+        Transform::from_xyz(0.0, -0.9, BOARD_LENGTH / 2.0),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(HALF_WIDTH * 2.0, BOARD_THICKNESS, BOARD_LENGTH))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.85, 0.7, 0.5),
+            perceptual_roughness: 0.2,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, -BOARD_THICKNESS / 2.0, BOARD_LENGTH / 2.0),
+        Name::new("Board"),
+    ));
+
+    // The gutters run down both sides and across the far end, a little below the board
+    let gutter = materials.add(Color::srgb(0.2, 0.12, 0.08));
+    let gutter_y = -BOARD_THICKNESS * 0.75;
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(GUTTER_WIDTH, 0.02, BOARD_LENGTH + GUTTER_WIDTH))),
+            MeshMaterial3d(gutter.clone()),
+            Transform::from_xyz(
+                side * (HALF_WIDTH + GUTTER_WIDTH / 2.0),
+                gutter_y,
+                (BOARD_LENGTH - GUTTER_WIDTH) / 2.0,
+            ),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(
+            (HALF_WIDTH + GUTTER_WIDTH) * 2.0,
+            0.02,
+            GUTTER_WIDTH,
+        ))),
+        MeshMaterial3d(gutter),
+        Transform::from_xyz(0.0, gutter_y, -GUTTER_WIDTH / 2.0),
+    ));
+
+    // Zones are tinted deeper the more they're worth, each painted just above the last
+    let zones = [
+        (ONE_LINE, Color::srgb(0.8, 0.6, 0.4)),
+        (TWO_LINE, Color::srgb(0.75, 0.5, 0.3)),
+        (THREE_LINE, Color::srgb(0.7, 0.4, 0.2)),
+    ];
+    for (layer, (line, color)) in zones.into_iter().enumerate() {
+        commands.spawn((
+            Mesh3d(meshes.add(Plane3d::default().mesh().size(HALF_WIDTH * 2.0, line))),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_xyz(0.0, 0.001 * (layer + 1) as f32, line / 2.0),
+        ));
+    }
+
+    let paint = materials.add(Color::srgb(0.15, 0.1, 0.1));
+    let red = materials.add(Color::srgb(0.8, 0.1, 0.1));
+    let across = meshes.add(Plane3d::default().mesh().size(HALF_WIDTH * 2.0, LINE_WIDTH));
+    for (z, material) in [
+        (FOUL_LINE, red.clone()),
+        (RELEASE_Z, red),
+        (ONE_LINE, paint.clone()),
+        (TWO_LINE, paint.clone()),
+        (THREE_LINE, paint),
+    ] {
+        commands.spawn((
+            Mesh3d(across.clone()),
+            MeshMaterial3d(material),
+            Transform::from_xyz(0.0, 0.005, z),
+        ));
+    }
+    // A thin strip along the far edge marks where a puck starts to hang
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(HALF_WIDTH * 2.0, PUCK_RADIUS),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.95, 0.85, 0.3))),
+        Transform::from_xyz(0.0, 0.004, PUCK_RADIUS / 2.0),
+    ));
+
+    commands.insert_resource(PuckModel {
+        body: meshes.add(Cylinder::new(PUCK_RADIUS, PUCK_HEIGHT)),
+        colors: [
+            Color::srgb(0.85, 0.15, 0.1),
+            Color::srgb(0.15, 0.45, 0.85),
+            Color::srgb(0.95, 0.8, 0.1),
+            Color::srgb(0.2, 0.7, 0.3),
+        ]
+        .into_iter()
+        .map(|color| materials.add(color))
+        .collect(),
+    });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.8, BOARD_LENGTH + 0.8).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(1.0, 6.0, BOARD_LENGTH).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
@@ -0,0 +1,318 @@
+//! Frames of a game: waiting for every puck to settle, handing the next puck to the next player,
+//! scoring the board once every puck is thrown, all shown on the shared scorecard HUD
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::Velocity;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    board::Puck,
+    phase::ShuffleboardPhase,
+    scoreboard::{frame_score, Scoreboard, TARGET_SCORE},
+    Delivery,
+};
+
+/// Speed below which a puck counts as still, in meters per second
+const REST_SPEED: f32 = 0.02;
+/// How long every puck has to stay still before the throw is over, in seconds
+const SETTLE_SECS: f32 = 0.5;
+/// How long the frame's score stays up before the board is cleared, in seconds
+const FRAME_PAUSE_SECS: f32 = 3.0;
+
+/// Asks for the game to be started over from the first frame
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Counts how long every puck has been still
+#[derive(Resource, Debug)]
+struct Settling(Timer);
+
+impl Default for Settling {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SETTLE_SECS, TimerMode::Once))
+    }
+}
+
+/// Counts down before the board is cleared for the next frame
+#[derive(Resource, Debug)]
+struct FramePause(Timer);
+
+impl Default for FramePause {
+    fn default() -> Self {
+        Self(Timer::from_seconds(FRAME_PAUSE_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct ShuffleboardSnapshot<'a> {
+    /// Player up to throw
+    player: usize,
+    /// Total that wins the game
+    target: u32,
+    /// Every finished frame's score and the pucks thrown this frame
+    scoreboard: &'a Scoreboard,
+    /// Where the game is at
+    phase: ShuffleboardPhase,
+}
+
+/// Plugin that runs each frame and shows the score on the scorecard HUD
+pub struct FramesPlugin;
+
+impl Plugin for FramesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<Settling>()
+            .init_resource::<FramePause>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    finish_throw.run_if(in_state(ShuffleboardPhase::Sliding)),
+                    next_frame.run_if(in_state(ShuffleboardPhase::FrameOver)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(ShuffleboardPhase::Sliding), reset_settling)
+            .add_systems(OnEnter(ShuffleboardPhase::FrameOver), reset_frame_pause)
+            .add_systems(
+                OnEnter(ShuffleboardPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(ShuffleboardPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Everything the end of a throw changes besides the pucks themselves
+#[derive(SystemParam)]
+struct ThrowEffects<'w> {
+    /// The game score
+    scoreboard: ResMut<'w, Scoreboard>,
+    /// Whose throw is next
+    turns: ResMut<'w, TurnManager>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the game goes next
+    next_phase: ResMut<'w, NextState<ShuffleboardPhase>>,
+}
+
+/// Once every puck has stopped, takes pucks that came up short of the foul line off the board and
+/// hands the next puck to the next player, or scores the board if every puck has been thrown
+fn finish_throw(
+    mut commands: Commands<'_, '_>,
+    pucks: Query<'_, '_, (Entity, &Transform, &Velocity, &Puck)>,
+    mut settling: ResMut<'_, Settling>,
+    mut effects: ThrowEffects<'_>,
+    delivery: Res<'_, Delivery>,
+    time: Res<'_, Time>,
+) {
+    if pucks
+        .iter()
+        .any(|(_, _, velocity, _)| velocity.linvel.length() > REST_SPEED)
+    {
+        settling.0.reset();
+        return;
+    }
+    if !settling.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let mut in_play = Vec::new();
+    for (entity, transform, _, puck) in &pucks {
+        let at = transform.translation;
+        if !Puck::past_foul_line(at) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if delivery.puck == Some(entity) && Puck::hanging(at) {
+            effects.banner.show("Hanger!");
+        }
+        in_play.push((puck.owner, at));
+    }
+
+    let ThrowEffects {
+        scoreboard,
+        turns,
+        banner,
+        next_phase,
+    } = &mut effects;
+    scoreboard.record_throw(turns.current());
+    if turns
+        .advance_until(|player| scoreboard.pucks_left(player) == 0)
+        .is_some()
+    {
+        next_phase.set(ShuffleboardPhase::Aiming);
+        return;
+    }
+
+    let score = frame_score(&in_play);
+    match score {
+        Some((scorer, points)) => banner.show(format!(
+            "Player {} scores {points} {}",
+            scorer + 1,
+            if points == 1 { "point" } else { "points" }
+        )),
+        None => banner.show("Dead frame"),
+    }
+    scoreboard.finish_frame(score);
+    next_phase.set(ShuffleboardPhase::FrameOver);
+}
+
+/// Starts waiting for every puck to settle after a throw
+fn reset_settling(mut settling: ResMut<'_, Settling>) {
+    settling.0.reset();
+}
+
+/// Keeps the frame's score up for a moment before clearing the board
+fn reset_frame_pause(mut pause: ResMut<'_, FramePause>) {
+    pause.0.reset();
+}
+
+/// Clears the board and starts the next frame with the last frame's scorer throwing first, or
+/// finishes the game once someone has reached the winning score
+fn next_frame(
+    mut commands: Commands<'_, '_>,
+    mut pause: ResMut<'_, FramePause>,
+    mut effects: ThrowEffects<'_>,
+    pucks: Query<'_, '_, Entity, With<Puck>>,
+    time: Res<'_, Time>,
+) {
+    if !pause.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for puck in &pucks {
+        commands.entity(puck).despawn_recursive();
+    }
+    if effects.scoreboard.winner().is_some() {
+        effects.next_phase.set(ShuffleboardPhase::GameOver);
+        return;
+    }
+    match effects.scoreboard.last_scorer() {
+        Some(scorer) => {
+            effects.turns.advance_until(|player| player != scorer);
+        }
+        None => {
+            effects.turns.advance();
+        }
+    }
+    effects.next_phase.set(ShuffleboardPhase::Aiming);
+}
+
+/// Starts the game over from the first player's first puck
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut next_phase: ResMut<'_, NextState<ShuffleboardPhase>>,
+    pucks: Query<'_, '_, Entity, With<Puck>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for puck in &pucks {
+        commands.entity(puck).despawn_recursive();
+    }
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    next_phase.set(ShuffleboardPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every player's total and pucks left this frame
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            format!(
+                "Player {}: {} ({} pucks left)",
+                player + 1,
+                scoreboard.total(player),
+                scoreboard.pucks_left(player)
+            )
+        })
+        .collect();
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Shuffleboard, frame {}", scoreboard.frames_played() + 1),
+        rows,
+        footer: format!(
+            "Player {} to throw, first to {TARGET_SCORE} wins",
+            turns.current() + 1
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's total once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scoreboard: Res<'_, Scoreboard>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    if let Some(winner) = scoreboard.winner() {
+        lines.push(format!("Player {} wins!", winner + 1));
+    }
+    for player in 0..scoreboard.players() {
+        lines.push(format!(
+            "Player {}: {}",
+            player + 1,
+            scoreboard.total(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's total back to the page once the game is over, so it can submit them to
+/// the server
+fn submit_result(scoreboard: Res<'_, Scoreboard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scoreboard.players())
+        .map(|player| scoreboard.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    phase: Res<'_, State<ShuffleboardPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&ShuffleboardSnapshot {
+        player: turns.current(),
+        target: TARGET_SCORE,
+        scoreboard: &scoreboard,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,74 @@
+//! How pucks slide: the waxed board slows them down steadily, and pucks that slide or get knocked
+//! off an edge drop into the gutter
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+use spjorts_core::{scorecard::Banner, spectator::is_playing, turns::TurnManager};
+
+use crate::{board::Puck, phase::ShuffleboardPhase, Delivery};
+
+/// How quickly the board slows a puck down, in meters per second per second
+pub const BOARD_DECEL: f32 = 0.9;
+/// Speed below which a puck stops dead, in meters per second
+const STOP_SPEED: f32 = 0.01;
+/// How quickly a spinning puck stops turning, as a fraction of its spin lost every second
+const SPIN_DECAY: f32 = 2.0;
+
+/// Steps a puck's velocity along by `secs` of sliding
+pub fn glide(velocity: Vec3, secs: f32) -> Vec3 {
+    let speed = velocity.length();
+    if speed < STOP_SPEED {
+        return Vec3::ZERO;
+    }
+    velocity * ((speed - BOARD_DECEL * secs).max(0.0) / speed)
+}
+
+/// Speed a puck has to be sent off at to slide `distance` before stopping, if it never hits
+/// anything
+pub fn speed_to_slide(distance: f32) -> f32 {
+    (2.0 * BOARD_DECEL * distance.max(0.0)).sqrt()
+}
+
+/// Plugin that slides pucks down the board and drops them into the gutter
+pub struct GlidePlugin;
+
+impl Plugin for GlidePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (slide_pucks, drop_off_edges)
+                .chain()
+                .run_if(in_state(ShuffleboardPhase::Sliding))
+                .run_if(is_playing),
+        );
+    }
+}
+
+/// Slows every moving puck and lets its spin die away
+fn slide_pucks(mut pucks: Query<'_, '_, &mut Velocity, With<Puck>>, time: Res<'_, Time>) {
+    let secs = time.delta_secs();
+    for mut velocity in &mut pucks {
+        velocity.linvel = glide(velocity.linvel, secs);
+        velocity.angvel *= (1.0 - SPIN_DECAY * secs).max(0.0);
+    }
+}
+
+/// Takes pucks that slide off an edge off the board, calling it when the puck just thrown knocks
+/// someone else's off
+fn drop_off_edges(
+    mut commands: Commands<'_, '_>,
+    pucks: Query<'_, '_, (Entity, &Transform, &Puck)>,
+    delivery: Res<'_, Delivery>,
+    turns: Res<'_, TurnManager>,
+    mut banner: ResMut<'_, Banner>,
+) {
+    for (entity, transform, puck) in &pucks {
+        if !Puck::off_board(transform.translation) {
+            continue;
+        }
+        if delivery.puck != Some(entity) && puck.owner != turns.current() {
+            banner.show(format!("Knocked off Player {}'s puck!", puck.owner + 1));
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,245 @@
+//! Bevy tabletop shuffleboard game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::Velocity,
+};
+use board::{BoardPlugin, Puck, PuckModel, HALF_WIDTH, PUCK_RADIUS, RELEASE_Z};
+use frames::{FramesPlugin, NewGame};
+use glide::{speed_to_slide, GlidePlugin};
+use phase::{ShuffleboardPhase, ShuffleboardPhasePlugin};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+
+pub mod board;
+pub mod frames;
+pub mod glide;
+pub mod phase;
+pub mod scoreboard;
+
+/// How far a radian of controller yaw turns the line of the throw, in radians
+const AIM_SCALE: f32 = 0.05;
+/// Widest the line of the throw can be turned from straight down the board, in radians
+const MAX_AIM: f32 = 0.04;
+/// How far the controller has to be rolled to let go right at the edge of the board, in radians
+const FULL_SHIFT_ROLL: f32 = 0.8;
+/// How fast the controller has to swing forward to throw the hardest, in radians per second
+const FULL_SWING_SPEED: f32 = 10.0;
+/// How far behind a sliding puck the camera follows
+const CAMERA_BACK: f32 = 1.2;
+/// How far above the board the camera sits
+const CAMERA_UP: f32 = 0.6;
+/// How quickly the camera catches up with the puck, higher is snappier
+const CAMERA_SMOOTHING: f32 = 3.0;
+
+/// Slowest a puck can be thrown, far enough to get past the foul line with nothing in the way,
+/// in meters per second
+pub fn min_weight() -> f32 {
+    speed_to_slide(RELEASE_Z - board::FOUL_LINE)
+}
+
+/// Fastest a puck can be thrown, well off the end of the board, in meters per second
+pub fn max_weight() -> f32 {
+    speed_to_slide(RELEASE_Z * 1.5)
+}
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(ShuffleboardPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(BoardPlugin)
+    .add_plugins(GlidePlugin)
+    .add_plugins(FramesPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.18, 0.15, 0.14)))
+    .init_resource::<Delivery>()
+    .add_event::<Push>()
+    .add_systems(OnEnter(ShuffleboardPhase::Aiming), ready_delivery)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            push_puck.run_if(in_state(ShuffleboardPhase::Aiming)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (follow_puck, draw_aim_guide));
+});
+
+/// The player up's line and where across the board they let go
+#[derive(Resource, Debug, Default)]
+pub struct Delivery {
+    /// Line of the throw, in radians from straight down the board. Positive turns left
+    pub aim: f32,
+    /// Where across the board the puck is let go, in meters from the middle. Positive is right
+    pub offset: f32,
+    /// The puck that was thrown last, while it's sliding
+    pub puck: Option<Entity>,
+    /// Watches the controller for the forward swing that throws
+    detector: GestureDetector,
+}
+
+/// A puck slid down the board
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Push {
+    /// Line of the throw, in radians from straight down the board. Positive turns left
+    pub aim: f32,
+    /// Where across the board the puck is let go, in meters from the middle. Positive is right
+    pub offset: f32,
+    /// Speed the puck leaves the hand at, in meters per second
+    pub weight: f32,
+}
+
+impl Push {
+    /// Where the puck is let go
+    pub fn release(&self) -> Vec3 {
+        Vec3::new(self.offset, 0.0, RELEASE_Z)
+    }
+
+    /// Velocity the puck leaves the hand with
+    pub fn velocity(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Vec3::NEG_Z * self.weight
+    }
+}
+
+/// Forgets the last puck ready for the next throw
+fn ready_delivery(mut delivery: ResMut<'_, Delivery>) {
+    delivery.puck = None;
+}
+
+/// Everything input handling changes besides the delivery itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Pucks to throw
+    pushes: EventWriter<'w, Push>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: yaw sets the line, roll shifts where the puck is let go across the
+/// board and a forward swing slides it as hard as it was swung. A starts a new game once it's
+/// over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut delivery: ResMut<'_, Delivery>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<ShuffleboardPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == ShuffleboardPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == ShuffleboardPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let orientation @ Orientation { roll, yaw, .. } =
+                    settings.apply_rotation(orientation);
+                delivery.aim = (yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                delivery.offset =
+                    (roll / FULL_SHIFT_ROLL).clamp(-1.0, 1.0) * (HALF_WIDTH - PUCK_RADIUS);
+
+                let Some(detected) = delivery.detector.update(orientation, time.elapsed_secs())
+                else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+                    let power = (detected.intensity / FULL_SWING_SPEED).clamp(0.0, 1.0);
+                    effects.pushes.send(Push {
+                        aim: delivery.aim,
+                        offset: delivery.offset,
+                        weight: min_weight() + (max_weight() - min_weight()) * power,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Slides a puck down the board for the player up
+fn push_puck(
+    mut commands: Commands<'_, '_>,
+    mut pushes: EventReader<'_, '_, Push>,
+    mut delivery: ResMut<'_, Delivery>,
+    mut next_phase: ResMut<'_, NextState<ShuffleboardPhase>>,
+    model: Res<'_, PuckModel>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(push) = pushes.read().last().copied() else {
+        return;
+    };
+
+    let puck = model.spawn(&mut commands, turns.current(), push.release());
+    commands
+        .entity(puck)
+        .insert(Velocity::linear(push.velocity()));
+    delivery.puck = Some(puck);
+    next_phase.set(ShuffleboardPhase::Sliding);
+}
+
+/// Follows the thrown puck down the board, settling back behind the release line otherwise
+fn follow_puck(
+    mut camera: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Puck>)>,
+    pucks: Query<'_, '_, &Transform, With<Puck>>,
+    delivery: Res<'_, Delivery>,
+    time: Res<'_, Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let home = RELEASE_Z + CAMERA_BACK;
+    let behind = delivery
+        .puck
+        .and_then(|puck| pucks.get(puck).ok())
+        .map_or(home, |puck| {
+            (puck.translation.z + CAMERA_BACK).clamp(CAMERA_BACK, home)
+        });
+
+    let goal = Vec3::new(0.0, CAMERA_UP, behind);
+    let blend = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(Vec3::ZERO, Vec3::Y);
+}
+
+/// Draws the line of the throw down the board while aiming, when the aim guide is on
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    delivery: Res<'_, Delivery>,
+    phase: Res<'_, State<ShuffleboardPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() != ShuffleboardPhase::Aiming {
+        return;
+    }
+    let start = Vec3::new(delivery.offset, 0.01, RELEASE_Z);
+    let direction = Quat::from_rotation_y(delivery.aim) * Vec3::NEG_Z;
+    gizmos.line(
+        start,
+        start + direction * RELEASE_Z,
+        Color::srgb(0.9, 0.2, 0.2),
+    );
+}
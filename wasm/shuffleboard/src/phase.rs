@@ -0,0 +1,27 @@
+//! Phases a shuffleboard game moves through, from lining up a puck to the final score
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of play
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ShuffleboardPhase {
+    /// The player up is lining up their next puck
+    #[default]
+    Aiming,
+    /// Pucks are sliding down the board
+    Sliding,
+    /// Every puck of the frame has been thrown and the frame's score is up
+    FrameOver,
+    /// Someone has reached the winning score and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct ShuffleboardPhasePlugin;
+
+impl Plugin for ShuffleboardPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<ShuffleboardPhase>();
+    }
+}
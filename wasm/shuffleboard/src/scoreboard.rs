@@ -0,0 +1,107 @@
+//! Frame scoring: once every puck is thrown, whoever has the puck furthest down the board scores
+//! the zone each of their pucks sits in, for every puck of theirs beyond anyone else's best
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::board::Puck;
+
+/// Pucks each player throws every frame
+pub const PUCKS_PER_PLAYER: usize = 4;
+/// Total that wins the game, checked at the end of every frame
+pub const TARGET_SCORE: u32 = 15;
+
+/// Works out a frame's score from where every puck left on the board lies, as the player who
+/// scores and how many points. `None` is a dead frame, with nothing in the scoring zones
+pub fn frame_score(pucks: &[(usize, Vec3)]) -> Option<(usize, u32)> {
+    let mut counting: Vec<(usize, Vec3)> = pucks.to_vec();
+    counting.sort_by(|a, b| a.1.z.total_cmp(&b.1.z));
+
+    let (scorer, _) = *counting.first()?;
+    let points: u32 = counting
+        .iter()
+        .take_while(|(owner, _)| *owner == scorer)
+        .map(|(_, at)| Puck::points(*at))
+        .sum();
+    (points > 0).then_some((scorer, points))
+}
+
+/// Every frame's score and the pucks thrown in the frame being played
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    /// Points each player scored in every finished frame, in turn order
+    frames: Vec<Vec<u32>>,
+    /// Pucks each player has thrown so far in the frame being played
+    thrown: Vec<usize>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a game for a number of players with nothing thrown
+    pub fn new(players: usize) -> Self {
+        Self {
+            frames: Vec::new(),
+            thrown: vec![0; players.max(1)],
+        }
+    }
+
+    /// How many players are in the game
+    pub fn players(&self) -> usize {
+        self.thrown.len()
+    }
+
+    /// How many frames have been finished
+    pub fn frames_played(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Counts a puck a player has thrown this frame
+    pub fn record_throw(&mut self, player: usize) {
+        if let Some(thrown) = self.thrown.get_mut(player) {
+            *thrown += 1;
+        }
+    }
+
+    /// Pucks a player has left to throw in the frame being played
+    pub fn pucks_left(&self, player: usize) -> usize {
+        self.thrown
+            .get(player)
+            .map_or(0, |thrown| PUCKS_PER_PLAYER.saturating_sub(*thrown))
+    }
+
+    /// Finishes the frame with a score from [`frame_score`], ready for the next
+    pub fn finish_frame(&mut self, score: Option<(usize, u32)>) {
+        let mut points = vec![0; self.players()];
+        if let Some((scorer, scored)) = score {
+            if let Some(slot) = points.get_mut(scorer) {
+                *slot = scored;
+            }
+        }
+        self.frames.push(points);
+        self.thrown.fill(0);
+    }
+
+    /// The player who scored the last finished frame, or `None` after a dead frame
+    pub fn last_scorer(&self) -> Option<usize> {
+        self.frames.last()?.iter().position(|points| *points > 0)
+    }
+
+    /// A player's total across every finished frame
+    pub fn total(&self, player: usize) -> u32 {
+        self.frames
+            .iter()
+            .filter_map(|points| points.get(player))
+            .sum()
+    }
+
+    /// The player who has reached the winning score, once someone has. Only one player scores
+    /// each frame, so there's never more than one
+    pub fn winner(&self) -> Option<usize> {
+        (0..self.players()).find(|player| self.total(*player) >= TARGET_SCORE)
+    }
+}
@@ -0,0 +1,379 @@
+//! The machine: a flat alley ending in a hump that throws the ball onto a sloped board of
+//! scoring rings, with a 100 pocket in either top corner. Each ring and pocket has a trigger the
+//! ball drops through, and the first one it touches is what it scores
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{Collider, Friction, Restitution, RigidBody, Sensor};
+
+/// Radius of a ball
+pub const BALL_RADIUS: f32 = 0.038;
+/// Where a ball sits before it's rolled, at the player's end of the alley
+pub const BALL_START: Vec3 = Vec3::new(0.0, BALL_RADIUS, 0.3);
+/// Half the width of the alley and the board
+pub const ALLEY_HALF_WIDTH: f32 = 0.25;
+/// Where the alley's far end meets the hump
+pub const HUMP_Z: f32 = -3.0;
+/// How steep the hump is, in radians
+const HUMP_ANGLE: f32 = 0.436;
+/// How high the top of the hump is off the alley
+const HUMP_HEIGHT: f32 = 0.08;
+/// Gap between the top of the hump and the bottom of the board, along the alley
+const GAP: f32 = 0.12;
+/// How steep the board is, in radians
+const BOARD_ANGLE: f32 = 0.524;
+/// Length of the board, up its slope
+const BOARD_LENGTH: f32 = 0.75;
+/// How far up the board the middle of the rings is
+pub const RINGS_UP: f32 = 0.3;
+/// Outer radius and points of each ring, innermost first. The 10 ring is widest and catches
+/// every ball that makes the board but misses the rest, as they roll back down into it
+const RINGS: [(f32, u32); 5] = [(0.035, 50), (0.08, 40), (0.13, 30), (0.19, 20), (0.26, 10)];
+/// Where the middle of each 100 pocket is, across the board and up it from the middle of the
+/// rings
+const CORNER_POCKETS: [Vec2; 2] = [Vec2::new(-0.19, 0.24), Vec2::new(0.19, 0.24)];
+/// Radius of a 100 pocket
+const CORNER_RADIUS: f32 = 0.04;
+/// Points for dropping into a 100 pocket
+pub const JACKPOT_POINTS: u32 = 100;
+/// Half the height of a pocket's trigger above the board, thin so only a ball touching down
+/// sets it off
+const TRIGGER_HALF_HEIGHT: f32 = 0.005;
+/// Segments each ring's trigger is built from
+const RING_SEGMENTS: usize = 24;
+/// Acceleration balls fall at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// How much of gravity slows a ball rolling up a slope, the rest going into its spin
+const ROLLING_SHARE: f32 = 5.0 / 7.0;
+
+/// A ring or pocket a ball scores for dropping into
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pocket {
+    /// Points it's worth
+    pub points: u32,
+}
+
+/// Where the top of the hump is
+fn hump_top() -> Vec3 {
+    Vec3::new(0.0, HUMP_HEIGHT, HUMP_Z - HUMP_HEIGHT / HUMP_ANGLE.tan())
+}
+
+/// Where the bottom edge of the board is
+fn board_bottom() -> Vec3 {
+    Vec3::new(0.0, 0.0, hump_top().z - GAP)
+}
+
+/// The board's surface: its middle and how it's tilted. The surface's +z points down the slope
+/// and its +y up out of it
+fn board() -> Transform {
+    Transform::from_translation(
+        board_bottom() + Quat::from_rotation_x(BOARD_ANGLE) * Vec3::NEG_Z * BOARD_LENGTH / 2.0,
+    )
+    .with_rotation(Quat::from_rotation_x(BOARD_ANGLE))
+}
+
+/// Where a spot on the board is in the board surface's space, from how far across the board
+/// and up its slope it is
+fn on_board(across: f32, up: f32) -> Vec3 {
+    Vec3::new(across, 0.0, BOARD_LENGTH / 2.0 - up)
+}
+
+/// Where a spot on the board is, from how far across the board and up its slope it is
+pub fn board_point(across: f32, up: f32) -> Vec3 {
+    board().transform_point(on_board(across, up))
+}
+
+/// Direction straight up out of the board
+pub fn board_up() -> Vec3 {
+    board().rotation * Vec3::Y
+}
+
+/// Where the middle of the rings is
+pub fn rings_centre() -> Vec3 {
+    board_point(0.0, RINGS_UP)
+}
+
+/// How far up the board a ball rolled straight at `speed` first touches down, or `None` if it
+/// doesn't make it over the hump or drops short of the board. Works it out from the ball
+/// rolling up the hump and flying off its top, so it reads how hard a roll needs to be
+pub fn touchdown(speed: f32) -> Option<f32> {
+    let (hump_sin, hump_cos) = HUMP_ANGLE.sin_cos();
+    let up_hump = speed * hump_cos;
+    let exit = (up_hump * up_hump - 2.0 * ROLLING_SHARE * GRAVITY * HUMP_HEIGHT).sqrt();
+    if exit.is_nan() {
+        return None;
+    }
+
+    let (board_sin, board_cos) = BOARD_ANGLE.sin_cos();
+    let board_tan = board_sin / board_cos;
+    let (along, rise) = (exit * hump_cos, exit * hump_sin);
+    let top = hump_top();
+    let bottom = board_bottom();
+    let a = -GRAVITY / 2.0;
+    let b = rise - along * board_tan;
+    let c = top.y + BALL_RADIUS + (top.z - bottom.z) * board_tan - BALL_RADIUS / board_cos;
+    let t = (-b - (b * b - 4.0 * a * c).sqrt()) / (2.0 * a);
+    let z = top.z - along * t;
+    let up = (bottom.z - z + BALL_RADIUS * board_sin) / board_cos;
+    (up >= 0.0).then_some(up)
+}
+
+/// Plugin that sets up the machine, the arcade around it and the view from the end of the
+/// alley
+pub struct AlleyPlugin;
+
+impl Plugin for AlleyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_alley);
+    }
+}
+
+/// A ring's trigger, built from short straight pieces running round between its radii
+fn ring_trigger(inner: f32, outer: f32) -> Collider {
+    let middle = (inner + outer) / 2.0;
+    let half_length = TAU * outer / RING_SEGMENTS as f32 / 2.0;
+    Collider::compound(
+        (0..RING_SEGMENTS)
+            .map(|segment| {
+                let angle = TAU * segment as f32 / RING_SEGMENTS as f32;
+                (
+                    Vec3::new(middle * angle.cos(), 0.0, middle * angle.sin()),
+                    Quat::from_rotation_y(-angle),
+                    Collider::cuboid((outer - inner) / 2.0, TRIGGER_HALF_HEIGHT, half_length),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// A fixed wall of the machine, drawn in the given material
+fn wall(
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardMaterial>,
+    transform: Transform,
+    half: Vec3,
+) -> impl Bundle {
+    (
+        Mesh3d(meshes.add(Cuboid::from_size(half * 2.0))),
+        MeshMaterial3d(material),
+        transform,
+        RigidBody::Fixed,
+        Collider::cuboid(half.x, half.y, half.z),
+        Friction::coefficient(0.3),
+        Restitution::coefficient(0.3),
+    )
+}
+
+/// Spawns the alley, the hump, the board with its rings and pockets, the cage round them, the
+/// arcade floor, the camera and the lights
+fn setup_alley(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let wood = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.8, 0.62, 0.38),
+        perceptual_roughness: 0.4,
+        ..default()
+    });
+    let cabinet = materials.add(Color::srgb(0.08, 0.08, 0.12));
+    let neon = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.2, 0.8),
+        emissive: LinearRgba::rgb(4.0, 0.6, 3.2),
+        ..default()
+    });
+    let thickness = 0.02;
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(8.0, 12.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.12, 0.1, 0.18))),
+        Transform::from_xyz(0.0, -0.6, -1.0),
+        Name::new("Arcade floor"),
+    ));
+
+    // The alley runs from the player up to the hump, with a low neon rail either side
+    let alley_length = BALL_START.z + 0.3 - HUMP_Z;
+    commands.spawn((
+        wall(
+            &mut meshes,
+            wood.clone(),
+            Transform::from_xyz(0.0, -thickness, HUMP_Z + alley_length / 2.0),
+            Vec3::new(ALLEY_HALF_WIDTH, thickness, alley_length / 2.0),
+        ),
+        Name::new("Alley"),
+    ));
+    let top = board_point(0.0, BOARD_LENGTH);
+    let cage_length = HUMP_Z - top.z;
+    for side in [-1.0, 1.0] {
+        commands.spawn(wall(
+            &mut meshes,
+            neon.clone(),
+            Transform::from_xyz(
+                side * (ALLEY_HALF_WIDTH + thickness),
+                0.04,
+                BALL_START.z + 0.3 - alley_length / 2.0,
+            ),
+            Vec3::new(thickness, 0.06, alley_length / 2.0),
+        ));
+        commands.spawn(wall(
+            &mut meshes,
+            cabinet.clone(),
+            Transform::from_xyz(
+                side * (ALLEY_HALF_WIDTH + thickness),
+                0.3,
+                HUMP_Z - cage_length / 2.0,
+            ),
+            Vec3::new(thickness, 0.45, cage_length / 2.0),
+        ));
+    }
+
+    // The hump's top face starts flush with the alley and climbs to the lip the ball jumps from
+    let hump_length = HUMP_HEIGHT / HUMP_ANGLE.sin();
+    let hump = Transform::from_translation((Vec3::new(0.0, 0.0, HUMP_Z) + hump_top()) / 2.0)
+        .with_rotation(Quat::from_rotation_x(HUMP_ANGLE));
+    commands.spawn((
+        wall(
+            &mut meshes,
+            wood.clone(),
+            hump.with_translation(hump.transform_point(Vec3::NEG_Y * thickness)),
+            Vec3::new(ALLEY_HALF_WIDTH, thickness, hump_length / 2.0),
+        ),
+        Name::new("Hump"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.2, (hump_top().z + board_bottom().z) / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(ALLEY_HALF_WIDTH, 0.02, GAP),
+        Name::new("Ball return"),
+    ));
+
+    // The board, with each ring drawn round its trigger
+    let board = board();
+    let ring_colors = [
+        Color::srgb(0.05, 0.05, 0.05),
+        Color::srgb(0.95, 0.85, 0.2),
+        Color::srgb(0.2, 0.45, 0.9),
+        Color::srgb(0.9, 0.3, 0.2),
+        Color::srgb(0.2, 0.7, 0.35),
+    ];
+    commands
+        .spawn((
+            board,
+            Visibility::Visible,
+            RigidBody::Fixed,
+            Name::new("Board"),
+        ))
+        .with_children(|board| {
+            board.spawn((
+                Mesh3d(meshes.add(Cuboid::new(
+                    ALLEY_HALF_WIDTH * 2.0,
+                    thickness * 2.0,
+                    BOARD_LENGTH,
+                ))),
+                MeshMaterial3d(wood.clone()),
+                Transform::from_xyz(0.0, -thickness, 0.0),
+                Collider::cuboid(ALLEY_HALF_WIDTH, thickness, BOARD_LENGTH / 2.0),
+                Friction::coefficient(0.4),
+                Restitution::coefficient(0.2),
+            ));
+
+            let centre = on_board(0.0, RINGS_UP);
+            let mut inner = 0.0;
+            for ((outer, points), color) in RINGS.into_iter().zip(ring_colors) {
+                let mesh = if inner > 0.0 {
+                    meshes.add(Annulus::new(inner, outer))
+                } else {
+                    meshes.add(Circle::new(outer))
+                };
+                let trigger = if inner > 0.0 {
+                    ring_trigger(inner, outer)
+                } else {
+                    Collider::cylinder(TRIGGER_HALF_HEIGHT, outer)
+                };
+                board.spawn((
+                    Mesh3d(mesh),
+                    MeshMaterial3d(materials.add(color)),
+                    Transform::from_translation(centre + Vec3::Y * 0.001)
+                        .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+                ));
+                board.spawn((
+                    Transform::from_translation(centre + Vec3::Y * TRIGGER_HALF_HEIGHT),
+                    trigger,
+                    Sensor,
+                    Pocket { points },
+                ));
+                inner = outer;
+            }
+
+            let jackpot = materials.add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.8, 0.1),
+                emissive: LinearRgba::rgb(3.0, 2.2, 0.2),
+                ..default()
+            });
+            let pocket = meshes.add(Circle::new(CORNER_RADIUS));
+            for corner in CORNER_POCKETS {
+                let at = on_board(corner.x, RINGS_UP + corner.y);
+                board.spawn((
+                    Mesh3d(pocket.clone()),
+                    MeshMaterial3d(jackpot.clone()),
+                    Transform::from_translation(at + Vec3::Y * 0.001)
+                        .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+                ));
+                board.spawn((
+                    Transform::from_translation(at + Vec3::Y * TRIGGER_HALF_HEIGHT),
+                    Collider::cylinder(TRIGGER_HALF_HEIGHT, CORNER_RADIUS),
+                    Sensor,
+                    Pocket {
+                        points: JACKPOT_POINTS,
+                    },
+                ));
+            }
+        });
+
+    // A back wall above the top of the board keeps long balls on it
+    commands.spawn((
+        wall(
+            &mut meshes,
+            cabinet.clone(),
+            Transform::from_xyz(0.0, top.y + 0.3, top.z - thickness),
+            Vec3::new(ALLEY_HALF_WIDTH + thickness * 2.0, 0.45, thickness),
+        ),
+        Name::new("Back wall"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(ALLEY_HALF_WIDTH * 2.0, 0.04, 0.01))),
+        MeshMaterial3d(neon),
+        Transform::from_xyz(0.0, top.y + 0.7, top.z),
+        Name::new("Marquee"),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: 0.55,
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 0.85, BALL_START.z + 1.0).looking_at(rings_centre(), Vec3::Y),
+    ));
+    commands.insert_resource(AmbientLight {
+        color: Color::srgb(0.6, 0.5, 0.9),
+        brightness: 150.0,
+    });
+    for (at, color) in [
+        (Vec3::new(-1.0, 1.5, -2.5), Color::srgb(1.0, 0.3, 0.8)),
+        (Vec3::new(1.0, 1.5, -2.5), Color::srgb(0.3, 0.8, 1.0)),
+        (Vec3::new(0.0, 2.0, 0.5), Color::srgb(1.0, 0.95, 0.85)),
+    ] {
+        commands.spawn((
+            PointLight {
+                color,
+                intensity: 400_000.0,
+                range: 8.0,
+                shadows_enabled: true,
+                ..default()
+            },
+            Transform::from_translation(at),
+        ));
+    }
+}
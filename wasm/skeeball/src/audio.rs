@@ -0,0 +1,158 @@
+//! Skee-ball sound effects and the arcade ambience looping behind them
+
+use bevy::{audio::Volume, prelude::*};
+use spjorts_core::{assets::AssetBasePath, settings::GameSettings};
+
+use crate::{alley::JACKPOT_POINTS, phase::SkeeBallPhase, rounds::Rolled};
+
+/// How loud the arcade ambience plays next to the sound effects
+const AMBIENCE_VOLUME: f32 = 0.35;
+
+/// Handles to every skee-ball sound
+#[derive(Resource)]
+struct SkeeBallSounds {
+    /// Arcade hum, chatter and machine bleeps, looped the whole game
+    ambience: Handle<AudioSource>,
+    /// Looping roll up the alley
+    rolling: Handle<AudioSource>,
+    /// Ball dropping through a ring
+    pocket: Handle<AudioSource>,
+    /// Ball dropping into a 100 pocket
+    jackpot: Handle<AudioSource>,
+    /// Ball rolling back without making the board
+    miss: Handle<AudioSource>,
+    /// Tickets spooling out once the game is over
+    tickets: Handle<AudioSource>,
+}
+
+/// Marks the looping arcade ambience
+#[derive(Component)]
+struct Ambience;
+
+/// Marks the looping rolling sound while a ball is on the alley
+#[derive(Component)]
+struct RollingSound;
+
+/// Plugin that loads and plays skee-ball sounds
+pub struct SkeeBallAudioPlugin;
+
+impl Plugin for SkeeBallAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_sounds)
+            .add_systems(PostStartup, start_ambience)
+            .add_systems(OnEnter(SkeeBallPhase::Rolling), start_rolling_sound)
+            .add_systems(OnExit(SkeeBallPhase::Rolling), stop_rolling_sound)
+            .add_systems(OnEnter(SkeeBallPhase::GameOver), play_tickets)
+            .add_systems(Update, (play_rolled, set_ambience_volume));
+    }
+}
+
+/// Loads every skee-ball sound through the shared asset path
+fn load_sounds(
+    mut commands: Commands<'_, '_>,
+    asset_server: Res<'_, AssetServer>,
+    base_path: Res<'_, AssetBasePath>,
+) {
+    let load = |name: &str| {
+        asset_server.load(base_path.join(&format!("frontend/sounds/skeeball/{name}.wav")))
+    };
+
+    commands.insert_resource(SkeeBallSounds {
+        ambience: load("ambience"),
+        rolling: load("rolling"),
+        pocket: load("pocket"),
+        jackpot: load("jackpot"),
+        miss: load("miss"),
+        tickets: load("tickets"),
+    });
+}
+
+/// Starts the arcade ambience looping behind the game
+fn start_ambience(
+    mut commands: Commands<'_, '_>,
+    sounds: Res<'_, SkeeBallSounds>,
+    settings: Res<'_, GameSettings>,
+) {
+    commands.spawn((
+        AudioPlayer(sounds.ambience.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::new(settings.volume * AMBIENCE_VOLUME)),
+        Ambience,
+    ));
+}
+
+/// Follows the player's volume with the ambience, which keeps playing through changes
+fn set_ambience_volume(
+    settings: Res<'_, GameSettings>,
+    ambience: Query<'_, '_, &AudioSink, With<Ambience>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for sink in &ambience {
+        sink.set_volume(settings.volume * AMBIENCE_VOLUME);
+    }
+}
+
+/// Spawns a one-shot sound at the player's volume, unless they've muted the game
+fn play_once(
+    commands: &mut Commands<'_, '_>,
+    settings: &GameSettings,
+    sound: &Handle<AudioSource>,
+) {
+    if settings.is_muted() {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer(sound.clone()),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(settings.volume)),
+    ));
+}
+
+/// Starts the rolling loop when a ball is let go
+fn start_rolling_sound(
+    mut commands: Commands<'_, '_>,
+    sounds: Res<'_, SkeeBallSounds>,
+    settings: Res<'_, GameSettings>,
+) {
+    commands.spawn((
+        AudioPlayer(sounds.rolling.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::new(settings.volume)),
+        RollingSound,
+    ));
+}
+
+/// Stops the rolling loop once the ball has finished its roll
+fn stop_rolling_sound(
+    mut commands: Commands<'_, '_>,
+    rolling: Query<'_, '_, Entity, With<RollingSound>>,
+) {
+    for entity in &rolling {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Plays what a finished ball dropped into, or its roll back if it missed
+fn play_rolled(
+    mut commands: Commands<'_, '_>,
+    mut rolls: EventReader<'_, '_, Rolled>,
+    sounds: Res<'_, SkeeBallSounds>,
+    settings: Res<'_, GameSettings>,
+) {
+    for rolled in rolls.read() {
+        let sound = match rolled.points {
+            Some(JACKPOT_POINTS) => &sounds.jackpot,
+            Some(_) => &sounds.pocket,
+            None => &sounds.miss,
+        };
+        play_once(&mut commands, &settings, sound);
+    }
+}
+
+/// Spools out the tickets once the game is over
+fn play_tickets(
+    mut commands: Commands<'_, '_>,
+    sounds: Res<'_, SkeeBallSounds>,
+    settings: Res<'_, GameSettings>,
+) {
+    play_once(&mut commands, &settings, &sounds.tickets);
+}
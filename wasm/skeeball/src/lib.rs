@@ -0,0 +1,317 @@
+//! Bevy skee-ball game
+
+use alley::{
+    board_point, board_up, touchdown, AlleyPlugin, Pocket, BALL_RADIUS, BALL_START, HUMP_Z,
+};
+use audio::SkeeBallAudioPlugin;
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{
+        ActiveEvents, Ccd, Collider, ColliderMassProperties, CollisionEvent, Friction, Restitution,
+        RigidBody, Velocity,
+    },
+};
+use phase::{SkeeBallPhase, SkeeBallPhasePlugin};
+use rounds::{NewGame, Rolled, RoundsPlugin};
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    swing::SwingSampler,
+    turns::TurnPlugin,
+    ActionReader,
+};
+
+pub mod alley;
+pub mod audio;
+pub mod phase;
+pub mod rounds;
+
+/// Scaling applied to the swing's angular velocity to get a release speed, gentler than bowling
+/// as the ball only has to make it up the alley
+const SPEED_SCALE: f32 = 1.2;
+/// Slowest a ball can be rolled, in meters per second
+pub const MIN_SPEED: f32 = 1.0;
+/// Fastest a ball can be rolled, in meters per second
+pub const MAX_SPEED: f32 = 6.5;
+/// Widest a roll can be turned from straight up the alley, in radians
+const MAX_AIM: f32 = 0.08;
+/// Mass of a ball, in kilograms
+const BALL_MASS: f32 = 0.23;
+/// Longest a roll is watched for before it's counted as a miss, in seconds
+const MAX_ROLL_SECS: f32 = 8.0;
+/// How far back down the alley from the hump a ball has to roll to count as not making it
+const ROLLED_BACK: f32 = 0.3;
+/// Lowest a ball can drop before it's counted as falling short of the board
+const DROPPED_Y: f32 = -0.05;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(SkeeBallPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(AlleyPlugin)
+    .add_plugins(RoundsPlugin)
+    .add_plugins(SkeeBallAudioPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.04, 0.02, 0.08)))
+    .init_resource::<Hand>()
+    .add_event::<Release>()
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            (rack_ball, release_ball)
+                .chain()
+                .run_if(in_state(SkeeBallPhase::Aiming)),
+            (drop_ball, lose_ball)
+                .chain()
+                .run_if(in_state(SkeeBallPhase::Rolling)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(
+        Update,
+        draw_aim_guide.run_if(in_state(SkeeBallPhase::Aiming)),
+    );
+});
+
+/// How the player up is swinging and lining up their roll
+#[derive(Resource, Debug, Default)]
+pub struct Hand {
+    /// How far the roll is turned from straight up the alley, in radians. Positive turns left
+    pub aim: f32,
+    /// Recent controller rotations, measured for the release speed
+    swing: SwingSampler,
+}
+
+impl Hand {
+    /// How fast a ball let go right now would roll, from how fast the controller is swinging
+    fn speed(&self) -> f32 {
+        self.swing
+            .angular_velocity()
+            .map_or(MIN_SPEED, |swing| swing * SPEED_SCALE)
+            .clamp(MIN_SPEED, MAX_SPEED)
+    }
+}
+
+/// A ball let go up the alley
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Release {
+    /// How far the roll is turned from straight up the alley, in radians
+    pub aim: f32,
+    /// How fast the ball leaves the hand, in meters per second
+    pub speed: f32,
+}
+
+impl Release {
+    /// Velocity the ball rolls away with
+    fn velocity(&self) -> Vec3 {
+        Quat::from_rotation_y(self.aim) * Vec3::NEG_Z * self.speed.clamp(MIN_SPEED, MAX_SPEED)
+    }
+}
+
+/// The ball in play
+#[derive(Component, Debug, Default)]
+pub struct Ball {
+    /// Seconds since it was let go, or `None` while it's still in hand
+    rolled_for: Option<f32>,
+}
+
+/// Sets the next ball at the end of the alley once the last has finished its roll
+fn rack_ball(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+    balls: Query<'_, '_, (), With<Ball>>,
+) {
+    if !balls.is_empty() {
+        return;
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS).mesh().uv(24, 16))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.32, 0.16),
+            perceptual_roughness: 0.3,
+            ..default()
+        })),
+        Transform::from_translation(BALL_START),
+        RigidBody::KinematicPositionBased,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Mass(BALL_MASS),
+        Friction::coefficient(0.6),
+        Restitution::coefficient(0.2),
+        Ccd::enabled(),
+        ActiveEvents::COLLISION_EVENTS,
+        Velocity::zero(),
+        Ball::default(),
+    ));
+}
+
+/// Everything input handling changes besides the hand itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Balls to let go
+    releases: EventWriter<'w, Release>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input the way bowling does: swinging the controller winds up the roll, yaw
+/// aims it and A lets the ball go as fast as the controller was swinging. A starts a new game
+/// once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut hand: ResMut<'_, Hand>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<SkeeBallPhase>>,
+    time: Res<'_, Time>,
+) {
+    let aiming = *phase.get() == SkeeBallPhase::Aiming;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == SkeeBallPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if aiming => {
+                effects.releases.send(Release {
+                    aim: hand.aim,
+                    speed: hand.speed(),
+                });
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if aiming => {
+                let Orientation { pitch, yaw, .. } = settings.apply_rotation(orientation);
+                hand.swing.record(
+                    Quat::from_euler(EulerRot::XYZ, pitch, 0.0, yaw),
+                    time.elapsed_secs(),
+                );
+                hand.aim = yaw.clamp(-MAX_AIM, MAX_AIM);
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Lets the ball in hand go up the alley, already rolling rather than skidding
+fn release_ball(
+    mut releases: EventReader<'_, '_, Release>,
+    mut hand: ResMut<'_, Hand>,
+    mut balls: Query<'_, '_, (&mut RigidBody, &mut Velocity, &mut Ball)>,
+    mut next_phase: ResMut<'_, NextState<SkeeBallPhase>>,
+) {
+    let Some(release) = releases.read().last().copied() else {
+        return;
+    };
+    let Ok((mut body, mut velocity, mut ball)) = balls.get_single_mut() else {
+        return;
+    };
+
+    let linvel = release.velocity();
+    *body = RigidBody::Dynamic;
+    *velocity = Velocity {
+        linvel,
+        angvel: Vec3::Y.cross(linvel) / BALL_RADIUS,
+    };
+    ball.rolled_for = Some(0.0);
+    hand.swing.clear();
+    next_phase.set(SkeeBallPhase::Rolling);
+}
+
+/// Drops the ball through the first ring or pocket it touches, scoring it
+fn drop_ball(
+    mut commands: Commands<'_, '_>,
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    mut rolls: EventWriter<'_, Rolled>,
+    balls: Query<'_, '_, (), With<Ball>>,
+    pockets: Query<'_, '_, &Pocket>,
+) {
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let (ball, other) = if balls.contains(first) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let (Ok(()), Ok(pocket)) = (balls.get(ball), pockets.get(other)) else {
+            continue;
+        };
+
+        commands.entity(ball).despawn_recursive();
+        rolls.send(Rolled {
+            points: Some(pocket.points),
+        });
+        return;
+    }
+}
+
+/// Counts a ball that rolls back down the alley, drops short of the board or never settles as a
+/// miss
+fn lose_ball(
+    mut commands: Commands<'_, '_>,
+    mut rolls: EventWriter<'_, Rolled>,
+    mut balls: Query<'_, '_, (Entity, &Transform, &Velocity, &mut Ball)>,
+    time: Res<'_, Time>,
+) {
+    for (entity, transform, velocity, mut ball) in &mut balls {
+        let Some(rolled_for) = &mut ball.rolled_for else {
+            continue;
+        };
+        *rolled_for += time.delta_secs();
+
+        let rolled_back = velocity.linvel.z > 0.0 && transform.translation.z > HUMP_Z + ROLLED_BACK;
+        if rolled_back || transform.translation.y < DROPPED_Y || *rolled_for > MAX_ROLL_SECS {
+            commands.entity(entity).despawn_recursive();
+            rolls.send(Rolled { points: None });
+        }
+    }
+}
+
+/// Rings where on the board a ball let go right now would first touch down, from how fast the
+/// controller is swinging and where it's aimed
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    hand: Res<'_, Hand>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide {
+        return;
+    }
+    let Some(up) = touchdown(hand.speed()) else {
+        return;
+    };
+    let straight = board_point(0.0, up);
+    let across = (BALL_START.z - straight.z) * -hand.aim.tan();
+    gizmos.circle(
+        Isometry3d::new(
+            board_point(across, up) + board_up() * 0.01,
+            Quat::from_rotation_arc(Vec3::Z, board_up()),
+        ),
+        BALL_RADIUS * 1.5,
+        Color::WHITE,
+    );
+    gizmos.line(
+        BALL_START.with_y(0.005),
+        BALL_START.with_y(0.005)
+            + Quat::from_rotation_y(hand.aim) * Vec3::NEG_Z * (BALL_START.z - HUMP_Z),
+        Color::srgba(1.0, 1.0, 1.0, 0.4),
+    );
+}
@@ -0,0 +1,25 @@
+//! Phases a skee-ball game moves through, from lining up a roll to the final tickets
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of a round
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SkeeBallPhase {
+    /// The player up is swinging and lining up their next ball
+    #[default]
+    Aiming,
+    /// A ball is on its way up the alley or over the rings
+    Rolling,
+    /// Every player has rolled their balls and the tickets are counted
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct SkeeBallPhasePlugin;
+
+impl Plugin for SkeeBallPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SkeeBallPhase>();
+    }
+}
@@ -0,0 +1,282 @@
+//! Rounds: each player rolls their nine balls in turn, every ball adding what it dropped into to
+//! their score, and the totals are paid out in tickets on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{alley::JACKPOT_POINTS, phase::SkeeBallPhase, Ball};
+
+/// Balls each player rolls in a round
+pub const BALLS_PER_ROUND: usize = 9;
+/// Points each ticket is worth when the score is paid out
+pub const POINTS_PER_TICKET: u32 = 10;
+
+/// Tickets a score pays out
+pub fn tickets(score: u32) -> u32 {
+    score / POINTS_PER_TICKET
+}
+
+/// A ball finished its roll
+#[derive(Event, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rolled {
+    /// Points of the ring or pocket it dropped into, or `None` if it never made the board
+    pub points: Option<u32>,
+}
+
+/// Asks for the game to be started over from the first ball
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Every player's score and how far through their balls the player up is
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Rounds {
+    /// Each player's score
+    scores: Vec<u32>,
+    /// Balls the player up has rolled so far
+    balls: usize,
+    /// What the last ball scored, if any has been rolled
+    last: Option<Rolled>,
+}
+
+impl Default for Rounds {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Rounds {
+    /// Starts a game for a number of players with nothing rolled
+    pub fn new(players: usize) -> Self {
+        Self {
+            scores: vec![0; players.max(1)],
+            balls: 0,
+            last: None,
+        }
+    }
+
+    /// How many players are in the game
+    pub fn players(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// A player's score
+    pub fn score(&self, player: usize) -> u32 {
+        self.scores.get(player).copied().unwrap_or_default()
+    }
+
+    /// Balls the player up has rolled so far
+    pub fn balls(&self) -> usize {
+        self.balls
+    }
+
+    /// Adds a finished ball to a player's score, returning whether it was their last of the
+    /// round
+    pub fn record(&mut self, player: usize, rolled: Rolled) -> bool {
+        if let Some(score) = self.scores.get_mut(player) {
+            *score += rolled.points.unwrap_or_default();
+        }
+        self.last = Some(rolled);
+        self.balls += 1;
+        if self.balls < BALLS_PER_ROUND {
+            return false;
+        }
+        self.balls = 0;
+        true
+    }
+
+    /// Players with the best score, more than one if they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = self.scores.iter().max().copied().unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.score(*player) == best)
+            .collect()
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct SkeeBallSnapshot<'a> {
+    /// Player up
+    player: usize,
+    /// Every player's score and the balls rolled
+    rounds: &'a Rounds,
+    /// Where the game is at
+    phase: SkeeBallPhase,
+}
+
+/// Plugin that scores balls and shows the scores and tickets on the scorecard HUD
+pub struct RoundsPlugin;
+
+impl Plugin for RoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Rounds>()
+            .add_event::<Rolled>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    score_ball.run_if(in_state(SkeeBallPhase::Rolling)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(SkeeBallPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(SkeeBallPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut rounds: ResMut<'_, Rounds>) {
+    if rounds.players() != turns.players() {
+        *rounds = Rounds::new(turns.players());
+    }
+}
+
+/// Adds a finished ball to the score of the player who rolled it, handing the alley to the next
+/// player once they're out of balls and ending the game once everyone is
+fn score_ball(
+    mut rolls: EventReader<'_, '_, Rolled>,
+    mut rounds: ResMut<'_, Rounds>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<SkeeBallPhase>>,
+) {
+    let Some(rolled) = rolls.read().last().copied() else {
+        return;
+    };
+
+    banner.show(match rolled.points {
+        Some(JACKPOT_POINTS) => format!("{JACKPOT_POINTS}! Jackpot!"),
+        Some(points) => format!("{points}!"),
+        None => "Missed the rings".to_string(),
+    });
+    if !rounds.record(turns.current(), rolled) {
+        next_phase.set(SkeeBallPhase::Aiming);
+        return;
+    }
+    turns.advance();
+    next_phase.set(if turns.round() >= 1 {
+        SkeeBallPhase::GameOver
+    } else {
+        SkeeBallPhase::Aiming
+    });
+}
+
+/// Starts the game over from the first player's first ball
+fn start_new_game(
+    mut commands: Commands<'_, '_>,
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut rounds: ResMut<'_, Rounds>,
+    mut next_phase: ResMut<'_, NextState<SkeeBallPhase>>,
+    balls: Query<'_, '_, Entity, With<Ball>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for ball in &balls {
+        commands.entity(ball).despawn_recursive();
+    }
+    turns.restart();
+    *rounds = Rounds::new(turns.players());
+    next_phase.set(SkeeBallPhase::Aiming);
+}
+
+/// Fills in the scorecard HUD with every player's score and tickets and which ball is up
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    rounds: Res<'_, Rounds>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..rounds.players())
+        .map(|player| {
+            let score = rounds.score(player);
+            format!(
+                "Player {}: {score} ({} tickets)",
+                player + 1,
+                tickets(score)
+            )
+        })
+        .collect();
+    let last = match rounds.last {
+        Some(Rolled {
+            points: Some(points),
+        }) => format!("\nLast ball: {points}"),
+        Some(Rolled { points: None }) => "\nLast ball: missed".to_string(),
+        None => String::new(),
+    };
+
+    hud.set_if_neq(ScorecardHud {
+        title: "Skee-Ball".to_string(),
+        rows,
+        footer: format!(
+            "Player {} rolling ball {} of {BALLS_PER_ROUND}{last}",
+            turns.current() + 1,
+            (rounds.balls() + 1).min(BALLS_PER_ROUND)
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Pays out every player's tickets once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, rounds: Res<'_, Rounds>) {
+    let mut lines = vec!["Final Tickets".to_string()];
+    match rounds.leaders()[..] {
+        [winner] if rounds.players() > 1 => lines.push(format!("Player {} wins!", winner + 1)),
+        [_, _, ..] => lines.push("It's a tie!".to_string()),
+        _ => {}
+    }
+    for player in 0..rounds.players() {
+        let score = rounds.score(player);
+        lines.push(format!(
+            "Player {}: {score} points, {} tickets",
+            player + 1,
+            tickets(score)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final tickets when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's score once the game is over, so the page can submit them to the server
+fn submit_result(rounds: Res<'_, Rounds>, feedback: Res<'_, FeedbackSender>) {
+    feedback.send(GameEvent::GameResult {
+        players: rounds.players(),
+        scores: rounds.scores.clone(),
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    rounds: Res<'_, Rounds>,
+    phase: Res<'_, State<SkeeBallPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&SkeeBallSnapshot {
+        player: turns.current(),
+        rounds: &rounds,
+        phase: *phase.get(),
+    });
+}
@@ -0,0 +1,105 @@
+//! The course: gates set alternately left and right of the fall line, which riders have to pass
+//! between the poles of, and the finish line at the bottom
+
+use bevy::prelude::*;
+
+use crate::slope::height_at;
+
+/// How many gates the course has
+pub const GATE_COUNT: usize = 12;
+/// Half the gap between a gate's poles, in meters
+pub const GATE_HALF_WIDTH: f32 = 2.0;
+/// How far down the slope the first gate is
+const FIRST_GATE_Z: f32 = -30.0;
+/// Distance between one gate and the next down the slope, in meters
+const GATE_SPACING: f32 = 22.0;
+/// How far either side of the fall line gates are set, in meters
+const GATE_OFFSET: f32 = 4.5;
+/// How far down the slope the finish line is
+pub const FINISH_Z: f32 = FIRST_GATE_Z - GATE_SPACING * GATE_COUNT as f32;
+/// Half the width of the finish line, in meters
+const FINISH_HALF_WIDTH: f32 = 8.0;
+/// Height of a pole above the snow
+const POLE_HEIGHT: f32 = 1.8;
+
+/// Where the middle of a gate stands on the slope, as X across and Z down it
+pub fn gate_at(index: usize) -> Vec2 {
+    let side = if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+    // Some gates are set tighter or wider than others so no two runs through them are alike
+    let shift = 1.5 * (index as f32 * 1.7).sin();
+    Vec2::new(
+        side * (GATE_OFFSET + shift),
+        FIRST_GATE_Z - GATE_SPACING * index as f32,
+    )
+}
+
+/// Whether going from one Z to another crosses a line across the slope at `line`
+pub fn crossed(line: f32, from: f32, to: f32) -> bool {
+    from > line && to <= line
+}
+
+/// Whether a rider crossing a gate's line at X went between its poles
+pub fn through_gate(index: usize, x: f32) -> bool {
+    (x - gate_at(index).x).abs() <= GATE_HALF_WIDTH
+}
+
+/// Plugin that sets the gates and the finish line
+pub struct GatesPlugin;
+
+impl Plugin for GatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_gates);
+    }
+}
+
+/// Spawns every gate's poles and flag, alternating red and blue, and the finish line's arch
+fn setup_gates(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let pole = meshes.add(Cylinder::new(0.03, POLE_HEIGHT));
+    let flag = meshes.add(Cuboid::new(GATE_HALF_WIDTH * 2.0, 0.5, 0.02));
+    let colors = [
+        materials.add(Color::srgb(0.85, 0.1, 0.1)),
+        materials.add(Color::srgb(0.1, 0.25, 0.85)),
+    ];
+
+    for index in 0..GATE_COUNT {
+        let centre = gate_at(index);
+        let color = colors[index % colors.len()].clone();
+        for side in [-1.0, 1.0] {
+            let x = centre.x + side * GATE_HALF_WIDTH;
+            commands.spawn((
+                Mesh3d(pole.clone()),
+                MeshMaterial3d(color.clone()),
+                Transform::from_xyz(x, height_at(x, centre.y) + POLE_HEIGHT / 2.0, centre.y),
+            ));
+        }
+        commands.spawn((
+            Mesh3d(flag.clone()),
+            MeshMaterial3d(color),
+            Transform::from_xyz(
+                centre.x,
+                height_at(centre.x, centre.y) + POLE_HEIGHT - 0.25,
+                centre.y,
+            ),
+        ));
+    }
+
+    let finish = materials.add(Color::srgb(0.15, 0.15, 0.2));
+    let post = meshes.add(Cuboid::new(0.3, 4.0, 0.3));
+    for side in [-1.0, 1.0] {
+        let x = side * FINISH_HALF_WIDTH;
+        commands.spawn((
+            Mesh3d(post.clone()),
+            MeshMaterial3d(finish.clone()),
+            Transform::from_xyz(x, height_at(x, FINISH_Z) + 2.0, FINISH_Z),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(FINISH_HALF_WIDTH * 2.0 + 0.3, 0.8, 0.3))),
+        MeshMaterial3d(finish),
+        Transform::from_xyz(0.0, height_at(0.0, FINISH_Z) + 4.4, FINISH_Z),
+    ));
+}
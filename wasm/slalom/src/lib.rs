@@ -0,0 +1,340 @@
+//! Bevy snowboard slalom game
+
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{
+        CharacterLength, Collider, KinematicCharacterController,
+        KinematicCharacterControllerOutput, RigidBody,
+    },
+};
+use gates::{gate_at, GatesPlugin, GATE_COUNT};
+use phase::{SlalomPhase, SlalomPhasePlugin};
+use runs::{NewGame, Run, RunsPlugin};
+use slope::{height_at, SlopePlugin};
+use spjorts_core::{
+    communication::JsMessage, menu::MenuAction, settings::GameSettings, spectator::is_playing,
+    turns::TurnPlugin, ActionReader,
+};
+
+pub mod gates;
+pub mod phase;
+pub mod runs;
+pub mod slope;
+pub mod timesheet;
+
+/// How far the controller has to be rolled to carve as hard as the board can, in radians
+const FULL_STEER_ROLL: f32 = 0.7;
+/// How far the controller has to be pitched forward to tuck all the way down, in radians
+const FULL_CROUCH_PITCH: f32 = 0.6;
+/// How fast a full carve turns the board, in radians per second
+const TURN_RATE: f32 = 1.4;
+/// Furthest the board can point across the slope, in radians from straight down it
+const MAX_HEADING: f32 = 1.2;
+/// How much of the board's turning is lost in a full tuck
+const TUCK_TURN_LOSS: f32 = 0.4;
+/// How hard the air holds back an upright rider, per meter per second squared
+const AIR_DRAG: f32 = 0.009;
+/// How much of the air's drag a full tuck cuts
+const TUCK_DRAG_CUT: f32 = 0.5;
+/// How hard carving scrubs off speed, per second at a full carve
+const CARVE_DRAG: f32 = 0.6;
+/// Fastest a rider can go, in meters per second
+const MAX_SPEED: f32 = 30.0;
+/// Speed a rider leaves the start gate at, in meters per second
+const PUSH_OFF_SPEED: f32 = 3.0;
+/// Acceleration riders fall at
+const GRAVITY: f32 = 9.81;
+/// Half the height of the rider's capsule between its rounded ends
+const RIDER_HALF_HEIGHT: f32 = 0.5;
+/// Radius of the rider's capsule
+const RIDER_RADIUS: f32 = 0.3;
+/// How far behind the rider the camera follows
+const CAMERA_BACK: f32 = 7.0;
+/// How far above the rider the camera sits
+const CAMERA_UP: f32 = 3.0;
+/// How quickly the camera catches up with the rider, higher is snappier
+const CAMERA_SMOOTHING: f32 = 4.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(SlalomPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(SlopePlugin)
+    .add_plugins(GatesPlugin)
+    .add_plugins(RunsPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.6, 0.78, 0.95)))
+    .init_resource::<Board>()
+    .add_event::<PushOff>()
+    .add_systems(Startup, spawn_rider)
+    .add_systems(OnEnter(SlalomPhase::Ready), return_to_start)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            push_off.run_if(in_state(SlalomPhase::Ready)),
+            ride.run_if(in_state(SlalomPhase::Riding)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, (lean_rider, follow_rider, draw_line));
+});
+
+/// How the rider on the course is standing on the board
+#[derive(Resource, Debug, Default)]
+pub struct Board {
+    /// How hard the rider is carving, from -1 for a full left turn to 1 for a full right turn
+    pub steer: f32,
+    /// How far the rider is tucked down, from 0 standing tall to 1 in a full tuck
+    pub crouch: f32,
+}
+
+/// The rider in the start gate pushed off down the course
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PushOff;
+
+/// The rider, moved down the slope by the character controller
+#[derive(Component, Debug, Default)]
+pub struct Rider {
+    /// Which way the board points, in radians from straight down the slope. Positive turns left
+    pub heading: f32,
+    /// How fast the rider is going across the snow, in meters per second
+    pub speed: f32,
+    /// How fast the rider is falling while off the snow, in meters per second
+    fall: f32,
+}
+
+impl Rider {
+    /// Which way across the snow the board points
+    pub fn direction(&self) -> Vec3 {
+        Quat::from_rotation_y(self.heading) * Vec3::NEG_Z
+    }
+}
+
+/// The rider's body, which leans into turns and crouches into a tuck
+#[derive(Component, Debug)]
+struct RiderBody;
+
+/// Where riders push off from, standing on the snow
+fn start_position() -> Vec3 {
+    Vec3::new(
+        0.0,
+        height_at(0.0, 0.0) + RIDER_HALF_HEIGHT + RIDER_RADIUS,
+        0.0,
+    )
+}
+
+/// Spawns the rider and their board in the start gate
+fn spawn_rider(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn((
+            Transform::from_translation(start_position()),
+            Visibility::Visible,
+            RigidBody::KinematicPositionBased,
+            Collider::capsule_y(RIDER_HALF_HEIGHT, RIDER_RADIUS),
+            KinematicCharacterController {
+                snap_to_ground: Some(CharacterLength::Absolute(0.5)),
+                max_slope_climb_angle: 60_f32.to_radians(),
+                min_slope_slide_angle: 70_f32.to_radians(),
+                ..default()
+            },
+            Rider::default(),
+        ))
+        .with_children(|rider| {
+            rider.spawn((
+                Mesh3d(meshes.add(Cuboid::new(0.3, 0.02, 1.5))),
+                MeshMaterial3d(materials.add(Color::srgb(0.9, 0.4, 0.1))),
+                Transform::from_xyz(0.0, -RIDER_HALF_HEIGHT - RIDER_RADIUS + 0.01, 0.0),
+            ));
+            rider
+                .spawn((
+                    Transform::from_xyz(0.0, -RIDER_HALF_HEIGHT - RIDER_RADIUS, 0.0),
+                    Visibility::Inherited,
+                    RiderBody,
+                ))
+                .with_children(|body| {
+                    body.spawn((
+                        Mesh3d(meshes.add(Capsule3d::new(RIDER_RADIUS, RIDER_HALF_HEIGHT * 2.0))),
+                        MeshMaterial3d(materials.add(Color::srgb(0.15, 0.3, 0.7))),
+                        Transform::from_xyz(0.0, RIDER_HALF_HEIGHT + RIDER_RADIUS, 0.0),
+                    ));
+                });
+        });
+}
+
+/// Puts the rider back in the start gate, standing still and pointing down the slope
+fn return_to_start(
+    mut riders: Query<'_, '_, (&mut Transform, &mut Rider)>,
+    mut board: ResMut<'_, Board>,
+) {
+    for (mut transform, mut rider) in &mut riders {
+        *rider = Rider::default();
+        *transform = Transform::from_translation(start_position());
+    }
+    *board = Board::default();
+}
+
+/// Everything input handling changes besides the board itself
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Riders to send off from the start gate
+    push_offs: EventWriter<'w, PushOff>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: roll carves the board and pitching forward tucks down, and A pushes
+/// off from the start gate. A starts a new race once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut board: ResMut<'_, Board>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<SlalomPhase>>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == SlalomPhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == SlalomPhase::Ready => {
+                effects.push_offs.send(PushOff);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let orientation = settings.apply_rotation(orientation);
+                board.steer = (orientation.roll / FULL_STEER_ROLL).clamp(-1.0, 1.0);
+                board.crouch = (-orientation.pitch / FULL_CROUCH_PITCH).clamp(0.0, 1.0);
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Sends the rider in the start gate off down the course
+fn push_off(
+    mut push_offs: EventReader<'_, '_, PushOff>,
+    mut riders: Query<'_, '_, &mut Rider>,
+    mut next_phase: ResMut<'_, NextState<SlalomPhase>>,
+) {
+    if push_offs.read().last().is_none() {
+        return;
+    }
+
+    for mut rider in &mut riders {
+        rider.speed = PUSH_OFF_SPEED;
+    }
+    next_phase.set(SlalomPhase::Riding);
+}
+
+/// Carves the board the way the rider leans and speeds them up with the fall of the slope,
+/// held back by the air and by carving, then asks the character controller to move them
+fn ride(
+    mut riders: Query<
+        '_,
+        '_,
+        (
+            &Transform,
+            &mut Rider,
+            &mut KinematicCharacterController,
+            Option<&KinematicCharacterControllerOutput>,
+        ),
+    >,
+    board: Res<'_, Board>,
+    time: Res<'_, Time>,
+) {
+    let secs = time.delta_secs();
+    for (transform, mut rider, mut controller, output) in &mut riders {
+        let turn = TURN_RATE * (1.0 - board.crouch * TUCK_TURN_LOSS);
+        rider.heading =
+            (rider.heading - board.steer * turn * secs).clamp(-MAX_HEADING, MAX_HEADING);
+
+        let here = transform.translation;
+        let ahead = here + rider.direction();
+        let grade = height_at(here.x, here.z) - height_at(ahead.x, ahead.z);
+        let drag = AIR_DRAG * (1.0 - board.crouch * TUCK_DRAG_CUT) * rider.speed * rider.speed
+            + CARVE_DRAG * board.steer.abs() * rider.speed;
+        rider.speed = (rider.speed + (GRAVITY * grade - drag) * secs).clamp(0.0, MAX_SPEED);
+
+        rider.fall = if output.is_some_and(|output| output.grounded) {
+            0.0
+        } else {
+            rider.fall - GRAVITY * secs
+        };
+        controller.translation =
+            Some((rider.direction() * rider.speed + Vec3::Y * rider.fall) * secs);
+    }
+}
+
+/// Turns the rider to face down their line, leaning the body into the carve and lowering it
+/// into a tuck
+fn lean_rider(
+    mut riders: Query<'_, '_, (&mut Transform, &Rider), Without<RiderBody>>,
+    mut bodies: Query<'_, '_, &mut Transform, With<RiderBody>>,
+    board: Res<'_, Board>,
+) {
+    for (mut transform, rider) in &mut riders {
+        transform.rotation = Quat::from_rotation_y(rider.heading);
+    }
+    for mut body in &mut bodies {
+        body.rotation = Quat::from_rotation_z(-board.steer * 0.4);
+        body.scale = Vec3::new(1.0, 1.0 - board.crouch * 0.35, 1.0);
+    }
+}
+
+/// Follows the rider from behind and above, looking down their line
+fn follow_rider(
+    mut cameras: Query<'_, '_, &mut Transform, (With<Camera3d>, Without<Rider>)>,
+    riders: Query<'_, '_, (&Transform, &Rider)>,
+    time: Res<'_, Time>,
+) {
+    let (Ok(mut camera), Ok((rider, state))) = (cameras.get_single_mut(), riders.get_single())
+    else {
+        return;
+    };
+    // Straight down the slope behind a rider carving across it keeps more of the course in view
+    let behind = (state.direction() + Vec3::NEG_Z).normalize() * -CAMERA_BACK;
+    let goal = rider.translation + behind + Vec3::Y * CAMERA_UP;
+    let blend = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+    camera.translation = camera.translation.lerp(goal, blend);
+    camera.look_at(rider.translation, Vec3::Y);
+}
+
+/// Marks the middle of the next gate while riding, when the aim guide is on
+fn draw_line(
+    mut gizmos: Gizmos<'_, '_>,
+    run: Res<'_, Run>,
+    phase: Res<'_, State<SlalomPhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || *phase.get() == SlalomPhase::GameOver || run.next_gate >= GATE_COUNT {
+        return;
+    }
+    let gate = gate_at(run.next_gate);
+    gizmos.circle(
+        Isometry3d::new(
+            Vec3::new(gate.x, height_at(gate.x, gate.y) + 0.05, gate.y),
+            Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+        ),
+        0.5,
+        Color::srgb(1.0, 0.8, 0.1),
+    );
+}
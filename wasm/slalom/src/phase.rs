@@ -0,0 +1,27 @@
+//! Phases a slalom race moves through, from the start gate to the final results
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the race is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SlalomPhase {
+    /// The rider up next is waiting in the start gate to push off
+    #[default]
+    Ready,
+    /// The rider is on their way down the course against the clock
+    Riding,
+    /// The rider crossed the finish line and their time is up
+    Finished,
+    /// Every rider has done every run and the final results are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the race is in
+pub struct SlalomPhasePlugin;
+
+impl Plugin for SlalomPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SlalomPhase>();
+    }
+}
@@ -0,0 +1,304 @@
+//! Runs down the course: timing the rider, checking them through every gate and across the
+//! finish line, and handing the start gate to the next rider, all shown on the shared scorecard
+//! HUD
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    gates::{crossed, gate_at, through_gate, FINISH_Z, GATE_COUNT},
+    phase::SlalomPhase,
+    timesheet::{RunTime, Timesheet, MISSED_GATE_SECS, RUNS},
+    Rider,
+};
+
+/// How long a finished run's time stays up before the next rider goes, in seconds
+const FINISHED_SECS: f32 = 3.0;
+/// Meters per second in a kilometer per hour
+const KMH: f32 = 3.6;
+
+/// Asks for the race to be started over from the first rider's first run
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// The run in progress
+#[derive(Resource, Serialize, Debug, Clone, Default)]
+pub struct Run {
+    /// Seconds on the clock since the rider pushed off
+    pub secs: f32,
+    /// The gate the rider has to go through next
+    pub next_gate: usize,
+    /// Gates the rider has gone the wrong side of so far
+    pub missed: usize,
+    /// How far down the slope the rider was when gates were last checked
+    last_z: f32,
+}
+
+/// Counts down before the next rider takes the start gate
+#[derive(Resource, Debug)]
+struct Finishing(Timer);
+
+impl Default for Finishing {
+    fn default() -> Self {
+        Self(Timer::from_seconds(FINISHED_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct SlalomSnapshot<'a> {
+    /// Rider on the course
+    player: usize,
+    /// The run being made, starting from zero
+    run: usize,
+    /// Every rider's finished runs
+    timesheet: &'a Timesheet,
+    /// The run in progress
+    current: &'a Run,
+    /// Where the race is at
+    phase: SlalomPhase,
+}
+
+/// Plugin that times runs and shows them on the scorecard HUD
+pub struct RunsPlugin;
+
+impl Plugin for RunsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Timesheet>()
+            .init_resource::<Run>()
+            .init_resource::<Finishing>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_match.run_if(resource_changed::<TurnManager>),
+                    (time_run, check_gates).run_if(in_state(SlalomPhase::Riding)),
+                    next_run.run_if(in_state(SlalomPhase::Finished)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(SlalomPhase::Ready), reset_run)
+            .add_systems(OnEnter(SlalomPhase::Finished), reset_finishing)
+            .add_systems(
+                OnEnter(SlalomPhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(SlalomPhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh race whenever the number of riders changes
+fn fit_match(turns: Res<'_, TurnManager>, mut timesheet: ResMut<'_, Timesheet>) {
+    if timesheet.players() != turns.players() {
+        *timesheet = Timesheet::new(turns.players());
+    }
+}
+
+/// Clears the clock and the gates for the rider in the start gate
+fn reset_run(mut run: ResMut<'_, Run>) {
+    *run = Run::default();
+}
+
+/// Runs the clock while the rider is on the course
+fn time_run(mut run: ResMut<'_, Run>, time: Res<'_, Time>) {
+    run.secs += time.delta_secs();
+}
+
+/// Checks the rider through each gate as they pass its line, adding a penalty for every one
+/// they go the wrong side of, and stops the clock at the finish line
+fn check_gates(
+    mut run: ResMut<'_, Run>,
+    mut timesheet: ResMut<'_, Timesheet>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<SlalomPhase>>,
+    riders: Query<'_, '_, &Transform, With<Rider>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Ok(rider) = riders.get_single() else {
+        return;
+    };
+    let Vec3 { x, z, .. } = rider.translation;
+
+    while run.next_gate < GATE_COUNT && crossed(gate_at(run.next_gate).y, run.last_z, z) {
+        if !through_gate(run.next_gate, x) {
+            run.missed += 1;
+            banner.show(format!("Missed gate! +{MISSED_GATE_SECS:.0}s"));
+        }
+        run.next_gate += 1;
+    }
+
+    if crossed(FINISH_Z, run.last_z, z) {
+        // Gates skipped around entirely count as missed too
+        run.missed += GATE_COUNT - run.next_gate;
+        run.next_gate = GATE_COUNT;
+        let finished = RunTime {
+            secs: run.secs,
+            missed: run.missed,
+        };
+        timesheet.record(turns.current(), finished);
+        banner.show(format!(
+            "Player {}: {:.2}s",
+            turns.current() + 1,
+            finished.total()
+        ));
+        next_phase.set(SlalomPhase::Finished);
+    }
+    run.last_z = z;
+}
+
+/// Gives riders a moment to see the run's time
+fn reset_finishing(mut finishing: ResMut<'_, Finishing>) {
+    finishing.0.reset();
+}
+
+/// Hands the start gate to the next rider, finishing the race once everyone has made every run
+fn next_run(
+    mut finishing: ResMut<'_, Finishing>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<SlalomPhase>>,
+    time: Res<'_, Time>,
+) {
+    if !finishing.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    turns.advance();
+    if turns.round() >= RUNS {
+        next_phase.set(SlalomPhase::GameOver);
+    } else {
+        next_phase.set(SlalomPhase::Ready);
+    }
+}
+
+/// Starts the race over from the first rider's first run
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut timesheet: ResMut<'_, Timesheet>,
+    mut next_phase: ResMut<'_, NextState<SlalomPhase>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *timesheet = Timesheet::new(turns.players());
+    next_phase.set(SlalomPhase::Ready);
+}
+
+/// How a run reads on the scorecard, with any penalty seconds after it
+fn run_label(run: &RunTime) -> String {
+    match run.missed {
+        0 => format!("{:.2}", run.secs),
+        missed => format!("{:.2}+{:.0}", run.secs, missed as f32 * MISSED_GATE_SECS),
+    }
+}
+
+/// Fills in the scorecard HUD with every rider's runs and total, and the clock, gates and speed
+/// of the run in progress
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    timesheet: Res<'_, Timesheet>,
+    run: Res<'_, Run>,
+    turns: Res<'_, TurnManager>,
+    phase: Res<'_, State<SlalomPhase>>,
+    riders: Query<'_, '_, &Rider>,
+) {
+    let rows = (0..timesheet.players())
+        .map(|player| {
+            let mut runs: Vec<String> = timesheet.runs(player).iter().map(run_label).collect();
+            runs.resize(RUNS, "-".to_string());
+            format!(
+                "Player {}: {:.2}s ({})",
+                player + 1,
+                timesheet.total(player),
+                runs.join(" / ")
+            )
+        })
+        .collect();
+
+    let speed = riders.get_single().map_or(0.0, |rider| rider.speed);
+    let mut footer = format!(
+        "Time: {:.2}s\nGates: {} of {GATE_COUNT}, missed {}\nSpeed: {:.0} km/h",
+        run.secs,
+        run.next_gate,
+        run.missed,
+        speed * KMH
+    );
+    if *phase.get() == SlalomPhase::Ready {
+        footer.push_str("\nPress A to push off");
+    }
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Slalom, run {} of {RUNS}", turns.round().min(RUNS - 1) + 1),
+        rows,
+        footer,
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every rider's total time once the race is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, timesheet: Res<'_, Timesheet>) {
+    let mut lines = vec!["Final Times".to_string()];
+    let leaders = timesheet.leaders();
+    if let [winner] = leaders[..] {
+        lines.push(format!("Player {} wins!", winner + 1));
+    } else if timesheet.players() > 1 {
+        lines.push("It's a tie!".to_string());
+    }
+    for player in 0..timesheet.players() {
+        lines.push(format!(
+            "Player {}: {:.2}s",
+            player + 1,
+            timesheet.total(player)
+        ));
+    }
+    lines.push("Press A to race again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final times when a new race starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every rider's points back to the page once the race is over, so it can submit them to
+/// the server
+fn submit_result(timesheet: Res<'_, Timesheet>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..timesheet.players())
+        .map(|player| timesheet.points(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    timesheet: Res<'_, Timesheet>,
+    run: Res<'_, Run>,
+    phase: Res<'_, State<SlalomPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&SlalomSnapshot {
+        player: turns.current(),
+        run: turns.round(),
+        timesheet: &timesheet,
+        current: &run,
+        phase: *phase.get(),
+    });
+}
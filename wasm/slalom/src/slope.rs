@@ -0,0 +1,182 @@
+//! The slope: a long run of rolling snow falling away from the start, generated from a single
+//! height function that both the snow's mesh and its collider are built from
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+use bevy_rapier3d::prelude::{Collider, RigidBody};
+
+/// How far the slope runs down from the start, in meters
+pub const SLOPE_LENGTH: f32 = 320.0;
+/// Half the width of the slope between the trees
+pub const HALF_WIDTH: f32 = 20.0;
+/// How many meters the slope drops for every meter it runs
+const GRADE: f32 = 0.28;
+/// Spacing between the points the slope is generated at, in meters
+const GRID_STEP: f32 = 2.0;
+/// Spacing between the trees lining the slope, in meters
+const TREE_SPACING: f32 = 12.0;
+/// Half the thickness of the invisible walls keeping riders on the slope
+const WALL_HALF_THICKNESS: f32 = 0.5;
+
+/// Height of the snow at a spot. The slope falls away down -Z from the start at the origin,
+/// with bumps across and down it that never turn it uphill
+pub fn height_at(x: f32, z: f32) -> f32 {
+    z * GRADE + 0.8 * (x * 0.21).sin() * (z * 0.13).cos() + 1.2 * (z * 0.037 + 0.6).sin()
+}
+
+/// Direction straight up out of the snow at a spot
+fn normal_at(x: f32, z: f32) -> Vec3 {
+    let step = 0.1;
+    let dx = height_at(x + step, z) - height_at(x - step, z);
+    let dz = height_at(x, z + step) - height_at(x, z - step);
+    Vec3::new(-dx, 2.0 * step, -dz).normalize()
+}
+
+/// Points across and down the slope it's generated at, as columns across and rows down
+fn grid() -> (usize, usize) {
+    let columns = (HALF_WIDTH * 2.0 / GRID_STEP) as usize + 1;
+    let rows = (SLOPE_LENGTH / GRID_STEP) as usize + 1;
+    (columns, rows)
+}
+
+/// Builds the snow's mesh from [`height_at`], in world space
+fn slope_mesh() -> Mesh {
+    let (columns, rows) = grid();
+    let mut positions = Vec::with_capacity(columns * rows);
+    let mut normals = Vec::with_capacity(columns * rows);
+    let mut uvs = Vec::with_capacity(columns * rows);
+
+    for row in 0..rows {
+        let z = -(row as f32) * GRID_STEP;
+        for column in 0..columns {
+            let x = column as f32 * GRID_STEP - HALF_WIDTH;
+            positions.push([x, height_at(x, z), z]);
+            normals.push(normal_at(x, z).to_array());
+            uvs.push([
+                column as f32 / (columns - 1) as f32,
+                row as f32 / (rows - 1) as f32,
+            ]);
+        }
+    }
+
+    // Rows run down the slope away from the camera, so each quad is wound with its far row
+    // first to face up
+    let mut indices = Vec::with_capacity((columns - 1) * (rows - 1) * 6);
+    for row in 0..rows - 1 {
+        for column in 0..columns - 1 {
+            let near = (row * columns + column) as u32;
+            let far = near + columns as u32;
+            indices.extend_from_slice(&[far, near, far + 1, far + 1, near, near + 1]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Builds the snow's collider from [`height_at`], centred on the middle of the slope
+fn slope_collider() -> Collider {
+    let (columns, rows) = grid();
+    // Heightfields take their heights column by column, with rows running along Z from the
+    // bottom of the slope up to the start
+    let mut heights = Vec::with_capacity(columns * rows);
+    for column in 0..columns {
+        let x = column as f32 * GRID_STEP - HALF_WIDTH;
+        for row in 0..rows {
+            let z = row as f32 * GRID_STEP - SLOPE_LENGTH;
+            heights.push(height_at(x, z));
+        }
+    }
+    Collider::heightfield(
+        heights,
+        rows,
+        columns,
+        Vec3::new(HALF_WIDTH * 2.0, 1.0, SLOPE_LENGTH),
+    )
+}
+
+/// Plugin that generates the slope and lines it with trees
+pub struct SlopePlugin;
+
+impl Plugin for SlopePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_slope);
+    }
+}
+
+/// Spawns the snow, the walls and trees down either side, the camera and the light
+fn setup_slope(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(slope_mesh())),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.95, 0.97, 1.0),
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+        Name::new("Slope"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, 0.0, -SLOPE_LENGTH / 2.0),
+        RigidBody::Fixed,
+        slope_collider(),
+    ));
+
+    // The walls are tall enough to cover the whole drop, so they can stay plain boxes
+    let drop = SLOPE_LENGTH * GRADE;
+    for side in [-1.0, 1.0] {
+        commands.spawn((
+            Transform::from_xyz(
+                side * (HALF_WIDTH + WALL_HALF_THICKNESS),
+                -drop / 2.0,
+                -SLOPE_LENGTH / 2.0,
+            ),
+            RigidBody::Fixed,
+            Collider::cuboid(WALL_HALF_THICKNESS, drop, SLOPE_LENGTH / 2.0),
+        ));
+    }
+
+    let tree = meshes.add(Cone {
+        radius: 1.2,
+        height: 4.0,
+    });
+    let needles = materials.add(Color::srgb(0.1, 0.35, 0.2));
+    let trees = (SLOPE_LENGTH / TREE_SPACING) as usize;
+    for row in 0..=trees {
+        let z = -(row as f32) * TREE_SPACING;
+        for side in [-1.0, 1.0] {
+            let x = side * (HALF_WIDTH + 1.5);
+            commands.spawn((
+                Mesh3d(tree.clone()),
+                MeshMaterial3d(needles.clone()),
+                Transform::from_xyz(x, height_at(x, z) + 2.0, z),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, height_at(0.0, 0.0) + 4.0, 8.0)
+            .looking_at(Vec3::new(0.0, height_at(0.0, -20.0), -20.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(10.0, 50.0, 20.0).looking_at(Vec3::new(0.0, -40.0, -150.0), Vec3::Y),
+    ));
+}
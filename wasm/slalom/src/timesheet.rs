@@ -0,0 +1,90 @@
+//! Race times: every rider's runs, the seconds added for missed gates, and the points sent to
+//! the server once the race is over
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Runs every rider makes, with their times added together
+pub const RUNS: usize = 2;
+/// Seconds added to a run for every gate missed
+pub const MISSED_GATE_SECS: f32 = 5.0;
+/// Total time across every run that earns no points, in seconds
+const PAR_SECS: f32 = 90.0;
+/// Points earned for every second under par
+const POINTS_PER_SEC: f32 = 100.0;
+
+/// One run down the course
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RunTime {
+    /// Seconds from the start gate to the finish line
+    pub secs: f32,
+    /// Gates the rider went the wrong side of
+    pub missed: usize,
+}
+
+impl RunTime {
+    /// The run's time with the penalties for missed gates added
+    pub fn total(&self) -> f32 {
+        self.secs + self.missed as f32 * MISSED_GATE_SECS
+    }
+}
+
+/// Every rider's runs so far
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Timesheet {
+    /// Runs each rider has finished, in turn order
+    runs: Vec<Vec<RunTime>>,
+}
+
+impl Default for Timesheet {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Timesheet {
+    /// Starts a race for a number of riders with no runs
+    pub fn new(players: usize) -> Self {
+        Self {
+            runs: vec![Vec::new(); players.max(1)],
+        }
+    }
+
+    /// How many riders are in the race
+    pub fn players(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Records a rider's finished run
+    pub fn record(&mut self, player: usize, run: RunTime) {
+        if let Some(runs) = self.runs.get_mut(player) {
+            runs.push(run);
+        }
+    }
+
+    /// A rider's finished runs
+    pub fn runs(&self, player: usize) -> &[RunTime] {
+        self.runs.get(player).map_or(&[], Vec::as_slice)
+    }
+
+    /// A rider's time across every finished run, penalties included
+    pub fn total(&self, player: usize) -> f32 {
+        self.runs(player).iter().map(RunTime::total).sum()
+    }
+
+    /// Points for a rider's total time, higher for faster so the server ranks them like every
+    /// other game's scores
+    pub fn points(&self, player: usize) -> u32 {
+        ((PAR_SECS - self.total(player)) * POINTS_PER_SEC).max(0.0) as u32
+    }
+
+    /// The riders on the fastest total, more than one when they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.total(player))
+            .fold(f32::INFINITY, f32::min);
+        (0..self.players())
+            .filter(|player| self.total(*player) == best)
+            .collect()
+    }
+}
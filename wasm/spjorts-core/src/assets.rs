@@ -0,0 +1,22 @@
+//! Asset path configuration for games served under a sub-path
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+
+/// Base path all game assets are loaded relative to
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetBasePath(pub String);
+
+impl AssetBasePath {
+    /// Joins an asset path onto the base path, so `frontend/sprites/ball.png` becomes
+    /// `/frontend/sprites/ball.png` by default or `/spjorts/frontend/sprites/ball.png` when mounted
+    /// under `/spjorts`
+    pub fn join(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.0.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+}
@@ -0,0 +1,75 @@
+//! Motion capture of the raw rotations a game receives, so firmware developers can analyze their
+//! filtering offline. The Runner hands the capture to JavaScript as CSV through `export_csv`
+
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{Arc, RwLock},
+};
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+
+use crate::communication::Orientation;
+
+/// Most rows kept, about an hour of readings at the firmware's 20 Hz. The oldest are dropped
+/// past this
+const MAX_ROWS: usize = 72_000;
+
+/// Header row of the exported CSV
+const CSV_HEADER: &str = "player,received_at_ms,sent_at_ms,pitch,roll,yaw";
+
+/// A rotation as it was received
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureRow {
+    /// Player slot of the controller that sent it
+    pub player: usize,
+    /// When the game received it, in milliseconds since the Unix epoch
+    pub received_at_ms: f64,
+    /// When the controller read it in milliseconds since the Unix epoch, if it was stamped
+    pub sent_at_ms: Option<f64>,
+    /// The orientation as the controller sent it, before any recentering or settings
+    pub orientation: Orientation,
+}
+
+/// Rotations recorded by a game, oldest first
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Default)]
+pub struct MotionCapture(Arc<RwLock<VecDeque<CaptureRow>>>);
+
+impl MotionCapture {
+    /// Records a rotation, dropping the oldest once [`MAX_ROWS`] are kept
+    pub fn record(&self, row: CaptureRow) {
+        let mut rows = self.0.write().unwrap();
+        if rows.len() >= MAX_ROWS {
+            rows.pop_front();
+        }
+        rows.push_back(row);
+    }
+
+    /// Drops everything recorded so far
+    pub fn clear(&self) {
+        self.0.write().unwrap().clear();
+    }
+
+    /// Gets everything recorded as CSV with a header row. Unstamped rotations leave `sent_at_ms`
+    /// empty
+    pub fn csv(&self) -> String {
+        let rows = self.0.read().unwrap();
+        let mut csv = String::from(CSV_HEADER);
+        for row in rows.iter() {
+            let sent_at = row.sent_at_ms.map(|at| at.to_string()).unwrap_or_default();
+            let _ = write!(
+                csv,
+                "\n{},{},{},{},{},{}",
+                row.player,
+                row.received_at_ms,
+                sent_at,
+                row.orientation.pitch,
+                row.orientation.roll,
+                row.orientation.yaw
+            );
+        }
+        csv
+    }
+}
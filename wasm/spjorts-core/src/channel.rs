@@ -0,0 +1,245 @@
+//! Game input channel with an optional backpressure policy
+
+use crossbeam_channel::{Receiver, SendError, Sender};
+
+use crate::{communication::JsMessage, Communication};
+
+/// How much input can back up in a channel before rotations start being dropped
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Never drop anything, the channel can grow without limit
+    #[default]
+    Unbounded,
+    /// Keep at most this many messages queued by dropping the oldest `Rotate` messages. Button
+    /// and session messages are never dropped
+    DropOldestRotate(usize),
+    /// Keep only the newest pending `Rotate` message for each player. Button and session
+    /// messages are never dropped and keep their order
+    CoalesceRotate,
+}
+
+/// Write half of a game's input channel
+#[derive(Clone)]
+pub struct InputWriter {
+    /// Channel into the game
+    sender: Sender<Communication>,
+    /// Read half kept around so stale rotations can be pulled back out
+    receiver: Option<Receiver<Communication>>,
+    /// The channel's backpressure policy
+    policy: Backpressure,
+    /// Channel `SetPlayers`, `SetBot` and `Settings` messages are routed to instead, if core
+    /// systems consume them
+    session: Option<Sender<Communication>>,
+}
+
+/// Creates a game's input channel with the given backpressure policy
+pub fn input_channel(policy: Backpressure) -> (InputWriter, Receiver<Communication>) {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let writer = InputWriter {
+        sender,
+        receiver: match policy {
+            Backpressure::Unbounded => None,
+            Backpressure::DropOldestRotate(_) | Backpressure::CoalesceRotate => {
+                Some(receiver.clone())
+            }
+        },
+        policy,
+        session: None,
+    };
+
+    (writer, receiver)
+}
+
+impl From<Sender<Communication>> for InputWriter {
+    fn from(sender: Sender<Communication>) -> Self {
+        Self {
+            sender,
+            receiver: None,
+            policy: Backpressure::Unbounded,
+            session: None,
+        }
+    }
+}
+
+impl InputWriter {
+    /// The channel's backpressure policy
+    pub fn policy(&self) -> Backpressure {
+        self.policy
+    }
+
+    /// Routes `SetPlayers`, `SetBot` and `Settings` messages to a separate channel so core systems
+    /// can consume them without competing with the game for its input
+    pub fn with_session_channel(mut self, session: Sender<Communication>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Sends a message into the game, making room for new rotations according to the channel's
+    /// backpressure policy
+    pub fn send(&self, msg: Communication) -> Result<(), SendError<Communication>> {
+        if let (
+            Some(session),
+            JsMessage::SetPlayers(..) | JsMessage::SetBot(..) | JsMessage::Settings { .. },
+        ) = (&self.session, &msg)
+        {
+            return session.send(msg);
+        }
+
+        if let (Some(receiver), Some(player)) = (&self.receiver, msg.rotation_player()) {
+            match self.policy {
+                Backpressure::DropOldestRotate(capacity) if self.sender.len() >= capacity => {
+                    self.drop_oldest_rotations(receiver, capacity.saturating_sub(1), None)?
+                }
+                Backpressure::CoalesceRotate if !self.sender.is_empty() => {
+                    self.drop_oldest_rotations(receiver, 0, Some(player))?
+                }
+                _ => {}
+            }
+        }
+
+        self.sender.send(msg)
+    }
+
+    /// Drains the queue and re-sends it in order, skipping the oldest rotations until at most
+    /// `target` messages remain. If a player is given, only their rotations are dropped
+    fn drop_oldest_rotations(
+        &self,
+        receiver: &Receiver<Communication>,
+        target: usize,
+        player: Option<usize>,
+    ) -> Result<(), SendError<Communication>> {
+        let droppable = |msg: &Communication| {
+            msg.rotation_player()
+                .is_some_and(|rotated| player.is_none_or(|player| player == rotated))
+        };
+        let queued: Vec<_> = receiver.try_iter().collect();
+        let rotations = queued.iter().filter(|msg| droppable(msg)).count();
+        let mut to_drop = queued.len().saturating_sub(target).min(rotations);
+
+        for msg in queued {
+            if to_drop > 0 && droppable(&msg) {
+                to_drop -= 1;
+                continue;
+            }
+            self.sender.send(msg)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{input_channel, Backpressure};
+    use crate::communication::{JsMessage, Orientation};
+
+    /// A rotation to a yaw, so rotations can be told apart
+    fn rotate(yaw: f32) -> JsMessage {
+        JsMessage::Rotate(Orientation::new(0.0, 0.0, yaw))
+    }
+
+    #[test]
+    fn drop_oldest_rotate_drops_the_oldest_rotations_first() {
+        let (writer, receiver) = input_channel(Backpressure::DropOldestRotate(3));
+        for yaw in 1..=5 {
+            writer.send(rotate(yaw as f32)).unwrap();
+        }
+
+        let queued: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(queued, vec![rotate(3.0), rotate(4.0), rotate(5.0)]);
+    }
+
+    #[test]
+    fn drop_oldest_rotate_never_drops_other_messages() {
+        let (writer, receiver) = input_channel(Backpressure::DropOldestRotate(2));
+        writer.send(JsMessage::ButtonA).unwrap();
+        writer.send(rotate(1.0)).unwrap();
+        writer.send(JsMessage::ButtonB).unwrap();
+        writer.send(JsMessage::SetPlayers(2)).unwrap();
+        writer.send(rotate(2.0)).unwrap();
+        writer.send(JsMessage::ReleaseA).unwrap();
+        writer.send(rotate(3.0)).unwrap();
+
+        let queued: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            queued,
+            vec![
+                JsMessage::ButtonA,
+                JsMessage::ButtonB,
+                JsMessage::SetPlayers(2),
+                JsMessage::ReleaseA,
+                rotate(3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_rotate_keeps_only_the_newest_rotation() {
+        let (writer, receiver) = input_channel(Backpressure::CoalesceRotate);
+        writer.send(rotate(1.0)).unwrap();
+        writer.send(JsMessage::ButtonA).unwrap();
+        writer.send(rotate(2.0)).unwrap();
+        writer
+            .send(JsMessage::SetPlayerName(0, "Ada".to_string()))
+            .unwrap();
+        writer.send(JsMessage::ButtonB).unwrap();
+        writer.send(rotate(3.0)).unwrap();
+        writer.send(JsMessage::Restart).unwrap();
+
+        let queued: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            queued,
+            vec![
+                JsMessage::ButtonA,
+                JsMessage::SetPlayerName(0, "Ada".to_string()),
+                JsMessage::ButtonB,
+                rotate(3.0),
+                JsMessage::Restart,
+            ]
+        );
+    }
+
+    #[test]
+    fn coalesce_rotate_keeps_each_players_newest_rotation() {
+        let (writer, receiver) = input_channel(Backpressure::CoalesceRotate);
+        let second = |yaw| JsMessage::Player(1, Box::new(rotate(yaw)));
+        writer.send(second(1.0)).unwrap();
+        writer.send(rotate(2.0)).unwrap();
+        writer
+            .send(JsMessage::Player(1, Box::new(JsMessage::ButtonA)))
+            .unwrap();
+        writer.send(second(3.0)).unwrap();
+
+        let queued: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            queued,
+            vec![
+                rotate(2.0),
+                JsMessage::Player(1, Box::new(JsMessage::ButtonA)),
+                second(3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn session_messages_skip_the_game() {
+        let (session, session_read) = crossbeam_channel::unbounded();
+        let (writer, receiver) = input_channel(Backpressure::Unbounded);
+        let writer = writer.with_session_channel(session);
+        let settings = JsMessage::Settings {
+            sensitivity: 1.5,
+            volume: 0.5,
+            invert_y: true,
+            aim_guide: false,
+            left_handed: true,
+        };
+        writer.send(JsMessage::SetPlayers(2)).unwrap();
+        writer.send(settings.clone()).unwrap();
+        writer.send(JsMessage::ButtonA).unwrap();
+
+        let routed: Vec<_> = session_read.try_iter().collect();
+        assert_eq!(routed, vec![JsMessage::SetPlayers(2), settings]);
+        let queued: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(queued, vec![JsMessage::ButtonA]);
+    }
+}
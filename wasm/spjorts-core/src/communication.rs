@@ -1,13 +1,147 @@
 //! Game Communication Protocol
 
+#[cfg(feature = "bevy")]
+use bevy::math::{EulerRot, Quat};
+
+/// A controller's orientation in radians
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Orientation {
+    /// Rotation about the X axis
+    pub pitch: f32,
+    /// Rotation about the Y axis
+    pub roll: f32,
+    /// Rotation about the Z axis
+    pub yaw: f32,
+}
+
+impl Orientation {
+    /// Creates a new orientation
+    pub fn new(pitch: f32, roll: f32, yaw: f32) -> Self {
+        Self { pitch, roll, yaw }
+    }
+
+    /// Converts the orientation to a quaternion
+    #[cfg(feature = "bevy")]
+    pub fn to_quat(&self) -> Quat {
+        Quat::from_euler(EulerRot::XYZ, self.pitch, self.roll, self.yaw)
+    }
+}
+
 /// All messages that can be send via a JavaScript web socket
+#[derive(Debug, Clone, PartialEq)]
 pub enum JsMessage {
-    /// Rotate by (pitch, roll, yaw)
-    Rotate(f32, f32, f32),
+    /// Rotate to an orientation
+    Rotate(Orientation),
+    /// Rotate to an orientation the controller read at a time, in milliseconds since the Unix
+    /// epoch
+    TimedRotate(Orientation, f64),
     /// Press A button
     ButtonA,
     /// Press B button
     ButtonB,
+    /// Release A button, after it has been held since its press
+    ReleaseA,
+    /// Release B button, after it has been held since its press
+    ReleaseB,
+    /// Move up in the current menu
+    MenuUp,
+    /// Move down in the current menu
+    MenuDown,
+    /// Select the highlighted menu item
+    MenuSelect,
+    /// Back out of the current menu
+    MenuBack,
     /// Set number of players in a game
     SetPlayers(usize),
+    /// Add a computer opponent with a skill level from 1 to 10, or remove it with `None`
+    SetBot(Option<u8>),
+    /// Name a player, by their index starting at 0
+    SetPlayerName(usize, String),
+    /// Start the current game over with the same players
+    Restart,
+    /// Update the player's game settings
+    Settings {
+        /// Multiplier applied to all incoming rotation data
+        sensitivity: f32,
+        /// Volume from 0.0 (muted) to 1.0
+        volume: f32,
+        /// Whether the pitch axis should be inverted
+        invert_y: bool,
+        /// Whether aiming aids should be drawn
+        aim_guide: bool,
+        /// Whether controls and layout are mirrored for left-handed play
+        left_handed: bool,
+    },
+    /// Controller input tagged with the player slot the controller is paired to, for games that
+    /// give every player their own controller
+    Player(usize, Box<JsMessage>),
+}
+
+impl JsMessage {
+    /// Splits off the player slot a message was tagged with. Untagged messages belong to the
+    /// first player
+    pub fn untag(self) -> (usize, Self) {
+        match self {
+            Self::Player(player, msg) => (player, msg.untag().1),
+            msg => (0, msg),
+        }
+    }
+
+    /// The player slot a rotation is for, if this is one
+    pub fn rotation_player(&self) -> Option<usize> {
+        match self {
+            Self::Rotate(_) | Self::TimedRotate(..) => Some(0),
+            Self::Player(player, msg) => msg.rotation_player().map(|_| *player),
+            _ => None,
+        }
+    }
+}
+
+/// All events a game can send back to JavaScript
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// A free-form notification for the surrounding page
+    Notify(String),
+    /// Asks the page to play a named sound cue. Games should skip these while muted
+    Sound(String),
+    /// A game finished, to be submitted to the server by the page
+    GameResult {
+        /// How many players took part
+        players: usize,
+        /// Each player's final score, in turn order
+        scores: Vec<u32>,
+    },
+    /// Asks the page to have the server buzz the controller's rumble motor
+    Rumble {
+        /// Motor strength, from 0 (off) to 255 (full)
+        intensity: u8,
+        /// How long to buzz for, in milliseconds
+        millis: u16,
+    },
+}
+
+impl GameEvent {
+    /// The event's kind, as seen from JavaScript
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Notify(_) => "notify",
+            Self::Sound(_) => "sound",
+            Self::GameResult { .. } => "result",
+            Self::Rumble { .. } => "rumble",
+        }
+    }
+
+    /// The event's payload, as seen from JavaScript. Results are sent as comma separated scores and
+    /// rumbles as `intensity,millis`
+    pub fn data(&self) -> String {
+        match self {
+            Self::Notify(msg) | Self::Sound(msg) => msg.clone(),
+            Self::GameResult { scores, .. } => scores
+                .iter()
+                .map(|score| score.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            Self::Rumble { intensity, millis } => format!("{intensity},{millis}"),
+        }
+    }
 }
@@ -0,0 +1,110 @@
+//! Diagnostic messages sent from inside the Bevy world back to the page
+
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+use js_sys::Function;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+/// How severe a diagnostic is
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    /// Informational, nothing went wrong
+    Info,
+    /// Something unexpected happened but the game recovered
+    Warn,
+    /// Something went wrong
+    Error,
+}
+
+impl DiagnosticLevel {
+    /// The level's name as passed to JavaScript
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A single diagnostic message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic(pub DiagnosticLevel, pub String);
+
+/// Resource games use to surface diagnostics to the page
+#[derive(Resource, Clone)]
+pub struct DiagnosticSender(pub Sender<Diagnostic>);
+
+impl DiagnosticSender {
+    /// Sends a diagnostic at the given level
+    pub fn log(&self, level: DiagnosticLevel, message: impl Into<String>) {
+        let _ = self.0.send(Diagnostic(level, message.into()));
+    }
+
+    /// Sends an informational diagnostic
+    pub fn info(&self, message: impl Into<String>) {
+        self.log(DiagnosticLevel::Info, message);
+    }
+
+    /// Sends a warning diagnostic
+    pub fn warn(&self, message: impl Into<String>) {
+        self.log(DiagnosticLevel::Warn, message);
+    }
+
+    /// Sends an error diagnostic
+    pub fn error(&self, message: impl Into<String>) {
+        self.log(DiagnosticLevel::Error, message);
+    }
+}
+
+/// JavaScript function diagnostics are handed to, called as `callback(level, message)`
+pub struct LogCallback(pub Function);
+
+/// Read half of the diagnostics channel
+#[derive(Resource)]
+struct DiagnosticReceiver(Receiver<Diagnostic>);
+
+/// Plugin that forwards diagnostics to the [`LogCallback`] if one is set, or to Bevy's log
+/// otherwise
+pub struct DiagnosticsPlugin {
+    /// Read half of the diagnostics channel
+    receiver: Receiver<Diagnostic>,
+}
+
+impl DiagnosticsPlugin {
+    /// Creates a new diagnostics plugin draining the given channel
+    pub fn new(receiver: Receiver<Diagnostic>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DiagnosticReceiver(self.receiver.clone()))
+            .add_systems(Last, forward_diagnostics);
+    }
+}
+
+/// Drains pending diagnostics, passing them to JavaScript
+fn forward_diagnostics(
+    receiver: Res<'_, DiagnosticReceiver>,
+    callback: Option<NonSend<'_, LogCallback>>,
+) {
+    for Diagnostic(level, message) in receiver.0.try_iter() {
+        match &callback {
+            Some(callback) => {
+                let _ = callback.0.call2(
+                    &JsValue::NULL,
+                    &JsValue::from_str(level.as_str()),
+                    &JsValue::from_str(&message),
+                );
+            }
+            None => match level {
+                DiagnosticLevel::Info => info!("{message}"),
+                DiagnosticLevel::Warn => warn!("{message}"),
+                DiagnosticLevel::Error => error!("{message}"),
+            },
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Focused JavaScript-facing facades over a game's communication channels
+
+use crossbeam_channel::{Receiver, SendError};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    channel::InputWriter,
+    communication::{GameEvent, JsMessage, Orientation},
+    gamepad::{self, GamepadState},
+    snapshot::SaveSlot,
+};
+
+/// Sends controller input (buttons and rotation) into a game
+#[wasm_bindgen]
+pub struct InputSender {
+    /// Channel into the game
+    sender: InputWriter,
+    /// Last polled gamepad state
+    gamepad: GamepadState,
+    /// Player slot every message is tagged with, if input is routed per player
+    player: Option<usize>,
+}
+
+impl InputSender {
+    /// Creates a new input sender
+    pub fn new(sender: impl Into<InputWriter>) -> Self {
+        Self {
+            sender: sender.into(),
+            gamepad: GamepadState::default(),
+            player: None,
+        }
+    }
+
+    /// Sends a message into the game, tagged with this sender's player slot if it has one
+    fn send(&self, msg: JsMessage) -> Result<(), SendError<JsMessage>> {
+        let msg = match self.player {
+            Some(player) => JsMessage::Player(player, Box::new(msg)),
+            None => msg,
+        };
+        self.sender.send(msg)
+    }
+}
+
+#[wasm_bindgen]
+impl InputSender {
+    /// Gets a sender whose input is tagged for a player slot, for pages that pair a controller
+    /// to each player
+    pub fn for_player(&self, player: usize) -> InputSender {
+        Self {
+            sender: self.sender.clone(),
+            gamepad: GamepadState::default(),
+            player: Some(player),
+        }
+    }
+
+    /// Press the A button
+    pub fn press_a(&mut self) {
+        self.send(JsMessage::ButtonA).expect("Press A Button")
+    }
+
+    /// Press the B button
+    pub fn press_b(&mut self) {
+        self.send(JsMessage::ButtonB).expect("Press B Button")
+    }
+
+    /// Release the A button
+    pub fn release_a(&mut self) {
+        self.send(JsMessage::ReleaseA).expect("Release A Button")
+    }
+
+    /// Release the B button
+    pub fn release_b(&mut self) {
+        self.send(JsMessage::ReleaseB).expect("Release B Button")
+    }
+
+    /// Rotate data with pitch, roll and yaw
+    pub fn rotate(&mut self, pitch: f32, roll: f32, yaw: f32) {
+        self.send(JsMessage::Rotate(Orientation::new(pitch, roll, yaw)))
+            .expect("Rotate")
+    }
+
+    /// Rotate data with pitch, roll and yaw, read by the controller at `sent_at_ms` milliseconds
+    /// since the Unix epoch
+    pub fn rotate_at(&mut self, pitch: f32, roll: f32, yaw: f32, sent_at_ms: f64) {
+        self.send(JsMessage::TimedRotate(
+            Orientation::new(pitch, roll, yaw),
+            sent_at_ms,
+        ))
+        .expect("Rotate")
+    }
+
+    /// Move up in the current menu
+    pub fn menu_up(&mut self) {
+        self.send(JsMessage::MenuUp).expect("Menu up")
+    }
+
+    /// Move down in the current menu
+    pub fn menu_down(&mut self) {
+        self.send(JsMessage::MenuDown).expect("Menu down")
+    }
+
+    /// Select the highlighted menu item
+    pub fn menu_select(&mut self) {
+        self.send(JsMessage::MenuSelect).expect("Menu select")
+    }
+
+    /// Back out of the current menu
+    pub fn menu_back(&mut self) {
+        self.send(JsMessage::MenuBack).expect("Menu back")
+    }
+
+    /// Polls the first connected browser gamepad and forwards any stick movement or button
+    /// presses as controller messages. Meant to be called once per animation frame
+    pub fn poll_gamepad(&mut self) {
+        if let Some((axes, buttons)) = gamepad::read_gamepad() {
+            for msg in self.gamepad.update(&axes, &buttons) {
+                self.send(msg).expect("Send gamepad input")
+            }
+        }
+    }
+}
+
+/// Controls session-wide state such as players and settings
+#[wasm_bindgen]
+pub struct SessionControl {
+    /// Channel into the game
+    sender: InputWriter,
+    /// Save the players are noted in, so a resumed game can be set up with them again
+    save: Option<SaveSlot>,
+}
+
+impl SessionControl {
+    /// Creates a new session controller
+    pub fn new(sender: impl Into<InputWriter>) -> Self {
+        Self {
+            sender: sender.into(),
+            save: None,
+        }
+    }
+
+    /// Notes the players the session is set up with in a save, to be exported along with it
+    pub fn recording_to(mut self, save: SaveSlot) -> Self {
+        self.save = Some(save);
+        self
+    }
+}
+
+#[wasm_bindgen]
+impl SessionControl {
+    /// Set the number of players in the game
+    pub fn set_players(&mut self, players: usize) {
+        if let Some(save) = &self.save {
+            save.record_players(players);
+        }
+        self.sender
+            .send(JsMessage::SetPlayers(players))
+            .expect("Set num of players")
+    }
+
+    /// Add a computer opponent with a skill level from 1 to 10, or remove it with `None`
+    pub fn set_bot(&mut self, skill: Option<u8>) {
+        if let Some(save) = &self.save {
+            save.record_bot(skill);
+        }
+        self.sender
+            .send(JsMessage::SetBot(skill))
+            .expect("Set computer opponent")
+    }
+
+    /// Name a player, by their index starting at 0
+    pub fn set_player_name(&mut self, player: usize, name: String) {
+        if let Some(save) = &self.save {
+            save.record_name(player, name.clone());
+        }
+        self.sender
+            .send(JsMessage::SetPlayerName(player, name))
+            .expect("Set player name")
+    }
+
+    /// Start the current game over with the same players
+    pub fn restart(&mut self) {
+        self.sender.send(JsMessage::Restart).expect("Restart game")
+    }
+
+    /// Apply new rotation sensitivity, volume, axis inversion, aiming aid and handedness settings
+    pub fn apply_settings(
+        &mut self,
+        sensitivity: f32,
+        volume: f32,
+        invert_y: bool,
+        aim_guide: bool,
+        left_handed: bool,
+    ) {
+        self.sender
+            .send(JsMessage::Settings {
+                sensitivity,
+                volume,
+                invert_y,
+                aim_guide,
+                left_handed,
+            })
+            .expect("Apply settings")
+    }
+}
+
+/// Receives events a game sends back to JavaScript
+#[wasm_bindgen]
+pub struct FeedbackReceiver {
+    /// Channel out of the game
+    receiver: Receiver<GameEvent>,
+}
+
+impl FeedbackReceiver {
+    /// Creates a new feedback receiver
+    pub fn new(receiver: Receiver<GameEvent>) -> Self {
+        Self { receiver }
+    }
+}
+
+#[wasm_bindgen]
+impl FeedbackReceiver {
+    /// Takes the next pending game event, if there is one
+    pub fn poll(&self) -> Option<FeedbackEvent> {
+        self.receiver.try_recv().ok().map(FeedbackEvent::from)
+    }
+}
+
+/// A single game event as seen from JavaScript
+#[wasm_bindgen]
+pub struct FeedbackEvent {
+    /// What kind of event this is
+    kind: String,
+    /// The event's payload
+    data: String,
+}
+
+#[wasm_bindgen]
+impl FeedbackEvent {
+    /// What kind of event this is
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    /// The event's payload
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> String {
+        self.data.clone()
+    }
+}
+
+impl From<GameEvent> for FeedbackEvent {
+    fn from(event: GameEvent) -> Self {
+        Self {
+            kind: event.kind().to_string(),
+            data: event.data(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Browser Gamepad API bridge, translating standard gamepads into controller messages
+
+use std::f32::consts::FRAC_PI_2;
+
+use wasm_bindgen::JsCast;
+use web_sys::GamepadButton;
+
+use crate::communication::{JsMessage, Orientation};
+
+/// Stick deflection below this is treated as centered
+pub const STICK_DEADZONE: f32 = 0.1;
+
+/// Angle in radians a fully deflected stick maps to
+pub const MAX_STICK_ANGLE: f32 = FRAC_PI_2;
+
+/// Smallest angle change worth sending a new rotation for
+const ROTATION_EPSILON: f32 = 0.001;
+
+/// Standard mapping index for the bottom face button
+const BUTTON_A_INDEX: usize = 0;
+/// Standard mapping index for the right face button
+const BUTTON_B_INDEX: usize = 1;
+/// Standard mapping index for the back/select button
+const BUTTON_BACK_INDEX: usize = 8;
+/// Standard mapping index for the start button
+const BUTTON_START_INDEX: usize = 9;
+/// Standard mapping index for d-pad up
+const DPAD_UP_INDEX: usize = 12;
+/// Standard mapping index for d-pad down
+const DPAD_DOWN_INDEX: usize = 13;
+
+/// Standard mapping axis indices for (pitch, roll, yaw)
+const PITCH_AXIS: usize = 1;
+/// Right stick horizontal axis
+const ROLL_AXIS: usize = 2;
+/// Left stick horizontal axis
+const YAW_AXIS: usize = 0;
+
+/// Previously seen gamepad state, used to only emit messages on changes
+#[derive(Debug, Default, Clone)]
+pub struct GamepadState {
+    /// Last orientation sent
+    last_rotation: Option<Orientation>,
+    /// Which buttons were held on the last poll
+    held: Vec<bool>,
+}
+
+impl GamepadState {
+    /// Converts a raw gamepad reading into the messages that should be sent. Buttons fire on
+    /// press, with A and B also firing on release, and rotations only fire when the sticks have
+    /// moved. The face buttons act as A/B, while the d-pad, start and back buttons drive menus
+    pub fn update(&mut self, axes: &[f32], buttons: &[bool]) -> Vec<JsMessage> {
+        let mut messages = vec![];
+
+        let mappings = [
+            (
+                BUTTON_A_INDEX,
+                JsMessage::ButtonA,
+                Some(JsMessage::ReleaseA),
+            ),
+            (
+                BUTTON_B_INDEX,
+                JsMessage::ButtonB,
+                Some(JsMessage::ReleaseB),
+            ),
+            (DPAD_UP_INDEX, JsMessage::MenuUp, None),
+            (DPAD_DOWN_INDEX, JsMessage::MenuDown, None),
+            (BUTTON_START_INDEX, JsMessage::MenuSelect, None),
+            (BUTTON_BACK_INDEX, JsMessage::MenuBack, None),
+        ];
+
+        for (idx, press, release) in mappings {
+            let pressed = buttons.get(idx).copied().unwrap_or(false);
+            let was_held = self.held.get(idx).copied().unwrap_or(false);
+            if pressed && !was_held {
+                messages.push(press);
+            } else if !pressed && was_held {
+                messages.extend(release);
+            }
+        }
+
+        self.held = buttons.to_vec();
+
+        let axis = |idx: usize| {
+            let val = axes.get(idx).copied().unwrap_or(0.0);
+            if val.abs() < STICK_DEADZONE {
+                0.0
+            } else {
+                val * MAX_STICK_ANGLE
+            }
+        };
+
+        // Stick up is negative on the standard mapping, but should pitch forwards
+        let rotation = Orientation::new(-axis(PITCH_AXIS), axis(ROLL_AXIS), axis(YAW_AXIS));
+
+        let changed = match self.last_rotation {
+            Some(last) => {
+                (rotation.pitch - last.pitch).abs() > ROTATION_EPSILON
+                    || (rotation.roll - last.roll).abs() > ROTATION_EPSILON
+                    || (rotation.yaw - last.yaw).abs() > ROTATION_EPSILON
+            }
+            None => true,
+        };
+
+        if changed {
+            self.last_rotation = Some(rotation);
+            messages.push(JsMessage::Rotate(rotation));
+        }
+
+        messages
+    }
+}
+
+/// Reads the axes and button states of the first connected gamepad, if any
+pub fn read_gamepad() -> Option<(Vec<f32>, Vec<bool>)> {
+    let navigator = web_sys::window()?.navigator();
+    let gamepads = navigator.get_gamepads().ok()?;
+
+    let gamepad = gamepads
+        .iter()
+        .find_map(|pad| pad.dyn_into::<web_sys::Gamepad>().ok())
+        .filter(|pad| pad.connected())?;
+
+    let axes = gamepad
+        .axes()
+        .iter()
+        .map(|axis| axis.as_f64().unwrap_or(0.0) as f32)
+        .collect();
+
+    let buttons = gamepad
+        .buttons()
+        .iter()
+        .map(|button| {
+            button
+                .dyn_into::<GamepadButton>()
+                .map(|button| button.pressed())
+                .unwrap_or(false)
+        })
+        .collect();
+
+    Some((axes, buttons))
+}
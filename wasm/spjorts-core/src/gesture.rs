@@ -0,0 +1,226 @@
+//! Gesture detection shared across games, turning a stream of controller orientations into
+//! swings, flicks and twists
+
+use std::f32::consts::{PI, TAU};
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+
+use crate::communication::Orientation;
+
+/// A motion the detector recognizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// A long, fast sweep, like a bat or club swing
+    Swing,
+    /// A short, sharp snap, like throwing a dart
+    Flick,
+    /// Turning the controller mostly about its yaw axis, like turning a key
+    Twist,
+}
+
+impl Gesture {
+    /// Name of the gesture to show players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Swing => "Swing",
+            Self::Flick => "Flick",
+            Self::Twist => "Twist",
+        }
+    }
+}
+
+/// A gesture the detector recognized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedGesture {
+    /// Which gesture it was
+    pub gesture: Gesture,
+    /// How hard it was, as the fastest the controller turned during it in radians per second
+    pub intensity: f32,
+    /// How long it lasted, in seconds
+    pub duration: f32,
+    /// How far it turned about each axis from start to finish, in radians
+    pub turned: Orientation,
+}
+
+/// Thresholds that decide when a motion counts as a gesture
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureThresholds {
+    /// Turning speed a motion has to reach to count as a swing, in radians per second
+    pub swing_speed: f32,
+    /// Turning speed a motion has to reach to count as a flick, in radians per second
+    pub flick_speed: f32,
+    /// Longest a motion can last and still count as a flick, in seconds
+    pub flick_max_secs: f32,
+    /// Angle a motion has to turn about yaw to count as a twist, in radians
+    pub twist_angle: f32,
+    /// Turning speed below which the controller counts as at rest, ending a motion, in radians
+    /// per second
+    pub rest_speed: f32,
+}
+
+impl Default for GestureThresholds {
+    fn default() -> Self {
+        Self {
+            swing_speed: 4.0,
+            flick_speed: 8.0,
+            flick_max_secs: 0.2,
+            twist_angle: 1.2,
+            rest_speed: 1.0,
+        }
+    }
+}
+
+/// Wraps an angle into `-PI..PI`
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// A motion in progress, from when the controller started turning
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Motion {
+    /// When the motion started, in seconds
+    started_at: f32,
+    /// Fastest the controller has turned so far, in radians per second
+    peak_speed: f32,
+    /// How far it has turned about each axis so far, in radians
+    turned: Orientation,
+}
+
+/// Watches controller orientations and reports gestures as they finish
+#[derive(Debug, Clone, Default)]
+pub struct GestureDetector {
+    /// Thresholds motions are classified with
+    pub thresholds: GestureThresholds,
+    /// The previous orientation and when it was read, in seconds
+    last: Option<(Orientation, f32)>,
+    /// The motion in progress, if the controller is turning
+    motion: Option<Motion>,
+    /// How fast the controller turned between the last two readings, in radians per second
+    speed: f32,
+}
+
+impl GestureDetector {
+    /// Creates a detector with the given thresholds
+    pub fn new(thresholds: GestureThresholds) -> Self {
+        Self {
+            thresholds,
+            ..Default::default()
+        }
+    }
+
+    /// How fast the controller turned between the last two readings, in radians per second
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Feeds in an orientation read at `at` seconds, returning a gesture if one just finished
+    pub fn update(&mut self, orientation: Orientation, at: f32) -> Option<DetectedGesture> {
+        let (last, last_at) = self.last.replace((orientation, at))?;
+        let dt = at - last_at;
+        if dt <= f32::EPSILON {
+            return None;
+        }
+
+        // Readings wrap around at half a turn, so the short way round is the way it turned
+        let delta = Orientation::new(
+            wrap_angle(orientation.pitch - last.pitch),
+            wrap_angle(orientation.roll - last.roll),
+            wrap_angle(orientation.yaw - last.yaw),
+        );
+        self.speed = (delta.pitch.powi(2) + delta.roll.powi(2) + delta.yaw.powi(2)).sqrt() / dt;
+
+        if self.speed >= self.thresholds.rest_speed {
+            let motion = self.motion.get_or_insert(Motion {
+                started_at: last_at,
+                peak_speed: 0.0,
+                turned: Orientation::default(),
+            });
+            motion.peak_speed = motion.peak_speed.max(self.speed);
+            motion.turned.pitch += delta.pitch;
+            motion.turned.roll += delta.roll;
+            motion.turned.yaw += delta.yaw;
+            return None;
+        }
+
+        let motion = self.motion.take()?;
+        self.classify(motion, last_at)
+    }
+
+    /// Works out which gesture, if any, a motion that ended at `ended_at` was
+    fn classify(&self, motion: Motion, ended_at: f32) -> Option<DetectedGesture> {
+        let duration = ended_at - motion.started_at;
+        let Motion {
+            peak_speed, turned, ..
+        } = motion;
+        let twist_led = turned.yaw.abs() > turned.pitch.abs().max(turned.roll.abs());
+
+        let gesture = if twist_led && turned.yaw.abs() >= self.thresholds.twist_angle {
+            Gesture::Twist
+        } else if duration <= self.thresholds.flick_max_secs
+            && peak_speed >= self.thresholds.flick_speed
+        {
+            Gesture::Flick
+        } else if duration > self.thresholds.flick_max_secs
+            && peak_speed >= self.thresholds.swing_speed
+        {
+            Gesture::Swing
+        } else {
+            return None;
+        };
+
+        Some(DetectedGesture {
+            gesture,
+            intensity: peak_speed,
+            duration,
+            turned,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wrap_angle, Gesture, GestureDetector};
+    use crate::communication::Orientation;
+
+    /// A controller pointed at `yaw`, level otherwise
+    fn facing(yaw: f32) -> Orientation {
+        Orientation::new(0.0, 0.0, yaw)
+    }
+
+    #[test]
+    fn small_turn_across_the_wrap_is_slow() {
+        let mut detector = GestureDetector::default();
+        assert_eq!(detector.update(facing(3.1), 0.0), None);
+        assert_eq!(detector.update(facing(-3.1), 0.1), None);
+
+        let rest_speed = detector.thresholds.rest_speed;
+        assert!(
+            detector.speed() < rest_speed,
+            "turned at {} rad/s",
+            detector.speed()
+        );
+        assert_eq!(detector.update(facing(-3.1), 0.2), None);
+    }
+
+    #[test]
+    fn twist_across_the_wrap_turns_the_short_way() {
+        let mut detector = GestureDetector::default();
+        let mut yaw = 2.6;
+        let mut at = 0.0;
+        assert_eq!(detector.update(facing(yaw), at), None);
+        for _ in 0..6 {
+            yaw += 0.3;
+            at += 0.05;
+            assert_eq!(detector.update(facing(wrap_angle(yaw)), at), None);
+        }
+
+        let detected = detector
+            .update(facing(wrap_angle(yaw)), at + 0.05)
+            .expect("Twist detected");
+        assert_eq!(detected.gesture, Gesture::Twist);
+        assert!((detected.turned.yaw - 1.8).abs() < 1e-4);
+        assert!((detected.intensity - 6.0).abs() < 1e-3);
+    }
+}
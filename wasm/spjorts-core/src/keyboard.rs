@@ -0,0 +1,104 @@
+//! Keyboard fallback for developing games without a physical controller
+
+use std::f32::consts::PI;
+
+use crate::{
+    channel::InputWriter,
+    communication::{JsMessage, Orientation},
+};
+use bevy::prelude::*;
+
+/// How many radians per second a held key rotates the virtual controller
+pub const KEYBOARD_ROTATION_SPEED: f32 = PI / 2.0;
+
+/// Plugin that maps arrow keys/WASD to rotation, Z/X to the A/B buttons and Q/E/Enter/Escape to
+/// menu navigation, injecting synthetic messages into the same channel JavaScript writes to
+pub struct KeyboardFallbackPlugin {
+    /// Sender half of the game's message channel
+    sender: InputWriter,
+}
+
+impl KeyboardFallbackPlugin {
+    /// Creates a new keyboard fallback plugin writing to a game's message channel
+    pub fn new(sender: impl Into<InputWriter>) -> Self {
+        Self {
+            sender: sender.into(),
+        }
+    }
+}
+
+/// Sender the keyboard fallback system writes synthetic messages through
+#[derive(Resource)]
+struct KeyboardSender(InputWriter);
+
+/// The virtual controller's current orientation
+#[derive(Resource, Default)]
+struct KeyboardOrientation(Orientation);
+
+impl Plugin for KeyboardFallbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(KeyboardSender(self.sender.clone()))
+            .init_resource::<KeyboardOrientation>()
+            .add_systems(PreUpdate, send_keyboard_input);
+    }
+}
+
+/// Reads keyboard state and sends the equivalent controller messages
+fn send_keyboard_input(
+    keys: Res<'_, ButtonInput<KeyCode>>,
+    time: Res<'_, Time>,
+    sender: Res<'_, KeyboardSender>,
+    mut orientation: ResMut<'_, KeyboardOrientation>,
+) {
+    if keys.just_pressed(KeyCode::KeyZ) {
+        let _ = sender.0.send(JsMessage::ButtonA);
+    }
+
+    if keys.just_pressed(KeyCode::KeyX) {
+        let _ = sender.0.send(JsMessage::ButtonB);
+    }
+
+    if keys.just_released(KeyCode::KeyZ) {
+        let _ = sender.0.send(JsMessage::ReleaseA);
+    }
+
+    if keys.just_released(KeyCode::KeyX) {
+        let _ = sender.0.send(JsMessage::ReleaseB);
+    }
+
+    let menu_keys = [
+        (KeyCode::KeyQ, JsMessage::MenuUp),
+        (KeyCode::KeyE, JsMessage::MenuDown),
+        (KeyCode::Enter, JsMessage::MenuSelect),
+        (KeyCode::Escape, JsMessage::MenuBack),
+    ];
+
+    for (key, msg) in menu_keys {
+        if keys.just_pressed(key) {
+            let _ = sender.0.send(msg);
+        }
+    }
+
+    let axis = |positive: [KeyCode; 2], negative: [KeyCode; 2]| {
+        let pos = if keys.any_pressed(positive) { 1.0 } else { 0.0 };
+        let neg = if keys.any_pressed(negative) { 1.0 } else { 0.0 };
+        pos - neg
+    };
+
+    let pitch = axis(
+        [KeyCode::ArrowUp, KeyCode::KeyW],
+        [KeyCode::ArrowDown, KeyCode::KeyS],
+    );
+    let yaw = axis(
+        [KeyCode::ArrowRight, KeyCode::KeyD],
+        [KeyCode::ArrowLeft, KeyCode::KeyA],
+    );
+
+    if pitch != 0.0 || yaw != 0.0 {
+        let step = KEYBOARD_ROTATION_SPEED * time.delta_secs();
+        orientation.0.pitch += pitch * step;
+        orientation.0.yaw += yaw * step;
+
+        let _ = sender.0.send(JsMessage::Rotate(orientation.0));
+    }
+}
@@ -1,53 +1,187 @@
 //! Shared struct and utilities for all WASM games
+//!
+//! The `bevy` feature (on by default) adds the Bevy resources, plugins and Runner plumbing.
+//! Without it only the protocol types are built, for consumers that don't use Bevy
 
+#[cfg(feature = "bevy")]
 use bevy::prelude::Resource;
+use channel::InputWriter;
+#[cfg(feature = "bevy")]
+use communication::GameEvent;
 use communication::JsMessage;
+#[cfg(feature = "bevy")]
 use crossbeam_channel::{Receiver, Sender};
+use facades::{InputSender, SessionControl};
 use wasm_bindgen::prelude::wasm_bindgen;
 
+pub use js_sys;
+
+pub mod assets;
+pub mod capture;
+pub mod channel;
 pub mod communication;
+#[cfg(feature = "bevy")]
+pub mod diagnostics;
+pub mod facades;
+pub mod gamepad;
+pub mod gesture;
+#[cfg(feature = "keyboard-fallback")]
+pub mod keyboard;
+#[cfg(feature = "bevy")]
+pub mod menu;
+pub mod physics;
+#[cfg(feature = "bevy")]
+pub mod players;
+#[cfg(feature = "bevy")]
+pub mod runner;
+#[cfg(feature = "bevy")]
+pub mod scorecard;
+pub mod settings;
+pub mod snapshot;
+#[cfg(feature = "bevy")]
+pub mod spectator;
+#[cfg(feature = "bevy")]
+pub mod swing;
+#[cfg(feature = "bevy")]
+pub mod turns;
 
 /// What is JavaScript sending back and forth
 pub type Communication = JsMessage;
 
-/// A JavaScript event sender pipeline
+/// A JavaScript event sender pipeline. Kept for compatibility, new glue code should prefer the
+/// focused [`InputSender`] and [`SessionControl`] facades
 #[wasm_bindgen]
-pub struct ActionSender(Sender<Communication>);
+pub struct ActionSender {
+    /// Controller input facade
+    input: InputSender,
+    /// Session control facade
+    session: SessionControl,
+}
 
 impl ActionSender {
     /// Creates a new sender
-    pub fn new(sender: Sender<Communication>) -> Self {
-        Self(sender)
+    pub fn new(sender: impl Into<InputWriter>) -> Self {
+        let sender = sender.into();
+        Self {
+            input: InputSender::new(sender.clone()),
+            session: SessionControl::new(sender),
+        }
     }
 }
 
 #[wasm_bindgen]
 impl ActionSender {
+    /// Gets a sender whose input is tagged for a player slot, for pages that pair a controller
+    /// to each player
+    pub fn for_player(&self, player: usize) -> InputSender {
+        self.input.for_player(player)
+    }
+
     /// Press the A button
     pub fn press_a(&mut self) {
-        self.0.send(JsMessage::ButtonA).expect("Press A Button")
+        self.input.press_a()
     }
 
     /// Press the B button
     pub fn press_b(&mut self) {
-        self.0.send(JsMessage::ButtonB).expect("Press B Button")
+        self.input.press_b()
+    }
+
+    /// Release the A button
+    pub fn release_a(&mut self) {
+        self.input.release_a()
+    }
+
+    /// Release the B button
+    pub fn release_b(&mut self) {
+        self.input.release_b()
     }
 
     /// Rotate data with pitch, roll and yaw
     pub fn rotate(&mut self, pitch: f32, roll: f32, yaw: f32) {
-        self.0
-            .send(JsMessage::Rotate(pitch, roll, yaw))
-            .expect("Rotate")
+        self.input.rotate(pitch, roll, yaw)
+    }
+
+    /// Rotate data with pitch, roll and yaw, read by the controller at `sent_at_ms` milliseconds
+    /// since the Unix epoch
+    pub fn rotate_at(&mut self, pitch: f32, roll: f32, yaw: f32, sent_at_ms: f64) {
+        self.input.rotate_at(pitch, roll, yaw, sent_at_ms)
+    }
+
+    /// Move up in the current menu
+    pub fn menu_up(&mut self) {
+        self.input.menu_up()
+    }
+
+    /// Move down in the current menu
+    pub fn menu_down(&mut self) {
+        self.input.menu_down()
+    }
+
+    /// Select the highlighted menu item
+    pub fn menu_select(&mut self) {
+        self.input.menu_select()
+    }
+
+    /// Back out of the current menu
+    pub fn menu_back(&mut self) {
+        self.input.menu_back()
     }
 
     /// Set the number of players in the game
     pub fn set_players(&mut self, players: usize) {
-        self.0
-            .send(JsMessage::SetPlayers(players))
-            .expect("Set num of players")
+        self.session.set_players(players)
+    }
+
+    /// Add a computer opponent with a skill level from 1 to 10, or remove it with `None`
+    pub fn set_bot(&mut self, skill: Option<u8>) {
+        self.session.set_bot(skill)
+    }
+
+    /// Name a player, by their index starting at 0
+    pub fn set_player_name(&mut self, player: usize, name: String) {
+        self.session.set_player_name(player, name)
+    }
+
+    /// Start the current game over with the same players
+    pub fn restart(&mut self) {
+        self.session.restart()
+    }
+
+    /// Apply new rotation sensitivity, volume, axis inversion, aiming aid and handedness settings
+    pub fn apply_settings(
+        &mut self,
+        sensitivity: f32,
+        volume: f32,
+        invert_y: bool,
+        aim_guide: bool,
+        left_handed: bool,
+    ) {
+        self.session
+            .apply_settings(sensitivity, volume, invert_y, aim_guide, left_handed)
+    }
+
+    /// Polls the first connected browser gamepad and forwards any stick movement or button
+    /// presses as controller messages. Meant to be called once per animation frame
+    pub fn poll_gamepad(&mut self) {
+        self.input.poll_gamepad()
     }
 }
 
 /// A JavaScript event reader pipeline
+#[cfg(feature = "bevy")]
 #[derive(Resource)]
 pub struct ActionReader(pub Receiver<Communication>);
+
+/// A game to JavaScript event writer pipeline
+#[cfg(feature = "bevy")]
+#[derive(Resource)]
+pub struct FeedbackSender(pub Sender<GameEvent>);
+
+#[cfg(feature = "bevy")]
+impl FeedbackSender {
+    /// Sends an event back to JavaScript
+    pub fn send(&self, event: GameEvent) {
+        let _ = self.0.send(event);
+    }
+}
@@ -0,0 +1,124 @@
+//! Reusable in-game menu navigation
+
+use bevy::prelude::*;
+
+use crate::communication::JsMessage;
+
+/// A single menu navigation input
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Move the highlight up
+    Up,
+    /// Move the highlight down
+    Down,
+    /// Select the highlighted item
+    Select,
+    /// Leave the menu
+    Back,
+}
+
+impl MenuAction {
+    /// Converts a JavaScript message into a menu action, if it is one. Games forward these from
+    /// their input handler so menus and gameplay can share a single reader
+    pub fn from_message(msg: &JsMessage) -> Option<Self> {
+        match msg {
+            JsMessage::MenuUp => Some(Self::Up),
+            JsMessage::MenuDown => Some(Self::Down),
+            JsMessage::MenuSelect => Some(Self::Select),
+            JsMessage::MenuBack => Some(Self::Back),
+            _ => None,
+        }
+    }
+}
+
+/// A navigable list of items. Only menus with `focused` set respond to input
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Menu {
+    /// How many items the menu holds
+    pub len: usize,
+    /// Index of the highlighted item
+    pub selected: usize,
+    /// Whether this menu currently receives input
+    pub focused: bool,
+}
+
+impl Menu {
+    /// Creates a focused menu with the first item highlighted
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            selected: 0,
+            focused: true,
+        }
+    }
+
+    /// Moves the highlight up, wrapping to the bottom
+    pub fn up(&mut self) {
+        if self.len > 0 {
+            self.selected = (self.selected + self.len - 1) % self.len;
+        }
+    }
+
+    /// Moves the highlight down, wrapping to the top
+    pub fn down(&mut self) {
+        if self.len > 0 {
+            self.selected = (self.selected + 1) % self.len;
+        }
+    }
+}
+
+/// Fired when an item of a focused menu is selected
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuSelected {
+    /// The menu entity
+    pub menu: Entity,
+    /// Index of the selected item
+    pub index: usize,
+}
+
+/// Fired when a focused menu is backed out of
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuClosed {
+    /// The menu entity
+    pub menu: Entity,
+}
+
+/// Plugin that registers menu events and moves the highlight of focused menus
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MenuAction>()
+            .add_event::<MenuSelected>()
+            .add_event::<MenuClosed>()
+            .add_systems(Update, navigate_menus);
+    }
+}
+
+/// Applies incoming menu actions to every focused menu
+pub fn navigate_menus(
+    mut actions: EventReader<'_, '_, MenuAction>,
+    mut menus: Query<'_, '_, (Entity, &mut Menu)>,
+    mut selected: EventWriter<'_, MenuSelected>,
+    mut closed: EventWriter<'_, MenuClosed>,
+) {
+    for action in actions.read() {
+        for (entity, mut menu) in menus.iter_mut().filter(|(_, menu)| menu.focused) {
+            match action {
+                MenuAction::Up => menu.up(),
+                MenuAction::Down => menu.down(),
+                MenuAction::Select => {
+                    if menu.selected < menu.len {
+                        selected.send(MenuSelected {
+                            menu: entity,
+                            index: menu.selected,
+                        });
+                    }
+                }
+                MenuAction::Back => {
+                    closed.send(MenuClosed { menu: entity });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Physics constants shared across games
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Physical properties for one kind of body
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyTuning {
+    /// Friction coefficient
+    pub friction: f32,
+    /// Restitution (bounciness) coefficient
+    pub restitution: f32,
+    /// Multiplier applied to gravity
+    pub gravity_scale: f32,
+    /// Collider density
+    pub density: f32,
+}
+
+#[wasm_bindgen]
+impl BodyTuning {
+    /// Creates a new set of body properties
+    #[wasm_bindgen(constructor)]
+    pub fn new(friction: f32, restitution: f32, gravity_scale: f32, density: f32) -> Self {
+        Self {
+            friction,
+            restitution,
+            gravity_scale,
+            density,
+        }
+    }
+}
+
+/// Physics constants games insert into their Rapier components instead of hardcoding them
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsTuning {
+    /// The surface play happens on, such as a lane or table
+    pub ground: BodyTuning,
+    /// The object the player launches, such as a ball or puck
+    pub projectile: BodyTuning,
+    /// Objects the projectile is aimed at, such as pins
+    pub target: BodyTuning,
+}
+
+impl Default for PhysicsTuning {
+    fn default() -> Self {
+        Self {
+            ground: BodyTuning::new(0.04, 0.01, 1.0, 1.0),
+            projectile: BodyTuning::new(0.6, 0.4, 1.0, 1.2),
+            target: BodyTuning::new(0.6, 0.8, 0.9, 0.8),
+        }
+    }
+}
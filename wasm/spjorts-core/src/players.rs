@@ -0,0 +1,143 @@
+//! Shared session bookkeeping driven by `SetPlayers`, `SetBot` and `Settings`
+
+use bevy::prelude::*;
+use crossbeam_channel::Receiver;
+
+use crate::{communication::JsMessage, settings::GameSettings, Communication};
+
+/// Highest skill level a computer opponent can have
+pub const MAX_BOT_SKILL: u8 = 10;
+
+/// How many players are in the current game
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerRegistry {
+    /// Number of human players, always at least one
+    count: usize,
+    /// Skill level of the computer opponent, if there is one
+    bot: Option<u8>,
+}
+
+impl Default for PlayerRegistry {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            bot: None,
+        }
+    }
+}
+
+impl PlayerRegistry {
+    /// Creates a registry with the given number of human players, at least one
+    pub fn new(count: usize) -> Self {
+        Self {
+            count: count.max(1),
+            bot: None,
+        }
+    }
+
+    /// Adds a computer opponent with a skill level from 1 to 10, or removes it with `None`
+    pub fn with_bot(mut self, skill: Option<u8>) -> Self {
+        self.bot = skill.map(|skill| skill.clamp(1, MAX_BOT_SKILL));
+        self
+    }
+
+    /// Number of human players in the game
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Skill level of the computer opponent, if there is one
+    pub fn bot(&self) -> Option<u8> {
+        self.bot
+    }
+
+    /// Number of players including the computer opponent, who always takes the last turn
+    pub fn total(&self) -> usize {
+        self.count + usize::from(self.bot.is_some())
+    }
+}
+
+/// Xorshift generator computer opponents draw their mistakes from. Every bot starts from the same
+/// seed, so one at a given skill plays the same way each game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jitter(u32);
+
+impl Default for Jitter {
+    fn default() -> Self {
+        Self(0x9E37_79B9)
+    }
+}
+
+impl Jitter {
+    /// A pseudo random number from -1.0 to 1.0
+    pub fn sample(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// Read half of the session channel `SetPlayers`, `SetBot` and `Settings` messages are routed to
+#[derive(Resource)]
+struct SessionReader(Receiver<Communication>);
+
+/// Plugin that keeps the [`PlayerRegistry`] up to date with `SetPlayers` and `SetBot` messages,
+/// and applies `Settings` messages to the [`GameSettings`] every game reads
+pub struct PlayersPlugin {
+    /// Read half of the session channel
+    receiver: Receiver<Communication>,
+}
+
+impl PlayersPlugin {
+    /// Creates a new players plugin reading from the given session channel
+    pub fn new(receiver: Receiver<Communication>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl Plugin for PlayersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerRegistry>()
+            .init_resource::<GameSettings>()
+            .insert_resource(SessionReader(self.receiver.clone()))
+            .add_systems(PreUpdate, apply_session);
+    }
+}
+
+/// Applies any pending `SetPlayers` and `SetBot` messages to the registry and `Settings` messages
+/// to the game settings
+fn apply_session(
+    reader: Res<'_, SessionReader>,
+    mut registry: ResMut<'_, PlayerRegistry>,
+    mut settings: ResMut<'_, GameSettings>,
+) {
+    for msg in reader.0.try_iter() {
+        match msg {
+            JsMessage::SetPlayers(num) => {
+                let updated = PlayerRegistry::new(num).with_bot(registry.bot());
+                registry.set_if_neq(updated);
+            }
+            JsMessage::SetBot(skill) => {
+                let updated = registry.with_bot(skill);
+                registry.set_if_neq(updated);
+            }
+            JsMessage::Settings {
+                sensitivity,
+                volume,
+                invert_y,
+                aim_guide,
+                left_handed,
+            } => {
+                settings.set_if_neq(GameSettings::new(
+                    sensitivity,
+                    volume,
+                    invert_y,
+                    aim_guide,
+                    left_handed,
+                ));
+            }
+            _ => {}
+        }
+    }
+}
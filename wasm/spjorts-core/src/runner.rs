@@ -0,0 +1,341 @@
+//! Shared Runner plumbing so every game doesn't have to rewrite channel wiring
+
+use bevy::prelude::App;
+use crossbeam_channel::Receiver;
+use js_sys::Function;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    assets::AssetBasePath,
+    capture::MotionCapture,
+    channel::{input_channel, Backpressure, InputWriter},
+    communication::GameEvent,
+    diagnostics::{DiagnosticSender, DiagnosticsPlugin, LogCallback},
+    facades::{FeedbackReceiver, InputSender, SessionControl},
+    menu::MenuPlugin,
+    physics::{BodyTuning, PhysicsTuning},
+    players::PlayersPlugin,
+    settings::GameSettings,
+    snapshot::{SaveSlot, StateSnapshot, StateSync},
+    spectator::{RunMode, SpectatorPlugin},
+    ActionReader, ActionSender, FeedbackSender,
+};
+
+/// JavaScript provided configuration for a game's Runner
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct RunnerConfig {
+    /// If set, the input channel drops the oldest rotations once this many messages are waiting
+    max_queued: Option<usize>,
+    /// Whether only the newest pending rotation should be kept
+    coalesce_rotations: bool,
+    /// Base path all game assets are loaded relative to
+    asset_base_path: String,
+    /// Physics constants games should build their bodies with
+    physics: PhysicsTuning,
+}
+
+#[wasm_bindgen]
+impl RunnerConfig {
+    /// Creates a default configuration
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounds the input channel, dropping the oldest rotations once this many messages are
+    /// waiting
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = Some(max_queued);
+        self
+    }
+
+    /// Keeps only the newest pending rotation so orientation input is always fresh. Takes
+    /// priority over `with_max_queued`
+    pub fn with_rotation_coalescing(mut self, enabled: bool) -> Self {
+        self.coalesce_rotations = enabled;
+        self
+    }
+
+    /// Sets the base path all game assets are loaded relative to
+    pub fn with_asset_base_path(mut self, path: String) -> Self {
+        self.asset_base_path = path;
+        self
+    }
+
+    /// Overrides the physics of the surface play happens on
+    pub fn with_ground_physics(mut self, tuning: BodyTuning) -> Self {
+        self.physics.ground = tuning;
+        self
+    }
+
+    /// Overrides the physics of the object the player launches
+    pub fn with_projectile_physics(mut self, tuning: BodyTuning) -> Self {
+        self.physics.projectile = tuning;
+        self
+    }
+
+    /// Overrides the physics of the objects the projectile is aimed at
+    pub fn with_target_physics(mut self, tuning: BodyTuning) -> Self {
+        self.physics.target = tuning;
+        self
+    }
+}
+
+/// A Bevy app wired up to JavaScript. Games wrap this in their own `#[wasm_bindgen]` Runner
+/// through [`define_runner!`](crate::define_runner)
+pub struct GameRunner {
+    /// The game's Bevy app
+    app: App,
+    /// Write half of the game's input channel
+    write: InputWriter,
+    /// Read half of the game's feedback channel
+    feedback: Receiver<GameEvent>,
+    /// The game's latest state snapshot
+    snapshot: StateSnapshot,
+    /// The game's latest save and any save waiting to be loaded
+    save: SaveSlot,
+    /// Frames published for spectators, or relayed to this app if it's one
+    sync: StateSync,
+    /// Rotations the game has recorded
+    capture: MotionCapture,
+    /// JavaScript function diagnostics are forwarded to once the app runs
+    log_callback: Option<Function>,
+}
+
+impl GameRunner {
+    /// Creates a new runner, wiring up all shared resources before handing the app to `build`
+    /// for game specific plugins and systems
+    pub fn new(config: RunnerConfig, build: impl FnOnce(&mut App)) -> Self {
+        Self::with_mode(config, RunMode::Play, build)
+    }
+
+    /// Creates a render-only runner that ignores controller input and shows the frames relayed
+    /// to it through [`apply_sync`](Self::apply_sync), for a TV or projector next to the
+    /// browser that's playing
+    pub fn new_spectator(config: RunnerConfig, build: impl FnOnce(&mut App)) -> Self {
+        Self::with_mode(config, RunMode::Spectate, build)
+    }
+
+    /// Creates a new runner that plays or spectates the game
+    fn with_mode(config: RunnerConfig, mode: RunMode, build: impl FnOnce(&mut App)) -> Self {
+        let policy = if config.coalesce_rotations {
+            Backpressure::CoalesceRotate
+        } else {
+            config
+                .max_queued
+                .map_or(Backpressure::Unbounded, Backpressure::DropOldestRotate)
+        };
+        let (session_write, session_read) = crossbeam_channel::unbounded();
+        let (write, read) = input_channel(policy);
+        let write = write.with_session_channel(session_write);
+        let (feedback_write, feedback) = crossbeam_channel::unbounded();
+        let snapshot = StateSnapshot::default();
+        let save = SaveSlot::default();
+        let sync = StateSync::default();
+        let capture = MotionCapture::default();
+        let (diagnostic_write, diagnostics) = crossbeam_channel::unbounded();
+
+        let mut app = App::new();
+        app.insert_resource(ActionReader(read))
+            .insert_resource(FeedbackSender(feedback_write))
+            .insert_resource(DiagnosticSender(diagnostic_write))
+            .insert_resource(snapshot.clone())
+            .insert_resource(save.clone())
+            .insert_resource(sync.clone())
+            .insert_resource(capture.clone())
+            .insert_resource(mode)
+            .insert_resource(AssetBasePath(config.asset_base_path))
+            .insert_resource(config.physics)
+            .init_resource::<GameSettings>()
+            .add_plugins((
+                MenuPlugin,
+                DiagnosticsPlugin::new(diagnostics),
+                PlayersPlugin::new(session_read),
+                SpectatorPlugin,
+            ));
+
+        #[cfg(feature = "keyboard-fallback")]
+        app.add_plugins(crate::keyboard::KeyboardFallbackPlugin::new(write.clone()));
+
+        build(&mut app);
+
+        Self {
+            app,
+            write,
+            feedback,
+            snapshot,
+            save,
+            sync,
+            capture,
+            log_callback: None,
+        }
+    }
+
+    /// Get the sender pipeline
+    pub fn get_send(&self) -> ActionSender {
+        ActionSender::new(self.write.clone())
+    }
+
+    /// Gets a sender for controller input
+    pub fn get_input(&self) -> InputSender {
+        InputSender::new(self.write.clone())
+    }
+
+    /// Gets a controller for session state such as players and settings
+    pub fn get_session(&self) -> SessionControl {
+        SessionControl::new(self.write.clone()).recording_to(self.save.clone())
+    }
+
+    /// Gets a receiver for events the game sends back to JavaScript
+    pub fn get_feedback(&self) -> FeedbackReceiver {
+        FeedbackReceiver::new(self.feedback.clone())
+    }
+
+    /// Gets the latest snapshot of the game's state as JSON
+    pub fn state_json(&self) -> String {
+        self.snapshot.json()
+    }
+
+    /// Gets the game's latest save as JSON, `{}` if it has none, for the page to stash and
+    /// resume from later. The players the session was set up with are kept alongside it as
+    /// `players`, `bot` and `names`, with the game's own save under `game`
+    pub fn export_state(&self) -> String {
+        self.save.export()
+    }
+
+    /// Hands the game a save from `export_state` to resume from. Saves the game can't read are
+    /// reported through diagnostics and ignored
+    pub fn import_state(&self, json: String) {
+        self.save.import(json);
+    }
+
+    /// Gets the latest frame of live state for the page to relay to spectators, `{}` until the
+    /// game publishes one
+    pub fn sync_json(&self) -> String {
+        self.sync.latest()
+    }
+
+    /// Hands a spectator a frame from a playing app's `sync_json` to show. Frames the game can't
+    /// read are reported through diagnostics and ignored
+    pub fn apply_sync(&self, json: String) {
+        self.sync.receive(json);
+    }
+
+    /// Gets the rotations the game has recorded as CSV, just the header row if it records none
+    pub fn export_csv(&self) -> String {
+        self.capture.csv()
+    }
+
+    /// Sets the JavaScript function diagnostics are passed to as `callback(level, message)`.
+    /// Without one, diagnostics go to Bevy's log
+    pub fn set_log_callback(&mut self, callback: Function) {
+        self.log_callback = Some(callback);
+    }
+
+    /// Run the Bevy App
+    pub fn run(&mut self) {
+        if let Some(callback) = self.log_callback.take() {
+            self.app.insert_non_send_resource(LogCallback(callback));
+        }
+        self.app.run();
+    }
+}
+
+/// Generates a game's `#[wasm_bindgen]` Runner. Takes a function that adds the game's plugins
+/// and systems to the app, everything else is wired up by [`GameRunner`]
+///
+/// ```ignore
+/// spjorts_core::define_runner!(|app| {
+///     app.add_plugins(DefaultPlugins).add_systems(Startup, setup);
+/// });
+/// ```
+#[macro_export]
+macro_rules! define_runner {
+    ($build:expr_2021) => {
+        /// System responsible for running and communicating with a Bevy app
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        pub struct Runner($crate::runner::GameRunner);
+
+        #[::wasm_bindgen::prelude::wasm_bindgen]
+        impl Runner {
+            /// Creates a new runner with an optional configuration
+            #[wasm_bindgen(constructor)]
+            pub fn new(config: Option<$crate::runner::RunnerConfig>) -> Self {
+                Self($crate::runner::GameRunner::new(
+                    config.unwrap_or_default(),
+                    $build,
+                ))
+            }
+
+            /// Creates a render-only runner that shows frames relayed from a playing runner
+            pub fn new_spectator(config: Option<$crate::runner::RunnerConfig>) -> Self {
+                Self($crate::runner::GameRunner::new_spectator(
+                    config.unwrap_or_default(),
+                    $build,
+                ))
+            }
+
+            /// Get the sender pipeline
+            pub fn get_send(&self) -> $crate::ActionSender {
+                self.0.get_send()
+            }
+
+            /// Gets a sender for controller input
+            pub fn get_input(&self) -> $crate::facades::InputSender {
+                self.0.get_input()
+            }
+
+            /// Gets a controller for session state such as players and settings
+            pub fn get_session(&self) -> $crate::facades::SessionControl {
+                self.0.get_session()
+            }
+
+            /// Gets a receiver for events the game sends back to JavaScript
+            pub fn get_feedback(&self) -> $crate::facades::FeedbackReceiver {
+                self.0.get_feedback()
+            }
+
+            /// Gets the latest snapshot of the game's state as JSON
+            pub fn state_json(&self) -> String {
+                self.0.state_json()
+            }
+
+            /// Gets the game's latest save as JSON, for the page to stash and resume from later
+            pub fn export_state(&self) -> String {
+                self.0.export_state()
+            }
+
+            /// Hands the game a save from `export_state` to resume from
+            pub fn import_state(&self, json: String) {
+                self.0.import_state(json);
+            }
+
+            /// Gets the latest frame of live state for the page to relay to spectators
+            pub fn sync_json(&self) -> String {
+                self.0.sync_json()
+            }
+
+            /// Hands a spectator a frame from a playing runner's `sync_json` to show
+            pub fn apply_sync(&self, json: String) {
+                self.0.apply_sync(json);
+            }
+
+            /// Gets the rotations the game has recorded as CSV, for analyzing offline
+            pub fn export_csv(&self) -> String {
+                self.0.export_csv()
+            }
+
+            /// Sets the JavaScript function diagnostics are passed to as
+            /// `callback(level, message)`
+            pub fn set_log_callback(&mut self, callback: $crate::js_sys::Function) {
+                self.0.set_log_callback(callback);
+            }
+
+            /// Run the Bevy App
+            pub fn run(&mut self) {
+                self.0.run();
+            }
+        }
+    };
+}
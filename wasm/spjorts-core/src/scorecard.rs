@@ -0,0 +1,182 @@
+//! A scorecard HUD shared across turn based games: a per-player table in the corner highlighting
+//! whose turn it is, a banner for announcements and a final card once the game is over
+
+use bevy::prelude::*;
+
+use crate::turns::TurnManager;
+
+/// How long a banner stays up, in seconds
+const BANNER_SECS: f32 = 2.0;
+
+/// What the scorecard HUD shows. Games fill it in and the HUD redraws whenever it or the turn
+/// changes
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScorecardHud {
+    /// Heading above the rows, like the hole's par or the starting score
+    pub title: String,
+    /// One line per player in turn order, the current player's is marked
+    pub rows: Vec<String>,
+    /// Extra lines under the rows, like the club in hand or the darts thrown this visit
+    pub footer: String,
+    /// The final scorecard shown across the middle of the screen, while the game is over
+    pub final_card: Option<String>,
+}
+
+impl ScorecardHud {
+    /// Lays the title, rows and footer out as text, marking the current player's row
+    pub fn render(&self, current: Option<usize>) -> String {
+        let mut lines = vec![self.title.clone()];
+        lines.extend(self.rows.iter().enumerate().map(|(player, row)| {
+            let marker = if Some(player) == current { "> " } else { "  " };
+            format!("{marker}{row}")
+        }));
+        if !self.footer.is_empty() {
+            lines.push(self.footer.clone());
+        }
+        lines.join("\n")
+    }
+}
+
+/// A message shown across the middle of the screen for a moment, like "Bust!"
+#[derive(Resource, Debug, Default)]
+pub struct Banner {
+    /// The message, empty while nothing is shown
+    text: String,
+    /// Seconds left before the message is hidden
+    remaining: f32,
+}
+
+impl Banner {
+    /// Shows a message for [`BANNER_SECS`]
+    pub fn show(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.remaining = BANNER_SECS;
+    }
+}
+
+/// Marks the table text
+#[derive(Component)]
+struct TableText;
+
+/// Marks the banner text
+#[derive(Component)]
+struct BannerText;
+
+/// Marks the final scorecard text
+#[derive(Component)]
+struct FinalCardText;
+
+/// Plugin that adds the scorecard HUD. Games using it add a [`TurnManager`] too, so the HUD can
+/// mark whose turn it is
+pub struct ScorecardHudPlugin;
+
+impl Plugin for ScorecardHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScorecardHud>()
+            .init_resource::<Banner>()
+            .add_systems(Startup, setup_scorecard_hud)
+            .add_systems(Update, (update_table, update_final_card, update_banner));
+    }
+}
+
+/// Spawns the table, banner and final scorecard texts
+fn setup_scorecard_hud(mut commands: Commands<'_, '_>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(24.0),
+        TextColor::WHITE,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        TableText,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(48.0),
+        TextColor::WHITE,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Visibility::Hidden,
+        BannerText,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        TextFont::from_font_size(32.0),
+        TextColor::WHITE,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        Visibility::Hidden,
+        FinalCardText,
+    ));
+}
+
+/// Redraws the table when the HUD's contents or the turn change
+fn update_table(
+    mut text: Query<'_, '_, &mut Text, With<TableText>>,
+    hud: Res<'_, ScorecardHud>,
+    turns: Option<Res<'_, TurnManager>>,
+) {
+    let turn_changed = turns.as_ref().is_some_and(|turns| turns.is_changed());
+    if !hud.is_changed() && !turn_changed {
+        return;
+    }
+    let current = turns.map(|turns| turns.current());
+    for mut text in &mut text {
+        text.0 = hud.render(current);
+    }
+}
+
+/// Shows the final scorecard while there is one
+fn update_final_card(
+    mut card: Query<'_, '_, (&mut Text, &mut Visibility), With<FinalCardText>>,
+    hud: Res<'_, ScorecardHud>,
+) {
+    if !hud.is_changed() {
+        return;
+    }
+    for (mut text, mut visibility) in &mut card {
+        match &hud.final_card {
+            Some(lines) => {
+                text.0.clone_from(lines);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+/// Shows the banner's message until it runs out
+fn update_banner(
+    mut text: Query<'_, '_, (&mut Text, &mut Visibility), With<BannerText>>,
+    mut banner: ResMut<'_, Banner>,
+    time: Res<'_, Time>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else {
+        return;
+    };
+    banner.remaining -= time.delta_secs();
+    if banner.remaining > 0.0 {
+        text.0.clone_from(&banner.text);
+        *visibility = Visibility::Visible;
+    } else {
+        *visibility = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,81 @@
+//! Player tunable game settings
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+
+use crate::communication::Orientation;
+
+/// Settings that games should read instead of relying on hardcoded constants
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameSettings {
+    /// Multiplier applied to all incoming rotation data
+    pub sensitivity: f32,
+    /// Volume from 0.0 (muted) to 1.0
+    pub volume: f32,
+    /// Whether the pitch axis should be inverted
+    pub invert_y: bool,
+    /// Whether aiming aids should be drawn
+    pub aim_guide: bool,
+    /// Whether controls and layout are mirrored for left-handed play
+    pub left_handed: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.0,
+            volume: 1.0,
+            invert_y: false,
+            aim_guide: true,
+            left_handed: false,
+        }
+    }
+}
+
+impl GameSettings {
+    /// Creates a new settings instance, clamping volume to a valid range
+    pub fn new(
+        sensitivity: f32,
+        volume: f32,
+        invert_y: bool,
+        aim_guide: bool,
+        left_handed: bool,
+    ) -> Self {
+        Self {
+            sensitivity,
+            volume: volume.clamp(0.0, 1.0),
+            invert_y,
+            aim_guide,
+            left_handed,
+        }
+    }
+
+    /// `1.0` for right-handed play and `-1.0` for left-handed, for mirroring sideways motion
+    pub fn handedness(&self) -> f32 {
+        if self.left_handed {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Applies sensitivity and axis inversion to an orientation reading
+    pub fn apply_rotation(&self, orientation: Orientation) -> Orientation {
+        let pitch = if self.invert_y {
+            -orientation.pitch
+        } else {
+            orientation.pitch
+        };
+        Orientation::new(
+            pitch * self.sensitivity,
+            orientation.roll * self.sensitivity,
+            orientation.yaw * self.sensitivity,
+        )
+    }
+
+    /// Returns true if audio should be silenced
+    pub fn is_muted(&self) -> bool {
+        self.volume <= 0.0
+    }
+}
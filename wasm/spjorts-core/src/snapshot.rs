@@ -0,0 +1,201 @@
+//! JavaScript readable snapshots of a game's state
+
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "bevy")]
+use bevy::prelude::Resource;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The latest JSON snapshot of a game's state. Games update it as their state changes and the
+/// Runner hands it to JavaScript through `state_json`
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone)]
+pub struct StateSnapshot(Arc<RwLock<String>>);
+
+impl Default for StateSnapshot {
+    fn default() -> Self {
+        Self(Arc::new(RwLock::new("{}".to_string())))
+    }
+}
+
+impl StateSnapshot {
+    /// Serializes and stores a new snapshot of the game's state
+    pub fn set<T: Serialize>(&self, state: &T) {
+        if let Ok(json) = serde_json::to_string(state) {
+            *self.0.write().unwrap() = json;
+        }
+    }
+
+    /// Gets the latest snapshot as a JSON string
+    pub fn json(&self) -> String {
+        self.0.read().unwrap().clone()
+    }
+}
+
+/// The players a page set a session up with, exported alongside a game's save so a resumed game
+/// can be set up the same way again
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSetup {
+    /// Number of human players
+    pub players: usize,
+    /// Skill level of the computer opponent, if there is one
+    pub bot: Option<u8>,
+    /// Names given to players by index, empty if a player hasn't been named
+    pub names: Vec<String>,
+}
+
+/// A save as JavaScript stashes it, the session setup next to the game's own state
+#[derive(Serialize, Deserialize)]
+struct Stashed<T> {
+    /// Players the game was being played by
+    #[serde(flatten)]
+    setup: SessionSetup,
+    /// The game's own save
+    game: T,
+}
+
+/// A game's resumable state. Games store a save whenever their state is worth resuming from, the
+/// Runner hands it to JavaScript through `export_state` along with the session's players and
+/// passes saves back in through `import_state` for the game to load
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Default)]
+pub struct SaveSlot {
+    /// The latest save, `{}` until the game stores one
+    saved: StateSnapshot,
+    /// The players the session was last set up with
+    setup: Arc<RwLock<SessionSetup>>,
+    /// A save handed in by JavaScript that the game hasn't loaded yet
+    pending: Arc<RwLock<Option<String>>>,
+}
+
+impl SaveSlot {
+    /// Serializes and stores a new save
+    pub fn store<T: Serialize>(&self, state: &T) {
+        self.saved.set(state);
+    }
+
+    /// Drops the latest save, once there's nothing left worth resuming
+    pub fn clear(&self) {
+        self.saved.set(&serde_json::json!({}));
+    }
+
+    /// Notes how many human players the session was set up with
+    pub fn record_players(&self, players: usize) {
+        self.setup.write().unwrap().players = players;
+    }
+
+    /// Notes the computer opponent's skill level, or that there isn't one
+    pub fn record_bot(&self, skill: Option<u8>) {
+        self.setup.write().unwrap().bot = skill;
+    }
+
+    /// Notes the name a player was given
+    pub fn record_name(&self, player: usize, name: String) {
+        let names = &mut self.setup.write().unwrap().names;
+        if names.len() <= player {
+            names.resize(player + 1, String::new());
+        }
+        names[player] = name;
+    }
+
+    /// Gets the latest save as a JSON string, along with the players the session was set up
+    /// with. `{}` if the game hasn't stored one
+    pub fn export(&self) -> String {
+        let game: serde_json::Value = serde_json::from_str(&self.saved.json()).unwrap_or_default();
+        if game.as_object().is_none_or(|game| game.is_empty()) {
+            return "{}".to_string();
+        }
+
+        let stashed = Stashed {
+            setup: self.setup.read().unwrap().clone(),
+            game,
+        };
+        serde_json::to_string(&stashed).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Queues a save for the game to load, replacing any it hasn't loaded yet
+    pub fn import(&self, json: String) {
+        *self.pending.write().unwrap() = Some(json);
+    }
+
+    /// Takes the queued save, if there is one, parsed into the game's state
+    pub fn take_import<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        let json = self.pending.write().unwrap().take()?;
+        Some(serde_json::from_str::<Stashed<T>>(&json).map(|stashed| stashed.game))
+    }
+}
+
+/// Live state a playing app publishes every frame for spectators to mirror. The Runner hands the
+/// latest frame to JavaScript through `sync_json`, and a spectator's Runner takes frames relayed
+/// to it through `apply_sync`
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[derive(Debug, Clone, Default)]
+pub struct StateSync {
+    /// The latest frame published by a playing app
+    published: StateSnapshot,
+    /// The newest frame relayed to a spectator that it hasn't applied yet
+    received: Arc<RwLock<Option<String>>>,
+}
+
+impl StateSync {
+    /// Serializes and publishes a new frame
+    pub fn publish<T: Serialize>(&self, frame: &T) {
+        self.published.set(frame);
+    }
+
+    /// Gets the latest published frame as a JSON string
+    pub fn latest(&self) -> String {
+        self.published.json()
+    }
+
+    /// Queues a relayed frame for a spectator to apply, replacing any older frame it hasn't
+    /// applied yet
+    pub fn receive(&self, json: String) {
+        *self.received.write().unwrap() = Some(json);
+    }
+
+    /// Takes the newest relayed frame, if there is one, parsed into the game's sync frame
+    pub fn take<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        let json = self.received.write().unwrap().take()?;
+        Some(serde_json::from_str(&json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{SaveSlot, SessionSetup};
+
+    #[test]
+    fn export_carries_the_session_setup() {
+        let slot = SaveSlot::default();
+        slot.record_players(1);
+        slot.record_bot(Some(7));
+        slot.record_name(0, "Ada".to_string());
+        assert_eq!(slot.export(), "{}");
+
+        slot.store(&HashMap::from([("frame", 4)]));
+        let exported: serde_json::Value = serde_json::from_str(&slot.export()).unwrap();
+        let setup: SessionSetup = serde_json::from_value(exported.clone()).unwrap();
+        assert_eq!(
+            setup,
+            SessionSetup {
+                players: 1,
+                bot: Some(7),
+                names: vec!["Ada".to_string()],
+            }
+        );
+        assert_eq!(exported["game"]["frame"], 4);
+    }
+
+    #[test]
+    fn import_hands_the_game_only_its_own_save() {
+        let slot = SaveSlot::default();
+        slot.import(r#"{"players":2,"bot":null,"names":[],"game":{"frame":4}}"#.to_string());
+
+        let game = slot.take_import::<HashMap<String, u32>>().unwrap().unwrap();
+        assert_eq!(game, HashMap::from([("frame".to_string(), 4)]));
+        assert!(slot.take_import::<HashMap<String, u32>>().is_none());
+    }
+}
@@ -0,0 +1,47 @@
+//! Render-only spectator apps that mirror a game played in another browser
+
+use bevy::prelude::*;
+
+use crate::ActionReader;
+
+/// Whether an app plays the game or only shows one played elsewhere
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunMode {
+    /// Takes controller input and runs the game
+    #[default]
+    Play,
+    /// Ignores controller input and shows the state synced in from a playing app
+    Spectate,
+}
+
+impl RunMode {
+    /// Whether this app only shows a game played elsewhere
+    pub fn is_spectating(&self) -> bool {
+        *self == Self::Spectate
+    }
+}
+
+/// Run condition for systems that drive the game, which a spectator leaves to the playing app
+pub fn is_playing(mode: Res<'_, RunMode>) -> bool {
+    !mode.is_spectating()
+}
+
+/// Run condition for systems that apply synced state to a spectator
+pub fn is_spectating(mode: Res<'_, RunMode>) -> bool {
+    mode.is_spectating()
+}
+
+/// Plugin that keeps a spectator's unused input channel from filling up
+pub struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunMode>()
+            .add_systems(First, drop_input.run_if(is_spectating));
+    }
+}
+
+/// Drops controller input sent to a spectator, the playing app is the one that acts on it
+fn drop_input(reader: Res<'_, ActionReader>) {
+    while reader.0.try_recv().is_ok() {}
+}
@@ -0,0 +1,59 @@
+//! Swing sampling shared by games that release a ball with a button press mid swing, measuring
+//! how fast the controller was turning over the last moment before the press
+
+use bevy::math::Quat;
+
+/// How far back from the newest rotation a swing is measured over, in seconds
+pub const SAMPLE_WINDOW: f32 = 0.1;
+
+/// Recent controller rotations, each with the seconds since startup it was read at
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwingSampler {
+    /// Rotations inside the sample window, oldest first
+    rotations: Vec<(Quat, f32)>,
+}
+
+impl SwingSampler {
+    /// Records a rotation read at `at` seconds since startup, dropping samples that have fallen
+    /// out of the [`SAMPLE_WINDOW`] but always keeping the one before the newest
+    pub fn record(&mut self, rotation: Quat, at: f32) {
+        self.rotations.push((rotation, at));
+
+        let stale = self
+            .rotations
+            .iter()
+            .take_while(|(_, read_at)| at - read_at > SAMPLE_WINDOW)
+            .count()
+            .min(self.rotations.len().saturating_sub(2));
+        self.rotations.drain(..stale);
+    }
+
+    /// Forgets every recorded rotation, ready for the next swing
+    pub fn clear(&mut self) {
+        self.rotations.clear();
+    }
+
+    /// Oldest and newest recorded rotations, if there are any
+    pub fn ends(&self) -> Option<(Quat, Quat)> {
+        Some((self.rotations.first()?.0, self.rotations.last()?.0))
+    }
+
+    /// Seconds between the oldest and newest recorded rotation, if there's enough to measure
+    pub fn span(&self) -> Option<f32> {
+        let (first, last) = (self.rotations.first()?, self.rotations.last()?);
+        let span = last.1 - first.1;
+        (self.rotations.len() >= 2 && span > f32::EPSILON).then_some(span)
+    }
+
+    /// How fast the controller swung over the recent rotations in radians per second, if there's
+    /// enough to measure
+    pub fn angular_velocity(&self) -> Option<f32> {
+        let span = self.span()?;
+        let swept: f32 = self
+            .rotations
+            .windows(2)
+            .map(|pair| 2.0 * pair[0].0.dot(pair[1].0).clamp(-1.0, 1.0).acos())
+            .sum();
+        Some(swept / span)
+    }
+}
@@ -0,0 +1,98 @@
+//! Turn order shared across games, kept in step with the [`PlayerRegistry`]
+
+use bevy::prelude::*;
+
+use crate::players::PlayerRegistry;
+
+/// Whose turn it is, cycling through every player including any computer opponent
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnManager {
+    /// How many players take turns, always at least one
+    players: usize,
+    /// Index of the player whose turn it is
+    current: usize,
+    /// How many times every player has had a turn, starting from zero
+    round: usize,
+}
+
+impl Default for TurnManager {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl TurnManager {
+    /// Creates a turn order for a number of players, at least one, starting with the first
+    pub fn new(players: usize) -> Self {
+        Self {
+            players: players.max(1),
+            current: 0,
+            round: 0,
+        }
+    }
+
+    /// How many players take turns
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Index of the player whose turn it is
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// How many times every player has had a turn, starting from zero
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Passes the turn to the next player, starting a new round after the last. Returns who's
+    /// up next
+    pub fn advance(&mut self) -> usize {
+        self.current += 1;
+        if self.current >= self.players {
+            self.current = 0;
+            self.round += 1;
+        }
+        self.current
+    }
+
+    /// Passes the turn to the next player who isn't `done`, returning who that is, or `None`
+    /// without changing turns if everyone is
+    pub fn advance_until(&mut self, mut done: impl FnMut(usize) -> bool) -> Option<usize> {
+        if (0..self.players).all(&mut done) {
+            return None;
+        }
+
+        while done(self.advance()) {}
+        Some(self.current)
+    }
+
+    /// Starts over from the first player's first turn
+    pub fn restart(&mut self) {
+        *self = Self::new(self.players);
+    }
+}
+
+/// Whether the computer opponent has the current turn. It always takes the last turn of a round
+pub fn bot_has_turn(registry: &PlayerRegistry, turns: &TurnManager) -> bool {
+    registry.bot().is_some() && turns.current() == registry.count()
+}
+
+/// Plugin that adds a [`TurnManager`], starting it over whenever the players change
+pub struct TurnPlugin;
+
+impl Plugin for TurnPlugin {
+    fn build(&self, app: &mut App) {
+        // Players are registered in `PreUpdate`, so any change has landed by `Update`
+        app.init_resource::<TurnManager>().add_systems(
+            Update,
+            sync_turns.run_if(resource_changed::<PlayerRegistry>),
+        );
+    }
+}
+
+/// Starts the turn order over for the registry's players
+fn sync_turns(registry: Res<'_, PlayerRegistry>, mut turns: ResMut<'_, TurnManager>) {
+    turns.set_if_neq(TurnManager::new(registry.total()));
+}
@@ -0,0 +1,226 @@
+//! The court: its lines, the net, the ball and the two players standing on either side
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::{
+    ActiveEvents, Ccd, Collider, Friction, Restitution, RigidBody, Velocity,
+};
+use serde::{Deserialize, Serialize};
+
+/// Half the length of the court, from the net to a baseline
+pub const HALF_LENGTH: f32 = 11.885;
+/// Half the width of the singles court
+pub const HALF_WIDTH: f32 = 4.115;
+/// Distance from the net to each service line
+pub const SERVICE_LINE: f32 = 6.4;
+/// Height of the net at its middle
+pub const NET_HEIGHT: f32 = 0.914;
+/// Radius of the ball, scaled up from a real one so it can be followed across the court
+pub const BALL_RADIUS: f32 = 0.06;
+/// How far behind the baseline players stand to receive
+pub const BASELINE_GAP: f32 = 0.8;
+/// How high above the ground players meet the ball
+pub const CONTACT_HEIGHT: f32 = 1.0;
+/// How far to their forehand side players meet the ball
+const FOREHAND_REACH: f32 = 0.6;
+
+/// Width of the painted lines
+const LINE_WIDTH: f32 = 0.05;
+/// How far the ground runs past the court's lines
+const RUNOFF: f32 = 6.0;
+/// Half the width of the net, spanning the doubles alleys
+const NET_HALF_WIDTH: f32 = 5.5;
+
+/// One end of the court
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The end nearest the camera
+    Near,
+    /// The end across the net
+    Far,
+}
+
+impl Side {
+    /// The end across the net from this one
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Near => Self::Far,
+            Self::Far => Self::Near,
+        }
+    }
+
+    /// Which way along z this end lies from the net, positive for the near end
+    pub fn sign(self) -> f32 {
+        match self {
+            Self::Near => 1.0,
+            Self::Far => -1.0,
+        }
+    }
+
+    /// Which end of the court a spot is on
+    pub fn of(position: Vec3) -> Self {
+        if position.z >= 0.0 {
+            Self::Near
+        } else {
+            Self::Far
+        }
+    }
+
+    /// Index of the end, near first, for per-side arrays
+    pub fn index(self) -> usize {
+        match self {
+            Self::Near => 0,
+            Self::Far => 1,
+        }
+    }
+
+    /// Where a player on this end waits for the ball, just behind the middle of the baseline
+    pub fn home(self) -> Vec3 {
+        Vec3::new(0.0, 0.0, (HALF_LENGTH + BASELINE_GAP) * self.sign())
+    }
+}
+
+/// Whether a bounce at a spot lands inside the singles court, lines included
+pub fn in_court(position: Vec3) -> bool {
+    position.x.abs() <= HALF_WIDTH + LINE_WIDTH / 2.0
+        && position.z.abs() <= HALF_LENGTH + LINE_WIDTH / 2.0
+}
+
+/// Marks the ball
+#[derive(Component, Debug, Default)]
+pub struct Ball;
+
+/// Marks the court's surface, which the ball bounces on
+#[derive(Component, Debug)]
+pub struct CourtSurface;
+
+/// A player standing on one end of the court
+#[derive(Component, Debug)]
+pub struct Player {
+    /// The end they play from
+    pub side: Side,
+}
+
+impl Player {
+    /// Where the player meets the ball, out to their forehand side. `handedness` is `-1.0` for
+    /// a left-handed player
+    pub fn contact(&self, transform: &Transform, handedness: f32) -> Vec3 {
+        let forehand = FOREHAND_REACH * self.side.sign() * handedness;
+        Vec3::new(
+            transform.translation.x + forehand,
+            CONTACT_HEIGHT,
+            transform.translation.z,
+        )
+    }
+}
+
+/// Plugin that lays out the court, the ball and the players
+pub struct CourtPlugin;
+
+impl Plugin for CourtPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_court);
+    }
+}
+
+/// Spawns the surface, lines, net, ball, players, camera and light
+fn setup_court(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    let ground_half = Vec2::new(NET_HALF_WIDTH + RUNOFF, HALF_LENGTH + RUNOFF);
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(ground_half.x * 2.0, ground_half.y * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.45, 0.65))),
+        Name::new("Court"),
+    ));
+    commands.spawn((
+        Transform::from_xyz(0.0, -0.1, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(ground_half.x, 0.1, ground_half.y),
+        Restitution::coefficient(0.75),
+        Friction::coefficient(0.4),
+        CourtSurface,
+    ));
+
+    // Baselines, sidelines, service lines and the centre service line
+    let line = materials.add(Color::WHITE);
+    let mut paint = |size: Vec2, at: Vec2| {
+        commands.spawn((
+            Mesh3d(meshes.add(Plane3d::default().mesh().size(size.x, size.y))),
+            MeshMaterial3d(line.clone()),
+            Transform::from_xyz(at.x, 0.005, at.y),
+        ));
+    };
+    for sign in [-1.0, 1.0] {
+        paint(
+            Vec2::new(HALF_WIDTH * 2.0, LINE_WIDTH),
+            Vec2::new(0.0, HALF_LENGTH * sign),
+        );
+        paint(
+            Vec2::new(LINE_WIDTH, HALF_LENGTH * 2.0),
+            Vec2::new(HALF_WIDTH * sign, 0.0),
+        );
+        paint(
+            Vec2::new(HALF_WIDTH * 2.0, LINE_WIDTH),
+            Vec2::new(0.0, SERVICE_LINE * sign),
+        );
+    }
+    paint(Vec2::new(LINE_WIDTH, SERVICE_LINE * 2.0), Vec2::ZERO);
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(NET_HALF_WIDTH * 2.0, NET_HEIGHT, 0.02))),
+        MeshMaterial3d(materials.add(Color::srgba(0.95, 0.95, 0.95, 0.6))),
+        Transform::from_xyz(0.0, NET_HEIGHT / 2.0, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(NET_HALF_WIDTH, NET_HEIGHT / 2.0, 0.01),
+        Restitution::coefficient(0.1),
+        Name::new("Net"),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(0.85, 1.0, 0.2))),
+        Transform::from_translation(Side::Far.home() + Vec3::Y * CONTACT_HEIGHT),
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        Restitution::coefficient(0.75),
+        Ccd::enabled(),
+        Velocity::zero(),
+        ActiveEvents::COLLISION_EVENTS,
+        Ball,
+    ));
+
+    let body = meshes.add(Capsule3d::new(0.3, 1.2));
+    for (side, color) in [
+        (Side::Near, Color::srgb(0.9, 0.3, 0.2)),
+        (Side::Far, Color::srgb(0.95, 0.85, 0.3)),
+    ] {
+        commands.spawn((
+            Mesh3d(body.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(side.home() + Vec3::Y * 0.9),
+            Player { side },
+        ));
+    }
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 5.5, HALF_LENGTH + 9.0)
+            .looking_at(Vec3::new(0.0, 0.0, 2.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 12.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
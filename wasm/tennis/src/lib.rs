@@ -0,0 +1,289 @@
+//! Bevy tennis game
+
+use ai::TennisAiPlugin;
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::{
+    plugin::{NoUserData, RapierPhysicsPlugin},
+    prelude::{RigidBody, Velocity},
+};
+use court::{Ball, CourtPlugin, Player, Side, SERVICE_LINE};
+use lineup::{Lineup, LineupPlugin};
+use phase::{TennisPhase, TennisPhasePlugin};
+use rally::{NewMatch, Rally, RallyPlugin};
+use score::TennisScore;
+use scoreboard::ScoreboardPlugin;
+use spjorts_core::{
+    communication::{JsMessage, Orientation},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    settings::GameSettings,
+    spectator::is_playing,
+    ActionReader,
+};
+
+pub mod ai;
+pub mod court;
+pub mod lineup;
+pub mod phase;
+pub mod rally;
+pub mod score;
+pub mod scoreboard;
+
+/// How fast the controller has to turn for a full power swing, in radians per second
+const FULL_SWING_SPEED: f32 = 14.0;
+/// Weakest a detected swing hits the ball, as a fraction of a full swing
+const MIN_POWER: f32 = 0.2;
+/// Furthest the ball can be from a player's contact point along the ground and still be hit, in
+/// meters
+pub const HIT_REACH: f32 = 1.5;
+/// How far across the court a radian of racket yaw sends the ball, in meters
+pub const AIM_SWAY: f32 = 5.0;
+/// How far across the court a swing a full reach early or late pulls the ball, in meters
+const TIMING_SWAY: f32 = 3.0;
+/// How far past the net a flat shot lands, in meters
+const BASE_DEPTH: f32 = 9.0;
+/// How far past the net a flat serve lands, in meters
+const SERVE_DEPTH: f32 = SERVICE_LINE - 1.2;
+/// How much further a radian of racket pitch sends the ball, in meters
+const DEPTH_PER_PITCH: f32 = 5.0;
+/// How long the softest shot takes to land, in seconds
+const SLOW_FLIGHT_SECS: f32 = 1.6;
+/// How long the hardest shot takes to land, in seconds
+const FAST_FLIGHT_SECS: f32 = 0.8;
+/// Acceleration the ball falls at, matching the physics' gravity
+const GRAVITY: f32 = 9.81;
+/// How fast players run across the court, in meters per second
+const RUN_SPEED: f32 = 6.0;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins(TennisPhasePlugin)
+    .add_plugins(LineupPlugin)
+    .add_plugins(CourtPlugin)
+    .add_plugins(RallyPlugin)
+    .add_plugins(ScoreboardPlugin)
+    .add_plugins(TennisAiPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Rackets>()
+    .add_event::<Swing>()
+    .add_systems(
+        Update,
+        (handle_input, resolve_swings, move_players)
+            .chain()
+            .run_if(is_playing),
+    );
+});
+
+/// Watches each end's racket for swings, near first
+#[derive(Resource, Debug, Default)]
+pub struct Rackets([GestureDetector; 2]);
+
+/// A swing of the racket from one end of the court
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Swing {
+    /// The end swinging
+    pub side: Side,
+    /// How hard the swing was, from 0 to 1
+    pub power: f32,
+    /// Yaw of the racket, in radians. Turning it one way sends the ball across the court
+    pub aim: f32,
+    /// Pitch of the racket, in radians. Opening the face up sends the ball deeper
+    pub lift: f32,
+}
+
+impl Swing {
+    /// Where on the other end the swing sends the ball. Meeting the ball early pulls it across
+    /// the body and meeting it late pushes it the other way, by how far `timing` is from a
+    /// perfect contact as a fraction of [`HIT_REACH`]
+    fn target(&self, depth: f32, timing: f32, handedness: f32) -> Vec3 {
+        let across = -self.aim * AIM_SWAY + timing * TIMING_SWAY * handedness;
+        let depth = depth + self.lift * DEPTH_PER_PITCH;
+        let sign = self.side.sign();
+        Vec3::new(across * sign, 0.0, -depth * sign)
+    }
+
+    /// How long the ball takes to land, shorter the harder the swing
+    fn flight_secs(&self) -> f32 {
+        SLOW_FLIGHT_SECS + (FAST_FLIGHT_SECS - SLOW_FLIGHT_SECS) * self.power.clamp(0.0, 1.0)
+    }
+}
+
+/// Which hand the player on an end holds the racket in, `-1.0` for left. The computer is always
+/// right-handed
+pub fn handedness(side: Side, lineup: &Lineup, settings: &GameSettings) -> f32 {
+    if lineup.player(side).is_some() {
+        settings.handedness()
+    } else {
+        1.0
+    }
+}
+
+/// Velocity that carries the ball from `from` to land on `to` after `secs` of flight
+fn ballistic(from: Vec3, to: Vec3, secs: f32) -> Vec3 {
+    (to - from) / secs + Vec3::Y * GRAVITY * secs / 2.0
+}
+
+/// Where the ball will cross the plane a player meets it on, if it's heading their way
+pub fn crossing_x(ball: Vec3, velocity: Vec3, contact_z: f32) -> Option<f32> {
+    let secs = (contact_z - ball.z) / velocity.z;
+    (secs.is_finite() && secs > 0.0).then_some(ball.x + velocity.x * secs)
+}
+
+/// Everything input handling changes besides the rackets
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Swings to hit the ball with
+    swings: EventWriter<'w, Swing>,
+    /// Requests to start over
+    new_match: EventWriter<'w, NewMatch>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: each player's rotation swings the racket on their end, and A starts
+/// a new match once one is over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut rackets: ResMut<'_, Rackets>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<TennisPhase>>,
+    lineup: Res<'_, Lineup>,
+    time: Res<'_, Time>,
+) {
+    while let Ok(msg) = read.0.try_recv() {
+        let (player, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_match.send(NewMatch);
+            }
+            JsMessage::ButtonA if *phase.get() == TennisPhase::MatchOver => {
+                effects.new_match.send(NewMatch);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) => {
+                let Some(side) = lineup.side_of(player) else {
+                    continue;
+                };
+                let orientation @ Orientation { pitch, yaw, .. } =
+                    settings.apply_rotation(orientation);
+                let Some(detected) =
+                    rackets.0[side.index()].update(orientation, time.elapsed_secs())
+                else {
+                    continue;
+                };
+                if matches!(detected.gesture, Gesture::Swing | Gesture::Flick) {
+                    let power = detected.intensity / FULL_SWING_SPEED;
+                    effects.swings.send(Swing {
+                        side,
+                        power: power.clamp(MIN_POWER, 1.0),
+                        aim: yaw,
+                        lift: pitch,
+                    });
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// The state of the match a swing is judged against
+#[derive(SystemParam)]
+struct MatchState<'w> {
+    /// Where the match is at
+    phase: Res<'w, State<TennisPhase>>,
+    /// Who is serving
+    score: Res<'w, TennisScore>,
+    /// Who plays each end
+    lineup: Res<'w, Lineup>,
+    /// Player settings, for handedness
+    settings: Res<'w, GameSettings>,
+}
+
+/// Hits the ball for swings that meet it: the server's swing serves the held ball, and in a rally
+/// a swing hits the ball back if it's coming that end's way and within reach
+fn resolve_swings(
+    mut swings: EventReader<'_, '_, Swing>,
+    mut ball: Query<'_, '_, (&Transform, &mut Velocity, &mut RigidBody), With<Ball>>,
+    players: Query<'_, '_, (&Transform, &Player), Without<Ball>>,
+    mut rally: ResMut<'_, Rally>,
+    mut next_phase: ResMut<'_, NextState<TennisPhase>>,
+    state: MatchState<'_>,
+) {
+    let Ok((transform, mut velocity, mut body)) = ball.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation;
+
+    for swing in swings.read() {
+        let handedness = handedness(swing.side, &state.lineup, &state.settings);
+        let target = match *state.phase.get() {
+            TennisPhase::Serving if swing.side == state.score.server() => {
+                swing.target(SERVE_DEPTH, 0.0, handedness)
+            }
+            TennisPhase::Rally if rally.incoming_to(swing.side) => {
+                let Some((player, contact)) = players
+                    .iter()
+                    .find(|(_, player)| player.side == swing.side)
+                    .map(|(transform, player)| (player, player.contact(transform, handedness)))
+                else {
+                    continue;
+                };
+                // Players reach up or down for the ball, so only how far off it is along the
+                // ground matters
+                if (position - contact).with_y(0.0).length() > HIT_REACH {
+                    continue;
+                }
+                let timing = (position.z - contact.z) * player.side.sign() / HIT_REACH;
+                swing.target(BASE_DEPTH, timing, handedness)
+            }
+            _ => continue,
+        };
+
+        *body = RigidBody::Dynamic;
+        *velocity = Velocity::linear(ballistic(position, target, swing.flight_secs()));
+        rally.hit_by(swing.side);
+        next_phase.set(TennisPhase::Rally);
+        return;
+    }
+}
+
+/// Runs each player across their baseline to meet the ball on their forehand, heading back to the
+/// middle when it's going the other way
+fn move_players(
+    mut players: Query<'_, '_, (&mut Transform, &Player), Without<Ball>>,
+    ball: Query<'_, '_, (&Transform, &Velocity), With<Ball>>,
+    rally: Res<'_, Rally>,
+    phase: Res<'_, State<TennisPhase>>,
+    lineup: Res<'_, Lineup>,
+    settings: Res<'_, GameSettings>,
+    time: Res<'_, Time>,
+) {
+    if *phase.get() != TennisPhase::Rally {
+        return;
+    }
+    let Ok((ball, velocity)) = ball.get_single() else {
+        return;
+    };
+
+    for (mut transform, player) in &mut players {
+        let contact = player.contact(&transform, handedness(player.side, &lineup, &settings));
+        let target = if rally.incoming_to(player.side) {
+            crossing_x(ball.translation, velocity.linvel, contact.z)
+                .map(|x| x - (contact.x - transform.translation.x))
+        } else {
+            None
+        }
+        .unwrap_or(player.side.home().x);
+
+        let step = RUN_SPEED * time.delta_secs();
+        transform.translation.x += (target - transform.translation.x).clamp(-step, step);
+    }
+}
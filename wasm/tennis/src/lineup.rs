@@ -0,0 +1,72 @@
+//! Who plays each end of the court: the first player always takes the near end, and the far end
+//! goes to the second player when there is one or the computer otherwise
+
+use bevy::prelude::*;
+use spjorts_core::players::PlayerRegistry;
+
+use crate::court::Side;
+
+/// Which player controls each end of the court, near first. `None` is the computer
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lineup([Option<usize>; 2]);
+
+impl Default for Lineup {
+    fn default() -> Self {
+        Self([Some(0), None])
+    }
+}
+
+impl Lineup {
+    /// The player controlling an end, or `None` for the computer
+    pub fn player(&self, side: Side) -> Option<usize> {
+        self.0[side.index()]
+    }
+
+    /// The end a player controls, if they're playing
+    pub fn side_of(&self, player: usize) -> Option<Side> {
+        [Side::Near, Side::Far]
+            .into_iter()
+            .find(|side| self.player(*side) == Some(player))
+    }
+
+    /// Whether both ends are played by people, who then take turns serving
+    pub fn is_two_player(&self) -> bool {
+        self.0.iter().all(Option::is_some)
+    }
+
+    /// The end that serves first. Against the computer it serves every game for the player to
+    /// return
+    pub fn first_server(&self) -> Side {
+        if self.is_two_player() {
+            Side::Near
+        } else {
+            Side::Far
+        }
+    }
+
+    /// What to call the player on an end
+    pub fn name(&self, side: Side) -> String {
+        match self.player(side) {
+            Some(player) => format!("Player {}", player + 1),
+            None => "Computer".to_string(),
+        }
+    }
+}
+
+/// Plugin that keeps the [`Lineup`] in step with the players
+pub struct LineupPlugin;
+
+impl Plugin for LineupPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lineup>().add_systems(
+            Update,
+            sync_lineup.run_if(resource_changed::<PlayerRegistry>),
+        );
+    }
+}
+
+/// Gives the far end to a second player when there is one
+fn sync_lineup(registry: Res<'_, PlayerRegistry>, mut lineup: ResMut<'_, Lineup>) {
+    let far = (registry.count() >= 2).then_some(1);
+    lineup.set_if_neq(Lineup([Some(0), far]));
+}
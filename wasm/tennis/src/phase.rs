@@ -0,0 +1,27 @@
+//! Phases a tennis match moves through, from each serve to match point
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the match is in the flow of a game
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TennisPhase {
+    /// The server is holding the ball, waiting to swing
+    #[default]
+    Serving,
+    /// The ball is in play
+    Rally,
+    /// The point has been decided and players are walking back for the next serve
+    PointOver,
+    /// An end has won enough games and the final score is up
+    MatchOver,
+}
+
+/// Plugin that tracks which phase the match is in
+pub struct TennisPhasePlugin;
+
+impl Plugin for TennisPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<TennisPhase>();
+    }
+}
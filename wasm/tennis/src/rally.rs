@@ -0,0 +1,236 @@
+//! Rules of a rally: the ball has to clear the net and land in court, and a point goes to the
+//! hitter once it bounces twice on the other end. Also sets each serve up and starts matches over
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_rapier3d::prelude::{CollisionEvent, RigidBody, Velocity};
+use spjorts_core::{scorecard::Banner, settings::GameSettings, spectator::is_playing};
+
+use crate::{
+    court::{in_court, Ball, CourtSurface, Player, Side, HALF_LENGTH},
+    handedness,
+    lineup::Lineup,
+    phase::TennisPhase,
+    score::{PointOutcome, TennisScore},
+};
+
+/// How long players get to walk back between points, in seconds
+const POINT_PAUSE_SECS: f32 = 1.5;
+/// How far above the server's contact point the ball is tossed
+const TOSS_HEIGHT: f32 = 0.4;
+/// How far from the court a ball can go before it's counted where it is
+const LOST_DISTANCE: f32 = HALF_LENGTH + 15.0;
+
+/// The state of the point being played
+#[derive(Resource, Debug)]
+pub struct Rally {
+    /// The end that hit the ball last, `None` until the serve
+    pub last_hitter: Option<Side>,
+    /// How many times the ball has bounced since it was last hit
+    bounces: u32,
+    /// Counts down the pause after a point
+    pause: Timer,
+}
+
+impl Default for Rally {
+    fn default() -> Self {
+        Self {
+            last_hitter: None,
+            bounces: 0,
+            pause: Timer::from_seconds(POINT_PAUSE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Rally {
+    /// Records that an end just hit the ball
+    pub fn hit_by(&mut self, side: Side) {
+        self.last_hitter = Some(side);
+        self.bounces = 0;
+    }
+
+    /// Whether the ball is on its way to an end, to be hit back
+    pub fn incoming_to(&self, side: Side) -> bool {
+        self.last_hitter == Some(side.opponent())
+    }
+
+    /// Works out who, if anyone, wins the point from the ball bouncing at a spot. The first
+    /// bounce after a hit has to be in court on the far end from the hitter, and a second
+    /// bounce wins the hitter the point
+    fn bounce(&mut self, at: Vec3) -> Option<(Side, &'static str)> {
+        let hitter = self.last_hitter?;
+        self.bounces += 1;
+        if self.bounces > 1 {
+            return Some((hitter, "Winner!"));
+        }
+        if Side::of(at) == hitter {
+            Some((hitter.opponent(), "Net!"))
+        } else if !in_court(at) {
+            Some((hitter.opponent(), "Out!"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Asks for the match to be started over
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewMatch;
+
+/// Plugin that runs the rules of each rally and the flow between points
+pub struct RallyPlugin;
+
+impl Plugin for RallyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rally>()
+            .insert_resource(TennisScore::new(Lineup::default().first_server()))
+            .add_event::<NewMatch>()
+            .add_systems(OnEnter(TennisPhase::Serving), prepare_serve)
+            .add_systems(OnEnter(TennisPhase::PointOver), reset_pause)
+            .add_systems(
+                Update,
+                (
+                    watch_ball.run_if(in_state(TennisPhase::Rally)),
+                    finish_point.run_if(in_state(TennisPhase::PointOver)),
+                    new_match_for_lineup.run_if(resource_changed::<Lineup>),
+                    start_new_match,
+                    // A new match started mid serve doesn't leave the phase, so it's set up here
+                    prepare_serve
+                        .run_if(in_state(TennisPhase::Serving))
+                        .run_if(resource_changed::<TennisScore>),
+                )
+                    .chain()
+                    .run_if(is_playing),
+            );
+    }
+}
+
+/// Holds the ball up over the server's contact point and sends players back to their marks
+fn prepare_serve(
+    mut ball: Query<'_, '_, (&mut Transform, &mut Velocity, &mut RigidBody), With<Ball>>,
+    mut players: Query<'_, '_, (&mut Transform, &Player), Without<Ball>>,
+    mut rally: ResMut<'_, Rally>,
+    score: Res<'_, TennisScore>,
+    lineup: Res<'_, Lineup>,
+    settings: Res<'_, GameSettings>,
+) {
+    *rally = Rally::default();
+    let mut toss = Vec3::ZERO;
+    for (mut transform, player) in &mut players {
+        let home = player.side.home();
+        transform.translation.x = home.x;
+        transform.translation.z = home.z;
+        if player.side == score.server() {
+            let handedness = handedness(player.side, &lineup, &settings);
+            toss = player.contact(&transform, handedness) + Vec3::Y * TOSS_HEIGHT;
+        }
+    }
+
+    for (mut transform, mut velocity, mut body) in &mut ball {
+        transform.translation = toss;
+        *velocity = Velocity::zero();
+        *body = RigidBody::KinematicPositionBased;
+    }
+}
+
+/// Everything that changes once a point is decided
+#[derive(SystemParam)]
+struct Umpire<'w> {
+    /// The match score
+    score: ResMut<'w, TennisScore>,
+    /// Calls shown to players
+    banner: ResMut<'w, Banner>,
+    /// Where the match goes next
+    next_phase: ResMut<'w, NextState<TennisPhase>>,
+    /// Who plays each end, to name the winner
+    lineup: Res<'w, Lineup>,
+}
+
+impl Umpire<'_> {
+    /// Awards a point, calling it and moving on to the next point or the end of the match
+    fn award(&mut self, winner: Side, call: &str) {
+        match self.score.point_won(winner, self.lineup.is_two_player()) {
+            PointOutcome::Point => {
+                self.banner.show(call);
+                self.next_phase.set(TennisPhase::PointOver);
+            }
+            PointOutcome::Game => {
+                let name = self.lineup.name(winner);
+                self.banner.show(format!("{call} Game, {name}"));
+                self.next_phase.set(TennisPhase::PointOver);
+            }
+            PointOutcome::Match => {
+                let name = self.lineup.name(winner);
+                self.banner.show(format!("Game, set and match, {name}"));
+                self.next_phase.set(TennisPhase::MatchOver);
+            }
+        }
+    }
+}
+
+/// Watches the ball bounce, awarding the point once a bounce decides it
+fn watch_ball(
+    mut collisions: EventReader<'_, '_, CollisionEvent>,
+    ball: Query<'_, '_, (Entity, &Transform), With<Ball>>,
+    surfaces: Query<'_, '_, (), With<CourtSurface>>,
+    mut rally: ResMut<'_, Rally>,
+    mut umpire: Umpire<'_>,
+) {
+    let Ok((ball, transform)) = ball.get_single() else {
+        return;
+    };
+    let position = transform.translation;
+
+    let mut decided = None;
+    for collision in collisions.read() {
+        let CollisionEvent::Started(first, second, _) = *collision else {
+            continue;
+        };
+        let other = if first == ball { second } else { first };
+        if (first == ball || second == ball) && surfaces.contains(other) && decided.is_none() {
+            decided = rally.bounce(position);
+        }
+    }
+    // A ball knocked clear of the court's surroundings lands wherever it left
+    if decided.is_none() && (position.z.abs() > LOST_DISTANCE || position.y < -1.0) {
+        decided = rally.bounce(position);
+    }
+
+    if let Some((winner, call)) = decided {
+        umpire.award(winner, call);
+    }
+}
+
+/// Restarts the pause between points
+fn reset_pause(mut rally: ResMut<'_, Rally>) {
+    rally.pause.reset();
+}
+
+/// Moves on to the next serve once players have had a moment
+fn finish_point(
+    mut rally: ResMut<'_, Rally>,
+    mut next_phase: ResMut<'_, NextState<TennisPhase>>,
+    time: Res<'_, Time>,
+) {
+    if rally.pause.tick(time.delta()).just_finished() {
+        next_phase.set(TennisPhase::Serving);
+    }
+}
+
+/// Starts a fresh match whenever a second player joins or leaves
+fn new_match_for_lineup(mut requests: EventWriter<'_, NewMatch>) {
+    requests.send(NewMatch);
+}
+
+/// Starts the match over with the lineup's first server
+fn start_new_match(
+    mut requests: EventReader<'_, '_, NewMatch>,
+    mut score: ResMut<'_, TennisScore>,
+    mut next_phase: ResMut<'_, NextState<TennisPhase>>,
+    lineup: Res<'_, Lineup>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+    *score = TennisScore::new(lineup.first_server());
+    next_phase.set(TennisPhase::Serving);
+}
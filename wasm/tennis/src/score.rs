@@ -0,0 +1,113 @@
+//! Rally scoring: points go love, 15, 30, 40 and game, with deuce and advantage once both ends
+//! reach 40. The first end to win enough games takes the match
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::court::Side;
+
+/// Games an end has to win to take the match
+pub const GAMES_TO_WIN: u8 = 3;
+
+/// What winning a point did
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointOutcome {
+    /// The game goes on
+    Point,
+    /// The point won the game, and the next game is underway
+    Game,
+    /// The point won the match
+    Match,
+}
+
+/// Points and games on each end, near first
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TennisScore {
+    /// Points won in the current game
+    points: [u8; 2],
+    /// Games won in the match
+    games: [u8; 2],
+    /// The end serving the current game
+    server: Side,
+    /// The end that won the match, once one has
+    winner: Option<Side>,
+}
+
+impl TennisScore {
+    /// Starts a match with an end serving first
+    pub fn new(server: Side) -> Self {
+        Self {
+            points: [0; 2],
+            games: [0; 2],
+            server,
+            winner: None,
+        }
+    }
+
+    /// The end serving the current game
+    pub fn server(&self) -> Side {
+        self.server
+    }
+
+    /// Games an end has won
+    pub fn games(&self, side: Side) -> u8 {
+        self.games[side.index()]
+    }
+
+    /// The end that won the match, once one has
+    pub fn winner(&self) -> Option<Side> {
+        self.winner
+    }
+
+    /// Awards a point to an end. Once a game is won, `alternate_serve` decides whether the serve
+    /// passes to the other end
+    pub fn point_won(&mut self, side: Side, alternate_serve: bool) -> PointOutcome {
+        let (won, lost) = (side.index(), side.opponent().index());
+        self.points[won] += 1;
+        if self.points[won] < 4 || self.points[won] < self.points[lost] + 2 {
+            // Back to deuce rather than counting up forever
+            if self.points[won] == self.points[lost] && self.points[won] > 3 {
+                self.points = [3; 2];
+            }
+            return PointOutcome::Point;
+        }
+
+        self.points = [0; 2];
+        self.games[won] += 1;
+        if self.games[won] >= GAMES_TO_WIN {
+            self.winner = Some(side);
+            return PointOutcome::Match;
+        }
+        if alternate_serve {
+            self.server = self.server.opponent();
+        }
+        PointOutcome::Game
+    }
+
+    /// The score of the current game as an umpire would call it, server first, like `30-15`,
+    /// `Deuce` or `Advantage Player 2`, naming ends with `name`
+    pub fn call(&self, name: impl Fn(Side) -> String) -> String {
+        let server = self.points[self.server.index()];
+        let receiver = self.points[self.server.opponent().index()];
+        if server >= 3 && receiver >= 3 {
+            return match server.cmp(&receiver) {
+                std::cmp::Ordering::Equal => "Deuce".to_string(),
+                std::cmp::Ordering::Greater => format!("Advantage {}", name(self.server)),
+                std::cmp::Ordering::Less => {
+                    format!("Advantage {}", name(self.server.opponent()))
+                }
+            };
+        }
+        format!("{}-{}", point_name(server), point_name(receiver))
+    }
+}
+
+/// What a number of points in a game is called
+fn point_name(points: u8) -> &'static str {
+    match points {
+        0 => "Love",
+        1 => "15",
+        2 => "30",
+        _ => "40",
+    }
+}
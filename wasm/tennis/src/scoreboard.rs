@@ -0,0 +1,119 @@
+//! The scoreboard: games on each end and the umpire's call on the shared scorecard HUD, plus
+//! the match result sent back to the page
+
+use bevy::prelude::*;
+use serde::Serialize;
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    FeedbackSender,
+};
+
+use crate::{
+    court::Side,
+    lineup::Lineup,
+    phase::TennisPhase,
+    score::{TennisScore, GAMES_TO_WIN},
+};
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct TennisSnapshot<'a> {
+    /// Points, games and who is serving
+    score: &'a TennisScore,
+    /// Where the match is at
+    phase: TennisPhase,
+}
+
+/// Plugin that shows the score on the scorecard HUD and reports the result
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(TennisPhase::MatchOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(TennisPhase::MatchOver), hide_final_card);
+    }
+}
+
+/// Fills in the scorecard HUD with games on each end, the call and who is serving
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, TennisScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    let rows = [Side::Near, Side::Far]
+        .into_iter()
+        .map(|side| format!("{}: {} games", lineup.name(side), score.games(side)))
+        .collect();
+    let call = score.call(|side| lineup.name(side));
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Tennis, first to {GAMES_TO_WIN} games"),
+        rows,
+        footer: format!("{call}\n{} to serve", lineup.name(score.server())),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Shows the games each end won once the match is over
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    score: Res<'_, TennisScore>,
+    lineup: Res<'_, Lineup>,
+) {
+    let mut lines = vec!["Final Score".to_string()];
+    if let Some(winner) = score.winner() {
+        lines.push(format!("{} wins!", lineup.name(winner)));
+    }
+    for side in [Side::Near, Side::Far] {
+        lines.push(format!(
+            "{}: {} games",
+            lineup.name(side),
+            score.games(side)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final score when a new match starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends the games each player won back to the page once the match is over, so it can submit
+/// them to the server
+fn submit_result(
+    score: Res<'_, TennisScore>,
+    lineup: Res<'_, Lineup>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let scores: Vec<u32> = [Side::Near, Side::Far]
+        .into_iter()
+        .filter(|side| lineup.player(*side).is_some())
+        .map(|side| u32::from(score.games(side)))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    score: Res<'_, TennisScore>,
+    phase: Res<'_, State<TennisPhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&TennisSnapshot {
+        score: &score,
+        phase: *phase.get(),
+    });
+}
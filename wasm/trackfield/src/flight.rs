@@ -0,0 +1,64 @@
+//! Flight shared by every event: javelins, shots and jumpers all fly as projectiles under gravity
+//! until they come down
+
+use bevy::prelude::*;
+
+/// Gravity pulling everything in flight down, in meters per second squared
+const GRAVITY: f32 = 9.81;
+
+/// Something flying through the air
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Flight {
+    /// How fast it's moving, in meters per second
+    pub velocity: Vec3,
+    /// Height it comes down at
+    pub floor: f32,
+}
+
+/// Turns something in flight to point along its path, the way a javelin flies
+#[derive(Component, Debug)]
+pub struct PointsAlongFlight;
+
+/// Something in flight came down
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Touchdown {
+    /// What came down
+    pub entity: Entity,
+    /// Where it came down
+    pub at: Vec3,
+}
+
+/// Plugin that flies everything in the air and reports where it comes down
+pub struct FlightPlugin;
+
+impl Plugin for FlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Touchdown>().add_systems(Update, fly);
+    }
+}
+
+/// Moves everything in flight along its path, bringing it to rest once it comes down
+fn fly(
+    mut commands: Commands<'_, '_>,
+    mut touchdowns: EventWriter<'_, Touchdown>,
+    mut flying: Query<'_, '_, (Entity, &mut Transform, &mut Flight, Has<PointsAlongFlight>)>,
+    time: Res<'_, Time>,
+) {
+    let secs = time.delta_secs();
+    for (entity, mut transform, mut flight, points_along) in &mut flying {
+        flight.velocity.y -= GRAVITY * secs;
+        transform.translation += flight.velocity * secs;
+        if points_along && flight.velocity.length_squared() > f32::EPSILON {
+            transform.rotation = Quat::from_rotation_arc(Vec3::NEG_Z, flight.velocity.normalize());
+        }
+
+        if transform.translation.y <= flight.floor && flight.velocity.y < 0.0 {
+            transform.translation.y = flight.floor;
+            commands.entity(entity).remove::<Flight>();
+            touchdowns.send(Touchdown {
+                entity,
+                at: transform.translation,
+            });
+        }
+    }
+}
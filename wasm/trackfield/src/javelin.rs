@@ -0,0 +1,144 @@
+//! The javelin: sprint down the runway by shaking the controller, tip it up to set the release
+//! angle and let go before the foul line. The faster the run-up, the harder the throw
+
+use bevy::prelude::*;
+use spjorts_core::{scorecard::Banner, spectator::is_playing, turns::TurnManager};
+
+use crate::{
+    clear_attempt,
+    flight::{Flight, PointsAlongFlight, Touchdown},
+    leaderboard::{start_new_game, NewGame},
+    measure_in,
+    phase::{JavelinPhase, Meet},
+    runup::{run, RunUp, Runner},
+    spawn_athlete,
+    stadium::{Followed, Kit, ATHLETE_HEIGHT},
+    Attempt, Measurement, Release,
+};
+
+/// How far back from the foul line the run-up starts
+const START: f32 = 25.0;
+/// How fast a javelin leaves the hand from a standing throw, in meters per second
+const STANDING_SPEED: f32 = 12.0;
+/// Release speed each meter per second of run-up adds, in meters per second
+const SPEED_PER_RUN: f32 = 2.0;
+/// Flattest a javelin can be thrown, in radians
+const MIN_ANGLE: f32 = 0.1;
+/// Steepest a javelin can be thrown, in radians
+const MAX_ANGLE: f32 = 1.2;
+/// Height the javelin leaves the hand at
+const RELEASE_HEIGHT: f32 = 1.9;
+/// How far past the foul line the athlete can run without throwing before it's a foul
+const OVERRUN: f32 = 1.0;
+
+/// The javelin in flight or stuck in the field
+#[derive(Component, Debug)]
+pub struct Javelin;
+
+/// Plugin that runs javelin attempts
+pub struct JavelinPlugin;
+
+impl Plugin for JavelinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(JavelinPhase::RunUp), ready_throw)
+            .add_systems(
+                Update,
+                (
+                    ready_throw
+                        .run_if(on_event::<NewGame>)
+                        .run_if(in_state(Meet::Javelin))
+                        .after(start_new_game),
+                    (run, throw).chain().run_if(in_state(JavelinPhase::RunUp)),
+                    stick.run_if(in_state(JavelinPhase::Flight)),
+                )
+                    .run_if(is_playing),
+            );
+        measure_in(app, JavelinPhase::Measured);
+    }
+}
+
+/// Clears away the last throw and stands the athlete up at the top of the runway
+fn ready_throw(
+    mut commands: Commands<'_, '_>,
+    mut run_up: ResMut<'_, RunUp>,
+    mut banner: ResMut<'_, Banner>,
+    attempt: Query<'_, '_, Entity, With<Attempt>>,
+    kit: Res<'_, Kit>,
+    turns: Res<'_, TurnManager>,
+) {
+    clear_attempt(&mut commands, &attempt);
+    run_up.clear();
+    let athlete = spawn_athlete(&mut commands, &kit, &turns, Meet::Javelin, Vec3::Z * START);
+    commands.entity(athlete).insert(Runner {
+        height: ATHLETE_HEIGHT / 2.0,
+    });
+    banner.show("Shake to sprint, tip up to aim and press A to throw");
+}
+
+/// Throws the javelin down the field as fast as the athlete was running and at the angle the
+/// controller is tipped up to. Letting go past the foul line, or running past it without letting
+/// go, is a foul
+fn throw(
+    mut commands: Commands<'_, '_>,
+    mut releases: EventReader<'_, '_, Release>,
+    mut measurement: ResMut<'_, Measurement>,
+    mut next_phase: ResMut<'_, NextState<JavelinPhase>>,
+    athletes: Query<'_, '_, (Entity, &Transform), With<Runner>>,
+    run_up: Res<'_, RunUp>,
+    kit: Res<'_, Kit>,
+) {
+    let Ok((athlete, at)) = athletes.get_single() else {
+        return;
+    };
+    let release = releases.read().last().copied();
+    let fouled = match release {
+        Some(_) => at.translation.z < 0.0,
+        None => at.translation.z < -OVERRUN,
+    };
+    if fouled {
+        commands.entity(athlete).remove::<Runner>();
+        measurement.measure(None);
+        next_phase.set(JavelinPhase::Measured);
+        return;
+    }
+    let Some(release) = release else {
+        return;
+    };
+
+    let angle = release.pitch.clamp(MIN_ANGLE, MAX_ANGLE);
+    let speed = STANDING_SPEED + run_up.speed * SPEED_PER_RUN;
+    commands.entity(athlete).remove::<(Runner, Followed)>();
+    commands.spawn((
+        Mesh3d(kit.javelin.clone()),
+        MeshMaterial3d(kit.metal.clone()),
+        Transform::from_translation(at.translation.with_y(RELEASE_HEIGHT) + Vec3::X * 0.3),
+        Flight {
+            velocity: Vec3::new(0.0, angle.sin(), -angle.cos()) * speed,
+            floor: 0.0,
+        },
+        PointsAlongFlight,
+        Followed,
+        Javelin,
+        Attempt,
+        StateScoped(Meet::Javelin),
+        Name::new("Javelin"),
+    ));
+    next_phase.set(JavelinPhase::Flight);
+}
+
+/// Sticks the javelin where its tip comes down and measures the throw from the foul line
+fn stick(
+    mut touchdowns: EventReader<'_, '_, Touchdown>,
+    mut measurement: ResMut<'_, Measurement>,
+    mut next_phase: ResMut<'_, NextState<JavelinPhase>>,
+    javelins: Query<'_, '_, (), With<Javelin>>,
+) {
+    let Some(touchdown) = touchdowns
+        .read()
+        .find(|touchdown| javelins.contains(touchdown.entity))
+    else {
+        return;
+    };
+    measurement.measure(Some(-touchdown.at.z));
+    next_phase.set(JavelinPhase::Measured);
+}
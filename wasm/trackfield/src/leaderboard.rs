@@ -0,0 +1,392 @@
+//! The leaderboard: each athlete's marks in every event, scored into points on the decathlon
+//! tables so throws and jumps can be totalled, and the best marks of the whole session
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::phase::{JavelinPhase, Meet};
+
+/// Attempts each athlete gets in every event
+pub const ATTEMPTS: usize = 2;
+
+/// An attempt was measured
+#[derive(Event, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Marked {
+    /// How far it went in meters, or `None` if it was a foul
+    pub mark: Option<f32>,
+}
+
+/// Asks for the meet to be started over from the first event
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// Points a mark in meters scores in an event, on the decathlon scoring tables
+pub fn points(event: Meet, mark: f32) -> u32 {
+    let (a, b, c, measure) = match event {
+        Meet::Javelin => (10.14, 7.0, 1.08, mark),
+        Meet::ShotPut => (51.39, 1.5, 1.05, mark),
+        Meet::LongJump => (0.14354, 220.0, 1.4, mark * 100.0),
+        Meet::Results => return 0,
+    };
+    if measure <= b {
+        return 0;
+    }
+    (a * (measure - b).powf(c)) as u32
+}
+
+/// A mark to show players
+pub fn format_mark(mark: Option<f32>) -> String {
+    mark.map_or("foul".to_string(), |mark| format!("{mark:.2} m"))
+}
+
+/// Every athlete's marks in every event
+#[derive(Resource, Serialize, Debug, Clone, PartialEq)]
+pub struct Scoreboard {
+    /// Each athlete's marks in each event in order, with `None` for fouls
+    marks: Vec<[Vec<Option<f32>>; 3]>,
+    /// The last attempt measured and the event it was in, if one has been
+    last: Option<(Meet, Option<f32>)>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a meet for a number of athletes with nothing measured
+    pub fn new(players: usize) -> Self {
+        Self {
+            marks: vec![Default::default(); players.max(1)],
+            last: None,
+        }
+    }
+
+    /// How many athletes are in the meet
+    pub fn players(&self) -> usize {
+        self.marks.len()
+    }
+
+    /// An athlete's marks in an event
+    fn marks(&self, player: usize, event: Meet) -> &[Option<f32>] {
+        event
+            .index()
+            .and_then(|index| Some(&self.marks.get(player)?[index][..]))
+            .unwrap_or_default()
+    }
+
+    /// Attempts an athlete has made in an event
+    pub fn attempts(&self, player: usize, event: Meet) -> usize {
+        self.marks(player, event).len()
+    }
+
+    /// An athlete's best mark in an event, if they've made one that wasn't a foul
+    pub fn best(&self, player: usize, event: Meet) -> Option<f32> {
+        self.marks(player, event)
+            .iter()
+            .flatten()
+            .copied()
+            .max_by(f32::total_cmp)
+    }
+
+    /// Points an athlete has scored across every event, from their best mark in each
+    pub fn points(&self, player: usize) -> u32 {
+        Meet::EVENTS
+            .iter()
+            .filter_map(|event| Some(points(*event, self.best(player, *event)?)))
+            .sum()
+    }
+
+    /// Adds an attempt's mark to an athlete's marks in an event
+    pub fn record(&mut self, player: usize, event: Meet, mark: Option<f32>) {
+        let (Some(index), Some(marks)) = (event.index(), self.marks.get_mut(player)) else {
+            return;
+        };
+        marks[index].push(mark);
+        self.last = Some((event, mark));
+    }
+
+    /// Athletes with the most points, more than one if they're tied
+    pub fn leaders(&self) -> Vec<usize> {
+        let best = (0..self.players())
+            .map(|player| self.points(player))
+            .max()
+            .unwrap_or_default();
+        (0..self.players())
+            .filter(|player| self.points(*player) == best)
+            .collect()
+    }
+}
+
+/// The best marks and points total of the whole session, kept across every meet since the game
+/// was opened
+#[derive(Resource, Serialize, Debug, Default, Clone, PartialEq)]
+pub struct SessionBests {
+    /// Best mark in each event in order, with the athlete who made it
+    marks: [Option<(f32, usize)>; 3],
+    /// Most points scored in a meet, with the athlete who scored them
+    points: Option<(u32, usize)>,
+}
+
+impl SessionBests {
+    /// Keeps a mark if it's the best yet in its event, returning whether it beat an earlier best
+    fn offer_mark(&mut self, player: usize, event: Meet, mark: f32) -> bool {
+        let Some(best) = event.index().map(|index| &mut self.marks[index]) else {
+            return false;
+        };
+        match best {
+            Some((best, _)) if *best >= mark => false,
+            _ => best.replace((mark, player)).is_some(),
+        }
+    }
+
+    /// Keeps a meet's points total if it's the most yet
+    fn offer_points(&mut self, player: usize, points: u32) {
+        if self.points.is_none_or(|(best, _)| points > best) {
+            self.points = Some((points, player));
+        }
+    }
+
+    /// Lines listing every session best, for the final card
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec!["Session Bests".to_string()];
+        for (event, best) in Meet::EVENTS.iter().zip(self.marks) {
+            if let Some((mark, player)) = best {
+                lines.push(format!(
+                    "{}: {} (Player {})",
+                    event.name(),
+                    format_mark(Some(mark)),
+                    player + 1
+                ));
+            }
+        }
+        if let Some((points, player)) = self.points {
+            lines.push(format!("Points: {points} (Player {})", player + 1));
+        }
+        lines
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct TrackFieldSnapshot<'a> {
+    /// Athlete up
+    player: usize,
+    /// Every athlete's marks
+    scoreboard: &'a Scoreboard,
+    /// The session's best marks
+    session: &'a SessionBests,
+    /// Which event is on
+    meet: Meet,
+}
+
+/// Plugin that measures attempts, moves the meet from event to event and shows the marks and
+/// points on the scorecard HUD
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<SessionBests>()
+            .add_event::<Marked>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    record_mark,
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(
+                OnEnter(Meet::Results),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(Meet::Results), hide_final_card);
+    }
+}
+
+/// Starts a fresh meet whenever the number of athletes changes
+fn fit_game(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Adds a measured attempt to the marks of the athlete who made it, handing over to the next
+/// athlete and moving on to the next event once everyone has had their attempts
+pub fn record_mark(
+    mut marked: EventReader<'_, '_, Marked>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut session: ResMut<'_, SessionBests>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut banner: ResMut<'_, Banner>,
+    meet: Res<'_, State<Meet>>,
+    mut next_meet: ResMut<'_, NextState<Meet>>,
+) {
+    let Some(Marked { mark }) = marked.read().last().copied() else {
+        return;
+    };
+
+    let event = *meet.get();
+    scoreboard.record(turns.current(), event, mark);
+    if let Some(mark) = mark {
+        if session.offer_mark(turns.current(), event, mark) {
+            banner.show(format!("Session best! {}", format_mark(Some(mark))));
+        }
+    }
+
+    turns.advance();
+    if turns.round() < ATTEMPTS {
+        return;
+    }
+    turns.restart();
+    let next = event.next();
+    if next == Meet::Results {
+        for player in 0..scoreboard.players() {
+            session.offer_points(player, scoreboard.points(player));
+        }
+    } else {
+        banner.show(format!("Next up: {}", next.name()));
+    }
+    next_meet.set(next);
+}
+
+/// Starts the meet over from the first athlete's first javelin throw
+pub fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut next_meet: ResMut<'_, NextState<Meet>>,
+    next_javelin: Option<ResMut<'_, NextState<JavelinPhase>>>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    next_meet.set(Meet::Javelin);
+    // Staying in the javelin doesn't start it over, so the throw in progress has to be
+    if let Some(mut next_javelin) = next_javelin {
+        next_javelin.set(JavelinPhase::RunUp);
+    }
+}
+
+/// Fills in the scorecard HUD with every athlete's best mark in the event on and their points,
+/// and whose attempt it is
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    turns: Res<'_, TurnManager>,
+    meet: Res<'_, State<Meet>>,
+) {
+    let event = *meet.get();
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            let best = scoreboard
+                .best(player, event)
+                .map_or(String::new(), |best| format!("{best:.2} m, "));
+            format!(
+                "Player {}: {best}{} pts",
+                player + 1,
+                scoreboard.points(player)
+            )
+        })
+        .collect();
+    let last = scoreboard.last.map_or(String::new(), |(event, mark)| {
+        format!(
+            "\nLast {}: {}",
+            event.name().to_lowercase(),
+            format_mark(mark)
+        )
+    });
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Track & Field: {}", event.name()),
+        rows,
+        footer: format!(
+            "Player {} on attempt {} of {ATTEMPTS}{last}",
+            turns.current() + 1,
+            (scoreboard.attempts(turns.current(), event) + 1).min(ATTEMPTS)
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Totals every athlete's points once the meet is over, calling the winner, with the session's
+/// best marks underneath
+fn show_final_card(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    session: Res<'_, SessionBests>,
+) {
+    let mut lines = vec!["Final Standings".to_string()];
+    match scoreboard.leaders()[..] {
+        [winner] if scoreboard.players() > 1 => {
+            lines.push(format!("Player {} wins!", winner + 1));
+        }
+        [_, _, ..] => lines.push("It's a tie!".to_string()),
+        _ => {}
+    }
+    for player in 0..scoreboard.players() {
+        let marks = Meet::EVENTS
+            .iter()
+            .map(|event| format_mark(scoreboard.best(player, *event)))
+            .collect::<Vec<_>>()
+            .join(" / ");
+        lines.push(format!(
+            "Player {}: {} pts ({marks})",
+            player + 1,
+            scoreboard.points(player)
+        ));
+    }
+    lines.push(String::new());
+    lines.extend(session.lines());
+    lines.push("Press A to compete again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final standings when a new meet starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every athlete's points once the meet is over, so the page can submit them to the server
+fn submit_result(scoreboard: Res<'_, Scoreboard>, feedback: Res<'_, FeedbackSender>) {
+    feedback.send(GameEvent::GameResult {
+        players: scoreboard.players(),
+        scores: (0..scoreboard.players())
+            .map(|player| scoreboard.points(player))
+            .collect(),
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    session: Res<'_, SessionBests>,
+    meet: Res<'_, State<Meet>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&TrackFieldSnapshot {
+        player: turns.current(),
+        scoreboard: &scoreboard,
+        session: &session,
+        meet: *meet.get(),
+    });
+}
@@ -0,0 +1,240 @@
+//! Bevy track and field game
+
+use std::time::Duration;
+
+use bevy::{
+    asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*, state::state::FreelyMutableState,
+};
+use flight::FlightPlugin;
+use javelin::JavelinPlugin;
+use leaderboard::{format_mark, LeaderboardPlugin, Marked, NewGame};
+use longjump::LongJumpPlugin;
+use phase::{Meet, MeetPhasePlugin};
+use runup::RunUp;
+use shotput::ShotPutPlugin;
+use spjorts_core::{
+    communication::JsMessage,
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    swing::SwingSampler,
+    turns::{TurnManager, TurnPlugin},
+    ActionReader,
+};
+use stadium::{Followed, Kit, StadiumPlugin, ATHLETE_HEIGHT};
+
+pub mod flight;
+pub mod javelin;
+pub mod leaderboard;
+pub mod longjump;
+pub mod phase;
+pub mod runup;
+pub mod shotput;
+pub mod stadium;
+
+/// How long a measured attempt stays up before the next athlete steps in, in seconds
+const MEASURED_SECS: f32 = 2.5;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(MeetPhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(StadiumPlugin)
+    .add_plugins(FlightPlugin)
+    .add_plugins(LeaderboardPlugin)
+    .add_plugins(JavelinPlugin)
+    .add_plugins(ShotPutPlugin)
+    .add_plugins(LongJumpPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.55, 0.75, 0.95)))
+    .init_resource::<Athlete>()
+    .init_resource::<RunUp>()
+    .init_resource::<Measurement>()
+    .add_event::<Release>()
+    .add_systems(Update, handle_input.run_if(is_playing));
+});
+
+/// How the athlete up is holding the controller
+#[derive(Resource, Debug, Default)]
+pub struct Athlete {
+    /// How far the controller is tipped up from level, in radians
+    pub pitch: f32,
+    /// Recent controller rotations, measured for how hard a shot is driven out
+    swing: SwingSampler,
+}
+
+/// The athlete up let go of the javelin or shot, or took off from the board
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Release {
+    /// How far the controller was tipped up from level, in radians, which sets the angle the
+    /// throw or jump leaves at
+    pub pitch: f32,
+    /// How fast the controller was swinging, in radians per second
+    pub swing: f32,
+}
+
+/// Belongs to the attempt in progress, and is cleared away before the next
+#[derive(Component, Debug)]
+pub struct Attempt;
+
+/// The mark the last attempt measured, held up for everyone to see before it's recorded
+#[derive(Resource, Debug)]
+pub struct Measurement {
+    /// How far it went in meters, or `None` if it was a foul
+    pub mark: Option<f32>,
+    /// Counts down how long the mark stays up
+    timer: Timer,
+}
+
+impl Default for Measurement {
+    fn default() -> Self {
+        Self {
+            mark: None,
+            timer: Timer::from_seconds(MEASURED_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Measurement {
+    /// Measures an attempt, putting its mark up
+    pub fn measure(&mut self, mark: Option<f32>) {
+        self.mark = mark.filter(|mark| *mark > 0.0);
+        self.timer = Timer::from_seconds(MEASURED_SECS, TimerMode::Once);
+    }
+
+    /// Counts down how long the mark has been up, returning it once it's been up long enough
+    fn wait(&mut self, delta: Duration) -> Option<Option<f32>> {
+        self.timer.tick(delta).just_finished().then_some(self.mark)
+    }
+}
+
+/// Spawns the athlete up in their vest at `at`, cleared away with the event and followed by the
+/// camera
+pub fn spawn_athlete(
+    commands: &mut Commands<'_, '_>,
+    kit: &Kit,
+    turns: &TurnManager,
+    event: Meet,
+    at: Vec3,
+) -> Entity {
+    commands
+        .spawn((
+            Mesh3d(kit.body.clone()),
+            MeshMaterial3d(kit.vest(turns)),
+            Transform::from_translation(at.with_y(ATHLETE_HEIGHT / 2.0)),
+            Attempt,
+            Followed,
+            StateScoped(event),
+            Name::new("Athlete"),
+        ))
+        .id()
+}
+
+/// Clears away everything left over from the last attempt
+pub fn clear_attempt(
+    commands: &mut Commands<'_, '_>,
+    attempt: &Query<'_, '_, Entity, With<Attempt>>,
+) {
+    for entity in attempt {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Puts the measured mark up on the banner
+fn announce_mark(measurement: Res<'_, Measurement>, mut banner: ResMut<'_, Banner>) {
+    match measurement.mark {
+        Some(mark) => banner.show(format_mark(Some(mark))),
+        None => banner.show("Foul!"),
+    }
+}
+
+/// Records the measured mark once it's been up long enough and sends the event back to the start
+/// of an attempt for the next athlete
+pub fn next_attempt<S: FreelyMutableState + Default>(
+    mut measurement: ResMut<'_, Measurement>,
+    mut marked: EventWriter<'_, Marked>,
+    mut next_phase: ResMut<'_, NextState<S>>,
+    time: Res<'_, Time>,
+) {
+    let Some(mark) = measurement.wait(time.delta()) else {
+        return;
+    };
+    marked.send(Marked { mark });
+    next_phase.set(S::default());
+}
+
+/// Adds measuring to an event: announcing the mark when the event reaches `measured` and recording
+/// it once it's been up long enough
+pub fn measure_in<S: FreelyMutableState + Default>(app: &mut App, measured: S) {
+    app.add_systems(OnEnter(measured.clone()), announce_mark)
+        .add_systems(
+            Update,
+            next_attempt::<S>
+                .run_if(in_state(measured))
+                .run_if(is_playing)
+                .before(leaderboard::record_mark),
+        );
+}
+
+/// Everything input handling changes besides the athlete
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// The run-up, pumped by shaking the controller
+    run_up: ResMut<'w, RunUp>,
+    /// Throws and takeoffs
+    releases: EventWriter<'w, Release>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: shaking the controller sprints down the runway, tipping it up sets the
+/// angle of the throw or jump, swinging it drives the shot and A lets go or takes off. A starts a
+/// new meet once the results are in
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut athlete: ResMut<'_, Athlete>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    meet: Res<'_, State<Meet>>,
+    time: Res<'_, Time>,
+) {
+    let competing = *meet.get() != Meet::Results;
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *meet.get() == Meet::Results => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if competing => {
+                effects.releases.send(Release {
+                    pitch: athlete.pitch,
+                    swing: athlete.swing.angular_velocity().unwrap_or_default(),
+                });
+                athlete.swing.clear();
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _)
+                if competing =>
+            {
+                let orientation = settings.apply_rotation(orientation);
+                athlete.pitch = orientation.pitch;
+                athlete
+                    .swing
+                    .record(orientation.to_quat(), time.elapsed_secs());
+                effects.run_up.shake(orientation);
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! The long jump: sprint down the runway by shaking the controller, tip it up to set the takeoff
+//! angle and press A to jump before the board. The faster the run-up, the longer the jump
+
+use bevy::prelude::*;
+use spjorts_core::{scorecard::Banner, spectator::is_playing, turns::TurnManager};
+
+use crate::{
+    clear_attempt,
+    flight::{Flight, Touchdown},
+    measure_in,
+    phase::{LongJumpPhase, Meet},
+    runup::{run, RunUp, Runner},
+    spawn_athlete,
+    stadium::{Kit, ATHLETE_HEIGHT},
+    Attempt, Measurement, Release,
+};
+
+/// How far back from the board the run-up starts
+const START: f32 = 35.0;
+/// How much of the athlete's run-up speed they carry into the air
+const TAKEOFF_KEEP: f32 = 0.9;
+/// Flattest a jump can take off, in radians
+const MIN_ANGLE: f32 = 0.1;
+/// Steepest a jump can take off, in radians
+const MAX_ANGLE: f32 = 0.6;
+/// Height the athlete's middle comes down to in the sand
+const LANDING_HEIGHT: f32 = 0.5;
+/// How far ahead of their middle the athlete's heels reach on landing
+const REACH: f32 = 0.5;
+/// Where the sand pit starts past the board
+const PIT_START: f32 = 1.0;
+/// Length of the sand pit
+const PIT_LENGTH: f32 = 9.0;
+/// Width of the sand pit
+const PIT_WIDTH: f32 = 2.8;
+/// How far past the board the athlete can run without jumping before it's a foul
+const OVERRUN: f32 = 1.0;
+
+/// The athlete in the air
+#[derive(Component, Debug)]
+pub struct Jumper;
+
+/// Plugin that runs long jump attempts
+pub struct LongJumpPlugin;
+
+impl Plugin for LongJumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Meet::LongJump), setup_pit)
+            .add_systems(OnEnter(LongJumpPhase::RunUp), ready_jump)
+            .add_systems(
+                Update,
+                (
+                    (run, take_off)
+                        .chain()
+                        .run_if(in_state(LongJumpPhase::RunUp)),
+                    land.run_if(in_state(LongJumpPhase::Flight)),
+                )
+                    .run_if(is_playing),
+            );
+        measure_in(app, LongJumpPhase::Measured);
+    }
+}
+
+/// Fills the sand pit past the board, cleared away once the event is over
+fn setup_pit(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(PIT_WIDTH, PIT_LENGTH))),
+        MeshMaterial3d(materials.add(Color::srgb(0.86, 0.76, 0.52))),
+        Transform::from_xyz(0.0, 0.01, -PIT_START - PIT_LENGTH / 2.0),
+        StateScoped(Meet::LongJump),
+        Name::new("Sand pit"),
+    ));
+}
+
+/// Clears away the last jump and stands the athlete up at the top of the runway
+fn ready_jump(
+    mut commands: Commands<'_, '_>,
+    mut run_up: ResMut<'_, RunUp>,
+    mut banner: ResMut<'_, Banner>,
+    attempt: Query<'_, '_, Entity, With<Attempt>>,
+    kit: Res<'_, Kit>,
+    turns: Res<'_, TurnManager>,
+) {
+    clear_attempt(&mut commands, &attempt);
+    run_up.clear();
+    let athlete = spawn_athlete(&mut commands, &kit, &turns, Meet::LongJump, Vec3::Z * START);
+    commands.entity(athlete).insert(Runner {
+        height: ATHLETE_HEIGHT / 2.0,
+    });
+    banner.show("Shake to sprint, tip up and press A to jump");
+}
+
+/// Launches the athlete off the runway carrying most of their speed, at the angle the controller
+/// is tipped up to. Taking off past the board, or running past it without jumping, is a foul
+fn take_off(
+    mut commands: Commands<'_, '_>,
+    mut releases: EventReader<'_, '_, Release>,
+    mut measurement: ResMut<'_, Measurement>,
+    mut next_phase: ResMut<'_, NextState<LongJumpPhase>>,
+    athletes: Query<'_, '_, (Entity, &Transform), With<Runner>>,
+    run_up: Res<'_, RunUp>,
+) {
+    let Ok((athlete, at)) = athletes.get_single() else {
+        return;
+    };
+    let release = releases.read().last().copied();
+    let fouled = match release {
+        Some(_) => at.translation.z < 0.0,
+        None => at.translation.z < -OVERRUN,
+    };
+    if fouled {
+        commands.entity(athlete).remove::<Runner>();
+        measurement.measure(None);
+        next_phase.set(LongJumpPhase::Measured);
+        return;
+    }
+    let Some(release) = release else {
+        return;
+    };
+
+    let angle = release.pitch.clamp(MIN_ANGLE, MAX_ANGLE);
+    commands.entity(athlete).remove::<Runner>().insert((
+        Flight {
+            velocity: Vec3::new(0.0, angle.sin(), -angle.cos()) * run_up.speed * TAKEOFF_KEEP,
+            floor: LANDING_HEIGHT,
+        },
+        Jumper,
+    ));
+    next_phase.set(LongJumpPhase::Flight);
+}
+
+/// Measures the jump from the board to where the athlete's heels come down in the sand
+fn land(
+    mut touchdowns: EventReader<'_, '_, Touchdown>,
+    mut measurement: ResMut<'_, Measurement>,
+    mut next_phase: ResMut<'_, NextState<LongJumpPhase>>,
+    jumpers: Query<'_, '_, (), With<Jumper>>,
+) {
+    let Some(touchdown) = touchdowns
+        .read()
+        .find(|touchdown| jumpers.contains(touchdown.entity))
+    else {
+        return;
+    };
+    measurement.measure(Some(REACH - touchdown.at.z));
+    next_phase.set(LongJumpPhase::Measured);
+}
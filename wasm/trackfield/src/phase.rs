@@ -0,0 +1,100 @@
+//! Phases of a track and field meet: which event is on, and within each event, where the
+//! athlete up is in their attempt
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Which event of the meet is on
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Meet {
+    /// Run up and throw the javelin as far down the field as it'll fly
+    #[default]
+    Javelin,
+    /// Put the shot from the circle
+    ShotPut,
+    /// Run up and jump from the board into the sand
+    LongJump,
+    /// Every event is done and the points are totalled
+    Results,
+}
+
+impl Meet {
+    /// Every event, in the order they're contested
+    pub const EVENTS: [Self; 3] = [Self::Javelin, Self::ShotPut, Self::LongJump];
+
+    /// Name of the event to show players
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Javelin => "Javelin",
+            Self::ShotPut => "Shot Put",
+            Self::LongJump => "Long Jump",
+            Self::Results => "Results",
+        }
+    }
+
+    /// Where the event comes in the meet, or `None` for the results
+    pub fn index(&self) -> Option<usize> {
+        Self::EVENTS.iter().position(|event| event == self)
+    }
+
+    /// What follows the event
+    pub fn next(&self) -> Self {
+        self.index()
+            .and_then(|index| Self::EVENTS.get(index + 1))
+            .copied()
+            .unwrap_or(Self::Results)
+    }
+}
+
+/// Where the athlete up is in a javelin attempt
+#[derive(SubStates, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(Meet = Meet::Javelin)]
+pub enum JavelinPhase {
+    /// Shaking the controller to sprint down the runway toward the foul line
+    #[default]
+    RunUp,
+    /// The javelin is flying down the field
+    Flight,
+    /// The javelin has stuck and the throw is measured
+    Measured,
+}
+
+/// Where the athlete up is in a shot put attempt
+#[derive(SubStates, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(Meet = Meet::ShotPut)]
+pub enum ShotPutPhase {
+    /// Winding up in the circle, ready to drive the shot out
+    #[default]
+    WindUp,
+    /// The shot is flying
+    Flight,
+    /// The shot has landed and the put is measured
+    Measured,
+}
+
+/// Where the athlete up is in a long jump attempt
+#[derive(SubStates, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[source(Meet = Meet::LongJump)]
+pub enum LongJumpPhase {
+    /// Shaking the controller to sprint down the runway toward the board
+    #[default]
+    RunUp,
+    /// The athlete is in the air
+    Flight,
+    /// The athlete has landed in the sand and the jump is measured
+    Measured,
+}
+
+/// Plugin that tracks which event is on and each event's phases, despawning an event's props
+/// once it's over
+pub struct MeetPhasePlugin;
+
+impl Plugin for MeetPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<Meet>()
+            .add_sub_state::<JavelinPhase>()
+            .add_sub_state::<ShotPutPhase>()
+            .add_sub_state::<LongJumpPhase>()
+            .enable_state_scoped_entities::<Meet>();
+    }
+}
@@ -0,0 +1,93 @@
+//! The run-up shared by the javelin and long jump: shaking the controller pumps the athlete's
+//! arms, and the harder it's shaken the faster they sprint down the runway
+
+use bevy::prelude::*;
+use spjorts_core::communication::Orientation;
+
+/// Fastest an athlete can sprint, in meters per second
+pub const TOP_SPEED: f32 = 10.0;
+/// Sprinting speed each radian per second of shaking is worth, in meters per second
+const SPEED_PER_SHAKE: f32 = 1.2;
+/// Furthest a single reading can turn and still count as shaking, so a reading that wraps the
+/// yaw around doesn't count as a huge shake, in radians
+const MAX_SHAKE_STEP: f32 = 1.0;
+/// How quickly the athlete's speed follows how hard the controller is shaken, per second
+const ACCELERATION: f32 = 1.2;
+/// How far an athlete's stride bobs them up and down
+const STRIDE_BOB: f32 = 0.06;
+/// Strides an athlete takes for each meter they run
+const STRIDES_PER_METER: f32 = 0.9;
+
+/// How fast the athlete up is sprinting, and how hard they're shaking the controller to do it
+#[derive(Resource, Debug, Default)]
+pub struct RunUp {
+    /// How fast the athlete is sprinting, in meters per second
+    pub speed: f32,
+    /// Meters run so far
+    pub run: f32,
+    /// Last orientation read from the controller, to measure the shaking from
+    last: Option<Orientation>,
+    /// Radians the controller has been shaken through since the athlete last took a stride
+    shaken: f32,
+}
+
+impl RunUp {
+    /// Shakes the controller to a new orientation, pumping the athlete's arms by however far it
+    /// turned
+    pub fn shake(&mut self, orientation: Orientation) {
+        if let Some(last) = self.last.replace(orientation) {
+            let turned = Vec3::new(
+                orientation.pitch - last.pitch,
+                orientation.roll - last.roll,
+                orientation.yaw - last.yaw,
+            );
+            self.shaken += turned.length().min(MAX_SHAKE_STEP);
+        }
+    }
+
+    /// Speeds the athlete up or lets them slow down over `secs` by how hard the controller was
+    /// shaken, returning how far they ran
+    fn stride(&mut self, secs: f32) -> f32 {
+        if secs <= f32::EPSILON {
+            return 0.0;
+        }
+        let effort = std::mem::take(&mut self.shaken) / secs;
+        let target = (effort * SPEED_PER_SHAKE).min(TOP_SPEED);
+        self.speed += (target - self.speed) * (1.0 - (-secs * ACCELERATION).exp());
+        let ran = self.speed * secs;
+        self.run += ran;
+        ran
+    }
+
+    /// Brings the athlete to a standstill at the top of the runway, ready for their next
+    /// attempt
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// An athlete sprinting down the runway
+#[derive(Component, Debug)]
+pub struct Runner {
+    /// Height the athlete's middle is at when standing
+    pub height: f32,
+}
+
+/// Sprints the athlete down the runway toward the foul line as fast as they're shaking the
+/// controller, bobbing with every stride
+pub fn run(
+    mut run_up: ResMut<'_, RunUp>,
+    mut runners: Query<'_, '_, (&mut Transform, &Runner)>,
+    time: Res<'_, Time>,
+) {
+    let ran = run_up.stride(time.delta_secs());
+    let bob = (run_up.run * STRIDES_PER_METER * std::f32::consts::TAU)
+        .sin()
+        .abs()
+        * STRIDE_BOB
+        * (run_up.speed / TOP_SPEED);
+    for (mut transform, runner) in &mut runners {
+        transform.translation.z -= ran;
+        transform.translation.y = runner.height + bob;
+    }
+}
@@ -0,0 +1,143 @@
+//! The shot put: wind up in the circle, then swing the controller and press A to drive the shot
+//! out, tipped up to set the angle it leaves at. The harder the swing, the further it flies
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use spjorts_core::{scorecard::Banner, spectator::is_playing, turns::TurnManager};
+
+use crate::{
+    clear_attempt,
+    flight::{Flight, Touchdown},
+    measure_in,
+    phase::{Meet, ShotPutPhase},
+    spawn_athlete,
+    stadium::{Followed, Kit, SHOT_RADIUS},
+    Attempt, Measurement, Release,
+};
+
+/// Radius of the throwing circle, its front edge on the foul line
+const CIRCLE_RADIUS: f32 = 1.07;
+/// How fast a shot leaves the hand without any swing behind it, in meters per second
+const STANDING_SPEED: f32 = 6.0;
+/// Release speed each radian per second of swing adds, in meters per second
+const SPEED_PER_SWING: f32 = 0.6;
+/// Fastest a shot can be put, in meters per second
+const MAX_SPEED: f32 = 14.5;
+/// Flattest a shot can be put, in radians
+const MIN_ANGLE: f32 = 0.1;
+/// Steepest a shot can be put, in radians
+const MAX_ANGLE: f32 = 1.2;
+/// Height the shot leaves the hand at
+const RELEASE_HEIGHT: f32 = 2.0;
+
+/// The shot in flight or resting in the field
+#[derive(Component, Debug)]
+pub struct Shot;
+
+/// Plugin that runs shot put attempts
+pub struct ShotPutPlugin;
+
+impl Plugin for ShotPutPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(Meet::ShotPut), setup_circle)
+            .add_systems(OnEnter(ShotPutPhase::WindUp), ready_put)
+            .add_systems(
+                Update,
+                (
+                    put.run_if(in_state(ShotPutPhase::WindUp)),
+                    land.run_if(in_state(ShotPutPhase::Flight)),
+                )
+                    .run_if(is_playing),
+            );
+        measure_in(app, ShotPutPhase::Measured);
+    }
+}
+
+/// Paints the throwing circle just behind the foul line, cleared away once the event is over
+fn setup_circle(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Circle::new(CIRCLE_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(0.55, 0.55, 0.55))),
+        Transform::from_xyz(0.0, 0.01, CIRCLE_RADIUS)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+        StateScoped(Meet::ShotPut),
+        Name::new("Throwing circle"),
+    ));
+}
+
+/// Clears away the last put and stands the athlete up in the circle
+fn ready_put(
+    mut commands: Commands<'_, '_>,
+    mut banner: ResMut<'_, Banner>,
+    attempt: Query<'_, '_, Entity, With<Attempt>>,
+    kit: Res<'_, Kit>,
+    turns: Res<'_, TurnManager>,
+) {
+    clear_attempt(&mut commands, &attempt);
+    spawn_athlete(
+        &mut commands,
+        &kit,
+        &turns,
+        Meet::ShotPut,
+        Vec3::Z * CIRCLE_RADIUS,
+    );
+    banner.show("Tip up to aim, then swing and press A to put");
+}
+
+/// Drives the shot out of the circle as fast as the controller was swinging and at the angle
+/// it's tipped up to
+fn put(
+    mut commands: Commands<'_, '_>,
+    mut releases: EventReader<'_, '_, Release>,
+    mut next_phase: ResMut<'_, NextState<ShotPutPhase>>,
+    athletes: Query<'_, '_, (Entity, &Transform), With<Followed>>,
+    kit: Res<'_, Kit>,
+) {
+    let Some(release) = releases.read().last().copied() else {
+        return;
+    };
+    let Ok((athlete, at)) = athletes.get_single() else {
+        return;
+    };
+
+    let angle = release.pitch.clamp(MIN_ANGLE, MAX_ANGLE);
+    let speed = (STANDING_SPEED + release.swing * SPEED_PER_SWING).min(MAX_SPEED);
+    commands.entity(athlete).remove::<Followed>();
+    commands.spawn((
+        Mesh3d(kit.shot.clone()),
+        MeshMaterial3d(kit.metal.clone()),
+        Transform::from_translation(at.translation.with_y(RELEASE_HEIGHT) + Vec3::X * 0.3),
+        Flight {
+            velocity: Vec3::new(0.0, angle.sin(), -angle.cos()) * speed,
+            floor: SHOT_RADIUS,
+        },
+        Followed,
+        Shot,
+        Attempt,
+        StateScoped(Meet::ShotPut),
+        Name::new("Shot"),
+    ));
+    next_phase.set(ShotPutPhase::Flight);
+}
+
+/// Measures the put from the foul line to where the shot comes down
+fn land(
+    mut touchdowns: EventReader<'_, '_, Touchdown>,
+    mut measurement: ResMut<'_, Measurement>,
+    mut next_phase: ResMut<'_, NextState<ShotPutPhase>>,
+    shots: Query<'_, '_, (), With<Shot>>,
+) {
+    let Some(touchdown) = touchdowns
+        .read()
+        .find(|touchdown| shots.contains(touchdown.entity))
+    else {
+        return;
+    };
+    measurement.measure(Some(-touchdown.at.z));
+    next_phase.set(ShotPutPhase::Measured);
+}
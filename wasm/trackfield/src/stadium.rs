@@ -0,0 +1,176 @@
+//! The stadium: a runway ending at the foul line with the field stretching out past it, marked
+//! every ten meters, the athletes' kit and a camera that follows whatever's moving
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use spjorts_core::turns::TurnManager;
+
+/// Length of the runway leading up to the foul line
+pub const RUNWAY_LENGTH: f32 = 40.0;
+/// Half the width of the runway
+const RUNWAY_HALF_WIDTH: f32 = 0.6;
+/// How far the field stretches out past the foul line
+const FIELD_LENGTH: f32 = 100.0;
+/// Distance between the lines marked across the field, in meters
+const MARKER_SPACING: f32 = 10.0;
+/// Where the camera sits relative to whatever it follows
+const CAMERA_OFFSET: Vec3 = Vec3::new(7.0, 3.0, 5.0);
+/// How quickly the camera catches up with whatever it follows, per second
+const CAMERA_FOLLOW: f32 = 4.0;
+/// Vest colors of each athlete in turn
+const VEST_COLORS: [Color; 4] = [
+    Color::srgb(0.85, 0.15, 0.15),
+    Color::srgb(0.15, 0.35, 0.85),
+    Color::srgb(0.95, 0.75, 0.1),
+    Color::srgb(0.15, 0.7, 0.3),
+];
+/// Height of an athlete
+pub const ATHLETE_HEIGHT: f32 = 1.8;
+/// Length of a javelin
+const JAVELIN_LENGTH: f32 = 2.6;
+/// Radius of a shot
+pub const SHOT_RADIUS: f32 = 0.06;
+
+/// Marks whatever the camera is following
+#[derive(Component, Debug)]
+pub struct Followed;
+
+/// Meshes and materials every event dresses its athletes and props in
+#[derive(Resource, Debug)]
+pub struct Kit {
+    /// An athlete's body
+    pub body: Handle<Mesh>,
+    /// An athlete's vest, one for each color in turn
+    pub vests: Vec<Handle<StandardMaterial>>,
+    /// A javelin
+    pub javelin: Handle<Mesh>,
+    /// A shot
+    pub shot: Handle<Mesh>,
+    /// Bare metal, for javelins and shots
+    pub metal: Handle<StandardMaterial>,
+}
+
+impl Kit {
+    /// The vest of the athlete up
+    pub fn vest(&self, turns: &TurnManager) -> Handle<StandardMaterial> {
+        self.vests[turns.current() % self.vests.len()].clone()
+    }
+}
+
+/// Plugin that builds the stadium and follows the action with the camera
+pub struct StadiumPlugin;
+
+impl Plugin for StadiumPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_stadium)
+            .add_systems(Update, follow_camera);
+    }
+}
+
+/// Spawns the field, the runway, the foul line and the distance markers, the camera and the sun,
+/// and makes the athletes' kit
+fn setup_stadium(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(80.0, FIELD_LENGTH + RUNWAY_LENGTH + 20.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.25, 0.55, 0.22))),
+        Transform::from_xyz(0.0, 0.0, (RUNWAY_LENGTH - FIELD_LENGTH) / 2.0),
+        Name::new("Field"),
+    ));
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(RUNWAY_HALF_WIDTH * 2.0, RUNWAY_LENGTH),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.25, 0.18))),
+        Transform::from_xyz(0.0, 0.005, RUNWAY_LENGTH / 2.0),
+        Name::new("Runway"),
+    ));
+
+    let chalk = materials.add(Color::WHITE);
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(RUNWAY_HALF_WIDTH * 2.0, 0.07),
+            ),
+        ),
+        MeshMaterial3d(chalk.clone()),
+        Transform::from_xyz(0.0, 0.008, 0.0),
+        Name::new("Foul line"),
+    ));
+    let marker = meshes.add(Plane3d::default().mesh().size(30.0, 0.05));
+    let mut distance = MARKER_SPACING;
+    while distance <= FIELD_LENGTH {
+        commands.spawn((
+            Mesh3d(marker.clone()),
+            MeshMaterial3d(chalk.clone()),
+            Transform::from_xyz(0.0, 0.006, -distance),
+        ));
+        distance += MARKER_SPACING;
+    }
+
+    commands.insert_resource(Kit {
+        body: meshes.add(Capsule3d::new(0.2, ATHLETE_HEIGHT - 0.4)),
+        vests: VEST_COLORS
+            .iter()
+            .map(|color| materials.add(*color))
+            .collect(),
+        javelin: meshes.add(Cuboid::new(0.035, 0.035, JAVELIN_LENGTH)),
+        shot: meshes.add(Sphere::new(SHOT_RADIUS).mesh().uv(16, 12)),
+        metal: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.6, 0.6, 0.62),
+            metallic: 0.9,
+            perceptual_roughness: 0.35,
+            ..default()
+        }),
+    });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(Vec3::new(0.0, 0.0, RUNWAY_LENGTH) + CAMERA_OFFSET)
+            .looking_at(Vec3::new(0.0, 1.0, RUNWAY_LENGTH), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::AMBIENT_DAYLIGHT,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(10.0, 20.0, 10.0)
+            .with_rotation(Quat::from_rotation_x(-FRAC_PI_2 * 0.7) * Quat::from_rotation_y(0.4)),
+    ));
+}
+
+/// Eases the camera along beside whatever it's following, looking at it
+fn follow_camera(
+    followed: Query<'_, '_, &Transform, (With<Followed>, Without<Camera3d>)>,
+    mut cameras: Query<'_, '_, &mut Transform, With<Camera3d>>,
+    time: Res<'_, Time>,
+) {
+    let Some(target) = followed.iter().next() else {
+        return;
+    };
+    let follow = 1.0 - (-time.delta_secs() * CAMERA_FOLLOW).exp();
+    for mut camera in &mut cameras {
+        let position = camera
+            .translation
+            .lerp(target.translation.with_y(0.0) + CAMERA_OFFSET, follow);
+        *camera = Transform::from_translation(position)
+            .looking_at(target.translation.with_y(1.0), Vec3::Y);
+    }
+}
@@ -0,0 +1,120 @@
+//! The ball: held in the server's hand, tossed, then struck over the net, flying under gravity
+//! until it drops into the net or lands
+
+use bevy::prelude::*;
+use spjorts_core::spectator::is_playing;
+
+use crate::court::{HALF_WIDTH, NET_HEIGHT, SERVE_SPOT};
+
+/// Radius of the ball
+pub const BALL_RADIUS: f32 = 0.105;
+/// Gravity pulling the ball down, in meters per second squared
+pub const GRAVITY: f32 = 9.81;
+/// Height the ball is held at and tossed from
+pub const TOSS_HEIGHT: f32 = 1.5;
+/// How far the net posts stand outside the sidelines, past which the ball misses the net
+const NET_REACH: f32 = HALF_WIDTH + 1.0;
+/// How much of the ball's speed toward the net is left after it drops into the net
+const NET_REBOUND: f32 = 0.1;
+
+/// Where the server holds the ball, just out in front of them
+pub fn hand() -> Vec3 {
+    SERVE_SPOT + Vec3::new(0.0, TOSS_HEIGHT, -0.3)
+}
+
+/// The volleyball
+#[derive(Component, Debug, Default)]
+pub struct Ball {
+    /// How fast it's moving, in meters per second
+    pub velocity: Vec3,
+    /// Whether it's in the air rather than in the server's hand or on the floor
+    pub airborne: bool,
+    /// Whether it's been struck on this serve
+    pub struck: bool,
+    /// Whether it dropped into the net on this serve
+    pub netted: bool,
+}
+
+impl Ball {
+    /// Puts the ball back in the server's hand, ready for the next serve
+    pub fn catch(&mut self, transform: &mut Transform) {
+        *self = Self::default();
+        transform.translation = hand();
+    }
+}
+
+/// The ball came down on the floor
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct Landed {
+    /// Where it came down
+    pub at: Vec3,
+    /// Whether it had been struck, rather than dropped from the toss
+    pub struck: bool,
+    /// Whether it dropped into the net on the way
+    pub netted: bool,
+}
+
+/// Plugin that spawns the ball and flies it through the air
+pub struct BallPlugin;
+
+impl Plugin for BallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Landed>()
+            .add_systems(Startup, spawn_ball)
+            .add_systems(Update, fly.run_if(is_playing));
+    }
+}
+
+/// Spawns the ball in the server's hand
+fn spawn_ball(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(BALL_RADIUS))),
+        MeshMaterial3d(materials.add(Color::srgb(0.95, 0.9, 0.3))),
+        Transform::from_translation(hand()),
+        Ball::default(),
+    ));
+}
+
+/// Moves the ball along its path, stopping it dead when it crosses the net below the tape and
+/// reporting where it comes down
+fn fly(
+    mut ball: Query<'_, '_, (&mut Transform, &mut Ball)>,
+    mut landings: EventWriter<'_, Landed>,
+    time: Res<'_, Time>,
+) {
+    let secs = time.delta_secs();
+    for (mut transform, mut ball) in &mut ball {
+        if !ball.airborne {
+            continue;
+        }
+        let from = transform.translation;
+        ball.velocity.y -= GRAVITY * secs;
+        transform.translation += ball.velocity * secs;
+
+        let to = transform.translation;
+        let crossed = from.z > 0.0 && to.z <= 0.0;
+        if crossed && !ball.netted && to.x.abs() < NET_REACH && to.y - BALL_RADIUS < NET_HEIGHT {
+            ball.netted = true;
+            ball.velocity = Vec3::new(
+                ball.velocity.x * NET_REBOUND,
+                ball.velocity.y.min(0.0),
+                -ball.velocity.z * NET_REBOUND,
+            );
+            transform.translation.z = BALL_RADIUS;
+        }
+
+        if transform.translation.y <= BALL_RADIUS && ball.velocity.y < 0.0 {
+            transform.translation.y = BALL_RADIUS;
+            ball.airborne = false;
+            landings.send(Landed {
+                at: transform.translation,
+                struck: ball.struck,
+                netted: ball.netted,
+            });
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! The court: the receiving half across the net split into six zones, the net itself, the spot
+//! the server stands on and the target that moves between zones serve to serve
+
+use bevy::prelude::*;
+use spjorts_core::turns::TurnManager;
+
+use crate::{
+    phase::ServePhase,
+    scoring::{Scoreboard, SERVES_PER_PLAYER},
+};
+
+/// Half the width of the court, sideline to sideline
+pub const HALF_WIDTH: f32 = 4.5;
+/// Length of each half of the court, from the net to the end line
+pub const HALF_LENGTH: f32 = 9.0;
+/// Distance from the net to the attack line, splitting each half into front and back rows
+pub const ATTACK_LINE: f32 = 3.0;
+/// Height of the top of the net
+pub const NET_HEIGHT: f32 = 2.43;
+/// Where the server stands, behind their end line
+pub const SERVE_SPOT: Vec3 = Vec3::new(1.5, 0.0, HALF_LENGTH + 1.5);
+/// Radius of the target ring, landing inside scores more than just landing in
+pub const TARGET_RADIUS: f32 = 1.2;
+/// Radius of the bullseye in the middle of the target
+pub const BULLSEYE_RADIUS: f32 = 0.4;
+/// Zone the target sits in for each of a player's serves, by zone number. Every player gets the
+/// same targets in the same order
+pub const ZONE_ORDER: [usize; SERVES_PER_PLAYER] = [1, 5, 6, 2, 4, 1, 3, 5, 6, 1];
+/// Width of the painted lines
+const LINE_WIDTH: f32 = 0.05;
+/// How far the floor runs past the court
+const RUNOFF: f32 = 6.0;
+/// How far outside the sidelines the net posts stand
+const POST_OFFSET: f32 = 1.0;
+
+/// Middle of a zone on the receiving half, numbered the way volleyball numbers them from the
+/// receivers' side: 1 to 6 counter-clockwise from back right
+pub fn zone_centre(zone: usize) -> Vec3 {
+    // Receivers face the server, so their right is toward negative x
+    let (x, deep) = match zone {
+        1 => (-1.0, true),
+        2 => (-1.0, false),
+        3 => (0.0, false),
+        4 => (1.0, false),
+        5 => (1.0, true),
+        _ => (0.0, true),
+    };
+    let z = if deep {
+        -(ATTACK_LINE + HALF_LENGTH) / 2.0
+    } else {
+        -ATTACK_LINE / 2.0
+    };
+    Vec3::new(x * HALF_WIDTH * 2.0 / 3.0, 0.0, z)
+}
+
+/// The zone a player's serve is aimed into, from how many serves they've already taken
+pub fn target_zone(serve: usize) -> usize {
+    ZONE_ORDER[serve % ZONE_ORDER.len()]
+}
+
+/// Whether a spot on the floor is inside the receiving half. Balls touching a line are in
+pub fn in_court(at: Vec3, ball_radius: f32) -> bool {
+    at.x.abs() <= HALF_WIDTH + ball_radius && at.z <= 0.0 && at.z >= -HALF_LENGTH - ball_radius
+}
+
+/// The target ring painted on the receiving half
+#[derive(Component, Debug)]
+pub struct Target;
+
+/// Plugin that lays out the court and keeps the target in the zone being served at
+pub struct CourtPlugin;
+
+impl Plugin for CourtPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_court)
+            .add_systems(OnEnter(ServePhase::Ready), place_target);
+    }
+}
+
+/// Spawns the floor, the court and its lines, the net, the server, the target, the camera and the
+/// light
+fn setup_court(
+    mut commands: Commands<'_, '_>,
+    mut meshes: ResMut<'_, Assets<Mesh>>,
+    mut materials: ResMut<'_, Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size((HALF_WIDTH + RUNOFF) * 2.0, (HALF_LENGTH + RUNOFF) * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.35, 0.55))),
+        Name::new("Floor"),
+    ));
+    commands.spawn((
+        Mesh3d(
+            meshes.add(
+                Plane3d::default()
+                    .mesh()
+                    .size(HALF_WIDTH * 2.0, HALF_LENGTH * 2.0),
+            ),
+        ),
+        MeshMaterial3d(materials.add(Color::srgb(0.85, 0.55, 0.3))),
+        Transform::from_xyz(0.0, 0.002, 0.0),
+        Name::new("Court"),
+    ));
+
+    // End lines, sidelines, the centre line under the net and both attack lines
+    let line = materials.add(Color::WHITE);
+    let mut paint = |size: Vec2, at: Vec2| {
+        commands.spawn((
+            Mesh3d(meshes.add(Plane3d::default().mesh().size(size.x, size.y))),
+            MeshMaterial3d(line.clone()),
+            Transform::from_xyz(at.x, 0.004, at.y),
+        ));
+    };
+    let across = Vec2::new(HALF_WIDTH * 2.0, LINE_WIDTH);
+    for z in [-HALF_LENGTH, -ATTACK_LINE, 0.0, ATTACK_LINE, HALF_LENGTH] {
+        paint(across, Vec2::new(0.0, z));
+    }
+    for x in [-HALF_WIDTH, HALF_WIDTH] {
+        paint(Vec2::new(LINE_WIDTH, HALF_LENGTH * 2.0), Vec2::new(x, 0.0));
+    }
+
+    let post_x = HALF_WIDTH + POST_OFFSET;
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(post_x * 2.0, 1.0, 0.02))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.1, 0.1, 0.1, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, NET_HEIGHT - 0.5, 0.0),
+        Name::new("Net"),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(post_x * 2.0, 0.07, 0.03))),
+        MeshMaterial3d(line.clone()),
+        Transform::from_xyz(0.0, NET_HEIGHT - 0.035, 0.0),
+        Name::new("Net Tape"),
+    ));
+    let post = meshes.add(Cylinder::new(0.05, NET_HEIGHT + 0.1));
+    let post_material = materials.add(Color::srgb(0.6, 0.6, 0.65));
+    for x in [-post_x, post_x] {
+        commands.spawn((
+            Mesh3d(post.clone()),
+            MeshMaterial3d(post_material.clone()),
+            Transform::from_xyz(x, (NET_HEIGHT + 0.1) / 2.0, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Capsule3d::new(0.25, 1.2))),
+        MeshMaterial3d(materials.add(Color::srgb(0.85, 0.2, 0.2))),
+        Transform::from_translation(SERVE_SPOT + Vec3::new(-0.3, 0.85, 0.2)),
+        Name::new("Server"),
+    ));
+
+    // The target is a ring with the bullseye painted just above it
+    commands
+        .spawn((
+            Mesh3d(meshes.add(Cylinder::new(TARGET_RADIUS, 0.01))),
+            MeshMaterial3d(materials.add(Color::srgb(0.95, 0.85, 0.2))),
+            Transform::from_translation(zone_centre(ZONE_ORDER[0]).with_y(0.006)),
+            Target,
+        ))
+        .with_children(|target| {
+            target.spawn((
+                Mesh3d(meshes.add(Cylinder::new(BULLSEYE_RADIUS, 0.01))),
+                MeshMaterial3d(materials.add(Color::srgb(0.85, 0.15, 0.1))),
+                Transform::from_xyz(0.0, 0.002, 0.0),
+            ));
+        });
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_translation(SERVE_SPOT + Vec3::new(-0.5, 3.5, 5.0))
+            .looking_at(Vec3::new(0.0, 0.0, -HALF_LENGTH / 2.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight {
+            illuminance: light_consts::lux::OVERCAST_DAY,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 12.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+/// Moves the target into the zone the server up is aiming for next
+fn place_target(
+    mut target: Query<'_, '_, &mut Transform, With<Target>>,
+    scoreboard: Res<'_, Scoreboard>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Ok(mut transform) = target.get_single_mut() else {
+        return;
+    };
+    let zone = target_zone(scoreboard.serves_taken(turns.current()));
+    transform.translation = zone_centre(zone).with_y(0.006);
+}
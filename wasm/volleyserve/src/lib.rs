@@ -0,0 +1,286 @@
+//! Bevy volleyball serve challenge
+
+use ball::{hand, Ball, BallPlugin};
+use bevy::{asset::AssetMetaCheck, ecs::system::SystemParam, prelude::*};
+use court::CourtPlugin;
+use phase::{ServePhase, ServePhasePlugin};
+use scoring::{NewGame, ScoringPlugin};
+use spjorts_core::{
+    communication::{GameEvent, JsMessage},
+    gesture::{Gesture, GestureDetector},
+    menu::MenuAction,
+    scorecard::Banner,
+    settings::GameSettings,
+    spectator::is_playing,
+    turns::TurnPlugin,
+    ActionReader, FeedbackSender,
+};
+
+pub mod ball;
+pub mod court;
+pub mod phase;
+pub mod scoring;
+
+/// Height the ball is best struck at, with the arm at full stretch
+pub const CONTACT_HEIGHT: f32 = 2.7;
+/// How far above or below the best height the ball can still be struck
+pub const CONTACT_WINDOW: f32 = 0.6;
+/// Slowest a serve leaves the hand, in meters per second
+pub const MIN_SERVE_SPEED: f32 = 8.0;
+/// Fastest a serve leaves the hand, in meters per second
+pub const MAX_SERVE_SPEED: f32 = 22.0;
+/// Slowest the ball can be tossed up, in meters per second
+pub const MIN_TOSS_SPEED: f32 = 3.5;
+/// Fastest the ball can be tossed up, in meters per second
+pub const MAX_TOSS_SPEED: f32 = 6.0;
+/// Lowest a serve can be launched, in radians above level
+pub const MIN_LAUNCH: f32 = -0.2;
+/// Highest a serve can be launched, in radians above level
+pub const MAX_LAUNCH: f32 = 1.2;
+/// Widest a serve can be aimed from straight down the court, in radians
+pub const MAX_AIM: f32 = 0.45;
+/// How far the launch tips up for every meter above the best height the ball is struck, in
+/// radians. Striking early, on a high ball, sends it long and striking late sends it into the net
+const TIMING_TILT: f32 = 0.5;
+/// How much of the serve's speed is lost striking at the very edge of the window
+const OFF_CENTRE_LOSS: f32 = 0.3;
+/// How far a radian of controller pitch tips the launch
+const LAUNCH_SCALE: f32 = 1.0;
+/// How far a radian of controller yaw turns the serve
+const AIM_SCALE: f32 = 0.5;
+/// How far the controller has to tip up during a flick for it to count as a toss, in radians
+const TOSS_PITCH: f32 = 0.3;
+/// How fast the controller has to flick up to toss the highest, in radians per second
+const FULL_TOSS_SPEED: f32 = 12.0;
+/// How fast the controller has to swing to serve the hardest, in radians per second
+const FULL_SWING_SPEED: f32 = 14.0;
+/// Turning speed below which the controller counts as held still, so its angle sets the launch,
+/// in radians per second
+const HELD_SPEED: f32 = 1.0;
+/// Rumble strength for a serve at full speed, out of 255
+const MAX_RUMBLE: f32 = 180.0;
+/// How long the controller rumbles when the ball is struck, in milliseconds
+const RUMBLE_MILLIS: u16 = 60;
+
+spjorts_core::define_runner!(|app| {
+    app.add_plugins(DefaultPlugins.set(AssetPlugin {
+        meta_check: AssetMetaCheck::Never,
+        ..default()
+    }))
+    .add_plugins(ServePhasePlugin)
+    .add_plugins(TurnPlugin)
+    .add_plugins(CourtPlugin)
+    .add_plugins(BallPlugin)
+    .add_plugins(ScoringPlugin)
+    .insert_resource(ClearColor(Color::srgb(0.5, 0.7, 0.9)))
+    .init_resource::<Server>()
+    .add_event::<Toss>()
+    .add_event::<Strike>()
+    .add_systems(OnEnter(ServePhase::Ready), ready_ball)
+    .add_systems(
+        Update,
+        (
+            handle_input,
+            toss_ball.run_if(in_state(ServePhase::Ready)),
+            strike_ball.run_if(in_state(ServePhase::Tossed)),
+        )
+            .chain()
+            .run_if(is_playing),
+    )
+    .add_systems(Update, draw_aim_guide);
+});
+
+/// The server up's line and launch, set by how they hold the controller
+#[derive(Resource, Debug, Default)]
+pub struct Server {
+    /// Line of the serve, in radians from straight down the court. Positive turns left
+    pub aim: f32,
+    /// Angle the serve leaves the hand at, in radians above level
+    pub launch: f32,
+    /// Watches the controller for the toss and the strike
+    detector: GestureDetector,
+}
+
+/// The ball tossed up out of the server's hand
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Toss {
+    /// Speed the ball leaves the hand at, in meters per second
+    pub speed: f32,
+}
+
+/// A swing at the tossed ball
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Strike {
+    /// Speed the ball leaves the hand at when struck at the best height, in meters per second
+    pub speed: f32,
+    /// Angle the ball leaves the hand at when struck at the best height, in radians above level
+    pub launch: f32,
+    /// Line of the serve, in radians from straight down the court. Positive turns left
+    pub aim: f32,
+}
+
+impl Strike {
+    /// Velocity the ball leaves the hand with when it's met `offset` meters above the best
+    /// contact height. Off-centre contact loses speed and tips the launch up or down
+    pub fn velocity(&self, offset: f32) -> Vec3 {
+        let miss = (offset.abs() / CONTACT_WINDOW).min(1.0);
+        let speed = self.speed * (1.0 - OFF_CENTRE_LOSS * miss);
+        let launch = self.launch + offset * TIMING_TILT;
+        Quat::from_rotation_y(self.aim) * Vec3::new(0.0, launch.sin(), -launch.cos()) * speed
+    }
+}
+
+/// Puts the ball back in the server's hand for the next serve
+fn ready_ball(mut ball: Query<'_, '_, (&mut Transform, &mut Ball)>) {
+    for (mut transform, mut ball) in &mut ball {
+        ball.catch(&mut transform);
+    }
+}
+
+/// Everything input handling changes besides the server's stance
+#[derive(SystemParam)]
+struct InputEffects<'w> {
+    /// Tosses to make
+    tosses: EventWriter<'w, Toss>,
+    /// Swings at the ball
+    strikes: EventWriter<'w, Strike>,
+    /// Requests to start over
+    new_game: EventWriter<'w, NewGame>,
+    /// Menu navigation
+    menu: EventWriter<'w, MenuAction>,
+}
+
+/// Reads controller input: the angle the controller is held at sets the line and launch, an
+/// upward flick tosses the ball and a swing strikes it as hard as it was swung. A starts a new
+/// game once it's over
+fn handle_input(
+    read: Res<'_, ActionReader>,
+    mut server: ResMut<'_, Server>,
+    mut effects: InputEffects<'_>,
+    settings: Res<'_, GameSettings>,
+    phase: Res<'_, State<ServePhase>>,
+    time: Res<'_, Time>,
+) {
+    let serving = matches!(phase.get(), ServePhase::Ready | ServePhase::Tossed);
+    while let Ok(msg) = read.0.try_recv() {
+        let (_, msg) = msg.untag();
+        match msg {
+            JsMessage::Restart => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::ButtonA if *phase.get() == ServePhase::GameOver => {
+                effects.new_game.send(NewGame);
+            }
+            JsMessage::Rotate(orientation) | JsMessage::TimedRotate(orientation, _) if serving => {
+                let orientation = settings.apply_rotation(orientation);
+                let detected = server.detector.update(orientation, time.elapsed_secs());
+                if server.detector.speed() < HELD_SPEED {
+                    server.aim = (orientation.yaw * AIM_SCALE).clamp(-MAX_AIM, MAX_AIM);
+                    server.launch =
+                        (orientation.pitch * LAUNCH_SCALE).clamp(MIN_LAUNCH, MAX_LAUNCH);
+                }
+
+                let Some(detected) = detected else {
+                    continue;
+                };
+                match (phase.get(), detected.gesture) {
+                    (ServePhase::Ready, Gesture::Flick | Gesture::Swing)
+                        if detected.turned.pitch > TOSS_PITCH =>
+                    {
+                        let power = (detected.intensity / FULL_TOSS_SPEED).clamp(0.0, 1.0);
+                        effects.tosses.send(Toss {
+                            speed: MIN_TOSS_SPEED + (MAX_TOSS_SPEED - MIN_TOSS_SPEED) * power,
+                        });
+                    }
+                    (ServePhase::Tossed, Gesture::Swing | Gesture::Flick) => {
+                        let power = (detected.intensity / FULL_SWING_SPEED).clamp(0.0, 1.0);
+                        effects.strikes.send(Strike {
+                            speed: MIN_SERVE_SPEED + (MAX_SERVE_SPEED - MIN_SERVE_SPEED) * power,
+                            launch: server.launch,
+                            aim: server.aim,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            other => {
+                if let Some(action) = MenuAction::from_message(&other) {
+                    effects.menu.send(action);
+                }
+            }
+        }
+    }
+}
+
+/// Tosses the ball straight up out of the server's hand
+fn toss_ball(
+    mut tosses: EventReader<'_, '_, Toss>,
+    mut ball: Query<'_, '_, &mut Ball>,
+    mut next_phase: ResMut<'_, NextState<ServePhase>>,
+) {
+    let Some(toss) = tosses.read().last().copied() else {
+        return;
+    };
+
+    for mut ball in &mut ball {
+        ball.velocity = Vec3::Y * toss.speed.clamp(MIN_TOSS_SPEED, MAX_TOSS_SPEED);
+        ball.airborne = true;
+    }
+    next_phase.set(ServePhase::Tossed);
+}
+
+/// Strikes the tossed ball over the net if it's in reach, or calls a miss if the swing came too
+/// early or too late
+fn strike_ball(
+    mut strikes: EventReader<'_, '_, Strike>,
+    mut ball: Query<'_, '_, (&Transform, &mut Ball)>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<ServePhase>>,
+    feedback: Res<'_, FeedbackSender>,
+) {
+    let Some(strike) = strikes.read().last().copied() else {
+        return;
+    };
+    let Ok((transform, mut ball)) = ball.get_single_mut() else {
+        return;
+    };
+
+    let offset = transform.translation.y - CONTACT_HEIGHT;
+    if offset.abs() > CONTACT_WINDOW {
+        // Below the window on the way up is as early as above it
+        banner.show(if offset > 0.0 || ball.velocity.y > 0.0 {
+            "Too early!"
+        } else {
+            "Too late!"
+        });
+        return;
+    }
+
+    ball.velocity = strike.velocity(offset);
+    ball.struck = true;
+    feedback.send(GameEvent::Rumble {
+        intensity: (strike.speed / MAX_SERVE_SPEED * MAX_RUMBLE) as u8,
+        millis: RUMBLE_MILLIS,
+    });
+    next_phase.set(ServePhase::Flying);
+}
+
+/// Draws the line the serve will leave the hand along while serving, when the aim guide is on
+fn draw_aim_guide(
+    mut gizmos: Gizmos<'_, '_>,
+    server: Res<'_, Server>,
+    phase: Res<'_, State<ServePhase>>,
+    settings: Res<'_, GameSettings>,
+) {
+    if !settings.aim_guide || !matches!(phase.get(), ServePhase::Ready | ServePhase::Tossed) {
+        return;
+    }
+    let start = hand().with_y(CONTACT_HEIGHT);
+    let direction = Strike {
+        speed: 1.0,
+        launch: server.launch,
+        aim: server.aim,
+    }
+    .velocity(0.0);
+    gizmos.line(start, start + direction * 3.0, Color::srgb(0.9, 0.2, 0.2));
+}
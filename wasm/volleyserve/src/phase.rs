@@ -0,0 +1,29 @@
+//! Phases a serve challenge moves through, from the ball in hand to the final score
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where the game is in the flow of play
+#[derive(States, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ServePhase {
+    /// The server up is holding the ball, ready to toss it
+    #[default]
+    Ready,
+    /// The ball has been tossed and is waiting to be struck
+    Tossed,
+    /// The struck ball is flying over the net
+    Flying,
+    /// The ball is down and the call on the serve is up
+    Called,
+    /// Every serve has been taken and the final scores are up
+    GameOver,
+}
+
+/// Plugin that tracks which phase the game is in
+pub struct ServePhasePlugin;
+
+impl Plugin for ServePhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<ServePhase>();
+    }
+}
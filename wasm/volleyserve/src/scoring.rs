@@ -0,0 +1,359 @@
+//! Calling each serve where it lands, handing the ball to the next server, and showing every
+//! player's points on the shared scorecard HUD
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use spjorts_core::{
+    communication::GameEvent,
+    scorecard::{Banner, ScorecardHud, ScorecardHudPlugin},
+    snapshot::StateSnapshot,
+    spectator::is_playing,
+    turns::TurnManager,
+    FeedbackSender,
+};
+
+use crate::{
+    ball::{Ball, Landed, BALL_RADIUS},
+    court::{in_court, target_zone, zone_centre, BULLSEYE_RADIUS, TARGET_RADIUS},
+    phase::ServePhase,
+};
+
+/// Serves each player takes in a game
+pub const SERVES_PER_PLAYER: usize = 10;
+/// How long the call on a serve stays up before the next serve, in seconds
+const CALL_PAUSE_SECS: f32 = 2.0;
+
+/// Asks for the game to be started over from the first serve
+#[derive(Event, Debug, Clone, Copy)]
+pub struct NewGame;
+
+/// The call on a serve, from where it came down
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Call {
+    /// The toss came down without being struck
+    Dropped,
+    /// The serve didn't clear the net
+    Net,
+    /// The serve cleared the net but landed outside the court
+    Out,
+    /// The serve landed in the court, away from the target
+    In,
+    /// The serve landed inside the target ring
+    OnTarget,
+    /// The serve landed in the middle of the target
+    Bullseye,
+}
+
+impl Call {
+    /// Calls a serve from where the ball came down and where the target was
+    pub fn of(landed: &Landed, target: Vec3) -> Self {
+        if !landed.struck {
+            return Self::Dropped;
+        }
+        if landed.netted {
+            return Self::Net;
+        }
+        if !in_court(landed.at, BALL_RADIUS) {
+            return Self::Out;
+        }
+        let off = landed.at.with_y(0.0).distance(target.with_y(0.0));
+        if off <= BULLSEYE_RADIUS {
+            Self::Bullseye
+        } else if off <= TARGET_RADIUS {
+            Self::OnTarget
+        } else {
+            Self::In
+        }
+    }
+
+    /// Points the serve scores
+    pub fn points(&self) -> u32 {
+        match self {
+            Self::Dropped | Self::Net | Self::Out => 0,
+            Self::In => 1,
+            Self::OnTarget => 3,
+            Self::Bullseye => 5,
+        }
+    }
+
+    /// What to show players
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Dropped => "Dropped the toss",
+            Self::Net => "Into the net",
+            Self::Out => "Out!",
+            Self::In => "In, but off target",
+            Self::OnTarget => "On target!",
+            Self::Bullseye => "Bullseye!",
+        }
+    }
+}
+
+/// Points each player has scored on every serve they've taken
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    /// Points from each serve, per player in turn order
+    serves: Vec<Vec<u32>>,
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Scoreboard {
+    /// Starts a game for a number of players with no serves taken
+    pub fn new(players: usize) -> Self {
+        Self {
+            serves: vec![Vec::new(); players.max(1)],
+        }
+    }
+
+    /// How many players are in the game
+    pub fn players(&self) -> usize {
+        self.serves.len()
+    }
+
+    /// How many serves a player has taken
+    pub fn serves_taken(&self, player: usize) -> usize {
+        self.serves.get(player).map_or(0, Vec::len)
+    }
+
+    /// Serves a player has left to take
+    pub fn serves_left(&self, player: usize) -> usize {
+        SERVES_PER_PLAYER.saturating_sub(self.serves_taken(player))
+    }
+
+    /// Records the points a player scored on a serve
+    pub fn record(&mut self, player: usize, points: u32) {
+        if let Some(serves) = self.serves.get_mut(player) {
+            serves.push(points);
+        }
+    }
+
+    /// A player's total across every serve they've taken
+    pub fn total(&self, player: usize) -> u32 {
+        self.serves
+            .get(player)
+            .map_or(0, |serves| serves.iter().sum())
+    }
+
+    /// The player with the most points, the first of them on a tie
+    pub fn leader(&self) -> Option<usize> {
+        (0..self.players())
+            .rev()
+            .max_by_key(|player| self.total(*player))
+    }
+}
+
+/// Counts down before the ball goes back to the next server
+#[derive(Resource, Debug)]
+struct CallPause(Timer);
+
+impl Default for CallPause {
+    fn default() -> Self {
+        Self(Timer::from_seconds(CALL_PAUSE_SECS, TimerMode::Once))
+    }
+}
+
+/// Game state published to JavaScript
+#[derive(Serialize, Debug)]
+struct ServeSnapshot<'a> {
+    /// Player up to serve
+    player: usize,
+    /// Zone the player up is aiming for
+    zone: usize,
+    /// Points from every serve taken so far
+    scoreboard: &'a Scoreboard,
+    /// Where the game is at
+    phase: ServePhase,
+}
+
+/// Plugin that calls serves and shows the score on the scorecard HUD
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ScorecardHudPlugin)
+            .init_resource::<Scoreboard>()
+            .init_resource::<CallPause>()
+            .add_event::<NewGame>()
+            .add_systems(
+                Update,
+                (
+                    fit_game.run_if(resource_changed::<TurnManager>),
+                    call_serve,
+                    next_serve.run_if(in_state(ServePhase::Called)),
+                    start_new_game,
+                )
+                    .chain()
+                    .run_if(is_playing),
+            )
+            .add_systems(Update, (update_hud, update_snapshot))
+            .add_systems(OnEnter(ServePhase::Called), reset_call_pause)
+            .add_systems(
+                OnEnter(ServePhase::GameOver),
+                (show_final_card, submit_result.run_if(is_playing)),
+            )
+            .add_systems(OnExit(ServePhase::GameOver), hide_final_card);
+    }
+}
+
+/// Starts a fresh game whenever the number of players changes
+fn fit_game(turns: Res<'_, TurnManager>, mut scoreboard: ResMut<'_, Scoreboard>) {
+    if scoreboard.players() != turns.players() {
+        *scoreboard = Scoreboard::new(turns.players());
+    }
+}
+
+/// Calls the serve once the ball comes down, scoring it for the server up
+fn call_serve(
+    mut landings: EventReader<'_, '_, Landed>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut banner: ResMut<'_, Banner>,
+    mut next_phase: ResMut<'_, NextState<ServePhase>>,
+    turns: Res<'_, TurnManager>,
+) {
+    let Some(landed) = landings.read().last() else {
+        return;
+    };
+
+    let player = turns.current();
+    let target = zone_centre(target_zone(scoreboard.serves_taken(player)));
+    let call = Call::of(landed, target);
+    match call.points() {
+        0 => banner.show(call.describe()),
+        points => banner.show(format!("{} +{points}", call.describe())),
+    }
+    scoreboard.record(player, call.points());
+    next_phase.set(ServePhase::Called);
+}
+
+/// Starts counting down before the next serve
+fn reset_call_pause(mut pause: ResMut<'_, CallPause>) {
+    pause.0.reset();
+}
+
+/// Hands the ball to the next player with serves left, or finishes the game once everyone has
+/// taken all of theirs
+fn next_serve(
+    mut pause: ResMut<'_, CallPause>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut next_phase: ResMut<'_, NextState<ServePhase>>,
+    scoreboard: Res<'_, Scoreboard>,
+    time: Res<'_, Time>,
+) {
+    if !pause.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if turns
+        .advance_until(|player| scoreboard.serves_left(player) == 0)
+        .is_some()
+    {
+        next_phase.set(ServePhase::Ready);
+    } else {
+        next_phase.set(ServePhase::GameOver);
+    }
+}
+
+/// Starts the game over from the first player's first serve
+fn start_new_game(
+    mut requests: EventReader<'_, '_, NewGame>,
+    mut turns: ResMut<'_, TurnManager>,
+    mut scoreboard: ResMut<'_, Scoreboard>,
+    mut next_phase: ResMut<'_, NextState<ServePhase>>,
+    mut ball: Query<'_, '_, (&mut Transform, &mut Ball)>,
+) {
+    if requests.read().last().is_none() {
+        return;
+    }
+
+    for (mut transform, mut ball) in &mut ball {
+        ball.catch(&mut transform);
+    }
+    turns.restart();
+    *scoreboard = Scoreboard::new(turns.players());
+    next_phase.set(ServePhase::Ready);
+}
+
+/// Fills in the scorecard HUD with every player's points and serves left
+fn update_hud(
+    mut hud: ResMut<'_, ScorecardHud>,
+    scoreboard: Res<'_, Scoreboard>,
+    turns: Res<'_, TurnManager>,
+) {
+    let rows = (0..scoreboard.players())
+        .map(|player| {
+            format!(
+                "Player {}: {} ({} serves left)",
+                player + 1,
+                scoreboard.total(player),
+                scoreboard.serves_left(player)
+            )
+        })
+        .collect();
+    let player = turns.current();
+    let serve = (scoreboard.serves_taken(player) + 1).min(SERVES_PER_PLAYER);
+
+    hud.set_if_neq(ScorecardHud {
+        title: format!("Serve Challenge, serve {serve} of {SERVES_PER_PLAYER}"),
+        rows,
+        footer: format!(
+            "Player {} to serve at zone {}: flick up to toss, swing to serve",
+            player + 1,
+            target_zone(scoreboard.serves_taken(player))
+        ),
+        final_card: hud.final_card.clone(),
+    });
+}
+
+/// Lists every player's points once the game is over, calling the winner
+fn show_final_card(mut hud: ResMut<'_, ScorecardHud>, scoreboard: Res<'_, Scoreboard>) {
+    let mut lines = vec!["Final Scores".to_string()];
+    if let Some(leader) = scoreboard.leader().filter(|_| scoreboard.players() > 1) {
+        lines.push(format!("Player {} wins!", leader + 1));
+    }
+    for player in 0..scoreboard.players() {
+        lines.push(format!(
+            "Player {}: {}",
+            player + 1,
+            scoreboard.total(player)
+        ));
+    }
+    lines.push("Press A to play again".to_string());
+    hud.final_card = Some(lines.join("\n"));
+}
+
+/// Hides the final scores when a new game starts
+fn hide_final_card(mut hud: ResMut<'_, ScorecardHud>) {
+    hud.final_card = None;
+}
+
+/// Sends every player's points back to the page once the game is over, so it can submit them to
+/// the server
+fn submit_result(scoreboard: Res<'_, Scoreboard>, feedback: Res<'_, FeedbackSender>) {
+    let scores: Vec<u32> = (0..scoreboard.players())
+        .map(|player| scoreboard.total(player))
+        .collect();
+    feedback.send(GameEvent::GameResult {
+        players: scores.len(),
+        scores,
+    });
+}
+
+/// Publishes the current game state to JavaScript
+fn update_snapshot(
+    turns: Res<'_, TurnManager>,
+    scoreboard: Res<'_, Scoreboard>,
+    phase: Res<'_, State<ServePhase>>,
+    snapshot: Res<'_, StateSnapshot>,
+) {
+    snapshot.set(&ServeSnapshot {
+        player: turns.current(),
+        zone: target_zone(scoreboard.serves_taken(turns.current())),
+        scoreboard: &scoreboard,
+        phase: *phase.get(),
+    });
+}
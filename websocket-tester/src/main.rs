@@ -4,7 +4,10 @@ use std::{f32::consts::PI, ops::Range, time::Duration};
 
 use futures_util::{SinkExt, StreamExt};
 use rand::{thread_rng, Rng};
-use server::control::{msg::WsMessage, ControllerMessage};
+use server::control::{
+    msg::{Orientation, WsMessage},
+    ControllerMessage,
+};
 use tokio_tungstenite::connect_async;
 
 /// Range of all angles that encompass a unit circle (or I guess any circle)
@@ -27,17 +30,17 @@ async fn main() {
         .unwrap();
     let mut rng = thread_rng();
     loop {
-        /*let (pitch, yaw, roll) = (
+        /*let (pitch, roll, yaw) = (
             rng.gen_range(UNIT_CIRCLE_RANGE),
             rng.gen_range(UNIT_CIRCLE_RANGE),
             rng.gen_range(UNIT_CIRCLE_RANGE),
         );*/
 
-        let (pitch, yaw, roll) = (2.0 * PI, 2.0 * PI, 2.0 * PI);
+        let (pitch, roll, yaw) = (2.0 * PI, 2.0 * PI, 2.0 * PI);
 
         write
             .send(
-                ControllerMessage::AngleInfo(pitch, yaw, roll)
+                ControllerMessage::AngleInfo(Orientation::new(pitch, roll, yaw))
                     .to_ws_message()
                     .unwrap(),
             )
@@ -46,11 +49,11 @@ async fn main() {
 
         std::thread::sleep(Duration::from_millis(100));
 
-        let (pitch, yaw, roll) = (0., 0., 0.);
+        let (pitch, roll, yaw) = (0., 0., 0.);
 
         write
             .send(
-                ControllerMessage::AngleInfo(pitch, yaw, roll)
+                ControllerMessage::AngleInfo(Orientation::new(pitch, roll, yaw))
                     .to_ws_message()
                     .unwrap(),
             )